@@ -0,0 +1,16 @@
+#!/usr/bin/env bash
+# Checks out the crates listed in the corpus manifest at their pinned revision.
+# Usage: fetch.sh <manifest> <corpus-dir>
+set -euo pipefail
+
+manifest="$1"
+corpus_dir="$2"
+mkdir -p "$corpus_dir"
+
+grep -v -e '^#' -e '^[[:space:]]*$' "$manifest" | while read -r name repository revision; do
+  if [ ! -d "$corpus_dir/$name" ]; then
+    git clone --quiet "$repository" "$corpus_dir/$name"
+  fi
+  git -C "$corpus_dir/$name" fetch --quiet --tags
+  git -C "$corpus_dir/$name" checkout --quiet "$revision"
+done
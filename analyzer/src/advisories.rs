@@ -0,0 +1,627 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    fingerprint::fingerprint_toml_issues,
+    issue::{severity_override, Issue, Severity},
+    manifest::{line_location, Manifest, MANIFEST_FILE_NAME},
+    options::AnalysisOptions,
+    rule_description::RuleDescription,
+    tree::{AnalyzerError, SonarLocation},
+};
+use serde_json::Value;
+use std::collections::HashMap;
+
+pub const LOCK_FILE_NAME: &str = "Cargo.lock";
+
+pub const VULNERABLE_DEPENDENCY_KEY: &str = "S7475";
+
+/// Description of the rule reporting the advisories of the dependencies.
+pub const VULNERABLE_DEPENDENCY_DESCRIPTION: RuleDescription = RuleDescription {
+    key: VULNERABLE_DEPENDENCY_KEY,
+    title: "Dependencies with security advisories should be upgraded",
+    markdown: r#"
+This rule raises an issue when a cargo-audit or cargo-deny report lists a RustSec advisory for a dependency of the
+project. The reports are read from the paths of the `sonar.rust.advisories.reportPaths` property.
+
+## Why is this an issue?
+
+The advisories of the RustSec database describe the vulnerabilities, the unsound code and the unmaintained crates
+found in the crates published on crates.io. A vulnerable dependency exposes the project to the attacks described by its
+advisory, even when the code of the project itself is correct.
+
+The issue is reported on the declaration of the dependency in `Cargo.toml`, or on the package in `Cargo.lock` for the
+transitive dependencies. Its severity follows the CVSS score of the vulnerability.
+
+## How to fix it
+
+Upgrade the dependency to one of the patched versions of the advisory, or replace it when none is available.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+[dependencies]
+time = "=0.1.43" # Noncompliant, RUSTSEC-2020-0071
+```
+
+#### Compliant solution
+
+```compliant
+[dependencies]
+time = "0.3"
+```
+
+## Resources
+
+### Documentation
+
+- RustSec - [The Rust Security Advisory Database](https://rustsec.org/)
+- [cargo-audit](https://github.com/rustsec/rustsec/tree/main/cargo-audit)
+- [cargo-deny](https://embarkstudios.github.io/cargo-deny/)
+"#,
+};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AdvisoryKind {
+    Vulnerability,
+    Unsound,
+    Unmaintained,
+    Notice,
+    /// The version of the package was yanked from its registry, without advisory.
+    Yanked,
+}
+
+/// An advisory of the RustSec database on a package of the dependencies, from `cargo audit --json` or
+/// `cargo deny --format json check advisories`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Advisory {
+    pub kind: AdvisoryKind,
+    /// Identifier of the advisory (e.g. 'RUSTSEC-2020-0071'), empty for yanked versions.
+    pub id: String,
+    /// Other identifiers of the advisory, e.g. its CVE.
+    pub aliases: Vec<String>,
+    pub title: String,
+    /// CVSS vector of the vulnerability, e.g. 'CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H'.
+    pub cvss: Option<String>,
+    pub package: String,
+    pub version: String,
+    /// Version requirements of the versions which fix the advisory.
+    pub patched: Vec<String>,
+}
+
+impl Advisory {
+    /// Severity of the advisory: from the CVSS base score for the vulnerabilities (major without score), minor for
+    /// the unmaintained packages, notices and yanked versions.
+    pub fn severity(&self) -> Severity {
+        match self.kind {
+            AdvisoryKind::Vulnerability => match self.cvss.as_deref().and_then(cvss_base_score) {
+                Some(score) if score >= 9.0 => Severity::Blocker,
+                Some(score) if score >= 7.0 => Severity::Critical,
+                Some(score) if score >= 4.0 || score == 0.0 => Severity::Major,
+                Some(_) => Severity::Minor,
+                None => Severity::Major,
+            },
+            AdvisoryKind::Unsound => Severity::Major,
+            AdvisoryKind::Unmaintained | AdvisoryKind::Notice | AdvisoryKind::Yanked => {
+                Severity::Minor
+            }
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let package = format!("\"{}\" {}", self.package, self.version);
+        let mut ids = self.id.clone();
+        if !self.aliases.is_empty() {
+            ids = format!("{} ({})", ids, self.aliases.join(", "));
+        }
+        let message = match self.kind {
+            AdvisoryKind::Vulnerability => {
+                format!(
+                    "Upgrade {}, it is vulnerable to {}: {}.",
+                    package, ids, self.title
+                )
+            }
+            AdvisoryKind::Unsound => {
+                format!(
+                    "Upgrade {}, it is unsound according to {}: {}.",
+                    package, ids, self.title
+                )
+            }
+            AdvisoryKind::Unmaintained => format!(
+                "Replace {}, it is unmaintained according to {}: {}.",
+                package, ids, self.title
+            ),
+            AdvisoryKind::Notice => format!("Review {}, see {}: {}.", package, ids, self.title),
+            AdvisoryKind::Yanked => format!("Upgrade {}, this version was yanked.", package),
+        };
+        match self.patched.as_slice() {
+            [] if self.kind == AdvisoryKind::Vulnerability => {
+                format!("{} No patched version is available.", message)
+            }
+            [] => message,
+            patched => format!("{} Patched versions: {}.", message, patched.join(", ")),
+        }
+    }
+}
+
+/// Parses the advisories of a report of cargo-audit (a JSON document) or of cargo-deny (one JSON object per line).
+pub fn parse_advisories(text: &str) -> Result<Vec<Advisory>, AnalyzerError> {
+    match serde_json::from_str::<Value>(text) {
+        Ok(report) if report.get("vulnerabilities").is_some() => Ok(audit_advisories(&report)),
+        // cargo-deny writes one object per line, which is not a single JSON document
+        _ if deny_diagnostics(text).next().is_some() => Ok(deny_advisories(text)),
+        Ok(_) => Err(invalid("neither a cargo-audit nor a cargo-deny report")),
+        Err(err) => Err(invalid(&err.to_string())),
+    }
+}
+
+fn invalid(message: &str) -> AnalyzerError {
+    AnalyzerError::FileError(format!("invalid advisory report: {}", message))
+}
+
+fn audit_advisories(report: &Value) -> Vec<Advisory> {
+    let mut advisories: Vec<Advisory> = report["vulnerabilities"]["list"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|vulnerability| audit_advisory(vulnerability, AdvisoryKind::Vulnerability))
+        .collect();
+    for (kind_name, warnings) in report["warnings"].as_object().into_iter().flatten() {
+        let kind = match kind_name.as_str() {
+            "unsound" => AdvisoryKind::Unsound,
+            "unmaintained" => AdvisoryKind::Unmaintained,
+            "yanked" => AdvisoryKind::Yanked,
+            _ => AdvisoryKind::Notice,
+        };
+        advisories.extend(
+            warnings
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|warning| audit_advisory(warning, kind)),
+        );
+    }
+    advisories
+}
+
+fn audit_advisory(entry: &Value, kind: AdvisoryKind) -> Option<Advisory> {
+    let advisory = &entry["advisory"];
+    Some(Advisory {
+        kind,
+        id: string(&advisory["id"]),
+        aliases: strings(&advisory["aliases"]),
+        title: string(&advisory["title"]),
+        cvss: advisory["cvss"].as_str().map(str::to_string),
+        package: entry["package"]["name"].as_str()?.to_string(),
+        version: string(&entry["package"]["version"]),
+        patched: strings(&entry["versions"]["patched"]),
+    })
+}
+
+fn deny_diagnostics(text: &str) -> impl Iterator<Item = Value> + '_ {
+    text.lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter(|diagnostic| diagnostic["type"] == "diagnostic")
+}
+
+fn deny_advisories(text: &str) -> Vec<Advisory> {
+    deny_diagnostics(text)
+        .filter_map(|diagnostic| {
+            let fields = &diagnostic["fields"];
+            let kind = match fields["code"].as_str()? {
+                "vulnerability" => AdvisoryKind::Vulnerability,
+                "unsound" => AdvisoryKind::Unsound,
+                "unmaintained" => AdvisoryKind::Unmaintained,
+                "notice" => AdvisoryKind::Notice,
+                "yanked" => AdvisoryKind::Yanked,
+                _ => return None,
+            };
+            let advisory = &fields["advisory"];
+            let krate = &fields["graphs"][0]["Krate"];
+            // The solution is one of the notes, e.g. 'Solution: Upgrade to >=0.2.23'
+            let patched = strings(&fields["notes"])
+                .iter()
+                .find_map(|note| note.strip_prefix("Solution: Upgrade to "))
+                .map(|patched| vec![patched.to_string()])
+                .unwrap_or_default();
+            Some(Advisory {
+                kind,
+                id: string(&advisory["id"]),
+                aliases: strings(&advisory["aliases"]),
+                title: advisory["title"]
+                    .as_str()
+                    .or(fields["message"].as_str())
+                    .unwrap_or_default()
+                    .to_string(),
+                cvss: advisory["cvss"].as_str().map(str::to_string),
+                package: krate["name"]
+                    .as_str()
+                    .or(advisory["package"].as_str())?
+                    .to_string(),
+                version: string(&krate["version"]),
+                patched,
+            })
+        })
+        .collect()
+}
+
+fn string(value: &Value) -> String {
+    value.as_str().unwrap_or_default().to_string()
+}
+
+fn strings(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|value| value.as_str().map(str::to_string))
+        .collect()
+}
+
+/// Base score of a CVSS 3.x vector, `None` for the other versions and for invalid vectors.
+fn cvss_base_score(vector: &str) -> Option<f64> {
+    let mut metrics = vector.split('/');
+    if !matches!(metrics.next()?, "CVSS:3.0" | "CVSS:3.1") {
+        return None;
+    }
+    let metrics: HashMap<&str, &str> = metrics
+        .filter_map(|metric| metric.split_once(':'))
+        .collect();
+    let changed = match *metrics.get("S")? {
+        "U" => false,
+        "C" => true,
+        _ => return None,
+    };
+    let value = |metric: &str, values: &[(&str, f64)]| {
+        let metric = metrics.get(metric)?;
+        values
+            .iter()
+            .find(|(name, _)| name == metric)
+            .map(|(_, value)| *value)
+    };
+    let impact_values = [("H", 0.56), ("L", 0.22), ("N", 0.0)];
+    let attack_vector = value("AV", &[("N", 0.85), ("A", 0.62), ("L", 0.55), ("P", 0.2)])?;
+    let attack_complexity = value("AC", &[("L", 0.77), ("H", 0.44)])?;
+    let privileges = if changed {
+        value("PR", &[("N", 0.85), ("L", 0.68), ("H", 0.5)])?
+    } else {
+        value("PR", &[("N", 0.85), ("L", 0.62), ("H", 0.27)])?
+    };
+    let user_interaction = value("UI", &[("N", 0.85), ("R", 0.62)])?;
+    let confidentiality = value("C", &impact_values)?;
+    let integrity = value("I", &impact_values)?;
+    let availability = value("A", &impact_values)?;
+
+    let iss = 1.0 - (1.0 - confidentiality) * (1.0 - integrity) * (1.0 - availability);
+    let impact = if changed {
+        7.52 * (iss - 0.029) - 3.25 * (iss - 0.02).powi(15)
+    } else {
+        6.42 * iss
+    };
+    let exploitability = 8.22 * attack_vector * attack_complexity * privileges * user_interaction;
+    if impact <= 0.0 {
+        return Some(0.0);
+    }
+    let score = if changed {
+        1.08 * (impact + exploitability)
+    } else {
+        impact + exploitability
+    };
+    Some(round_up(score.min(10.0)))
+}
+
+/// Rounds up to one decimal, as specified by CVSS 3.1 to avoid floating point errors.
+fn round_up(value: f64) -> f64 {
+    let integer = (value * 100_000.0).round() as i64;
+    if integer % 10_000 == 0 {
+        integer as f64 / 100_000.0
+    } else {
+        ((integer / 10_000) + 1) as f64 / 10.0
+    }
+}
+
+/// Issues of the advisories, on the dependency of the manifest for the direct dependencies or on the package of the
+/// lock file for the others, with the name of their file. The advisories of the packages found in neither file are
+/// reported on the `[package]` table of the manifest.
+///
+/// The severity is the one of the advisory, unless the rule has a severity override.
+pub fn advisory_issues(
+    advisories: &[Advisory],
+    manifest: &str,
+    lock_file: Option<&str>,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<(&'static str, Issue)>, AnalyzerError> {
+    let options = AnalysisOptions::from_parameters(parameters)?;
+    if options
+        .disabled_rules
+        .iter()
+        .any(|key| key == VULNERABLE_DEPENDENCY_KEY)
+    {
+        return Ok(vec![]);
+    }
+    let severity = severity_override(parameters, VULNERABLE_DEPENDENCY_KEY)?;
+    let manifest_text = manifest;
+    let manifest = Manifest::parse(manifest_text)?;
+    let mut issues: Vec<(&'static str, Issue)> = advisories
+        .iter()
+        .map(|advisory| {
+            let direct_dependency = manifest
+                .dependencies
+                .iter()
+                .find(|dependency| dependency.package() == advisory.package);
+            let (file, location) = match (direct_dependency, lock_file) {
+                (Some(dependency), _) => (MANIFEST_FILE_NAME, dependency.location.clone()),
+                (None, Some(lock_file)) => {
+                    match lock_file_location(lock_file, &advisory.package, &advisory.version) {
+                        Some(location) => (LOCK_FILE_NAME, location),
+                        None => (MANIFEST_FILE_NAME, package_location(&manifest)),
+                    }
+                }
+                (None, None) => (MANIFEST_FILE_NAME, package_location(&manifest)),
+            };
+            let issue = Issue {
+                rule_key: VULNERABLE_DEPENDENCY_KEY.to_string(),
+                message: advisory.message(),
+                location,
+                secondary_locations: vec![],
+                severity: Some(severity.unwrap_or(advisory.severity())),
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            };
+            (file, issue)
+        })
+        .collect();
+    // The same advisory is reported once per version of the package in the lock file
+    issues.dedup();
+    for (file, text) in [
+        (MANIFEST_FILE_NAME, manifest_text),
+        (LOCK_FILE_NAME, lock_file.unwrap_or_default()),
+    ] {
+        let (indexes, mut file_issues): (Vec<usize>, Vec<Issue>) = issues
+            .iter()
+            .enumerate()
+            .filter(|(_, (issue_file, _))| *issue_file == file)
+            .map(|(index, (_, issue))| (index, issue.clone()))
+            .unzip();
+        fingerprint_toml_issues(&mut file_issues, text);
+        for (index, issue) in indexes.into_iter().zip(file_issues) {
+            issues[index].1.fingerprint = issue.fingerprint;
+        }
+    }
+    Ok(issues)
+}
+
+fn package_location(manifest: &Manifest) -> SonarLocation {
+    manifest.package.clone().unwrap_or(SonarLocation {
+        start_line: 1,
+        start_column: 0,
+        end_line: 1,
+        end_column: 0,
+    })
+}
+
+/// Location of the name of the `[[package]]` of the lock file with the given name and version.
+fn lock_file_location(lock_file: &str, package: &str, version: &str) -> Option<SonarLocation> {
+    let lines: Vec<&str> = lock_file.lines().collect();
+    let quoted = |key: &str, line: &str| {
+        let (line_key, value) = line.split_once('=')?;
+        (line_key.trim() == key).then(|| value.trim().trim_matches('"').to_string())
+    };
+    (0..lines.len()).find_map(|index| {
+        if quoted("name", lines[index]).as_deref() != Some(package) {
+            return None;
+        }
+        let next = lines.get(index + 1).copied().unwrap_or_default();
+        (version.is_empty() || quoted("version", next).as_deref() == Some(version))
+            .then(|| line_location(lines[index], index, package))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::rule::default_parameters;
+
+    const AUDIT_REPORT: &str = r#"{
+  "database": {"advisory-count": 600},
+  "lockfile": {"dependency-count": 3},
+  "vulnerabilities": {
+    "found": true,
+    "count": 1,
+    "list": [{
+      "advisory": {
+        "id": "RUSTSEC-2020-0071",
+        "package": "time",
+        "title": "Potential segfault in the time crate",
+        "aliases": ["CVE-2020-26235"],
+        "cvss": "CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:N/I:N/A:H"
+      },
+      "versions": {"patched": [">=0.2.23"], "unaffected": ["=0.2.0"]},
+      "package": {"name": "time", "version": "0.1.45", "source": "registry+https://github.com/rust-lang/crates.io-index"}
+    }]
+  },
+  "warnings": {
+    "unmaintained": [{
+      "kind": "unmaintained",
+      "package": {"name": "ansi_term", "version": "0.12.1"},
+      "advisory": {"id": "RUSTSEC-2021-0139", "title": "ansi_term is Unmaintained", "aliases": [], "cvss": null},
+      "versions": {"patched": [], "unaffected": []}
+    }],
+    "yanked": [{
+      "kind": "yanked",
+      "package": {"name": "log", "version": "0.4.15"},
+      "advisory": null,
+      "versions": null
+    }]
+  }
+}"#;
+
+    const DENY_REPORT: &str = r#"{"type":"diagnostic","fields":{"code":"vulnerability","severity":"error","message":"Potential segfault in the time crate","advisory":{"id":"RUSTSEC-2020-0071","package":"time","title":"Potential segfault in the time crate","aliases":["CVE-2020-26235"],"cvss":"CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:N/I:N/A:H"},"labels":[],"notes":["ID: RUSTSEC-2020-0071","Solution: Upgrade to >=0.2.23"],"graphs":[{"Krate":{"name":"time","version":"0.1.45"},"parents":[]}]}}
+{"type":"diagnostic","fields":{"code":"advisory-not-detected","severity":"warning","message":"advisory was not encountered","labels":[],"notes":[],"graphs":[]}}
+{"type":"summary","fields":{"advisories":{"errors":1,"warnings":0}}}
+"#;
+
+    const MANIFEST: &str = r#"[package]
+name = "demo"
+version = "0.1.0"
+
+[dependencies]
+time = "0.1"
+"#;
+
+    const LOCK_FILE: &str = r#"version = 3
+
+[[package]]
+name = "ansi_term"
+version = "0.12.1"
+
+[[package]]
+name = "log"
+version = "0.4.14"
+
+[[package]]
+name = "log"
+version = "0.4.15"
+"#;
+
+    #[test]
+    fn test_cvss_base_score() {
+        let score = |vector| cvss_base_score(vector);
+        assert_eq!(
+            score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"),
+            Some(9.8)
+        );
+        assert_eq!(
+            score("CVSS:3.1/AV:N/AC:L/PR:N/UI:N/S:C/C:H/I:H/A:H"),
+            Some(10.0)
+        );
+        assert_eq!(
+            score("CVSS:3.0/AV:L/AC:L/PR:L/UI:N/S:U/C:H/I:N/A:N"),
+            Some(5.5)
+        );
+        assert_eq!(
+            score("CVSS:3.1/AV:N/AC:H/PR:N/UI:R/S:U/C:L/I:N/A:N"),
+            Some(3.1)
+        );
+        assert_eq!(
+            score("CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:N/I:N/A:N"),
+            Some(0.0)
+        );
+        assert_eq!(score("CVSS:3.1/AV:X/AC:L/PR:N/UI:N/S:U/C:H/I:H/A:H"), None);
+        assert_eq!(score("CVSS:4.0/AV:N/AC:L/AT:N/PR:N/UI:N"), None);
+    }
+
+    #[test]
+    fn test_parse_advisories() {
+        let advisories = parse_advisories(AUDIT_REPORT).unwrap();
+        assert_eq!(
+            advisories[0],
+            Advisory {
+                kind: AdvisoryKind::Vulnerability,
+                id: "RUSTSEC-2020-0071".to_string(),
+                aliases: vec!["CVE-2020-26235".to_string()],
+                title: "Potential segfault in the time crate".to_string(),
+                cvss: Some("CVSS:3.1/AV:N/AC:H/PR:N/UI:N/S:U/C:N/I:N/A:H".to_string()),
+                package: "time".to_string(),
+                version: "0.1.45".to_string(),
+                patched: vec![">=0.2.23".to_string()],
+            }
+        );
+        let kinds: Vec<AdvisoryKind> = advisories.iter().map(|advisory| advisory.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                AdvisoryKind::Vulnerability,
+                AdvisoryKind::Unmaintained,
+                AdvisoryKind::Yanked
+            ]
+        );
+
+        assert_eq!(parse_advisories(DENY_REPORT).unwrap(), advisories[..1]);
+
+        assert!(matches!(
+            parse_advisories("{\"database\": {}}"),
+            Err(AnalyzerError::FileError(_))
+        ));
+        assert!(parse_advisories("not json").is_err());
+    }
+
+    #[test]
+    fn test_advisory_issues() {
+        let advisories = parse_advisories(AUDIT_REPORT).unwrap();
+        let issues = advisory_issues(
+            &advisories,
+            MANIFEST,
+            Some(LOCK_FILE),
+            &default_parameters(),
+        )
+        .unwrap();
+        let actual: Vec<_> = issues
+            .iter()
+            .map(|(file, issue)| {
+                (
+                    *file,
+                    issue.location.start_line,
+                    issue.message.as_str(),
+                    issue.severity,
+                )
+            })
+            .collect();
+        assert_eq!(
+            actual,
+            vec![
+                (
+                    "Cargo.toml",
+                    6,
+                    "Upgrade \"time\" 0.1.45, it is vulnerable to RUSTSEC-2020-0071 (CVE-2020-26235): Potential \
+                     segfault in the time crate. Patched versions: >=0.2.23.",
+                    Some(Severity::Major)
+                ),
+                (
+                    "Cargo.lock",
+                    4,
+                    "Replace \"ansi_term\" 0.12.1, it is unmaintained according to RUSTSEC-2021-0139: ansi_term is \
+                     Unmaintained.",
+                    Some(Severity::Minor)
+                ),
+                (
+                    "Cargo.lock",
+                    12,
+                    "Upgrade \"log\" 0.4.15, this version was yanked.",
+                    Some(Severity::Minor)
+                ),
+            ]
+        );
+
+        // Without lock file, the transitive dependencies are reported on the package
+        let issues = advisory_issues(&advisories, MANIFEST, None, &default_parameters()).unwrap();
+        assert_eq!(issues[1].0, "Cargo.toml");
+        assert_eq!(issues[1].1.location.start_line, 1);
+
+        let mut parameters = default_parameters();
+        parameters.insert("S7475:severity".to_string(), "blocker".to_string());
+        let issues = advisory_issues(&advisories, MANIFEST, None, &parameters).unwrap();
+        assert_eq!(issues[2].1.severity, Some(Severity::Blocker));
+
+        parameters.insert("disabled_rules".to_string(), "S7475".to_string());
+        assert!(advisory_issues(&advisories, MANIFEST, None, &parameters)
+            .unwrap()
+            .is_empty());
+    }
+}
@@ -15,43 +15,1015 @@
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
 use crate::{
-    issue::{find_issues, Issue},
-    tree::{parse_rust_code, AnalyzerError},
+    advisories::{advisory_issues, parse_advisories, LOCK_FILE_NAME},
+    cargo_metadata::TargetKind,
+    clippy::ClippyReport,
+    coverage::{CoverageReport, FileCoverage},
+    doctest::{find_doctest_issues, highlight_doctests},
+    duplication::{find_duplications, FileDuplication, DEFAULT_MINIMUM_TOKENS},
+    encoding::{decode, Decoded, Encoding},
+    fingerprint::{fingerprint_rust_issues, structural_hash},
+    formatting::{check_formatting, FORMATTING_KEY},
+    issue::{
+        apply_hotspot_reviews, deduplicate_issues, find_issues_timed, severity_override,
+        sort_issues, Issue, RuleDurations, RuleFailures,
+    },
+    macro_expansion::find_expanded_issues,
+    manifest::{analyze_manifest, MANIFEST_FILE_NAME},
+    options::{file_parameters, list_parameter, AnalysisOptions},
+    project_index::{
+        duplicated_function_issues, module_cycle_issues, ProjectIndex, PROJECT_RULE_KEYS,
+    },
+    test_execution::{TestCase, TestReport},
+    tree::{
+        catch_panic, check_deadline, current_cancellation, parse_rust_code,
+        parse_rust_code_incrementally, set_cancellation, set_deadline, split_lines, syntax_errors,
+        AnalyzerError, CancellationToken, SonarLocation, TreeSitterLocation,
+    },
+    validation::validate_locations,
     visitors::{
-        cpd::{calculate_cpd_tokens, CpdToken},
-        highlight::{highlight, HighlightToken},
-        metrics::{calculate_metrics, Metrics},
+        cfg::{calculate_cfg_regions, configuration, CfgRegion},
+        cpd::{calculate_borrowed_cpd_tokens, BorrowedCpdToken, CpdToken},
+        function_metrics::FunctionMetrics,
+        generated_code::{GeneratedCode, GeneratedCodeMode},
+        highlight::{highlight_with_bindings, HighlightToken},
+        metrics::{calculate_file_metrics, FileMetrics, Metrics},
+        name_resolution::resolve_bindings,
+        symbols::{symbols_of_bindings, Symbol},
+        test_code::{find_test_functions, TestCode, TestFunction},
     },
 };
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt, fs, mem,
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+use tree_sitter::Tree;
 
-#[derive(Debug)]
+/// Results of the analysis of a file.
+///
+/// The highlight tokens, the CPD tokens and the issues are sorted by location, then by token type or by rule key and
+/// message, so that the output of the same file is always the same.
+#[derive(Debug, Default)]
 pub struct Output {
     pub highlight_tokens: Vec<HighlightToken>,
+    /// Declarations and references of the symbols, computed along with the highlighting.
+    pub symbols: Vec<Symbol>,
+    /// Metrics of the production code, the test items of the file are measured in `test_metrics`.
     pub metrics: Metrics,
+    pub test_metrics: Metrics,
+    /// The file only contains test code, see `is_test_file`. It is measured as a whole in `metrics`, the plugin reports
+    /// it as a test file.
+    pub test_file: bool,
+    /// Metrics of each function of the file, computed along with the file metrics.
+    pub function_metrics: Vec<FunctionMetrics>,
+    /// Tests of the file, computed along with the metrics, see `find_test_functions`.
+    pub test_functions: Vec<TestFunction>,
+    /// Hash of the code of the file ignoring whitespace and comments, see `structural_hash`. `None` when the file was
+    /// not parsed.
+    pub structural_hash: Option<String>,
     pub cpd_tokens: Vec<CpdToken>,
     pub issues: Vec<Issue>,
+    /// Items compiled only in some configurations, computed along with the issues, see `issue_configuration`.
+    pub cfg_regions: Vec<CfgRegion>,
+    /// Conditions that made the output incomplete.
+    pub warnings: Vec<AnalysisWarning>,
+    /// Time spent in each step, when enabled by the 'telemetry' parameter.
+    pub telemetry: Option<Telemetry>,
+    /// Coverage of the executable lines of the file from the coverage reports, set by `analyze_batch`.
+    pub coverage: Option<FileCoverage>,
+    /// Results of the tests of the file from the test reports, set by `analyze_batch`.
+    pub test_cases: Vec<TestCase>,
+    /// Blocks of the file duplicated in the project, set by `analyze_batch` unless the CPD tokens are skipped.
+    pub duplication: Option<FileDuplication>,
+}
+
+/// Same as `Output`, with CPD tokens borrowing their images from the analyzed source code.
+///
+/// Embedders that serialize the results right away can use it to avoid copying the text of every token.
+/// Use `into_owned` to detach it from the source code.
+#[derive(Debug, Default)]
+pub struct BorrowedOutput<'a> {
+    pub highlight_tokens: Vec<HighlightToken>,
+    pub symbols: Vec<Symbol>,
+    pub metrics: Metrics,
+    pub test_metrics: Metrics,
+    pub test_file: bool,
+    pub function_metrics: Vec<FunctionMetrics>,
+    pub test_functions: Vec<TestFunction>,
+    pub structural_hash: Option<String>,
+    pub cpd_tokens: Vec<BorrowedCpdToken<'a>>,
+    pub issues: Vec<Issue>,
+    pub cfg_regions: Vec<CfgRegion>,
+    pub warnings: Vec<AnalysisWarning>,
+    pub telemetry: Option<Telemetry>,
+}
+
+impl Output {
+    /// Configuration predicate under which the issue is compiled, e.g. 'windows', or `None` when it is compiled in all
+    /// configurations.
+    pub fn issue_configuration(&self, issue: &Issue) -> Option<String> {
+        configuration(&self.cfg_regions, &issue.location)
+    }
+}
+
+impl BorrowedOutput<'_> {
+    pub fn into_owned(self) -> Output {
+        Output {
+            highlight_tokens: self.highlight_tokens,
+            symbols: self.symbols,
+            metrics: self.metrics,
+            test_metrics: self.test_metrics,
+            test_file: self.test_file,
+            function_metrics: self.function_metrics,
+            test_functions: self.test_functions,
+            structural_hash: self.structural_hash,
+            cpd_tokens: self
+                .cpd_tokens
+                .iter()
+                .map(BorrowedCpdToken::to_owned_token)
+                .collect(),
+            issues: self.issues,
+            cfg_regions: self.cfg_regions,
+            warnings: self.warnings,
+            telemetry: self.telemetry,
+            coverage: None,
+            test_cases: vec![],
+            duplication: None,
+        }
+    }
+}
+
+/// The independent steps of the analysis of a parsed file, each one producing a part of the output.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AnalysisStep {
+    Highlighting,
+    Metrics,
+    Cpd,
+    Issues,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum AnalysisWarning {
+    /// The analysis exceeded the configured timeout. The listed steps did not complete and are missing from the output.
+    TimedOut {
+        timeout_ms: u128,
+        skipped_steps: Vec<AnalysisStep>,
+    },
+    /// The file is larger than the configured maximum size and was not analyzed, the output is empty.
+    FileTooLarge { size: usize, max_file_size: usize },
+    /// The file contains syntax errors at the given locations. The rest of the file is analyzed, but the results are
+    /// partial in these regions.
+    SyntaxErrors { locations: Vec<SonarLocation> },
+    /// A step failed on the file, its results are missing from the output.
+    StepFailed { step: AnalysisStep, message: String },
+    /// A rule failed on the file, its issues are missing from the output.
+    RuleFailed { rule_key: String, message: String },
+    /// The file is not valid in its encoding from the given line (1-based), its invalid bytes were replaced by U+FFFD.
+    InvalidEncoding { encoding: Encoding, line: usize },
+    /// Some locations of the results exceeded the lines of the file and were clamped to them, or were outside of the
+    /// file and dropped with their results, see `validate_locations`.
+    InvalidLocations { clamped: usize, dropped: usize },
+    /// The estimated memory of the analysis exceeded the configured soft limit, see `MemoryUsage`. Only the metrics of
+    /// the file were computed, the listed steps are missing from the output.
+    MemoryLimitExceeded {
+        estimated_bytes: usize,
+        memory_limit: usize,
+        skipped_steps: Vec<AnalysisStep>,
+    },
+}
+
+/// Durations of the parts of the analysis of a file, to identify slow visitors and rules, and its memory usage.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct Telemetry {
+    pub parse: Duration,
+    /// Duration of each completed step, followed by the duration of each rule of the issues step.
+    pub durations: Vec<(String, Duration)>,
+    pub memory: MemoryUsage,
+}
+
+impl fmt::Display for Telemetry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "parse={:?}", self.parse)?;
+        for (name, duration) in &self.durations {
+            write!(f, " {}={:?}", name, duration)?;
+        }
+        write!(f, " {}", self.memory)
+    }
+}
+
+/// Approximate number of bytes of a node of the tree, including its share of the subtrees of Tree-sitter.
+const TREE_NODE_BYTES: usize = 64;
+
+/// Sizes of the data held at the same time by the analysis of a file, from which its peak memory is estimated.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct MemoryUsage {
+    pub source_bytes: usize,
+    pub tree_nodes: usize,
+    pub highlight_tokens: usize,
+    pub cpd_tokens: usize,
+    pub issues: usize,
+}
+
+impl MemoryUsage {
+    /// Usage expected from the analysis of the parsed file before running its steps, with at most one highlighting
+    /// token and one CPD token per node of the tree.
+    fn projected(tree: &Tree, source_code: &str) -> Self {
+        let tree_nodes = tree.root_node().descendant_count();
+        MemoryUsage {
+            source_bytes: source_code.len(),
+            tree_nodes,
+            highlight_tokens: tree_nodes,
+            cpd_tokens: tree_nodes,
+            issues: 0,
+        }
+    }
+
+    /// Approximate peak memory of the analysis in bytes. The sizes of the text of the issues and of the trees built by
+    /// the visitors are not counted.
+    pub fn estimated_bytes(&self) -> usize {
+        self.source_bytes
+            + self.tree_nodes * TREE_NODE_BYTES
+            + self.highlight_tokens * mem::size_of::<HighlightToken>()
+            + self.cpd_tokens * mem::size_of::<BorrowedCpdToken>()
+            + self.issues * mem::size_of::<Issue>()
+    }
+}
+
+impl fmt::Display for MemoryUsage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "memory={}B tree_nodes={} highlight_tokens={} cpd_tokens={} issues={}",
+            self.estimated_bytes(),
+            self.tree_nodes,
+            self.highlight_tokens,
+            self.cpd_tokens,
+            self.issues
+        )
+    }
+}
+
+impl fmt::Display for AnalysisStep {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AnalysisStep::Highlighting => "highlighting",
+            AnalysisStep::Metrics => "metrics",
+            AnalysisStep::Cpd => "cpd",
+            AnalysisStep::Issues => "issues",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for AnalysisWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AnalysisWarning::TimedOut {
+                timeout_ms,
+                skipped_steps,
+            } => {
+                let steps: Vec<String> = skipped_steps.iter().map(|s| s.to_string()).collect();
+                write!(
+                    f,
+                    "analysis timed out after {} ms, missing results: {}",
+                    timeout_ms,
+                    steps.join(", ")
+                )
+            }
+            AnalysisWarning::FileTooLarge {
+                size,
+                max_file_size,
+            } => write!(
+                f,
+                "file skipped, its size of {} bytes exceeds the maximum of {} bytes",
+                size, max_file_size
+            ),
+            AnalysisWarning::SyntaxErrors { locations } => {
+                let lines: Vec<String> = locations
+                    .iter()
+                    .map(|location| {
+                        if location.start_line == location.end_line {
+                            location.start_line.to_string()
+                        } else {
+                            format!("{}-{}", location.start_line, location.end_line)
+                        }
+                    })
+                    .collect();
+                write!(
+                    f,
+                    "syntax errors at lines {}, the results are partial in these regions",
+                    lines.join(", ")
+                )
+            }
+            AnalysisWarning::StepFailed { step, message } => {
+                write!(f, "{} step failed, missing results: {}", step, message)
+            }
+            AnalysisWarning::RuleFailed { rule_key, message } => {
+                write!(f, "rule {} failed, missing issues: {}", rule_key, message)
+            }
+            AnalysisWarning::InvalidEncoding { encoding, line } => write!(
+                f,
+                "file is not valid {} from line {}, its invalid bytes were replaced",
+                encoding, line
+            ),
+            AnalysisWarning::InvalidLocations { clamped, dropped } => write!(
+                f,
+                "invalid locations in the results: {} clamped to the lines of the file, {} dropped",
+                clamped, dropped
+            ),
+            AnalysisWarning::MemoryLimitExceeded {
+                estimated_bytes,
+                memory_limit,
+                skipped_steps,
+            } => {
+                let steps: Vec<String> = skipped_steps.iter().map(|s| s.to_string()).collect();
+                write!(
+                    f,
+                    "estimated memory of {} bytes exceeds the limit of {} bytes, only the metrics are computed, \
+                     missing results: {}",
+                    estimated_bytes,
+                    memory_limit,
+                    steps.join(", ")
+                )
+            }
+        }
+    }
 }
 
 pub fn analyze(
     source_code: &str,
     parameters: &HashMap<String, String>,
 ) -> Result<Output, AnalyzerError> {
-    let tree = parse_rust_code(source_code)?;
+    analyze_borrowed(source_code, parameters).map(BorrowedOutput::into_owned)
+}
+
+/// Same as `analyze`, failing with `AnalyzerError::Cancelled` once the token is cancelled, e.g. by another thread.
+///
+/// The analysis stops between its steps and at the safe points of its traversals, without waiting for the results of
+/// the remaining steps.
+pub fn analyze_cancellable(
+    source_code: &str,
+    parameters: &HashMap<String, String>,
+    cancellation: &CancellationToken,
+) -> Result<Output, AnalyzerError> {
+    let _cancellation = set_cancellation(Some(cancellation.clone()));
+    analyze(source_code, parameters)
+}
+
+/// Same as `analyze`, decoding the source code from the bytes of the file with the 'encoding' setting.
+///
+/// A file which is not valid in its encoding is still analyzed, with a warning, after replacing its invalid bytes.
+pub fn analyze_bytes(
+    bytes: &[u8],
+    parameters: &HashMap<String, String>,
+) -> Result<Output, AnalyzerError> {
+    let options = AnalysisOptions::from_parameters(parameters)?;
+    analyze_decoded(
+        &decode(bytes, options.encoding),
+        options.encoding,
+        parameters,
+    )
+}
+
+/// Same as `analyze`, for the source code decoded from the bytes of the file, warning about its invalid bytes.
+fn analyze_decoded(
+    decoded: &Decoded<'_>,
+    encoding: Encoding,
+    parameters: &HashMap<String, String>,
+) -> Result<Output, AnalyzerError> {
+    let mut output = analyze(&decoded.source_code, parameters)?;
+    if let Some(line) = decoded.first_invalid_line {
+        output
+            .warnings
+            .insert(0, AnalysisWarning::InvalidEncoding { encoding, line });
+    }
+    Ok(output)
+}
+
+/// Analyzes the files of a project together, each file being given by its path relative to the project directory and
+/// its bytes.
+///
+/// Each file is analyzed as by `analyze_bytes`, with its `file_parameters`, then the issues of the project-level rules,
+/// which depend on all the files (see `ProjectIndex`), are added to the outputs. So are the issues of the Clippy
+/// reports, the coverage of the coverage reports and the results of the test reports of the project, see
+/// `ClippyReport`, `CoverageReport` and `TestReport`, and the duplications found between the CPD tokens of the files,
+/// see `find_duplications`. The results are in the order of the files, a global error stops the whole batch.
+pub fn analyze_batch(
+    files: &[(String, Vec<u8>)],
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<Result<Output, AnalyzerError>>, AnalyzerError> {
+    let project_options = AnalysisOptions::from_parameters(parameters)?;
+    let clippy_report = ClippyReport::load(parameters)?;
+    let coverage_report = CoverageReport::load(parameters)?;
+    let test_report = TestReport::load(parameters)?;
+    let project_dir = parameters.get("project_dir").map(Path::new);
+    let mut index = ProjectIndex::default();
+    let mut outputs = Vec::with_capacity(files.len());
+    // The files are decoded once, for their analysis, the reports and the fingerprints of the issues
+    let mut sources = Vec::with_capacity(files.len());
+    // Number of lines of each file whose duplications are computed
+    let mut cpd_lines = vec![None; files.len()];
+    for (file, (path, bytes)) in files.iter().enumerate() {
+        let parameters = file_parameters(parameters, path);
+        let options = AnalysisOptions::from_parameters(&parameters)?;
+        let decoded = decode(bytes, options.encoding);
+        let mut output = match analyze_decoded(&decoded, options.encoding, &parameters) {
+            Err(err @ AnalyzerError::GlobalError(_)) => return Err(err),
+            output => output,
+        };
+        if let Ok(output) = &mut output {
+            if !options.skip_cpd && !options.excluded {
+                cpd_lines[file] = Some(split_lines(&decoded.source_code).count());
+            }
+            if let Some(clippy_report) = clippy_report.as_ref().filter(|_| !options.excluded) {
+                output
+                    .issues
+                    .extend(clippy_report.issues(path, &decoded.source_code));
+            }
+            if let Some(coverage_report) = coverage_report.as_ref().filter(|_| !options.excluded) {
+                output.coverage = coverage_report.file(path, project_dir).map(|mut coverage| {
+                    if !options.skip_metrics {
+                        coverage.retain_lines(&output.metrics.executable_lines);
+                    }
+                    coverage
+                });
+            }
+            if let Some(test_report) = test_report.as_ref().filter(|_| !options.excluded) {
+                match test_report.test_cases(path, &decoded.source_code) {
+                    Ok(test_cases) => output.test_cases = test_cases,
+                    Err(err @ AnalyzerError::GlobalError(_)) => return Err(err),
+                    // The file keeps its other results, as for the index
+                    Err(_) => {}
+                }
+            }
+            // A file which cannot be indexed keeps its own results, only its references are missing from the index
+            if let Err(err @ AnalyzerError::GlobalError(_)) =
+                index.add_file(file, path, &decoded.source_code, &options)
+            {
+                return Err(err);
+            }
+        }
+        outputs.push(output);
+        sources.push(decoded);
+    }
+
+    let paths: Vec<String> = files.iter().map(|(path, _)| path.clone()).collect();
+    // The files whose CPD tokens are skipped, or which failed, have no duplications
+    let cpd_files: Vec<usize> = (0..files.len())
+        .filter(|&file| cpd_lines[file].is_some() && outputs[file].is_ok())
+        .collect();
+    let tokens: Vec<(&str, &[CpdToken])> = cpd_files
+        .iter()
+        .filter_map(|&file| {
+            let output = outputs[file].as_ref().ok()?;
+            Some((paths[file].as_str(), output.cpd_tokens.as_slice()))
+        })
+        .collect();
+    let blocks = find_duplications(
+        &tokens,
+        project_options
+            .cpd_minimum_tokens
+            .unwrap_or(DEFAULT_MINIMUM_TOKENS),
+    );
+    for (file, blocks) in cpd_files.into_iter().zip(blocks) {
+        if let (Ok(output), Some(lines)) = (&mut outputs[file], cpd_lines[file]) {
+            output.duplication = Some(FileDuplication::new(blocks, lines));
+        }
+    }
+
+    for (file, issue) in project_rule_issues(&index, &paths, parameters)? {
+        if let Some(Ok(output)) = outputs.get_mut(file) {
+            output.issues.push(issue);
+        }
+    }
+
+    // The issues of the reports and of the project-level rules are fingerprinted along with the issues of the file
+    for (decoded, output) in sources.iter().zip(&mut outputs) {
+        let Ok(output) = output else {
+            continue;
+        };
+        deduplicate_issues(&mut output.issues);
+        sort_issues(&mut output.issues);
+        if output
+            .issues
+            .iter()
+            .all(|issue| issue.fingerprint.is_some())
+        {
+            continue;
+        }
+        let source_code = decoded
+            .source_code
+            .strip_prefix('\u{feff}')
+            .unwrap_or(&decoded.source_code);
+        if let Ok(tree) = parse_rust_code(source_code) {
+            fingerprint_rust_issues(&mut output.issues, &tree, source_code);
+        }
+    }
+    Ok(outputs)
+}
+
+/// Issues of the rules on the index of the files, with the index of their files, see `PROJECT_RULE_KEYS`.
+fn project_rule_issues(
+    index: &ProjectIndex,
+    paths: &[String],
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<(usize, Issue)>, AnalyzerError> {
+    let options = AnalysisOptions::from_parameters(parameters)?;
+    let mut project_issues: Vec<(usize, Issue)> = index
+        .unused_public_items()
+        .into_iter()
+        .map(|item| (item.file, item.to_issue()))
+        .collect();
+    project_issues.extend(duplicated_function_issues(
+        &index.duplicated_functions(),
+        paths,
+    ));
+    project_issues.extend(module_cycle_issues(&index.module_cycles(), paths));
+    let mut issues = vec![];
+    for rule_key in PROJECT_RULE_KEYS {
+        if options.disabled_rules.iter().any(|key| key == rule_key) {
+            continue;
+        }
+        let severity = severity_override(parameters, rule_key)?;
+        for (file, issue) in &project_issues {
+            if issue.rule_key == rule_key {
+                issues.push((
+                    *file,
+                    Issue {
+                        severity,
+                        ..issue.clone()
+                    },
+                ));
+            }
+        }
+    }
+    Ok(issues)
+}
+
+/// Project-level analysis of the files analyzed one at a time by `analyze_bytes`, as the plugin sends them.
+///
+/// Unlike `analyze_batch`, the results of the files are not kept: the files are only added to the index of the
+/// project, whose issues are computed once all the files are analyzed, see `ProjectAnalysis::issues`.
+#[derive(Debug, Default)]
+pub struct ProjectAnalysis {
+    index: ProjectIndex,
+    /// Paths of the files of the index, relative to the project directory.
+    paths: Vec<String>,
+}
+
+/// Issues of the project-level rules, with the paths of their files relative to the project directory.
+#[derive(Debug, Default)]
+pub struct ProjectOutput {
+    pub issues: Vec<(String, Issue)>,
+    /// Failures of the project-level rules on a file, which do not prevent the issues of the other files.
+    pub warnings: Vec<(String, String)>,
+}
+
+impl ProjectAnalysis {
+    /// Adds a file to the index, with its `file_parameters`. A file which cannot be indexed is only missing from the
+    /// index, as in `analyze_batch`.
+    pub fn add_file(
+        &mut self,
+        path: &str,
+        bytes: &[u8],
+        parameters: &HashMap<String, String>,
+    ) -> Result<(), AnalyzerError> {
+        let options = AnalysisOptions::from_parameters(parameters)?;
+        let decoded = decode(bytes, options.encoding);
+        let file = self.paths.len();
+        self.paths.push(path.to_string());
+        match self
+            .index
+            .add_file(file, path, &decoded.source_code, &options)
+        {
+            Err(err @ AnalyzerError::GlobalError(_)) => Err(err),
+            _ => Ok(()),
+        }
+    }
+
+    /// Issues of the rules on the index of the files, of the rules on the `Cargo.toml` manifest of the project
+    /// directory if any, see `analyze_manifest`, and of the advisories of the reports of the 'advisories_report_paths'
+    /// setting on its dependencies, see `advisory_issues`.
+    pub fn issues(
+        &self,
+        parameters: &HashMap<String, String>,
+    ) -> Result<ProjectOutput, AnalyzerError> {
+        let mut output = ProjectOutput {
+            issues: project_rule_issues(&self.index, &self.paths, parameters)?
+                .into_iter()
+                .map(|(file, issue)| (self.paths[file].clone(), issue))
+                .collect(),
+            warnings: vec![],
+        };
+        let project_dir = Path::new(
+            parameters
+                .get("project_dir")
+                .map_or(".", |dir| dir.as_str()),
+        );
+        let Ok(manifest) = fs::read_to_string(project_dir.join(MANIFEST_FILE_NAME)) else {
+            return Ok(output);
+        };
+        match analyze_manifest(&manifest, parameters) {
+            Ok(issues) => output.issues.extend(
+                issues
+                    .into_iter()
+                    .map(|issue| (MANIFEST_FILE_NAME.to_string(), issue)),
+            ),
+            Err(err @ AnalyzerError::GlobalError(_)) => return Err(err),
+            Err(err) => {
+                output
+                    .warnings
+                    .push((MANIFEST_FILE_NAME.to_string(), err.to_string()));
+                return Ok(output);
+            }
+        }
+        let Some(paths) = parameters.get("advisories_report_paths") else {
+            return Ok(output);
+        };
+        let lock_file = fs::read_to_string(project_dir.join(LOCK_FILE_NAME)).ok();
+        for path in list_parameter(paths) {
+            let report_path = project_dir.join(&path);
+            let report = fs::read_to_string(&report_path).map_err(|err| {
+                AnalyzerError::GlobalError(format!(
+                    "failed to read {}: {}",
+                    report_path.display(),
+                    err
+                ))
+            })?;
+            match parse_advisories(&report).and_then(|advisories| {
+                advisory_issues(&advisories, &manifest, lock_file.as_deref(), parameters)
+            }) {
+                Ok(issues) => output.issues.extend(
+                    issues
+                        .into_iter()
+                        .map(|(file, issue)| (file.to_string(), issue)),
+                ),
+                Err(err @ AnalyzerError::GlobalError(_)) => return Err(err),
+                Err(err) => output.warnings.push((path, err.to_string())),
+            }
+        }
+        Ok(output)
+    }
+}
+
+/// Sorts the collections of the output, see `Output`.
+fn sort_results(output: &mut BorrowedOutput<'_>) {
+    output
+        .highlight_tokens
+        .sort_by(|a, b| (&a.location, &a.token_type).cmp(&(&b.location, &b.token_type)));
+    output
+        .cpd_tokens
+        .sort_by(|a, b| a.location.cmp(&b.location));
+    sort_issues(&mut output.issues);
+}
+
+/// Same as `analyze`, without copying the text of the tokens out of the source code.
+pub fn analyze_borrowed<'a>(
+    source_code: &'a str,
+    parameters: &HashMap<String, String>,
+) -> Result<BorrowedOutput<'a>, AnalyzerError> {
+    analyze_borrowed_incrementally(source_code, parameters, None).map(|(output, _)| output)
+}
+
+/// Same as `analyze_borrowed`, reusing the tree of a previous version of the source code, see
+/// `parse_rust_code_incrementally`. Also returns the tree to reuse for the next version, if the file was parsed.
+pub(crate) fn analyze_borrowed_incrementally<'a>(
+    source_code: &'a str,
+    parameters: &HashMap<String, String>,
+    previous: Option<(&Tree, &str)>,
+) -> Result<(BorrowedOutput<'a>, Option<Tree>), AnalyzerError> {
+    let options = AnalysisOptions::from_parameters(parameters)?;
+    // The columns of the first line do not count the byte order mark, as for the file contents read by SonarQube
+    let source_code = source_code.strip_prefix('\u{feff}').unwrap_or(source_code);
+    if options.excluded {
+        return Ok((BorrowedOutput::default(), None));
+    }
+    if let Some(max_file_size) = options.max_file_size {
+        if source_code.len() > max_file_size {
+            let output = BorrowedOutput {
+                warnings: vec![AnalysisWarning::FileTooLarge {
+                    size: source_code.len(),
+                    max_file_size,
+                }],
+                ..BorrowedOutput::default()
+            };
+            return Ok((output, None));
+        }
+    }
+
+    let deadline = options.timeout.map(|timeout| Instant::now() + timeout);
+    let _deadline = set_deadline(deadline);
+    let cancellation = current_cancellation();
+
+    let mut steps = Vec::new();
+    if !options.skip_highlighting {
+        steps.push(AnalysisStep::Highlighting);
+    }
+    if !options.skip_metrics {
+        steps.push(AnalysisStep::Metrics);
+    }
+    // Examples are self-contained, they repeat the same setup code on purpose
+    if !options.skip_cpd && options.target_kind != Some(TargetKind::Example) {
+        steps.push(AnalysisStep::Cpd);
+    }
+    steps.push(AnalysisStep::Issues);
+
+    let mut output = BorrowedOutput {
+        test_file: options.test_file,
+        ..BorrowedOutput::default()
+    };
+    let timed_out = |skipped_steps: &[AnalysisStep]| AnalysisWarning::TimedOut {
+        timeout_ms: options.timeout.unwrap_or_default().as_millis(),
+        skipped_steps: skipped_steps.to_vec(),
+    };
+
+    let mut telemetry = Telemetry::default();
+    let start = Instant::now();
+    let tree = match parse_rust_code_incrementally(source_code, previous) {
+        Err(AnalyzerError::TimedOut) => {
+            output.warnings.push(timed_out(&steps));
+            return Ok((output, None));
+        }
+        result => result?,
+    };
+    telemetry.parse = start.elapsed();
+    output.structural_hash = Some(structural_hash(&tree, source_code));
+
+    let generated_code = GeneratedCode::find(
+        &tree,
+        source_code,
+        &options.generated_code.markers,
+        options.generated_file,
+    );
+    if generated_code.is_generated_file() {
+        match options.generated_code.mode {
+            GeneratedCodeMode::Analyze => {}
+            // The issues and duplications of generated code are not actionable, its size is still measured
+            GeneratedCodeMode::Suppress => {
+                steps.retain(|step| !matches!(step, AnalysisStep::Cpd | AnalysisStep::Issues))
+            }
+            GeneratedCodeMode::Skip => return Ok((output, Some(tree))),
+        }
+    }
+
+    let projected_memory = MemoryUsage::projected(&tree, source_code);
+    if let Some(memory_limit) = options.memory_limit {
+        let estimated_bytes = projected_memory.estimated_bytes();
+        if estimated_bytes > memory_limit {
+            // The metrics only keep a few numbers per function, unlike the tokens and issues of the other steps
+            let skipped_steps: Vec<_> = steps
+                .iter()
+                .copied()
+                .filter(|step| *step != AnalysisStep::Metrics)
+                .collect();
+            steps.retain(|step| *step == AnalysisStep::Metrics);
+            output.warnings.push(AnalysisWarning::MemoryLimitExceeded {
+                estimated_bytes,
+                memory_limit,
+                skipped_steps,
+            });
+        }
+    }
+
+    // Tree-sitter recovers from syntax errors, the steps run on the rest of the tree
+    let syntax_errors = syntax_error_locations(&tree, source_code);
+    if !syntax_errors.is_empty() {
+        output.warnings.push(AnalysisWarning::SyntaxErrors {
+            locations: syntax_errors,
+        });
+    }
+
+    let run = |step: AnalysisStep| {
+        // The deadline and the cancellation token are set per thread
+        let _deadline = set_deadline(deadline);
+        let _cancellation = set_cancellation(cancellation.clone());
+        let start = Instant::now();
+        let result = run_step(
+            step,
+            &tree,
+            source_code,
+            parameters,
+            &options,
+            &generated_code,
+        );
+        (result, start.elapsed())
+    };
+    let results: Vec<_> = if options.parallel_steps {
+        thread::scope(|scope| {
+            let handles: Vec<_> = steps
+                .iter()
+                .map(|&step| scope.spawn(move || run(step)))
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().expect("the panics of the steps are caught"))
+                .collect()
+        })
+    } else {
+        // Once the deadline is exceeded, the remaining steps time out without running
+        steps.iter().map(|&step| run(step)).collect()
+    };
+
+    let mut rule_durations = vec![];
+    let mut timed_out_steps = vec![];
+    for (step, (result, duration)) in steps.iter().zip(results) {
+        match result {
+            Ok(StepOutput::Highlighting(tokens, symbols)) => {
+                output.highlight_tokens = tokens;
+                output.symbols = symbols;
+            }
+            Ok(StepOutput::Metrics(file_metrics, test_functions)) => {
+                output.metrics = file_metrics.metrics;
+                output.test_metrics = file_metrics.test_metrics;
+                output.function_metrics = file_metrics.functions;
+                output.test_functions = test_functions;
+            }
+            Ok(StepOutput::Cpd(tokens)) => output.cpd_tokens = tokens,
+            Ok(StepOutput::Issues {
+                issues,
+                cfg_regions,
+                durations,
+                failures,
+            }) => {
+                output.issues = issues;
+                output.cfg_regions = cfg_regions;
+                rule_durations = durations;
+                output
+                    .warnings
+                    .extend(failures.into_iter().map(|(rule_key, message)| {
+                        AnalysisWarning::RuleFailed {
+                            rule_key: rule_key.to_string(),
+                            message,
+                        }
+                    }));
+            }
+            Err(AnalyzerError::TimedOut) => {
+                // Keep the results of the completed steps
+                timed_out_steps.push(*step);
+                continue;
+            }
+            Err(AnalyzerError::FileError(message)) => {
+                // Keep going with the other steps, which do not depend on this one
+                output.warnings.push(AnalysisWarning::StepFailed {
+                    step: *step,
+                    message,
+                });
+            }
+            // A cancelled analysis has no results, not even those of the completed steps
+            Err(err) => return Err(err),
+        }
+        telemetry.durations.push((step.to_string(), duration));
+    }
+    if !timed_out_steps.is_empty() {
+        output.warnings.push(timed_out(&timed_out_steps));
+    }
+
+    deduplicate_issues(&mut output.issues);
+    fingerprint_rust_issues(&mut output.issues, &tree, source_code);
+    apply_hotspot_reviews(&mut output.issues, parameters)?;
+    if let Some(warning) = validate_locations(&mut output, source_code) {
+        output.warnings.push(warning);
+    }
+    sort_results(&mut output);
 
-    Ok(Output {
-        highlight_tokens: highlight(&tree, source_code)?,
-        metrics: calculate_metrics(&tree, source_code)?,
-        cpd_tokens: calculate_cpd_tokens(&tree, source_code)?,
-        issues: find_issues(&tree, source_code, parameters)?,
+    if options.telemetry {
+        telemetry.memory = MemoryUsage {
+            highlight_tokens: output.highlight_tokens.len(),
+            cpd_tokens: output.cpd_tokens.len(),
+            issues: output.issues.len(),
+            ..projected_memory
+        };
+        telemetry.durations.extend(
+            rule_durations
+                .into_iter()
+                .map(|(rule_key, duration)| (rule_key.to_string(), duration)),
+        );
+        output.telemetry = Some(telemetry);
+    }
+
+    Ok((output, Some(tree)))
+}
+
+/// Results of a step of the analysis, moved to the output once all the steps completed.
+enum StepOutput<'a> {
+    Highlighting(Vec<HighlightToken>, Vec<Symbol>),
+    Metrics(Box<FileMetrics>, Vec<TestFunction>),
+    Cpd(Vec<BorrowedCpdToken<'a>>),
+    Issues {
+        issues: Vec<Issue>,
+        cfg_regions: Vec<CfgRegion>,
+        durations: RuleDurations,
+        failures: RuleFailures,
+    },
+}
+
+/// Runs a step on the parsed file. The steps only share immutable state, so that they can run on separate threads.
+fn run_step<'a>(
+    step: AnalysisStep,
+    tree: &Tree,
+    source_code: &'a str,
+    parameters: &HashMap<String, String>,
+    options: &AnalysisOptions,
+    generated_code: &GeneratedCode,
+) -> Result<StepOutput<'a>, AnalyzerError> {
+    check_deadline()?;
+    catch_panic(|| match step {
+        AnalysisStep::Highlighting => {
+            let bindings = resolve_bindings(tree, source_code)?;
+            let mut tokens = highlight_with_bindings(tree, source_code, &bindings)?;
+            if options.analyze_doctests {
+                tokens.extend(highlight_doctests(tree, source_code)?);
+            }
+            let symbols = symbols_of_bindings(&bindings);
+            Ok(StepOutput::Highlighting(tokens, symbols))
+        }
+        AnalysisStep::Metrics => {
+            let file_metrics = calculate_file_metrics(tree, source_code, options.test_file)?;
+            let test_functions = find_test_functions(tree, source_code);
+            Ok(StepOutput::Metrics(Box::new(file_metrics), test_functions))
+        }
+        AnalysisStep::Cpd => calculate_borrowed_cpd_tokens(
+            tree,
+            source_code,
+            &options.cpd,
+            &TestCode::find(tree, source_code, options.test_file),
+            generated_code,
+        )
+        .map(StepOutput::Cpd),
+        AnalysisStep::Issues => {
+            // The failures of the type-aware rules are added to the ones of the rules
+            #[cfg_attr(not(feature = "type-aware"), allow(unused_mut))]
+            let (mut issues, durations, mut failures) =
+                find_issues_timed(tree, source_code, parameters)?;
+            let cfg_regions = calculate_cfg_regions(tree, source_code);
+            if options.analyze_doctests {
+                issues.extend(find_doctest_issues(tree, source_code, parameters)?);
+            }
+            if options.expand_macros {
+                // Issues on the tokens passed to the macros may already be reported on the invocations, they are
+                // deduplicated with the others
+                issues.extend(find_expanded_issues(tree, source_code, parameters)?);
+            }
+            if let Some(rustfmt) = &options.rustfmt {
+                if !options
+                    .disabled_rules
+                    .iter()
+                    .any(|key| key == FORMATTING_KEY)
+                {
+                    let project_dir = parameters.get("project_dir").map(Path::new);
+                    let severity = severity_override(parameters, FORMATTING_KEY)?;
+                    issues.extend(
+                        check_formatting(source_code, rustfmt, project_dir)?
+                            .map(|issue| Issue { severity, ..issue }),
+                    );
+                }
+            }
+            #[cfg(feature = "type-aware")]
+            match crate::type_aware::find_type_aware_issues(
+                tree,
+                source_code,
+                parameters,
+                &options.disabled_rules,
+            ) {
+                Ok(type_aware_issues) => issues.extend(type_aware_issues),
+                Err(err @ AnalyzerError::GlobalError(_)) => return Err(err),
+                // e.g. rust-analyzer is not installed, the file keeps the issues of the other rules
+                Err(err) => {
+                    failures.push((crate::type_aware::DISCARDED_RESULT_KEY, err.to_string()))
+                }
+            }
+            Ok(StepOutput::Issues {
+                issues,
+                cfg_regions,
+                durations,
+                failures,
+            })
+        }
     })
 }
 
+/// Locations of the syntax errors of the tree, see `syntax_errors`.
+fn syntax_error_locations(tree: &Tree, source_code: &str) -> Vec<SonarLocation> {
+    let mut locations: Vec<SonarLocation> = syntax_errors(tree)
+        .into_iter()
+        .map(|node| TreeSitterLocation::from_tree_sitter_node(node).to_sonar_location(source_code))
+        .collect();
+    locations.sort();
+    locations
+}
+
 #[cfg(test)]
 mod tests {
-    use std::vec;
+    use std::{collections::BTreeSet, vec};
 
+    use crate::duplication::DuplicatedBlock;
+    use crate::issue::Severity;
+    use crate::options::file_parameters;
     use crate::tree::SonarLocation;
+    use crate::visitors::halstead::HalsteadMetrics;
     use crate::visitors::highlight::HighlightTokenType;
 
     use super::*;
@@ -76,8 +1048,38 @@ fn main() {
                 functions: 1,
                 statements: 2,
                 classes: 0,
+                structs: 0,
+                enums: 0,
+                traits: 0,
+                impls: 0,
+                modules: 0,
+                public_api: 0,
+                public_documented_api: 0,
+                exported_items: 0,
+                unsafe_blocks: 0,
+                unsafe_functions: 0,
+                unsafe_impls: 0,
+                unsafe_lines: 0,
                 cognitive_complexity: 0,
-                cyclomatic_complexity: 1
+                cyclomatic_complexity: 1,
+                halstead: Some(HalsteadMetrics {
+                    distinct_operators: 7,
+                    distinct_operands: 5,
+                    operators: 9,
+                    operands: 5,
+                }),
+                ncloc_data: BTreeSet::from([3, 5, 6, 7]),
+                comment_lines_data: BTreeSet::from([2, 4]),
+                executable_lines: BTreeSet::from([5, 6]),
+                function_complexity_distribution: vec![
+                    (1, 1),
+                    (2, 0),
+                    (4, 0),
+                    (6, 0),
+                    (8, 0),
+                    (10, 0),
+                    (12, 0)
+                ],
             }
         );
 
@@ -139,13 +1141,23 @@ fn main() {
                     end_column: 28,
                 },
             },
+            HighlightToken {
+                token_type: HighlightTokenType::PreprocessDirective,
+                location: SonarLocation {
+                    start_line: 6,
+                    start_column: 4,
+                    end_line: 6,
+                    end_column: 12,
+                },
+            },
         ];
         expected_highlighting.sort();
 
         assert_eq!(expected_highlighting, actual_highlighting);
 
         let issues = output.issues;
-        assert_eq!(issues.len(), 0);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule_key, "S1481");
     }
 
     #[test]
@@ -165,7 +1177,7 @@ fn main() {
                 }
             }]
         );
-        assert_eq!("𠱓".as_bytes().len(), 4);
+        assert_eq!("𠱓".len(), 4);
 
         // 3 byte unicode
         assert_eq!(
@@ -180,7 +1192,7 @@ fn main() {
                 }
             }]
         );
-        assert_eq!("ࢣ".as_bytes().len(), 3);
+        assert_eq!("ࢣ".len(), 3);
 
         // 2 byte unicode
         assert_eq!(
@@ -195,7 +1207,63 @@ fn main() {
                 }
             }]
         );
-        assert_eq!("©".as_bytes().len(), 2);
+        assert_eq!("©".len(), 2);
+    }
+
+    #[test]
+    fn test_unicode_string_literals() {
+        let string_tokens = |source_code: &str| -> Vec<SonarLocation> {
+            let mut tokens = analyze(source_code, &test_parameters())
+                .unwrap()
+                .highlight_tokens;
+            tokens.sort();
+            tokens
+                .into_iter()
+                .filter(|token| token.token_type == HighlightTokenType::String)
+                .map(|token| token.location)
+                .collect()
+        };
+
+        // Raw string with several hashes, the inner '"#' does not end it
+        assert_eq!(
+            string_tokens(r####"const S: &str = r##"𠱓"#©"##;"####),
+            vec![SonarLocation {
+                start_line: 1,
+                start_column: 16,
+                end_line: 1,
+                end_column: 28,
+            }]
+        );
+
+        // Multi-line raw string
+        assert_eq!(
+            string_tokens("const S: &str = r#\"\n𠱓\n  ©\"#;"),
+            vec![SonarLocation {
+                start_line: 1,
+                start_column: 16,
+                end_line: 3,
+                end_column: 5,
+            }]
+        );
+
+        // Byte string and char literals
+        assert_eq!(
+            string_tokens("const B: &[u8] = b\"©\"; const C: char = '𠱓';"),
+            vec![
+                SonarLocation {
+                    start_line: 1,
+                    start_column: 17,
+                    end_line: 1,
+                    end_column: 21,
+                },
+                SonarLocation {
+                    start_line: 1,
+                    start_column: 39,
+                    end_line: 1,
+                    end_column: 43,
+                }
+            ]
+        );
     }
 
     #[test]
@@ -251,7 +1319,892 @@ fn main() {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_skip_outputs() {
+        let source_code = "fn main() {\n    let _x = 42\n}";
+
+        let mut parameters = test_parameters();
+        parameters.insert("skip_highlighting".to_string(), "true".to_string());
+        parameters.insert("skip_cpd".to_string(), "true".to_string());
+        parameters.insert("skip_metrics".to_string(), "true".to_string());
+
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(output.highlight_tokens, vec![]);
+        assert_eq!(output.symbols, vec![]);
+        assert_eq!(output.cpd_tokens, vec![]);
+        assert_eq!(output.metrics, Metrics::default());
+        assert_eq!(output.issues.len(), 1);
+
+        let output = analyze(source_code, &test_parameters()).unwrap();
+        assert!(!output.highlight_tokens.is_empty());
+        assert_eq!(output.symbols.len(), 2);
+        assert!(!output.cpd_tokens.is_empty());
+        assert_eq!(output.metrics.functions, 1);
+        assert_eq!(output.function_metrics.len(), 1);
+    }
+
+    #[test]
+    fn test_test_file() {
+        let source_code = "fn helper() {\n    setup();\n}";
+
+        let output = analyze(source_code, &test_parameters()).unwrap();
+        assert_eq!(output.metrics.ncloc, 3);
+        assert_eq!(output.test_metrics.ncloc, 0);
+
+        assert!(!output.test_file);
+
+        // The test files are measured as a whole
+        let mut parameters = test_parameters();
+        parameters.insert("test_file".to_string(), "true".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert!(output.test_file);
+        assert_eq!(output.metrics.ncloc, 3);
+        assert_eq!(output.metrics.ncloc_data, BTreeSet::from([1, 2, 3]));
+        assert_eq!(output.metrics.functions, 1);
+        assert_eq!(output.test_metrics.ncloc, 0);
+    }
+
+    #[test]
+    fn test_cancellation() {
+        let source_code = "fn main() {\n    let _x = 42;\n}";
+        let cancellation = CancellationToken::new();
+        let output = analyze_cancellable(source_code, &test_parameters(), &cancellation).unwrap();
+        assert_eq!(output.metrics.functions, 1);
+
+        // Once cancelled, the analysis stops as soon as it starts
+        cancellation.cancel();
+        for parallel_steps in ["false", "true"] {
+            let mut parameters = test_parameters();
+            parameters.insert("parallel_steps".to_string(), parallel_steps.to_string());
+            assert!(matches!(
+                analyze_cancellable(source_code, &parameters, &cancellation),
+                Err(AnalyzerError::Cancelled)
+            ));
+        }
+        assert!(analyze(source_code, &test_parameters()).is_ok());
+    }
+
+    #[test]
+    fn test_timeout() {
+        let source_code = "fn main() {\n    let _x = 42;\n}";
+
+        let mut parameters = test_parameters();
+        parameters.insert("timeout_ms".to_string(), "60000".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(output.warnings, vec![]);
+        assert_eq!(output.metrics.functions, 1);
+
+        // A zero timeout is always exceeded, so none of the steps can complete
+        parameters.insert("timeout_ms".to_string(), "0".to_string());
+        parameters.insert("skip_cpd".to_string(), "true".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(
+            output.warnings,
+            vec![AnalysisWarning::TimedOut {
+                timeout_ms: 0,
+                skipped_steps: vec![
+                    AnalysisStep::Highlighting,
+                    AnalysisStep::Metrics,
+                    AnalysisStep::Issues
+                ],
+            }]
+        );
+        assert_eq!(
+            output.warnings[0].to_string(),
+            "analysis timed out after 0 ms, missing results: highlighting, metrics, issues"
+        );
+        assert_eq!(output.highlight_tokens, vec![]);
+        assert_eq!(output.metrics, Metrics::default());
+    }
+
+    #[test]
+    fn test_max_file_size() {
+        let source_code = "fn main() {\n    let _x = 42\n}";
+
+        let mut parameters = test_parameters();
+        parameters.insert("max_file_size".to_string(), source_code.len().to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert!(matches!(
+            output.warnings.as_slice(),
+            [AnalysisWarning::SyntaxErrors { .. }]
+        ));
+        assert_eq!(output.issues.len(), 1);
+
+        parameters.insert("max_file_size".to_string(), "10".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(
+            output.warnings,
+            vec![AnalysisWarning::FileTooLarge {
+                size: 29,
+                max_file_size: 10
+            }]
+        );
+        assert_eq!(
+            output.warnings[0].to_string(),
+            "file skipped, its size of 29 bytes exceeds the maximum of 10 bytes"
+        );
+        assert_eq!(output.highlight_tokens, vec![]);
+        assert_eq!(output.metrics, Metrics::default());
+        assert_eq!(output.issues, vec![]);
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let source_code = r#"
+fn broken() {
+    let x = ;
+}
+
+fn valid(a: bool) {
+    if a {
+        println!("{}", 42);
+    }
+}
+"#;
+        let output = analyze(source_code, &test_parameters()).unwrap();
+        assert_eq!(output.warnings.len(), 1);
+        assert_eq!(
+            output.warnings[0].to_string(),
+            "syntax errors at lines 3, the results are partial in these regions"
+        );
+
+        // The code around the syntax error is still analyzed
+        assert_eq!(output.metrics.functions, 2);
+        assert_eq!(output.metrics.cognitive_complexity, 1);
+        assert!(!output.highlight_tokens.is_empty());
+        assert!(!output.cpd_tokens.is_empty());
+        assert!(output.issues.iter().any(|issue| issue.rule_key == "S2260"));
+    }
+
+    #[test]
+    fn test_invalid_encoding() {
+        let bytes = b"fn main() {}\n// Caf\xe9\nfn f() {}\n";
+        let output = analyze_bytes(bytes, &test_parameters()).unwrap();
+        assert_eq!(output.metrics.functions, 2);
+        assert_eq!(
+            output.warnings,
+            vec![AnalysisWarning::InvalidEncoding {
+                encoding: Encoding::Utf8,
+                line: 2
+            }]
+        );
+        assert_eq!(
+            output.warnings[0].to_string(),
+            "file is not valid UTF-8 from line 2, its invalid bytes were replaced"
+        );
+
+        let mut parameters = test_parameters();
+        parameters.insert("encoding".to_string(), "ISO-8859-1".to_string());
+        let output = analyze_bytes(bytes, &parameters).unwrap();
+        assert_eq!(output.metrics.functions, 2);
+        assert!(output.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_project_settings() {
+        let mut parameters = test_parameters();
+        parameters.insert("disabled_rules".to_string(), "S2260".to_string());
+        let output = analyze("fn main() { let _x = 42 }", &parameters).unwrap();
+        assert!(output.issues.is_empty());
+        assert_eq!(output.metrics.functions, 1);
+
+        parameters.insert("excluded".to_string(), "true".to_string());
+        let output = analyze("fn main() {}", &parameters).unwrap();
+        assert_eq!(output.metrics.functions, 0);
+        assert!(output.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_severity_overrides() {
+        let source_code = "fn main() { let _x = 42 }";
+        let output = analyze(source_code, &test_parameters()).unwrap();
+        assert_eq!(output.issues[0].severity, None);
+
+        let mut parameters = test_parameters();
+        parameters.insert("S2260:severity".to_string(), "blocker".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(output.issues[0].severity, Some(Severity::Blocker));
+
+        parameters.insert("S2260:severity".to_string(), "URGENT".to_string());
+        assert!(matches!(
+            analyze(source_code, &parameters),
+            Err(AnalyzerError::GlobalError(message)) if message.starts_with("could not parse 'S2260:severity' parameter: unknown severity 'URGENT'")
+        ));
+    }
+
+    #[test]
+    fn test_example_target() {
+        let source_code = "fn main() { let _x = 42; }";
+        let mut parameters = test_parameters();
+        parameters.insert("example_paths".to_string(), "examples/**".to_string());
+        let output = analyze(
+            source_code,
+            &file_parameters(&parameters, "examples/demo.rs"),
+        )
+        .unwrap();
+        assert!(output.cpd_tokens.is_empty());
+        let output = analyze(source_code, &file_parameters(&parameters, "src/main.rs")).unwrap();
+        assert!(!output.cpd_tokens.is_empty());
+    }
+
+    #[test]
+    fn test_analyze_batch() {
+        let files = vec![
+            (
+                "src/lib.rs".to_string(),
+                b"pub fn used() -> u8 { 1 }\npub fn unused() -> u8 { 2 }\n".to_vec(),
+            ),
+            (
+                "src/main.rs".to_string(),
+                b"fn main() { used() }\n".to_vec(),
+            ),
+        ];
+        let outputs = analyze_batch(&files, &test_parameters()).unwrap();
+        let issues: Vec<Vec<(String, String, usize)>> = outputs
+            .iter()
+            .map(|output| {
+                output
+                    .as_ref()
+                    .unwrap()
+                    .issues
+                    .iter()
+                    .map(|issue| {
+                        (
+                            issue.rule_key.clone(),
+                            issue.message.clone(),
+                            issue.location.start_line,
+                        )
+                    })
+                    .collect()
+            })
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                vec![(
+                    "S7466".to_string(),
+                    "Remove this unused public function \"unused\".".to_string(),
+                    2
+                )],
+                vec![]
+            ]
+        );
+
+        let mut parameters = test_parameters();
+        parameters.insert("S7466:severity".to_string(), "minor".to_string());
+        let outputs = analyze_batch(&files, &parameters).unwrap();
+        assert_eq!(
+            outputs[0].as_ref().unwrap().issues[0].severity,
+            Some(Severity::Minor)
+        );
+
+        parameters.insert("disabled_rules".to_string(), "S7466".to_string());
+        let outputs = analyze_batch(&files, &parameters).unwrap();
+        assert!(outputs[0].as_ref().unwrap().issues.is_empty());
+
+        parameters.insert("skip_cpd".to_string(), "maybe".to_string());
+        assert!(analyze_batch(&files, &parameters).is_err());
+    }
+
+    #[test]
+    fn test_project_analysis() {
+        let dir = std::env::temp_dir().join(format!("project-analysis-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[dependencies]\nserde = \"*\"\n",
+        )
+        .unwrap();
+        let mut parameters = test_parameters();
+        parameters.insert("project_dir".to_string(), dir.to_string_lossy().to_string());
+
+        let mut project = ProjectAnalysis::default();
+        for (path, source_code) in [
+            (
+                "src/lib.rs",
+                "pub fn used() -> u8 { 1 }\npub fn unused() -> u8 { 2 }\n",
+            ),
+            ("src/main.rs", "fn main() { used() }\n"),
+        ] {
+            project
+                .add_file(
+                    path,
+                    source_code.as_bytes(),
+                    &file_parameters(&parameters, path),
+                )
+                .unwrap();
+        }
+        let output = project.issues(&parameters).unwrap();
+        let issues: Vec<(&str, &str, usize)> = output
+            .issues
+            .iter()
+            .map(|(path, issue)| {
+                (
+                    path.as_str(),
+                    issue.rule_key.as_str(),
+                    issue.location.start_line,
+                )
+            })
+            .collect();
+        assert!(issues.contains(&("src/lib.rs", "S7466", 2)));
+        assert!(issues.contains(&("Cargo.toml", "S7469", 5)));
+        assert!(output.warnings.is_empty());
+
+        parameters.insert(
+            "advisories_report_paths".to_string(),
+            "missing.json".to_string(),
+        );
+        assert!(matches!(
+            project.issues(&parameters),
+            Err(AnalyzerError::GlobalError(message)) if message.starts_with("failed to read")
+        ));
+
+        fs::write(dir.join("Cargo.toml"), "[package\n").unwrap();
+        let output = project.issues(&parameters).unwrap();
+        assert_eq!(output.warnings.len(), 1);
+        assert!(output.issues.iter().all(|(path, _)| path == "src/lib.rs"));
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_analyze_batch_duplications() {
+        let function = "fn area(width: u32, height: u32) -> u32 {\n    let area = width * height;\n    area\n}\n";
+        let files = vec![
+            ("src/a.rs".to_string(), function.as_bytes().to_vec()),
+            (
+                "src/b.rs".to_string(),
+                format!("fn other() {{}}\n\n{}", function).into_bytes(),
+            ),
+        ];
+        let mut parameters = test_parameters();
+        parameters.insert("cpd_minimum_tokens".to_string(), "10".to_string());
+        let outputs = analyze_batch(&files, &parameters).unwrap();
+        let duplication = outputs[1].as_ref().unwrap().duplication.as_ref().unwrap();
+        assert_eq!(
+            duplication.blocks,
+            vec![DuplicatedBlock {
+                start_line: 3,
+                end_line: 6,
+                other_file: "src/a.rs".to_string(),
+                other_start_line: 1,
+                other_end_line: 4,
+            }]
+        );
+        assert_eq!(duplication.density(), 4.0 * 100.0 / 6.0);
+        assert_eq!(
+            outputs[0]
+                .as_ref()
+                .unwrap()
+                .duplication
+                .as_ref()
+                .unwrap()
+                .density(),
+            100.0
+        );
+
+        // Without the setting, the function is too small to be a duplicated block
+        let outputs = analyze_batch(&files, &test_parameters()).unwrap();
+        assert!(outputs[0]
+            .as_ref()
+            .unwrap()
+            .duplication
+            .as_ref()
+            .unwrap()
+            .blocks
+            .is_empty());
+
+        parameters.insert("exclusions".to_string(), "src/b.rs".to_string());
+        let outputs = analyze_batch(&files, &parameters).unwrap();
+        assert!(outputs[0]
+            .as_ref()
+            .unwrap()
+            .duplication
+            .as_ref()
+            .unwrap()
+            .blocks
+            .is_empty());
+        assert_eq!(outputs[1].as_ref().unwrap().duplication, None);
+    }
+
+    #[test]
+    fn test_generated_code() {
+        let source_code = "// @generated by prost-build\nfn main() { let _x = 42 }\n";
+        let output = analyze(source_code, &test_parameters()).unwrap();
+        assert_eq!(output.metrics.ncloc, 1);
+        assert!(!output.highlight_tokens.is_empty());
+        assert!(output.cpd_tokens.is_empty());
+        assert!(output.issues.is_empty());
+
+        let mut parameters = test_parameters();
+        parameters.insert("generated_code".to_string(), "analyze".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(output.issues.len(), 1);
+
+        parameters.insert("generated_code".to_string(), "skip".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(output.metrics.ncloc, 0);
+        assert!(output.highlight_tokens.is_empty());
+
+        // Files generated without markers are identified by their path
+        let mut parameters = file_parameters(
+            &HashMap::from([("generated_paths".to_string(), "src/pb/**".to_string())]),
+            "src/pb/foo.rs",
+        );
+        parameters.extend(test_parameters());
+        let output = analyze("fn main() { let _x = 42 }", &parameters).unwrap();
+        assert_eq!(output.metrics.ncloc, 1);
+        assert!(output.issues.is_empty());
+    }
+
+    #[test]
+    fn test_bom_and_shebang() {
+        let source_code =
+            "\u{feff}#!/usr/bin/env -S cargo +nightly -Zscript\nfn main() { let _s = \"é\"; }\n";
+        let output = analyze(source_code, &test_parameters()).unwrap();
+
+        assert_eq!(
+            output.highlight_tokens[0].location,
+            SonarLocation {
+                start_line: 2,
+                start_column: 0,
+                end_line: 2,
+                end_column: 2,
+            }
+        );
+        assert_eq!(
+            output
+                .highlight_tokens
+                .iter()
+                .find(|token| token.token_type == HighlightTokenType::String)
+                .map(|token| token.location.clone()),
+            Some(SonarLocation {
+                start_line: 2,
+                start_column: 21,
+                end_line: 2,
+                end_column: 24,
+            })
+        );
+        // The shebang is neither code nor a duplicable token
+        assert_eq!(output.metrics.ncloc_data, BTreeSet::from([2]));
+        assert_eq!(output.cpd_tokens[0].image, "fn");
+        assert!(output.issues.is_empty());
+
+        assert_eq!(
+            analyze("\u{feff}fn main() {}", &test_parameters())
+                .unwrap()
+                .highlight_tokens[0]
+                .location,
+            SonarLocation {
+                start_line: 1,
+                start_column: 0,
+                end_line: 1,
+                end_column: 2,
+            }
+        );
+    }
+
+    #[test]
+    fn test_expand_macros() {
+        let source_code = r#"
+macro_rules! nested {
+    ($name:ident, $a:expr, $b:expr) => {
+        fn $name() {
+            if $a { run(); if $b { run(); if $a { run() } } }
+        }
+    };
+}
+
+nested!(branches, x > 0, y > 0);
+"#;
+        let mut parameters = test_parameters();
+        parameters.insert("S3776:threshold".to_string(), "5".to_string());
+        assert!(analyze(source_code, &parameters).unwrap().issues.is_empty());
+
+        parameters.insert("expand_macros".to_string(), "true".to_string());
+        let issues = analyze(source_code, &parameters).unwrap().issues;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule_key, "S3776");
+        assert!(issues[0].fingerprint.is_some());
+        assert_eq!(issues[0].location.start_line, 10);
+    }
+
+    #[test]
+    fn test_doctests() {
+        let source_code = "/// ```\n/// let x = ;\n/// ```\nfn main() {\n    run();\n}\n";
+        let mut parameters = test_parameters();
+        let output = analyze(source_code, &parameters).unwrap();
+        assert!(output.issues.is_empty());
+        let highlight_tokens = output.highlight_tokens.len();
+
+        parameters.insert("analyze_doctests".to_string(), "true".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(output.issues.len(), 1);
+        assert_eq!(output.issues[0].rule_key, "S2260");
+        assert_eq!(output.issues[0].location.start_line, 2);
+        assert!(output.highlight_tokens.len() > highlight_tokens);
+    }
+
+    #[test]
+    fn test_duplicated_expanded_issues() {
+        let source_code = r#"
+macro_rules! twice {
+    ($body:block) => {
+        fn first() $body
+        fn second() $body
+    };
+}
+
+twice!({ if a { run(); if b { run(); if c { run() } } } });
+"#;
+        let mut parameters = test_parameters();
+        parameters.insert("S3776:threshold".to_string(), "5".to_string());
+        parameters.insert("expand_macros".to_string(), "true".to_string());
+        let issues = analyze(source_code, &parameters).unwrap().issues;
+        assert_eq!(issues.len(), 1);
+    }
+
+    #[test]
+    fn test_rustfmt() {
+        let source_code = "fn main() {\n  let _x=1;\n}\n";
+        let mut parameters = test_parameters();
+        assert!(analyze(source_code, &parameters).unwrap().issues.is_empty());
+
+        parameters.insert("rustfmt".to_string(), "rustfmt".to_string());
+        parameters.insert("S7474:severity".to_string(), "info".to_string());
+        let issues = analyze(source_code, &parameters).unwrap().issues;
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule_key, "S7474");
+        assert_eq!(issues[0].location.start_line, 2);
+        assert_eq!(issues[0].severity, Some(Severity::Info));
+
+        parameters.insert("disabled_rules".to_string(), "S7474".to_string());
+        assert!(analyze(source_code, &parameters).unwrap().issues.is_empty());
+    }
+
+    #[test]
+    fn test_cfg_branches() {
+        let source_code = r#"
+#[cfg(windows)]
+fn separator() -> char {
+    let _x = 42
+    '\\'
+}
+
+#[cfg(not(windows))]
+fn separator() -> char {
+    '/'
+}
+
+fn main() {
+    separator();
+}
+"#;
+        let output = analyze(source_code, &test_parameters()).unwrap();
+
+        // Both branches are measured
+        assert_eq!(output.metrics.functions, 3);
+        assert_eq!(
+            output
+                .issues
+                .iter()
+                .map(|issue| (issue.rule_key.as_str(), output.issue_configuration(issue)))
+                .collect::<Vec<_>>(),
+            vec![("S2260", Some("windows".to_string()))]
+        );
+        assert_eq!(output.cfg_regions.len(), 2);
+    }
+
+    #[test]
+    fn test_failure_warnings() {
+        let step_failed = AnalysisWarning::StepFailed {
+            step: AnalysisStep::Metrics,
+            message: "unexpected node".to_string(),
+        };
+        assert_eq!(
+            step_failed.to_string(),
+            "metrics step failed, missing results: unexpected node"
+        );
+
+        let rule_failed = AnalysisWarning::RuleFailed {
+            rule_key: "S3776".to_string(),
+            message: "unexpected node".to_string(),
+        };
+        assert_eq!(
+            rule_failed.to_string(),
+            "rule S3776 failed, missing issues: unexpected node"
+        );
+
+        let syntax_errors = AnalysisWarning::SyntaxErrors {
+            locations: vec![
+                SonarLocation {
+                    start_line: 2,
+                    start_column: 0,
+                    end_line: 2,
+                    end_column: 3,
+                },
+                SonarLocation {
+                    start_line: 5,
+                    start_column: 4,
+                    end_line: 7,
+                    end_column: 1,
+                },
+            ],
+        };
+        assert_eq!(
+            syntax_errors.to_string(),
+            "syntax errors at lines 2, 5-7, the results are partial in these regions"
+        );
+    }
+
+    #[test]
+    fn test_telemetry() {
+        let source_code = "fn main() {\n    let _x = 42;\n}";
+
+        let output = analyze(source_code, &test_parameters()).unwrap();
+        assert_eq!(output.telemetry, None);
+
+        let mut parameters = test_parameters();
+        parameters.insert("telemetry".to_string(), "true".to_string());
+        parameters.insert("skip_cpd".to_string(), "true".to_string());
+        let telemetry = analyze(source_code, &parameters)
+            .unwrap()
+            .telemetry
+            .unwrap();
+        let names: Vec<&str> = telemetry
+            .durations
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "highlighting",
+                "metrics",
+                "issues",
+                "S3776",
+                "S7465",
+                "S2260",
+                "S138",
+                "S107",
+                "S104",
+                "S7467",
+                "S7468",
+                "S1481",
+                "S1172",
+                "S125",
+                "S1135",
+                "S1134",
+                "S1186",
+                "S108",
+                "S3923",
+                "S1871",
+                "S1862",
+                "S134",
+                "S1125",
+                "S1066",
+                "S109",
+                "S1764",
+                "S1192",
+                "S2068",
+                "S1313",
+                "S124"
+            ]
+        );
+        assert!(telemetry
+            .to_string()
+            .starts_with(&format!("parse={:?} highlighting=", telemetry.parse)));
+        assert_eq!(telemetry.memory.source_bytes, source_code.len());
+        assert_eq!(telemetry.memory.cpd_tokens, 0);
+        assert!(telemetry.memory.highlight_tokens > 0);
+        assert!(telemetry.memory.tree_nodes > telemetry.memory.highlight_tokens);
+        assert!(telemetry.to_string().ends_with(&format!(
+            " memory={}B tree_nodes={} highlight_tokens={} cpd_tokens=0 issues=0",
+            telemetry.memory.estimated_bytes(),
+            telemetry.memory.tree_nodes,
+            telemetry.memory.highlight_tokens
+        )));
+    }
+
+    #[test]
+    fn test_memory_limit() {
+        let source_code = "fn main() {\n    let _x = 42;\n}";
+
+        let mut parameters = test_parameters();
+        parameters.insert("memory_limit".to_string(), "1000000".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(output.warnings, vec![]);
+        assert!(!output.highlight_tokens.is_empty());
+
+        parameters.insert("memory_limit".to_string(), "1000".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        let estimated_bytes = match &output.warnings[..] {
+            [AnalysisWarning::MemoryLimitExceeded {
+                estimated_bytes,
+                memory_limit: 1000,
+                skipped_steps,
+            }] => {
+                assert_eq!(
+                    skipped_steps,
+                    &vec![
+                        AnalysisStep::Highlighting,
+                        AnalysisStep::Cpd,
+                        AnalysisStep::Issues
+                    ]
+                );
+                *estimated_bytes
+            }
+            other => panic!("unexpected warnings {:?}", other),
+        };
+        assert_eq!(
+            output.warnings[0].to_string(),
+            format!(
+                "estimated memory of {} bytes exceeds the limit of 1000 bytes, only the metrics are computed, \
+                 missing results: highlighting, cpd, issues",
+                estimated_bytes
+            )
+        );
+        assert_eq!(output.metrics.functions, 1);
+        assert_eq!(output.highlight_tokens, vec![]);
+        assert_eq!(output.cpd_tokens, vec![]);
+    }
+
+    #[test]
+    fn test_parallel_steps() {
+        let source_code = r#"
+fn foo(x: bool) -> i32 {
+    if x {
+        let y = 42
+    }
+    /// ```
+    /// foo(true);
+    /// ```
+    0
+}
+"#;
+        let mut parameters = test_parameters();
+        parameters.insert("analyze_doctests".to_string(), "true".to_string());
+        let sequential = analyze(source_code, &parameters).unwrap();
+        parameters.insert("parallel_steps".to_string(), "true".to_string());
+        let parallel = analyze(source_code, &parameters).unwrap();
+
+        assert!(!parallel.issues.is_empty());
+        assert_eq!(format!("{:?}", parallel), format!("{:?}", sequential));
+
+        parameters.insert("timeout_ms".to_string(), "0".to_string());
+        let output = analyze(source_code, &parameters).unwrap();
+        assert_eq!(
+            output.warnings,
+            vec![AnalysisWarning::TimedOut {
+                timeout_ms: 0,
+                skipped_steps: vec![
+                    AnalysisStep::Highlighting,
+                    AnalysisStep::Metrics,
+                    AnalysisStep::Cpd,
+                    AnalysisStep::Issues
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_test_functions() {
+        let source_code =
+            "#[test]\nfn test_main() {}\n\n#[tokio::test]\nasync fn test_async() {}\n";
+
+        let output = analyze(source_code, &test_parameters()).unwrap();
+        let names: Vec<&str> = output
+            .test_functions
+            .iter()
+            .map(|test| test.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["test_main", "test_async"]);
+        assert_eq!(output.test_functions[1].location.start_line, 5);
+    }
+
+    #[test]
+    fn test_analyze_borrowed() {
+        let source_code = "fn main() {\n    let _x = 42;\n}";
+
+        let output = analyze_borrowed(source_code, &test_parameters()).unwrap();
+        let images: Vec<&str> = output.cpd_tokens.iter().map(|token| token.image).collect();
+        assert_eq!(
+            images,
+            vec!["fn", "main", "(", ")", "{", "let", "_x", "=", "NUMBER", ";", "}"]
+        );
+        // Images other than the normalized ones point into the source code
+        assert!(source_code
+            .as_bytes()
+            .as_ptr_range()
+            .contains(&images[1].as_ptr()));
+
+        let owned = output.into_owned();
+        assert_eq!(
+            owned.cpd_tokens,
+            analyze(source_code, &test_parameters()).unwrap().cpd_tokens
+        );
+        assert_eq!(owned.metrics.functions, 1);
+    }
+
+    #[test]
+    fn test_sorted_output() {
+        let source_code = r#"
+// TODO: remove
+/// Documented
+#[derive(Debug)]
+struct S(&'static str);
+
+fn main() {
+    // TODO: later
+    let s = S("a");
+    println!("{:?}", s);
+}
+"#;
+        let mut parameters = test_parameters();
+        parameters.insert("S124:regularExpression".to_string(), "TODO".to_string());
+        parameters.insert("S124:message".to_string(), "Fix it".to_string());
+
+        let output = analyze(source_code, &parameters).unwrap();
+        assert!(output
+            .highlight_tokens
+            .windows(2)
+            .all(|pair| pair[0].location <= pair[1].location));
+        assert!(output
+            .cpd_tokens
+            .windows(2)
+            .all(|pair| pair[0].location <= pair[1].location));
+        let issues: Vec<(usize, &str)> = output
+            .issues
+            .iter()
+            .map(|issue| (issue.location.start_line, issue.rule_key.as_str()))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![(2, "S124"), (2, "S1135"), (8, "S124"), (8, "S1135")]
+        );
+    }
+
     fn test_parameters() -> HashMap<String, String> {
-        HashMap::from([("S3776:threshold".to_string(), "15".to_string())])
+        HashMap::from([
+            ("S104:maximum".to_string(), "1000".to_string()),
+            ("S107:max".to_string(), "7".to_string()),
+            ("S138:max".to_string(), "100".to_string()),
+            ("S3776:threshold".to_string(), "15".to_string()),
+            ("S7465:sizeThreshold".to_string(), "256".to_string()),
+            ("S7467:allowInMain".to_string(), "false".to_string()),
+            (
+                "S7468:allowJustifiedUnreachable".to_string(),
+                "false".to_string(),
+            ),
+            ("S1135:additionalMarkers".to_string(), "".to_string()),
+            ("S1134:additionalMarkers".to_string(), "".to_string()),
+            ("S134:max".to_string(), "3".to_string()),
+            ("S109:authorizedNumbers".to_string(), "-1,0,1,2".to_string()),
+            ("S1192:threshold".to_string(), "3".to_string()),
+            (
+                "S2068:credentialWords".to_string(),
+                "password,passwd,passphrase,secret,token,api_key,apikey,access_key,private_key"
+                    .to_string(),
+            ),
+        ])
     }
 }
@@ -0,0 +1,404 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::tree::AnalyzerError;
+use serde_json::Value;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+};
+
+/// Kind of a Cargo target, as reported by `cargo metadata`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum TargetKind {
+    /// Library, including the 'cdylib', 'staticlib' and 'proc-macro' crate types.
+    Lib,
+    Bin,
+    Example,
+    Test,
+    Bench,
+    /// The 'build.rs' script of a package.
+    BuildScript,
+}
+
+impl TargetKind {
+    /// Kind of the target from the kinds of `cargo metadata`, e.g. 'custom-build'.
+    fn from_cargo_kind(kind: &str) -> Option<Self> {
+        match kind {
+            "lib" | "rlib" | "dylib" | "cdylib" | "staticlib" | "proc-macro" => {
+                Some(TargetKind::Lib)
+            }
+            "bin" => Some(TargetKind::Bin),
+            "example" => Some(TargetKind::Example),
+            "test" => Some(TargetKind::Test),
+            "bench" => Some(TargetKind::Bench),
+            "custom-build" => Some(TargetKind::BuildScript),
+            _ => None,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            TargetKind::Lib => "lib",
+            TargetKind::Bin => "bin",
+            TargetKind::Example => "example",
+            TargetKind::Test => "test",
+            TargetKind::Bench => "bench",
+            TargetKind::BuildScript => "build_script",
+        }
+    }
+
+    /// Whether the code of the target is test code, see `TestCode`.
+    pub fn is_test(&self) -> bool {
+        matches!(self, TargetKind::Test | TargetKind::Bench)
+    }
+
+    /// Name of the parameter of the path globs of the targets of this kind.
+    pub fn paths_parameter(&self) -> String {
+        format!("{}_paths", self.name())
+    }
+}
+
+impl Display for TargetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for TargetKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            TargetKind::Lib,
+            TargetKind::Bin,
+            TargetKind::Example,
+            TargetKind::Test,
+            TargetKind::Bench,
+            TargetKind::BuildScript,
+        ]
+        .into_iter()
+        .find(|kind| kind.name() == s)
+        .ok_or_else(|| format!("unknown target kind '{}'", s))
+    }
+}
+
+/// Kinds of the targets whose files are identified by the `file_parameters`, the files of the other targets being
+/// production code. A file shared by several targets gets the first kind of this list.
+pub const IDENTIFIED_TARGET_KINDS: [TargetKind; 4] = [
+    TargetKind::BuildScript,
+    TargetKind::Test,
+    TargetKind::Bench,
+    TargetKind::Example,
+];
+
+/// A target of a package of the workspace.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Target {
+    pub package: String,
+    pub name: String,
+    pub kind: TargetKind,
+    /// Absolute path of the root file of the target.
+    pub src_path: PathBuf,
+}
+
+/// A package of the workspace.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Package {
+    pub name: String,
+    /// Features declared by the package, with the features or dependencies each one enables.
+    pub features: HashMap<String, Vec<String>>,
+}
+
+impl Package {
+    /// Features enabled by default, i.e. the 'default' feature and the features it enables, transitively.
+    pub fn default_features(&self) -> BTreeSet<String> {
+        let mut enabled = BTreeSet::new();
+        let mut pending = vec!["default".to_string()];
+        while let Some(feature) = pending.pop() {
+            if let Some(enables) = self.features.get(&feature) {
+                if feature != "default" && !enabled.insert(feature.clone()) {
+                    continue;
+                }
+                // Dependencies ('dep:foo') and features of dependencies ('foo/bar') are not features of the package
+                pending.extend(
+                    enables
+                        .iter()
+                        .filter(|enabled| self.features.contains_key(*enabled))
+                        .cloned(),
+                );
+            }
+        }
+        enabled
+    }
+}
+
+/// The members of a Cargo workspace and their targets, from the output of `cargo metadata --format-version 1`.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct CargoMetadata {
+    pub workspace_root: PathBuf,
+    /// Packages which are members of the workspace, dependencies excluded.
+    pub members: Vec<Package>,
+    pub targets: Vec<Target>,
+}
+
+impl CargoMetadata {
+    pub fn parse(json: &str) -> Result<Self, AnalyzerError> {
+        let json: Value = serde_json::from_str(json).map_err(|err| invalid(&err.to_string()))?;
+        let members: Vec<&str> = json["workspace_members"]
+            .as_array()
+            .ok_or_else(|| invalid("missing 'workspace_members' array"))?
+            .iter()
+            .filter_map(Value::as_str)
+            .collect();
+        let packages = json["packages"]
+            .as_array()
+            .ok_or_else(|| invalid("missing 'packages' array"))?;
+
+        let mut metadata = CargoMetadata {
+            workspace_root: PathBuf::from(json["workspace_root"].as_str().unwrap_or_default()),
+            ..CargoMetadata::default()
+        };
+        for package in packages {
+            if !package["id"]
+                .as_str()
+                .is_some_and(|id| members.contains(&id))
+            {
+                continue;
+            }
+            let name = package["name"]
+                .as_str()
+                .ok_or_else(|| invalid("missing package name"))?
+                .to_string();
+            let features = package["features"]
+                .as_object()
+                .map(|features| {
+                    features
+                        .iter()
+                        .map(|(feature, enables)| {
+                            let enables = enables
+                                .as_array()
+                                .map(|enables| {
+                                    enables
+                                        .iter()
+                                        .filter_map(Value::as_str)
+                                        .map(str::to_string)
+                                        .collect()
+                                })
+                                .unwrap_or_default();
+                            (feature.clone(), enables)
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            for target in package["targets"].as_array().into_iter().flatten() {
+                let kind = target["kind"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(Value::as_str)
+                    .find_map(TargetKind::from_cargo_kind);
+                let (Some(kind), Some(target_name), Some(src_path)) =
+                    (kind, target["name"].as_str(), target["src_path"].as_str())
+                else {
+                    continue;
+                };
+                metadata.targets.push(Target {
+                    package: name.clone(),
+                    name: target_name.to_string(),
+                    kind,
+                    src_path: PathBuf::from(src_path),
+                });
+            }
+            metadata.members.push(Package { name, features });
+        }
+        Ok(metadata)
+    }
+
+    /// Reads the metadata of the 'cargo_metadata' setting: `run` runs `cargo metadata` in the project directory, any
+    /// other value is the path of its output, relative to the project directory.
+    pub fn load(setting: &str, project_dir: &Path) -> Result<Self, AnalyzerError> {
+        if setting != "run" {
+            let path = project_dir.join(setting);
+            let json = fs::read_to_string(&path).map_err(|err| {
+                AnalyzerError::GlobalError(format!("failed to read {}: {}", path.display(), err))
+            })?;
+            return Self::parse(&json);
+        }
+
+        let output = Command::new("cargo")
+            .args(["metadata", "--format-version", "1", "--no-deps"])
+            .current_dir(project_dir)
+            .output()
+            .map_err(|err| {
+                AnalyzerError::GlobalError(format!("failed to run cargo metadata: {}", err))
+            })?;
+        if !output.status.success() {
+            return Err(AnalyzerError::GlobalError(format!(
+                "cargo metadata failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Self::parse(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    /// Parameters of the path globs of the targets of each identified kind, e.g. 'test_paths', relative to the project
+    /// directory. The globs cover the root file of each target and the modules in its directory.
+    pub fn to_parameters(&self, project_dir: &Path) -> HashMap<String, String> {
+        let mut parameters = HashMap::new();
+        for kind in IDENTIFIED_TARGET_KINDS {
+            let globs: BTreeSet<String> = self
+                .targets
+                .iter()
+                .filter(|target| target.kind == kind)
+                .filter_map(|target| target_globs(&target.src_path, project_dir))
+                .flatten()
+                .collect();
+            if !globs.is_empty() {
+                parameters.insert(
+                    kind.paths_parameter(),
+                    globs.into_iter().collect::<Vec<_>>().join(","),
+                );
+            }
+        }
+        parameters
+    }
+}
+
+/// Globs of the files of a target: its root file, and the files of the directory of modules of a root file named
+/// 'main.rs' (e.g. 'tests/it/main.rs'), or named after the target (e.g. 'tests/it.rs' with 'tests/it/').
+fn target_globs(src_path: &Path, project_dir: &Path) -> Option<Vec<String>> {
+    let relative = src_path.strip_prefix(project_dir).ok()?;
+    let path = relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/");
+    let file_name = relative.file_name()?.to_string_lossy();
+    let directory = path.rsplit_once('/').map(|(directory, _)| directory);
+    let modules = match (file_name.as_ref(), directory) {
+        // The directory of the package contains the other targets
+        ("build.rs", _) => None,
+        ("main.rs" | "lib.rs" | "mod.rs", Some(directory)) => Some(format!("{}/**", directory)),
+        _ => path
+            .strip_suffix(".rs")
+            .map(|module_dir| format!("{}/**", module_dir)),
+    };
+    Some(std::iter::once(path.clone()).chain(modules).collect())
+}
+
+fn invalid(message: &str) -> AnalyzerError {
+    AnalyzerError::GlobalError(format!("invalid cargo metadata: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const METADATA: &str = r#"{
+        "packages": [
+            {
+                "id": "path+file:///work/foo#0.1.0",
+                "name": "foo",
+                "features": {"default": ["std"], "std": ["alloc"], "alloc": [], "serde": ["dep:serde"]},
+                "targets": [
+                    {"kind": ["lib"], "name": "foo", "src_path": "/work/foo/src/lib.rs"},
+                    {"kind": ["bin"], "name": "cli", "src_path": "/work/foo/src/bin/cli.rs"},
+                    {"kind": ["example"], "name": "demo", "src_path": "/work/foo/examples/demo/main.rs"},
+                    {"kind": ["test"], "name": "it", "src_path": "/work/foo/tests/it.rs"},
+                    {"kind": ["bench"], "name": "perf", "src_path": "/work/foo/benches/perf.rs"},
+                    {"kind": ["custom-build"], "name": "build-script-build", "src_path": "/work/foo/build.rs"}
+                ]
+            },
+            {
+                "id": "registry+https://github.com/rust-lang/crates.io-index#serde@1.0.0",
+                "name": "serde",
+                "features": {},
+                "targets": [{"kind": ["lib"], "name": "serde", "src_path": "/home/.cargo/serde/src/lib.rs"}]
+            }
+        ],
+        "workspace_members": ["path+file:///work/foo#0.1.0"],
+        "workspace_root": "/work"
+    }"#;
+
+    #[test]
+    fn test_parse() {
+        let metadata = CargoMetadata::parse(METADATA).unwrap();
+        assert_eq!(metadata.workspace_root, PathBuf::from("/work"));
+        assert_eq!(metadata.members.len(), 1);
+        assert_eq!(
+            metadata
+                .targets
+                .iter()
+                .map(|target| (target.name.as_str(), target.kind))
+                .collect::<Vec<_>>(),
+            vec![
+                ("foo", TargetKind::Lib),
+                ("cli", TargetKind::Bin),
+                ("demo", TargetKind::Example),
+                ("it", TargetKind::Test),
+                ("perf", TargetKind::Bench),
+                ("build-script-build", TargetKind::BuildScript),
+            ]
+        );
+        assert_eq!(
+            metadata.members[0].default_features(),
+            BTreeSet::from(["std".to_string(), "alloc".to_string()])
+        );
+
+        assert!(matches!(
+            CargoMetadata::parse("{}"),
+            Err(AnalyzerError::GlobalError(message)) if message == "invalid cargo metadata: missing 'workspace_members' array"
+        ));
+    }
+
+    #[test]
+    fn test_to_parameters() {
+        let metadata = CargoMetadata::parse(METADATA).unwrap();
+        assert_eq!(
+            metadata.to_parameters(Path::new("/work")),
+            HashMap::from([
+                ("build_script_paths".to_string(), "foo/build.rs".to_string()),
+                (
+                    "test_paths".to_string(),
+                    "foo/tests/it.rs,foo/tests/it/**".to_string()
+                ),
+                (
+                    "bench_paths".to_string(),
+                    "foo/benches/perf.rs,foo/benches/perf/**".to_string()
+                ),
+                (
+                    "example_paths".to_string(),
+                    "foo/examples/demo/**,foo/examples/demo/main.rs".to_string()
+                ),
+            ])
+        );
+        // Targets outside of the project directory are ignored
+        assert!(metadata.to_parameters(Path::new("/other")).is_empty());
+    }
+
+    #[test]
+    fn test_target_kind_names() {
+        assert_eq!("build_script".parse(), Ok(TargetKind::BuildScript));
+        assert_eq!(TargetKind::Example.to_string(), "example");
+        assert!("library".parse::<TargetKind>().is_err());
+    }
+}
@@ -0,0 +1,225 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    advisories::{advisory_issues, parse_advisories, LOCK_FILE_NAME},
+    analyze::analyze_batch,
+    config::project_parameters,
+    corpus::{diff_snapshots, parse_manifest, run_corpus, Snapshot},
+    dump::{dump_tree, DumpFormat},
+    explain::explain,
+    manifest::analyze_manifest,
+    quick_fix::apply_quick_fixes,
+    rule_description::{export_rule_descriptions, DescriptionFormat},
+    rules::rule::default_parameters,
+    tree::AnalyzerError,
+};
+use std::{
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+const USAGE: &str = "usage:
+  analyzer advisories <cargo-audit.json | cargo-deny.json> <Cargo.toml>
+  analyzer corpus run <manifest> <corpus-dir> <snapshot.json>
+  analyzer corpus diff <old-snapshot.json> <new-snapshot.json>
+  analyzer explain <rule-key> <file:line>
+  analyzer dump [--json] <file | ->
+  analyzer fix [--dry-run] <file>
+  analyzer manifest <Cargo.toml>
+  analyzer rules export [--markdown] <directory>";
+
+/// Runs the command-line interface and returns the exit code of the process.
+///
+/// Without arguments, the analyzer speaks the binary protocol used by the Sonar plugin instead (see `main`).
+pub fn run(args: &[String]) -> i32 {
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let result = match args.as_slice() {
+        ["advisories", report, manifest] => check_advisories(report, manifest),
+        ["corpus", "run", manifest, corpus_dir, output] => corpus_run(manifest, corpus_dir, output),
+        ["corpus", "diff", old, new] => corpus_diff(old, new),
+        ["explain", rule_key, location] => match parse_file_line(location) {
+            Some((file, line)) => explain_issue(rule_key, file, line),
+            None => {
+                eprintln!("{}", USAGE);
+                return 2;
+            }
+        },
+        ["dump", file] => dump(file, DumpFormat::SExpression),
+        ["dump", "--json", file] => dump(file, DumpFormat::Json),
+        ["fix", file] => fix(file, false),
+        ["fix", "--dry-run", file] => fix(file, true),
+        ["manifest", file] => check_manifest(file),
+        ["rules", "export", directory] => export_rules(directory, DescriptionFormat::Html),
+        ["rules", "export", "--markdown", directory] => {
+            export_rules(directory, DescriptionFormat::Markdown)
+        }
+        _ => {
+            eprintln!("{}", USAGE);
+            return 2;
+        }
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("error {}", err);
+            1
+        }
+    }
+}
+
+fn corpus_run(manifest: &str, corpus_dir: &str, output: &str) -> Result<(), AnalyzerError> {
+    let crates = parse_manifest(&read_file(manifest)?)?;
+    let snapshot = run_corpus(&crates, Path::new(corpus_dir), &default_parameters())?;
+    let json = serde_json::to_string_pretty(&snapshot.to_json())
+        .map_err(|err| AnalyzerError::GlobalError(format!("failed to write snapshot: {}", err)))?;
+    fs::write(output, json + "\n")
+        .map_err(|err| AnalyzerError::GlobalError(format!("failed to write {}: {}", output, err)))
+}
+
+fn corpus_diff(old: &str, new: &str) -> Result<(), AnalyzerError> {
+    let diff = diff_snapshots(&read_snapshot(old)?, &read_snapshot(new)?);
+    print!("{}", diff.report());
+    Ok(())
+}
+
+fn explain_issue(rule_key: &str, file: &str, line: usize) -> Result<(), AnalyzerError> {
+    // Same settings as the analysis of the project in the current directory
+    let mut parameters = default_parameters();
+    parameters.insert("project_dir".to_string(), ".".to_string());
+    let report = explain(
+        rule_key,
+        &read_file(file)?,
+        line,
+        &project_parameters(&parameters)?,
+    )?;
+    print!("{}", report);
+    Ok(())
+}
+
+/// Prints the tree of a file, or of the snippet read from the standard input for `-`.
+fn dump(file: &str, format: DumpFormat) -> Result<(), AnalyzerError> {
+    let source_code = if file == "-" {
+        let mut source_code = String::new();
+        io::stdin()
+            .read_to_string(&mut source_code)
+            .map_err(|err| AnalyzerError::GlobalError(format!("failed to read stdin: {}", err)))?;
+        source_code
+    } else {
+        read_file(file)?
+    };
+    print!("{}", dump_tree(&source_code, format)?);
+    Ok(())
+}
+
+/// Applies the quick fixes of the issues of a file, including those of the Clippy reports, with the settings of the
+/// project in the current directory. The fixed file is printed instead of written with `--dry-run`.
+fn fix(file: &str, dry_run: bool) -> Result<(), AnalyzerError> {
+    let mut parameters = default_parameters();
+    parameters.insert("project_dir".to_string(), ".".to_string());
+    let source_code = read_file(file)?;
+    let files = [(file.to_string(), source_code.clone().into_bytes())];
+    let output = analyze_batch(&files, &project_parameters(&parameters)?)?
+        .pop()
+        .expect("one output per file")?;
+    let (fixed, count) = apply_quick_fixes(&source_code, &output.issues)?;
+    if dry_run {
+        print!("{}", fixed);
+    } else {
+        if count > 0 {
+            fs::write(file, fixed).map_err(|err| {
+                AnalyzerError::GlobalError(format!("failed to write {}: {}", file, err))
+            })?;
+        }
+        eprintln!("{}: {} quick fixes applied", file, count);
+    }
+    Ok(())
+}
+
+/// Prints the issues of a `Cargo.toml` manifest, with the settings of the project in the current directory.
+fn check_manifest(file: &str) -> Result<(), AnalyzerError> {
+    let mut parameters = default_parameters();
+    parameters.insert("project_dir".to_string(), ".".to_string());
+    let issues = analyze_manifest(&read_file(file)?, &project_parameters(&parameters)?)?;
+    for issue in issues {
+        println!(
+            "{}:{}:{} {} {}",
+            file,
+            issue.location.start_line,
+            issue.location.start_column,
+            issue.rule_key,
+            issue.message
+        );
+    }
+    Ok(())
+}
+
+/// Prints the issues of the advisories of a cargo-audit or cargo-deny report on the dependencies of a manifest, and
+/// of the `Cargo.lock` file next to it if any.
+fn check_advisories(report: &str, manifest: &str) -> Result<(), AnalyzerError> {
+    let mut parameters = default_parameters();
+    parameters.insert("project_dir".to_string(), ".".to_string());
+    let lock_file_path = Path::new(manifest).with_file_name(LOCK_FILE_NAME);
+    let lock_file = fs::read_to_string(&lock_file_path).ok();
+    let issues = advisory_issues(
+        &parse_advisories(&read_file(report)?)?,
+        &read_file(manifest)?,
+        lock_file.as_deref(),
+        &project_parameters(&parameters)?,
+    )?;
+    for (file, issue) in issues {
+        let path = if file == LOCK_FILE_NAME {
+            lock_file_path.display().to_string()
+        } else {
+            manifest.to_string()
+        };
+        println!(
+            "{}:{}:{} {} {}",
+            path,
+            issue.location.start_line,
+            issue.location.start_column,
+            issue.rule_key,
+            issue.message
+        );
+    }
+    Ok(())
+}
+
+/// Writes the descriptions of the rules, in HTML for the resources of the Sonar plugin or in Markdown.
+fn export_rules(directory: &str, format: DescriptionFormat) -> Result<(), AnalyzerError> {
+    let count = export_rule_descriptions(Path::new(directory), format)?;
+    eprintln!("{} rule descriptions written to {}", count, directory);
+    Ok(())
+}
+
+/// Parses a `<file>:<line>` argument.
+fn parse_file_line(location: &str) -> Option<(&str, usize)> {
+    let (file, line) = location.rsplit_once(':')?;
+    Some((file, line.parse().ok()?))
+}
+
+fn read_snapshot(path: &str) -> Result<Snapshot, AnalyzerError> {
+    let json = serde_json::from_str(&read_file(path)?)
+        .map_err(|err| AnalyzerError::GlobalError(format!("failed to parse {}: {}", path, err)))?;
+    Snapshot::from_json(&json)
+}
+
+fn read_file(path: &str) -> Result<String, AnalyzerError> {
+    fs::read_to_string(path)
+        .map_err(|err| AnalyzerError::GlobalError(format!("failed to read {}: {}", path, err)))
+}
@@ -0,0 +1,349 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, QuickFix, SecondaryLocation, Severity, TextEdit},
+    options::list_parameter,
+    tree::{AnalyzerError, SonarLocation},
+};
+use serde_json::Value;
+use std::{collections::HashMap, fs, path::Path};
+
+/// Diagnostics of the output of `cargo clippy --message-format=json`, by file path relative to the workspace root.
+///
+/// The columns of the locations count the characters of the lines from 1, as in the output of the compiler. They are
+/// converted to the columns of `SonarLocation` with the source code of the file, see `ClippyReport::issues`.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct ClippyReport {
+    diagnostics: HashMap<String, Vec<Issue>>,
+}
+
+impl ClippyReport {
+    /// Parses the messages of Cargo, one JSON object per line. The other messages of Cargo (e.g. artifacts) and the
+    /// lines which are not JSON (e.g. the output of build scripts) are ignored.
+    ///
+    /// Errors and warnings with a code (e.g. 'clippy::needless_return' or 'unused_variables') are imported, with the
+    /// labels of their other spans as secondary locations and their suggestions as quick fixes.
+    pub fn parse(text: &str) -> Self {
+        let mut report = ClippyReport::default();
+        for line in text.lines() {
+            let Ok(message) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            if message["reason"] != "compiler-message" {
+                continue;
+            }
+            if let Some((file, diagnostic)) = diagnostic(&message["message"]) {
+                let diagnostics = report.diagnostics.entry(file).or_default();
+                // The same diagnostic is emitted for each target compiling the file, e.g. the lib and its unit tests
+                if !diagnostics.contains(&diagnostic) {
+                    diagnostics.push(diagnostic);
+                }
+            }
+        }
+        report
+    }
+
+    /// Reads the reports of the 'clippy_report_paths' setting, relative to the project directory, if any. Their issues
+    /// are added to the results of `analyze_batch`, the plugin imports the reports itself.
+    pub fn load(parameters: &HashMap<String, String>) -> Result<Option<Self>, AnalyzerError> {
+        let Some(paths) = parameters.get("clippy_report_paths") else {
+            return Ok(None);
+        };
+        let project_dir = Path::new(
+            parameters
+                .get("project_dir")
+                .map_or(".", |dir| dir.as_str()),
+        );
+        let mut report = ClippyReport::default();
+        for path in list_parameter(paths) {
+            let path = project_dir.join(path);
+            let text = fs::read_to_string(&path).map_err(|err| {
+                AnalyzerError::GlobalError(format!("failed to read {}: {}", path.display(), err))
+            })?;
+            for (file, diagnostics) in Self::parse(&text).diagnostics {
+                report
+                    .diagnostics
+                    .entry(file)
+                    .or_default()
+                    .extend(diagnostics);
+            }
+        }
+        Ok(Some(report))
+    }
+
+    /// Issues of the file at the given path, relative to the project directory, with its source code.
+    pub fn issues(&self, path: &str, source_code: &str) -> Vec<Issue> {
+        let lines: Vec<&str> = source_code.lines().collect();
+        let convert = |location: &SonarLocation| SonarLocation {
+            start_line: location.start_line,
+            start_column: utf16_column(&lines, location.start_line, location.start_column),
+            end_line: location.end_line,
+            end_column: utf16_column(&lines, location.end_line, location.end_column),
+        };
+        self.diagnostics
+            .get(&normalize_path(path))
+            .into_iter()
+            .flatten()
+            .map(|diagnostic| Issue {
+                location: convert(&diagnostic.location),
+                secondary_locations: diagnostic
+                    .secondary_locations
+                    .iter()
+                    .map(|secondary| SecondaryLocation {
+                        location: convert(&secondary.location),
+                        ..secondary.clone()
+                    })
+                    .collect(),
+                quick_fixes: diagnostic
+                    .quick_fixes
+                    .iter()
+                    .map(|quick_fix| QuickFix {
+                        edits: quick_fix
+                            .edits
+                            .iter()
+                            .map(|edit| TextEdit {
+                                location: convert(&edit.location),
+                                ..edit.clone()
+                            })
+                            .collect(),
+                        ..quick_fix.clone()
+                    })
+                    .collect(),
+                ..diagnostic.clone()
+            })
+            .collect()
+    }
+}
+
+/// File and diagnostic of a message of the compiler, without the spans in other files.
+fn diagnostic(message: &Value) -> Option<(String, Issue)> {
+    let severity = match message["level"].as_str()? {
+        "error" => Severity::Critical,
+        "warning" => Severity::Major,
+        _ => return None,
+    };
+    let code = message["code"]["code"].as_str()?;
+    let spans = message["spans"].as_array()?;
+    let primary = spans
+        .iter()
+        .find(|span| span["is_primary"] == true)
+        .or(spans.first())?;
+    let file = normalize_path(primary["file_name"].as_str()?);
+
+    let mut secondary_locations: Vec<SecondaryLocation> = spans
+        .iter()
+        .filter(|span| *span != primary && span["file_name"] == primary["file_name"])
+        .filter_map(|span| {
+            Some(SecondaryLocation {
+                message: span["label"].as_str().unwrap_or_default().to_string(),
+                location: span_location(span)?,
+                file: None,
+            })
+        })
+        .collect();
+    let mut quick_fixes = vec![];
+    for child in message["children"].as_array().into_iter().flatten() {
+        let child_message = child["message"].as_str().unwrap_or_default();
+        let mut edits = vec![];
+        for span in child["spans"].as_array().into_iter().flatten() {
+            if span["file_name"] != primary["file_name"] {
+                continue;
+            }
+            let Some(location) = span_location(span) else {
+                continue;
+            };
+            match span["suggested_replacement"].as_str() {
+                Some(replacement) => edits.push(TextEdit {
+                    location,
+                    text: replacement.to_string(),
+                }),
+                None => secondary_locations.push(SecondaryLocation {
+                    message: child_message.to_string(),
+                    location,
+                    file: None,
+                }),
+            }
+        }
+        if !edits.is_empty() {
+            quick_fixes.push(QuickFix {
+                message: child_message.to_string(),
+                edits,
+            });
+        }
+    }
+
+    Some((
+        file,
+        Issue {
+            rule_key: code.to_string(),
+            message: message["message"].as_str()?.to_string(),
+            location: span_location(primary)?,
+            secondary_locations,
+            severity: Some(severity),
+            quick_fixes,
+            fingerprint: None,
+            hotspot: None,
+        },
+    ))
+}
+
+fn span_location(span: &Value) -> Option<SonarLocation> {
+    let number = |key: &str| span[key].as_u64().map(|number| number as usize);
+    Some(SonarLocation {
+        start_line: number("line_start")?,
+        start_column: number("column_start")?,
+        end_line: number("line_end")?,
+        end_column: number("column_end")?,
+    })
+}
+
+/// Number of UTF-16 code units before the column of the line, which counts characters from 1.
+fn utf16_column(lines: &[&str], line: usize, column: usize) -> usize {
+    let Some(text) = line.checked_sub(1).and_then(|index| lines.get(index)) else {
+        return column.saturating_sub(1);
+    };
+    let characters = column.saturating_sub(1);
+    let prefix: String = text.chars().take(characters).collect();
+    prefix.encode_utf16().count() + characters.saturating_sub(prefix.chars().count())
+}
+
+fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    path.strip_prefix("./").unwrap_or(&path).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analyze::analyze_batch, rules::rule::default_parameters};
+
+    const REPORT: &str = r#"{"reason":"compiler-artifact","package_id":"demo 0.1.0","target":{"name":"demo"}}
+warning: unused manifest key: package.foo
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"message":"unneeded `return` statement","code":{"code":"clippy::needless_return","explanation":null},"level":"warning","spans":[{"file_name":"src/lib.rs","byte_start":40,"byte_end":49,"line_start":3,"line_end":3,"column_start":5,"column_end":14,"is_primary":true,"text":[],"label":null,"suggested_replacement":null}],"children":[{"message":"for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#needless_return","code":null,"level":"help","spans":[],"children":[]},{"message":"remove `return`","code":null,"level":"help","spans":[{"file_name":"src/lib.rs","byte_start":40,"byte_end":49,"line_start":3,"line_end":3,"column_start":5,"column_end":14,"is_primary":true,"text":[],"label":null,"suggested_replacement":"x"}],"children":[]}],"rendered":"warning: unneeded `return` statement"}}
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"message":"unneeded `return` statement","code":{"code":"clippy::needless_return","explanation":null},"level":"warning","spans":[{"file_name":"src/lib.rs","byte_start":40,"byte_end":49,"line_start":3,"line_end":3,"column_start":5,"column_end":14,"is_primary":true,"text":[],"label":null,"suggested_replacement":null}],"children":[{"message":"for further information visit https://rust-lang.github.io/rust-clippy/master/index.html#needless_return","code":null,"level":"help","spans":[],"children":[]},{"message":"remove `return`","code":null,"level":"help","spans":[{"file_name":"src/lib.rs","byte_start":40,"byte_end":49,"line_start":3,"line_end":3,"column_start":5,"column_end":14,"is_primary":true,"text":[],"label":null,"suggested_replacement":"x"}],"children":[]}],"rendered":"warning: unneeded `return` statement"}}
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"message":"mismatched types","code":{"code":"E0308","explanation":"..."},"level":"error","spans":[{"file_name":".\\src\\main.rs","byte_start":30,"byte_end":33,"line_start":2,"line_end":2,"column_start":20,"column_end":23,"is_primary":true,"text":[],"label":"expected `i32`, found `&str`","suggested_replacement":null},{"file_name":".\\src\\main.rs","byte_start":20,"byte_end":23,"line_start":2,"line_end":2,"column_start":12,"column_end":15,"is_primary":false,"text":[],"label":"expected due to this","suggested_replacement":null},{"file_name":"src/other.rs","byte_start":0,"byte_end":3,"line_start":1,"line_end":1,"column_start":1,"column_end":4,"is_primary":false,"text":[],"label":"defined here","suggested_replacement":null}],"children":[],"rendered":"error[E0308]: mismatched types"}}
+{"reason":"compiler-message","package_id":"demo 0.1.0","message":{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[],"children":[],"rendered":"error: aborting due to 1 previous error"}}
+{"reason":"build-finished","success":false}
+"#;
+
+    #[test]
+    fn test_issues() {
+        let report = ClippyReport::parse(REPORT);
+
+        assert_eq!(
+            report.issues(
+                "./src/lib.rs",
+                "fn f(x: i32) -> i32 {\n\n    return x;\n}\n"
+            ),
+            vec![Issue {
+                rule_key: "clippy::needless_return".to_string(),
+                message: "unneeded `return` statement".to_string(),
+                location: SonarLocation {
+                    start_line: 3,
+                    start_column: 4,
+                    end_line: 3,
+                    end_column: 13,
+                },
+                secondary_locations: vec![],
+                severity: Some(Severity::Major),
+                quick_fixes: vec![QuickFix {
+                    message: "remove `return`".to_string(),
+                    edits: vec![TextEdit {
+                        location: SonarLocation {
+                            start_line: 3,
+                            start_column: 4,
+                            end_line: 3,
+                            end_column: 13,
+                        },
+                        text: "x".to_string(),
+                    }],
+                }],
+                fingerprint: None,
+                hotspot: None,
+            }]
+        );
+        assert_eq!(
+            report.issues("src/main.rs", "fn main() {\n    let 𝑥: i32 = \"abc\";\n}\n"),
+            vec![Issue {
+                rule_key: "E0308".to_string(),
+                message: "mismatched types".to_string(),
+                location: SonarLocation {
+                    start_line: 2,
+                    start_column: 20,
+                    end_line: 2,
+                    end_column: 23,
+                },
+                secondary_locations: vec![SecondaryLocation {
+                    message: "expected due to this".to_string(),
+                    location: SonarLocation {
+                        start_line: 2,
+                        start_column: 12,
+                        end_line: 2,
+                        end_column: 15,
+                    },
+                    file: None,
+                }],
+                severity: Some(Severity::Critical),
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            }]
+        );
+        assert_eq!(report.issues("src/other.rs", ""), vec![]);
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("clippy-report-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("clippy.json"), REPORT).unwrap();
+        let mut parameters =
+            HashMap::from([("project_dir".to_string(), dir.to_string_lossy().to_string())]);
+
+        assert_eq!(ClippyReport::load(&parameters).unwrap(), None);
+
+        parameters.insert(
+            "clippy_report_paths".to_string(),
+            "clippy.json, missing.json".to_string(),
+        );
+        assert!(matches!(
+            ClippyReport::load(&parameters),
+            Err(AnalyzerError::GlobalError(message)) if message.contains("missing.json")
+        ));
+
+        parameters.insert("clippy_report_paths".to_string(), "clippy.json".to_string());
+        let report = ClippyReport::load(&parameters).unwrap().unwrap();
+        assert_eq!(report.issues("src/lib.rs", "").len(), 1);
+
+        let files = vec![(
+            "src/lib.rs".to_string(),
+            b"fn f(x: i32) -> i32 {\n\n    return x;\n}\n".to_vec(),
+        )];
+        parameters.extend(default_parameters());
+        let outputs = analyze_batch(&files, &parameters).unwrap();
+        let rule_keys: Vec<&str> = outputs[0]
+            .as_ref()
+            .unwrap()
+            .issues
+            .iter()
+            .map(|issue| issue.rule_key.as_str())
+            .collect();
+        assert_eq!(rule_keys, vec!["clippy::needless_return"]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,367 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    cargo_metadata::CargoMetadata,
+    profile::QualityProfile,
+    toml::{read_value, split_key, split_key_value, strip_comment},
+    tree::AnalyzerError,
+};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Name of the configuration file, at the root of the project.
+pub const CONFIG_FILE_NAME: &str = "sonar-rust.toml";
+
+/// Analysis settings versioned with the project, in a `sonar-rust.toml` file such as:
+///
+/// ```toml
+/// max_file_size = 1000000
+/// exclusions = ["src/generated/**", "**/bindings.rs"]
+///
+/// [rules]
+/// disabled = ["S7465"]
+///
+/// [rules.S3776]
+/// threshold = 25
+/// ```
+///
+/// The top-level keys are analysis settings (see `AnalysisOptions`, and 'quality_profile' in `project_parameters`),
+/// the disabled rules are the 'disabled_rules' setting, and the keys of the `[rules.<key>]` tables are the parameters
+/// of these rules. Arrays are converted to comma-separated lists. Only the subset of TOML of the `toml` module is
+/// supported.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct ProjectConfig {
+    pub parameters: HashMap<String, String>,
+}
+
+impl ProjectConfig {
+    pub fn parse(text: &str) -> Result<Self, AnalyzerError> {
+        let mut parameters = HashMap::new();
+        let mut table: Vec<String> = vec![];
+        let mut lines = text.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let error = |message: &str| {
+                AnalyzerError::GlobalError(format!(
+                    "invalid {} at line {}: {}",
+                    CONFIG_FILE_NAME,
+                    index + 1,
+                    message
+                ))
+            };
+            let content = line.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+
+            if content.starts_with('[') {
+                let header = strip_comment(content)
+                    .trim()
+                    .strip_prefix('[')
+                    .and_then(|header| header.strip_suffix(']'))
+                    .ok_or_else(|| error("unclosed table header"))?;
+                table = split_key(header).map_err(|message| error(&message))?;
+                if !matches!(table.as_slice(), [rules] if rules == "rules")
+                    && !matches!(table.as_slice(), [rules, _] if rules == "rules")
+                {
+                    return Err(error(&format!("unknown table '{}'", header.trim())));
+                }
+                continue;
+            }
+
+            let (key, value) =
+                split_key_value(content).ok_or_else(|| error("expected 'key = value'"))?;
+            let key = match split_key(key)
+                .map_err(|message| error(&message))?
+                .as_slice()
+            {
+                [key] => key.clone(),
+                _ => return Err(error(&format!("unsupported dotted key '{}'", key.trim()))),
+            };
+            // Arrays may span several lines
+            let value = read_value(value, &mut lines)
+                .and_then(|value| value.to_parameter())
+                .map_err(|message| error(&message))?;
+
+            let parameter = match table.as_slice() {
+                [] => key,
+                [_] if key == "disabled" => "disabled_rules".to_string(),
+                [_] => return Err(error(&format!("unknown key '{}' in [rules]", key))),
+                [_, rule_key] => format!("{}:{}", rule_key, key),
+                _ => unreachable!("tables are validated with their header"),
+            };
+            parameters.insert(parameter, value);
+        }
+        Ok(ProjectConfig { parameters })
+    }
+
+    /// Reads the configuration file of the project, if any.
+    pub fn load(project_dir: &Path) -> Result<Option<Self>, AnalyzerError> {
+        let path = project_dir.join(CONFIG_FILE_NAME);
+        if !path.is_file() {
+            return Ok(None);
+        }
+        let text = fs::read_to_string(&path).map_err(|err| {
+            AnalyzerError::GlobalError(format!("failed to read {}: {}", path.display(), err))
+        })?;
+        Self::parse(&text).map(Some)
+    }
+
+    /// Adds the settings of the configuration to the parameters, which they override: unlike the settings of the
+    /// server, which are the same for all projects by default, they are specific to the project.
+    pub fn merge(&self, parameters: &HashMap<String, String>) -> HashMap<String, String> {
+        let mut merged = parameters.clone();
+        merged.extend(self.parameters.clone());
+        merged
+    }
+}
+
+/// Merges the configuration file of the project found in the 'project_dir' setting, if any, into the parameters.
+///
+/// The quality profile of the 'quality_profile' setting, relative to the project directory, is then applied, see
+/// `QualityProfile::to_parameters`. The settings of the configuration file still override the ones of the profile.
+/// Last, the paths of the targets of the 'cargo_metadata' setting are added, see `CargoMetadata::to_parameters`.
+pub fn project_parameters(
+    parameters: &HashMap<String, String>,
+) -> Result<HashMap<String, String>, AnalyzerError> {
+    let project_dir = parameters.get("project_dir").map(Path::new);
+    let config = match project_dir {
+        Some(project_dir) => ProjectConfig::load(project_dir)?.unwrap_or_default(),
+        None => ProjectConfig::default(),
+    };
+    let mut merged = config.merge(parameters);
+    if let Some(profile) = merged.get("quality_profile") {
+        let profile_path = match project_dir {
+            Some(project_dir) => project_dir.join(profile),
+            None => PathBuf::from(profile),
+        };
+        let mut profile_parameters = parameters.clone();
+        profile_parameters.extend(QualityProfile::load(&profile_path)?.to_parameters()?);
+        merged = config.merge(&profile_parameters);
+    }
+
+    if let Some(setting) = merged.get("cargo_metadata") {
+        // The paths of the targets are absolute
+        let project_dir = project_dir.unwrap_or(Path::new("."));
+        let project_dir = fs::canonicalize(project_dir).unwrap_or(project_dir.to_path_buf());
+        let target_parameters =
+            CargoMetadata::load(setting, &project_dir)?.to_parameters(&project_dir);
+        for (key, value) in target_parameters {
+            merged.entry(key).or_insert(value);
+        }
+    }
+    Ok(merged)
+}
+
+/// Whether the path, with '/' separators, matches the glob: `**` matches any number of directories, `*` and `?`
+/// match any characters but '/'.
+pub(crate) fn glob_matches(glob: &str, path: &str) -> bool {
+    let glob: Vec<&str> = glob.split('/').collect();
+    let path: Vec<&str> = path.split('/').collect();
+    segments_match(&glob, &path)
+}
+
+fn segments_match(glob: &[&str], path: &[&str]) -> bool {
+    match glob.split_first() {
+        None => path.is_empty(),
+        Some((&"**", rest)) => {
+            (0..=path.len()).any(|skipped| segments_match(rest, &path[skipped..]))
+        }
+        Some((segment, rest)) => path.split_first().is_some_and(|(name, path)| {
+            segment_matches(segment.as_bytes(), name.as_bytes()) && segments_match(rest, path)
+        }),
+    }
+}
+
+fn segment_matches(glob: &[u8], name: &[u8]) -> bool {
+    match glob.split_first() {
+        None => name.is_empty(),
+        Some((b'*', rest)) => {
+            (0..=name.len()).any(|skipped| segment_matches(rest, &name[skipped..]))
+        }
+        Some((b'?', rest)) => !name.is_empty() && segment_matches(rest, &name[1..]),
+        Some((c, rest)) => name.first() == Some(c) && segment_matches(rest, &name[1..]),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::list_parameter;
+
+    #[test]
+    fn test_parse() {
+        let config = ProjectConfig::parse(
+            r#"
+# Analysis settings
+max_file_size = 1_000_000
+skip_cpd = true
+exclusions = [
+    "src/generated/**", # Protobuf
+    'vendor/*.rs',
+]
+
+[rules]
+disabled = ["S7465"]
+
+[rules.S3776]
+threshold = 25
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.parameters,
+            HashMap::from([
+                ("max_file_size".to_string(), "1000000".to_string()),
+                ("skip_cpd".to_string(), "true".to_string()),
+                (
+                    "exclusions".to_string(),
+                    "src/generated/**,vendor/*.rs".to_string()
+                ),
+                ("disabled_rules".to_string(), "S7465".to_string()),
+                ("S3776:threshold".to_string(), "25".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_strings() {
+        let config = ProjectConfig::parse(
+            r##"generated_markers = ["# DO NOT EDIT", 'C:\gen', "\"generated\""]"##,
+        )
+        .unwrap();
+        assert_eq!(
+            config.parameters["generated_markers"],
+            "# DO NOT EDIT,C:\\gen,\"generated\""
+        );
+    }
+
+    #[test]
+    fn test_invalid() {
+        for (text, message) in [
+            (
+                "skip_cpd",
+                "invalid sonar-rust.toml at line 1: expected 'key = value'",
+            ),
+            (
+                "\n[lints]",
+                "invalid sonar-rust.toml at line 2: unknown table 'lints'",
+            ),
+            (
+                "[rules]\nthreshold = 1",
+                "invalid sonar-rust.toml at line 2: unknown key 'threshold' in [rules]",
+            ),
+            (
+                "timeout_ms = soon",
+                "invalid sonar-rust.toml at line 1: unsupported value 'soon'",
+            ),
+            (
+                "exclusions = [\"a\",",
+                "invalid sonar-rust.toml at line 1: unclosed array",
+            ),
+        ] {
+            assert!(
+                matches!(ProjectConfig::parse(text), Err(AnalyzerError::GlobalError(m)) if m == message),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn test_merge() {
+        let config = ProjectConfig::parse("[rules.S3776]\nthreshold = 25").unwrap();
+        let parameters = HashMap::from([
+            ("S3776:threshold".to_string(), "15".to_string()),
+            ("skip_cpd".to_string(), "true".to_string()),
+        ]);
+        assert_eq!(
+            config.merge(&parameters),
+            HashMap::from([
+                ("S3776:threshold".to_string(), "25".to_string()),
+                ("skip_cpd".to_string(), "true".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_project_parameters() {
+        let project_dir = std::env::temp_dir().join(format!("config-{}", std::process::id()));
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(project_dir.join(CONFIG_FILE_NAME), "skip_metrics = true\n").unwrap();
+
+        let parameters = HashMap::from([(
+            "project_dir".to_string(),
+            project_dir.to_string_lossy().to_string(),
+        )]);
+        let merged = project_parameters(&parameters);
+        fs::remove_dir_all(&project_dir).unwrap();
+        assert_eq!(merged.unwrap()["skip_metrics"], "true");
+
+        assert_eq!(project_parameters(&HashMap::new()).unwrap(), HashMap::new());
+    }
+
+    #[test]
+    fn test_quality_profile() {
+        let project_dir = std::env::temp_dir().join(format!("profile-{}", std::process::id()));
+        fs::create_dir_all(&project_dir).unwrap();
+        fs::write(
+            project_dir.join(CONFIG_FILE_NAME),
+            "quality_profile = \"profile.json\"\n[rules.S7465]\nsizeThreshold = 1024\n",
+        )
+        .unwrap();
+        fs::write(
+            project_dir.join("profile.json"),
+            r#"{"rules": [
+                {"repositoryKey": "rust", "key": "S3776", "parameters": [{"key": "threshold", "value": "25"}]},
+                {"repositoryKey": "rust", "key": "S7465", "parameters": [{"key": "sizeThreshold", "value": "512"}]}
+            ]}"#,
+        )
+        .unwrap();
+
+        let parameters = HashMap::from([
+            (
+                "project_dir".to_string(),
+                project_dir.to_string_lossy().to_string(),
+            ),
+            ("S3776:threshold".to_string(), "15".to_string()),
+        ]);
+        let merged = project_parameters(&parameters);
+        fs::remove_dir_all(&project_dir).unwrap();
+
+        let merged = merged.unwrap();
+        assert_eq!(merged["S3776:threshold"], "25");
+        assert_eq!(merged["S7465:sizeThreshold"], "1024");
+        let disabled_rules = list_parameter(&merged["disabled_rules"]);
+        assert!(disabled_rules.contains(&"S2260".to_string()));
+        assert!(!disabled_rules.contains(&"S3776".to_string()));
+    }
+
+    #[test]
+    fn test_glob_matches() {
+        assert!(glob_matches("src/generated/**", "src/generated/a/b.rs"));
+        assert!(glob_matches("**/bindings.rs", "bindings.rs"));
+        assert!(glob_matches("**/bindings.rs", "crates/sys/src/bindings.rs"));
+        assert!(glob_matches("src/*_gen.rs", "src/proto_gen.rs"));
+        assert!(glob_matches("src/?.rs", "src/a.rs"));
+        assert!(!glob_matches("src/*.rs", "src/a/b.rs"));
+        assert!(!glob_matches("src/?.rs", "src/ab.rs"));
+        assert!(!glob_matches("tests/**", "src/tests.rs"));
+    }
+}
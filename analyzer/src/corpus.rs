@@ -0,0 +1,739 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{analyze::analyze_batch, config::project_parameters, tree::AnalyzerError};
+use serde_json::{json, Value};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Version of the layout of the snapshots, see `Snapshot::to_json`. Increment it only when the layout changes, so
+/// that the snapshots of the previous releases can still be diffed.
+pub const SNAPSHOT_SCHEMA_VERSION: i64 = 8;
+
+/// Oldest version of the layout which can be read. The snapshots were versioned with the protocol of the plugin from
+/// its version 2, whose increments up to 8 did not change their layout.
+const OLDEST_SNAPSHOT_SCHEMA_VERSION: i64 = 2;
+
+/// A crate of the corpus, pinned to a specific revision (tag or commit) of its repository.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CorpusCrate {
+    pub name: String,
+    pub repository: String,
+    pub revision: String,
+}
+
+/// Analysis results over the whole corpus, produced by a given analyzer version.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Snapshot {
+    pub analyzer_version: String,
+    pub crates: Vec<CrateSnapshot>,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CrateSnapshot {
+    pub name: String,
+    pub revision: String,
+    pub files: usize,
+    /// Files that could not be analyzed, with the reason.
+    pub failures: BTreeMap<String, String>,
+    /// Metrics summed over all the files of the crate.
+    pub metrics: BTreeMap<String, i64>,
+    pub issues: Vec<SnapshotIssue>,
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct SnapshotIssue {
+    pub file: String,
+    pub rule_key: String,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+/// Differences between two snapshots of the same corpus.
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct SnapshotDiff {
+    pub old_version: String,
+    pub new_version: String,
+    /// Number of added and removed issues per rule, over all crates.
+    pub rules: BTreeMap<String, (usize, usize)>,
+    pub crates: Vec<CrateDiff>,
+}
+
+#[derive(Debug, PartialEq, Eq, Default)]
+pub struct CrateDiff {
+    pub name: String,
+    /// Old and new revisions, if the crate was analyzed at different revisions.
+    pub revision: Option<(String, String)>,
+    pub added_issues: Vec<SnapshotIssue>,
+    pub removed_issues: Vec<SnapshotIssue>,
+    /// Old and new values of the metrics that changed.
+    pub metrics: BTreeMap<String, (i64, i64)>,
+    pub added_failures: Vec<String>,
+    pub removed_failures: Vec<String>,
+}
+
+impl CrateDiff {
+    fn is_empty(&self) -> bool {
+        self.revision.is_none()
+            && self.added_issues.is_empty()
+            && self.removed_issues.is_empty()
+            && self.metrics.is_empty()
+            && self.added_failures.is_empty()
+            && self.removed_failures.is_empty()
+    }
+}
+
+/// Parses the corpus manifest.
+///
+/// Each non-empty line that is not a comment (starting with '#') has the form `<name> <repository> <revision>`.
+pub fn parse_manifest(content: &str) -> Result<Vec<CorpusCrate>, AnalyzerError> {
+    let mut crates = Vec::new();
+    for (index, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        match line.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [name, repository, revision] => crates.push(CorpusCrate {
+                name: name.to_string(),
+                repository: repository.to_string(),
+                revision: revision.to_string(),
+            }),
+            _ => {
+                return Err(AnalyzerError::GlobalError(format!(
+                    "invalid corpus manifest entry at line {}: '{}'",
+                    index + 1,
+                    line
+                )))
+            }
+        }
+    }
+    Ok(crates)
+}
+
+/// Analyzes every crate of the corpus. Each crate is expected to be checked out in `corpus_dir/<name>`.
+pub fn run_corpus(
+    crates: &[CorpusCrate],
+    corpus_dir: &Path,
+    parameters: &HashMap<String, String>,
+) -> Result<Snapshot, AnalyzerError> {
+    let mut snapshots = Vec::new();
+    for corpus_crate in crates {
+        let crate_dir = corpus_dir.join(&corpus_crate.name);
+        if !crate_dir.is_dir() {
+            return Err(AnalyzerError::GlobalError(format!(
+                "crate '{}' not found in {}",
+                corpus_crate.name,
+                corpus_dir.display()
+            )));
+        }
+        snapshots.push(analyze_crate(corpus_crate, &crate_dir, parameters)?);
+    }
+
+    Ok(Snapshot {
+        analyzer_version: env!("CARGO_PKG_VERSION").to_string(),
+        crates: snapshots,
+    })
+}
+
+fn analyze_crate(
+    corpus_crate: &CorpusCrate,
+    crate_dir: &Path,
+    parameters: &HashMap<String, String>,
+) -> Result<CrateSnapshot, AnalyzerError> {
+    let mut snapshot = CrateSnapshot {
+        name: corpus_crate.name.clone(),
+        revision: corpus_crate.revision.clone(),
+        files: 0,
+        failures: BTreeMap::new(),
+        metrics: BTreeMap::new(),
+        issues: Vec::new(),
+    };
+
+    let mut parameters = parameters.clone();
+    parameters.insert(
+        "project_dir".to_string(),
+        crate_dir.to_string_lossy().to_string(),
+    );
+    let parameters = project_parameters(&parameters)?;
+    let mut files = Vec::new();
+    for path in rust_files(crate_dir)? {
+        let file = relative_path(crate_dir, &path);
+        snapshot.files += 1;
+
+        match fs::read(&path) {
+            Ok(bytes) => files.push((file, bytes)),
+            Err(err) => {
+                snapshot.failures.insert(file, err.to_string());
+            }
+        }
+    }
+
+    // The files are analyzed together for the project-level rules
+    for ((file, _), output) in files.iter().zip(analyze_batch(&files, &parameters)?) {
+        let file = file.clone();
+        let output = match output {
+            Ok(output) => output,
+            Err(err) => {
+                snapshot.failures.insert(file, err.to_string());
+                continue;
+            }
+        };
+
+        let metrics = &output.metrics;
+        for (name, value) in [
+            ("ncloc", metrics.ncloc),
+            ("comment_lines", metrics.comment_lines),
+            ("functions", metrics.functions),
+            ("statements", metrics.statements),
+            ("classes", metrics.classes),
+            ("structs", metrics.structs),
+            ("enums", metrics.enums),
+            ("traits", metrics.traits),
+            ("impls", metrics.impls),
+            ("modules", metrics.modules),
+            ("public_api", metrics.public_api),
+            ("public_documented_api", metrics.public_documented_api),
+            ("exported_items", metrics.exported_items),
+            ("unsafe_blocks", metrics.unsafe_blocks),
+            ("unsafe_functions", metrics.unsafe_functions),
+            ("unsafe_impls", metrics.unsafe_impls),
+            ("unsafe_lines", metrics.unsafe_lines),
+            ("cognitive_complexity", metrics.cognitive_complexity),
+            ("cyclomatic_complexity", metrics.cyclomatic_complexity),
+            ("executable_lines", metrics.executable_lines.len() as i32),
+            ("test_ncloc", output.test_metrics.ncloc),
+            ("test_functions", output.test_metrics.functions),
+        ] {
+            *snapshot.metrics.entry(name.to_string()).or_default() += value as i64;
+        }
+        if let Some(duplication) = &output.duplication {
+            for (name, value) in [
+                ("duplicated_blocks", duplication.blocks.len()),
+                ("duplicated_lines", duplication.duplicated_lines.len()),
+            ] {
+                *snapshot.metrics.entry(name.to_string()).or_default() += value as i64;
+            }
+        }
+
+        snapshot
+            .issues
+            .extend(output.issues.into_iter().map(|issue| SnapshotIssue {
+                file: file.clone(),
+                rule_key: issue.rule_key,
+                line: issue.location.start_line,
+                column: issue.location.start_column,
+                message: issue.message,
+            }));
+    }
+
+    snapshot.issues.sort();
+    Ok(snapshot)
+}
+
+/// Returns all Rust files below the given directory, in a deterministic order.
+/// Hidden directories and Cargo 'target' directories are skipped.
+fn rust_files(dir: &Path) -> Result<Vec<PathBuf>, AnalyzerError> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        let entries = fs::read_dir(&current).map_err(|err| {
+            AnalyzerError::GlobalError(format!("failed to read {}: {}", current.display(), err))
+        })?;
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = entry.file_name().to_string_lossy().to_string();
+            if path.is_dir() {
+                if !name.starts_with('.') && name != "target" {
+                    pending.push(path);
+                }
+            } else if name.ends_with(".rs") {
+                files.push(path);
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn relative_path(base: &Path, path: &Path) -> String {
+    path.strip_prefix(base)
+        .unwrap_or(path)
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+impl Snapshot {
+    pub fn to_json(&self) -> Value {
+        json!({
+            "schema_version": SNAPSHOT_SCHEMA_VERSION,
+            "analyzer_version": self.analyzer_version,
+            "crates": self.crates.iter().map(|c| json!({
+                "name": c.name,
+                "revision": c.revision,
+                "files": c.files,
+                "failures": c.failures,
+                "metrics": c.metrics,
+                "issues": c.issues.iter().map(|issue| json!({
+                    "file": issue.file,
+                    "rule_key": issue.rule_key,
+                    "line": issue.line,
+                    "column": issue.column,
+                    "message": issue.message,
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+        })
+    }
+
+    pub fn from_json(value: &Value) -> Result<Self, AnalyzerError> {
+        let schema_version = integer_field(value, "schema_version")?;
+        if !(OLDEST_SNAPSHOT_SCHEMA_VERSION..=SNAPSHOT_SCHEMA_VERSION).contains(&schema_version) {
+            return Err(AnalyzerError::GlobalError(format!(
+                "invalid snapshot: schema version {} is not supported, expected versions {} to {}",
+                schema_version, OLDEST_SNAPSHOT_SCHEMA_VERSION, SNAPSHOT_SCHEMA_VERSION
+            )));
+        }
+        let crates = array_field(value, "crates")?
+            .iter()
+            .map(|c| {
+                Ok(CrateSnapshot {
+                    name: string_field(c, "name")?,
+                    revision: string_field(c, "revision")?,
+                    files: integer_field(c, "files")? as usize,
+                    failures: object_field(c, "failures")?
+                        .iter()
+                        .map(|(file, reason)| {
+                            Ok((file.clone(), as_string(reason, "failures")?.to_string()))
+                        })
+                        .collect::<Result<_, AnalyzerError>>()?,
+                    metrics: object_field(c, "metrics")?
+                        .iter()
+                        .map(|(name, value)| Ok((name.clone(), as_integer(value, name)?)))
+                        .collect::<Result<_, AnalyzerError>>()?,
+                    issues: array_field(c, "issues")?
+                        .iter()
+                        .map(|issue| {
+                            Ok(SnapshotIssue {
+                                file: string_field(issue, "file")?,
+                                rule_key: string_field(issue, "rule_key")?,
+                                line: integer_field(issue, "line")? as usize,
+                                column: integer_field(issue, "column")? as usize,
+                                message: string_field(issue, "message")?,
+                            })
+                        })
+                        .collect::<Result<_, AnalyzerError>>()?,
+                })
+            })
+            .collect::<Result<_, AnalyzerError>>()?;
+
+        Ok(Snapshot {
+            analyzer_version: string_field(value, "analyzer_version")?,
+            crates,
+        })
+    }
+}
+
+fn field<'a>(value: &'a Value, name: &str) -> Result<&'a Value, AnalyzerError> {
+    value.get(name).ok_or(AnalyzerError::GlobalError(format!(
+        "invalid snapshot: missing field '{}'",
+        name
+    )))
+}
+
+fn as_string<'a>(value: &'a Value, name: &str) -> Result<&'a str, AnalyzerError> {
+    value.as_str().ok_or(AnalyzerError::GlobalError(format!(
+        "invalid snapshot: '{}' should be a string",
+        name
+    )))
+}
+
+fn as_integer(value: &Value, name: &str) -> Result<i64, AnalyzerError> {
+    value.as_i64().ok_or(AnalyzerError::GlobalError(format!(
+        "invalid snapshot: '{}' should be an integer",
+        name
+    )))
+}
+
+fn string_field(value: &Value, name: &str) -> Result<String, AnalyzerError> {
+    Ok(as_string(field(value, name)?, name)?.to_string())
+}
+
+fn integer_field(value: &Value, name: &str) -> Result<i64, AnalyzerError> {
+    as_integer(field(value, name)?, name)
+}
+
+fn array_field<'a>(value: &'a Value, name: &str) -> Result<&'a Vec<Value>, AnalyzerError> {
+    field(value, name)?
+        .as_array()
+        .ok_or(AnalyzerError::GlobalError(format!(
+            "invalid snapshot: '{}' should be an array",
+            name
+        )))
+}
+
+fn object_field<'a>(
+    value: &'a Value,
+    name: &str,
+) -> Result<&'a serde_json::Map<String, Value>, AnalyzerError> {
+    field(value, name)?
+        .as_object()
+        .ok_or(AnalyzerError::GlobalError(format!(
+            "invalid snapshot: '{}' should be an object",
+            name
+        )))
+}
+
+/// Computes the differences between two snapshots. Crates are matched by name.
+pub fn diff_snapshots(old: &Snapshot, new: &Snapshot) -> SnapshotDiff {
+    let mut diff = SnapshotDiff {
+        old_version: old.analyzer_version.clone(),
+        new_version: new.analyzer_version.clone(),
+        ..Default::default()
+    };
+
+    let empty = CrateSnapshot {
+        name: String::new(),
+        revision: String::new(),
+        files: 0,
+        failures: BTreeMap::new(),
+        metrics: BTreeMap::new(),
+        issues: Vec::new(),
+    };
+
+    let names: BTreeSet<&String> = old
+        .crates
+        .iter()
+        .chain(&new.crates)
+        .map(|c| &c.name)
+        .collect();
+    for name in names {
+        let old_crate = old
+            .crates
+            .iter()
+            .find(|c| &c.name == name)
+            .unwrap_or(&empty);
+        let new_crate = new
+            .crates
+            .iter()
+            .find(|c| &c.name == name)
+            .unwrap_or(&empty);
+
+        let crate_diff = diff_crates(name, old_crate, new_crate);
+        for issue in &crate_diff.added_issues {
+            diff.rules.entry(issue.rule_key.clone()).or_default().0 += 1;
+        }
+        for issue in &crate_diff.removed_issues {
+            diff.rules.entry(issue.rule_key.clone()).or_default().1 += 1;
+        }
+
+        if !crate_diff.is_empty() {
+            diff.crates.push(crate_diff);
+        }
+    }
+
+    diff
+}
+
+fn diff_crates(name: &str, old: &CrateSnapshot, new: &CrateSnapshot) -> CrateDiff {
+    let old_issues: BTreeSet<&SnapshotIssue> = old.issues.iter().collect();
+    let new_issues: BTreeSet<&SnapshotIssue> = new.issues.iter().collect();
+
+    let metric_names: BTreeSet<&String> = old.metrics.keys().chain(new.metrics.keys()).collect();
+    let metrics = metric_names
+        .into_iter()
+        .filter_map(|metric| {
+            let old_value = old.metrics.get(metric).copied().unwrap_or_default();
+            let new_value = new.metrics.get(metric).copied().unwrap_or_default();
+            (old_value != new_value).then(|| (metric.clone(), (old_value, new_value)))
+        })
+        .collect();
+
+    CrateDiff {
+        name: name.to_string(),
+        revision: (old.revision != new.revision)
+            .then(|| (old.revision.clone(), new.revision.clone())),
+        added_issues: new_issues
+            .difference(&old_issues)
+            .map(|&i| i.clone())
+            .collect(),
+        removed_issues: old_issues
+            .difference(&new_issues)
+            .map(|&i| i.clone())
+            .collect(),
+        metrics,
+        added_failures: new
+            .failures
+            .keys()
+            .filter(|file| !old.failures.contains_key(*file))
+            .cloned()
+            .collect(),
+        removed_failures: old
+            .failures
+            .keys()
+            .filter(|file| !new.failures.contains_key(*file))
+            .cloned()
+            .collect(),
+    }
+}
+
+impl SnapshotDiff {
+    /// Human-readable report of the differences, meant to be reviewed before a release.
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+        let _ = writeln!(
+            report,
+            "Analyzer {} -> {}",
+            self.old_version, self.new_version
+        );
+
+        if self.crates.is_empty() {
+            report.push_str("No differences.\n");
+            return report;
+        }
+
+        if !self.rules.is_empty() {
+            let _ = writeln!(report, "\n{:<10} {:>8} {:>8}", "Rule", "Added", "Removed");
+            for (rule_key, (added, removed)) in &self.rules {
+                let _ = writeln!(report, "{:<10} {:>8} {:>8}", rule_key, added, removed);
+            }
+        }
+
+        for crate_diff in &self.crates {
+            let _ = writeln!(report, "\n{}", crate_diff.name);
+            if let Some((old, new)) = &crate_diff.revision {
+                let _ = writeln!(report, "  revision: {} -> {}", old, new);
+            }
+            for (metric, (old, new)) in &crate_diff.metrics {
+                let _ = writeln!(report, "  {}: {} -> {}", metric, old, new);
+            }
+            for file in &crate_diff.added_failures {
+                let _ = writeln!(report, "  ! {}: analysis failed", file);
+            }
+            for file in &crate_diff.removed_failures {
+                let _ = writeln!(report, "  ! {}: analysis no longer fails", file);
+            }
+            for (sign, issues) in [
+                ('+', &crate_diff.added_issues),
+                ('-', &crate_diff.removed_issues),
+            ] {
+                for issue in issues {
+                    let _ = writeln!(
+                        report,
+                        "  {} {}:{}:{} {} {}",
+                        sign, issue.file, issue.line, issue.column, issue.rule_key, issue.message
+                    );
+                }
+            }
+        }
+
+        report
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::rule::default_parameters;
+
+    fn issue(file: &str, rule_key: &str, line: usize) -> SnapshotIssue {
+        SnapshotIssue {
+            file: file.to_string(),
+            rule_key: rule_key.to_string(),
+            line,
+            column: 3,
+            message: "message".to_string(),
+        }
+    }
+
+    fn snapshot(version: &str, issues: Vec<SnapshotIssue>, ncloc: i64) -> Snapshot {
+        Snapshot {
+            analyzer_version: version.to_string(),
+            crates: vec![CrateSnapshot {
+                name: "foo".to_string(),
+                revision: "v1.0.0".to_string(),
+                files: 1,
+                failures: BTreeMap::new(),
+                metrics: BTreeMap::from([("ncloc".to_string(), ncloc)]),
+                issues,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_parse_manifest() {
+        let manifest = r#"
+# Pinned crates
+serde https://github.com/serde-rs/serde v1.0.217
+
+itoa   https://github.com/dtolnay/itoa   1.0.14
+"#;
+        assert_eq!(
+            parse_manifest(manifest).unwrap(),
+            vec![
+                CorpusCrate {
+                    name: "serde".to_string(),
+                    repository: "https://github.com/serde-rs/serde".to_string(),
+                    revision: "v1.0.217".to_string(),
+                },
+                CorpusCrate {
+                    name: "itoa".to_string(),
+                    repository: "https://github.com/dtolnay/itoa".to_string(),
+                    revision: "1.0.14".to_string(),
+                },
+            ]
+        );
+
+        assert!(parse_manifest("serde https://github.com/serde-rs/serde").is_err());
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let mut snapshot = snapshot("0.1.0", vec![issue("src/lib.rs", "S3776", 4)], 42);
+        snapshot.crates[0]
+            .failures
+            .insert("src/bad.rs".to_string(), "invalid UTF-8".to_string());
+
+        let json = snapshot.to_json();
+        assert_eq!(Snapshot::from_json(&json).unwrap(), snapshot);
+        assert!(Snapshot::from_json(&json!({"crates": []})).is_err());
+
+        let mut json = json;
+        json["schema_version"] = json!(OLDEST_SNAPSHOT_SCHEMA_VERSION);
+        assert_eq!(Snapshot::from_json(&json).unwrap(), snapshot);
+        json["schema_version"] = json!(1);
+        assert!(Snapshot::from_json(&json).is_err());
+        json["schema_version"] = json!(SNAPSHOT_SCHEMA_VERSION + 1);
+        assert!(Snapshot::from_json(&json).is_err());
+    }
+
+    #[test]
+    fn test_diff() {
+        let old = snapshot(
+            "0.1.0",
+            vec![issue("src/a.rs", "S3776", 1), issue("src/b.rs", "S2260", 2)],
+            10,
+        );
+        let new = snapshot(
+            "0.2.0",
+            vec![
+                issue("src/a.rs", "S3776", 1),
+                issue("src/a.rs", "S3776", 8),
+                issue("src/c.rs", "S3776", 5),
+            ],
+            12,
+        );
+
+        let diff = diff_snapshots(&old, &new);
+        assert_eq!(
+            diff.rules,
+            BTreeMap::from([("S2260".to_string(), (0, 1)), ("S3776".to_string(), (2, 0)),])
+        );
+        assert_eq!(diff.crates.len(), 1);
+        assert_eq!(
+            diff.crates[0].added_issues,
+            vec![issue("src/a.rs", "S3776", 8), issue("src/c.rs", "S3776", 5)]
+        );
+        assert_eq!(
+            diff.crates[0].removed_issues,
+            vec![issue("src/b.rs", "S2260", 2)]
+        );
+        assert_eq!(
+            diff.crates[0].metrics,
+            BTreeMap::from([("ncloc".to_string(), (10, 12))])
+        );
+
+        let report = diff.report();
+        assert!(report.contains("Analyzer 0.1.0 -> 0.2.0"));
+        assert!(report.contains("  + src/c.rs:5:3 S3776 message"));
+        assert!(report.contains("  - src/b.rs:2:3 S2260 message"));
+        assert!(report.contains("  ncloc: 10 -> 12"));
+    }
+
+    #[test]
+    fn test_no_differences() {
+        let old = snapshot("0.1.0", vec![issue("src/a.rs", "S3776", 1)], 10);
+        let diff = diff_snapshots(&old, &old);
+        assert!(diff.crates.is_empty());
+        assert_eq!(diff.report(), "Analyzer 0.1.0 -> 0.1.0\nNo differences.\n");
+    }
+
+    #[test]
+    fn test_vendored_corpus() {
+        let corpus_dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("corpus")
+            .join("vendored");
+        let crates =
+            parse_manifest(&fs::read_to_string(corpus_dir.join("crates.txt")).unwrap()).unwrap();
+        let snapshot = run_corpus(&crates, &corpus_dir, &default_parameters()).unwrap();
+
+        let expected: Value =
+            serde_json::from_str(&fs::read_to_string(corpus_dir.join("expected.json")).unwrap())
+                .unwrap();
+        let diff = diff_snapshots(&Snapshot::from_json(&expected).unwrap(), &snapshot);
+        // See corpus/README.md to update the snapshot
+        assert!(diff.crates.is_empty(), "{}", diff.report());
+    }
+
+    #[test]
+    fn test_run_corpus() {
+        let corpus_dir = std::env::temp_dir().join(format!("corpus-{}", std::process::id()));
+        let src_dir = corpus_dir.join("foo").join("src");
+        fs::create_dir_all(&src_dir).unwrap();
+        fs::create_dir_all(corpus_dir.join("foo").join("target")).unwrap();
+        fs::write(src_dir.join("lib.rs"), "fn foo() {\n    let _x = 42\n}\n").unwrap();
+        fs::write(src_dir.join("bad.rs"), [0xff, 0xfe]).unwrap();
+        fs::write(
+            corpus_dir.join("foo").join("target").join("ignored.rs"),
+            "fn",
+        )
+        .unwrap();
+
+        let crates = parse_manifest("foo https://example.com/foo v1.0.0").unwrap();
+        let snapshot = run_corpus(&crates, &corpus_dir, &default_parameters()).unwrap();
+        fs::remove_dir_all(&corpus_dir).unwrap();
+
+        let foo = &snapshot.crates[0];
+        assert_eq!(foo.files, 2);
+        // Files which are not valid UTF-8 are analyzed after replacing their invalid bytes
+        assert!(foo.failures.is_empty());
+        assert_eq!(foo.metrics["functions"], 1);
+        assert_eq!(
+            foo.issues
+                .iter()
+                .map(|issue| (issue.file.as_str(), issue.rule_key.as_str()))
+                .collect::<Vec<_>>(),
+            vec![("src/bad.rs", "S2260"), ("src/lib.rs", "S2260")]
+        );
+
+        assert!(run_corpus(
+            &parse_manifest("bar x y").unwrap(),
+            &corpus_dir,
+            &default_parameters()
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,494 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{options::list_parameter, tree::AnalyzerError};
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fs,
+    path::Path,
+};
+
+/// Line and branch coverage of a file.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct FileCoverage {
+    /// Execution count of each line (1-based).
+    pub line_hits: BTreeMap<usize, u64>,
+    /// Number of times each branch of each line was taken, by branch identifier.
+    pub branch_hits: BTreeMap<usize, BTreeMap<String, u64>>,
+}
+
+impl FileCoverage {
+    /// Number of conditions of each line with branches, and number of them which were taken.
+    pub fn conditions(&self) -> BTreeMap<usize, (usize, usize)> {
+        self.branch_hits
+            .iter()
+            .map(|(line, branches)| {
+                let covered = branches.values().filter(|taken| **taken > 0).count();
+                (*line, (branches.len(), covered))
+            })
+            .collect()
+    }
+
+    /// Keeps only the coverage of the given lines, e.g. the executable lines of the file, so that the lines reported
+    /// as covered or not by the tools agree with the lines measured by the analyzer.
+    pub fn retain_lines(&mut self, lines: &BTreeSet<usize>) {
+        self.line_hits.retain(|line, _| lines.contains(line));
+        self.branch_hits.retain(|line, _| lines.contains(line));
+    }
+
+    fn add_line_hits(&mut self, line: usize, hits: u64) {
+        *self.line_hits.entry(line).or_default() += hits;
+    }
+
+    fn add_branch_hits(&mut self, line: usize, branch: String, taken: u64) {
+        *self
+            .branch_hits
+            .entry(line)
+            .or_default()
+            .entry(branch)
+            .or_default() += taken;
+    }
+
+    fn merge(&mut self, other: &FileCoverage) {
+        for (line, hits) in &other.line_hits {
+            self.add_line_hits(*line, *hits);
+        }
+        for (line, branches) in &other.branch_hits {
+            for (branch, taken) in branches {
+                self.add_branch_hits(*line, branch.clone(), *taken);
+            }
+        }
+    }
+}
+
+/// Coverage of the files of LCOV reports (e.g. from grcov or `cargo llvm-cov`) and Cobertura reports (e.g. from
+/// tarpaulin), by path of the file in the reports.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct CoverageReport {
+    files: Vec<(String, FileCoverage)>,
+}
+
+impl CoverageReport {
+    /// Parses an LCOV report. The records of unknown types and the invalid records are ignored.
+    pub fn parse_lcov(text: &str) -> Self {
+        let mut report = CoverageReport::default();
+        let mut current: Option<(String, FileCoverage)> = None;
+        for line in text.lines().map(str::trim) {
+            if let Some(path) = line.strip_prefix("SF:") {
+                report.files.extend(current.take());
+                current = Some((path.to_string(), FileCoverage::default()));
+            } else if line == "end_of_record" {
+                report.files.extend(current.take());
+            } else if let Some((_, coverage)) = current.as_mut() {
+                if let Some(record) = line.strip_prefix("DA:") {
+                    // DA:<line>,<hits>[,<checksum>]
+                    let fields: Vec<&str> = record.split(',').collect();
+                    if let [line, hits, ..] = fields.as_slice() {
+                        if let (Ok(line), Ok(hits)) = (line.parse(), hits.parse::<i64>()) {
+                            coverage.add_line_hits(line, hits.max(0) as u64);
+                        }
+                    }
+                } else if let Some(record) = line.strip_prefix("BRDA:") {
+                    // BRDA:<line>,<block>,<branch>,<taken>, where <taken> is '-' when the block was never executed
+                    let fields: Vec<&str> = record.split(',').collect();
+                    if let [line, block, branch, taken] = fields.as_slice() {
+                        if let Ok(line) = line.parse() {
+                            let taken = taken.parse::<i64>().unwrap_or_default().max(0) as u64;
+                            coverage.add_branch_hits(line, format!("{}:{}", block, branch), taken);
+                        }
+                    }
+                }
+            }
+        }
+        report.files.extend(current);
+        report
+    }
+
+    /// Parses a Cobertura report. The lines of the `class` elements are read, with the conditions of their
+    /// `condition-coverage` attribute (e.g. '50% (1/2)') for the branches.
+    pub fn parse_cobertura(text: &str) -> Self {
+        let mut report = CoverageReport::default();
+        let mut current: Option<(String, FileCoverage)> = None;
+        // The lines of the methods repeat the lines of their class
+        let mut in_methods = false;
+        for tag in XmlTags::new(text) {
+            match (tag.name, tag.kind) {
+                ("class", TagKind::Start | TagKind::Empty) => {
+                    report.files.extend(current.take());
+                    current = tag
+                        .attribute("filename")
+                        .map(|filename| (filename, FileCoverage::default()));
+                }
+                ("class", TagKind::End) => report.files.extend(current.take()),
+                ("methods", TagKind::Start) => in_methods = true,
+                ("methods", TagKind::End) => in_methods = false,
+                ("line", TagKind::Start | TagKind::Empty) if !in_methods => {
+                    let Some((_, coverage)) = current.as_mut() else {
+                        continue;
+                    };
+                    let number = tag.attribute("number").and_then(|n| n.parse().ok());
+                    let hits = tag.attribute("hits").and_then(|n| n.parse::<i64>().ok());
+                    let (Some(number), Some(hits)) = (number, hits) else {
+                        continue;
+                    };
+                    coverage.add_line_hits(number, hits.max(0) as u64);
+                    if tag.attribute("branch").as_deref() != Some("true") {
+                        continue;
+                    }
+                    if let Some((covered, total)) = tag
+                        .attribute("condition-coverage")
+                        .as_deref()
+                        .and_then(condition_coverage)
+                    {
+                        for branch in 0..total {
+                            let taken = u64::from(branch < covered);
+                            coverage.add_branch_hits(number, branch.to_string(), taken);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        report.files.extend(current);
+        report
+    }
+
+    /// Reads the reports of the 'lcov_report_paths' and 'cobertura_report_paths' settings, relative to the project
+    /// directory, if any. Their coverage is added to the results of `analyze_batch`.
+    pub fn load(parameters: &HashMap<String, String>) -> Result<Option<Self>, AnalyzerError> {
+        let project_dir = Path::new(
+            parameters
+                .get("project_dir")
+                .map_or(".", |dir| dir.as_str()),
+        );
+        let mut report = None;
+        for (key, parse) in [
+            ("lcov_report_paths", Self::parse_lcov as fn(&str) -> Self),
+            ("cobertura_report_paths", Self::parse_cobertura),
+        ] {
+            let Some(paths) = parameters.get(key) else {
+                continue;
+            };
+            for path in list_parameter(paths) {
+                let path = project_dir.join(path);
+                let text = fs::read_to_string(&path).map_err(|err| {
+                    AnalyzerError::GlobalError(format!(
+                        "failed to read {}: {}",
+                        path.display(),
+                        err
+                    ))
+                })?;
+                report
+                    .get_or_insert_with(CoverageReport::default)
+                    .files
+                    .extend(parse(&text).files);
+            }
+        }
+        Ok(report)
+    }
+
+    /// Coverage of the file at the given path, relative to the project directory, merged from all the reports.
+    ///
+    /// The paths of the reports match when they are the same path, absolute in the project directory, or a suffix of
+    /// the path (as reported by some tools relative to the crate or to the source directory).
+    pub fn file(&self, path: &str, project_dir: Option<&Path>) -> Option<FileCoverage> {
+        let path = normalize_path(path);
+        let project_dir = project_dir.map(|dir| normalize_path(&dir.to_string_lossy()));
+        let mut coverage: Option<FileCoverage> = None;
+        for (report_path, file_coverage) in &self.files {
+            let report_path = normalize_path(report_path);
+            let relative_path = project_dir
+                .as_ref()
+                .and_then(|dir| report_path.strip_prefix(&format!("{}/", dir)))
+                .unwrap_or(&report_path);
+            if relative_path == path || path.ends_with(&format!("/{}", relative_path)) {
+                coverage
+                    .get_or_insert_with(FileCoverage::default)
+                    .merge(file_coverage);
+            }
+        }
+        coverage
+    }
+}
+
+fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let path = path.strip_prefix("./").unwrap_or(&path);
+    path.trim_end_matches('/').to_string()
+}
+
+/// Covered and total conditions of a `condition-coverage` attribute, e.g. '50% (1/2)'.
+fn condition_coverage(value: &str) -> Option<(usize, usize)> {
+    let (_, counts) = value.split_once('(')?;
+    let (covered, total) = counts.strip_suffix(')')?.split_once('/')?;
+    let (covered, total) = (covered.trim().parse().ok()?, total.trim().parse().ok()?);
+    (covered <= total).then_some((covered, total))
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum TagKind {
+    Start,
+    End,
+    Empty,
+}
+
+/// A tag of an XML document, with the raw text of its attributes.
+#[derive(Debug)]
+struct XmlTag<'a> {
+    name: &'a str,
+    kind: TagKind,
+    attributes: &'a str,
+}
+
+impl XmlTag<'_> {
+    /// Unescaped value of an attribute.
+    fn attribute(&self, name: &str) -> Option<String> {
+        let mut rest = self.attributes;
+        loop {
+            let (key, after) = rest.split_once('=')?;
+            let after = after.trim_start();
+            let quote = after.chars().next()?;
+            if quote != '"' && quote != '\'' {
+                return None;
+            }
+            let (value, next) = after[1..].split_once(quote)?;
+            if key.trim() == name {
+                return Some(unescape(value));
+            }
+            rest = next;
+        }
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Tags of an XML document, skipping its text, comments, declarations and CDATA sections.
+///
+/// This is enough for the coverage reports, which only carry data in attributes.
+struct XmlTags<'a> {
+    rest: &'a str,
+}
+
+impl<'a> XmlTags<'a> {
+    fn new(text: &'a str) -> Self {
+        XmlTags { rest: text }
+    }
+}
+
+impl<'a> Iterator for XmlTags<'a> {
+    type Item = XmlTag<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.rest.find('<')?;
+            self.rest = &self.rest[start..];
+            // Comments, CDATA sections, processing instructions and declarations (e.g. DOCTYPE) are not tags
+            let close = [
+                ("<!--", "-->"),
+                ("<![CDATA[", "]]>"),
+                ("<?", "?>"),
+                ("<!", ">"),
+            ]
+            .into_iter()
+            .find_map(|(open, close)| self.rest.starts_with(open).then_some(close));
+            if let Some(close) = close {
+                let end = self
+                    .rest
+                    .find(close)
+                    .map_or(self.rest.len(), |end| end + close.len());
+                self.rest = &self.rest[end..];
+                continue;
+            }
+
+            // The end of the tag is the first '>' outside of the quoted attribute values
+            let mut quote = None;
+            let end = self.rest.char_indices().skip(1).find_map(|(index, c)| {
+                match (quote, c) {
+                    (None, '"' | '\'') => quote = Some(c),
+                    (Some(q), c) if q == c => quote = None,
+                    (None, '>') => return Some(index),
+                    _ => {}
+                }
+                None
+            })?;
+            let content = &self.rest[1..end];
+            self.rest = &self.rest[end + 1..];
+
+            let (kind, content) = if let Some(content) = content.strip_prefix('/') {
+                (TagKind::End, content)
+            } else if let Some(content) = content.strip_suffix('/') {
+                (TagKind::Empty, content)
+            } else {
+                (TagKind::Start, content)
+            };
+            let name_end = content
+                .find(|c: char| c.is_whitespace())
+                .unwrap_or(content.len());
+            return Some(XmlTag {
+                name: &content[..name_end],
+                kind,
+                attributes: &content[name_end..],
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analyze::analyze_batch, rules::rule::default_parameters};
+
+    const LCOV: &str = "TN:
+SF:/work/demo/src/lib.rs
+FN:1,add
+DA:1,3
+DA:2,3
+DA:4,0
+BRDA:2,0,0,2
+BRDA:2,0,1,-
+BRDA:2,1,0,0
+DA:x,1
+end_of_record
+SF:src/lib.rs
+DA:1,1
+BRDA:2,0,1,1
+end_of_record
+";
+
+    const COBERTURA: &str = r#"<?xml version="1.0"?>
+<!DOCTYPE coverage SYSTEM "http://cobertura.sourceforge.net/xml/coverage-04.dtd">
+<coverage lines-valid="3" lines-covered="2">
+  <sources><source>/work/demo</source></sources>
+  <packages>
+    <package name="src">
+      <classes>
+        <!-- <class filename="src/commented.rs"> -->
+        <class name="main" filename="src/main.rs" line-rate="0.5">
+          <methods>
+            <method name="main"><lines><line number="1" hits="9"/></lines></method>
+          </methods>
+          <lines>
+            <line number="1" hits="1"/>
+            <line number="2" hits="0" branch="true" condition-coverage="50% (1/2)"/>
+            <line number="3" hits="-1"/>
+          </lines>
+        </class>
+      </classes>
+    </package>
+  </packages>
+</coverage>
+"#;
+
+    #[test]
+    fn test_lcov() {
+        let report = CoverageReport::parse_lcov(LCOV);
+        let coverage = report
+            .file("src/lib.rs", Some(Path::new("/work/demo")))
+            .unwrap();
+        assert_eq!(coverage.line_hits, BTreeMap::from([(1, 4), (2, 3), (4, 0)]));
+        assert_eq!(coverage.conditions(), BTreeMap::from([(2, (3, 2))]));
+
+        // Paths relative to the crate match the files of the workspace
+        assert!(report.file("crates/demo/src/lib.rs", None).is_some());
+        assert_eq!(report.file("src/main.rs", None), None);
+        assert_eq!(report.file("lib.rs", None), None);
+    }
+
+    #[test]
+    fn test_cobertura() {
+        let report = CoverageReport::parse_cobertura(COBERTURA);
+        let coverage = report.file("src/main.rs", None).unwrap();
+        assert_eq!(coverage.line_hits, BTreeMap::from([(1, 1), (2, 0), (3, 0)]));
+        assert_eq!(coverage.conditions(), BTreeMap::from([(2, (2, 1))]));
+        assert_eq!(report.file("src/commented.rs", None), None);
+    }
+
+    #[test]
+    fn test_xml_tags() {
+        let tags: Vec<_> = XmlTags::new(r#"<a x="1 > 2" y='&amp;'>text<b/></a>"#)
+            .map(|tag| (tag.name, tag.kind, tag.attribute("x"), tag.attribute("y")))
+            .collect();
+        assert_eq!(
+            tags,
+            vec![
+                (
+                    "a",
+                    TagKind::Start,
+                    Some("1 > 2".to_string()),
+                    Some("&".to_string())
+                ),
+                ("b", TagKind::Empty, None, None),
+                ("a", TagKind::End, None, None),
+            ]
+        );
+        assert_eq!(condition_coverage("100% (2/2)"), Some((2, 2)));
+        assert_eq!(condition_coverage("50% (3/2)"), None);
+    }
+
+    #[test]
+    fn test_analyze_batch_coverage() {
+        let dir = std::env::temp_dir().join(format!("coverage-report-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("lcov.info"),
+            "SF:src/lib.rs\nDA:1,2\nDA:2,2\nDA:3,2\nend_of_record\n",
+        )
+        .unwrap();
+        let mut parameters = default_parameters();
+        parameters.insert("project_dir".to_string(), dir.to_string_lossy().to_string());
+        parameters.insert("lcov_report_paths".to_string(), "lcov.info".to_string());
+
+        let files = vec![(
+            "src/lib.rs".to_string(),
+            b"fn f() {\n    g();\n}\n".to_vec(),
+        )];
+        let outputs = analyze_batch(&files, &parameters).unwrap();
+        let output = outputs[0].as_ref().unwrap();
+        let lines: Vec<usize> = output
+            .coverage
+            .as_ref()
+            .unwrap()
+            .line_hits
+            .keys()
+            .copied()
+            .collect();
+        assert_eq!(
+            lines,
+            output
+                .metrics
+                .executable_lines
+                .iter()
+                .copied()
+                .collect::<Vec<_>>()
+        );
+        assert!(lines.len() < 3);
+
+        parameters.insert(
+            "cobertura_report_paths".to_string(),
+            "missing.xml".to_string(),
+        );
+        assert!(analyze_batch(&files, &parameters).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,379 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{find_issues, Issue, SecondaryLocation},
+    macro_expansion::{byte_offset, location_of},
+    options::list_parameter,
+    rules::rule::all_rules,
+    tree::{parse_rust_code, AnalyzerError, NodeIterator, SonarLocation},
+    visitors::highlight::{highlight, HighlightToken},
+};
+use std::collections::HashMap;
+use tree_sitter::Tree;
+
+/// Rules run on the doctests when the 'doctest_rules' setting is absent.
+pub const DEFAULT_DOCTEST_RULES: &str = "S2260";
+
+/// Code of a Rust code block of the doc comments, wrapped in a `main` function when it has none, as by rustdoc.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Doctest {
+    pub code: String,
+    /// Byte offset in the source code of each line of the code, `None` for the lines wrapping it.
+    line_starts: Vec<Option<usize>>,
+}
+
+impl Doctest {
+    fn new(lines: &[(usize, &str)]) -> Self {
+        let wrapped = !lines.iter().any(|(_, line)| line.contains("fn main"));
+        let mut doctest = Doctest {
+            code: String::new(),
+            line_starts: vec![],
+        };
+        if wrapped {
+            doctest.push(None, "fn main() {");
+        }
+        for &(start, line) in lines {
+            doctest.push(Some(start), line);
+        }
+        if wrapped {
+            doctest.push(None, "}");
+        }
+        doctest
+    }
+
+    fn push(&mut self, start: Option<usize>, line: &str) {
+        self.code.push_str(line);
+        self.code.push('\n');
+        self.line_starts.push(start);
+    }
+
+    /// Location in the doc comment of a location in the code, `None` when it starts or ends in the wrapping lines.
+    pub fn source_location(
+        &self,
+        location: &SonarLocation,
+        source_code: &str,
+    ) -> Option<SonarLocation> {
+        let start = self.source_offset(location.start_line, location.start_column)?;
+        let end = self.source_offset(location.end_line, location.end_column)?;
+        Some(location_of(source_code, start..end.max(start)))
+    }
+
+    fn source_offset(&self, line: usize, column: usize) -> Option<usize> {
+        let line_start = (*self.line_starts.get(line.checked_sub(1)?)?)?;
+        Some(line_start + byte_offset(&self.code, line, column) - byte_offset(&self.code, line, 0))
+    }
+}
+
+/// State of the doc comment lines while looking for code blocks.
+enum Block<'a> {
+    Outside,
+    /// Code block of another language, or which is not compiled by rustdoc (e.g. 'ignore').
+    Skipped,
+    /// Lines of a Rust code block, with their byte offset in the source code.
+    Rust(Vec<(usize, &'a str)>),
+}
+
+/// Finds the Rust code blocks of the `///` and `//!` doc comments, in source order.
+///
+/// The blocks without language, or whose attributes are Rust ones such as `no_run` or `should_panic`, are doctests.
+/// The blocks marked `ignore` or `compile_fail` are not, as they are not expected to compile. The lines hidden from
+/// the documentation with a leading `# ` are part of the code.
+pub fn find_doctests(tree: &Tree, source_code: &str) -> Vec<Doctest> {
+    let mut comments: Vec<_> =
+        NodeIterator::new(tree.root_node(), |node| node.kind() == "line_comment").collect();
+    comments.sort_by_key(|comment| comment.start_byte());
+
+    let mut doctests = vec![];
+    let mut block = Block::Outside;
+    let mut previous: Option<(usize, &str)> = None;
+    for comment in comments {
+        let start = comment.start_byte();
+        let end = source_code[start..]
+            .find(['\n', '\r'])
+            .map_or(source_code.len(), |index| start + index);
+        let text = &source_code[start..end];
+        let prefix = ["///", "//!"]
+            .into_iter()
+            .find(|prefix| text.starts_with(prefix) && !text.starts_with("////"));
+        let row = comment.start_position().row;
+
+        // A code block ends with its doc comment, i.e. on a line which does not continue it
+        let continued = matches!((previous, prefix), (Some((previous_row, previous_prefix)), Some(prefix))
+            if previous_row + 1 == row && previous_prefix == prefix);
+        if !continued {
+            if let Block::Rust(lines) = std::mem::replace(&mut block, Block::Outside) {
+                doctests.push(Doctest::new(&lines));
+            }
+        }
+        let Some(prefix) = prefix else {
+            previous = None;
+            continue;
+        };
+        previous = Some((row, prefix));
+
+        let content_start =
+            start + prefix.len() + usize::from(text[prefix.len()..].starts_with(' '));
+        let content = &source_code[content_start..end];
+        let fence = content.trim_start().strip_prefix("```");
+        block = match (block, fence) {
+            (Block::Outside, Some(info)) if is_doctest(info) => Block::Rust(vec![]),
+            (Block::Outside, Some(_)) => Block::Skipped,
+            (Block::Outside, None) => Block::Outside,
+            (Block::Rust(lines), Some(info)) if info.trim().is_empty() => {
+                doctests.push(Doctest::new(&lines));
+                Block::Outside
+            }
+            (Block::Skipped, Some(info)) if info.trim().is_empty() => Block::Outside,
+            (Block::Rust(mut lines), _) => {
+                let trimmed = content.trim_start();
+                let hidden = if trimmed == "#" {
+                    Some("")
+                } else {
+                    trimmed.strip_prefix("# ")
+                };
+                match hidden {
+                    Some(code) => lines.push((end - code.len(), code)),
+                    None => lines.push((content_start, content)),
+                }
+                Block::Rust(lines)
+            }
+            (Block::Skipped, _) => Block::Skipped,
+        };
+    }
+    if let Block::Rust(lines) = block {
+        doctests.push(Doctest::new(&lines));
+    }
+    doctests
+}
+
+fn is_doctest(info: &str) -> bool {
+    info.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|attribute| !attribute.is_empty())
+        .all(|attribute| {
+            matches!(
+                attribute,
+                "rust" | "no_run" | "should_panic" | "test_harness" | "standalone_crate"
+            ) || attribute.starts_with("edition")
+        })
+}
+
+/// Highlighting of the code of the doctests, inside the highlighting of their doc comments.
+pub fn highlight_doctests(
+    tree: &Tree,
+    source_code: &str,
+) -> Result<Vec<HighlightToken>, AnalyzerError> {
+    let mut tokens = vec![];
+    for doctest in find_doctests(tree, source_code) {
+        let doctest_tree = parse_rust_code(&doctest.code)?;
+        for token in highlight(&doctest_tree, &doctest.code)? {
+            if let Some(location) = doctest.source_location(&token.location, source_code) {
+                tokens.push(HighlightToken { location, ..token });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Finds the issues of the rules of the 'doctest_rules' setting in the code of the doctests, reported in their doc
+/// comments. The rules of the 'disabled_rules' setting stay disabled.
+pub fn find_doctest_issues(
+    tree: &Tree,
+    source_code: &str,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<Issue>, AnalyzerError> {
+    let doctests = find_doctests(tree, source_code);
+    if doctests.is_empty() {
+        return Ok(vec![]);
+    }
+    let doctest_rules = list_parameter(
+        parameters
+            .get("doctest_rules")
+            .map_or(DEFAULT_DOCTEST_RULES, String::as_str),
+    );
+    let disabled_rules: Vec<&str> = all_rules(parameters)?
+        .iter()
+        .map(|rule| rule.key())
+        .filter(|key| !doctest_rules.iter().any(|doctest_rule| doctest_rule == key))
+        .collect();
+    let mut parameters = parameters.clone();
+    let mut disabled = parameters
+        .get("disabled_rules")
+        .map(|rules| list_parameter(rules))
+        .unwrap_or_default();
+    disabled.extend(disabled_rules.into_iter().map(str::to_string));
+    parameters.insert("disabled_rules".to_string(), disabled.join(","));
+
+    let mut issues = vec![];
+    for doctest in doctests {
+        let doctest_tree = parse_rust_code(&doctest.code)?;
+        for issue in find_issues(&doctest_tree, &doctest.code, &parameters)? {
+            let Some(location) = doctest.source_location(&issue.location, source_code) else {
+                continue;
+            };
+            let secondary_locations = issue
+                .secondary_locations
+                .iter()
+                .filter_map(|secondary| {
+                    doctest
+                        .source_location(&secondary.location, source_code)
+                        .map(|location| SecondaryLocation {
+                            file: None,
+                            message: secondary.message.clone(),
+                            location,
+                        })
+                })
+                .collect();
+            issues.push(Issue {
+                location,
+                secondary_locations,
+                // The quick fixes are computed on the code of the doctest
+                quick_fixes: vec![],
+                ..issue
+            });
+        }
+    }
+    Ok(issues)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{rules::rule::default_parameters, visitors::highlight::HighlightTokenType};
+
+    const SOURCE_CODE: &str = r#"//! ```
+//! use shapes::Point;
+//! ```
+
+/// Creates a point.
+///
+/// ```no_run
+/// # use shapes::Point;
+/// let p = Point::new(1, 2);
+/// assert_eq!(p.x, 1);
+/// ```
+///
+/// ```text
+/// not rust
+/// ```
+///
+/// ```ignore
+/// let p = ;
+/// ```
+pub fn new(x: i32, y: i32) -> Point {
+    // ```
+    // let p = ;
+    // ```
+    Point { x, y }
+}
+
+/// ```
+/// fn main() {
+///     let p = Point::new(1, 2)
+/// }
+/// ```
+pub fn broken() {}
+"#;
+
+    fn doctest_issues(source_code: &str, parameters: &HashMap<String, String>) -> Vec<Issue> {
+        let tree = parse_rust_code(source_code).unwrap();
+        find_doctest_issues(&tree, source_code, parameters).unwrap()
+    }
+
+    #[test]
+    fn test_find_doctests() {
+        let tree = parse_rust_code(SOURCE_CODE).unwrap();
+        let codes: Vec<String> = find_doctests(&tree, SOURCE_CODE)
+            .into_iter()
+            .map(|doctest| doctest.code)
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                "fn main() {\nuse shapes::Point;\n}\n",
+                "fn main() {\nuse shapes::Point;\nlet p = Point::new(1, 2);\nassert_eq!(p.x, 1);\n}\n",
+                "fn main() {\n    let p = Point::new(1, 2)\n}\n",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_doctest_issues() {
+        let issues = doctest_issues(SOURCE_CODE, &default_parameters());
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule_key, "S2260");
+        assert_eq!(
+            issues[0].location,
+            SonarLocation {
+                start_line: 29,
+                start_column: 31,
+                end_line: 29,
+                end_column: 32,
+            }
+        );
+
+        let mut parameters = default_parameters();
+        parameters.insert("disabled_rules".to_string(), "S2260".to_string());
+        assert_eq!(doctest_issues(SOURCE_CODE, &parameters), vec![]);
+
+        let source_code = "/// ```\n/// fn f(a: bool, b: bool) { if a { if b { if a {} } } }\n/// fn main() {}\n/// ```\nfn g() {}\n";
+        let mut parameters = default_parameters();
+        parameters.insert("S3776:threshold".to_string(), "2".to_string());
+        assert_eq!(doctest_issues(source_code, &parameters), vec![]);
+        parameters.insert("doctest_rules".to_string(), "S3776".to_string());
+        let issues = doctest_issues(source_code, &parameters);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(
+            issues[0].location,
+            SonarLocation {
+                start_line: 2,
+                start_column: 7,
+                end_line: 2,
+                end_column: 8,
+            }
+        );
+        assert_eq!(issues[0].secondary_locations[0].location.start_column, 29);
+    }
+
+    #[test]
+    fn test_highlight_doctests() {
+        let source_code = "/// ```\n/// let s = \"a\";\n/// ```\nfn main() {}\n";
+        let tree = parse_rust_code(source_code).unwrap();
+        assert_eq!(
+            highlight_doctests(&tree, source_code).unwrap(),
+            vec![
+                HighlightToken {
+                    token_type: HighlightTokenType::Keyword,
+                    location: SonarLocation {
+                        start_line: 2,
+                        start_column: 4,
+                        end_line: 2,
+                        end_column: 7,
+                    },
+                },
+                HighlightToken {
+                    token_type: HighlightTokenType::String,
+                    location: SonarLocation {
+                        start_line: 2,
+                        start_column: 12,
+                        end_line: 2,
+                        end_column: 15,
+                    },
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::tree::{parse_rust_code, AnalyzerError, SonarLocation, TreeSitterLocation};
+use serde_json::{json, Value};
+use std::fmt::Write;
+use tree_sitter::{Node, TreeCursor};
+
+/// Format of the dump of a parsed tree.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DumpFormat {
+    /// One node per line, indented by depth, e.g. `name: (identifier [1:3-1:7] "main")`.
+    SExpression,
+    /// Nested objects with the kind, field name, location, text (for leaves) and children of each node.
+    Json,
+}
+
+/// Dumps the named nodes of the tree of the source code, with their Sonar locations, for writing and debugging rules.
+///
+/// Anonymous nodes (keywords and punctuation) are left out, as in the tree-sitter S-expressions, unless they are missing
+/// nodes inserted by the parser, which are marked as `MISSING`.
+pub fn dump_tree(source_code: &str, format: DumpFormat) -> Result<String, AnalyzerError> {
+    let tree = parse_rust_code(source_code)?;
+    let mut cursor = tree.walk();
+    Ok(match format {
+        DumpFormat::SExpression => {
+            let mut dump = String::new();
+            write_sexp(&mut dump, &mut cursor, source_code, 0);
+            dump
+        }
+        DumpFormat::Json => {
+            let json = node_json(&mut cursor, source_code);
+            serde_json::to_string_pretty(&json).map_err(|err| {
+                AnalyzerError::GlobalError(format!("failed to write the tree: {}", err))
+            })? + "\n"
+        }
+    })
+}
+
+fn write_sexp(dump: &mut String, cursor: &mut TreeCursor<'_>, source_code: &str, depth: usize) {
+    let node = cursor.node();
+    let field = cursor
+        .field_name()
+        .map(|name| format!("{}: ", name))
+        .unwrap_or_default();
+    let missing = if node.is_missing() { "MISSING " } else { "" };
+    write!(
+        dump,
+        "{}{}({}{} [{}]",
+        "  ".repeat(depth),
+        field,
+        missing,
+        node.kind(),
+        position(&location(node, source_code))
+    )
+    .unwrap();
+    if let Some(text) = leaf_text(node, source_code) {
+        write!(dump, " {:?}", text).unwrap();
+    }
+    writeln!(dump, ")").unwrap();
+
+    if cursor.goto_first_child() {
+        loop {
+            if cursor.node().is_named() || cursor.node().is_missing() {
+                write_sexp(dump, cursor, source_code, depth + 1);
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+        cursor.goto_parent();
+    }
+}
+
+fn node_json(cursor: &mut TreeCursor<'_>, source_code: &str) -> Value {
+    let node = cursor.node();
+    let location = location(node, source_code);
+    let mut json = json!({
+        "kind": node.kind(),
+        "location": {
+            "startLine": location.start_line,
+            "startColumn": location.start_column,
+            "endLine": location.end_line,
+            "endColumn": location.end_column,
+        },
+    });
+    if let Some(field) = cursor.field_name() {
+        json["field"] = json!(field);
+    }
+    if node.is_missing() {
+        json["missing"] = json!(true);
+    }
+    if let Some(text) = leaf_text(node, source_code) {
+        json["text"] = json!(text);
+    }
+
+    let mut children = vec![];
+    if cursor.goto_first_child() {
+        loop {
+            if cursor.node().is_named() || cursor.node().is_missing() {
+                children.push(node_json(cursor, source_code));
+            }
+            if !cursor.goto_next_sibling() {
+                break;
+            }
+        }
+        cursor.goto_parent();
+    }
+    if !children.is_empty() {
+        json["children"] = Value::Array(children);
+    }
+    json
+}
+
+fn location(node: Node<'_>, source_code: &str) -> SonarLocation {
+    TreeSitterLocation::from_tree_sitter_node(node).to_sonar_location(source_code)
+}
+
+/// Text of the nodes without named children, e.g. identifiers and literals.
+fn leaf_text<'a>(node: Node<'_>, source_code: &'a str) -> Option<&'a str> {
+    (node.named_child_count() == 0 && !node.is_missing()).then(|| &source_code[node.byte_range()])
+}
+
+fn position(location: &SonarLocation) -> String {
+    format!(
+        "{}:{}-{}:{}",
+        location.start_line, location.start_column, location.end_line, location.end_column
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sexp_dump() {
+        assert_eq!(
+            dump_tree("fn main() {\n    f(\"é\");\n}\n", DumpFormat::SExpression).unwrap(),
+            "\
+(source_file [1:0-4:0])
+  (function_item [1:0-3:1])
+    name: (identifier [1:3-1:7] \"main\")
+    parameters: (parameters [1:7-1:9] \"()\")
+    body: (block [1:10-3:1])
+      (expression_statement [2:4-2:11])
+        (call_expression [2:4-2:10])
+          function: (identifier [2:4-2:5] \"f\")
+          arguments: (arguments [2:5-2:10])
+            (string_literal [2:6-2:9])
+              (string_content [2:7-2:8] \"é\")
+"
+        );
+    }
+
+    #[test]
+    fn test_missing_nodes() {
+        let dump = dump_tree("fn main() { let x = 1 }", DumpFormat::SExpression).unwrap();
+        assert!(dump.contains("(MISSING ; [1:21-1:21])"), "{}", dump);
+    }
+
+    #[test]
+    fn test_json_dump() {
+        let dump = dump_tree("struct A;", DumpFormat::Json).unwrap();
+        let json: Value = serde_json::from_str(&dump).unwrap();
+        assert_eq!(
+            json,
+            json!({
+                "kind": "source_file",
+                "location": {"startLine": 1, "startColumn": 0, "endLine": 1, "endColumn": 9},
+                "children": [{
+                    "kind": "struct_item",
+                    "location": {"startLine": 1, "startColumn": 0, "endLine": 1, "endColumn": 9},
+                    "children": [{
+                        "kind": "type_identifier",
+                        "field": "name",
+                        "location": {"startLine": 1, "startColumn": 7, "endLine": 1, "endColumn": 8},
+                        "text": "A",
+                    }],
+                }],
+            })
+        );
+    }
+}
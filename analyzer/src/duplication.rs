@@ -0,0 +1,296 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::visitors::cpd::CpdToken;
+use std::{
+    collections::{BTreeSet, HashMap},
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+/// Minimum number of tokens of a duplicated block when the 'cpd_minimum_tokens' setting is absent, as for the other
+/// languages of SonarQube.
+pub const DEFAULT_MINIMUM_TOKENS: usize = 100;
+
+/// A block of a file duplicated elsewhere in the project, possibly in the same file.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct DuplicatedBlock {
+    /// 1-based lines of the block.
+    pub start_line: usize,
+    pub end_line: usize,
+    /// Path of the file of the other copy, as given to `analyze_batch`.
+    pub other_file: String,
+    pub other_start_line: usize,
+    pub other_end_line: usize,
+}
+
+/// Duplications of a file, computed by `analyze_batch` from the CPD tokens of all the files.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct FileDuplication {
+    /// The blocks of the file, once for each other copy, in source order.
+    pub blocks: Vec<DuplicatedBlock>,
+    /// Lines of the file belonging to at least one block.
+    pub duplicated_lines: BTreeSet<usize>,
+    /// Number of lines of the file.
+    pub lines: usize,
+}
+
+impl FileDuplication {
+    pub fn new(blocks: Vec<DuplicatedBlock>, lines: usize) -> Self {
+        let duplicated_lines = blocks
+            .iter()
+            .flat_map(|block| block.start_line..=block.end_line)
+            .collect();
+        FileDuplication {
+            blocks,
+            duplicated_lines,
+            lines,
+        }
+    }
+
+    /// Percentage of duplicated lines among the lines of the file, as the 'duplicated_lines_density' metric.
+    pub fn density(&self) -> f64 {
+        if self.lines == 0 {
+            0.0
+        } else {
+            self.duplicated_lines.len() as f64 * 100.0 / self.lines as f64
+        }
+    }
+}
+
+/// Finds the sequences of at least `minimum_tokens` tokens appearing more than once in the files, each file being
+/// given by its path and its CPD tokens. Returns the blocks of each file, in the order of the files.
+///
+/// Matching sequences are extended as far as they go, so that a duplicated function is a single block rather than
+/// many overlapping ones. A sequence repeating right after itself in the same file is only a block up to the start of
+/// the next copy.
+pub fn find_duplications(
+    files: &[(&str, &[CpdToken])],
+    minimum_tokens: usize,
+) -> Vec<Vec<DuplicatedBlock>> {
+    let mut blocks = vec![vec![]; files.len()];
+    let minimum_tokens = minimum_tokens.max(1);
+    let windows: Vec<usize> = files
+        .iter()
+        .map(|(_, tokens)| (tokens.len() + 1).saturating_sub(minimum_tokens))
+        .collect();
+
+    // Windows of `minimum_tokens` tokens by rolling hash, then by equal images within each hash
+    let mut by_hash: HashMap<u64, Vec<(usize, usize)>> = HashMap::new();
+    for (file, (_, tokens)) in files.iter().enumerate() {
+        for (index, hash) in window_hashes(tokens, minimum_tokens)
+            .into_iter()
+            .enumerate()
+        {
+            by_hash.entry(hash).or_default().push((file, index));
+        }
+    }
+    let images = |(file, index): (usize, usize)| {
+        files[file].1[index..index + minimum_tokens]
+            .iter()
+            .map(|token| token.image.as_str())
+    };
+    let mut groups: Vec<Vec<(usize, usize)>> = vec![];
+    let mut group_of: Vec<Vec<Option<usize>>> = windows.iter().map(|&n| vec![None; n]).collect();
+    let mut hashes: Vec<_> = by_hash.into_iter().collect();
+    hashes.sort();
+    for (_, occurrences) in hashes {
+        let mut pending = occurrences;
+        while pending.len() > 1 {
+            let first = pending[0];
+            let (same, others): (Vec<_>, Vec<_>) = pending
+                .into_iter()
+                .partition(|&occurrence| images(occurrence).eq(images(first)));
+            if same.len() > 1 {
+                for &(file, index) in &same {
+                    group_of[file][index] = Some(groups.len());
+                }
+                groups.push(same);
+            }
+            pending = others;
+        }
+    }
+
+    for group in &groups {
+        for (position, &(file, index)) in group.iter().enumerate() {
+            for &(other_file, other_index) in &group[position + 1..] {
+                // The pair was already found, extended from the previous windows
+                if index > 0
+                    && other_index > 0
+                    && group_of[file][index - 1].is_some()
+                    && group_of[file][index - 1] == group_of[other_file][other_index - 1]
+                {
+                    continue;
+                }
+                let mut length = 1;
+                while index + length < windows[file]
+                    && other_index + length < windows[other_file]
+                    && group_of[file][index + length].is_some()
+                    && group_of[file][index + length] == group_of[other_file][other_index + length]
+                {
+                    length += 1;
+                }
+                let mut token_count = length + minimum_tokens - 1;
+                if file == other_file {
+                    token_count = token_count.min(other_index - index);
+                    if token_count < minimum_tokens {
+                        continue;
+                    }
+                }
+                let (path, tokens) = files[file];
+                let (other_path, other_tokens) = files[other_file];
+                let lines = |tokens: &[CpdToken], start: usize| {
+                    (
+                        tokens[start].location.start_line,
+                        tokens[start + token_count - 1].location.end_line,
+                    )
+                };
+                let (start_line, end_line) = lines(tokens, index);
+                let (other_start_line, other_end_line) = lines(other_tokens, other_index);
+                blocks[file].push(DuplicatedBlock {
+                    start_line,
+                    end_line,
+                    other_file: other_path.to_string(),
+                    other_start_line,
+                    other_end_line,
+                });
+                blocks[other_file].push(DuplicatedBlock {
+                    start_line: other_start_line,
+                    end_line: other_end_line,
+                    other_file: path.to_string(),
+                    other_start_line: start_line,
+                    other_end_line: end_line,
+                });
+            }
+        }
+    }
+
+    for file_blocks in &mut blocks {
+        file_blocks.sort();
+        file_blocks.dedup();
+    }
+    blocks
+}
+
+/// Hash of each window of `size` consecutive tokens, a polynomial hash updated from one window to the next.
+fn window_hashes(tokens: &[CpdToken], size: usize) -> Vec<u64> {
+    const BASE: u64 = 31;
+    if tokens.len() < size {
+        return vec![];
+    }
+    let token_hashes: Vec<u64> = tokens
+        .iter()
+        .map(|token| {
+            let mut hasher = DefaultHasher::new();
+            token.image.hash(&mut hasher);
+            hasher.finish()
+        })
+        .collect();
+    let highest_power = (1..size).fold(1u64, |power, _| power.wrapping_mul(BASE));
+    let mut hash = token_hashes[..size].iter().fold(0u64, |hash, &token| {
+        hash.wrapping_mul(BASE).wrapping_add(token)
+    });
+    let mut hashes = vec![hash];
+    for index in size..tokens.len() {
+        hash = hash
+            .wrapping_sub(token_hashes[index - size].wrapping_mul(highest_power))
+            .wrapping_mul(BASE)
+            .wrapping_add(token_hashes[index]);
+        hashes.push(hash);
+    }
+    hashes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tree::parse_rust_code, visitors::cpd::calculate_cpd_tokens};
+
+    fn tokens(source_code: &str) -> Vec<CpdToken> {
+        calculate_cpd_tokens(&parse_rust_code(source_code).unwrap(), source_code).unwrap()
+    }
+
+    #[test]
+    fn test_find_duplications() {
+        let first = tokens(
+            r#"
+fn first(a: i32) -> i32 {
+    let b = a * 2;
+    b + 1
+}
+
+fn second(a: i32) -> i32 {
+    let b = a * 2;
+    b + 1
+}
+"#,
+        );
+        let second = tokens("fn other() {}\nfn third(a: i32) -> i32 { let b = a * 2; b + 1 }\n");
+        let blocks = find_duplications(&[("a.rs", &first), ("b.rs", &second)], 15);
+        let block = |start_line, end_line, other_file: &str, other_start_line, other_end_line| {
+            DuplicatedBlock {
+                start_line,
+                end_line,
+                other_file: other_file.to_string(),
+                other_start_line,
+                other_end_line,
+            }
+        };
+        assert_eq!(
+            blocks,
+            vec![
+                vec![
+                    block(2, 5, "a.rs", 7, 10),
+                    block(2, 5, "b.rs", 2, 2),
+                    block(7, 10, "a.rs", 2, 5),
+                    block(7, 10, "b.rs", 2, 2),
+                ],
+                vec![block(2, 2, "a.rs", 2, 5), block(2, 2, "a.rs", 7, 10)],
+            ]
+        );
+
+        let duplication = FileDuplication::new(blocks[0].clone(), 11);
+        assert_eq!(duplication.duplicated_lines.len(), 8);
+        assert!((duplication.density() - 800.0 / 11.0).abs() < 1e-9);
+
+        assert_eq!(
+            find_duplications(&[("a.rs", &first), ("b.rs", &second)], 100),
+            vec![vec![], vec![]]
+        );
+    }
+
+    #[test]
+    fn test_repeated_sequence() {
+        let tokens = tokens("fn f() {\n    g();\n    g();\n    g();\n    g();\n    g();\n}\n");
+        let blocks = find_duplications(&[("a.rs", &tokens)], 8);
+        let block = |start_line, end_line, other_start_line, other_end_line| DuplicatedBlock {
+            start_line,
+            end_line,
+            other_file: "a.rs".to_string(),
+            other_start_line,
+            other_end_line,
+        };
+        // The blocks of two calls stop before the next copy, instead of covering all the calls but the first ones
+        assert_eq!(
+            blocks[0],
+            vec![
+                block(2, 3, 4, 5),
+                block(2, 3, 5, 6),
+                block(4, 5, 2, 3),
+                block(5, 6, 2, 3),
+            ]
+        );
+    }
+}
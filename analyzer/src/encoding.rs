@@ -0,0 +1,130 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use std::{borrow::Cow, fmt, str::FromStr};
+
+/// Encoding of the source files, see the 'encoding' analysis setting.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum Encoding {
+    #[default]
+    Utf8,
+    /// ISO-8859-1, where every byte is the Unicode code point of the same value.
+    Latin1,
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Ok(Encoding::Utf8),
+            "iso-8859-1" | "latin1" | "latin-1" => Ok(Encoding::Latin1),
+            _ => Err(format!("unsupported encoding '{}'", name)),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encoding::Utf8 => write!(f, "UTF-8"),
+            Encoding::Latin1 => write!(f, "ISO-8859-1"),
+        }
+    }
+}
+
+/// Source code decoded from the bytes of a file.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Decoded<'a> {
+    pub source_code: Cow<'a, str>,
+    /// Line (1-based) of the first invalid byte sequence, when the file is not valid in its encoding. The invalid
+    /// sequences are then replaced by U+FFFD.
+    pub first_invalid_line: Option<usize>,
+}
+
+/// Decodes the bytes of a file, replacing the invalid byte sequences rather than failing.
+pub fn decode(bytes: &[u8], encoding: Encoding) -> Decoded<'_> {
+    match encoding {
+        Encoding::Utf8 => match std::str::from_utf8(bytes) {
+            Ok(source_code) => Decoded {
+                source_code: Cow::Borrowed(source_code),
+                first_invalid_line: None,
+            },
+            Err(err) => Decoded {
+                source_code: String::from_utf8_lossy(bytes),
+                first_invalid_line: Some(
+                    bytes[..err.valid_up_to()]
+                        .iter()
+                        .filter(|byte| **byte == b'\n')
+                        .count()
+                        + 1,
+                ),
+            },
+        },
+        Encoding::Latin1 => Decoded {
+            source_code: if bytes.is_ascii() {
+                // ASCII is valid in both encodings
+                Cow::Borrowed(std::str::from_utf8(bytes).unwrap_or_default())
+            } else {
+                Cow::Owned(bytes.iter().map(|byte| *byte as char).collect())
+            },
+            first_invalid_line: None,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encoding_names() {
+        assert_eq!("UTF-8".parse(), Ok(Encoding::Utf8));
+        assert_eq!("utf8".parse(), Ok(Encoding::Utf8));
+        assert_eq!("ISO-8859-1".parse(), Ok(Encoding::Latin1));
+        assert_eq!("latin1".parse(), Ok(Encoding::Latin1));
+        assert_eq!(
+            "Shift_JIS".parse::<Encoding>(),
+            Err("unsupported encoding 'Shift_JIS'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode("// café\n".as_bytes(), Encoding::Utf8),
+            Decoded {
+                source_code: Cow::Borrowed("// café\n"),
+                first_invalid_line: None,
+            }
+        );
+        // 'é' in ISO-8859-1
+        assert_eq!(
+            decode(b"fn main() {}\n// caf\xe9\n", Encoding::Utf8),
+            Decoded {
+                source_code: Cow::Owned("fn main() {}\n// caf\u{fffd}\n".to_string()),
+                first_invalid_line: Some(2),
+            }
+        );
+        assert_eq!(
+            decode(b"fn main() {}\n// caf\xe9\n", Encoding::Latin1),
+            Decoded {
+                source_code: Cow::Owned("fn main() {}\n// café\n".to_string()),
+                first_invalid_line: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,133 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    rules::rule::{all_rules, Trace},
+    tree::{parse_rust_code, AnalyzerError, SonarLocation},
+};
+use std::{collections::HashMap, fmt::Write};
+
+/// Re-runs a single rule on a file and describes why it raised, or did not raise, an issue at the given line.
+///
+/// The description lists the issues starting at that line, followed by the traces the rule recorded for them
+/// (e.g. the complexity increments and the threshold comparison of S3776).
+pub fn explain(
+    rule_key: &str,
+    source_code: &str,
+    line: usize,
+    parameters: &HashMap<String, String>,
+) -> Result<String, AnalyzerError> {
+    let rule = all_rules(parameters)?
+        .into_iter()
+        .find(|rule| rule.key() == rule_key)
+        .ok_or(AnalyzerError::GlobalError(format!(
+            "unknown rule '{}'",
+            rule_key
+        )))?;
+
+    let tree = parse_rust_code(source_code)?;
+    let mut traces: Vec<Trace> = vec![];
+    let issues = rule.check_traced(&tree, source_code, &mut traces)?;
+
+    let mut report = String::new();
+    let issues: Vec<_> = issues
+        .iter()
+        .filter(|issue| issue.location.start_line == line)
+        .collect();
+    if issues.is_empty() {
+        writeln!(report, "{}: no issue at line {}", rule_key, line).unwrap();
+    }
+    for issue in issues {
+        writeln!(
+            report,
+            "{}: issue at {}: {}",
+            rule_key,
+            position(&issue.location),
+            issue.message
+        )
+        .unwrap();
+    }
+
+    let traces: Vec<_> = traces
+        .iter()
+        .filter(|trace| trace.subject.start_line == line)
+        .collect();
+    if traces.is_empty() {
+        writeln!(report, "  the rule did not inspect any code at this line").unwrap();
+    }
+    for trace in traces {
+        writeln!(report, "  {}: {}", position(&trace.location), trace.message).unwrap();
+    }
+
+    Ok(report)
+}
+
+fn position(location: &SonarLocation) -> String {
+    format!("{}:{}", location.start_line, location.start_column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::rule::default_parameters;
+
+    #[test]
+    fn test_explain_issue() {
+        let source_code = r#"
+fn foo(c1: bool, c2: bool) {
+    if c1 {
+        if c2 {}
+    }
+}
+"#;
+        let mut parameters = default_parameters();
+        parameters.insert("S3776:threshold".to_string(), "2".to_string());
+
+        assert_eq!(
+            explain("S3776", source_code, 2, &parameters).unwrap(),
+            "S3776: issue at 2:3: Refactor this function to reduce its Cognitive Complexity from 3 to the 2 allowed.
+  3:4: +1 for 'if' at nesting level 0
+  4:8: +2 for 'if' at nesting level 1
+  2:3: total complexity of 3 is above the threshold of 2
+"
+        );
+    }
+
+    #[test]
+    fn test_explain_no_issue() {
+        let source_code = "fn foo(c1: bool) {\n    if c1 {}\n}\n";
+
+        assert_eq!(
+            explain("S3776", source_code, 1, &default_parameters()).unwrap(),
+            "S3776: no issue at line 1
+  2:4: +1 for 'if' at nesting level 0
+  1:3: total complexity of 1 is not above the threshold of 15
+"
+        );
+        assert_eq!(
+            explain("S3776", source_code, 2, &default_parameters()).unwrap(),
+            "S3776: no issue at line 2\n  the rule did not inspect any code at this line\n"
+        );
+    }
+
+    #[test]
+    fn test_explain_unknown_rule() {
+        assert!(matches!(
+            explain("S0000", "", 1, &default_parameters()),
+            Err(AnalyzerError::GlobalError(message)) if message == "unknown rule 'S0000'"
+        ));
+    }
+}
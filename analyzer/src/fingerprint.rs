@@ -0,0 +1,280 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{issue::Issue, macro_expansion::byte_offset, tree::SonarLocation};
+use std::collections::HashMap;
+use tree_sitter::{Node, Tree};
+
+/// Sets the fingerprints of the issues of a Rust file, see `Issue::fingerprint`.
+///
+/// The fingerprint of an issue hashes its rule key, the code at its location with normalized whitespace, and the
+/// items enclosing it (e.g. `impl Point > fn shift`), so that it does not change when the code around the item moves.
+/// Issues having the same rule key, code and context are told apart by their order in the file.
+pub fn fingerprint_rust_issues(issues: &mut [Issue], tree: &Tree, source_code: &str) {
+    set_fingerprints(issues, source_code, |location| {
+        let start = byte_offset(source_code, location.start_line, location.start_column);
+        let end = byte_offset(source_code, location.end_line, location.end_column).max(start);
+        tree.root_node()
+            .descendant_for_byte_range(start, end)
+            .map(|node| enclosing_items(node, source_code))
+            .unwrap_or_default()
+    });
+}
+
+/// Same as `fingerprint_rust_issues` for the issues of a TOML file (e.g. `Cargo.toml`), whose context is the table of
+/// the issue (e.g. `[dependencies]`).
+pub fn fingerprint_toml_issues(issues: &mut [Issue], text: &str) {
+    let lines: Vec<&str> = text.lines().collect();
+    set_fingerprints(issues, text, |location| {
+        lines
+            .iter()
+            .take(location.start_line)
+            .rev()
+            .map(|line| line.trim())
+            .find(|line| line.starts_with('['))
+            .unwrap_or_default()
+            .to_string()
+    });
+}
+
+fn set_fingerprints(
+    issues: &mut [Issue],
+    source_code: &str,
+    context: impl Fn(&SonarLocation) -> String,
+) {
+    let mut order: Vec<usize> = (0..issues.len()).collect();
+    order.sort_by(|a, b| issues[*a].location.cmp(&issues[*b].location));
+    let mut occurrences: HashMap<(String, String, String), usize> = HashMap::new();
+    for index in order {
+        let issue = &issues[index];
+        let key = (
+            issue.rule_key.clone(),
+            snippet(source_code, &issue.location),
+            context(&issue.location),
+        );
+        let occurrence = occurrences.entry(key.clone()).or_default();
+        let (rule_key, snippet, context) = key;
+        issues[index].fingerprint = Some(fingerprint(&[
+            &rule_key,
+            &snippet,
+            &context,
+            &occurrence.to_string(),
+        ]));
+        *occurrence += 1;
+    }
+}
+
+/// Code at the location with its whitespace normalized, or its line for an empty location.
+fn snippet(source_code: &str, location: &SonarLocation) -> String {
+    let start = byte_offset(source_code, location.start_line, location.start_column);
+    let end = byte_offset(source_code, location.end_line, location.end_column);
+    let text = if start < end {
+        &source_code[start..end]
+    } else {
+        source_code
+            .lines()
+            .nth(location.start_line.saturating_sub(1))
+            .unwrap_or_default()
+    };
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Kinds and names of the items containing the node, from the outermost one, e.g. `mod parser > fn parse`.
+fn enclosing_items(node: Node<'_>, source_code: &str) -> String {
+    let mut items = vec![];
+    let mut current = Some(node);
+    while let Some(node) = current {
+        items.extend(item_name(node, source_code));
+        current = node.parent();
+    }
+    items.reverse();
+    items.join(" > ")
+}
+
+fn item_name(node: Node<'_>, source_code: &str) -> Option<String> {
+    let field = |name: &str| {
+        node.child_by_field_name(name)
+            .map(|child| source_code[child.byte_range()].to_string())
+    };
+    let keyword = match node.kind() {
+        "impl_item" => {
+            let implemented_type = field("type").unwrap_or_default();
+            return Some(match field("trait") {
+                Some(implemented_trait) => {
+                    format!("impl {} for {}", implemented_trait, implemented_type)
+                }
+                None => format!("impl {}", implemented_type),
+            });
+        }
+        "function_item" | "function_signature_item" => "fn",
+        "struct_item" => "struct",
+        "enum_item" => "enum",
+        "union_item" => "union",
+        "trait_item" => "trait",
+        "mod_item" => "mod",
+        "const_item" => "const",
+        "static_item" => "static",
+        "type_item" => "type",
+        "macro_definition" => "macro_rules!",
+        _ => return None,
+    };
+    Some(format!("{} {}", keyword, field("name").unwrap_or_default()))
+}
+
+/// Hash of the syntax tree of a Rust file, which only changes when its code changes: the whitespace and the comments
+/// (including the doc comments) are ignored. Files with the same hash have the same tokens in the same structure, so
+/// that the results of the analysis which do not depend on locations or comments (e.g. the issues of a rule, up to
+/// their locations, or the complexity metrics) are the same.
+pub fn structural_hash(tree: &Tree, source_code: &str) -> String {
+    let mut hash = FNV_OFFSET_BASIS;
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.is_extra() {
+            continue;
+        }
+        // The comments are children of the nodes they are in, they are not counted
+        let mut cursor = node.walk();
+        let children: Vec<Node<'_>> = node
+            .children(&mut cursor)
+            .filter(|child| !child.is_extra())
+            .collect();
+        hash = fnv1a(hash, &node.kind_id().to_be_bytes());
+        hash = fnv1a(hash, &(children.len() as u32).to_be_bytes());
+        if node.child_count() == 0 {
+            hash = fnv1a(hash, source_code[node.byte_range()].as_bytes());
+        }
+        stack.extend(children.into_iter().rev());
+    }
+    format!("{:016x}", hash)
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+
+fn fnv1a(hash: u64, bytes: &[u8]) -> u64 {
+    bytes.iter().chain(&[0]).fold(hash, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+/// 64-bit FNV-1a hash of the parts, which is stable across platforms and versions of Rust, as hexadecimal.
+fn fingerprint(parts: &[&str]) -> String {
+    let hash = parts
+        .iter()
+        .fold(FNV_OFFSET_BASIS, |hash, part| fnv1a(hash, part.as_bytes()));
+    format!("{:016x}", hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    #[test]
+    fn test_structural_hash() {
+        let hash = |source_code: &str| {
+            structural_hash(&parse_rust_code(source_code).unwrap(), source_code)
+        };
+        let original = hash("fn main() {\n    let x = \"a b\";\n}\n");
+
+        assert_eq!(
+            hash("/// Entry point\nfn main()\n{ let x = \"a b\"; // greeting\n}"),
+            original
+        );
+        assert_eq!(hash("fn main() { let /* name */ x = \"a b\"; }"), original);
+        assert_ne!(hash("fn main() {\n    let x = \"a  b\";\n}\n"), original);
+        assert_ne!(hash("fn main() {\n    let y = \"a b\";\n}\n"), original);
+        assert_ne!(hash("fn main() {\n    { let x = \"a b\"; }\n}\n"), original);
+    }
+
+    fn issue(rule_key: &str, line: usize, start_column: usize, end_column: usize) -> Issue {
+        Issue {
+            rule_key: rule_key.to_string(),
+            message: "Issue".to_string(),
+            location: SonarLocation {
+                start_line: line,
+                start_column,
+                end_line: line,
+                end_column,
+            },
+            secondary_locations: vec![],
+            severity: None,
+            quick_fixes: vec![],
+            fingerprint: None,
+            hotspot: None,
+        }
+    }
+
+    fn fingerprints(source_code: &str, mut issues: Vec<Issue>) -> Vec<String> {
+        let tree = parse_rust_code(source_code).unwrap();
+        fingerprint_rust_issues(&mut issues, &tree, source_code);
+        issues
+            .into_iter()
+            .map(|issue| issue.fingerprint.unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn test_fingerprint_rust_issues() {
+        let original = fingerprints(
+            "impl Point {\n    fn shift(&self) {\n        let x = a  +  b;\n        let y = a + b;\n    }\n}\n",
+            vec![issue("S1", 3, 16, 23), issue("S1", 4, 16, 21), issue("S2", 4, 16, 21)],
+        );
+        assert_eq!(original[0], "12c216adcf661021");
+        // The same code in the same function, its occurrences told apart
+        assert_ne!(original[0], original[1]);
+        assert_ne!(original[1], original[2]);
+
+        let moved = fingerprints(
+            "use std::fmt;\n\nimpl Point {\n    fn shift(&self) {\n        let x = a + b;\n\n        let y = a + b;\n    }\n}\n",
+            vec![issue("S2", 7, 16, 21), issue("S1", 5, 16, 21), issue("S1", 7, 16, 21)],
+        );
+        assert_eq!(
+            moved,
+            vec![
+                original[2].clone(),
+                original[0].clone(),
+                original[1].clone()
+            ]
+        );
+
+        let renamed = fingerprints(
+            "impl Point {\n    fn translate(&self) {\n        let x = a + b;\n    }\n}\n",
+            vec![issue("S1", 3, 16, 21)],
+        );
+        assert_ne!(renamed[0], original[0]);
+    }
+
+    #[test]
+    fn test_fingerprint_toml_issues() {
+        let fingerprint = |text: &str, line: usize| {
+            let mut issues = vec![issue("S7469", line, 0, 5)];
+            fingerprint_toml_issues(&mut issues, text);
+            issues[0].fingerprint.clone().unwrap()
+        };
+        let dependency = fingerprint("[dependencies]\nserde = \"*\"\n", 2);
+        assert_eq!(
+            fingerprint(
+                "[package]\nname = \"demo\"\n\n[dependencies]\nserde = \"*\"\n",
+                5
+            ),
+            dependency
+        );
+        assert_ne!(
+            fingerprint("[dev-dependencies]\nserde = \"*\"\n", 2),
+            dependency
+        );
+    }
+}
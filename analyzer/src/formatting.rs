@@ -0,0 +1,301 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, SecondaryLocation},
+    rule_description::RuleDescription,
+    tree::{AnalyzerError, SonarLocation},
+};
+use std::{
+    io::Write,
+    ops::Range,
+    path::Path,
+    process::{Command, Stdio},
+};
+
+pub const FORMATTING_KEY: &str = "S7474";
+
+/// Description of the formatting rule, which runs rustfmt.
+pub const FORMATTING_DESCRIPTION: RuleDescription = RuleDescription {
+    key: FORMATTING_KEY,
+    title: "Source files should be formatted with rustfmt",
+    markdown: r#"
+This rule raises an issue when a file is not formatted as rustfmt formats it, with the configuration of the project.
+
+## Why is this an issue?
+
+A consistent formatting makes the code easier to read, and keeps the changes reviewed free of unrelated whitespace
+edits. Rustfmt is the formatter of the Rust toolchain, its style is the one the Rust community reads every day.
+
+The issue is reported on the first ranges of lines which differ from the formatted file. Rustfmt runs in the project
+directory, so that it reads the `rustfmt.toml` file of the project, with the edition of the
+`sonar.rust.rustfmt.edition` property.
+
+## How to fix it
+
+Run `cargo fmt`, and check the formatting in the continuous integration with `cargo fmt --check`.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+fn add(a:i32,b:i32)->i32{a+b} // Noncompliant
+```
+
+#### Compliant solution
+
+```compliant
+fn add(a: i32, b: i32) -> i32 {
+    a + b
+}
+```
+
+## Resources
+
+### Documentation
+
+- Rustfmt - [Configuring Rustfmt](https://rust-lang.github.io/rustfmt/)
+"#,
+};
+
+/// Maximum number of diverging ranges reported on a file, the first one is the primary location.
+const MAX_DIVERGING_RANGES: usize = 5;
+
+/// Maximum number of lines skipped in each version of the file to find where they agree again, see `diverging_ranges`.
+const RESYNC_WINDOW: usize = 50;
+
+/// Settings of the formatting check, 'rustfmt' is the command to run and 'rustfmt_edition' the edition of the code.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RustfmtOptions {
+    pub command: String,
+    pub edition: String,
+}
+
+/// Formats the file with rustfmt and reports a single issue if the result differs from the source code, with the first
+/// diverging ranges of lines as locations.
+///
+/// Rustfmt runs in the project directory, so that it reads the `rustfmt.toml` configuration of the project. The files
+/// which rustfmt cannot format (e.g. because of syntax errors) fail with a `FileError`.
+pub fn check_formatting(
+    source_code: &str,
+    options: &RustfmtOptions,
+    project_dir: Option<&Path>,
+) -> Result<Option<Issue>, AnalyzerError> {
+    let formatted = rustfmt(source_code, options, project_dir)?;
+    let original_lines: Vec<&str> = source_code.lines().collect();
+    let formatted_lines: Vec<&str> = formatted.lines().collect();
+    let mut locations = diverging_ranges(&original_lines, &formatted_lines)
+        .into_iter()
+        .take(MAX_DIVERGING_RANGES)
+        .map(|range| range_location(&original_lines, range));
+    let Some(location) = locations.next() else {
+        return Ok(None);
+    };
+    Ok(Some(Issue {
+        rule_key: FORMATTING_KEY.to_string(),
+        message: "Format this file with rustfmt.".to_string(),
+        location,
+        secondary_locations: locations
+            .map(|location| SecondaryLocation {
+                message: "Not formatted".to_string(),
+                location,
+                file: None,
+            })
+            .collect(),
+        severity: None,
+        quick_fixes: vec![],
+        fingerprint: None,
+        hotspot: None,
+    }))
+}
+
+fn rustfmt(
+    source_code: &str,
+    options: &RustfmtOptions,
+    project_dir: Option<&Path>,
+) -> Result<String, AnalyzerError> {
+    let mut command = Command::new(&options.command);
+    command
+        .args(["--edition", &options.edition, "--emit", "stdout"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(project_dir) = project_dir {
+        command.current_dir(project_dir);
+    }
+    // A command which cannot run fails the same way for all files
+    let mut child = command.spawn().map_err(|err| {
+        AnalyzerError::GlobalError(format!("failed to run {}: {}", options.command, err))
+    })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(source_code.as_bytes())
+            .map_err(|err| AnalyzerError::FileError(format!("failed to run rustfmt: {}", err)))?;
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|err| AnalyzerError::FileError(format!("failed to run rustfmt: {}", err)))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(AnalyzerError::FileError(format!(
+            "rustfmt failed: {}",
+            stderr.lines().next().unwrap_or_default()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Ranges of the lines of the original version which differ from the formatted version, in order.
+///
+/// An empty range is a place where the formatted version has additional lines. After a difference, the versions agree
+/// again on the nearest pair of equal lines followed by another pair of equal lines, within `RESYNC_WINDOW` lines.
+fn diverging_ranges(original: &[&str], formatted: &[&str]) -> Vec<Range<usize>> {
+    let agree = |i: usize, j: usize| {
+        original.get(i) == formatted.get(j)
+            && (i + 1 >= original.len()
+                || j + 1 >= formatted.len()
+                || original[i + 1] == formatted[j + 1])
+    };
+
+    let mut ranges = vec![];
+    let (mut i, mut j) = (0, 0);
+    while i < original.len() && j < formatted.len() {
+        if original[i] == formatted[j] {
+            i += 1;
+            j += 1;
+            continue;
+        }
+        let resync = (1..=2 * RESYNC_WINDOW).find_map(|distance| {
+            (0..=distance)
+                .map(|skipped| (skipped, distance - skipped))
+                .filter(|&(skipped, added)| skipped <= RESYNC_WINDOW && added <= RESYNC_WINDOW)
+                .find(|&(skipped, added)| agree(i + skipped, j + added))
+        });
+        match resync {
+            Some((skipped, added)) => {
+                ranges.push(i..i + skipped);
+                i += skipped;
+                j += added;
+            }
+            None => {
+                ranges.push(i..original.len());
+                return ranges;
+            }
+        }
+    }
+    if i < original.len() || j < formatted.len() {
+        ranges.push(i..original.len());
+    }
+    ranges
+}
+
+/// Location of whole lines of the original version, or of the line before which lines are missing for empty ranges.
+fn range_location(lines: &[&str], range: Range<usize>) -> SonarLocation {
+    let last_line = lines.len().saturating_sub(1);
+    let start = range.start.min(last_line);
+    let end = range.end.saturating_sub(1).clamp(start, last_line);
+    SonarLocation {
+        start_line: start + 1,
+        start_column: 0,
+        end_line: end + 1,
+        end_column: lines.get(end).map_or(0, |line| line.encode_utf16().count()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rustfmt_options() -> RustfmtOptions {
+        RustfmtOptions {
+            command: "rustfmt".to_string(),
+            edition: "2021".to_string(),
+        }
+    }
+
+    #[test]
+    fn test_diverging_ranges() {
+        let original = ["fn a() {}", "fn b(){", "  1", "}", "", "fn c() {}"];
+        let formatted = ["fn a() {}", "fn b() {", "    1", "}", "", "fn c() {}"];
+        assert_eq!(diverging_ranges(&original, &formatted), vec![1..3]);
+
+        let original = ["fn a() { 1 }", "", "fn b() {}"];
+        let formatted = ["fn a() {", "    1", "}", "", "fn b() {}"];
+        assert_eq!(diverging_ranges(&original, &formatted), vec![0..1]);
+
+        let original = ["use a;", "fn b() {}"];
+        let formatted = ["use a;", "", "fn b() {}"];
+        assert_eq!(diverging_ranges(&original, &formatted), vec![1..1]);
+
+        let original = ["fn a() {}", "", ""];
+        let formatted = ["fn a() {}"];
+        assert_eq!(diverging_ranges(&original, &formatted), vec![1..3]);
+
+        assert!(diverging_ranges(&original, &original).is_empty());
+    }
+
+    #[test]
+    fn test_check_formatting() {
+        let source_code = "fn main() {\n    let x = 1;\n}\n";
+        assert_eq!(
+            check_formatting(source_code, &rustfmt_options(), None).unwrap(),
+            None
+        );
+
+        let source_code = "fn main() {\n  let x=1;\n}\n\nfn g() {}\nfn h(){}\n";
+        assert_eq!(
+            check_formatting(source_code, &rustfmt_options(), None).unwrap(),
+            Some(Issue {
+                rule_key: FORMATTING_KEY.to_string(),
+                message: "Format this file with rustfmt.".to_string(),
+                location: SonarLocation {
+                    start_line: 2,
+                    start_column: 0,
+                    end_line: 2,
+                    end_column: 10,
+                },
+                secondary_locations: vec![SecondaryLocation {
+                    message: "Not formatted".to_string(),
+                    location: SonarLocation {
+                        start_line: 6,
+                        start_column: 0,
+                        end_line: 6,
+                        end_column: 8,
+                    },
+                    file: None,
+                }],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            })
+        );
+
+        assert!(matches!(
+            check_formatting("fn main({\n", &rustfmt_options(), None),
+            Err(AnalyzerError::FileError(_))
+        ));
+        let missing = RustfmtOptions {
+            command: "missing-rustfmt".to_string(),
+            ..rustfmt_options()
+        };
+        assert!(matches!(
+            check_formatting(source_code, &missing, None),
+            Err(AnalyzerError::GlobalError(_))
+        ));
+    }
+}
@@ -14,9 +14,14 @@
  * You should have received a copy of the Sonar Source-Available License
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
+use crate::options::list_parameter;
 use crate::rules::rule::all_rules;
-use crate::tree::{AnalyzerError, SonarLocation};
-use std::collections::HashMap;
+use crate::tree::{catch_panic, AnalyzerError, SonarLocation};
+use std::{
+    collections::{BTreeMap, HashMap},
+    str::FromStr,
+    time::{Duration, Instant},
+};
 use tree_sitter::Tree;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -25,22 +30,382 @@ pub struct Issue {
     pub message: String,
     pub location: SonarLocation,
     pub secondary_locations: Vec<SecondaryLocation>,
+    /// Severity overriding the default severity of the rule, see the '<rule key>:severity' parameters.
+    pub severity: Option<Severity>,
+    /// Alternative changes of the source code fixing the issue, see `apply_quick_fix`.
+    pub quick_fixes: Vec<QuickFix>,
+    /// Hash identifying the issue across changes of the code which do not affect it (e.g. lines added above), set by
+    /// the analysis, see `fingerprint_rust_issues`.
+    pub fingerprint: Option<String>,
+    /// Review status of the issues of the security hotspot rules, see `RuleType::SecurityHotspot`, set by the analysis.
+    /// `None` for the other issues.
+    pub hotspot: Option<HotspotStatus>,
+}
+
+/// Type of the issues of a rule, as declared in the metadata of the rule (e.g. `S3776.json`).
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum RuleType {
+    #[default]
+    CodeSmell,
+    Bug,
+    Vulnerability,
+    /// Security-sensitive code which is not necessarily a vulnerability, such as running an external command, raised
+    /// for a human review rather than to be fixed.
+    SecurityHotspot,
+}
+
+impl RuleType {
+    pub fn to_sonar_api_name(&self) -> &str {
+        match self {
+            RuleType::CodeSmell => "CODE_SMELL",
+            RuleType::Bug => "BUG",
+            RuleType::Vulnerability => "VULNERABILITY",
+            RuleType::SecurityHotspot => "SECURITY_HOTSPOT",
+        }
+    }
+}
+
+/// Status of a security hotspot, which is to review until its review is recorded in the 'reviewed_hotspots' setting,
+/// see `apply_hotspot_reviews`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum HotspotStatus {
+    ToReview,
+    /// The code was reviewed and does not need to change.
+    Safe,
+    /// The code was reviewed and is a risk to fix.
+    Acknowledged,
+}
+
+impl HotspotStatus {
+    pub fn to_sonar_api_name(&self) -> &str {
+        match self {
+            HotspotStatus::ToReview => "TO_REVIEW",
+            HotspotStatus::Safe => "SAFE",
+            HotspotStatus::Acknowledged => "ACKNOWLEDGED",
+        }
+    }
+}
+
+impl FromStr for HotspotStatus {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "TO_REVIEW" => Ok(HotspotStatus::ToReview),
+            "SAFE" => Ok(HotspotStatus::Safe),
+            "ACKNOWLEDGED" => Ok(HotspotStatus::Acknowledged),
+            _ => Err(format!(
+                "unknown hotspot status '{}', expected one of TO_REVIEW, SAFE or ACKNOWLEDGED",
+                name
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum Severity {
+    Info,
+    Minor,
+    Major,
+    Critical,
+    Blocker,
+}
+
+impl Severity {
+    pub fn to_sonar_api_name(&self) -> &str {
+        match self {
+            Severity::Info => "INFO",
+            Severity::Minor => "MINOR",
+            Severity::Major => "MAJOR",
+            Severity::Critical => "CRITICAL",
+            Severity::Blocker => "BLOCKER",
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name {
+            "INFO" => Ok(Severity::Info),
+            "MINOR" => Ok(Severity::Minor),
+            "MAJOR" => Ok(Severity::Major),
+            "CRITICAL" => Ok(Severity::Critical),
+            "BLOCKER" => Ok(Severity::Blocker),
+            _ => Err(format!(
+                "unknown severity '{}', expected one of BLOCKER, CRITICAL, MAJOR, MINOR or INFO",
+                name
+            )),
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
 pub struct SecondaryLocation {
     pub message: String,
     pub location: SonarLocation,
+    /// Path of the file of the location, for the project-level rules, when it is not the file of the issue.
+    pub file: Option<String>,
+}
+
+/// A change of the source code fixing an issue, made of edits which do not overlap.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct QuickFix {
+    /// Description of the change, e.g. 'Remove the parentheses'.
+    pub message: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// Replacement of the text at a location of the source code, an empty location inserting the text.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct TextEdit {
+    pub location: SonarLocation,
+    pub text: String,
 }
 
+/// Runs all rules but the ones of the 'disabled_rules' setting.
 pub fn find_issues(
     tree: &Tree,
     source_code: &str,
     parameters: &HashMap<String, String>,
 ) -> Result<Vec<Issue>, AnalyzerError> {
+    find_issues_timed(tree, source_code, parameters).map(|(issues, _, _)| issues)
+}
+
+/// Time spent by each rule, identified by its key.
+pub type RuleDurations = Vec<(&'static str, Duration)>;
+
+/// Message of the error of each rule which failed on the file, identified by its key.
+pub type RuleFailures = Vec<(&'static str, String)>;
+
+/// Same as `find_issues`, additionally returning the time spent by each rule.
+///
+/// A rule failing on the file, e.g. on an unexpected tree around a syntax error, does not prevent the other rules from
+/// reporting their issues: its error is returned along with the issues.
+pub fn find_issues_timed(
+    tree: &Tree,
+    source_code: &str,
+    parameters: &HashMap<String, String>,
+) -> Result<(Vec<Issue>, RuleDurations, RuleFailures), AnalyzerError> {
     let mut issues = Vec::new();
-    for rule in all_rules(parameters)? {
-        issues.extend(rule.check(tree, source_code)?);
+    let mut durations = Vec::new();
+    let mut failures = Vec::new();
+    let disabled_rules = parameters
+        .get("disabled_rules")
+        .map(|rules| list_parameter(rules))
+        .unwrap_or_default();
+    for rule in all_rules(parameters)?
+        .into_iter()
+        .filter(|rule| !disabled_rules.iter().any(|key| key == rule.key()))
+    {
+        let start = Instant::now();
+        let severity = severity_override(parameters, rule.key())?;
+        let hotspot =
+            (rule.rule_type() == RuleType::SecurityHotspot).then_some(HotspotStatus::ToReview);
+        match catch_panic(|| rule.check(tree, source_code)) {
+            Ok(rule_issues) => issues.extend(rule_issues.into_iter().map(|issue| Issue {
+                severity: severity.or(issue.severity),
+                hotspot: hotspot.or(issue.hotspot),
+                ..issue
+            })),
+            Err(AnalyzerError::FileError(message)) => failures.push((rule.key(), message)),
+            Err(err) => return Err(err),
+        }
+        durations.push((rule.key(), start.elapsed()));
+    }
+    Ok((issues, durations, failures))
+}
+
+/// Severity of the '<rule key>:severity' parameter, e.g. 'S2068:severity=BLOCKER'.
+pub(crate) fn severity_override(
+    parameters: &HashMap<String, String>,
+    rule_key: &str,
+) -> Result<Option<Severity>, AnalyzerError> {
+    let key = format!("{}:severity", rule_key);
+    parameters
+        .get(&key)
+        .map(|severity| {
+            severity.trim().to_ascii_uppercase().parse().map_err(|err| {
+                AnalyzerError::GlobalError(format!("could not parse '{}' parameter: {}", key, err))
+            })
+        })
+        .transpose()
+}
+
+/// Sets the status of the security hotspots reviewed in the 'reviewed_hotspots' setting, a comma-separated list of
+/// '<fingerprint>:<status>' entries such as '9f86d081884c7d65:SAFE'. The issues must have their fingerprints, see
+/// `fingerprint_rust_issues`, which do not change when the code around the hotspot changes.
+pub fn apply_hotspot_reviews(
+    issues: &mut [Issue],
+    parameters: &HashMap<String, String>,
+) -> Result<(), AnalyzerError> {
+    let Some(reviewed) = parameters.get("reviewed_hotspots") else {
+        return Ok(());
+    };
+    let mut statuses = HashMap::new();
+    for entry in list_parameter(reviewed) {
+        let status = entry
+            .split_once(':')
+            .ok_or_else(|| format!("expected '<fingerprint>:<status>' but was '{}'", entry))
+            .and_then(|(fingerprint, status)| {
+                Ok((
+                    fingerprint.to_string(),
+                    status.trim().to_ascii_uppercase().parse()?,
+                ))
+            })
+            .map_err(|err| {
+                AnalyzerError::GlobalError(format!(
+                    "could not parse 'reviewed_hotspots' parameter: {}",
+                    err
+                ))
+            })?;
+        statuses.insert(status.0, status.1);
+    }
+    for issue in issues.iter_mut().filter(|issue| issue.hotspot.is_some()) {
+        if let Some(status) = issue
+            .fingerprint
+            .as_ref()
+            .and_then(|fingerprint| statuses.get(fingerprint))
+        {
+            issue.hotspot = Some(*status);
+        }
+    }
+    Ok(())
+}
+
+/// Sorts the issues by location, then by rule key and message, keeping the order of the issues with the same ones.
+pub fn sort_issues(issues: &mut [Issue]) {
+    issues.sort_by(|a, b| {
+        (&a.location, &a.rule_key, &a.message).cmp(&(&b.location, &b.rule_key, &b.message))
+    });
+}
+
+/// Removes the issues raised more than once with the same rule, location and message, e.g. by several expansions of a
+/// macro, keeping the first one in place. The secondary locations and quick fixes of the removed issues are added to
+/// the kept one when it does not have them already.
+pub fn deduplicate_issues(issues: &mut Vec<Issue>) {
+    let mut kept: BTreeMap<(String, SonarLocation, String), usize> = BTreeMap::new();
+    let mut deduplicated: Vec<Issue> = Vec::with_capacity(issues.len());
+    for issue in issues.drain(..) {
+        let key = (
+            issue.rule_key.clone(),
+            issue.location.clone(),
+            issue.message.clone(),
+        );
+        match kept.get(&key) {
+            Some(&index) => {
+                let first = &mut deduplicated[index];
+                for location in issue.secondary_locations {
+                    if !first.secondary_locations.contains(&location) {
+                        first.secondary_locations.push(location);
+                    }
+                }
+                for quick_fix in issue.quick_fixes {
+                    if !first.quick_fixes.contains(&quick_fix) {
+                        first.quick_fixes.push(quick_fix);
+                    }
+                }
+            }
+            None => {
+                kept.insert(key, deduplicated.len());
+                deduplicated.push(issue);
+            }
+        }
+    }
+    *issues = deduplicated;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issue(rule_key: &str, line: usize, message: &str, secondary_lines: &[usize]) -> Issue {
+        let location = |line| SonarLocation {
+            start_line: line,
+            start_column: 0,
+            end_line: line,
+            end_column: 1,
+        };
+        Issue {
+            rule_key: rule_key.to_string(),
+            message: message.to_string(),
+            location: location(line),
+            secondary_locations: secondary_lines
+                .iter()
+                .map(|&line| SecondaryLocation {
+                    message: String::new(),
+                    location: location(line),
+                    file: None,
+                })
+                .collect(),
+            severity: None,
+            quick_fixes: vec![],
+            fingerprint: None,
+            hotspot: None,
+        }
+    }
+
+    #[test]
+    fn test_deduplicate_issues() {
+        let mut issues = vec![
+            issue("S3776", 1, "Refactor.", &[2]),
+            issue("S2260", 1, "Refactor.", &[]),
+            issue("S3776", 1, "Refactor.", &[2, 3]),
+            issue("S3776", 1, "Other message.", &[]),
+            issue("S3776", 4, "Refactor.", &[]),
+            issue("S2260", 1, "Refactor.", &[]),
+        ];
+        deduplicate_issues(&mut issues);
+        assert_eq!(
+            issues,
+            vec![
+                issue("S3776", 1, "Refactor.", &[2, 3]),
+                issue("S2260", 1, "Refactor.", &[]),
+                issue("S3776", 1, "Other message.", &[]),
+                issue("S3776", 4, "Refactor.", &[]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_apply_hotspot_reviews() {
+        let hotspot = |line: usize, fingerprint: &str| Issue {
+            fingerprint: Some(fingerprint.to_string()),
+            hotspot: Some(HotspotStatus::ToReview),
+            ..issue("S4721", line, "Make sure that this command is safe.", &[])
+        };
+        let mut issues = vec![
+            hotspot(1, "aaaa"),
+            hotspot(2, "bbbb"),
+            hotspot(3, "cccc"),
+            Issue {
+                fingerprint: Some("dddd".to_string()),
+                ..issue("S3776", 4, "Refactor.", &[])
+            },
+        ];
+        let parameters = HashMap::from([(
+            "reviewed_hotspots".to_string(),
+            "aaaa:SAFE, bbbb:acknowledged, dddd:SAFE".to_string(),
+        )]);
+        apply_hotspot_reviews(&mut issues, &parameters).unwrap();
+
+        let statuses: Vec<Option<HotspotStatus>> =
+            issues.iter().map(|issue| issue.hotspot).collect();
+        assert_eq!(
+            statuses,
+            vec![
+                Some(HotspotStatus::Safe),
+                Some(HotspotStatus::Acknowledged),
+                Some(HotspotStatus::ToReview),
+                None
+            ]
+        );
+
+        for invalid in ["aaaa", "aaaa:FIXED"] {
+            let parameters =
+                HashMap::from([("reviewed_hotspots".to_string(), invalid.to_string())]);
+            assert!(apply_hotspot_reviews(&mut issues, &parameters).is_err());
+        }
     }
-    Ok(issues)
 }
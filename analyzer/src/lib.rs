@@ -0,0 +1,101 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+//! Rust analyzer of the Sonar Rust plugin.
+//!
+//! The `analyzer` binary drives the analysis through the protocol spoken by the plugin, while embedders can
+//! call `analyze::analyze` (or `analyze::analyze_borrowed`, which avoids copying token text) directly.
+//! `analyze::analyze_batch` analyzes the files of a project together, for the rules which need all of them, and
+//! `session::AnalysisSession` analyzes the unsaved content of the files open in an editor.
+pub mod advisories;
+pub mod analyze;
+pub mod cargo_metadata;
+pub mod cli;
+pub mod clippy;
+pub mod config;
+pub mod corpus;
+pub mod coverage;
+pub mod doctest;
+pub mod dump;
+pub mod duplication;
+pub mod encoding;
+pub mod explain;
+pub mod fingerprint;
+pub mod formatting;
+pub mod issue;
+pub mod logging;
+pub mod macro_expansion;
+pub mod manifest;
+pub mod options;
+pub mod profile;
+pub mod project_index;
+pub mod protocol;
+pub mod quick_fix;
+pub mod rule_description;
+pub mod rules {
+    pub mod boolean_literal_check;
+    pub mod cognitive_complexity_check;
+    pub mod collapsible_if_check;
+    pub mod comment_pattern_check;
+    pub mod commented_out_code_check;
+    pub mod duplicate_condition_check;
+    pub mod duplicated_string_check;
+    pub mod empty_block_check;
+    pub mod empty_function_check;
+    pub mod file_length_check;
+    pub mod function_length_check;
+    pub mod hardcoded_credentials_check;
+    pub mod hardcoded_ip_check;
+    pub mod identical_branches_check;
+    pub mod identical_operands_check;
+    pub mod large_pass_by_value_check;
+    pub mod magic_number_check;
+    pub mod marker_comment_check;
+    pub mod nesting_depth_check;
+    pub mod panic_macro_check;
+    pub mod parsing_error_check;
+    pub mod query_rule;
+    pub mod rule;
+    pub mod too_many_parameters_check;
+    pub mod unused_parameter_check;
+    pub mod unused_variable_check;
+    pub mod unwrap_check;
+}
+pub mod session;
+pub mod test_execution;
+pub mod toml;
+pub mod tree;
+#[cfg(feature = "type-aware")]
+pub mod type_aware;
+pub mod validation;
+pub mod verifier;
+pub mod visitors {
+    pub mod cfg;
+    pub mod cognitive_complexity;
+    pub mod control_flow;
+    pub mod cpd;
+    pub mod cyclomatic_complexity;
+    pub mod data_flow;
+    pub mod function_metrics;
+    pub mod generated_code;
+    pub mod halstead;
+    pub mod highlight;
+    pub mod metrics;
+    pub mod name_resolution;
+    pub mod symbols;
+    pub mod taint;
+    pub mod test_code;
+}
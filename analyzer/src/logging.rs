@@ -0,0 +1,327 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+//! Structured log records of the analyzer.
+//!
+//! The records are written to stderr as '<level> <message>' lines, which the plugin forwards to the scanner logs
+//! at the same level, and, when the 'log_file' setting is present, appended to this file as JSON lines with their
+//! context (file, rule, location). Only the records at or above the 'log_level' setting ('info' by default) are
+//! written.
+use crate::{
+    analyze::AnalysisWarning,
+    tree::{AnalyzerError, SonarLocation},
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    fmt,
+    fs::{File, OpenOptions},
+    io::Write,
+    str::FromStr,
+};
+
+/// Severity of a record, from the most to the least severe.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "error" => Ok(LogLevel::Error),
+            "warn" => Ok(LogLevel::Warn),
+            "info" => Ok(LogLevel::Info),
+            "debug" | "trace" => Ok(LogLevel::Debug),
+            _ => Err(format!(
+                "unknown log level '{}', expected one of error, warn, info, debug",
+                value
+            )),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LogRecord {
+    pub level: LogLevel,
+    pub message: String,
+    /// Path of the analyzed file, as sent by the plugin.
+    pub file: Option<String>,
+    pub rule_key: Option<String>,
+    pub location: Option<SonarLocation>,
+}
+
+impl LogRecord {
+    pub fn new(level: LogLevel, message: impl Into<String>) -> Self {
+        LogRecord {
+            level,
+            message: message.into(),
+            file: None,
+            rule_key: None,
+            location: None,
+        }
+    }
+
+    pub fn with_file(mut self, file: &str) -> Self {
+        self.file = Some(file.to_string());
+        self
+    }
+
+    pub fn with_rule_key(mut self, rule_key: &str) -> Self {
+        self.rule_key = Some(rule_key.to_string());
+        self
+    }
+
+    pub fn with_location(mut self, location: SonarLocation) -> Self {
+        self.location = Some(location);
+        self
+    }
+
+    /// The records reporting a warning of the analysis of a file: the warning itself, followed, for syntax errors,
+    /// by one debug record per region whose results are partial.
+    pub fn from_warning(file: &str, warning: &AnalysisWarning) -> Vec<LogRecord> {
+        let record = LogRecord::new(LogLevel::Warn, warning.to_string()).with_file(file);
+        match warning {
+            AnalysisWarning::RuleFailed { rule_key, .. } => vec![record.with_rule_key(rule_key)],
+            AnalysisWarning::SyntaxErrors { locations } => std::iter::once(record)
+                .chain(locations.iter().map(|location| {
+                    LogRecord::new(LogLevel::Debug, "skipped region with syntax errors")
+                        .with_file(file)
+                        .with_location(location.clone())
+                }))
+                .collect(),
+            _ => vec![record],
+        }
+    }
+
+    /// The line written to stderr, prefixed by the level for the plugin.
+    pub fn to_text(&self) -> String {
+        let mut text = format!("{} ", self.level);
+        if let Some(file) = &self.file {
+            text.push_str(file);
+            if let Some(location) = &self.location {
+                text.push_str(&format!(
+                    ":{}:{}",
+                    location.start_line, location.start_column
+                ));
+            }
+            text.push_str(": ");
+        }
+        text.push_str(&self.message);
+        text
+    }
+
+    pub fn to_json(&self) -> Value {
+        let mut value = json!({
+            "level": self.level.to_string(),
+            "message": self.message,
+        });
+        if let Some(file) = &self.file {
+            value["file"] = json!(file);
+        }
+        if let Some(rule_key) = &self.rule_key {
+            value["rule_key"] = json!(rule_key);
+        }
+        if let Some(location) = &self.location {
+            value["location"] = json!({
+                "start_line": location.start_line,
+                "start_column": location.start_column,
+                "end_line": location.end_line,
+                "end_column": location.end_column,
+            });
+        }
+        value
+    }
+}
+
+/// Writes the records at or above its level to stderr, and to the JSON log file, if any.
+#[derive(Debug)]
+pub struct Logger {
+    level: LogLevel,
+    json_sink: Option<File>,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Logger {
+            level: LogLevel::Info,
+            json_sink: None,
+        }
+    }
+}
+
+impl Logger {
+    /// Reads the 'log_level' and 'log_file' settings. The log file is created when missing, and appended to otherwise.
+    pub fn from_parameters(parameters: &HashMap<String, String>) -> Result<Self, AnalyzerError> {
+        let level = match parameters.get("log_level") {
+            Some(value) => value.parse().map_err(|err| {
+                AnalyzerError::GlobalError(format!(
+                    "could not parse 'log_level' parameter: {}",
+                    err
+                ))
+            })?,
+            None => LogLevel::Info,
+        };
+        let json_sink = match parameters.get("log_file") {
+            Some(path) => Some(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .map_err(|err| {
+                        AnalyzerError::GlobalError(format!(
+                            "failed to open log file {}: {}",
+                            path, err
+                        ))
+                    })?,
+            ),
+            None => None,
+        };
+        Ok(Logger { level, json_sink })
+    }
+
+    pub fn enabled(&self, level: LogLevel) -> bool {
+        level <= self.level
+    }
+
+    pub fn log(&mut self, record: &LogRecord) {
+        if !self.enabled(record.level) {
+            return;
+        }
+        eprintln!("{}", record.to_text());
+        if let Some(sink) = &mut self.json_sink {
+            // A log file which cannot be written must not fail the analysis
+            if writeln!(sink, "{}", record.to_json()).is_err() {
+                self.json_sink = None;
+                eprintln!("warn failed to write to the log file, it is no longer written");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn location(line: usize) -> SonarLocation {
+        SonarLocation {
+            start_line: line,
+            start_column: 4,
+            end_line: line,
+            end_column: 8,
+        }
+    }
+
+    #[test]
+    fn test_log_level() {
+        assert_eq!("WARN".parse::<LogLevel>(), Ok(LogLevel::Warn));
+        assert_eq!("trace".parse::<LogLevel>(), Ok(LogLevel::Debug));
+        assert!("verbose".parse::<LogLevel>().is_err());
+
+        let logger = Logger::default();
+        assert!(logger.enabled(LogLevel::Error));
+        assert!(logger.enabled(LogLevel::Info));
+        assert!(!logger.enabled(LogLevel::Debug));
+    }
+
+    #[test]
+    fn test_warning_records() {
+        let records = LogRecord::from_warning(
+            "src/lib.rs",
+            &AnalysisWarning::RuleFailed {
+                rule_key: "S3776".to_string(),
+                message: "the analysis timed out".to_string(),
+            },
+        );
+        assert_eq!(
+            records,
+            vec![LogRecord::new(
+                LogLevel::Warn,
+                "rule S3776 failed, missing issues: the analysis timed out"
+            )
+            .with_file("src/lib.rs")
+            .with_rule_key("S3776")]
+        );
+        assert_eq!(
+            records[0].to_text(),
+            "warn src/lib.rs: rule S3776 failed, missing issues: the analysis timed out"
+        );
+
+        let records = LogRecord::from_warning(
+            "src/lib.rs",
+            &AnalysisWarning::SyntaxErrors {
+                locations: vec![location(2), location(5)],
+            },
+        );
+        assert_eq!(records.len(), 3);
+        assert_eq!(records[1].level, LogLevel::Debug);
+        assert_eq!(
+            records[2].to_text(),
+            "debug src/lib.rs:5:4: skipped region with syntax errors"
+        );
+        assert_eq!(
+            records[2].to_json(),
+            json!({
+                "level": "debug",
+                "message": "skipped region with syntax errors",
+                "file": "src/lib.rs",
+                "location": {"start_line": 5, "start_column": 4, "end_line": 5, "end_column": 8},
+            })
+        );
+    }
+
+    #[test]
+    fn test_log_file() {
+        let path = std::env::temp_dir().join(format!("analyzer-log-{}.jsonl", std::process::id()));
+        let _ = fs::remove_file(&path);
+        let parameters = HashMap::from([
+            ("log_level".to_string(), "warn".to_string()),
+            ("log_file".to_string(), path.to_string_lossy().to_string()),
+        ]);
+
+        let mut logger = Logger::from_parameters(&parameters).unwrap();
+        logger.log(&LogRecord::new(LogLevel::Info, "analyzing").with_file("src/lib.rs"));
+        logger.log(&LogRecord::new(LogLevel::Error, "failure").with_file("src/lib.rs"));
+
+        let content = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(
+            content,
+            "{\"file\":\"src/lib.rs\",\"level\":\"error\",\"message\":\"failure\"}\n"
+        );
+
+        let parameters = HashMap::from([("log_level".to_string(), "verbose".to_string())]);
+        assert!(Logger::from_parameters(&parameters).is_err());
+    }
+}
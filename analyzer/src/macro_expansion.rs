@@ -0,0 +1,565 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{find_issues, Issue, SecondaryLocation},
+    tree::{
+        line_starts, parse_rust_code, point_of, AnalyzerError, NodeIterator, SonarLocation,
+        TreeSitterLocation,
+    },
+};
+use std::{collections::HashMap, ops::Range};
+use tree_sitter::{Node, Tree};
+
+/// Code wrapping the expansions of the macros invoked in expressions and statements, so that they parse.
+const WRAPPER_PREFIX: &str = "fn __expansion() {\n";
+const WRAPPER_SUFFIX: &str = "\n}\n";
+
+/// Where a part of the expanded code comes from.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+enum Origin {
+    /// Tokens passed to the macro, starting at the given byte offset of the source code.
+    Argument(usize),
+    /// Tokens of the macro definition, their issues are reported at the invocation.
+    Definition,
+    /// Code added to parse the expansion, its issues are not reported.
+    Wrapper,
+}
+
+#[derive(Debug)]
+struct Segment {
+    range: Range<usize>,
+    origin: Origin,
+}
+
+/// Code produced by the invocation of a declarative macro defined in the same file.
+#[derive(Debug)]
+pub struct Expansion {
+    /// Location of the invocation in the source code.
+    pub invocation: SonarLocation,
+    pub code: String,
+    segments: Vec<Segment>,
+}
+
+impl Expansion {
+    fn push(&mut self, text: &str, origin: Origin) {
+        let start = self.code.len();
+        self.code.push_str(text);
+        self.segments.push(Segment {
+            range: start..self.code.len(),
+            origin,
+        });
+    }
+
+    /// Location in the source code of a location in the expanded code: the tokens passed to the macro are mapped to
+    /// their own location, the others to the invocation. `None` for the code added to parse the expansion.
+    pub fn source_location(
+        &self,
+        location: &SonarLocation,
+        source_code: &str,
+    ) -> Option<SonarLocation> {
+        let start = byte_offset(&self.code, location.start_line, location.start_column);
+        let end = byte_offset(&self.code, location.end_line, location.end_column);
+        let segment = self
+            .segments
+            .iter()
+            .find(|segment| segment.range.contains(&start))?;
+        match segment.origin {
+            Origin::Wrapper => None,
+            Origin::Argument(source_start) if end <= segment.range.end => {
+                let offset = source_start - segment.range.start;
+                Some(location_of(source_code, start + offset..end + offset))
+            }
+            _ => Some(self.invocation.clone()),
+        }
+    }
+}
+
+/// Expands the invocations of the `macro_rules!` macros defined in the file, on a best-effort basis.
+///
+/// Only the rules without repetitions (`$(...)*`) are supported, and the macros invoked in the expansions are not
+/// expanded. The invocations which match none of the supported rules are ignored.
+pub fn expand_macros(tree: &Tree, source_code: &str) -> Vec<Expansion> {
+    let definitions = macro_definitions(tree, source_code);
+    if definitions.is_empty() {
+        return vec![];
+    }
+
+    NodeIterator::new(tree.root_node(), |node| node.kind() == "macro_invocation")
+        .filter_map(|invocation| {
+            let name = invocation.child_by_field_name("macro")?;
+            let rules = definitions.get(&source_code[name.byte_range()])?;
+            let arguments = invocation.named_child(invocation.named_child_count() - 1)?;
+            let tokens = token_trees(arguments);
+            rules.iter().find_map(|rule| {
+                let mut bindings = HashMap::new();
+                if !match_tokens(&rule.matchers, &tokens, source_code, &mut bindings) {
+                    return None;
+                }
+                expand(invocation, rule.transcriber, &bindings, source_code)
+            })
+        })
+        .collect()
+}
+
+/// Finds the issues of the expanded code of the macro invocations, reported at the invocations.
+///
+/// The expansions which do not parse are ignored, as they are more likely to be a limitation of the expansion than an
+/// actual syntax error.
+pub fn find_expanded_issues(
+    tree: &Tree,
+    source_code: &str,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<Issue>, AnalyzerError> {
+    let mut issues = Vec::new();
+    for expansion in expand_macros(tree, source_code) {
+        let expanded_tree = parse_rust_code(&expansion.code)?;
+        if expanded_tree.root_node().has_error() {
+            continue;
+        }
+
+        for issue in find_issues(&expanded_tree, &expansion.code, parameters)? {
+            if let Some(location) = expansion.source_location(&issue.location, source_code) {
+                let secondary_locations = issue
+                    .secondary_locations
+                    .iter()
+                    .filter_map(|secondary| {
+                        expansion
+                            .source_location(&secondary.location, source_code)
+                            .map(|location| SecondaryLocation {
+                                file: None,
+                                message: secondary.message.clone(),
+                                location,
+                            })
+                    })
+                    .collect();
+                issues.push(Issue {
+                    location,
+                    secondary_locations,
+                    ..issue
+                });
+            }
+        }
+    }
+    issues.sort();
+    issues.dedup();
+    Ok(issues)
+}
+
+enum Matcher<'a> {
+    Token(&'a str),
+    Binding {
+        name: &'a str,
+        fragment: &'a str,
+    },
+    Group {
+        open: &'a str,
+        matchers: Vec<Matcher<'a>>,
+    },
+}
+
+struct MacroRule<'a> {
+    matchers: Vec<Matcher<'a>>,
+    transcriber: Node<'a>,
+}
+
+/// Supported rules of the macros defined in the file, by macro name.
+fn macro_definitions<'a>(
+    tree: &'a Tree,
+    source_code: &'a str,
+) -> HashMap<&'a str, Vec<MacroRule<'a>>> {
+    let mut definitions = HashMap::new();
+    for definition in NodeIterator::new(tree.root_node(), |node| node.kind() == "macro_definition")
+    {
+        let Some(name) = definition.child_by_field_name("name") else {
+            continue;
+        };
+        let mut cursor = definition.walk();
+        let rules = definition
+            .named_children(&mut cursor)
+            .filter(|child| child.kind() == "macro_rule")
+            .filter_map(|rule| {
+                Some(MacroRule {
+                    matchers: matchers(rule.child_by_field_name("left")?, source_code)?,
+                    transcriber: rule.child_by_field_name("right")?,
+                })
+            })
+            .collect();
+        definitions.insert(&source_code[name.byte_range()], rules);
+    }
+    definitions
+}
+
+/// Matchers of a pattern, `None` when the pattern is not supported.
+fn matchers<'a>(pattern: Node<'a>, source_code: &'a str) -> Option<Vec<Matcher<'a>>> {
+    token_trees(pattern)
+        .into_iter()
+        .map(|child| match child.kind() {
+            "token_binding_pattern" => Some(Matcher::Binding {
+                name: &source_code[child.child_by_field_name("name")?.byte_range()],
+                fragment: &source_code[child.child_by_field_name("type")?.byte_range()],
+            }),
+            "token_tree_pattern" => Some(Matcher::Group {
+                open: &source_code[child.child(0)?.byte_range()],
+                matchers: matchers(child, source_code)?,
+            }),
+            "token_repetition_pattern" => None,
+            _ => Some(Matcher::Token(&source_code[child.byte_range()])),
+        })
+        .collect()
+}
+
+/// Children of a token tree, without its delimiters and comments.
+fn token_trees(token_tree: Node<'_>) -> Vec<Node<'_>> {
+    let mut cursor = token_tree.walk();
+    let children: Vec<Node<'_>> = token_tree
+        .children(&mut cursor)
+        .filter(|child| !child.is_extra())
+        .collect();
+    match children.as_slice() {
+        [_, inner @ .., _] => inner.to_vec(),
+        _ => vec![],
+    }
+}
+
+fn is_group(node: Node<'_>) -> bool {
+    node.kind() == "token_tree"
+}
+
+/// Tokens bound to each metavariable, along with its fragment specifier.
+type Bindings<'a> = HashMap<&'a str, (Vec<Node<'a>>, &'a str)>;
+
+fn match_tokens<'a>(
+    matchers: &[Matcher<'a>],
+    tokens: &[Node<'a>],
+    source_code: &str,
+    bindings: &mut Bindings<'a>,
+) -> bool {
+    let text = |node: &Node<'_>| &source_code[node.byte_range()];
+    let mut position = 0;
+    for (index, matcher) in matchers.iter().enumerate() {
+        match matcher {
+            Matcher::Token(expected) => {
+                if !tokens
+                    .get(position)
+                    .is_some_and(|token| !is_group(*token) && text(token) == *expected)
+                {
+                    return false;
+                }
+                position += 1;
+            }
+            Matcher::Group { open, matchers } => {
+                let Some(group) = tokens
+                    .get(position)
+                    .filter(|token| is_group(**token))
+                    .filter(|token| {
+                        token
+                            .child(0)
+                            .is_some_and(|delimiter| text(&delimiter) == *open)
+                    })
+                else {
+                    return false;
+                };
+                if !match_tokens(matchers, &token_trees(*group), source_code, bindings) {
+                    return false;
+                }
+                position += 1;
+            }
+            Matcher::Binding { name, fragment } => {
+                let end = match (*fragment, matchers.get(index + 1)) {
+                    ("tt" | "ident" | "lifetime" | "literal" | "block", _) => position + 1,
+                    // Other fragments extend up to the next expected token, e.g. the comma in '$a:expr, $b:expr'
+                    (_, Some(Matcher::Token(next))) => match (position..tokens.len())
+                        .find(|&i| !is_group(tokens[i]) && text(&tokens[i]) == *next)
+                    {
+                        Some(end) => end,
+                        None => return false,
+                    },
+                    (_, Some(_)) => position + 1,
+                    (_, None) => tokens.len(),
+                };
+                if end <= position || end > tokens.len() {
+                    return false;
+                }
+                let first = tokens[position];
+                let valid = match *fragment {
+                    "ident" => first.kind() == "identifier",
+                    "lifetime" => first.kind() == "lifetime",
+                    "literal" => !is_group(first),
+                    "block" => is_group(first) && text(&first).starts_with('{'),
+                    _ => true,
+                };
+                if !valid {
+                    return false;
+                }
+                bindings.insert(*name, (tokens[position..end].to_vec(), *fragment));
+                position = end;
+            }
+        }
+    }
+    position == tokens.len()
+}
+
+fn expand(
+    invocation: Node<'_>,
+    transcriber: Node<'_>,
+    bindings: &Bindings<'_>,
+    source_code: &str,
+) -> Option<Expansion> {
+    let mut expansion = Expansion {
+        invocation: TreeSitterLocation::from_tree_sitter_node(invocation)
+            .to_sonar_location(source_code),
+        code: String::new(),
+        segments: vec![],
+    };
+    // Invocations at the item level are parsed as statements at the top of the file
+    let items = invocation
+        .parent()
+        .and_then(|parent| match parent.kind() {
+            "expression_statement" => parent.parent(),
+            _ => Some(parent),
+        })
+        .is_some_and(|parent| matches!(parent.kind(), "source_file" | "declaration_list"));
+    if !items {
+        expansion.push(WRAPPER_PREFIX, Origin::Wrapper);
+    }
+    if !transcribe(&mut expansion, transcriber, bindings, source_code) {
+        return None;
+    }
+    if !items {
+        expansion.push(WRAPPER_SUFFIX, Origin::Wrapper);
+    }
+    Some(expansion)
+}
+
+/// Appends the tokens of the transcriber, separated by spaces, with the metavariables replaced by their tokens.
+fn transcribe(
+    expansion: &mut Expansion,
+    transcriber: Node<'_>,
+    bindings: &Bindings<'_>,
+    source_code: &str,
+) -> bool {
+    for token in token_trees(transcriber) {
+        let text = &source_code[token.byte_range()];
+        match token.kind() {
+            "metavariable" if text == "$crate" => expansion.push("crate", Origin::Definition),
+            "metavariable" => {
+                let Some((tokens, fragment)) = bindings.get(text) else {
+                    return false;
+                };
+                let (Some(first), Some(last)) = (tokens.first(), tokens.last()) else {
+                    return false;
+                };
+                // Expressions are substituted as a whole, e.g. '$x * 2' multiplies the whole expression
+                let parenthesize = *fragment == "expr" && tokens.len() > 1;
+                if parenthesize {
+                    expansion.push("(", Origin::Definition);
+                }
+                expansion.push(
+                    &source_code[first.start_byte()..last.end_byte()],
+                    Origin::Argument(first.start_byte()),
+                );
+                if parenthesize {
+                    expansion.push(")", Origin::Definition);
+                }
+            }
+            "token_tree" => {
+                let (Some(open), Some(close)) =
+                    (token.child(0), token.child(token.child_count() - 1))
+                else {
+                    return false;
+                };
+                expansion.push(&source_code[open.byte_range()], Origin::Definition);
+                if !transcribe(expansion, token, bindings, source_code) {
+                    return false;
+                }
+                expansion.push(&source_code[close.byte_range()], Origin::Definition);
+            }
+            "token_repetition" => return false,
+            _ => expansion.push(text, Origin::Definition),
+        }
+        expansion.push(" ", Origin::Definition);
+    }
+    true
+}
+
+/// Byte offset of a Sonar line and column, i.e. the column counts UTF-16 code units.
+pub(crate) fn byte_offset(text: &str, line: usize, column: usize) -> usize {
+    let Some(&line_start) = line_starts(text).get(line.saturating_sub(1)) else {
+        return text.len();
+    };
+    let mut units = 0;
+    for (index, c) in text[line_start..].char_indices() {
+        if units >= column || c == '\n' || c == '\r' {
+            return line_start + index;
+        }
+        units += c.len_utf16();
+    }
+    text.len()
+}
+
+pub(crate) fn location_of(source_code: &str, range: Range<usize>) -> SonarLocation {
+    let line_starts = line_starts(source_code);
+    TreeSitterLocation {
+        start_byte: range.start,
+        end_byte: range.end,
+        start_position: point_of(&line_starts, range.start),
+        end_position: point_of(&line_starts, range.end),
+    }
+    .to_sonar_location(source_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::rule::default_parameters;
+
+    fn expanded_code(source_code: &str) -> Vec<String> {
+        let tree = parse_rust_code(source_code).unwrap();
+        expand_macros(&tree, source_code)
+            .into_iter()
+            .map(|expansion| expansion.code)
+            .collect()
+    }
+
+    #[test]
+    fn test_expand_macros() {
+        let source_code = r#"
+macro_rules! square {
+    ($x:ident) => { $x * $x };
+    ($x:expr) => { $x * $x };
+}
+macro_rules! pair {
+    ($a:expr, $b:expr) => { ($a, $b) };
+}
+macro_rules! repeated {
+    ($($x:expr),*) => { $( f($x); )* };
+}
+
+fn main() {
+    square!(a);
+    square!(a + 1);
+    pair!(f(1, 2), [3]);
+    repeated!(1, 2);
+    unknown!(a);
+}
+"#;
+        assert_eq!(
+            expanded_code(source_code),
+            vec![
+                format!("{}a * a {}", WRAPPER_PREFIX, WRAPPER_SUFFIX),
+                format!("{}(a + 1) * (a + 1) {}", WRAPPER_PREFIX, WRAPPER_SUFFIX),
+                format!("{}((f(1, 2)) , [3] ) {}", WRAPPER_PREFIX, WRAPPER_SUFFIX),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_expand_items() {
+        let source_code = r#"
+macro_rules! getter {
+    ($name:ident, $type:ty) => {
+        fn $name(&self) -> $type { $crate::get() }
+    };
+}
+
+impl Point {
+    getter!(x, i32);
+}
+"#;
+        assert_eq!(
+            expanded_code(source_code),
+            vec!["fn x (& self ) -> i32 {crate :: get () } "]
+        );
+    }
+
+    #[test]
+    fn test_source_location() {
+        let source_code = "macro_rules! m { ($x:expr) => { $x + 1 }; }\nfn f() { m!(a * b); }\n";
+        let tree = parse_rust_code(source_code).unwrap();
+        let expansions = expand_macros(&tree, source_code);
+        let expansion = &expansions[0];
+        assert_eq!(
+            expansion.code,
+            format!("{}(a * b) + 1 {}", WRAPPER_PREFIX, WRAPPER_SUFFIX)
+        );
+
+        let location = |start_line, start_column, end_line, end_column| SonarLocation {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        };
+        // 'b' is mapped to its own location
+        assert_eq!(
+            expansion.source_location(&location(2, 5, 2, 6), source_code),
+            Some(location(2, 16, 2, 17))
+        );
+        // '1' comes from the definition
+        assert_eq!(
+            expansion.source_location(&location(2, 10, 2, 11), source_code),
+            Some(location(2, 9, 2, 18))
+        );
+        // The wrapper function is not reported
+        assert_eq!(
+            expansion.source_location(&location(1, 3, 1, 14), source_code),
+            None
+        );
+    }
+
+    #[test]
+    fn test_find_expanded_issues() {
+        let source_code = r#"
+macro_rules! checked {
+    ($name:ident) => {
+        fn $name(a: bool, b: bool, c: bool) {
+            if a && b || c {
+                run();
+                if b {
+                    run();
+                    if c { run() }
+                }
+            }
+        }
+    };
+}
+
+checked!(complex);
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let mut parameters = default_parameters();
+        parameters.insert("S3776:threshold".to_string(), "3".to_string());
+
+        let issues = find_expanded_issues(&tree, source_code, &parameters).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule_key, "S3776");
+        // Reported on the name passed to the macro
+        assert_eq!(
+            issues[0].location,
+            SonarLocation {
+                start_line: 16,
+                start_column: 9,
+                end_line: 16,
+                end_column: 16,
+            }
+        );
+        // The increments are secondary locations within the macro, reported at the invocation
+        assert!(issues[0]
+            .secondary_locations
+            .iter()
+            .all(|secondary| secondary.location.start_line == 16));
+    }
+}
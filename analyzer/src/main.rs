@@ -14,65 +14,140 @@
  * You should have received a copy of the Sonar Source-Available License
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
-mod analyze;
-mod issue;
-mod rules {
-    pub mod cognitive_complexity_check;
-    pub mod parsing_error_check;
-    pub mod rule;
-}
-mod tree;
-mod visitors {
-    pub mod cognitive_complexity;
-    pub mod cpd;
-    pub mod cyclomatic_complexity;
-    pub mod highlight;
-    pub mod metrics;
-}
-
-use analyze::analyze;
+use analyzer::{
+    analyze::{analyze_bytes, ProjectAnalysis},
+    cli,
+    config::project_parameters,
+    issue::Issue,
+    logging::{LogLevel, LogRecord, Logger},
+    options::file_parameters,
+    protocol::{check_protocol_version, PROTOCOL_VERSION},
+    tree::{catching_panic, AnalyzerError, SonarLocation},
+};
 use std::{
-    collections::HashMap,
+    collections::{BTreeSet, HashMap},
     io::{self, Read, Write},
 };
-use tree::{AnalyzerError, SonarLocation};
 
 fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if !args.is_empty() {
+        std::process::exit(cli::run(&args));
+    }
+
+    // The panics of the steps and rules are reported as warnings of the file, their details are only logged for
+    // debugging, while any other panic aborts the analysis
+    std::panic::set_hook(Box::new(|info| {
+        let level = if catching_panic() { "debug" } else { "error" };
+        eprintln!("{} {}", level, info.to_string().replace('\n', " "))
+    }));
+
     if read_string() != "sonar" {
         return;
     }
-    let parameters = read_map();
+    // The plugin reads the version of the analyzer even when it is not the expected one, to report the mismatch
+    let expected_version = read_i32();
+    write_string("version");
+    write_int(PROTOCOL_VERSION);
+    // Unlike the strings, the integers are not flushed, and the plugin waits for the version before sending anything else
+    io::stdout().flush().expect("flush stdout");
+    if let Err(err) = check_protocol_version(expected_version) {
+        eprintln!("error {}", err);
+        return;
+    }
+    let parameters = match project_parameters(&read_map()) {
+        Ok(parameters) => parameters,
+        Err(err) => {
+            eprintln!("error {}", err);
+            return;
+        }
+    };
+    let mut logger = match Logger::from_parameters(&parameters) {
+        Ok(logger) => logger,
+        Err(err) => {
+            eprintln!("error {}", err);
+            return;
+        }
+    };
 
+    let mut project = ProjectAnalysis::default();
     loop {
         let command = read_string();
+        if command == "project" {
+            // Sent once all the files are analyzed, for the rules which depend on all of them
+            match project.issues(&parameters) {
+                Ok(output) => {
+                    for (path, message) in &output.warnings {
+                        logger.log(&LogRecord::new(LogLevel::Warn, message).with_file(path));
+                    }
+                    for (path, issue) in &output.issues {
+                        write_string("project_issue");
+                        write_string(path);
+                        write_issue(issue, true);
+                    }
+                }
+                Err(err) => logger.log(&LogRecord::new(LogLevel::Error, err.to_string())),
+            }
+            write_string("end");
+            continue;
+        }
         if command != "analyze" {
             return;
         }
 
+        let path = read_string();
         let len = read_i32();
         let mut buf = vec![0u8; len as usize];
         io::stdin().read_exact(&mut buf).expect("read from stdin");
+        logger.log(&LogRecord::new(LogLevel::Debug, "analyzing file").with_file(&path));
 
-        let source_code = std::str::from_utf8(&buf).expect("UTF-8 conversion error");
-
-        let output = match analyze(source_code, &parameters) {
+        let file_parameters = file_parameters(&parameters, &path);
+        let output = match analyze_bytes(&buf, &file_parameters) {
             Ok(output) => output,
-            Err(AnalyzerError::FileError(message)) => {
-                eprintln!("warn {}", message);
-                continue;
-            }
             Err(AnalyzerError::GlobalError(message)) => {
-                eprintln!("error {}", message);
+                logger.log(&LogRecord::new(LogLevel::Error, message).with_file(&path));
                 return;
             }
+            Err(err) => {
+                // The plugin waits for the end of the results of every file, even when there are none
+                logger.log(&LogRecord::new(LogLevel::Warn, err.to_string()).with_file(&path));
+                write_string("end");
+                continue;
+            }
         };
 
+        if let Err(err) = project.add_file(&path, &buf, &file_parameters) {
+            logger.log(&LogRecord::new(LogLevel::Error, err.to_string()).with_file(&path));
+            return;
+        }
+
+        for warning in &output.warnings {
+            for record in LogRecord::from_warning(&path, warning) {
+                logger.log(&record);
+            }
+        }
+        if let Some(telemetry) = &output.telemetry {
+            logger.log(
+                &LogRecord::new(LogLevel::Debug, format!("telemetry {}", telemetry))
+                    .with_file(&path),
+            );
+        }
+
         for token in &output.highlight_tokens {
             write_string("highlight");
             write_string(token.token_type.to_sonar_api_name());
             write_location(&token.location);
         }
 
+        for symbol in &output.symbols {
+            write_string("symbol");
+            write_location(&symbol.declaration);
+            write_int(symbol.references.len() as i32);
+            for reference in &symbol.references {
+                write_location(reference);
+            }
+        }
+
         write_string("metrics");
         write_int(output.metrics.ncloc);
         write_int(output.metrics.comment_lines);
@@ -81,6 +156,26 @@ fn main() {
         write_int(output.metrics.classes);
         write_int(output.metrics.cognitive_complexity);
         write_int(output.metrics.cyclomatic_complexity);
+        write_int(output.metrics.public_api);
+        write_int(output.metrics.public_documented_api);
+        write_int(i32::from(output.test_file));
+
+        write_string("function_complexity_distribution");
+        write_int(output.metrics.function_complexity_distribution.len() as i32);
+        for (lower_bound, functions) in &output.metrics.function_complexity_distribution {
+            write_int(*lower_bound);
+            write_int(*functions);
+        }
+
+        write_lines("ncloc_data", &output.metrics.ncloc_data);
+        write_lines("comment_lines_data", &output.metrics.comment_lines_data);
+        write_lines("executable_lines", &output.metrics.executable_lines);
+
+        for test in &output.test_functions {
+            write_string("test");
+            write_string(&test.name);
+            write_location(&test.location);
+        }
 
         for token in &output.cpd_tokens {
             write_string("cpd");
@@ -90,20 +185,43 @@ fn main() {
 
         for issue in &output.issues {
             write_string("issue");
-            write_string(&issue.rule_key);
-            write_string(&issue.message);
-            write_location(&issue.location);
-            write_int(issue.secondary_locations.len() as i32);
-            for secondary in &issue.secondary_locations {
-                write_string(&secondary.message);
-                write_location(&secondary.location);
-            }
+            write_issue(issue, false);
         }
 
         write_string("end");
     }
 }
 
+/// Writes the fields of an issue. The secondary locations of the project-level issues may be in other files, whose
+/// paths precede them, empty for the file of the issue.
+fn write_issue(issue: &Issue, secondary_files: bool) {
+    write_string(&issue.rule_key);
+    write_string(&issue.message);
+    // Empty for the default severity of the rule
+    write_string(
+        issue
+            .severity
+            .as_ref()
+            .map_or("", |severity| severity.to_sonar_api_name()),
+    );
+    // Empty for the issues which are not security hotspots
+    write_string(
+        issue
+            .hotspot
+            .as_ref()
+            .map_or("", |status| status.to_sonar_api_name()),
+    );
+    write_location(&issue.location);
+    write_int(issue.secondary_locations.len() as i32);
+    for secondary in &issue.secondary_locations {
+        if secondary_files {
+            write_string(secondary.file.as_deref().unwrap_or_default());
+        }
+        write_string(&secondary.message);
+        write_location(&secondary.location);
+    }
+}
+
 fn read_i32() -> i32 {
     // Read an i32 from stdin
     let mut buf = [0u8; 4];
@@ -144,6 +262,14 @@ fn write_string(value: &str) {
     io::stdout().flush().expect("flush stdout");
 }
 
+fn write_lines(message: &str, lines: &BTreeSet<usize>) {
+    write_string(message);
+    write_int(lines.len() as i32);
+    for line in lines {
+        write_int(*line as i32);
+    }
+}
+
 fn write_location(location: &SonarLocation) {
     write_int(location.start_line as i32);
     write_int(location.start_column as i32);
@@ -0,0 +1,749 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    fingerprint::fingerprint_toml_issues,
+    issue::{severity_override, Issue, SecondaryLocation},
+    options::AnalysisOptions,
+    rule_description::RuleDescription,
+    toml::{read_value, split_key, split_key_value, strip_comment, Value},
+    tree::{AnalyzerError, SonarLocation},
+};
+use std::collections::HashMap;
+
+/// Name of the manifest of the Cargo packages.
+pub const MANIFEST_FILE_NAME: &str = "Cargo.toml";
+
+const WILDCARD_VERSION_KEY: &str = "S7469";
+const DUPLICATE_DEPENDENCY_KEY: &str = "S7470";
+const PACKAGE_METADATA_KEY: &str = "S7471";
+const GIT_BRANCH_KEY: &str = "S7472";
+
+/// Keys of the rules of the manifest, which are not rules of the Rust code (see `all_rules`).
+pub const MANIFEST_RULE_KEYS: [&str; 4] = [
+    WILDCARD_VERSION_KEY,
+    DUPLICATE_DEPENDENCY_KEY,
+    PACKAGE_METADATA_KEY,
+    GIT_BRANCH_KEY,
+];
+
+/// Descriptions of the rules of the manifest, see `MANIFEST_RULE_KEYS`.
+pub const MANIFEST_RULE_DESCRIPTIONS: [RuleDescription; 4] = [
+    RuleDescription {
+        key: WILDCARD_VERSION_KEY,
+        title: "Dependencies should not have wildcard version requirements",
+        markdown: r#"
+This rule raises an issue when a dependency of a `Cargo.toml` manifest has a version requirement with a `*` wildcard.
+
+## Why is this an issue?
+
+A wildcard accepts any version of the dependency, including the future major versions which are not compatible with
+the code. The build can break, or change behavior, as soon as a new version is published. The crates.io registry
+rejects the packages having wildcard dependencies.
+
+## How to fix it
+
+Require the versions compatible with the one the code is tested with.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+[dependencies]
+serde = "*" # Noncompliant
+```
+
+#### Compliant solution
+
+```compliant
+[dependencies]
+serde = "1.0"
+```
+
+## Resources
+
+### Documentation
+
+- The Cargo Book - [Specifying
+  Dependencies](https://doc.rust-lang.org/cargo/reference/specifying-dependencies.html)
+"#,
+    },
+    RuleDescription {
+        key: DUPLICATE_DEPENDENCY_KEY,
+        title: "Dependencies should not be declared in several sections",
+        markdown: r#"
+This rule raises an issue when a dependency of a `Cargo.toml` manifest is declared again in another dependency section
+for the same target, e.g. both in `[dependencies]` and `[dev-dependencies]`.
+
+## Why is this an issue?
+
+The normal dependencies of a package are already available to its tests, examples and build script. Declaring them again
+is redundant, and the two declarations easily diverge: their versions and features are unified by Cargo, so changing
+one of them has surprising effects.
+
+The dependencies inherited from the workspace with `workspace = true` are not reported.
+
+## How to fix it
+
+Remove the additional declaration, and enable the features needed by the tests on the normal dependency, or declare the
+dependency only in the section it is needed in.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+[dependencies]
+tokio = { version = "1", features = ["rt"] }
+
+[dev-dependencies]
+tokio = { version = "1", features = ["rt", "macros"] } # Noncompliant
+```
+
+#### Compliant solution
+
+```compliant
+[dependencies]
+tokio = { version = "1", features = ["rt", "macros"] }
+```
+"#,
+    },
+    RuleDescription {
+        key: PACKAGE_METADATA_KEY,
+        title: "Publishable packages should have a license and a description",
+        markdown: r#"
+This rule raises an issue when the `[package]` table of a `Cargo.toml` manifest has no `license` or `license-file`
+field, or no `description` field, although the package can be published.
+
+## Why is this an issue?
+
+The crates.io registry rejects the packages without a license or a description. Without a license, the users of the
+package cannot know whether they are allowed to use it at all.
+
+The packages whose `publish` field is `false`, or an empty list of registries, are not reported.
+
+## How to fix it
+
+Add the missing fields, or mark the package as not publishable.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+[package] # Noncompliant
+name = "checksum"
+version = "0.1.0"
+```
+
+#### Compliant solution
+
+```compliant
+[package]
+name = "checksum"
+version = "0.1.0"
+description = "Checksums of byte buffers"
+license = "MIT OR Apache-2.0"
+```
+
+## Resources
+
+### Documentation
+
+- The Cargo Book - [The Manifest Format](https://doc.rust-lang.org/cargo/reference/manifest.html)
+"#,
+    },
+    RuleDescription {
+        key: GIT_BRANCH_KEY,
+        title: "Git dependencies should be pinned to a revision or a tag",
+        markdown: r#"
+This rule raises an issue when a git dependency of a `Cargo.toml` manifest refers to a branch.
+
+## Why is this an issue?
+
+A branch moves: the code which is built depends on when `Cargo.lock` was last updated, and a commit pushed to the
+branch can break the build or introduce malicious code. A revision or a tag identifies the code of the dependency which
+was reviewed and tested.
+
+## How to fix it
+
+Refer to a tag, or to the revision of the commit.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+[dependencies]
+parser = { git = "https://github.com/example/parser", branch = "main" } # Noncompliant
+```
+
+#### Compliant solution
+
+```compliant
+[dependencies]
+parser = { git = "https://github.com/example/parser", tag = "v1.2.0" }
+```
+"#,
+    },
+];
+
+/// A dependency declared in one of the dependency tables of the manifest.
+#[derive(Debug, PartialEq, Clone)]
+pub struct Dependency {
+    /// Header of the table declaring the dependency, e.g. 'dev-dependencies' or "target.'cfg(unix)'.dependencies".
+    pub section: String,
+    /// Name of the dependency, which is the name of the package unless it is renamed with the 'package' field.
+    pub name: String,
+    /// Fields of the dependency, a plain version requirement being the 'version' field.
+    pub fields: HashMap<String, Value>,
+    /// Location of the name of the dependency.
+    pub location: SonarLocation,
+}
+
+impl Dependency {
+    fn field(&self, name: &str) -> Option<&str> {
+        self.fields.get(name).and_then(Value::as_str)
+    }
+
+    pub(crate) fn package(&self) -> &str {
+        self.field("package").unwrap_or(&self.name)
+    }
+
+    /// Platform of the 'target' tables, `None` for the tables of all platforms.
+    fn target(&self) -> Option<&str> {
+        self.section
+            .strip_prefix("target.")
+            .and_then(|section| section.rsplit_once('.'))
+            .map(|(target, _)| target)
+    }
+
+    fn is_inherited(&self) -> bool {
+        self.fields.get("workspace") == Some(&Value::Boolean(true))
+            || self.section == "workspace.dependencies"
+    }
+}
+
+/// The parts of a `Cargo.toml` manifest checked by the rules of the manifest.
+///
+/// Only the subset of TOML of the `toml` module is supported.
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Manifest {
+    /// Location of the `[package]` header, if any.
+    pub package: Option<SonarLocation>,
+    /// Fields of the `[package]` table, a dotted key such as `license.workspace` being a field of its first part.
+    pub package_fields: HashMap<String, Value>,
+    /// Dependencies in declaration order.
+    pub dependencies: Vec<Dependency>,
+}
+
+impl Manifest {
+    pub fn parse(text: &str) -> Result<Self, AnalyzerError> {
+        let mut manifest = Manifest::default();
+        let mut table: Vec<String> = vec![];
+        let mut header = String::new();
+        let mut lines = text.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let error = |message: &str| {
+                AnalyzerError::FileError(format!(
+                    "invalid {} at line {}: {}",
+                    MANIFEST_FILE_NAME,
+                    index + 1,
+                    message
+                ))
+            };
+            let content = line.trim();
+            if content.is_empty() || content.starts_with('#') {
+                continue;
+            }
+
+            if content.starts_with('[') {
+                let content = strip_comment(content).trim();
+                let inner = content
+                    .strip_prefix("[[")
+                    .and_then(|inner| inner.strip_suffix("]]"))
+                    .or_else(|| {
+                        content
+                            .strip_prefix('[')
+                            .and_then(|inner| inner.strip_suffix(']'))
+                    })
+                    .ok_or_else(|| error("unclosed table header"))?;
+                header = inner.trim().to_string();
+                table = split_key(inner).map_err(|message| error(&message))?;
+                let location = line_location(line, index, inner.trim());
+                if table == ["package"] {
+                    manifest.package = Some(location.clone());
+                }
+                // `[dependencies.<name>]` tables declare a single dependency
+                if let [parent @ .., name] = table.as_slice() {
+                    if is_dependency_table(parent) {
+                        manifest.dependencies.push(Dependency {
+                            section: parent_section(&header),
+                            name: name.clone(),
+                            fields: HashMap::new(),
+                            location,
+                        });
+                    }
+                }
+                continue;
+            }
+
+            let (key, value) =
+                split_key_value(content).ok_or_else(|| error("expected 'key = value'"))?;
+            let value = read_value(value, &mut lines).map_err(|message| error(&message))?;
+            let key_path = split_key(key).map_err(|message| error(&message))?;
+
+            if table == ["package"] {
+                manifest.package_fields.insert(key_path[0].clone(), value);
+            } else if is_dependency_table(&table) {
+                let location = line_location(line, index, key.trim());
+                let fields = match (&key_path[1..], value) {
+                    ([], Value::Table(fields)) => fields.into_iter().collect(),
+                    ([], value) => HashMap::from([("version".to_string(), value)]),
+                    ([field], value) => HashMap::from([(field.clone(), value)]),
+                    _ => return Err(error("unsupported dependency key")),
+                };
+                let section = section_header(&header);
+                match manifest.dependencies.iter_mut().find(|dependency| {
+                    dependency.section == section && dependency.name == key_path[0]
+                }) {
+                    Some(dependency) => dependency.fields.extend(fields),
+                    None => manifest.dependencies.push(Dependency {
+                        section,
+                        name: key_path[0].clone(),
+                        fields,
+                        location,
+                    }),
+                }
+            } else if let [parent @ .., name] = table.as_slice() {
+                if is_dependency_table(parent) {
+                    let section = parent_section(&header);
+                    if let Some(dependency) =
+                        manifest.dependencies.iter_mut().rev().find(|dependency| {
+                            dependency.section == section && &dependency.name == name
+                        })
+                    {
+                        dependency.fields.insert(key_path[0].clone(), value);
+                    }
+                }
+            }
+        }
+        Ok(manifest)
+    }
+
+    /// Whether the package can be published to a registry, i.e. its 'publish' field is neither false nor empty.
+    fn is_publishable(&self) -> bool {
+        match self.package_fields.get("publish") {
+            Some(Value::Boolean(publish)) => *publish,
+            Some(Value::Array(registries)) => !registries.is_empty(),
+            _ => true,
+        }
+    }
+}
+
+/// Runs the rules of the manifest but the ones of the 'disabled_rules' setting.
+pub fn analyze_manifest(
+    text: &str,
+    parameters: &HashMap<String, String>,
+) -> Result<Vec<Issue>, AnalyzerError> {
+    let options = AnalysisOptions::from_parameters(parameters)?;
+    let manifest = Manifest::parse(text)?;
+    let mut issues = vec![];
+    for rule_key in MANIFEST_RULE_KEYS {
+        if options.disabled_rules.iter().any(|key| key == rule_key) {
+            continue;
+        }
+        let severity = severity_override(parameters, rule_key)?;
+        let rule_issues = match rule_key {
+            WILDCARD_VERSION_KEY => wildcard_versions(&manifest),
+            DUPLICATE_DEPENDENCY_KEY => duplicate_dependencies(&manifest),
+            PACKAGE_METADATA_KEY => missing_package_metadata(&manifest),
+            _ => git_branches(&manifest),
+        };
+        issues.extend(
+            rule_issues
+                .into_iter()
+                .map(|issue| Issue { severity, ..issue }),
+        );
+    }
+    fingerprint_toml_issues(&mut issues, text);
+    Ok(issues)
+}
+
+fn wildcard_versions(manifest: &Manifest) -> Vec<Issue> {
+    manifest
+        .dependencies
+        .iter()
+        .filter(|dependency| {
+            dependency
+                .field("version")
+                .is_some_and(|version| version.contains('*'))
+        })
+        .map(|dependency| {
+            issue(
+                WILDCARD_VERSION_KEY,
+                format!(
+                    "Replace the wildcard version requirement of \"{}\" by a version range.",
+                    dependency.name
+                ),
+                &dependency.location,
+            )
+        })
+        .collect()
+}
+
+fn duplicate_dependencies(manifest: &Manifest) -> Vec<Issue> {
+    let mut issues = vec![];
+    let dependencies: Vec<&Dependency> = manifest
+        .dependencies
+        .iter()
+        .filter(|dependency| !dependency.is_inherited())
+        .collect();
+    for (index, dependency) in dependencies.iter().enumerate() {
+        let first = dependencies[..index].iter().find(|previous| {
+            previous.package() == dependency.package()
+                && previous.target() == dependency.target()
+                && previous.section != dependency.section
+        });
+        if let Some(first) = first {
+            let mut duplicate = issue(
+                DUPLICATE_DEPENDENCY_KEY,
+                format!(
+                    "Remove this duplicate declaration of \"{}\", it is already declared in [{}].",
+                    dependency.package(),
+                    first.section
+                ),
+                &dependency.location,
+            );
+            duplicate.secondary_locations.push(SecondaryLocation {
+                message: "First declaration".to_string(),
+                location: first.location.clone(),
+                file: None,
+            });
+            issues.push(duplicate);
+        }
+    }
+    issues
+}
+
+fn missing_package_metadata(manifest: &Manifest) -> Vec<Issue> {
+    let Some(package) = &manifest.package else {
+        return vec![];
+    };
+    if !manifest.is_publishable() {
+        return vec![];
+    }
+    let has_field = |name: &str| manifest.package_fields.contains_key(name);
+    let mut issues = vec![];
+    if !has_field("license") && !has_field("license-file") {
+        issues.push(issue(
+            PACKAGE_METADATA_KEY,
+            "Add a \"license\" or \"license-file\" field to this publishable package.".to_string(),
+            package,
+        ));
+    }
+    if !has_field("description") {
+        issues.push(issue(
+            PACKAGE_METADATA_KEY,
+            "Add a \"description\" field to this publishable package.".to_string(),
+            package,
+        ));
+    }
+    issues
+}
+
+fn git_branches(manifest: &Manifest) -> Vec<Issue> {
+    manifest
+        .dependencies
+        .iter()
+        .filter(|dependency| dependency.field("git").is_some())
+        .filter_map(|dependency| {
+            dependency.field("branch").map(|branch| {
+                issue(
+                    GIT_BRANCH_KEY,
+                    format!(
+                        "Pin this git dependency to a \"rev\" or a \"tag\" instead of the \"{}\" branch.",
+                        branch
+                    ),
+                    &dependency.location,
+                )
+            })
+        })
+        .collect()
+}
+
+fn issue(rule_key: &str, message: String, location: &SonarLocation) -> Issue {
+    Issue {
+        rule_key: rule_key.to_string(),
+        message,
+        location: location.clone(),
+        secondary_locations: vec![],
+        severity: None,
+        quick_fixes: vec![],
+        fingerprint: None,
+        hotspot: None,
+    }
+}
+
+/// Whether the table declares dependencies: `[dependencies]`, `[target.<platform>.dependencies]`,
+/// `[workspace.dependencies]`, and the same for the 'dev' and 'build' dependencies.
+fn is_dependency_table(table: &[String]) -> bool {
+    let is_section = |name: &str| {
+        matches!(
+            name,
+            "dependencies"
+                | "dev-dependencies"
+                | "build-dependencies"
+                | "dev_dependencies"
+                | "build_dependencies"
+        )
+    };
+    match table {
+        [section] => is_section(section),
+        [target, _, section] => target == "target" && is_section(section),
+        [workspace, section] => workspace == "workspace" && section == "dependencies",
+        _ => false,
+    }
+}
+
+/// Header of a table without the spaces around its dots.
+fn section_header(header: &str) -> String {
+    let mut parts = vec![];
+    let mut start = 0;
+    let mut quote = None;
+    for (index, c) in header.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '.') => {
+                parts.push(header[start..index].trim());
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(header[start..].trim());
+    parts.join(".")
+}
+
+/// Header of the table containing the table of the header, e.g. 'dependencies' for 'dependencies.serde'.
+fn parent_section(header: &str) -> String {
+    let section = section_header(header);
+    match section.rsplit_once('.') {
+        Some((parent, _)) => parent.to_string(),
+        None => section,
+    }
+}
+
+/// Location of the first occurrence of the text in the line, the columns counting UTF-16 code units.
+pub(crate) fn line_location(line: &str, index: usize, text: &str) -> SonarLocation {
+    let start = line.find(text).unwrap_or_default();
+    let start_column = line[..start].encode_utf16().count();
+    SonarLocation {
+        start_line: index + 1,
+        start_column,
+        end_line: index + 1,
+        end_column: start_column + text.encode_utf16().count(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn issues(text: &str) -> Vec<(String, usize, usize, String)> {
+        analyze_manifest(text, &HashMap::new())
+            .unwrap()
+            .into_iter()
+            .map(|issue| {
+                (
+                    issue.rule_key,
+                    issue.location.start_line,
+                    issue.location.start_column,
+                    issue.message,
+                )
+            })
+            .collect()
+    }
+
+    const MANIFEST: &str = r#"
+[package]
+name = "foo"
+version = "0.1.0"
+description = """
+A multi-line
+description."""
+license.workspace = true
+keywords = [
+    "parser", # comment
+    "rust",
+]
+
+[dependencies]
+regex = "1"
+serde = { version = "*", features = ["derive"] }
+log = { git = "https://github.com/rust-lang/log", branch = "master" }
+rand = { workspace = true }
+
+[dependencies.tokio]
+version = "1.*"
+features = ["full"]
+
+[dev-dependencies]
+regex = "1.10"
+new_rand = { package = "rand", version = "0.9" }
+
+[target.'cfg(unix)'.dependencies]
+regex = "1"
+
+[build-dependencies]
+cc = { git = "https://github.com/rust-lang/cc-rs", rev = "abc123" }
+"#;
+
+    #[test]
+    fn test_parse() {
+        let manifest = Manifest::parse(MANIFEST).unwrap();
+        assert_eq!(
+            manifest.package_fields.get("description"),
+            Some(&Value::String("A multi-line\ndescription.".to_string()))
+        );
+        assert_eq!(
+            manifest.package_fields.get("keywords"),
+            Some(&Value::Array(vec![
+                Value::String("parser".to_string()),
+                Value::String("rust".to_string())
+            ]))
+        );
+        assert!(manifest.package_fields.contains_key("license"));
+
+        let dependencies: Vec<(&str, &str, Option<&str>)> = manifest
+            .dependencies
+            .iter()
+            .map(|dependency| {
+                (
+                    dependency.section.as_str(),
+                    dependency.name.as_str(),
+                    dependency.field("version"),
+                )
+            })
+            .collect();
+        assert_eq!(
+            dependencies,
+            vec![
+                ("dependencies", "regex", Some("1")),
+                ("dependencies", "serde", Some("*")),
+                ("dependencies", "log", None),
+                ("dependencies", "rand", None),
+                ("dependencies", "tokio", Some("1.*")),
+                ("dev-dependencies", "regex", Some("1.10")),
+                ("dev-dependencies", "new_rand", Some("0.9")),
+                ("target.'cfg(unix)'.dependencies", "regex", Some("1")),
+                ("build-dependencies", "cc", None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_issues() {
+        // The inherited 'rand' and the 'regex' of the unix platform are not duplicates
+        assert_eq!(
+            issues(MANIFEST),
+            vec![
+                (
+                    "S7469".to_string(),
+                    16,
+                    0,
+                    "Replace the wildcard version requirement of \"serde\" by a version range.".to_string()
+                ),
+                (
+                    "S7469".to_string(),
+                    20,
+                    1,
+                    "Replace the wildcard version requirement of \"tokio\" by a version range.".to_string()
+                ),
+                (
+                    "S7470".to_string(),
+                    25,
+                    0,
+                    "Remove this duplicate declaration of \"regex\", it is already declared in [dependencies].".to_string()
+                ),
+                (
+                    "S7472".to_string(),
+                    17,
+                    0,
+                    "Pin this git dependency to a \"rev\" or a \"tag\" instead of the \"master\" branch.".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_package_metadata() {
+        assert_eq!(
+            issues("[package]\nname = \"foo\"\n"),
+            vec![
+                (
+                    "S7471".to_string(),
+                    1,
+                    1,
+                    "Add a \"license\" or \"license-file\" field to this publishable package."
+                        .to_string()
+                ),
+                (
+                    "S7471".to_string(),
+                    1,
+                    1,
+                    "Add a \"description\" field to this publishable package.".to_string()
+                ),
+            ]
+        );
+        assert!(issues("[package]\nname = \"foo\"\npublish = false\n").is_empty());
+        assert!(issues("[package]\nname = \"foo\"\npublish = []\n").is_empty());
+        assert!(issues("[workspace]\nmembers = [\"foo\"]\n").is_empty());
+    }
+
+    #[test]
+    fn test_disabled_rules_and_severities() {
+        let parameters = HashMap::from([
+            (
+                "disabled_rules".to_string(),
+                "S7469,S7470,S7471".to_string(),
+            ),
+            ("S7472:severity".to_string(), "CRITICAL".to_string()),
+        ]);
+        let issues = analyze_manifest(MANIFEST, &parameters).unwrap();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].rule_key, "S7472");
+        assert_eq!(issues[0].severity, Some(crate::issue::Severity::Critical));
+    }
+
+    #[test]
+    fn test_invalid_manifest() {
+        assert!(matches!(
+            Manifest::parse("[package\nname = \"foo\"\n"),
+            Err(AnalyzerError::FileError(message)) if message == "invalid Cargo.toml at line 1: unclosed table header"
+        ));
+        assert!(matches!(
+            Manifest::parse("[package]\nname = \"foo\n"),
+            Err(AnalyzerError::FileError(message)) if message == "invalid Cargo.toml at line 2: unclosed string"
+        ));
+        assert!(matches!(
+            Manifest::parse("[dependencies]\nserde = { version = \"1\"\n"),
+            Err(AnalyzerError::FileError(message)) if message.starts_with("invalid Cargo.toml at line 2")
+        ));
+    }
+}
@@ -0,0 +1,437 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    cargo_metadata::{TargetKind, IDENTIFIED_TARGET_KINDS},
+    config::glob_matches,
+    encoding::Encoding,
+    formatting::RustfmtOptions,
+    tree::AnalyzerError,
+    visitors::{cpd::CpdOptions, generated_code::GeneratedCodeOptions, test_code::is_test_file},
+};
+use std::{collections::HashMap, fmt::Display, str::FromStr, time::Duration};
+
+/// Analysis settings, as opposed to rule parameters.
+///
+/// Both are read from the same parameters map, rule parameters are prefixed by their rule key (e.g. 'S3776:threshold')
+/// while analysis settings are not (e.g. 'skip_cpd').
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct AnalysisOptions {
+    /// Do not compute syntax highlighting, nor the symbols.
+    pub skip_highlighting: bool,
+    /// Do not compute CPD tokens.
+    pub skip_cpd: bool,
+    /// Settings of the CPD tokens, 'cpd_normalize_literals', 'cpd_exclude_test_code' and 'cpd_exclude_generated_code'
+    /// are true when absent, 'cpd_minimum_item_tokens' and 'cpd_minimum_item_lines' are 0.
+    pub cpd: CpdOptions,
+    /// Minimum number of tokens of the duplicated blocks found by `analyze_batch`, `DEFAULT_MINIMUM_TOKENS` when
+    /// absent.
+    pub cpd_minimum_tokens: Option<usize>,
+    /// Settings of the generated files, 'generated_code' is one of 'analyze', 'suppress' (the default) or 'skip',
+    /// 'generated_markers' is a comma-separated list.
+    pub generated_code: GeneratedCodeOptions,
+    /// The file matches one of the 'generated_paths' globs, see `file_parameters`.
+    pub generated_file: bool,
+    /// Do not compute metrics, the output then contains zeroed metrics.
+    pub skip_metrics: bool,
+    /// Also report the issues of the expansions of the macros defined in the file, see `find_expanded_issues`.
+    pub expand_macros: bool,
+    /// Also highlight the code blocks of the doc comments, and report the issues of the 'doctest_rules' in them, see
+    /// `find_doctests`.
+    pub analyze_doctests: bool,
+    /// Maximum duration of the analysis of a file, after which only the results computed so far are returned.
+    pub timeout: Option<Duration>,
+    /// Maximum size in bytes of the files to analyze, larger files are skipped.
+    pub max_file_size: Option<usize>,
+    /// Soft limit in bytes of the estimated memory of the analysis of a file, see `MemoryUsage`. Only the metrics of
+    /// the files exceeding it are computed.
+    pub memory_limit: Option<usize>,
+    /// Record the time spent in each step of the analysis, see `Telemetry`.
+    pub telemetry: bool,
+    /// Run the steps of the analysis of the file on separate threads, to shorten the analysis of very large files.
+    pub parallel_steps: bool,
+    /// The file only contains test code, see `is_test_file`.
+    pub test_file: bool,
+    /// Encoding of the bytes of the file, UTF-8 by default.
+    pub encoding: Encoding,
+    /// Keys of the rules which do not report issues, 'disabled_rules' is a comma-separated list.
+    pub disabled_rules: Vec<String>,
+    /// The file matches one of the 'exclusions' globs, see `file_parameters`. It is not analyzed.
+    pub excluded: bool,
+    /// Kind of the Cargo target of the file, from the paths of the targets (see `file_parameters`). The files of the
+    /// test and bench targets are test files, and the examples are not checked for duplications.
+    pub target_kind: Option<TargetKind>,
+    /// Report the files which differ from their formatting by rustfmt, see `check_formatting`. 'rustfmt' is the
+    /// command to run, 'rustfmt_edition' the edition of the code (2021 when absent).
+    pub rustfmt: Option<RustfmtOptions>,
+}
+
+impl AnalysisOptions {
+    pub fn from_parameters(parameters: &HashMap<String, String>) -> Result<Self, AnalyzerError> {
+        Ok(AnalysisOptions {
+            skip_highlighting: bool_parameter(parameters, "skip_highlighting")?,
+            skip_cpd: bool_parameter(parameters, "skip_cpd")?,
+            cpd: CpdOptions {
+                normalize_literals: parse_parameter(parameters, "cpd_normalize_literals")?
+                    .unwrap_or(true),
+                exclude_test_code: parse_parameter(parameters, "cpd_exclude_test_code")?
+                    .unwrap_or(true),
+                exclude_generated_code: parse_parameter(parameters, "cpd_exclude_generated_code")?
+                    .unwrap_or(true),
+                minimum_item_tokens: parse_parameter(parameters, "cpd_minimum_item_tokens")?
+                    .unwrap_or_default(),
+                minimum_item_lines: parse_parameter(parameters, "cpd_minimum_item_lines")?
+                    .unwrap_or_default(),
+            },
+            cpd_minimum_tokens: parse_parameter(parameters, "cpd_minimum_tokens")?,
+            generated_code: GeneratedCodeOptions {
+                mode: parse_parameter(parameters, "generated_code")?.unwrap_or_default(),
+                markers: match parameters.get("generated_markers") {
+                    Some(markers) => list_parameter(markers),
+                    None => GeneratedCodeOptions::default().markers,
+                },
+            },
+            generated_file: bool_parameter(parameters, "generated_file")?,
+            skip_metrics: bool_parameter(parameters, "skip_metrics")?,
+            expand_macros: bool_parameter(parameters, "expand_macros")?,
+            analyze_doctests: bool_parameter(parameters, "analyze_doctests")?,
+            timeout: parse_parameter::<u64>(parameters, "timeout_ms")?.map(Duration::from_millis),
+            max_file_size: parse_parameter(parameters, "max_file_size")?,
+            memory_limit: parse_parameter(parameters, "memory_limit")?,
+            telemetry: bool_parameter(parameters, "telemetry")?,
+            parallel_steps: bool_parameter(parameters, "parallel_steps")?,
+            test_file: bool_parameter(parameters, "test_file")?,
+            encoding: parse_parameter(parameters, "encoding")?.unwrap_or_default(),
+            disabled_rules: parameters
+                .get("disabled_rules")
+                .map(|rules| list_parameter(rules))
+                .unwrap_or_default(),
+            excluded: bool_parameter(parameters, "excluded")?,
+            target_kind: parse_parameter(parameters, "target_kind")?,
+            rustfmt: parameters.get("rustfmt").map(|command| RustfmtOptions {
+                command: command.clone(),
+                edition: parameters
+                    .get("rustfmt_edition")
+                    .cloned()
+                    .unwrap_or_else(|| "2021".to_string()),
+            }),
+        })
+    }
+}
+
+/// Parameters of the analysis of a single file, adding the settings which depend on its path (e.g. 'test_file').
+///
+/// The path is relative to the project directory, the 'exclusions', 'generated_paths' and target paths (e.g.
+/// 'test_paths') globs are matched against it. It is kept as the 'file_path' setting.
+pub fn file_parameters(
+    parameters: &HashMap<String, String>,
+    path: &str,
+) -> HashMap<String, String> {
+    let mut file_parameters = parameters.clone();
+    if is_test_file(path) {
+        file_parameters.insert("test_file".to_string(), "true".to_string());
+    }
+    let path = path.replace('\\', "/");
+    file_parameters.insert("file_path".to_string(), path.clone());
+    let matches = |key: &str| {
+        parameters.get(key).is_some_and(|globs| {
+            list_parameter(globs)
+                .iter()
+                .any(|glob| glob_matches(glob, &path))
+        })
+    };
+    if matches("exclusions") {
+        file_parameters.insert("excluded".to_string(), "true".to_string());
+    }
+    if matches("generated_paths") {
+        file_parameters.insert("generated_file".to_string(), "true".to_string());
+    }
+    if let Some(kind) = IDENTIFIED_TARGET_KINDS
+        .into_iter()
+        .find(|kind| matches(&kind.paths_parameter()))
+    {
+        file_parameters.insert("target_kind".to_string(), kind.to_string());
+        if kind.is_test() {
+            file_parameters.insert("test_file".to_string(), "true".to_string());
+        }
+    }
+    file_parameters
+}
+
+/// Reads a boolean parameter, which is false when absent.
+fn bool_parameter(parameters: &HashMap<String, String>, key: &str) -> Result<bool, AnalyzerError> {
+    Ok(parse_parameter(parameters, key)?.unwrap_or(false))
+}
+
+/// Splits a comma-separated list, ignoring blank elements.
+pub(crate) fn list_parameter(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|element| !element.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+fn parse_parameter<T>(
+    parameters: &HashMap<String, String>,
+    key: &str,
+) -> Result<Option<T>, AnalyzerError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parameters
+        .get(key)
+        .map(|value| {
+            value.parse::<T>().map_err(|err| {
+                AnalyzerError::GlobalError(format!("could not parse '{}' parameter: {}", key, err))
+            })
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::visitors::generated_code::GeneratedCodeMode;
+
+    #[test]
+    fn test_default_options() {
+        assert_eq!(
+            AnalysisOptions::from_parameters(&HashMap::new()).unwrap(),
+            AnalysisOptions::default()
+        );
+    }
+
+    #[test]
+    fn test_skip_options() {
+        let parameters = HashMap::from([
+            ("skip_highlighting".to_string(), "true".to_string()),
+            ("skip_cpd".to_string(), "false".to_string()),
+            ("skip_metrics".to_string(), "true".to_string()),
+        ]);
+        assert_eq!(
+            AnalysisOptions::from_parameters(&parameters).unwrap(),
+            AnalysisOptions {
+                skip_highlighting: true,
+                skip_cpd: false,
+                cpd: CpdOptions::default(),
+                cpd_minimum_tokens: None,
+                generated_code: GeneratedCodeOptions::default(),
+                generated_file: false,
+                skip_metrics: true,
+                expand_macros: false,
+                analyze_doctests: false,
+                timeout: None,
+                max_file_size: None,
+                memory_limit: None,
+                telemetry: false,
+                parallel_steps: false,
+                test_file: false,
+                encoding: Encoding::Utf8,
+                disabled_rules: vec![],
+                excluded: false,
+                target_kind: None,
+                rustfmt: None,
+            }
+        );
+    }
+
+    #[test]
+    fn test_rustfmt_option() {
+        let mut parameters = HashMap::from([("rustfmt".to_string(), "rustfmt".to_string())]);
+        assert_eq!(
+            AnalysisOptions::from_parameters(&parameters)
+                .unwrap()
+                .rustfmt,
+            Some(RustfmtOptions {
+                command: "rustfmt".to_string(),
+                edition: "2021".to_string(),
+            })
+        );
+
+        parameters.insert("rustfmt_edition".to_string(), "2018".to_string());
+        assert_eq!(
+            AnalysisOptions::from_parameters(&parameters)
+                .unwrap()
+                .rustfmt
+                .unwrap()
+                .edition,
+            "2018"
+        );
+    }
+
+    #[test]
+    fn test_timeout_option() {
+        let parameters = HashMap::from([("timeout_ms".to_string(), "1500".to_string())]);
+        assert_eq!(
+            AnalysisOptions::from_parameters(&parameters)
+                .unwrap()
+                .timeout,
+            Some(Duration::from_millis(1500))
+        );
+
+        let parameters = HashMap::from([("timeout_ms".to_string(), "-1".to_string())]);
+        assert!(AnalysisOptions::from_parameters(&parameters).is_err());
+    }
+
+    #[test]
+    fn test_max_file_size_option() {
+        let parameters = HashMap::from([("max_file_size".to_string(), "1000000".to_string())]);
+        assert_eq!(
+            AnalysisOptions::from_parameters(&parameters)
+                .unwrap()
+                .max_file_size,
+            Some(1_000_000)
+        );
+    }
+
+    #[test]
+    fn test_encoding_option() {
+        let parameters = HashMap::from([("encoding".to_string(), "latin1".to_string())]);
+        assert_eq!(
+            AnalysisOptions::from_parameters(&parameters)
+                .unwrap()
+                .encoding,
+            Encoding::Latin1
+        );
+
+        let parameters = HashMap::from([("encoding".to_string(), "EBCDIC".to_string())]);
+        assert!(AnalysisOptions::from_parameters(&parameters).is_err());
+    }
+
+    #[test]
+    fn test_invalid_option() {
+        let parameters = HashMap::from([("skip_cpd".to_string(), "yes".to_string())]);
+        assert!(matches!(
+            AnalysisOptions::from_parameters(&parameters),
+            Err(AnalyzerError::GlobalError(message)) if message.starts_with("could not parse 'skip_cpd' parameter")
+        ));
+    }
+
+    #[test]
+    fn test_cpd_options() {
+        assert!(
+            AnalysisOptions::from_parameters(&HashMap::new())
+                .unwrap()
+                .cpd
+                .normalize_literals
+        );
+
+        let parameters = HashMap::from([
+            ("cpd_normalize_literals".to_string(), "false".to_string()),
+            ("cpd_exclude_test_code".to_string(), "false".to_string()),
+            (
+                "cpd_exclude_generated_code".to_string(),
+                "false".to_string(),
+            ),
+            ("cpd_minimum_item_tokens".to_string(), "20".to_string()),
+            ("cpd_minimum_item_lines".to_string(), "3".to_string()),
+        ]);
+        assert_eq!(
+            AnalysisOptions::from_parameters(&parameters).unwrap().cpd,
+            CpdOptions {
+                normalize_literals: false,
+                exclude_test_code: false,
+                exclude_generated_code: false,
+                minimum_item_tokens: 20,
+                minimum_item_lines: 3,
+            }
+        );
+    }
+
+    #[test]
+    fn test_generated_code_options() {
+        let parameters = HashMap::from([
+            ("generated_code".to_string(), "skip".to_string()),
+            (
+                "generated_markers".to_string(),
+                "DO NOT EDIT, ,@generated".to_string(),
+            ),
+        ]);
+        assert_eq!(
+            AnalysisOptions::from_parameters(&parameters)
+                .unwrap()
+                .generated_code,
+            GeneratedCodeOptions {
+                mode: GeneratedCodeMode::Skip,
+                markers: vec!["DO NOT EDIT".to_string(), "@generated".to_string()],
+            }
+        );
+
+        let parameters =
+            HashMap::from([("generated_paths".to_string(), "src/pb/*.rs".to_string())]);
+        assert!(
+            AnalysisOptions::from_parameters(&file_parameters(&parameters, "src/pb/foo.rs"))
+                .unwrap()
+                .generated_file
+        );
+    }
+
+    #[test]
+    fn test_file_parameters() {
+        let parameters = HashMap::from([("skip_cpd".to_string(), "true".to_string())]);
+
+        let options =
+            AnalysisOptions::from_parameters(&file_parameters(&parameters, "tests/it.rs"));
+        assert!(options.as_ref().unwrap().test_file);
+        assert!(options.unwrap().skip_cpd);
+
+        let options = AnalysisOptions::from_parameters(&file_parameters(&parameters, "src/lib.rs"));
+        assert!(!options.unwrap().test_file);
+    }
+
+    #[test]
+    fn test_target_kinds() {
+        let parameters = HashMap::from([
+            ("bench_paths".to_string(), "benches/perf.rs".to_string()),
+            (
+                "example_paths".to_string(),
+                "examples/demo/**,examples/demo/main.rs".to_string(),
+            ),
+        ]);
+        let options =
+            |path| AnalysisOptions::from_parameters(&file_parameters(&parameters, path)).unwrap();
+        assert_eq!(
+            options("benches/perf.rs").target_kind,
+            Some(TargetKind::Bench)
+        );
+        assert!(options("benches/perf.rs").test_file);
+        assert_eq!(
+            options("examples/demo/util.rs").target_kind,
+            Some(TargetKind::Example)
+        );
+        assert!(!options("examples/demo/util.rs").test_file);
+        assert_eq!(options("src/lib.rs").target_kind, None);
+    }
+
+    #[test]
+    fn test_exclusions() {
+        let parameters = HashMap::from([(
+            "exclusions".to_string(),
+            "src/generated/**,**/bindings.rs".to_string(),
+        )]);
+        let excluded = |path| {
+            AnalysisOptions::from_parameters(&file_parameters(&parameters, path))
+                .unwrap()
+                .excluded
+        };
+        assert!(excluded("src/generated/proto.rs"));
+        assert!(excluded("sys\\src\\bindings.rs"));
+        assert!(!excluded("src/lib.rs"));
+    }
+}
@@ -0,0 +1,320 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    advisories::VULNERABLE_DEPENDENCY_KEY,
+    formatting::FORMATTING_KEY,
+    manifest::MANIFEST_RULE_KEYS,
+    project_index::PROJECT_RULE_KEYS,
+    rules::rule::{all_rules, default_parameters},
+    tree::AnalyzerError,
+};
+use serde_json::Value;
+use std::{collections::HashMap, fs, path::Path};
+
+/// Key of the repository of the rules of the analyzer, as declared by the Sonar plugin.
+const REPOSITORY_KEY: &str = "rust";
+
+/// Rule activated by a quality profile, with the parameters it overrides.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ActiveRule {
+    pub key: String,
+    /// Severity of the issues of the rule in the profile, e.g. 'CRITICAL'.
+    pub severity: Option<String>,
+    pub parameters: Vec<(String, String)>,
+}
+
+/// Rules of the analyzer activated by a Sonar quality profile, read from a backup of the profile.
+///
+/// Backups are XML documents (see "Back up" in the quality profiles page), JSON documents of the same structure are
+/// also accepted:
+///
+/// ```json
+/// {"rules": [{"repositoryKey": "rust", "key": "S3776", "priority": "CRITICAL",
+///             "parameters": [{"key": "threshold", "value": "25"}]}]}
+/// ```
+///
+/// The rules of other repositories, e.g. Clippy, are ignored.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct QualityProfile {
+    pub rules: Vec<ActiveRule>,
+}
+
+impl QualityProfile {
+    pub fn parse(text: &str) -> Result<Self, AnalyzerError> {
+        let text = text.trim_start_matches('\u{feff}').trim_start();
+        let rules = if text.starts_with('<') {
+            parse_xml(text)?
+        } else {
+            parse_json(text)?
+        };
+        Ok(QualityProfile { rules })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, AnalyzerError> {
+        let text = fs::read_to_string(path).map_err(|err| {
+            AnalyzerError::GlobalError(format!("failed to read {}: {}", path.display(), err))
+        })?;
+        Self::parse(&text)
+    }
+
+    /// Parameters of the analysis with the rules of the profile: the other rules are disabled, and the severities and
+    /// parameters of the active rules override their defaults.
+    pub fn to_parameters(&self) -> Result<HashMap<String, String>, AnalyzerError> {
+        let disabled_rules: Vec<&str> = all_rule_keys()?
+            .into_iter()
+            .filter(|key| !self.rules.iter().any(|rule| rule.key == *key))
+            .collect();
+
+        let mut parameters =
+            HashMap::from([("disabled_rules".to_string(), disabled_rules.join(","))]);
+        for rule in &self.rules {
+            if let Some(severity) = &rule.severity {
+                parameters.insert(format!("{}:severity", rule.key), severity.clone());
+            }
+            for (key, value) in &rule.parameters {
+                parameters.insert(format!("{}:{}", rule.key, key), value.clone());
+            }
+        }
+        Ok(parameters)
+    }
+}
+
+/// Keys of all the rules of the analyzer, including the project-level rules, the rules of the manifest, the formatting
+/// check and the advisories of the dependencies, sorted.
+pub fn all_rule_keys() -> Result<Vec<&'static str>, AnalyzerError> {
+    let mut keys: Vec<&str> = all_rules(&default_parameters())?
+        .iter()
+        .map(|rule| rule.key())
+        .chain(PROJECT_RULE_KEYS)
+        .chain(MANIFEST_RULE_KEYS)
+        .chain([FORMATTING_KEY, VULNERABLE_DEPENDENCY_KEY])
+        .collect();
+    // The type-aware rules only exist in the builds with the `type-aware` feature
+    #[cfg(feature = "type-aware")]
+    keys.push(crate::type_aware::DISCARDED_RESULT_KEY);
+    keys.sort();
+    Ok(keys)
+}
+
+fn invalid(message: &str) -> AnalyzerError {
+    AnalyzerError::GlobalError(format!("invalid quality profile: {}", message))
+}
+
+fn parse_json(text: &str) -> Result<Vec<ActiveRule>, AnalyzerError> {
+    let json: Value = serde_json::from_str(text).map_err(|err| invalid(&err.to_string()))?;
+    let rules = json["rules"]
+        .as_array()
+        .ok_or_else(|| invalid("missing 'rules' array"))?;
+
+    let text = |value: &Value, name: &str| -> Result<String, AnalyzerError> {
+        match &value[name] {
+            Value::String(text) => Ok(text.clone()),
+            // Parameter values may be written as numbers or booleans
+            Value::Number(number) => Ok(number.to_string()),
+            Value::Bool(boolean) => Ok(boolean.to_string()),
+            _ => Err(invalid(&format!("missing '{}' of a rule", name))),
+        }
+    };
+    let mut active_rules = vec![];
+    for rule in rules {
+        if text(rule, "repositoryKey")? != REPOSITORY_KEY {
+            continue;
+        }
+        let mut parameters = vec![];
+        for parameter in rule["parameters"].as_array().into_iter().flatten() {
+            parameters.push((text(parameter, "key")?, text(parameter, "value")?));
+        }
+        active_rules.push(ActiveRule {
+            key: text(rule, "key")?,
+            severity: text(rule, "priority").ok(),
+            parameters,
+        });
+    }
+    Ok(active_rules)
+}
+
+fn parse_xml(text: &str) -> Result<Vec<ActiveRule>, AnalyzerError> {
+    let mut active_rules = vec![];
+    for rule in elements(text, "rule") {
+        let parameters_element = elements(rule, "parameters").next().unwrap_or_default();
+        // The keys of the parameters are not the key of the rule
+        let rule_fields = rule.replacen(parameters_element, "", 1);
+        let field = |name: &str| elements(&rule_fields, name).next().map(unescape);
+
+        if field("repositoryKey").as_deref() != Some(REPOSITORY_KEY) {
+            continue;
+        }
+        let mut parameters = vec![];
+        for parameter in elements(parameters_element, "parameter") {
+            let key = elements(parameter, "key").next().map(unescape);
+            let value = elements(parameter, "value").next().map(unescape);
+            parameters.push((
+                key.ok_or_else(|| invalid("missing key of a parameter"))?,
+                value.unwrap_or_default(),
+            ));
+        }
+        active_rules.push(ActiveRule {
+            key: field("key").ok_or_else(|| invalid("missing key of a rule"))?,
+            severity: field("priority"),
+            parameters,
+        });
+    }
+    Ok(active_rules)
+}
+
+/// Contents of the elements with the given name, which are not nested in elements of the same name.
+fn elements<'a>(text: &'a str, name: &str) -> impl Iterator<Item = &'a str> {
+    let open = format!("<{}>", name);
+    let close = format!("</{}>", name);
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let start = rest.find(&open)? + open.len();
+        let end = start + rest[start..].find(&close)?;
+        let content = &rest[start..end];
+        rest = &rest[end + close.len()..];
+        Some(content)
+    })
+}
+
+fn unescape(text: &str) -> String {
+    let text = text.trim();
+    if let Some(data) = text
+        .strip_prefix("<![CDATA[")
+        .and_then(|data| data.strip_suffix("]]>"))
+    {
+        return data.to_string();
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const XML_PROFILE: &str = r#"<?xml version='1.0' encoding='UTF-8'?>
+<profile>
+  <name>Company way</name>
+  <language>rust</language>
+  <rules>
+    <rule>
+      <repositoryKey>rust</repositoryKey>
+      <key>S3776</key>
+      <type>CODE_SMELL</type>
+      <priority>CRITICAL</priority>
+      <parameters>
+        <parameter>
+          <key>threshold</key>
+          <value>25</value>
+        </parameter>
+      </parameters>
+    </rule>
+    <rule>
+      <repositoryKey>rust-clippy</repositoryKey>
+      <key>clippy::needless_return</key>
+      <priority>MINOR</priority>
+      <parameters/>
+    </rule>
+    <rule>
+      <repositoryKey>rust</repositoryKey>
+      <key>S2260</key>
+      <priority>MAJOR</priority>
+      <parameters/>
+    </rule>
+  </rules>
+</profile>
+"#;
+
+    #[test]
+    fn test_parse_xml() {
+        assert_eq!(
+            QualityProfile::parse(XML_PROFILE).unwrap().rules,
+            vec![
+                ActiveRule {
+                    key: "S3776".to_string(),
+                    severity: Some("CRITICAL".to_string()),
+                    parameters: vec![("threshold".to_string(), "25".to_string())],
+                },
+                ActiveRule {
+                    key: "S2260".to_string(),
+                    severity: Some("MAJOR".to_string()),
+                    parameters: vec![],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_json() {
+        let profile = QualityProfile::parse(
+            r#"{"name": "Company way", "rules": [
+                {"repositoryKey": "rust", "key": "S7465", "parameters": [{"key": "sizeThreshold", "value": 512}]},
+                {"repositoryKey": "rust-clippy", "key": "clippy::needless_return"}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            profile.rules,
+            vec![ActiveRule {
+                key: "S7465".to_string(),
+                severity: None,
+                parameters: vec![("sizeThreshold".to_string(), "512".to_string())],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_invalid_profile() {
+        assert!(matches!(
+            QualityProfile::parse("{\"name\": \"Company way\"}"),
+            Err(AnalyzerError::GlobalError(message)) if message == "invalid quality profile: missing 'rules' array"
+        ));
+        assert!(QualityProfile::parse("{").is_err());
+    }
+
+    #[test]
+    fn test_to_parameters() {
+        // All the rules but the two active ones are disabled
+        let disabled_rules: Vec<&str> = all_rule_keys()
+            .unwrap()
+            .into_iter()
+            .filter(|key| *key != "S2260" && *key != "S3776")
+            .collect();
+        assert!(disabled_rules.contains(&"S7465"));
+        assert_eq!(
+            QualityProfile::parse(XML_PROFILE)
+                .unwrap()
+                .to_parameters()
+                .unwrap(),
+            HashMap::from([
+                ("disabled_rules".to_string(), disabled_rules.join(",")),
+                ("S3776:severity".to_string(), "CRITICAL".to_string()),
+                ("S3776:threshold".to_string(), "25".to_string()),
+                ("S2260:severity".to_string(), "MAJOR".to_string()),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(unescape(" a &lt;&amp;&gt; b "), "a <&> b");
+        assert_eq!(unescape("<![CDATA[a <&> b]]>"), "a <&> b");
+    }
+}
@@ -0,0 +1,1207 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, SecondaryLocation},
+    options::AnalysisOptions,
+    rule_description::RuleDescription,
+    tree::{parse_rust_code, AnalyzerError, NodeIterator, SonarLocation, TreeSitterLocation},
+    visitors::{
+        cpd::{calculate_borrowed_cpd_tokens, CpdOptions},
+        generated_code::GeneratedCode,
+        metrics::preceding_attributes,
+        test_code::TestCode,
+    },
+};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use tree_sitter::{Node, Tree};
+
+/// Key of the rule reporting the public items which are never used in the project.
+pub const UNUSED_PUBLIC_ITEM_KEY: &str = "S7466";
+/// Key of the rule reporting the functions whose body is duplicated in another file of the project.
+pub const DUPLICATED_FUNCTION_KEY: &str = "S4144";
+/// Key of the rule reporting the circular dependencies between the modules of a crate.
+pub const MODULE_CYCLE_KEY: &str = "S7473";
+/// Keys of the rules which depend on all the files of the project, run by `analyze_batch` and `ProjectAnalysis`.
+pub const PROJECT_RULE_KEYS: [&str; 3] = [
+    UNUSED_PUBLIC_ITEM_KEY,
+    DUPLICATED_FUNCTION_KEY,
+    MODULE_CYCLE_KEY,
+];
+
+/// Descriptions of the rules which depend on all the files of the project, see `PROJECT_RULE_KEYS`.
+pub const PROJECT_RULE_DESCRIPTIONS: [RuleDescription; 3] = [
+    UNUSED_PUBLIC_ITEM_DESCRIPTION,
+    DUPLICATED_FUNCTION_DESCRIPTION,
+    MODULE_CYCLE_DESCRIPTION,
+];
+
+const UNUSED_PUBLIC_ITEM_DESCRIPTION: RuleDescription = RuleDescription {
+    key: UNUSED_PUBLIC_ITEM_KEY,
+    title: "Unused public items should be removed",
+    markdown: r#"
+This rule raises an issue when a public function or type is never referred to by the other files of the project.
+
+## Why is this an issue?
+
+A public item which is not used anymore is dead code: it still has to be read, compiled, documented and maintained,
+while the compiler does not warn about it as it does for private items.
+
+Items are considered used as soon as any file of the project refers to their name. The items of excluded files,
+generated code and test code are not reported, nor are the items used outside of the Rust code, such as the functions
+exported with `#[no_mangle]` or registered by attribute macros.
+
+### Exceptions
+
+The public items of libraries may be used by other projects. Disable this rule for the projects which publish their
+crates.
+
+## How to fix it
+
+Remove the item, or reduce its visibility to let the compiler check its usages.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+pub fn legacy_checksum(data: &[u8]) -> u8 { // Noncompliant, never called
+    data.iter().fold(0, |sum, b| sum.wrapping_add(*b))
+}
+```
+"#,
+};
+
+const DUPLICATED_FUNCTION_DESCRIPTION: RuleDescription = RuleDescription {
+    key: DUPLICATED_FUNCTION_KEY,
+    title: "Functions should not have identical implementations",
+    markdown: r#"
+This rule raises an issue when a function has the same body as a function of another file of the project.
+
+## Why is this an issue?
+
+Two functions having the same implementation are either a copy-paste error, or duplicated code which has to be kept
+in sync: a fix applied to one of them is easily forgotten in the other.
+
+Bodies are compared after normalizing their whitespace and comments. The bodies of less than 3 lines, generated code
+and test code are ignored.
+
+## How to fix it
+
+Call one function from the other, or move the shared implementation to a common module.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+// src/parser.rs
+pub fn parse_port(text: &str) -> Option<u16> {
+    let port = text.trim().parse().ok()?;
+    (port != 0).then_some(port)
+}
+
+// src/config.rs
+fn port(text: &str) -> Option<u16> { // Noncompliant
+    let port = text.trim().parse().ok()?;
+    (port != 0).then_some(port)
+}
+```
+
+#### Compliant solution
+
+```compliant
+// src/config.rs
+fn port(text: &str) -> Option<u16> {
+    crate::parser::parse_port(text)
+}
+```
+"#,
+};
+
+const MODULE_CYCLE_DESCRIPTION: RuleDescription = RuleDescription {
+    key: MODULE_CYCLE_KEY,
+    title: "Modules should not have circular dependencies",
+    markdown: r#"
+This rule raises an issue when the modules of a crate depend on each other through their `use` declarations.
+
+## Why is this an issue?
+
+Modules which depend on each other can only be understood, tested and changed together. Circular dependencies are a
+sign of a missing abstraction, and they make it impossible to later extract a module to a crate of its own.
+
+A module using the items of its parent, or re-exporting the items of its children, is part of the design of the module
+tree and is not a dependency. Each cycle is reported once, on its first `use` declaration, with the following ones as
+secondary locations.
+
+## How to fix it
+
+Move the items used by both modules to a third module, or invert one of the dependencies with a trait.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+// src/client.rs
+use crate::server::Response; // Noncompliant
+
+// src/server.rs
+use crate::client::Request;
+```
+
+#### Compliant solution
+
+```compliant
+// src/client.rs
+use crate::protocol::Response;
+
+// src/server.rs
+use crate::protocol::Request;
+```
+"#,
+};
+
+/// Minimum number of lines of a function body, between its braces, for its duplications to be reported.
+const MIN_DUPLICATED_BODY_LINES: usize = 3;
+
+/// A public function or type declared in a file of the project.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct PublicItem {
+    /// Index of the file in the analyzed file set.
+    pub file: usize,
+    /// Location of the name of the item.
+    pub location: SonarLocation,
+    /// Kind of the item, as named in the messages (e.g. 'function').
+    pub kind: &'static str,
+    pub name: String,
+}
+
+/// A function with a body, declared in a file of the project.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct FunctionBody {
+    /// Index of the file in the analyzed file set.
+    pub file: usize,
+    /// Location of the name of the function.
+    pub location: SonarLocation,
+    pub name: String,
+}
+
+/// Path of a module: the directory of its crate (e.g. 'core', '' for the package at the root of the project),
+/// followed by the names of the modules from the crate root.
+pub type ModulePath = Vec<String>;
+
+/// A `use` declaration of a module referring to another module of the same crate.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct ModuleDependency {
+    pub from: ModulePath,
+    pub to: ModulePath,
+    /// Index of the file of the declaration in the analyzed file set.
+    pub file: usize,
+    /// Location of the `use` declaration.
+    pub location: SonarLocation,
+}
+
+/// Index of the public items of a project and of the names referenced across its files, of its function bodies, and
+/// of the dependencies between its modules.
+///
+/// References are resolved by name only: an item is used as soon as any file refers to its name, so that items
+/// shadowed by homonyms are never reported by mistake. Module dependencies are resolved within each crate, the
+/// imports of other crates are ignored.
+#[derive(Debug, Default)]
+pub struct ProjectIndex {
+    items: Vec<PublicItem>,
+    references: HashSet<String>,
+    /// Functions by the normalized tokens of their body, see `normalized_body`.
+    bodies: HashMap<String, Vec<FunctionBody>>,
+    /// Modules of the crates, declared by their files and their `mod` items.
+    modules: BTreeSet<ModulePath>,
+    /// Paths imported by `use` declarations, resolved against `modules` once all files are indexed.
+    imports: Vec<(ModuleDependency, Vec<String>)>,
+}
+
+impl ProjectIndex {
+    /// Adds the public items, the references and the function bodies of a file.
+    ///
+    /// Excluded files, generated code and test code only contribute references, their items and functions are not
+    /// indexed.
+    ///
+    /// The path of the file, relative to the project directory, gives its module, see `file_module`.
+    pub fn add_file(
+        &mut self,
+        file: usize,
+        path: &str,
+        source_code: &str,
+        options: &AnalysisOptions,
+    ) -> Result<(), AnalyzerError> {
+        let tree = parse_rust_code(source_code)?;
+        let test_code = TestCode::find(&tree, source_code, options.test_file);
+        if let Some(module) = file_module(path) {
+            self.add_modules(file, &module, &tree, source_code, &test_code);
+        }
+        let generated_code = GeneratedCode::find(
+            &tree,
+            source_code,
+            &options.generated_code.markers,
+            options.generated_file,
+        );
+
+        for node in NodeIterator::new(tree.root_node(), |node| {
+            matches!(
+                node.kind(),
+                "identifier" | "type_identifier" | "field_identifier"
+            )
+        }) {
+            if !is_declared_name(node) {
+                self.references
+                    .insert(source_code[node.byte_range()].to_string());
+            }
+        }
+
+        if options.excluded || generated_code.is_generated_file() {
+            return Ok(());
+        }
+        self.add_function_bodies(file, &tree, source_code, &test_code, &generated_code)?;
+        for item in NodeIterator::new(tree.root_node(), |node| item_kind(node).is_some()) {
+            if test_code.contains_node(item)
+                || generated_code.contains_byte(item.start_byte())
+                || !is_public(item)
+                || is_externally_used(item, source_code)
+            {
+                continue;
+            }
+            let (Some(kind), Some(name)) = (item_kind(item), item.child_by_field_name("name"))
+            else {
+                continue;
+            };
+            let name_text = &source_code[name.byte_range()];
+            if kind == "function" && name_text == "main" {
+                continue;
+            }
+            self.items.push(PublicItem {
+                file,
+                kind,
+                name: name_text.to_string(),
+                location: TreeSitterLocation::from_tree_sitter_node(name)
+                    .to_sonar_location(source_code),
+            });
+        }
+        Ok(())
+    }
+
+    fn add_function_bodies(
+        &mut self,
+        file: usize,
+        tree: &Tree,
+        source_code: &str,
+        test_code: &TestCode<'_>,
+        generated_code: &GeneratedCode,
+    ) -> Result<(), AnalyzerError> {
+        // Bodies are compared regardless of their layout and comments, but their literals must be identical
+        let options = CpdOptions {
+            normalize_literals: false,
+            ..CpdOptions::default()
+        };
+        let tokens =
+            calculate_borrowed_cpd_tokens(tree, source_code, &options, test_code, generated_code)?;
+        for function_item in
+            NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item")
+        {
+            let (Some(name), Some(body)) = (
+                function_item.child_by_field_name("name"),
+                function_item.child_by_field_name("body"),
+            ) else {
+                continue;
+            };
+            let body =
+                TreeSitterLocation::from_tree_sitter_node(body).to_sonar_location(source_code);
+            let body_tokens: Vec<_> = tokens
+                .iter()
+                .filter(|token| {
+                    (token.location.start_line, token.location.start_column)
+                        >= (body.start_line, body.start_column)
+                        && (token.location.end_line, token.location.end_column)
+                            <= (body.end_line, body.end_column)
+                })
+                .collect();
+            let lines: BTreeSet<usize> = body_tokens
+                .iter()
+                .skip(1)
+                .take(body_tokens.len().saturating_sub(2))
+                .map(|token| token.location.start_line)
+                .collect();
+            if lines.len() < MIN_DUPLICATED_BODY_LINES {
+                continue;
+            }
+            let normalized_body: Vec<&str> = body_tokens.iter().map(|token| token.image).collect();
+            self.bodies
+                .entry(normalized_body.join(" "))
+                .or_default()
+                .push(FunctionBody {
+                    file,
+                    name: source_code[name.byte_range()].to_string(),
+                    location: TreeSitterLocation::from_tree_sitter_node(name)
+                        .to_sonar_location(source_code),
+                });
+        }
+        Ok(())
+    }
+
+    fn add_modules(
+        &mut self,
+        file: usize,
+        file_module: &ModulePath,
+        tree: &Tree,
+        source_code: &str,
+        test_code: &TestCode<'_>,
+    ) {
+        self.modules.insert(file_module.clone());
+        for node in NodeIterator::new(tree.root_node(), |node| {
+            matches!(node.kind(), "mod_item" | "use_declaration")
+        }) {
+            if test_code.contains_node(node) {
+                continue;
+            }
+            let module = enclosing_module(file_module, node, source_code);
+            if node.kind() == "mod_item" {
+                if let Some(name) = node.child_by_field_name("name") {
+                    let mut declared = module;
+                    declared.push(source_code[name.byte_range()].to_string());
+                    self.modules.insert(declared);
+                }
+                continue;
+            }
+            let Some(argument) = node.child_by_field_name("argument") else {
+                continue;
+            };
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(node).to_sonar_location(source_code);
+            for path in use_paths(argument, source_code) {
+                if let Some(resolved) = resolve_path(&module, &path) {
+                    self.imports.push((
+                        ModuleDependency {
+                            from: module.clone(),
+                            to: vec![],
+                            file,
+                            location: location.clone(),
+                        },
+                        resolved,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Dependencies between the modules of the crates, except between a module and its ancestors: a module using
+    /// the items of its parent, or re-exporting the items of its children, is part of the design of the module tree.
+    /// Only the first declaration of each dependency is kept.
+    pub fn module_dependencies(&self) -> Vec<ModuleDependency> {
+        let mut dependencies: Vec<ModuleDependency> = vec![];
+        for (import, path) in &self.imports {
+            // The longest prefix of the path naming a module, the rest of the path being an item of this module
+            let Some(to) = (1..=path.len())
+                .rev()
+                .map(|len| &path[..len])
+                .find(|prefix| self.modules.contains(*prefix))
+            else {
+                continue;
+            };
+            if to.starts_with(&import.from) || import.from.starts_with(to) {
+                continue;
+            }
+            if dependencies
+                .iter()
+                .any(|dependency| dependency.from == import.from && dependency.to == to)
+            {
+                continue;
+            }
+            dependencies.push(ModuleDependency {
+                to: to.to_vec(),
+                ..import.clone()
+            });
+        }
+        dependencies.sort();
+        dependencies
+    }
+
+    /// Circular dependencies between modules, each one as the dependencies of a cycle starting at its smallest
+    /// module. A single cycle is reported for each set of mutually dependent modules.
+    pub fn module_cycles(&self) -> Vec<Vec<ModuleDependency>> {
+        let dependencies = self.module_dependencies();
+        let mut cycles = vec![];
+        for component in strongly_connected_components(&dependencies) {
+            if component.len() < 2 {
+                continue;
+            }
+            let start = component.iter().min().expect("components are not empty");
+            if let Some(cycle) = shortest_cycle(start, &component, &dependencies) {
+                cycles.push(cycle);
+            }
+        }
+        cycles.sort();
+        cycles
+    }
+
+    /// Functions having the same body as functions of other files, grouped by body. Each group is in file and source
+    /// order, and the groups are ordered by their first function.
+    pub fn duplicated_functions(&self) -> Vec<Vec<&FunctionBody>> {
+        let mut groups: Vec<Vec<&FunctionBody>> = self
+            .bodies
+            .values()
+            .filter(|functions| {
+                functions
+                    .iter()
+                    .any(|function| function.file != functions[0].file)
+            })
+            .map(|functions| {
+                let mut group: Vec<&FunctionBody> = functions.iter().collect();
+                group.sort();
+                group
+            })
+            .collect();
+        groups.sort();
+        groups
+    }
+
+    /// Public items whose name is never referenced in the indexed files, in file and source order.
+    pub fn unused_public_items(&self) -> Vec<&PublicItem> {
+        let mut unused: Vec<&PublicItem> = self
+            .items
+            .iter()
+            .filter(|item| !self.references.contains(&item.name))
+            .collect();
+        unused.sort();
+        unused
+    }
+}
+
+impl PublicItem {
+    pub fn to_issue(&self) -> Issue {
+        Issue {
+            rule_key: UNUSED_PUBLIC_ITEM_KEY.to_string(),
+            message: format!("Remove this unused public {} \"{}\".", self.kind, self.name),
+            location: self.location.clone(),
+            secondary_locations: vec![],
+            severity: None,
+            quick_fixes: vec![],
+            fingerprint: None,
+            hotspot: None,
+        }
+    }
+}
+
+/// Issues of the duplicated functions, with their file: the first function of each group is the original, the
+/// issues are reported on its copies, with secondary locations on the other functions of the group.
+pub fn duplicated_function_issues(
+    groups: &[Vec<&FunctionBody>],
+    paths: &[String],
+) -> Vec<(usize, Issue)> {
+    let mut issues = vec![];
+    for group in groups {
+        let original = group[0];
+        for copy in &group[1..] {
+            let secondary_locations = group
+                .iter()
+                .filter(|function| function != &copy)
+                .map(|function| SecondaryLocation {
+                    message: "Identical implementation".to_string(),
+                    location: function.location.clone(),
+                    file: (function.file != copy.file).then(|| paths[function.file].clone()),
+                })
+                .collect();
+            issues.push((
+                copy.file,
+                Issue {
+                    rule_key: DUPLICATED_FUNCTION_KEY.to_string(),
+                    message: format!(
+                        "Update this function so that its implementation is not identical to \"{}\" in {} on line {}.",
+                        original.name, paths[original.file], original.location.start_line
+                    ),
+                    location: copy.location.clone(),
+                    secondary_locations,
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                },
+            ));
+        }
+    }
+    issues
+}
+
+/// Issues of the module cycles, with their file: each issue is reported on the first dependency of the cycle, with
+/// secondary locations on the following ones.
+pub fn module_cycle_issues(
+    cycles: &[Vec<ModuleDependency>],
+    paths: &[String],
+) -> Vec<(usize, Issue)> {
+    cycles
+        .iter()
+        .map(|cycle| {
+            let first = &cycle[0];
+            let mut modules: Vec<String> = cycle
+                .iter()
+                .map(|dependency| module_name(&dependency.from))
+                .collect();
+            modules.push(module_name(&first.from));
+            let secondary_locations = cycle[1..]
+                .iter()
+                .map(|dependency| SecondaryLocation {
+                    message: format!(
+                        "{} depends on {}",
+                        module_name(&dependency.from),
+                        module_name(&dependency.to)
+                    ),
+                    location: dependency.location.clone(),
+                    file: (dependency.file != first.file).then(|| paths[dependency.file].clone()),
+                })
+                .collect();
+            (
+                first.file,
+                Issue {
+                    rule_key: MODULE_CYCLE_KEY.to_string(),
+                    message: format!(
+                        "Break the circular dependency between these modules: {}.",
+                        modules.join(" -> ")
+                    ),
+                    location: first.location.clone(),
+                    secondary_locations,
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                },
+            )
+        })
+        .collect()
+}
+
+/// Name of a module in the messages, e.g. 'crate::parser::lexer'.
+fn module_name(module: &ModulePath) -> String {
+    std::iter::once("crate")
+        .chain(module[1..].iter().map(String::as_str))
+        .collect::<Vec<_>>()
+        .join("::")
+}
+
+/// Module of a file from its path in a Cargo package: 'src/lib.rs' and 'src/main.rs' are the crate root, 'src/a.rs'
+/// and 'src/a/mod.rs' are the module `a`. `None` for the files outside of the 'src' directory, such as the
+/// integration tests, and for the binaries of 'src/bin', which are crates of their own.
+pub fn file_module(path: &str) -> Option<ModulePath> {
+    let components: Vec<&str> = path.split(['/', '\\']).collect();
+    let src = components
+        .iter()
+        .rposition(|component| *component == "src")?;
+    let (file, directories) = components[src + 1..].split_last()?;
+    if directories.first() == Some(&"bin") {
+        return None;
+    }
+    let mut module: ModulePath = vec![components[..src].join("/")];
+    module.extend(directories.iter().map(|directory| directory.to_string()));
+    match file.strip_suffix(".rs")? {
+        "lib" | "main" if directories.is_empty() => {}
+        "mod" => {}
+        name => module.push(name.to_string()),
+    }
+    Some(module)
+}
+
+/// Module of the node: the module of its file, followed by the inline `mod` items containing it.
+fn enclosing_module(file_module: &ModulePath, node: Node<'_>, source_code: &str) -> ModulePath {
+    let mut names = vec![];
+    let mut current = node.parent();
+    while let Some(parent) = current {
+        if parent.kind() == "mod_item" {
+            if let Some(name) = parent.child_by_field_name("name") {
+                names.push(source_code[name.byte_range()].to_string());
+            }
+        }
+        current = parent.parent();
+    }
+    names.reverse();
+    let mut module = file_module.clone();
+    module.extend(names);
+    module
+}
+
+/// Paths imported by the argument of a `use` declaration, e.g. `a::b` and `a::c` for `a::{b, c as d}`.
+fn use_paths(node: Node<'_>, source_code: &str) -> Vec<Vec<String>> {
+    let segments = |node: Node<'_>| -> Vec<String> {
+        source_code[node.byte_range()]
+            .split("::")
+            .map(|segment| segment.trim().to_string())
+            .filter(|segment| !segment.is_empty())
+            .collect()
+    };
+    match node.kind() {
+        "identifier" | "scoped_identifier" | "crate" | "super" | "self" => vec![segments(node)],
+        "use_as_clause" => node
+            .child_by_field_name("path")
+            .map(|path| vec![segments(path)])
+            .unwrap_or_default(),
+        "use_wildcard" => node
+            .named_child(0)
+            .map(|path| vec![segments(path)])
+            .unwrap_or_default(),
+        "scoped_use_list" => {
+            let prefix = node
+                .child_by_field_name("path")
+                .map(segments)
+                .unwrap_or_default();
+            node.child_by_field_name("list")
+                .map(|list| {
+                    use_paths(list, source_code)
+                        .into_iter()
+                        .map(|path| {
+                            // `a::{self}` imports the module `a` itself
+                            let path = path.into_iter().filter(|segment| segment != "self");
+                            prefix.iter().cloned().chain(path).collect()
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        }
+        "use_list" => {
+            let mut cursor = node.walk();
+            let paths = node
+                .named_children(&mut cursor)
+                .flat_map(|item| use_paths(item, source_code))
+                .collect();
+            paths
+        }
+        _ => vec![],
+    }
+}
+
+/// Absolute path, in the crate of the module, of a path imported in the module. `None` for the paths of other
+/// crates, which are not resolved.
+fn resolve_path(module: &ModulePath, path: &[String]) -> Option<Vec<String>> {
+    let (first, rest) = path.split_first()?;
+    match first.as_str() {
+        "crate" => Some(module[..1].iter().chain(rest).cloned().collect()),
+        "self" => Some(module.iter().chain(rest).cloned().collect()),
+        "super" => {
+            let mut resolved = module.clone();
+            let mut rest = path;
+            while let Some(("super", tail)) = rest.split_first().map(|(s, t)| (s.as_str(), t)) {
+                if resolved.len() <= 1 {
+                    return None;
+                }
+                resolved.pop();
+                rest = tail;
+            }
+            resolved.extend(rest.iter().cloned());
+            Some(resolved)
+        }
+        // Relative to the current module, or the name of another crate, in which case no module matches
+        _ => Some(module.iter().chain(path).cloned().collect()),
+    }
+}
+
+/// Strongly connected components of the module graph, with Tarjan's algorithm.
+fn strongly_connected_components(dependencies: &[ModuleDependency]) -> Vec<Vec<&ModulePath>> {
+    struct State<'a> {
+        dependencies: &'a [ModuleDependency],
+        index: HashMap<&'a ModulePath, usize>,
+        low_link: HashMap<&'a ModulePath, usize>,
+        stack: Vec<&'a ModulePath>,
+        components: Vec<Vec<&'a ModulePath>>,
+    }
+
+    fn connect<'a>(state: &mut State<'a>, module: &'a ModulePath) {
+        let index = state.index.len();
+        state.index.insert(module, index);
+        state.low_link.insert(module, index);
+        state.stack.push(module);
+        let dependencies = state.dependencies;
+        for dependency in dependencies
+            .iter()
+            .filter(|dependency| &dependency.from == module)
+        {
+            let to = &dependency.to;
+            if !state.index.contains_key(to) {
+                connect(state, to);
+                let low_link = state.low_link[module].min(state.low_link[to]);
+                state.low_link.insert(module, low_link);
+            } else if state.stack.contains(&to) {
+                let low_link = state.low_link[module].min(state.index[to]);
+                state.low_link.insert(module, low_link);
+            }
+        }
+        if state.low_link[module] == state.index[module] {
+            let mut component = vec![];
+            while let Some(member) = state.stack.pop() {
+                component.push(member);
+                if member == module {
+                    break;
+                }
+            }
+            state.components.push(component);
+        }
+    }
+
+    let mut state = State {
+        dependencies,
+        index: HashMap::new(),
+        low_link: HashMap::new(),
+        stack: vec![],
+        components: vec![],
+    };
+    for dependency in dependencies {
+        if !state.index.contains_key(&dependency.from) {
+            connect(&mut state, &dependency.from);
+        }
+    }
+    state.components
+}
+
+/// Shortest cycle from the module back to itself in its component, as the dependencies followed.
+fn shortest_cycle(
+    start: &ModulePath,
+    component: &[&ModulePath],
+    dependencies: &[ModuleDependency],
+) -> Option<Vec<ModuleDependency>> {
+    let mut previous: HashMap<&ModulePath, &ModuleDependency> = HashMap::new();
+    let mut queue = std::collections::VecDeque::from([start]);
+    while let Some(module) = queue.pop_front() {
+        for dependency in dependencies
+            .iter()
+            .filter(|dependency| &dependency.from == module && component.contains(&&dependency.to))
+        {
+            if &dependency.to == start {
+                let mut cycle = vec![dependency.clone()];
+                let mut current = module;
+                while current != start {
+                    let dependency = previous[current];
+                    cycle.push(dependency.clone());
+                    current = &dependency.from;
+                }
+                cycle.reverse();
+                return Some(cycle);
+            }
+            if !previous.contains_key(&dependency.to) && &dependency.to != start {
+                previous.insert(&dependency.to, dependency);
+                queue.push_back(&dependency.to);
+            }
+        }
+    }
+    None
+}
+
+fn item_kind(node: Node<'_>) -> Option<&'static str> {
+    match node.kind() {
+        "function_item" => Some("function"),
+        "struct_item" => Some("struct"),
+        "enum_item" => Some("enum"),
+        "union_item" => Some("union"),
+        "trait_item" => Some("trait"),
+        "type_item" => Some("type alias"),
+        _ => None,
+    }
+}
+
+fn is_declared_name(node: Node<'_>) -> bool {
+    node.parent().is_some_and(|parent| {
+        item_kind(parent).is_some()
+            && parent
+                .child_by_field_name("name")
+                .is_some_and(|name| name.id() == node.id())
+    })
+}
+
+fn is_public(item: Node<'_>) -> bool {
+    let mut cursor = item.walk();
+    let public = item
+        .children(&mut cursor)
+        .any(|child| child.kind() == "visibility_modifier");
+    public
+}
+
+/// Whether the item is used outside of the project's source code, e.g. exported to C or registered by a macro.
+fn is_externally_used(item: Node<'_>, source_code: &str) -> bool {
+    let mut cursor = item.walk();
+    let extern_function = item
+        .children(&mut cursor)
+        .filter(|child| child.kind() == "function_modifiers")
+        .any(|modifiers| source_code[modifiers.byte_range()].contains("extern"));
+
+    extern_function
+        || preceding_attributes(item)
+            .filter(|attribute| attribute.kind() == "attribute_item")
+            .any(|attribute| !is_inert_attribute(&source_code[attribute.byte_range()]))
+}
+
+/// Whether an attribute leaves the usages of an item to the code, as opposed to attribute macros
+/// (e.g. `#[tokio::main]`), exports (e.g. `#[no_mangle]`) or lint allowances for dead code.
+fn is_inert_attribute(attribute: &str) -> bool {
+    let content = attribute.trim_start_matches("#[").trim_start();
+    let name: String = content
+        .chars()
+        .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == ':')
+        .collect();
+    match name.as_str() {
+        "doc" | "inline" | "cold" | "must_use" | "derive" | "cfg" | "cfg_attr" | "deprecated"
+        | "repr" | "non_exhaustive" | "track_caller" => true,
+        "allow" | "expect" | "warn" | "deny" => {
+            !content.contains("dead_code") && !content.contains("unused")
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unused(files: &[(&str, &str)]) -> Vec<(usize, String)> {
+        let mut index = ProjectIndex::default();
+        for (file, (path, source_code)) in files.iter().enumerate() {
+            let options = AnalysisOptions {
+                test_file: path.starts_with("tests/"),
+                ..AnalysisOptions::default()
+            };
+            index.add_file(file, path, source_code, &options).unwrap();
+        }
+        index
+            .unused_public_items()
+            .into_iter()
+            .map(|item| (item.file, item.to_issue().message))
+            .collect()
+    }
+
+    #[test]
+    fn test_unused_public_items() {
+        let lib = r#"
+pub fn used() {}
+pub fn unused() {}
+fn private() {}
+pub struct Point { pub x: i32 }
+pub enum Shape { Circle }
+pub trait Draw {}
+pub type Id = u32;
+"#;
+        let main = r#"
+use crate::{used, Point};
+
+fn main() {
+    used();
+    let shape = Shape::Circle;
+    impl Draw for Point {}
+}
+"#;
+        assert_eq!(
+            unused(&[("src/lib.rs", lib), ("src/main.rs", main)]),
+            vec![
+                (
+                    0,
+                    "Remove this unused public function \"unused\".".to_string()
+                ),
+                (
+                    0,
+                    "Remove this unused public type alias \"Id\".".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_methods_and_test_usages() {
+        let lib = r#"
+pub struct Counter;
+
+impl Counter {
+    pub fn increment(&mut self) {}
+    pub fn reset(&mut self) {}
+}
+
+#[cfg(test)]
+mod tests {
+    pub fn helper() {}
+
+    #[test]
+    fn test() {
+        super::Counter.increment();
+    }
+}
+"#;
+        assert_eq!(
+            unused(&[("src/lib.rs", lib)]),
+            vec![(
+                0,
+                "Remove this unused public function \"reset\".".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_externally_used_items() {
+        let lib = r#"
+#[no_mangle]
+pub extern "C" fn exported() {}
+
+pub extern "C" fn callback() {}
+
+#[tokio::main]
+pub async fn entry() {}
+
+#[allow(dead_code)]
+pub fn kept() {}
+
+/// Documented, but still unused.
+#[inline]
+pub fn documented() {}
+
+pub fn main() {}
+"#;
+        assert_eq!(
+            unused(&[("src/lib.rs", lib)]),
+            vec![(
+                0,
+                "Remove this unused public function \"documented\".".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_duplicated_functions() {
+        let a = r#"
+fn parse(input: &str) -> u32 {
+    let trimmed = input.trim();
+    let parsed = trimmed.parse();
+    parsed.unwrap_or(0)
+}
+
+fn short() -> u32 { 1 }
+
+fn local_copy(input: &str) -> u32 {
+    let trimmed = input.trim();
+    let parsed = trimmed.parse();
+    parsed.unwrap_or(0)
+}
+"#;
+        let b = r#"
+// Same body with another layout and comments
+fn read(input: &str) -> u32 {
+    let trimmed = input
+        .trim(); // trimmed
+    let parsed = trimmed.parse();
+    parsed.unwrap_or(0)
+}
+
+fn short() -> u32 { 1 }
+
+fn other_default(input: &str) -> u32 {
+    let trimmed = input.trim();
+    let parsed = trimmed.parse();
+    parsed.unwrap_or(1)
+}
+"#;
+        let mut index = ProjectIndex::default();
+        index
+            .add_file(0, "src/a.rs", a, &AnalysisOptions::default())
+            .unwrap();
+        index
+            .add_file(1, "src/b.rs", b, &AnalysisOptions::default())
+            .unwrap();
+        let paths = vec!["src/a.rs".to_string(), "src/b.rs".to_string()];
+        let issues = duplicated_function_issues(&index.duplicated_functions(), &paths);
+
+        let summary: Vec<_> = issues
+            .iter()
+            .map(|(file, issue)| {
+                (
+                    *file,
+                    issue.message.clone(),
+                    issue.location.start_line,
+                    issue
+                        .secondary_locations
+                        .iter()
+                        .map(|secondary| (secondary.file.clone(), secondary.location.start_line))
+                        .collect(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (
+                    0,
+                    "Update this function so that its implementation is not identical to \"parse\" in src/a.rs on line 2.".to_string(),
+                    10,
+                    vec![(None, 2), (Some("src/b.rs".to_string()), 3)]
+                ),
+                (
+                    1,
+                    "Update this function so that its implementation is not identical to \"parse\" in src/a.rs on line 2.".to_string(),
+                    3,
+                    vec![(Some("src/a.rs".to_string()), 2), (Some("src/a.rs".to_string()), 10)]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_duplicated_functions_in_a_single_file() {
+        let source_code = r#"
+fn first() {
+    let x = 1;
+    let y = x + 1;
+    println!("{}", y);
+}
+
+fn second() {
+    let x = 1;
+    let y = x + 1;
+    println!("{}", y);
+}
+"#;
+        let mut index = ProjectIndex::default();
+        index
+            .add_file(0, "src/lib.rs", source_code, &AnalysisOptions::default())
+            .unwrap();
+        assert!(index.duplicated_functions().is_empty());
+    }
+
+    /// File, message and secondary messages of the module cycle issues.
+    fn module_cycles(files: &[(&str, &str)]) -> Vec<(usize, String, Vec<String>)> {
+        let mut index = ProjectIndex::default();
+        for (file, (path, source_code)) in files.iter().enumerate() {
+            index
+                .add_file(file, path, source_code, &AnalysisOptions::default())
+                .unwrap();
+        }
+        let paths: Vec<String> = files.iter().map(|(path, _)| path.to_string()).collect();
+        module_cycle_issues(&index.module_cycles(), &paths)
+            .into_iter()
+            .map(|(file, issue)| {
+                (
+                    file,
+                    issue.message,
+                    issue
+                        .secondary_locations
+                        .into_iter()
+                        .map(|secondary| secondary.message)
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_file_module() {
+        assert_eq!(file_module("src/lib.rs"), Some(vec!["".to_string()]));
+        assert_eq!(
+            file_module("core/src/parser/mod.rs"),
+            Some(vec!["core".to_string(), "parser".to_string()])
+        );
+        assert_eq!(
+            file_module("src/parser/lexer.rs"),
+            Some(vec![
+                "".to_string(),
+                "parser".to_string(),
+                "lexer".to_string()
+            ])
+        );
+        assert_eq!(file_module("src/bin/cli.rs"), None);
+        assert_eq!(file_module("tests/it.rs"), None);
+    }
+
+    #[test]
+    fn test_module_cycles() {
+        let lib = "mod lexer;\nmod parser;\nmod ast;\npub use parser::parse;\n";
+        let lexer = "use crate::parser::Parser;\n";
+        let parser = "use super::{ast::Node, lexer};\npub struct Parser;\n";
+        let ast = "use crate::lexer::Token;\n";
+        assert_eq!(
+            module_cycles(&[
+                ("src/lib.rs", lib),
+                ("src/lexer.rs", lexer),
+                ("src/parser.rs", parser),
+                ("src/ast.rs", ast),
+            ]),
+            vec![(
+                3,
+                "Break the circular dependency between these modules: crate::ast -> crate::lexer -> crate::parser -> crate::ast."
+                    .to_string(),
+                vec![
+                    "crate::lexer depends on crate::parser".to_string(),
+                    "crate::parser depends on crate::ast".to_string()
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn test_inline_modules_and_ancestors() {
+        // Children using their parent, and parents re-exporting their children, are not cycles
+        let lib = r#"
+pub use a::child::Thing;
+
+mod a {
+    pub mod child {
+        use super::super::b::B;
+        pub struct Thing;
+    }
+    use super::*;
+    pub struct A;
+}
+
+mod b {
+    use crate::a::A;
+    pub struct B;
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::b::B;
+}
+"#;
+        assert_eq!(module_cycles(&[("src/lib.rs", lib)]), vec![]);
+        let workspace = module_cycles(&[
+            ("core/src/lib.rs", "mod a;\nmod b;\n"),
+            ("core/src/a.rs", "use crate::b::B;\n"),
+            ("cli/src/b.rs", "use crate::a::A;\n"),
+        ]);
+        assert!(workspace.is_empty());
+    }
+
+    #[test]
+    fn test_generated_and_excluded_files() {
+        let generated = "// @generated\npub fn generated() {}\n";
+        let mut index = ProjectIndex::default();
+        index
+            .add_file(
+                0,
+                "src/generated.rs",
+                generated,
+                &AnalysisOptions::default(),
+            )
+            .unwrap();
+        index
+            .add_file(
+                1,
+                "src/excluded.rs",
+                "pub fn excluded() {}\n",
+                &AnalysisOptions {
+                    excluded: true,
+                    ..AnalysisOptions::default()
+                },
+            )
+            .unwrap();
+        assert!(index.unused_public_items().is_empty());
+    }
+}
@@ -0,0 +1,58 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+//! Version of the protocol spoken between the plugin and the analyzer.
+//!
+//! The plugin states the version it expects right after the "sonar" greeting, and the analyzer answers with the
+//! version it implements before reading any file, so that a plugin started with an analyzer binary of another
+//! release fails with a clear message instead of misreading the results.
+use crate::tree::AnalyzerError;
+
+/// Version of the messages exchanged with the plugin. Increment it on every change of their layout, the corpus
+/// snapshots have their own version, see `SNAPSHOT_SCHEMA_VERSION`.
+pub const PROTOCOL_VERSION: i32 = 8;
+
+/// Checks that the version expected by the client is the one implemented by the analyzer.
+pub fn check_protocol_version(expected: i32) -> Result<(), AnalyzerError> {
+    if expected == PROTOCOL_VERSION {
+        Ok(())
+    } else {
+        Err(AnalyzerError::GlobalError(format!(
+            "the plugin expects version {} of the analyzer protocol, but the analyzer implements version {}",
+            expected, PROTOCOL_VERSION
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_protocol_version() {
+        assert!(check_protocol_version(PROTOCOL_VERSION).is_ok());
+        match check_protocol_version(1) {
+            Err(AnalyzerError::GlobalError(message)) => assert_eq!(
+                message,
+                format!(
+                    "the plugin expects version 1 of the analyzer protocol, but the analyzer implements version {}",
+                    PROTOCOL_VERSION
+                )
+            ),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+}
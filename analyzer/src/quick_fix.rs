@@ -0,0 +1,231 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, QuickFix, TextEdit},
+    tree::{line_starts, split_lines, AnalyzerError, SonarLocation},
+};
+use std::ops::Range;
+
+/// Applies the edits of the quick fix to the source code the issue was found in, and returns the fixed source code.
+///
+/// The locations of the edits are converted the same way as they were computed, the columns counting UTF-16 code
+/// units. Edits which overlap, or which are outside of the source code, fail with a `FileError`.
+pub fn apply_quick_fix(source_code: &str, quick_fix: &QuickFix) -> Result<String, AnalyzerError> {
+    let mut edits = quick_fix
+        .edits
+        .iter()
+        .map(|edit| Ok((byte_range(source_code, &edit.location)?, edit)))
+        .collect::<Result<Vec<_>, AnalyzerError>>()?;
+    edits.sort_by_key(|(range, _)| (range.start, range.end));
+    for pair in edits.windows(2) {
+        if overlap(&pair[0].0, &pair[1].0) {
+            return Err(invalid(&format!(
+                "overlapping edits at {} and {}",
+                location_text(&pair[0].1.location),
+                location_text(&pair[1].1.location)
+            )));
+        }
+    }
+    Ok(apply_edits(source_code, &edits))
+}
+
+/// Applies the first quick fix of each issue, in the order of the issues, and returns the fixed source code with the
+/// number of applied quick fixes. The quick fixes which overlap a quick fix already applied are skipped, so that the
+/// fixed code can be analyzed again to fix the remaining issues.
+pub fn apply_quick_fixes(
+    source_code: &str,
+    issues: &[Issue],
+) -> Result<(String, usize), AnalyzerError> {
+    let mut applied: Vec<(Range<usize>, &TextEdit)> = vec![];
+    let mut count = 0;
+    for quick_fix in issues.iter().filter_map(|issue| issue.quick_fixes.first()) {
+        // Each quick fix is valid on its own
+        apply_quick_fix(source_code, quick_fix)?;
+        let edits = quick_fix
+            .edits
+            .iter()
+            .map(|edit| Ok((byte_range(source_code, &edit.location)?, edit)))
+            .collect::<Result<Vec<_>, AnalyzerError>>()?;
+        if edits
+            .iter()
+            .any(|(range, _)| applied.iter().any(|(other, _)| overlap(range, other)))
+        {
+            continue;
+        }
+        applied.extend(edits);
+        count += 1;
+    }
+    applied.sort_by_key(|(range, _)| (range.start, range.end));
+    Ok((apply_edits(source_code, &applied), count))
+}
+
+/// Whether two edits overlap. Insertions at the same offset overlap, as their order is ambiguous.
+fn overlap(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end || a.start == b.start
+}
+
+fn apply_edits(source_code: &str, edits: &[(Range<usize>, &TextEdit)]) -> String {
+    let mut fixed = String::with_capacity(source_code.len());
+    let mut offset = 0;
+    for (range, edit) in edits {
+        fixed.push_str(&source_code[offset..range.start]);
+        fixed.push_str(&edit.text);
+        offset = range.end;
+    }
+    fixed.push_str(&source_code[offset..]);
+    fixed
+}
+
+fn byte_range(source_code: &str, location: &SonarLocation) -> Result<Range<usize>, AnalyzerError> {
+    let start = byte_offset(source_code, location.start_line, location.start_column);
+    let end = byte_offset(source_code, location.end_line, location.end_column);
+    match (start, end) {
+        (Some(start), Some(end)) if start <= end => Ok(start..end),
+        _ => Err(invalid(&format!(
+            "edit at {} is outside of the source code",
+            location_text(location)
+        ))),
+    }
+}
+
+/// Byte offset of a line (1-based) and a column (0-based, in UTF-16 code units), `None` when it is not in the line or
+/// when it splits a character.
+fn byte_offset(source_code: &str, line: usize, column: usize) -> Option<usize> {
+    let line_start = *line_starts(source_code).get(line.checked_sub(1)?)?;
+    let line_text = split_lines(&source_code[line_start..])
+        .next()
+        .unwrap_or_default();
+    let mut units = 0;
+    for (index, c) in line_text.char_indices() {
+        if units == column {
+            return Some(line_start + index);
+        }
+        if units > column {
+            return None;
+        }
+        units += c.len_utf16();
+    }
+    (units == column).then_some(line_start + line_text.len())
+}
+
+fn location_text(location: &SonarLocation) -> String {
+    format!("{}:{}", location.start_line, location.start_column)
+}
+
+fn invalid(message: &str) -> AnalyzerError {
+    AnalyzerError::FileError(format!("invalid quick fix: {}", message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(line: usize, start_column: usize, end_column: usize, text: &str) -> TextEdit {
+        TextEdit {
+            location: SonarLocation {
+                start_line: line,
+                start_column,
+                end_line: line,
+                end_column,
+            },
+            text: text.to_string(),
+        }
+    }
+
+    fn quick_fix(edits: Vec<TextEdit>) -> QuickFix {
+        QuickFix {
+            message: "Fix it".to_string(),
+            edits,
+        }
+    }
+
+    fn issue(quick_fixes: Vec<QuickFix>) -> Issue {
+        Issue {
+            rule_key: "S0000".to_string(),
+            message: "Issue".to_string(),
+            location: SonarLocation {
+                start_line: 1,
+                start_column: 0,
+                end_line: 1,
+                end_column: 0,
+            },
+            secondary_locations: vec![],
+            severity: None,
+            quick_fixes,
+            fingerprint: None,
+            hotspot: None,
+        }
+    }
+
+    #[test]
+    fn test_apply_quick_fix() {
+        let source_code = "fn main() {\r\n    let 𝑥 = (1);\r\n}\r\n";
+        // The columns count the two UTF-16 code units of '𝑥'
+        let fixed = apply_quick_fix(
+            source_code,
+            &quick_fix(vec![
+                edit(2, 15, 16, ""),
+                edit(2, 13, 14, ""),
+                edit(2, 8, 10, "y"),
+            ]),
+        )
+        .unwrap();
+        assert_eq!(fixed, "fn main() {\r\n    let y = 1;\r\n}\r\n");
+
+        let insertion = quick_fix(vec![edit(3, 1, 1, "\r\n")]);
+        assert_eq!(
+            apply_quick_fix(source_code, &insertion).unwrap(),
+            "fn main() {\r\n    let 𝑥 = (1);\r\n}\r\n\r\n"
+        );
+        let end_of_file = quick_fix(vec![edit(4, 0, 0, "// end\n")]);
+        assert!(apply_quick_fix(source_code, &end_of_file)
+            .unwrap()
+            .ends_with("}\r\n// end\n"));
+
+        for invalid in [
+            vec![edit(2, 12, 15, ""), edit(2, 14, 15, "")],
+            vec![edit(1, 0, 0, "a"), edit(1, 0, 0, "b")],
+            vec![edit(2, 9, 10, "")],
+            vec![edit(1, 12, 13, "")],
+            vec![edit(5, 0, 0, "")],
+            vec![edit(2, 10, 8, "")],
+        ] {
+            assert!(matches!(
+                apply_quick_fix(source_code, &quick_fix(invalid)),
+                Err(AnalyzerError::FileError(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_apply_quick_fixes() {
+        let source_code = "let a = (1);\n";
+        let issues = [
+            issue(vec![quick_fix(vec![
+                edit(1, 8, 9, ""),
+                edit(1, 10, 11, ""),
+            ])]),
+            issue(vec![]),
+            issue(vec![quick_fix(vec![edit(1, 8, 11, "2")])]),
+            issue(vec![quick_fix(vec![edit(1, 4, 5, "b")])]),
+        ];
+        assert_eq!(
+            apply_quick_fixes(source_code, &issues).unwrap(),
+            ("let b = 1;\n".to_string(), 2)
+        );
+    }
+}
@@ -0,0 +1,346 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    advisories::VULNERABLE_DEPENDENCY_DESCRIPTION,
+    formatting::FORMATTING_DESCRIPTION,
+    manifest::MANIFEST_RULE_DESCRIPTIONS,
+    project_index::PROJECT_RULE_DESCRIPTIONS,
+    rules::rule::{all_rules, default_parameters},
+    tree::AnalyzerError,
+};
+use std::{fmt::Write, fs, path::Path};
+
+/// Description of a rule, written in Markdown next to its implementation and exported as the HTML description of the
+/// Sonar plugin (e.g. `S3776.html`), see `export_rule_descriptions`.
+///
+/// Only a subset of Markdown is supported: paragraphs, `##` to `####` headings, `-` lists, code spans, `**strong**`
+/// text, `[links](url)`, and code blocks. The info string of a code block is `noncompliant` or `compliant` for the
+/// code examples, which are paired in the order they are written.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct RuleDescription {
+    pub key: &'static str,
+    /// Title of the rule, which is also declared in the metadata of the rule (e.g. `S3776.json`).
+    pub title: &'static str,
+    pub markdown: &'static str,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DescriptionFormat {
+    Html,
+    Markdown,
+}
+
+impl DescriptionFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            DescriptionFormat::Html => "html",
+            DescriptionFormat::Markdown => "md",
+        }
+    }
+}
+
+impl RuleDescription {
+    pub fn export(&self, format: DescriptionFormat) -> String {
+        match format {
+            DescriptionFormat::Html => self.to_html(),
+            DescriptionFormat::Markdown => self.to_markdown(),
+        }
+    }
+
+    /// The description as a Markdown document, with the title as its heading.
+    pub fn to_markdown(&self) -> String {
+        format!("# {}\n\n{}\n", self.title, self.markdown.trim())
+    }
+
+    /// The description as HTML, without the title which SonarQube displays from the metadata of the rule.
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        let mut diff_id = 0;
+        let mut lines = self.markdown.trim().lines().peekable();
+        while let Some(line) = lines.next() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(info) = line.strip_prefix("```") {
+                let mut code = String::new();
+                for line in lines.by_ref() {
+                    if line.starts_with("```") {
+                        break;
+                    }
+                    writeln!(code, "{}", escape(line)).unwrap();
+                }
+                match info.trim() {
+                    diff_type @ ("noncompliant" | "compliant") => {
+                        if diff_type == "noncompliant" {
+                            diff_id += 1;
+                        }
+                        writeln!(
+                            html,
+                            "<pre data-diff-id=\"{}\" data-diff-type=\"{}\">\n{}</pre>",
+                            diff_id, diff_type, code
+                        )
+                        .unwrap();
+                    }
+                    _ => writeln!(html, "<pre>\n{}</pre>", code).unwrap(),
+                }
+            } else if let Some((level, heading)) = heading(line) {
+                writeln!(html, "<h{}>{}</h{}>", level, inline(heading), level).unwrap();
+            } else if let Some(first_item) = line.strip_prefix("- ") {
+                html.push_str("<ul>\n");
+                let mut item = first_item.to_string();
+                while let Some(next) = lines.peek() {
+                    if let Some(next_item) = next.strip_prefix("- ") {
+                        writeln!(html, "  <li> {} </li>", inline(&item)).unwrap();
+                        item = next_item.to_string();
+                    } else if next.starts_with("  ") {
+                        item.push('\n');
+                        item.push_str(next.trim());
+                    } else {
+                        break;
+                    }
+                    lines.next();
+                }
+                writeln!(html, "  <li> {} </li>\n</ul>", inline(&item)).unwrap();
+            } else {
+                let mut paragraph = line.to_string();
+                while let Some(next) = lines.peek() {
+                    if next.trim().is_empty()
+                        || next.starts_with("```")
+                        || next.starts_with("- ")
+                        || heading(next).is_some()
+                    {
+                        break;
+                    }
+                    paragraph.push('\n');
+                    paragraph.push_str(next);
+                    lines.next();
+                }
+                writeln!(html, "<p>{}</p>", inline(&paragraph)).unwrap();
+            }
+        }
+        html
+    }
+}
+
+/// Descriptions of all the rules implemented by the analyzer, ordered by key.
+pub fn all_rule_descriptions() -> Result<Vec<RuleDescription>, AnalyzerError> {
+    let mut descriptions: Vec<RuleDescription> = all_rules(&default_parameters())?
+        .iter()
+        .map(|rule| rule.description())
+        .chain(PROJECT_RULE_DESCRIPTIONS)
+        .chain(MANIFEST_RULE_DESCRIPTIONS)
+        .chain([FORMATTING_DESCRIPTION, VULNERABLE_DEPENDENCY_DESCRIPTION])
+        .collect();
+    // The type-aware rules only exist in the builds with the `type-aware` feature
+    #[cfg(feature = "type-aware")]
+    descriptions.push(crate::type_aware::DISCARDED_RESULT_DESCRIPTION);
+    descriptions.sort_by_key(|description| description.key);
+    Ok(descriptions)
+}
+
+/// Writes the description of each rule to a `<key>.html` or `<key>.md` file of the directory, and returns the number
+/// of written files.
+pub fn export_rule_descriptions(
+    directory: &Path,
+    format: DescriptionFormat,
+) -> Result<usize, AnalyzerError> {
+    let descriptions = all_rule_descriptions()?;
+    fs::create_dir_all(directory).map_err(|err| {
+        AnalyzerError::GlobalError(format!("failed to create {}: {}", directory.display(), err))
+    })?;
+    for description in &descriptions {
+        let path = directory.join(format!("{}.{}", description.key, format.extension()));
+        fs::write(&path, description.export(format)).map_err(|err| {
+            AnalyzerError::GlobalError(format!("failed to write {}: {}", path.display(), err))
+        })?;
+    }
+    Ok(descriptions.len())
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let text = line[level..].strip_prefix(' ')?;
+    (2..=4).contains(&level).then_some((level, text))
+}
+
+/// Converts the code spans, strong text and links of a text to HTML.
+fn inline(text: &str) -> String {
+    let mut html = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        if let Some(code) = delimited(rest, "`", "`") {
+            write!(html, "<code>{}</code>", escape(code)).unwrap();
+            rest = &rest[code.len() + 2..];
+        } else if let Some(strong) = delimited(rest, "**", "**") {
+            write!(html, "<strong>{}</strong>", inline(strong)).unwrap();
+            rest = &rest[strong.len() + 4..];
+        } else if let Some((label, url)) = delimited(rest, "[", "](").and_then(|label| {
+            delimited(&rest[label.len() + 1..], "](", ")").map(|url| (label, url))
+        }) {
+            write!(html, "<a href=\"{}\">{}</a>", escape(url), inline(label)).unwrap();
+            rest = &rest[label.len() + url.len() + 4..];
+        } else {
+            html.push_str(&escape(&c.to_string()));
+            rest = &rest[c.len_utf8()..];
+        }
+    }
+    html
+}
+
+/// Text between the opening delimiter at the start of the text and the next closing delimiter.
+fn delimited<'a>(text: &'a str, open: &str, close: &str) -> Option<&'a str> {
+    let content = text.strip_prefix(open)?;
+    content.find(close).map(|end| &content[..end])
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::profile::all_rule_keys;
+
+    const DESCRIPTION: RuleDescription = RuleDescription {
+        key: "S0000",
+        title: "Examples should be described",
+        markdown: r#"
+This rule raises an issue on **undocumented** `<T>` examples.
+
+## Why is this an issue?
+
+Examples without a description
+are hard to follow, see [the guide](https://example.com/?a=1&b=2).
+
+### Exceptions
+
+- Short examples
+- Examples of
+  `main` functions
+
+## How to fix it
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+fn f() -> &str { "a" } // Noncompliant
+```
+
+#### Compliant solution
+
+```compliant
+fn f() -> &'static str { "a" }
+```
+
+```
+// Other code
+```
+"#,
+    };
+
+    #[test]
+    fn test_to_html() {
+        assert_eq!(
+            DESCRIPTION.to_html(),
+            r#"<p>This rule raises an issue on <strong>undocumented</strong> <code>&lt;T&gt;</code> examples.</p>
+<h2>Why is this an issue?</h2>
+<p>Examples without a description
+are hard to follow, see <a href="https://example.com/?a=1&amp;b=2">the guide</a>.</p>
+<h3>Exceptions</h3>
+<ul>
+  <li> Short examples </li>
+  <li> Examples of
+<code>main</code> functions </li>
+</ul>
+<h2>How to fix it</h2>
+<h3>Code examples</h3>
+<h4>Noncompliant code example</h4>
+<pre data-diff-id="1" data-diff-type="noncompliant">
+fn f() -&gt; &amp;str { &quot;a&quot; } // Noncompliant
+</pre>
+<h4>Compliant solution</h4>
+<pre data-diff-id="1" data-diff-type="compliant">
+fn f() -&gt; &amp;'static str { &quot;a&quot; }
+</pre>
+<pre>
+// Other code
+</pre>
+"#
+        );
+    }
+
+    #[test]
+    fn test_to_markdown() {
+        assert!(DESCRIPTION
+            .to_markdown()
+            .starts_with("# Examples should be described\n\nThis rule raises"));
+    }
+
+    #[test]
+    fn test_all_rule_descriptions() {
+        let keys: Vec<&str> = all_rule_descriptions()
+            .unwrap()
+            .iter()
+            .map(|description| description.key)
+            .collect();
+        assert_eq!(keys, all_rule_keys().unwrap());
+    }
+
+    /// Every rule has metadata in the plugin, whose descriptions are the exported ones, see
+    /// `export_rule_descriptions`.
+    #[test]
+    fn test_plugin_descriptions() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../sonar-rust-plugin/src/main/resources/org/sonar/l10n/rust/rules/rust");
+        for description in all_rule_descriptions().unwrap() {
+            let path = directory.join(format!("{}.html", description.key));
+            let html = fs::read_to_string(&path).unwrap_or_else(|_| panic!("{}", path.display()));
+            assert_eq!(html, description.to_html(), "{}", path.display());
+
+            let path = directory.join(format!("{}.json", description.key));
+            let metadata =
+                fs::read_to_string(&path).unwrap_or_else(|_| panic!("{}", path.display()));
+            let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+            assert_eq!(metadata["title"], description.title, "{}", path.display());
+        }
+    }
+
+    /// The type of every rule is the one declared in its metadata, see `Rule::rule_type`.
+    #[test]
+    fn test_plugin_rule_types() {
+        let directory = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("../sonar-rust-plugin/src/main/resources/org/sonar/l10n/rust/rules/rust");
+        for rule in all_rules(&default_parameters()).unwrap() {
+            let path = directory.join(format!("{}.json", rule.key()));
+            let metadata =
+                fs::read_to_string(&path).unwrap_or_else(|_| panic!("{}", path.display()));
+            let metadata: serde_json::Value = serde_json::from_str(&metadata).unwrap();
+            assert_eq!(
+                metadata["type"],
+                rule.rule_type().to_sonar_api_name(),
+                "{}",
+                path.display()
+            );
+        }
+    }
+}
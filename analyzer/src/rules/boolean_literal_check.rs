@@ -0,0 +1,228 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, QuickFix, TextEdit},
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S1125";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Boolean literals should not be redundant",
+    markdown: r#"
+This rule raises an issue on the comparisons of an expression with `true` or `false` using `==` or `!=`. The quick
+fix replaces the comparison with the expression, negated when needed.
+
+## Why is this an issue?
+
+Comparing a boolean with a literal adds noise without changing the result: `ready == true` is `ready`, and
+`ready == false` is `!ready`.
+
+```noncompliant
+if connection.is_closed() == false {
+    connection.send(message);
+}
+```
+
+### How to fix it
+
+Use the boolean expression directly.
+
+```compliant
+if !connection.is_closed() {
+    connection.send(message);
+}
+```
+
+## Resources
+
+### Documentation
+
+- Clippy Lints - [bool_comparison](https://rust-lang.github.io/rust-clippy/master/index.html#bool_comparison)
+"#,
+};
+
+pub struct BooleanLiteralCheck;
+
+impl Rule for BooleanLiteralCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        _tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for comparison in
+            NodeIterator::new(tree.root_node(), |node| node.kind() == "binary_expression")
+        {
+            let (Some(left), Some(operator), Some(right)) = (
+                comparison.child_by_field_name("left"),
+                comparison.child_by_field_name("operator"),
+                comparison.child_by_field_name("right"),
+            ) else {
+                continue;
+            };
+            let equal = match operator.kind() {
+                "==" => true,
+                "!=" => false,
+                _ => continue,
+            };
+            let (literal, operand) = if right.kind() == "boolean_literal" {
+                (right, left)
+            } else if left.kind() == "boolean_literal" {
+                (left, right)
+            } else {
+                continue;
+            };
+
+            let literal_text = &source_code[literal.byte_range()];
+            let operand_text = &source_code[operand.byte_range()];
+            let replacement = if equal == (literal_text == "true") {
+                operand_text.to_string()
+            } else if is_primary(operand) {
+                format!("!{}", operand_text)
+            } else {
+                format!("!({})", operand_text)
+            };
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: "Remove the unnecessary boolean literal.".to_string(),
+                location: TreeSitterLocation::from_tree_sitter_node(literal)
+                    .to_sonar_location(source_code),
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![QuickFix {
+                    message: format!("Remove the comparison with \"{}\"", literal_text),
+                    edits: vec![TextEdit {
+                        location: TreeSitterLocation::from_tree_sitter_node(comparison)
+                            .to_sonar_location(source_code),
+                        text: replacement,
+                    }],
+                }],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Whether the expression binds tighter than the `!` operator, so that it can be negated without parentheses.
+fn is_primary(expression: Node<'_>) -> bool {
+    matches!(
+        expression.kind(),
+        "identifier"
+            | "boolean_literal"
+            | "field_expression"
+            | "call_expression"
+            | "index_expression"
+            | "macro_invocation"
+            | "parenthesized_expression"
+            | "unary_expression"
+            | "try_expression"
+            | "await_expression"
+            | "scoped_identifier"
+            | "self"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{quick_fix::apply_quick_fix, tree::parse_rust_code, verifier::assert_issues};
+
+    #[test]
+    fn test_boolean_literal() {
+        assert_issues(
+            &BooleanLiteralCheck,
+            r#"
+fn main() {
+    if ready == true {} // Noncompliant {{Remove the unnecessary boolean literal.}}
+//              ^^^^
+    if ready != false {} // Noncompliant
+//              ^^^^^
+    if conn.is_closed() == false {} // Noncompliant
+//                         ^^^^^
+    if false != a.b {} // Noncompliant
+//     ^^^^^
+    let x = a && b == true; // Noncompliant
+    let y = (a || b) != true; // Noncompliant
+    let z = count as bool == false; // Noncompliant
+    let w = a == b;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_quick_fixes() {
+        let source_code = r#"
+fn main() {
+    if ready == true {}
+    if ready != false {}
+    if conn.is_closed() == false {}
+    if false != a.b {}
+    let x = a && b == true;
+    let y = (a || b) != true;
+    let z = count as bool == false;
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let issues = BooleanLiteralCheck.check(&tree, source_code).unwrap();
+        assert_eq!(
+            issues[0].quick_fixes[0].message,
+            "Remove the comparison with \"true\""
+        );
+
+        let fixed: Vec<_> = issues
+            .iter()
+            .map(|issue| {
+                let fixed = apply_quick_fix(source_code, &issue.quick_fixes[0]).unwrap();
+                fixed
+                    .lines()
+                    .nth(issue.location.start_line - 1)
+                    .unwrap()
+                    .to_string()
+            })
+            .collect();
+        assert_eq!(
+            fixed,
+            vec![
+                "    if ready {}",
+                "    if ready {}",
+                "    if !conn.is_closed() {}",
+                "    if a.b {}",
+                "    let x = a && b;",
+                "    let y = !(a || b);",
+                "    let z = !(count as bool);",
+            ]
+        );
+    }
+}
@@ -16,7 +16,8 @@
  */
 use crate::{
     issue::{Issue, SecondaryLocation},
-    rules::rule::Rule,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
     tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
     visitors::cognitive_complexity::calculate_cognitive_complexity,
 };
@@ -24,6 +25,53 @@ use tree_sitter::{Node, Tree};
 
 const RULE_KEY: &str = "S3776";
 
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Cognitive Complexity of functions should not be too high",
+    markdown: r#"
+This rule raises an issue when the code cognitive complexity of a function is above a certain threshold.
+
+## Why is this an issue?
+
+Cognitive Complexity is a measure of how hard it is to understand the control flow of a unit of code. Code with high
+cognitive complexity is hard to read, understand, test, and modify.
+
+As a rule of thumb, high cognitive complexity is a sign that the code should be refactored into smaller,
+easier-to-manage pieces.
+
+### Which syntax in code does impact cognitive complexity score?
+
+Here are the core concepts:
+
+- **Cognitive complexity is incremented each time the code breaks the normal linear reading flow.** This concerns,
+  for example, loop structures, conditionals, `match` expressions, jumps to labels, and conditions mixing multiple
+  operators.
+- **Each nesting level increases complexity.** During code reading, the deeper you go through nested layers, the
+  harder it becomes to keep the context in mind.
+- **Function calls are free.** A well-picked function name is a summary of multiple lines of code. A reader can first
+  explore a high-level view of what the code is performing then go deeper and deeper by looking at called functions
+  content. This does not apply to recursive calls, those will increment cognitive score.
+
+The method of computation is fully detailed in the pdf linked in the resources.
+
+### What is the potential impact?
+
+Developers spend more time reading and understanding code than writing it. High cognitive complexity slows down
+changes and increases the cost of maintenance.
+
+## Resources
+
+### Documentation
+
+- Sonar - [Cognitive Complexity](https://www.sonarsource.com/docs/CognitiveComplexity.pdf)
+
+### Articles & blog posts
+
+- Sonar Blog - [5 Clean Code Tips for Reducing Cognitive
+  Complexity](https://www.sonarsource.com/blog/5-clean-code-tips-for-reducing-cognitive-complexity/)
+"#,
+};
+
 pub struct CognitiveComplexityCheck {
     threshold: i32,
 }
@@ -35,7 +83,20 @@ impl CognitiveComplexityCheck {
 }
 
 impl Rule for CognitiveComplexityCheck {
-    fn check(&self, tree: &Tree, source_code: &str) -> Result<Vec<Issue>, AnalyzerError> {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
         let iter = NodeIterator::new(tree.root_node(), |node| is_outer_function_node(node));
         let mut issues: Vec<Issue> = vec![];
 
@@ -43,10 +104,46 @@ impl Rule for CognitiveComplexityCheck {
             let increments = calculate_cognitive_complexity(function_item)?;
             let total: i32 = increments.iter().map(|inc| inc.nesting + 1).sum();
 
+            if tracer.is_enabled() {
+                if let Some(name) = function_item.child_by_field_name("name") {
+                    let subject = TreeSitterLocation::from_tree_sitter_node(name)
+                        .to_sonar_location(source_code);
+                    for inc in &increments {
+                        let token = &source_code[inc.location.start_byte..inc.location.end_byte];
+                        tracer.trace(
+                            &subject,
+                            &inc.location.to_sonar_location(source_code),
+                            format!(
+                                "+{} for '{}' at nesting level {}",
+                                inc.nesting + 1,
+                                token,
+                                inc.nesting
+                            ),
+                        );
+                    }
+                    let comparison = if total > self.threshold {
+                        "above"
+                    } else {
+                        "not above"
+                    };
+                    tracer.trace(
+                        &subject,
+                        &subject,
+                        format!(
+                            "total complexity of {} is {} the threshold of {}",
+                            total, comparison, self.threshold
+                        ),
+                    );
+                }
+            }
+
             if total > self.threshold {
-                let secondary_locations: Vec<SecondaryLocation> = increments
+                // The `else` of an `if` is counted along with the `if`, before the `if`s nested in it: the increments
+                // are listed in the order of the code instead
+                let mut secondary_locations: Vec<SecondaryLocation> = increments
                     .iter()
                     .map(|inc| SecondaryLocation {
+                        file: None,
                         location: inc.location.to_sonar_location(source_code),
                         message: if inc.nesting == 0 {
                             format!("+{}", inc.nesting + 1)
@@ -55,6 +152,7 @@ impl Rule for CognitiveComplexityCheck {
                         },
                     })
                     .collect();
+                secondary_locations.sort_by(|a, b| a.location.cmp(&b.location));
 
                 let location =
                     function_item
@@ -67,7 +165,11 @@ impl Rule for CognitiveComplexityCheck {
                     rule_key: RULE_KEY.to_string(),
                     message: format!("Refactor this function to reduce its Cognitive Complexity from {} to the {} allowed.", total, self.threshold),
                     location: TreeSitterLocation::from_tree_sitter_node(location).to_sonar_location(source_code),
-                    secondary_locations
+                    secondary_locations,
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
                 });
             }
         }
@@ -153,6 +255,7 @@ fn foo(c1: bool, c2: bool) {
             actual[0].secondary_locations,
             vec![
                 SecondaryLocation {
+                    file: None,
                     message: "+1".to_owned(),
                     location: SonarLocation {
                         start_line: 3,
@@ -162,15 +265,7 @@ fn foo(c1: bool, c2: bool) {
                     }
                 },
                 SecondaryLocation {
-                    message: "+1".to_owned(),
-                    location: SonarLocation {
-                        start_line: 9,
-                        start_column: 6,
-                        end_line: 9,
-                        end_column: 10
-                    }
-                },
-                SecondaryLocation {
+                    file: None,
                     message: "+2 (incl 1 for nesting)".to_owned(),
                     location: SonarLocation {
                         start_line: 4,
@@ -180,6 +275,7 @@ fn foo(c1: bool, c2: bool) {
                     }
                 },
                 SecondaryLocation {
+                    file: None,
                     message: "+1".to_owned(),
                     location: SonarLocation {
                         start_line: 6,
@@ -188,6 +284,16 @@ fn foo(c1: bool, c2: bool) {
                         end_column: 14
                     }
                 },
+                SecondaryLocation {
+                    file: None,
+                    message: "+1".to_owned(),
+                    location: SonarLocation {
+                        start_line: 9,
+                        start_column: 6,
+                        end_line: 9,
+                        end_column: 10
+                    }
+                },
             ]
         );
     }
@@ -232,6 +338,7 @@ fn foo(c1: bool) {
             actual[0].secondary_locations,
             vec![
                 SecondaryLocation {
+                    file: None,
                     message: "+2 (incl 1 for nesting)".to_string(),
                     location: SonarLocation {
                         start_line: 4,
@@ -241,6 +348,7 @@ fn foo(c1: bool) {
                     }
                 },
                 SecondaryLocation {
+                    file: None,
                     message: "+1".to_string(),
                     location: SonarLocation {
                         start_line: 9,
@@ -0,0 +1,328 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, QuickFix, SecondaryLocation, TextEdit},
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, SonarLocation, TreeSitterLocation},
+};
+use tree_sitter::{Node, Point, Tree};
+
+const RULE_KEY: &str = "S1066";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Mergeable \"if\" statements should be combined",
+    markdown: r#"
+This rule raises an issue on the `if` expressions which are the only content of the block of another `if`, when
+neither has an `else` branch. The quick fix merges the two conditions with `&&`. The `if let` conditions are ignored,
+as merging them requires the let chains of the Rust 2024 edition, and so are the blocks containing comments, which the
+merge would lose.
+
+## Why is this an issue?
+
+Nested `if` expressions without `else` are equivalent to a single `if` with both conditions, which is easier to read.
+
+```noncompliant
+if file.is_modified() {
+    if file.is_writable() {
+        file.save();
+    }
+}
+```
+
+### How to fix it
+
+Merge the conditions with `&&`.
+
+```compliant
+if file.is_modified() && file.is_writable() {
+    file.save();
+}
+```
+
+## Resources
+
+### Documentation
+
+- Clippy Lints - [collapsible_if](https://rust-lang.github.io/rust-clippy/master/index.html#collapsible_if)
+"#,
+};
+
+pub struct CollapsibleIfCheck;
+
+impl Rule for CollapsibleIfCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for outer in NodeIterator::new(tree.root_node(), |node| node.kind() == "if_expression") {
+            let Some(inner) = nested_if(outer) else {
+                continue;
+            };
+            let (
+                Some(outer_condition),
+                Some(outer_block),
+                Some(inner_condition),
+                Some(inner_block),
+            ) = (
+                outer.child_by_field_name("condition"),
+                outer.child_by_field_name("consequence"),
+                inner.child_by_field_name("condition"),
+                inner.child_by_field_name("consequence"),
+            )
+            else {
+                continue;
+            };
+            let location = keyword_location(inner, source_code);
+            if is_let(outer_condition) || is_let(inner_condition) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, "let condition".to_string());
+                }
+                continue;
+            }
+
+            let (outer_open, outer_close) = parentheses(outer_condition);
+            let (inner_open, inner_close) = parentheses(inner_condition);
+            let quick_fix = QuickFix {
+                message: "Merge the conditions with \"&&\"".to_string(),
+                edits: vec![
+                    TextEdit {
+                        location: span(
+                            (
+                                outer_condition.start_byte(),
+                                outer_condition.start_position(),
+                            ),
+                            (
+                                outer_condition.start_byte(),
+                                outer_condition.start_position(),
+                            ),
+                            source_code,
+                        ),
+                        text: outer_open.to_string(),
+                    },
+                    TextEdit {
+                        location: span(
+                            (outer_condition.end_byte(), outer_condition.end_position()),
+                            (
+                                inner_condition.start_byte(),
+                                inner_condition.start_position(),
+                            ),
+                            source_code,
+                        ),
+                        text: format!("{} && {}", outer_close, inner_open),
+                    },
+                    TextEdit {
+                        location: span(
+                            (inner_condition.end_byte(), inner_condition.end_position()),
+                            (inner_condition.end_byte(), inner_condition.end_position()),
+                            source_code,
+                        ),
+                        text: inner_close.to_string(),
+                    },
+                    TextEdit {
+                        location: span(
+                            (inner_block.end_byte(), inner_block.end_position()),
+                            (outer_block.end_byte(), outer_block.end_position()),
+                            source_code,
+                        ),
+                        text: String::new(),
+                    },
+                ],
+            };
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: "Merge this \"if\" with the enclosing one.".to_string(),
+                location,
+                secondary_locations: vec![SecondaryLocation {
+                    message: "Enclosing \"if\"".to_string(),
+                    location: keyword_location(outer, source_code),
+                    file: None,
+                }],
+                severity: None,
+                quick_fixes: vec![quick_fix],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// The `if` which is the only content of the block of the other `if`, when neither has an `else` branch.
+fn nested_if(outer: Node<'_>) -> Option<Node<'_>> {
+    if outer.child_by_field_name("alternative").is_some() {
+        return None;
+    }
+    let block = outer.child_by_field_name("consequence")?;
+    let mut cursor = block.walk();
+    let mut contents = block
+        .named_children(&mut cursor)
+        .filter(|child| child.kind() != "empty_statement");
+    let (Some(content), None) = (contents.next(), contents.next()) else {
+        return None;
+    };
+    let inner = if content.kind() == "expression_statement" && content.named_child_count() == 1 {
+        content.named_child(0)?
+    } else {
+        content
+    };
+    (inner.kind() == "if_expression" && inner.child_by_field_name("alternative").is_none())
+        .then_some(inner)
+}
+
+fn is_let(condition: Node<'_>) -> bool {
+    matches!(condition.kind(), "let_condition" | "let_chain")
+}
+
+/// The parentheses needed around the condition for it to be an operand of `&&`.
+fn parentheses(condition: Node<'_>) -> (&'static str, &'static str) {
+    let lower_precedence = condition.kind() == "binary_expression"
+        && condition
+            .child_by_field_name("operator")
+            .is_some_and(|operator| operator.kind() == "||");
+    if lower_precedence {
+        ("(", ")")
+    } else {
+        ("", "")
+    }
+}
+
+fn keyword_location(if_expression: Node<'_>, source_code: &str) -> SonarLocation {
+    let keyword = if_expression.child(0).unwrap_or(if_expression);
+    TreeSitterLocation::from_tree_sitter_node(keyword).to_sonar_location(source_code)
+}
+
+fn span(start: (usize, Point), end: (usize, Point), source_code: &str) -> SonarLocation {
+    TreeSitterLocation {
+        start_byte: start.0,
+        end_byte: end.0,
+        start_position: start.1,
+        end_position: end.1,
+    }
+    .to_sonar_location(source_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{quick_fix::apply_quick_fix, tree::parse_rust_code, verifier::assert_issues};
+
+    #[test]
+    fn test_collapsible_if() {
+        // The annotations precede the outer blocks, a comment in them would prevent the issue
+        assert_issues(
+            &CollapsibleIfCheck,
+            r#"
+fn main() {
+    // Noncompliant@+2 {{Merge this "if" with the enclosing one.}}
+    if a {
+        if b {
+            run();
+        }
+    }
+    // Noncompliant@+2
+    if a || b {
+        if c || d { run() }
+    }
+    if a { if b { run() } } else { stop() }
+    if a { if b { run() } else { stop() } }
+    if a {
+        // Only when b
+        if b { run() }
+    }
+    if a {
+        if b { run() }
+        stop();
+    }
+    if let Some(x) = a {
+        if x > 0 { run() }
+    }
+    // Noncompliant@+3
+    if x {
+    } else if y {
+        if z { run() };
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_quick_fixes() {
+        let source_code = r#"
+fn main() {
+    if a {
+        if b {
+            run();
+        }
+    }
+    if a || b {
+        if c || d { run() }
+    }
+    if a { if b { run() } } else { stop() }
+    if a { if b { run() } else { stop() } }
+    if a {
+        // Only when b
+        if b { run() }
+    }
+    if a {
+        if b { run() }
+        stop();
+    }
+    if let Some(x) = a {
+        if x > 0 { run() }
+    }
+    if x {
+    } else if y {
+        if z { run() };
+    }
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let issues = CollapsibleIfCheck.check(&tree, source_code).unwrap();
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| (
+                    issue.location.start_line,
+                    issue.location.start_column,
+                    issue.secondary_locations[0].location.start_line
+                ))
+                .collect::<Vec<_>>(),
+            vec![(4, 8, 3), (9, 8, 8), (26, 8, 25)]
+        );
+        let fixed = apply_quick_fix(source_code, &issues[0].quick_fixes[0]).unwrap();
+        assert!(fixed.contains("    if a && b {\n            run();\n        }\n    if a || b {"));
+        let fixed = apply_quick_fix(source_code, &issues[1].quick_fixes[0]).unwrap();
+        assert!(fixed.contains("    if (a || b) && (c || d) { run() }\n"));
+        let fixed = apply_quick_fix(source_code, &issues[2].quick_fixes[0]).unwrap();
+        assert!(fixed.contains("    } else if y && z { run() }\n}"));
+    }
+}
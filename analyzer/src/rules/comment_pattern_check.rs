@@ -0,0 +1,252 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{check_deadline, interrupted, AnalyzerError, TreeSitterLocation},
+};
+use tree_sitter::{Query, QueryCursor, QueryCursorOptions, StreamingIterator, Tree};
+
+const RULE_KEY: &str = "S124";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Track comments matching a regular expression",
+    markdown: r#"
+This rule raises an issue on each comment matching the regular expression of the `regularExpression` parameter, with
+the message of the `message` parameter. It does nothing until a regular expression is configured.
+
+## Why is this an issue?
+
+Teams flag code needing attention with conventions of their own, e.g. `HACK` comments or references to the tickets of
+their issue tracker. This rule tracks these comments, so that they are not forgotten.
+
+The regular expression matches anywhere in the comment, including its delimiters: use `^` and `$` to match the whole
+comment. Doc comments are comments too.
+
+### Code examples
+
+With the `(?i)\bhack\b` regular expression:
+
+```noncompliant
+// HACK: the parser does not handle nested generics yet // Noncompliant
+fn parse(input: &str) -> Type {
+    todo!()
+}
+```
+"#,
+};
+
+/// Default message of the issues, when the 'S124:message' parameter is empty.
+pub const DEFAULT_MESSAGE: &str = "The regular expression matches this comment.";
+
+/// Flags the comments matching a regular expression, which is run by the regular expression engine of the tree-sitter
+/// queries.
+pub struct CommentPatternCheck {
+    /// `None` when no regular expression is configured.
+    query: Option<Query>,
+    message: String,
+}
+
+impl CommentPatternCheck {
+    pub fn new(regular_expression: &str, message: &str) -> Result<Self, AnalyzerError> {
+        let query = if regular_expression.is_empty() {
+            None
+        } else {
+            let pattern = regular_expression
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"");
+            let source = format!(
+                "([(line_comment) (block_comment)] @comment (#match? @comment \"{}\"))",
+                pattern
+            );
+            let query = Query::new(&tree_sitter_rust::LANGUAGE.into(), &source).map_err(|err| {
+                AnalyzerError::GlobalError(format!(
+                    "could not parse '{}:regularExpression' parameter: {}",
+                    RULE_KEY, err.message
+                ))
+            })?;
+            Some(query)
+        };
+        let message = if message.trim().is_empty() {
+            DEFAULT_MESSAGE
+        } else {
+            message
+        };
+        Ok(CommentPatternCheck {
+            query,
+            message: message.to_string(),
+        })
+    }
+}
+
+impl Rule for CommentPatternCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let Some(query) = &self.query else {
+            return Ok(vec![]);
+        };
+        let mut cursor = QueryCursor::new();
+        let mut progress = |_: &_| interrupted();
+        let mut matches = cursor.matches_with_options(
+            query,
+            tree.root_node(),
+            source_code.as_bytes(),
+            QueryCursorOptions::new().progress_callback(&mut progress),
+        );
+        let mut issues = vec![];
+        while let Some(query_match) = matches.next() {
+            for capture in query_match.captures {
+                let location = TreeSitterLocation::from_tree_sitter_node(capture.node)
+                    .to_sonar_location(source_code);
+                if tracer.is_enabled() {
+                    tracer.trace(
+                        &location,
+                        &location,
+                        "comment matching the regular expression".to_string(),
+                    );
+                }
+                issues.push(Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: self.message.clone(),
+                    location,
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                });
+            }
+        }
+        // Query execution halts when the progress callback reports that the deadline is exceeded
+        check_deadline()?;
+        Ok(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tree::{parse_rust_code, SonarLocation},
+        verifier::assert_issues,
+    };
+
+    fn issues(regular_expression: &str, message: &str, source_code: &str) -> Vec<Issue> {
+        let tree = parse_rust_code(source_code).unwrap();
+        CommentPatternCheck::new(regular_expression, message)
+            .unwrap()
+            .check(&tree, source_code)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_comment_pattern() {
+        let source_code = r#"
+// HACK: to be removed
+/* Hack around "the" bug */
+/// See JIRA-1234
+fn main() {
+    let hack = "// HACK in a string";
+}
+"#;
+        assert_eq!(issues("", "", source_code), vec![]);
+        assert_eq!(
+            issues(r"(?i)\bhack\b", "", source_code),
+            vec![
+                Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: DEFAULT_MESSAGE.to_string(),
+                    location: SonarLocation {
+                        start_line: 2,
+                        start_column: 0,
+                        end_line: 2,
+                        end_column: 22,
+                    },
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                },
+                Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: DEFAULT_MESSAGE.to_string(),
+                    location: SonarLocation {
+                        start_line: 3,
+                        start_column: 0,
+                        end_line: 3,
+                        end_column: 27,
+                    },
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                },
+            ]
+        );
+
+        let tickets = issues("[A-Z]+-[0-9]+", "Resolve this ticket.", source_code);
+        assert_eq!(tickets.len(), 1);
+        assert_eq!(tickets[0].location.start_line, 4);
+        assert_eq!(tickets[0].message, "Resolve this ticket.");
+
+        assert_eq!(issues("\"the\"", "", source_code).len(), 1);
+        assert_eq!(
+            issues(r"^// HACK: to be removed$", "", source_code).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_annotated_comments() {
+        let check = CommentPatternCheck::new("FIXME", "Fix it.").unwrap();
+        assert_issues(
+            &check,
+            r#"
+fn add(a: i32, b: i32) -> i32 {
+    // FIXME: overflows // Noncompliant {{Fix it.}}
+//  ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    /* FIXME */ a + b // Noncompliant
+//  ^^^^^^^^^^^
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_invalid_regular_expression() {
+        assert!(matches!(
+            CommentPatternCheck::new("(unclosed", ""),
+            Err(AnalyzerError::GlobalError(message)) if message.starts_with("could not parse 'S124:regularExpression' parameter")
+        ));
+    }
+}
@@ -0,0 +1,284 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{parse_rust_code, AnalyzerError, NodeIterator, TreeSitterLocation},
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S125";
+
+const MESSAGE: &str = "Remove this commented out code.";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Sections of code should not be commented out",
+    markdown: r#"
+This rule raises an issue on the comments whose content is Rust code: statements or items which parse without errors,
+ending with `;`, `{` or `}`. The consecutive line comments are checked together, so that a statement split on several
+lines raises a single issue. Doc comments, which often contain code examples, are ignored.
+
+## Why is this an issue?
+
+Commented-out code distracts the reader from the actual code, and gets outdated as the code around it changes. The
+version control system keeps the history of the code: there is no need to keep it in comments.
+
+```noncompliant
+fn total(prices: &[u32]) -> u32 {
+    // let discount = compute_discount(prices);
+    prices.iter().sum()
+}
+```
+
+### How to fix it
+
+Remove the commented-out code.
+
+```compliant
+fn total(prices: &[u32]) -> u32 {
+    prices.iter().sum()
+}
+```
+"#,
+};
+
+pub struct CommentedOutCodeCheck;
+
+impl Rule for CommentedOutCodeCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for group in comment_groups(tree, source_code) {
+            let group: Vec<(Node<'_>, &str)> = group
+                .into_iter()
+                .filter_map(|comment| Some((comment, comment_text(comment, source_code)?)))
+                .collect();
+            if group.is_empty() {
+                continue;
+            }
+
+            let text = group
+                .iter()
+                .map(|(_, text)| *text)
+                .collect::<Vec<_>>()
+                .join("\n");
+            let runs = if is_code(&text)? {
+                vec![group.iter().map(|(comment, _)| *comment).collect()]
+            } else {
+                // Only some lines of the group may be code, e.g. after an explanation
+                code_lines(&group)?
+            };
+            for run in runs {
+                let (Some(first), Some(last)) = (run.first(), run.last()) else {
+                    continue;
+                };
+                let location = TreeSitterLocation {
+                    start_byte: first.start_byte(),
+                    end_byte: last.end_byte(),
+                    start_position: first.start_position(),
+                    end_position: last.end_position(),
+                }
+                .to_sonar_location(source_code);
+                if tracer.is_enabled() {
+                    tracer.trace(
+                        &location,
+                        &location,
+                        format!("{} comment line(s) parsed as code", run.len()),
+                    );
+                }
+                issues.push(Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: MESSAGE.to_string(),
+                    location,
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Comments of the file, the line comments alone on consecutive lines being grouped together.
+fn comment_groups<'a>(tree: &'a Tree, source_code: &str) -> Vec<Vec<Node<'a>>> {
+    let mut groups: Vec<Vec<Node<'a>>> = vec![];
+    let mut previous: Option<Node<'a>> = None;
+    for comment in NodeIterator::new(tree.root_node(), |node| {
+        matches!(node.kind(), "line_comment" | "block_comment")
+    }) {
+        let grouped = comment.kind() == "line_comment"
+            && is_alone_on_line(comment, source_code)
+            && previous.is_some_and(|previous| {
+                previous.kind() == "line_comment"
+                    && is_alone_on_line(previous, source_code)
+                    && previous.start_position().row + 1 == comment.start_position().row
+            });
+        match groups.last_mut() {
+            Some(group) if grouped => group.push(comment),
+            _ => groups.push(vec![comment]),
+        }
+        previous = Some(comment);
+    }
+    groups
+}
+
+fn is_alone_on_line(comment: Node<'_>, source_code: &str) -> bool {
+    let line_start = comment.start_byte() - comment.start_position().column;
+    source_code[line_start..comment.start_byte()]
+        .trim()
+        .is_empty()
+}
+
+/// Content of a comment without its delimiters, `None` for the doc comments.
+fn comment_text<'a>(comment: Node<'_>, source_code: &'a str) -> Option<&'a str> {
+    let text = &source_code[comment.byte_range()];
+    if let Some(content) = text.strip_prefix("//") {
+        let is_doc =
+            content.starts_with('!') || (content.starts_with('/') && !content.starts_with("//"));
+        return (!is_doc).then_some(content);
+    }
+    let content = text.strip_prefix("/*")?;
+    let content = content.strip_suffix("*/").unwrap_or(content);
+    let is_doc = content.starts_with('!')
+        || (content.starts_with('*') && !content.starts_with("**") && !content.is_empty());
+    (!is_doc).then_some(content)
+}
+
+/// Runs of consecutive comments of a group which are code on their own.
+fn code_lines<'a>(group: &[(Node<'a>, &str)]) -> Result<Vec<Vec<Node<'a>>>, AnalyzerError> {
+    let mut runs: Vec<Vec<Node<'a>>> = vec![];
+    let mut in_run = false;
+    for (comment, text) in group {
+        let code = is_code(text)?;
+        match runs.last_mut() {
+            Some(run) if code && in_run => run.push(*comment),
+            _ if code => runs.push(vec![*comment]),
+            _ => {}
+        }
+        in_run = code;
+    }
+    Ok(runs)
+}
+
+/// Whether the text parses as the statements of a block, which look like code rather than prose: a single word, or
+/// words in parentheses or braces, e.g. the placeholders of a format string, parse as an expression too.
+fn is_code(text: &str) -> Result<bool, AnalyzerError> {
+    let text = text.trim();
+    if !text.ends_with([';', '{', '}']) {
+        return Ok(false);
+    }
+
+    let wrapped = format!("fn commented_out() {{\n{}\n}}", text);
+    let tree = parse_rust_code(&wrapped)?;
+    let root = tree.root_node();
+    if root.has_error() || root.named_child_count() != 1 {
+        return Ok(false);
+    }
+    // The name of the wrapping function is an identifier too
+    let identifiers = NodeIterator::new(root, |node| {
+        matches!(
+            node.kind(),
+            "identifier" | "field_identifier" | "type_identifier"
+        )
+    })
+    .count();
+    Ok(identifiers > 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_commented_out_code() {
+        // The annotations of the statements split on several lines are separated from them by an empty line, as they
+        // would be checked with them otherwise
+        assert_issues(
+            &CommentedOutCodeCheck,
+            r#"
+// Noncompliant@+1 {{Remove this commented out code.}}
+// use std::collections::HashMap;
+fn total(prices: &[u32]) -> u32 {
+    // Noncompliant@+2
+
+    // if prices.is_empty() {
+    //     return 0;
+    // }
+    let sum = prices.iter().sum(); // println!("{}", sum);
+//                                 ^^^^^^^^^^^^^^^^^^^^^^^
+    /* let discount = 0; */ // Noncompliant
+//  ^^^^^^^^^^^^^^^^^^^^^^^
+    sum
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_mixed_comments() {
+        assert_issues(
+            &CommentedOutCodeCheck,
+            r#"
+// Noncompliant@+2
+// The total used to be computed here:
+// let total = compute();
+// log(total);
+// It is now computed lazily.
+fn main() {}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_prose() {
+        assert_issues(
+            &CommentedOutCodeCheck,
+            r#"
+// Returns the total (without taxes).
+// TODO
+// (deprecated)
+// mem::size_of::<T>()
+// {:#}
+// SAFETY: the pointer is valid for reads.
+// See https://example.com/docs.
+/// let x = 42;
+//! fn example() {}
+/** let y = 42; */
+fn main() {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,271 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, RuleType, SecondaryLocation},
+    rule_description::RuleDescription,
+    rules::{
+        identical_branches_check::are_equivalent,
+        rule::{Rule, RuleTracer},
+    },
+    tree::{child_of_kind, AnalyzerError, NodeIterator, TreeSitterLocation},
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S1862";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Related \"if/else if\" statements should not have the same condition",
+    markdown: r#"
+This rule raises an issue on the conditions of an `if` chain which are the same as a previous condition of the chain,
+and on the patterns of a `match` which are the same as the pattern of a previous arm without a guard. The comments
+and the formatting are ignored.
+
+## Why is this an issue?
+
+A branch whose condition was already checked by a previous branch is never executed, and the same goes for a `match`
+arm whose pattern was already matched. This is usually a copy-paste error.
+
+```noncompliant
+if command == "stop" {
+    stop();
+} else if command == "run" {
+    run();
+} else if command == "stop" {
+    quit();
+}
+```
+
+### How to fix it
+
+Fix the condition, or remove the unreachable branch.
+
+```compliant
+if command == "stop" {
+    stop();
+} else if command == "run" {
+    run();
+} else if command == "quit" {
+    quit();
+}
+```
+
+## Resources
+
+### Documentation
+
+- Clippy Lints - [ifs_same_cond](https://rust-lang.github.io/rust-clippy/master/index.html#ifs_same_cond)
+"#,
+};
+
+pub struct DuplicateConditionCheck;
+
+impl Rule for DuplicateConditionCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn rule_type(&self) -> RuleType {
+        RuleType::Bug
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for node in NodeIterator::new(tree.root_node(), |node| {
+            node.kind() == "match_expression"
+                || (node.kind() == "if_expression"
+                    && node
+                        .parent()
+                        .is_none_or(|parent| parent.kind() != "else_clause"))
+        }) {
+            let (kind, candidates) = if node.kind() == "if_expression" {
+                ("condition", chain_conditions(node))
+            } else {
+                ("pattern", arm_patterns(node, source_code, tracer))
+            };
+            for (index, (candidate, _)) in candidates.iter().enumerate() {
+                let Some((original, _)) = candidates[..index].iter().find(|(original, guarded)| {
+                    !guarded && are_equivalent(*original, *candidate, source_code)
+                }) else {
+                    continue;
+                };
+                let original_location = TreeSitterLocation::from_tree_sitter_node(*original)
+                    .to_sonar_location(source_code);
+                issues.push(Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: format!(
+                        "This {kind} duplicates the one on line {}.",
+                        original_location.start_line
+                    ),
+                    location: TreeSitterLocation::from_tree_sitter_node(*candidate)
+                        .to_sonar_location(source_code),
+                    secondary_locations: vec![SecondaryLocation {
+                        message: "Original".to_string(),
+                        location: original_location,
+                        file: None,
+                    }],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// The conditions of the `if` and `else if` branches of a chain. None of them is guarded.
+fn chain_conditions(if_expression: Node<'_>) -> Vec<(Node<'_>, bool)> {
+    let mut conditions = vec![];
+    let mut current = Some(if_expression);
+    while let Some(if_expression) = current {
+        if let Some(condition) = if_expression.child_by_field_name("condition") {
+            conditions.push((condition, false));
+        }
+        current = if_expression
+            .child_by_field_name("alternative")
+            .and_then(|alternative| {
+                let mut cursor = alternative.walk();
+                let next = alternative
+                    .named_children(&mut cursor)
+                    .find(|child| child.kind() == "if_expression");
+                next
+            });
+    }
+    conditions
+}
+
+/// The alternatives of the patterns of the arms of a `match`, with whether their arm has a guard. The wildcard
+/// patterns are left to the compiler, which reports the arms following them as unreachable, and the arms with
+/// attributes are ignored, as they are usually compiled under exclusive `cfg` conditions.
+fn arm_patterns<'a>(
+    match_expression: Node<'a>,
+    source_code: &str,
+    tracer: &mut dyn RuleTracer,
+) -> Vec<(Node<'a>, bool)> {
+    let mut patterns = vec![];
+    let Some(body) = match_expression.child_by_field_name("body") else {
+        return patterns;
+    };
+    let mut cursor = body.walk();
+    for arm in body
+        .named_children(&mut cursor)
+        .filter(|arm| arm.kind() == "match_arm")
+    {
+        let Some(match_pattern) = arm.child_by_field_name("pattern") else {
+            continue;
+        };
+        if child_of_kind(arm, "attribute_item").is_some() {
+            if tracer.is_enabled() {
+                let location = TreeSitterLocation::from_tree_sitter_node(match_pattern)
+                    .to_sonar_location(source_code);
+                tracer.trace(&location, &location, "attributed arm".to_string());
+            }
+            continue;
+        }
+        let guard = match_pattern.child_by_field_name("condition");
+        let pattern = match_pattern
+            .named_child(0)
+            .filter(|pattern| Some(*pattern) != guard && !pattern.is_extra());
+        let Some(pattern) = pattern else {
+            if tracer.is_enabled() {
+                let location = TreeSitterLocation::from_tree_sitter_node(match_pattern)
+                    .to_sonar_location(source_code);
+                tracer.trace(&location, &location, "wildcard pattern".to_string());
+            }
+            continue;
+        };
+        push_alternatives(pattern, guard.is_some(), &mut patterns);
+    }
+    patterns
+}
+
+fn push_alternatives<'a>(pattern: Node<'a>, guarded: bool, patterns: &mut Vec<(Node<'a>, bool)>) {
+    if pattern.kind() == "or_pattern" {
+        let mut cursor = pattern.walk();
+        let alternatives: Vec<_> = pattern
+            .named_children(&mut cursor)
+            .filter(|alternative| !alternative.is_extra())
+            .collect();
+        for alternative in alternatives {
+            push_alternatives(alternative, guarded, patterns);
+        }
+    } else {
+        patterns.push((pattern, guarded));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_duplicate_condition() {
+        assert_issues(
+            &DuplicateConditionCheck,
+            r#"
+fn main() {
+    if a == 1 {
+        one();
+    } else if a == 2 {
+        two();
+    } else if a  ==  1 /* again */ { // Noncompliant {{This condition duplicates the one on line 3.}}
+//            ^^^^^^^^
+        three();
+    } else if let Some(b) = c {
+        four();
+    } else if let Some(b) = c { // Noncompliant {{This condition duplicates the one on line 10.}}
+        five();
+    }
+    if a {
+        if a {}
+    }
+    if a {} else { if a {} }
+    match x {
+        Some(1) if ready => 1,
+        Some(1) => 2,
+        Some(2) | None => 3,
+        Some(3) | Some(2) => 4, // Noncompliant {{This pattern duplicates the one on line 22.}}
+//                ^^^^^^^
+        Some(1) => 5, // Noncompliant {{This pattern duplicates the one on line 21.}}
+        _ => 6,
+        _ => 7,
+    }
+    match y {
+        #[cfg(unix)]
+        Ok(0) => 1,
+        #[cfg(windows)]
+        Ok(0) => 2,
+        _ => 3,
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,245 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, SecondaryLocation},
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::test_code::TestCode,
+};
+use std::collections::HashMap;
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S1192";
+
+/// The strings shorter than this number of characters, without the quotes, are not reported.
+const MINIMUM_LENGTH: usize = 5;
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "String literals should not be duplicated",
+    markdown: r#"
+This rule raises an issue on the string literals appearing at least `threshold` times in a file. The issue is reported
+on the first occurrence, with the other occurrences as secondary locations. The strings with fewer than 5 characters
+are ignored, and so are the strings of the `const` and `static` items, of the attributes, of the `macro_rules!`
+definitions, whose attributes are not parsed, and of the test code.
+
+## Why is this an issue?
+
+Duplicated string literals make the code harder to change: each occurrence must be found and updated, and missing one
+introduces a bug.
+
+```noncompliant
+fn connect(config: &Config) -> Result<Connection, Error> {
+    let host = config.get("database.host").ok_or(Error::Missing("database.host"))?;
+    log::info!("connecting to {}", config.get("database.host").unwrap_or_default());
+    Connection::open(host)
+}
+```
+
+### How to fix it
+
+Define a constant and use it instead of the literal.
+
+```compliant
+const DATABASE_HOST: &str = "database.host";
+
+fn connect(config: &Config) -> Result<Connection, Error> {
+    let host = config.get(DATABASE_HOST).ok_or(Error::Missing(DATABASE_HOST))?;
+    log::info!("connecting to {}", config.get(DATABASE_HOST).unwrap_or_default());
+    Connection::open(host)
+}
+```
+"#,
+};
+
+pub struct DuplicatedStringCheck {
+    threshold: usize,
+    /// The file only contains test code, see `TestCode`.
+    test_file: bool,
+}
+
+impl DuplicatedStringCheck {
+    pub fn new(threshold: usize, test_file: bool) -> Self {
+        DuplicatedStringCheck {
+            threshold,
+            test_file,
+        }
+    }
+}
+
+impl Rule for DuplicatedStringCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let test_code = TestCode::find(tree, source_code, self.test_file);
+        let mut literals: Vec<&str> = vec![];
+        let mut occurrences: HashMap<&str, Vec<Node<'_>>> = HashMap::new();
+        for literal in NodeIterator::new(tree.root_node(), |node| {
+            matches!(node.kind(), "string_literal" | "raw_string_literal")
+        }) {
+            let text = &source_code[literal.byte_range()];
+            if content_length(text) < MINIMUM_LENGTH {
+                continue;
+            }
+            if let Some(reason) = excluded_context(literal) {
+                if tracer.is_enabled() {
+                    let location = TreeSitterLocation::from_tree_sitter_node(literal)
+                        .to_sonar_location(source_code);
+                    tracer.trace(&location, &location, reason.to_string());
+                }
+                continue;
+            }
+            if test_code.contains_node(literal) {
+                continue;
+            }
+            let nodes = occurrences.entry(text).or_default();
+            if nodes.is_empty() {
+                literals.push(text);
+            }
+            nodes.push(literal);
+        }
+
+        let mut issues = vec![];
+        for text in literals {
+            let nodes = &occurrences[text];
+            if nodes.len() < self.threshold {
+                continue;
+            }
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: format!(
+                    "Define a constant instead of duplicating this literal {} {} times.",
+                    text,
+                    nodes.len()
+                ),
+                location: TreeSitterLocation::from_tree_sitter_node(nodes[0])
+                    .to_sonar_location(source_code),
+                secondary_locations: nodes[1..]
+                    .iter()
+                    .map(|node| SecondaryLocation {
+                        message: "Duplication".to_string(),
+                        location: TreeSitterLocation::from_tree_sitter_node(*node)
+                            .to_sonar_location(source_code),
+                        file: None,
+                    })
+                    .collect(),
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Number of characters of the literal, without its prefix and quotes.
+fn content_length(text: &str) -> usize {
+    match (text.find('"'), text.rfind('"')) {
+        (Some(start), Some(end)) if start < end => text[start + 1..end].chars().count(),
+        _ => 0,
+    }
+}
+
+/// The reason why the literal is ignored in its context, if it is.
+fn excluded_context(literal: Node<'_>) -> Option<&'static str> {
+    let mut current = literal;
+    while let Some(ancestor) = current.parent() {
+        match ancestor.kind() {
+            "const_item" | "static_item" => return Some("string of a constant"),
+            "attribute_item" | "inner_attribute_item" => return Some("string in an attribute"),
+            "macro_definition" => return Some("string in a macro definition"),
+            "function_item" => return None,
+            _ => {}
+        }
+        current = ancestor;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tree::parse_rust_code, verifier::assert_issues};
+
+    const SOURCE_CODE: &str = r##"
+const HOST: &str = "database.host";
+
+#[deprecated(note = "use connect")]
+#[cfg(feature = "database")]
+fn open(config: &Config) {
+    let host = config.get("database.host"); // Noncompliant {{Define a constant instead of duplicating this literal "database.host" 3 times.}}
+//                        ^^^^^^^^^^^^^^^
+    let port = config.get("database.port"); // Noncompliant {{Define a constant instead of duplicating this literal "database.port" 3 times.}}
+    log::info!("connecting to {}", config.get("database.host"));
+    let missing = Error::Missing(r"database.host");
+    let raw = r#"database.port"#;
+    let short = ["abcd", "abcd", "abcd"];
+    let other = "database.port";
+//              ^^^^^^^^^^^^^^^< {{Duplication}}
+}
+
+#[deprecated(note = "use connect")]
+#[cfg(feature = "database")]
+fn close() {
+    let host = "database.host";
+    let port = "database.port";
+//             ^^^^^^^^^^^^^^^<
+}
+
+macro_rules! open {
+    () => {
+        #[cfg(feature = "database.host")]
+        fn open() {}
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    fn test() {
+        assert_eq!(get("database.host"), "database.host");
+    }
+}
+"##;
+
+    #[test]
+    fn test_duplicated_string() {
+        assert_issues(&DuplicatedStringCheck::new(3, false), SOURCE_CODE);
+    }
+
+    #[test]
+    fn test_threshold() {
+        let tree = parse_rust_code(SOURCE_CODE).unwrap();
+        assert!(DuplicatedStringCheck::new(4, false)
+            .check(&tree, SOURCE_CODE)
+            .unwrap()
+            .is_empty());
+    }
+}
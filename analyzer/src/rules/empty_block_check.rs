@@ -0,0 +1,167 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::data_flow::pattern_identifiers,
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S108";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Nested blocks of code should not be left empty",
+    markdown: r#"
+This rule raises an issue on the empty blocks of `if` and `else` branches, and of the `match` arms binding variables.
+The empty arms binding nothing, e.g. `None => {}` or `_ => {}`, are the idiomatic way to ignore a case and are not
+reported. A block containing a comment is not empty.
+
+## Why is this an issue?
+
+An empty block is either dead code, or a branch whose code was forgotten. The reader cannot tell which one.
+
+```noncompliant
+if let Err(error) = save(&document) {
+}
+```
+
+### How to fix it
+
+Complete the block, remove it, or explain in a comment why nothing is done.
+
+```compliant
+if let Err(error) = save(&document) {
+    log::warn!("the document was not saved: {}", error);
+}
+```
+"#,
+};
+
+pub struct EmptyBlockCheck;
+
+impl Rule for EmptyBlockCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for block in NodeIterator::new(tree.root_node(), |node| node.kind() == "block") {
+            if !is_empty_block(block) {
+                continue;
+            }
+            let Some(parent) = block.parent() else {
+                continue;
+            };
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(block).to_sonar_location(source_code);
+            match parent.kind() {
+                "if_expression" | "else_clause" => {}
+                "match_arm" => {
+                    if !binds_variables(parent, source_code) {
+                        if tracer.is_enabled() {
+                            tracer.trace(
+                                &location,
+                                &location,
+                                "match arm ignoring a case".to_string(),
+                            );
+                        }
+                        continue;
+                    }
+                }
+                _ => continue,
+            }
+
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: "Either remove or fill this block of code.".to_string(),
+                location,
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Whether the block contains neither code nor comments.
+pub(crate) fn is_empty_block(block: Node<'_>) -> bool {
+    block.kind() == "block" && block.named_child_count() == 0
+}
+
+/// Whether the pattern of the match arm binds variables, which the empty arm ignores.
+pub(crate) fn binds_variables(arm: Node<'_>, source_code: &str) -> bool {
+    let Some(pattern) = arm.child_by_field_name("pattern") else {
+        return false;
+    };
+    let guard = pattern.child_by_field_name("condition");
+    pattern_identifiers(pattern, source_code)
+        .into_iter()
+        .any(|identifier| {
+            guard.is_none_or(|guard| !guard.byte_range().contains(&identifier.start_byte()))
+                && !source_code[identifier.byte_range()].starts_with('_')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_empty_block() {
+        assert_issues(
+            &EmptyBlockCheck,
+            r#"
+fn main() {
+    // Noncompliant@+1 {{Either remove or fill this block of code.}}
+    if ready() {
+    } else if done() {
+        // Nothing to do
+    } else {} // Noncompliant
+//         ^^
+    match state() {
+        State::Done(result) => {} // Noncompliant
+//                             ^^
+        State::Busy(_) => {}
+        State::Failed(error) if error.is_fatal() => { /* Retried later */ }
+        State::Failed(_error) => {}
+        State::Idle if idle() => {}
+        _ => {}
+    }
+    while next() {}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,195 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::{
+        empty_block_check::is_empty_block,
+        rule::{Rule, RuleTracer},
+    },
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::metrics::preceding_attributes,
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S1186";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Functions should not be empty",
+    markdown: r#"
+This rule raises an issue on the functions and methods whose body is empty. A body containing a comment is not empty,
+and the functions with an `#[allow(...)]` attribute, or in an `impl` or a `trait` with one, are ignored: both explain
+that the function is empty on purpose, e.g. for a default method of a trait. The generic functions without parameters,
+which assert at compile time that a type satisfies their bounds, are ignored too.
+
+## Why is this an issue?
+
+An empty function is either unfinished, or does nothing on purpose, e.g. to implement a trait whose method is not
+needed. The reader cannot tell which one.
+
+```noncompliant
+impl Drop for Connection {
+    fn drop(&mut self) {}
+}
+```
+
+### How to fix it
+
+Complete the function, or explain in a comment why it is empty.
+
+```compliant
+impl Drop for Connection {
+    fn drop(&mut self) {
+        // The socket is closed by the pool
+    }
+}
+```
+"#,
+};
+
+pub struct EmptyFunctionCheck;
+
+impl Rule for EmptyFunctionCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for function in NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item") {
+            let (Some(name), Some(body)) = (
+                function.child_by_field_name("name"),
+                function.child_by_field_name("body"),
+            ) else {
+                continue;
+            };
+            if !is_empty_block(body) {
+                continue;
+            }
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(name).to_sonar_location(source_code);
+            if is_allowed(function, source_code) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, "'#[allow]' attribute".to_string());
+                }
+                continue;
+            }
+            if is_bound_assertion(function) {
+                if tracer.is_enabled() {
+                    tracer.trace(
+                        &location,
+                        &location,
+                        "generic function without parameters".to_string(),
+                    );
+                }
+                continue;
+            }
+
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: "Add a nested comment explaining why this function is empty, or complete the implementation.".to_string(),
+                location,
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Whether the function, or the `impl` or `trait` declaring it, has an `#[allow(...)]` attribute.
+fn is_allowed(function: Node<'_>, source_code: &str) -> bool {
+    let container = function
+        .parent()
+        .filter(|parent| parent.kind() == "declaration_list")
+        .and_then(|list| list.parent())
+        .filter(|item| matches!(item.kind(), "impl_item" | "trait_item"));
+    [Some(function), container]
+        .into_iter()
+        .flatten()
+        .flat_map(preceding_attributes)
+        .any(|attribute| {
+            attribute.kind() == "attribute_item"
+                && source_code[attribute.byte_range()]
+                    .trim_start_matches("#[")
+                    .trim_start()
+                    .starts_with("allow")
+        })
+}
+
+/// Whether the function only checks the bounds of its type parameters when called, e.g.
+/// `fn assert_send<T: Send>() {}`.
+fn is_bound_assertion(function: Node<'_>) -> bool {
+    function.child_by_field_name("type_parameters").is_some()
+        && function
+            .child_by_field_name("parameters")
+            .is_some_and(|parameters| parameters.named_child_count() == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_empty_function() {
+        assert_issues(
+            &EmptyFunctionCheck,
+            r#"
+fn setup() {} // Noncompliant {{Add a nested comment explaining why this function is empty, or complete the implementation.}}
+// ^^^^^
+
+fn teardown() {
+    // Nothing to clean up
+}
+
+trait Visitor {
+    fn visit(&mut self) {} // Noncompliant
+//     ^^^^^
+
+    #[allow(unused_variables)]
+    fn leave(&mut self, depth: usize) {}
+}
+
+#[allow(clippy::needless_lifetimes)]
+impl Visitor for () {
+    fn visit(&mut self) {}
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {} // Noncompliant
+}
+
+fn assert_send<T: Send>() {}
+"#,
+        );
+    }
+}
@@ -0,0 +1,135 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{split_lines, AnalyzerError, SonarLocation},
+    visitors::function_metrics::lines_of_code,
+};
+use tree_sitter::Tree;
+
+const RULE_KEY: &str = "S104";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Files should not have too many lines of code",
+    markdown: r#"
+This rule raises an issue on the first line of a file which has more lines of code than the maximum. Lines with only
+comments or blank lines do not count.
+
+## Why is this an issue?
+
+When a source file grows too much, it can accumulate numerous responsibilities and become challenging to understand
+and maintain.
+
+Above a specific threshold, refactor the file into smaller modules, each focusing on a well-defined task. Those smaller
+modules will be easier to understand and probably easier to test.
+
+### How to fix it
+
+Move the items which belong together, e.g. a type and its implementations, to their own module.
+"#,
+};
+
+pub struct FileLengthCheck {
+    maximum: i32,
+}
+
+impl FileLengthCheck {
+    pub fn new(maximum: i32) -> Self {
+        FileLengthCheck { maximum }
+    }
+}
+
+impl Rule for FileLengthCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let lines = lines_of_code(tree.root_node());
+        let first_line = split_lines(source_code).next().unwrap_or_default();
+        let location = SonarLocation {
+            start_line: 1,
+            start_column: 0,
+            end_line: 1,
+            end_column: first_line.encode_utf16().count(),
+        };
+        if tracer.is_enabled() {
+            let comparison = if lines > self.maximum {
+                "above"
+            } else {
+                "not above"
+            };
+            tracer.trace(
+                &location,
+                &location,
+                format!(
+                    "file has {} lines of code, {} the maximum of {}",
+                    lines, comparison, self.maximum
+                ),
+            );
+        }
+        if lines <= self.maximum {
+            return Ok(vec![]);
+        }
+
+        Ok(vec![Issue {
+            rule_key: RULE_KEY.to_string(),
+            message: format!(
+                "This file has {} lines of code, which is greater than the {} authorized. Split it into smaller modules.",
+                lines, self.maximum
+            ),
+            location,
+            secondary_locations: vec![],
+            severity: None,
+            quick_fixes: vec![],
+            fingerprint: None,
+            hotspot: None,
+        }])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_file_length() {
+        let source_code = "//! Général\n\nfn main() {\n    let x = 1;\n}\n";
+        assert_issues(&FileLengthCheck::new(3), source_code);
+        assert_issues(
+            &FileLengthCheck::new(2),
+            &source_code.replacen(
+                '\n',
+                " // Noncompliant {{This file has 3 lines of code, which is greater than the 2 authorized. Split it into smaller modules.}}\n",
+                1,
+            ),
+        );
+    }
+}
@@ -0,0 +1,185 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::function_metrics::lines_of_code,
+};
+use tree_sitter::Tree;
+
+const RULE_KEY: &str = "S138";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Functions should not have too many lines of code",
+    markdown: r#"
+This rule raises an issue when the body of a function or of a closure has more lines of code than the maximum. Lines
+with only comments or blank lines do not count.
+
+## Why is this an issue?
+
+A function that grows too large tends to aggregate too many responsibilities. Such functions inevitably become harder
+to understand and therefore harder to maintain and to test.
+
+Above a specific threshold, it is strongly advised to refactor the function into smaller functions which focus on
+well-defined tasks. Those smaller functions will not only be easier to understand, but also probably easier to test.
+
+### How to fix it
+
+Extract the distinct steps of the function into their own functions, named after what they do.
+
+## Resources
+
+### Documentation
+
+- Clippy Lints - [too_many_lines](https://rust-lang.github.io/rust-clippy/master/index.html#too_many_lines)
+"#,
+};
+
+pub struct FunctionLengthCheck {
+    max: i32,
+}
+
+impl FunctionLengthCheck {
+    pub fn new(max: i32) -> Self {
+        FunctionLengthCheck { max }
+    }
+}
+
+impl Rule for FunctionLengthCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for function in NodeIterator::new(tree.root_node(), |node| {
+            matches!(node.kind(), "function_item" | "closure_expression")
+        }) {
+            // Functions are reported on their name, closures on their parameters
+            let (kind, name_field) = if function.kind() == "function_item" {
+                ("function", "name")
+            } else {
+                ("closure", "parameters")
+            };
+            let (Some(name), Some(body)) = (
+                function.child_by_field_name(name_field),
+                function.child_by_field_name("body"),
+            ) else {
+                continue;
+            };
+
+            let lines = lines_of_code(body);
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(name).to_sonar_location(source_code);
+            if tracer.is_enabled() {
+                let comparison = if lines > self.max {
+                    "above"
+                } else {
+                    "not above"
+                };
+                tracer.trace(
+                    &location,
+                    &location,
+                    format!(
+                        "body has {} lines of code, {} the maximum of {}",
+                        lines, comparison, self.max
+                    ),
+                );
+            }
+            if lines > self.max {
+                issues.push(Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: format!(
+                        "This {} has {} lines of code, which is greater than the {} authorized. Split it into smaller functions.",
+                        kind, lines, self.max
+                    ),
+                    location,
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_function_length() {
+        assert_issues(
+            &FunctionLengthCheck::new(3),
+            r#"
+fn short() {
+    let x = 1;
+}
+
+fn long() { // Noncompliant {{This function has 4 lines of code, which is greater than the 3 authorized. Split it into smaller functions.}}
+// ^^^^
+    // Comments and blank lines do not count
+
+    let x = 1;
+    let y = 2;
+}
+
+fn declaration_only();
+
+fn main() { // Noncompliant {{This function has 6 lines of code, which is greater than the 3 authorized. Split it into smaller functions.}}
+    let f = |x: i32| {
+        x + 1
+    };
+    let g = |x| x;
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_closure_length() {
+        assert_issues(
+            &FunctionLengthCheck::new(2),
+            r#"
+fn main() { // Noncompliant
+    let f = |x: i32| { // Noncompliant {{This closure has 3 lines of code, which is greater than the 2 authorized. Split it into smaller functions.}}
+//          ^^^^^^^^
+        x + 1
+    };
+    let g = |x| x;
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,263 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, RuleType},
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::test_code::TestCode,
+};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S1313";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Using hard-coded IP addresses is security-sensitive",
+    markdown: r#"
+This security hotspot is raised on the string literals made of an IPv4 or IPv6 address, optionally followed by a port,
+and on the URLs whose host is an IP address. The loopback, unspecified and broadcast addresses are ignored, and so are
+the ranges reserved for the documentation (`192.0.2.0/24`, `198.51.100.0/24`, `203.0.113.0/24` and `2001:db8::/32`),
+the attributes and the test code. The hotspots reviewed as safe in the 'reviewed_hotspots' setting are not reported
+to SonarQube.
+
+## Why is this an issue?
+
+A hard-coded IP address ties the program to one deployment: moving the service, switching to IPv6 or running the
+program in another environment requires a code change and a new release. IP addresses are also sensitive information
+which reveals the topology of the network.
+
+The address may however be safe, for instance the one of a well-known public service: review the code to decide.
+
+```noncompliant
+fn connect() -> std::io::Result<TcpStream> {
+    TcpStream::connect("10.1.24.8:5432")
+}
+```
+
+### How to fix it
+
+Read the address from the configuration or the environment, or use a host name resolved by the DNS.
+
+```compliant
+fn connect(config: &Config) -> std::io::Result<TcpStream> {
+    TcpStream::connect(&config.database_address)
+}
+```
+
+## Resources
+
+### Standards
+
+- OWASP - [Top 10 2021 Category A1 - Broken Access Control](https://owasp.org/Top10/A01_2021-Broken_Access_Control/)
+- OWASP - [Top 10 2017 Category A3 - Sensitive Data Exposure](https://owasp.org/www-project-top-ten/2017/A3_2017-Sensitive_Data_Exposure)
+"#,
+};
+
+pub struct HardcodedIpCheck {
+    /// The file only contains test code, see `TestCode`.
+    test_file: bool,
+}
+
+impl HardcodedIpCheck {
+    pub fn new(test_file: bool) -> Self {
+        HardcodedIpCheck { test_file }
+    }
+}
+
+impl Rule for HardcodedIpCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn rule_type(&self) -> RuleType {
+        RuleType::SecurityHotspot
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let test_code = TestCode::find(tree, source_code, self.test_file);
+        let mut issues = vec![];
+        for literal in NodeIterator::new(tree.root_node(), |node| {
+            matches!(node.kind(), "string_literal" | "raw_string_literal")
+        }) {
+            let text = &source_code[literal.byte_range()];
+            let Some((address, ip)) = literal_content(text).and_then(ip_address) else {
+                continue;
+            };
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(literal).to_sonar_location(source_code);
+            if let Some(reason) = ignored_address(ip) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, reason.to_string());
+                }
+                continue;
+            }
+            if is_in_attribute(literal) || test_code.contains_node(literal) {
+                continue;
+            }
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: format!(
+                    "Make sure using this hard-coded IP address \"{address}\" is safe here."
+                ),
+                location,
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// The text of the literal, without its prefix and quotes.
+fn literal_content(text: &str) -> Option<&str> {
+    match (text.find('"'), text.rfind('"')) {
+        (Some(start), Some(end)) if start < end => Some(&text[start + 1..end]),
+        _ => None,
+    }
+}
+
+/// The IP address which is the whole text, optionally followed by a port, or the host of the URL.
+fn ip_address(text: &str) -> Option<(&str, IpAddr)> {
+    let host = match text.split_once("://") {
+        Some((scheme, rest))
+            if !scheme.is_empty() && scheme.chars().all(|c| c.is_ascii_alphanumeric()) =>
+        {
+            let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+            authority.rsplit('@').next().unwrap_or_default()
+        }
+        Some(_) => return None,
+        None => text,
+    };
+    if let Some(bracketed) = host.strip_prefix('[') {
+        let (address, rest) = bracketed.split_once(']')?;
+        if !(rest.is_empty() || is_port(rest)) {
+            return None;
+        }
+        return Some((address, IpAddr::V6(parse_ipv6(address)?)));
+    }
+    if let Ok(ip) = host.parse::<Ipv4Addr>() {
+        return Some((host, IpAddr::V4(ip)));
+    }
+    if let Some((address, port)) = host.rsplit_once(':') {
+        if let Ok(ip) = address.parse::<Ipv4Addr>() {
+            return is_port(&format!(":{port}")).then_some((address, IpAddr::V4(ip)));
+        }
+    }
+    Some((host, IpAddr::V6(parse_ipv6(host)?)))
+}
+
+/// The IPv6 address, which must contain a digit, so that words like `add::bad` are not considered addresses.
+fn parse_ipv6(text: &str) -> Option<Ipv6Addr> {
+    if !text.chars().any(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    text.parse().ok()
+}
+
+fn is_port(text: &str) -> bool {
+    text.strip_prefix(':')
+        .is_some_and(|port| port.parse::<u16>().is_ok())
+}
+
+/// The reason why the address is not reported, if it is not.
+fn ignored_address(ip: IpAddr) -> Option<&'static str> {
+    let ip = match ip {
+        IpAddr::V6(ip) => ip.to_ipv4_mapped().map_or(IpAddr::V6(ip), IpAddr::V4),
+        ip => ip,
+    };
+    if ip.is_loopback() {
+        return Some("loopback address");
+    }
+    if ip.is_unspecified() {
+        return Some("unspecified address");
+    }
+    let documentation = match ip {
+        IpAddr::V4(ip) => {
+            if ip.is_broadcast() {
+                return Some("broadcast address");
+            }
+            matches!(
+                ip.octets(),
+                [192, 0, 2, _] | [198, 51, 100, _] | [203, 0, 113, _]
+            )
+        }
+        IpAddr::V6(ip) => matches!(ip.segments(), [0x2001, 0xdb8, ..]),
+    };
+    documentation.then_some("documentation address")
+}
+
+fn is_in_attribute(node: Node<'_>) -> bool {
+    let mut current = node;
+    while let Some(parent) = current.parent() {
+        if matches!(parent.kind(), "attribute_item" | "inner_attribute_item") {
+            return true;
+        }
+        current = parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_hardcoded_ip() {
+        assert_issues(
+            &HardcodedIpCheck::new(false),
+            r#"
+#[doc(alias = "10.0.0.1")]
+fn connect() {
+    let database = "10.1.24.8"; // Noncompliant {{Make sure using this hard-coded IP address "10.1.24.8" is safe here.}}
+//                 ^^^^^^^^^^^
+    let cache = std::net::TcpStream::connect("172.16.0.4:6379"); // Noncompliant {{Make sure using this hard-coded IP address "172.16.0.4" is safe here.}}
+//                                           ^^^^^^^^^^^^^^^^^
+    let api = "https://user@192.168.1.20:8443/v1?query=1"; // Noncompliant {{Make sure using this hard-coded IP address "192.168.1.20" is safe here.}}
+    let ipv6 = r"[fe80::1ff:fe23:4567:890a]:80"; // Noncompliant {{Make sure using this hard-coded IP address "fe80::1ff:fe23:4567:890a" is safe here.}}
+//             ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+    let mapped = "::ffff:10.0.0.1"; // Noncompliant {{Make sure using this hard-coded IP address "::ffff:10.0.0.1" is safe here.}}
+    let local = ["127.0.0.1", "127.0.0.1:8080", "::1", "http://[::1]/", "0.0.0.0:80", "::"];
+    let other = ["255.255.255.255", "192.0.2.10", "198.51.100.7", "203.0.113.1", "2001:db8::8a2e:370:7334"];
+    let not_addresses = ["1.2.3", "256.1.1.1", "10.0.0.1 is down", "add::bad", "std::io", "10.0.0.1:70000"];
+}
+
+#[cfg(test)]
+mod tests {
+    fn test() {
+        let address = "10.0.0.2";
+    }
+}
+"#,
+        );
+    }
+}
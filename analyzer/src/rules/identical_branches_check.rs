@@ -0,0 +1,513 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, RuleType, SecondaryLocation},
+    rule_description::RuleDescription,
+    rules::{
+        empty_block_check::binds_variables,
+        rule::{Rule, RuleTracer},
+    },
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+};
+use tree_sitter::{Node, Tree};
+
+const ALL_BRANCHES_DESCRIPTION: RuleDescription = RuleDescription {
+    key: "S3923",
+    title:
+        "All branches in a conditional structure should not have exactly the same implementation",
+    markdown: r#"
+This rule raises an issue on the `if` chains ending with an `else`, and on the `match` expressions, whose branches all
+have the same code. The comments are ignored, and so are the `match` expressions having an arm binding variables.
+
+## Why is this an issue?
+
+When all the branches do the same thing, the condition is useless: either it is a copy-paste error, or the
+conditional structure should be removed.
+
+```noncompliant
+let delay = if retry { backoff(attempt) } else { backoff(attempt) };
+```
+
+### How to fix it
+
+Fix the branches which should differ, or remove the conditional structure.
+
+```compliant
+let delay = if retry { backoff(attempt) } else { Duration::ZERO };
+```
+"#,
+};
+
+const DUPLICATE_BRANCH_DESCRIPTION: RuleDescription = RuleDescription {
+    key: "S1871",
+    title:
+        "Two branches in a conditional structure should not have exactly the same implementation",
+    markdown: r#"
+This rule raises an issue on the branches of an `if` chain, and on the arms of a `match`, having the same code as a
+previous branch. The comments are ignored, and so are the branches with a single line of code, as well as the `match`
+arms binding variables, which often cannot be merged. When all the branches are identical, S3923 raises an issue
+instead.
+
+## Why is this an issue?
+
+Two branches with the same code are either a copy-paste error, or a duplication which should be removed by merging
+the conditions or the patterns.
+
+```noncompliant
+match command {
+    Command::Stop => {
+        flush(&mut output)?;
+        return Ok(());
+    }
+    Command::Quit => {
+        flush(&mut output)?;
+        return Ok(());
+    }
+    Command::Run(task) => run(task),
+}
+```
+
+### How to fix it
+
+Fix the branch which should differ, or merge the branches.
+
+```compliant
+match command {
+    Command::Stop | Command::Quit => {
+        flush(&mut output)?;
+        return Ok(());
+    }
+    Command::Run(task) => run(task),
+}
+```
+"#,
+};
+
+/// Reports the conditional structures whose branches are all identical.
+pub struct AllBranchesIdenticalCheck;
+
+/// Reports the branches identical to a previous branch of the same conditional structure.
+pub struct DuplicateBranchCheck;
+
+impl Rule for AllBranchesIdenticalCheck {
+    fn key(&self) -> &'static str {
+        ALL_BRANCHES_DESCRIPTION.key
+    }
+
+    fn description(&self) -> RuleDescription {
+        ALL_BRANCHES_DESCRIPTION
+    }
+
+    fn rule_type(&self) -> RuleType {
+        RuleType::Bug
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for structure in conditional_structures(tree, source_code) {
+            if !all_identical(&structure, source_code) {
+                continue;
+            }
+            let location = TreeSitterLocation::from_tree_sitter_node(structure.keyword)
+                .to_sonar_location(source_code);
+            if structure.binds_variables {
+                if tracer.is_enabled() {
+                    tracer.trace(
+                        &location,
+                        &location,
+                        "match arm binding variables".to_string(),
+                    );
+                }
+                continue;
+            }
+            issues.push(Issue {
+                rule_key: self.key().to_string(),
+                message: "Remove this conditional structure or edit its code blocks so that they're not all the same."
+                    .to_string(),
+                location,
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+impl Rule for DuplicateBranchCheck {
+    fn key(&self) -> &'static str {
+        DUPLICATE_BRANCH_DESCRIPTION.key
+    }
+
+    fn description(&self) -> RuleDescription {
+        DUPLICATE_BRANCH_DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for structure in conditional_structures(tree, source_code) {
+            if all_identical(&structure, source_code) {
+                continue;
+            }
+            for (index, branch) in structure.branches.iter().enumerate() {
+                if code_lines(branch.body) < 2 {
+                    continue;
+                }
+                let Some(original) = structure.branches[..index]
+                    .iter()
+                    .find(|original| are_equivalent(original.body, branch.body, source_code))
+                else {
+                    continue;
+                };
+                let location = TreeSitterLocation::from_tree_sitter_node(branch.body)
+                    .to_sonar_location(source_code);
+                if branch.binds_variables || original.binds_variables {
+                    if tracer.is_enabled() {
+                        tracer.trace(
+                            &location,
+                            &location,
+                            "match arm binding variables".to_string(),
+                        );
+                    }
+                    continue;
+                }
+                let original_location = TreeSitterLocation::from_tree_sitter_node(original.body)
+                    .to_sonar_location(source_code);
+                let kind = if structure.keyword.kind() == "match" {
+                    "match arm"
+                } else {
+                    "branch"
+                };
+                issues.push(Issue {
+                    rule_key: self.key().to_string(),
+                    message: format!(
+                        "This {kind}'s code block is the same as the block for the {kind} on line {}.",
+                        original_location.start_line
+                    ),
+                    location,
+                    secondary_locations: vec![SecondaryLocation {
+                        message: "Original".to_string(),
+                        location: original_location,
+                        file: None,
+                    }],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// An `if` chain, with its `else if` and `else` branches, or a `match` expression.
+struct ConditionalStructure<'a> {
+    /// The `if` or `match` keyword.
+    keyword: Node<'a>,
+    branches: Vec<Branch<'a>>,
+    /// Whether one of the branches is always taken: the chain ends with an `else`, or it is a `match`.
+    exhaustive: bool,
+    /// Whether one of the `match` arms binds variables.
+    binds_variables: bool,
+}
+
+struct Branch<'a> {
+    /// The block of an `if` branch, or the value of a `match` arm.
+    body: Node<'a>,
+    binds_variables: bool,
+}
+
+fn conditional_structures<'a>(
+    tree: &'a Tree,
+    source_code: &'a str,
+) -> impl Iterator<Item = ConditionalStructure<'a>> + 'a {
+    NodeIterator::new(tree.root_node(), |node| {
+        node.kind() == "match_expression"
+            || (node.kind() == "if_expression"
+                && node
+                    .parent()
+                    .is_none_or(|parent| parent.kind() != "else_clause"))
+    })
+    .filter_map(move |node| {
+        if node.kind() == "if_expression" {
+            Some(if_chain(node))
+        } else {
+            match_arms(node, source_code)
+        }
+    })
+}
+
+fn if_chain(if_expression: Node<'_>) -> ConditionalStructure<'_> {
+    let mut branches = vec![];
+    let mut exhaustive = false;
+    let mut current = if_expression;
+    loop {
+        if let Some(consequence) = current.child_by_field_name("consequence") {
+            branches.push(Branch {
+                body: consequence,
+                binds_variables: false,
+            });
+        }
+        let Some(alternative) = current.child_by_field_name("alternative") else {
+            break;
+        };
+        let mut cursor = alternative.walk();
+        let next = alternative
+            .named_children(&mut cursor)
+            .find(|child| matches!(child.kind(), "block" | "if_expression"));
+        match next {
+            Some(next) if next.kind() == "if_expression" => current = next,
+            Some(block) => {
+                branches.push(Branch {
+                    body: block,
+                    binds_variables: false,
+                });
+                exhaustive = true;
+                break;
+            }
+            None => break,
+        }
+    }
+    ConditionalStructure {
+        keyword: if_expression.child(0).unwrap_or(if_expression),
+        branches,
+        exhaustive,
+        binds_variables: false,
+    }
+}
+
+fn match_arms<'a>(
+    match_expression: Node<'a>,
+    source_code: &str,
+) -> Option<ConditionalStructure<'a>> {
+    let body = match_expression.child_by_field_name("body")?;
+    let mut cursor = body.walk();
+    let branches: Vec<_> = body
+        .named_children(&mut cursor)
+        .filter(|arm| arm.kind() == "match_arm")
+        .filter_map(|arm| {
+            Some(Branch {
+                body: arm.child_by_field_name("value")?,
+                binds_variables: binds_variables(arm, source_code),
+            })
+        })
+        .collect();
+    Some(ConditionalStructure {
+        keyword: match_expression.child(0)?,
+        exhaustive: true,
+        binds_variables: branches.iter().any(|branch| branch.binds_variables),
+        branches,
+    })
+}
+
+fn all_identical(structure: &ConditionalStructure<'_>, source_code: &str) -> bool {
+    structure.exhaustive
+        && structure.branches.len() > 1
+        && structure.branches[1..]
+            .iter()
+            .all(|branch| are_equivalent(structure.branches[0].body, branch.body, source_code))
+}
+
+/// Number of lines spanned by the code of the branch, without its braces and comments.
+fn code_lines(body: Node<'_>) -> usize {
+    let mut cursor = body.walk();
+    let code: Vec<_> = if body.kind() == "block" {
+        body.named_children(&mut cursor)
+            .filter(|child| !child.is_extra())
+            .collect()
+    } else {
+        vec![body]
+    };
+    match (code.first(), code.last()) {
+        (Some(first), Some(last)) => last.end_position().row - first.start_position().row + 1,
+        _ => 0,
+    }
+}
+
+/// Whether the two nodes have the same tokens, ignoring the comments and the formatting.
+pub(crate) fn are_equivalent(first: Node<'_>, second: Node<'_>, source_code: &str) -> bool {
+    tokens(first, source_code).eq(tokens(second, source_code))
+}
+
+fn tokens<'a>(node: Node<'a>, source_code: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+    // The iterator does not visit its root, which is the only token of a leaf node.
+    let leaf = (node.child_count() == 0).then_some(node);
+    leaf.into_iter()
+        .chain(NodeIterator::new(node, |node| {
+            node.child_count() == 0 && !node.is_extra()
+        }))
+        .map(move |token| &source_code[token.byte_range()])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_all_branches_identical() {
+        assert_issues(
+            &AllBranchesIdenticalCheck,
+            r#"
+fn main() {
+    let x = if a { 1 } else { 1 }; // Noncompliant {{Remove this conditional structure or edit its code blocks so that they're not all the same.}}
+    if a { // Noncompliant
+        run(); // first
+    } else if b {
+        run(); // second
+    } else {
+        run();
+    }
+    if a { 1 } else if b { 1 } else { 2 };
+    if a {
+        one();
+        two();
+    } else if b {
+        one();
+        two();
+    }
+    if a {
+        one();
+        two();
+    } else if b {
+        one();
+        three();
+    } else {
+        /* same */ one();
+        two();
+    }
+    match x { // Noncompliant
+        1 => stop(),
+        _ => stop(),
+    }
+    match x {
+        1 => 2,
+        _ => 4,
+    }
+    match x {
+        Some(y) => {
+            log(y);
+            stop()
+        }
+        None => {
+            log(y);
+            stop()
+        }
+        _ => stop(),
+    }
+    match x {
+        A => {
+            log();
+            stop()
+        }
+        B => run(),
+        C => {
+            log();
+            stop()
+        }
+    }
+    match x {
+        Ok(y) => stop(),
+        Err(y) => stop(),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_duplicate_branch() {
+        assert_issues(
+            &DuplicateBranchCheck,
+            r#"
+fn main() {
+    let x = if a { 1 } else { 1 };
+    if a {
+        run(); // first
+    } else if b {
+        run(); // second
+    } else {
+        run();
+    }
+    if a { 1 } else if b { 1 } else { 2 };
+    if a {
+        one();
+        two();
+    } else if b { // Noncompliant {{This branch's code block is the same as the block for the branch on line 12.}}
+        one();
+        two();
+    }
+    if a {
+        one();
+        two();
+    } else if b {
+        one();
+        three();
+    } else { // Noncompliant {{This branch's code block is the same as the block for the branch on line 19.}}
+        /* same */ one();
+        two();
+    }
+    match x {
+        1 => stop(),
+        _ => stop(),
+    }
+    match x {
+        Some(y) => {
+            log(y);
+            stop()
+        }
+        None => {
+            log(y);
+            stop()
+        }
+        _ => stop(),
+    }
+    match x {
+        A => {
+            log();
+            stop()
+        }
+        B => run(),
+        C => { // Noncompliant {{This match arm's code block is the same as the block for the match arm on line 45.}}
+            log();
+            stop()
+        }
+    }
+    match x {
+        Ok(y) => stop(),
+        Err(y) => stop(),
+    }
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,202 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, RuleType, SecondaryLocation},
+    rule_description::RuleDescription,
+    rules::{
+        identical_branches_check::are_equivalent,
+        rule::{Rule, RuleTracer},
+    },
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::test_code::TestCode,
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S1764";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Identical expressions should not be used on both sides of a binary operator",
+    markdown: r#"
+This rule raises an issue on the comparison, logical, bitwise `&`, `|` and `^`, subtraction, division and remainder
+operators whose operands are the same expression, ignoring the comments and the formatting. The other operators, e.g.
+`1 << 1` or `x * x`, have legitimate uses with identical operands, and so do the operands calling functions or macros,
+which may return different values. The test code is not reported, as it often checks that `==` is reflexive.
+
+## Why is this an issue?
+
+An operator applied to identical operands has a constant result (`a == a`, `a - a`) or returns the operand unchanged
+(`a && a`, `a | a`). This is usually a copy-paste error, where one operand should be different.
+
+```noncompliant
+if first.len() == first.len() {
+    merge(first, second);
+}
+```
+
+### How to fix it
+
+Fix the operand, or simplify the expression.
+
+```compliant
+if first.len() == second.len() {
+    merge(first, second);
+}
+```
+
+## Resources
+
+### Documentation
+
+- Clippy Lints - [eq_op](https://rust-lang.github.io/rust-clippy/master/index.html#eq_op)
+"#,
+};
+
+/// The operators whose result is constant or an operand when both operands are the same.
+const OPERATORS: [&str; 14] = [
+    "==", "!=", "<", "<=", ">", ">=", "&&", "||", "&", "|", "^", "-", "/", "%",
+];
+
+pub struct IdenticalOperandsCheck {
+    /// The file only contains test code, see `TestCode`.
+    test_file: bool,
+}
+
+impl IdenticalOperandsCheck {
+    pub fn new(test_file: bool) -> Self {
+        IdenticalOperandsCheck { test_file }
+    }
+}
+
+impl Rule for IdenticalOperandsCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn rule_type(&self) -> RuleType {
+        RuleType::Bug
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let test_code = TestCode::find(tree, source_code, self.test_file);
+        let mut issues = vec![];
+        for expression in
+            NodeIterator::new(tree.root_node(), |node| node.kind() == "binary_expression")
+        {
+            let (Some(left), Some(operator), Some(right)) = (
+                expression.child_by_field_name("left"),
+                expression.child_by_field_name("operator"),
+                expression.child_by_field_name("right"),
+            ) else {
+                continue;
+            };
+            if !OPERATORS.contains(&operator.kind()) || !are_equivalent(left, right, source_code) {
+                continue;
+            }
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(right).to_sonar_location(source_code);
+            if has_call(left) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, "operands with calls".to_string());
+                }
+                continue;
+            }
+            if test_code.contains_node(expression) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, "operands in test code".to_string());
+                }
+                continue;
+            }
+
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: format!(
+                    "Correct one of the identical sub-expressions on both sides of operator \"{}\".",
+                    operator.kind()
+                ),
+                location,
+                secondary_locations: vec![SecondaryLocation {
+                    message: "Identical sub-expression".to_string(),
+                    location: TreeSitterLocation::from_tree_sitter_node(left)
+                        .to_sonar_location(source_code),
+                    file: None,
+                }],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Whether the expression calls a function or a macro, which may return different values on each call.
+fn has_call(expression: Node<'_>) -> bool {
+    let is_call = |node: Node<'_>| matches!(node.kind(), "call_expression" | "macro_invocation");
+    is_call(expression) || NodeIterator::new(expression, is_call).next().is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_identical_operands() {
+        assert_issues(
+            &IdenticalOperandsCheck::new(false),
+            r#"
+fn main() {
+    if a == a {} // Noncompliant {{Correct one of the identical sub-expressions on both sides of operator "==".}}
+//          ^
+//     ^<
+    let b = x - x; // Noncompliant
+//              ^
+//          ^<
+    let c = flag && flag; // Noncompliant
+    let d = point.x /* first */ != point.x; // Noncompliant
+//                                 ^^^^^^^
+//          ^^^^^^^<
+    let e = 1 << 1;
+    let f = x * x + x + x;
+    let g = next() == next();
+    let h = v.len() < v.len();
+    let i = (a | b) & (a | b); // Noncompliant
+    let j = a == b;
+    let k = a.b[0] % a.b[0]; // Noncompliant
+}
+
+#[test]
+fn reflexive() {
+    assert!(value == value);
+    let same = value == value;
+}
+"#,
+        );
+    }
+}
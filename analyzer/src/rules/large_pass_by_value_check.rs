@@ -0,0 +1,726 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{
+        child_of_kind, is_closure, is_move_closure, AnalyzerError, NodeIterator, TreeSitterLocation,
+    },
+};
+use std::collections::{HashMap, HashSet};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S7465";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Large values that are only read should be passed by reference",
+    markdown: r#"
+This rule raises an issue when a function takes a parameter of a large type by value although it only reads it.
+
+## Why is this an issue?
+
+Passing a value to a function copies it. For large structs and arrays, this copy can be significantly more expensive
+than passing a reference, especially in frequently called functions. When the function never moves, consumes or
+mutates the parameter, taking it by reference is equivalent and avoids the copy.
+
+The size of a type is estimated from its declaration: primitive types, arrays, tuples, common standard library types,
+and the structs and enums declared in the same file are taken into account. Trait methods and `extern` functions are
+ignored, as their signature cannot be changed.
+
+### What is the potential impact?
+
+Large copies on every call waste CPU time and stack space.
+
+## How to fix it
+
+Take the parameter by reference and adjust the call sites.
+
+### Code examples
+
+#### Noncompliant code example
+
+```noncompliant
+fn checksum(buffer: [u8; 4096]) -> u32 { // Noncompliant
+    buffer.iter().map(|b| *b as u32).sum()
+}
+```
+
+#### Compliant solution
+
+```compliant
+fn checksum(buffer: &[u8; 4096]) -> u32 {
+    buffer.iter().map(|b| *b as u32).sum()
+}
+```
+
+## Resources
+
+### Documentation
+
+- Clippy Lints -
+  [large_types_passed_by_value](https://rust-lang.github.io/rust-clippy/master/index.html#large_types_passed_by_value)
+"#,
+};
+
+/// Size in bytes assumed for pointers and the types that are only made of pointer-sized fields.
+const POINTER_SIZE: usize = 8;
+
+/// Flags parameters of a large type that are passed by value although the function only reads them.
+///
+/// There is no type information available, so the size of a type is a lower bound estimated from its syntax:
+/// primitive types, arrays, tuples, common standard library types and the structs and enums declared in the same file.
+/// Types whose size cannot be estimated (e.g. generic parameters or types declared in other files) count as zero bytes.
+pub struct LargePassByValueCheck {
+    size_threshold: usize,
+}
+
+impl LargePassByValueCheck {
+    pub fn new(size_threshold: usize) -> Self {
+        LargePassByValueCheck { size_threshold }
+    }
+}
+
+impl Rule for LargePassByValueCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let sizes = TypeSizes::new(tree.root_node(), source_code);
+        let consuming_methods = consuming_method_names(tree.root_node(), source_code);
+        let mut issues = vec![];
+
+        for function_item in
+            NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item")
+        {
+            if has_fixed_signature(function_item) {
+                continue;
+            }
+            let (Some(parameters), Some(body)) = (
+                function_item.child_by_field_name("parameters"),
+                function_item.child_by_field_name("body"),
+            ) else {
+                continue;
+            };
+
+            let mut cursor = parameters.walk();
+            for parameter in parameters.named_children(&mut cursor) {
+                if parameter.kind() != "parameter"
+                    || has_child_of_kind(parameter, "mutable_specifier")
+                {
+                    continue;
+                }
+                let (Some(pattern), Some(parameter_type)) = (
+                    parameter.child_by_field_name("pattern"),
+                    parameter.child_by_field_name("type"),
+                ) else {
+                    continue;
+                };
+                if pattern.kind() != "identifier" {
+                    continue;
+                }
+
+                let location = TreeSitterLocation::from_tree_sitter_node(parameter)
+                    .to_sonar_location(source_code);
+                let size = sizes.size_of(parameter_type);
+                if tracer.is_enabled() {
+                    let comparison = if size > self.size_threshold {
+                        "above"
+                    } else {
+                        "not above"
+                    };
+                    tracer.trace(
+                        &location,
+                        &location,
+                        format!(
+                            "type takes at least {} bytes, {} the threshold of {}",
+                            size, comparison, self.size_threshold
+                        ),
+                    );
+                }
+                if size <= self.size_threshold {
+                    continue;
+                }
+
+                let name = &source_code[pattern.byte_range()];
+                let usages = usages(body, name, source_code, &consuming_methods);
+                if tracer.is_enabled() {
+                    for (node, usage) in &usages {
+                        tracer.trace(
+                            &location,
+                            &TreeSitterLocation::from_tree_sitter_node(*node)
+                                .to_sonar_location(source_code),
+                            usage.description().to_string(),
+                        );
+                    }
+                }
+
+                // Unused parameters are a different problem
+                if !usages.is_empty() && usages.iter().all(|(_, usage)| *usage == Usage::Read) {
+                    issues.push(Issue {
+                        rule_key: RULE_KEY.to_string(),
+                        message: format!(
+                            "Pass \"{}\" by reference, its type takes at least {} bytes and it is only read.",
+                            name, size
+                        ),
+                        location,
+                        secondary_locations: vec![],
+                        severity: None,
+                        quick_fixes: vec![],
+                        fingerprint: None,
+                        hotspot: None,
+                    });
+                }
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Trait methods and foreign functions cannot change the way their parameters are passed.
+fn has_fixed_signature(function_item: Node<'_>) -> bool {
+    if let Some(modifiers) = child_of_kind(function_item, "function_modifiers") {
+        if has_child_of_kind(modifiers, "extern_modifier") {
+            return true;
+        }
+    }
+
+    let container = function_item
+        .parent()
+        .filter(|parent| parent.kind() == "declaration_list")
+        .and_then(|declaration_list| declaration_list.parent());
+    match container {
+        Some(item) if item.kind() == "trait_item" => true,
+        Some(item) if item.kind() == "impl_item" => item.child_by_field_name("trait").is_some(),
+        _ => false,
+    }
+}
+
+fn has_child_of_kind(node: Node<'_>, kind: &str) -> bool {
+    let mut cursor = node.walk();
+    let result = node.children(&mut cursor).any(|child| child.kind() == kind);
+    result
+}
+
+/// Names of the methods declared in the file that take `self` by value.
+fn consuming_method_names(root: Node<'_>, source_code: &str) -> HashSet<String> {
+    NodeIterator::new(root, |node| node.kind() == "function_item")
+        .filter(|function_item| {
+            function_item
+                .child_by_field_name("parameters")
+                .and_then(|parameters| child_of_kind(parameters, "self_parameter"))
+                .is_some_and(|self_parameter| !has_child_of_kind(self_parameter, "&"))
+        })
+        .filter_map(|function_item| function_item.child_by_field_name("name"))
+        .map(|name| source_code[name.byte_range()].to_string())
+        .collect()
+}
+
+/// How the value bound to a parameter is used at one of its occurrences.
+#[derive(Debug, PartialEq, Eq)]
+enum Usage {
+    Read,
+    Write,
+    Move,
+}
+
+impl Usage {
+    fn description(&self) -> &'static str {
+        match self {
+            Usage::Read => "read",
+            Usage::Write => "mutated",
+            Usage::Move => "possibly moved",
+        }
+    }
+}
+
+/// Finds the occurrences of the parameter in the body, in source order.
+fn usages<'a>(
+    body: Node<'a>,
+    name: &str,
+    source_code: &str,
+    consuming_methods: &HashSet<String>,
+) -> Vec<(Node<'a>, Usage)> {
+    let mut usages = vec![];
+    let mut stack = vec![body];
+
+    while let Some(node) = stack.pop() {
+        match node.kind() {
+            // Nested items have their own scope
+            "function_item" | "impl_item" | "trait_item" | "mod_item" => continue,
+            "identifier" if &source_code[node.byte_range()] == name => {
+                if let Some(usage) = usage_of(node, source_code, consuming_methods) {
+                    usages.push((node, usage));
+                }
+            }
+            _ => {
+                let mut cursor = node.walk();
+                stack.extend(node.children(&mut cursor));
+            }
+        }
+    }
+
+    usages.sort_by_key(|(node, _)| node.start_byte());
+    usages
+}
+
+/// Classifies an occurrence of the parameter name, or returns `None` when the identifier does not refer to a variable
+/// (e.g. a path segment).
+fn usage_of(
+    identifier: Node<'_>,
+    source_code: &str,
+    consuming_methods: &HashSet<String>,
+) -> Option<Usage> {
+    let parent = identifier.parent()?;
+    if matches!(
+        parent.kind(),
+        "scoped_identifier" | "scoped_type_identifier"
+    ) {
+        return None;
+    }
+    if is_captured_by_move_closure(identifier) {
+        return Some(Usage::Move);
+    }
+
+    // Walk up the chain of field accesses and indexing applied to the parameter, e.g. `p.a[0].b`
+    let mut place = identifier;
+    loop {
+        let parent = place.parent()?;
+        let is_projection = place != identifier;
+        let usage = match parent.kind() {
+            "field_expression" if parent.child_by_field_name("value") == Some(place) => {
+                match parent.parent() {
+                    Some(call)
+                        if call.kind() == "call_expression"
+                            && call.child_by_field_name("function") == Some(parent) =>
+                    {
+                        let method = parent
+                            .child_by_field_name("field")
+                            .map(|field| &source_code[field.byte_range()])
+                            .unwrap_or_default();
+                        // Method receivers are borrowed automatically, unless the method consumes them
+                        if method.starts_with("into") || consuming_methods.contains(method) {
+                            Usage::Move
+                        } else {
+                            Usage::Read
+                        }
+                    }
+                    _ => {
+                        place = parent;
+                        continue;
+                    }
+                }
+            }
+            "index_expression" if parent.named_child(0) == Some(place) => {
+                place = parent;
+                continue;
+            }
+            "parenthesized_expression" => {
+                place = parent;
+                continue;
+            }
+            "assignment_expression" | "compound_assignment_expr"
+                if parent.child_by_field_name("left") == Some(place) =>
+            {
+                Usage::Write
+            }
+            "reference_expression" => {
+                if has_child_of_kind(parent, "mutable_specifier") {
+                    Usage::Write
+                } else {
+                    Usage::Read
+                }
+            }
+            "binary_expression" | "unary_expression" | "token_tree" | "type_cast_expression" => {
+                Usage::Read
+            }
+            // Using a part of the parameter as a value copies it in the common case of small fields
+            _ if is_projection => Usage::Read,
+            _ => Usage::Move,
+        };
+        return Some(usage);
+    }
+}
+
+fn is_captured_by_move_closure(node: Node<'_>) -> bool {
+    let mut parent = node.parent();
+    while let Some(ancestor) = parent {
+        if ancestor.kind() == "function_item" {
+            return false;
+        }
+        if is_closure(ancestor) && is_move_closure(ancestor) {
+            return true;
+        }
+        parent = ancestor.parent();
+    }
+    false
+}
+
+/// Estimates the size of types from their syntax and the type declarations of the file.
+struct TypeSizes<'a> {
+    source_code: &'a str,
+    declarations: HashMap<&'a str, Node<'a>>,
+}
+
+impl<'a> TypeSizes<'a> {
+    fn new(root: Node<'a>, source_code: &'a str) -> Self {
+        let declarations = NodeIterator::new(root, |node| {
+            matches!(node.kind(), "struct_item" | "enum_item")
+        })
+        .filter_map(|item| {
+            item.child_by_field_name("name")
+                .map(|name| (&source_code[name.byte_range()], item))
+        })
+        .collect();
+
+        TypeSizes {
+            source_code,
+            declarations,
+        }
+    }
+
+    fn size_of(&self, type_node: Node<'a>) -> usize {
+        self.size_of_type(type_node, &mut vec![])
+    }
+
+    fn size_of_type(&self, type_node: Node<'a>, visiting: &mut Vec<&'a str>) -> usize {
+        let text = &self.source_code[type_node.byte_range()];
+        match type_node.kind() {
+            "primitive_type" => primitive_size(text),
+            "reference_type" | "pointer_type" => {
+                let is_fat = type_node
+                    .child_by_field_name("type")
+                    .is_some_and(|pointee| {
+                        matches!(
+                            pointee.kind(),
+                            "array_type" | "dynamic_type" | "primitive_type"
+                        ) && (pointee.kind() != "primitive_type"
+                            || &self.source_code[pointee.byte_range()] == "str")
+                            && (pointee.kind() != "array_type"
+                                || pointee.child_by_field_name("length").is_none())
+                    });
+                if is_fat {
+                    2 * POINTER_SIZE
+                } else {
+                    POINTER_SIZE
+                }
+            }
+            "function_type" => POINTER_SIZE,
+            "array_type" => {
+                let length = type_node
+                    .child_by_field_name("length")
+                    .filter(|length| length.kind() == "integer_literal")
+                    .and_then(|length| parse_integer(&self.source_code[length.byte_range()]));
+                match (length, type_node.child_by_field_name("element")) {
+                    (Some(length), Some(element)) => {
+                        length.saturating_mul(self.size_of_type(element, visiting))
+                    }
+                    _ => 0,
+                }
+            }
+            "tuple_type" => {
+                let mut cursor = type_node.walk();
+                let elements: Vec<Node<'a>> = type_node.named_children(&mut cursor).collect();
+                elements
+                    .into_iter()
+                    .map(|element| self.size_of_type(element, visiting))
+                    .sum()
+            }
+            "generic_type" => {
+                let name = type_node
+                    .child_by_field_name("type")
+                    .map(|name| &self.source_code[name.byte_range()])
+                    .unwrap_or_default();
+                let first_argument = type_node
+                    .child_by_field_name("type_arguments")
+                    .and_then(|arguments| arguments.named_child(0));
+                match name {
+                    "Vec" | "String" | "VecDeque" | "HashMap" | "HashSet" | "BTreeMap"
+                    | "BTreeSet" => 3 * POINTER_SIZE,
+                    "Box" | "Rc" | "Arc" | "NonNull" => POINTER_SIZE,
+                    "Option" | "Cell" | "RefCell" | "ManuallyDrop" | "Wrapping" => first_argument
+                        .map(|argument| self.size_of_type(argument, visiting))
+                        .unwrap_or_default(),
+                    _ => self.declared_size(name, visiting),
+                }
+            }
+            "type_identifier" => match text {
+                "String" | "PathBuf" | "OsString" => 3 * POINTER_SIZE,
+                _ => self.declared_size(text, visiting),
+            },
+            _ => 0,
+        }
+    }
+
+    fn declared_size(&self, name: &'a str, visiting: &mut Vec<&'a str>) -> usize {
+        let Some(declaration) = self.declarations.get(name).copied() else {
+            return 0;
+        };
+        if visiting.contains(&name) {
+            // Recursive types are only possible through indirections, which are already accounted for
+            return 0;
+        }
+
+        visiting.push(name);
+        let size = match declaration.kind() {
+            "struct_item" => declaration
+                .child_by_field_name("body")
+                .map(|body| self.fields_size(body, visiting))
+                .unwrap_or_default(),
+            _ => {
+                let variants: Vec<Node<'a>> = declaration
+                    .child_by_field_name("body")
+                    .map(|body| {
+                        let mut cursor = body.walk();
+                        body.named_children(&mut cursor)
+                            .filter(|variant| variant.kind() == "enum_variant")
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                variants
+                    .into_iter()
+                    .filter_map(|variant| variant.child_by_field_name("body"))
+                    .map(|body| self.fields_size(body, visiting))
+                    .max()
+                    .unwrap_or_default()
+            }
+        };
+        visiting.pop();
+        size
+    }
+
+    /// Sums the sizes of the fields of a struct or enum variant, whether they are named or not.
+    fn fields_size(&self, body: Node<'a>, visiting: &mut Vec<&'a str>) -> usize {
+        let mut cursor = body.walk();
+        let field_types: Vec<Node<'a>> = match body.kind() {
+            "field_declaration_list" => body
+                .named_children(&mut cursor)
+                .filter_map(|field| field.child_by_field_name("type"))
+                .collect(),
+            "ordered_field_declaration_list" => {
+                body.children_by_field_name("type", &mut cursor).collect()
+            }
+            _ => vec![],
+        };
+        field_types
+            .into_iter()
+            .map(|field_type| self.size_of_type(field_type, visiting))
+            .sum()
+    }
+}
+
+fn primitive_size(name: &str) -> usize {
+    match name {
+        "bool" | "u8" | "i8" => 1,
+        "u16" | "i16" => 2,
+        "u32" | "i32" | "f32" | "char" => 4,
+        "u64" | "i64" | "f64" | "usize" | "isize" => 8,
+        "u128" | "i128" => 16,
+        _ => 0,
+    }
+}
+
+fn parse_integer(literal: &str) -> Option<usize> {
+    let digits: String = literal
+        .trim_end_matches("usize")
+        .chars()
+        .filter(|c| *c != '_')
+        .collect();
+    if let Some(hex) = digits.strip_prefix("0x") {
+        usize::from_str_radix(hex, 16).ok()
+    } else {
+        digits.parse().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        rules::rule::Trace,
+        tree::{parse_rust_code, SonarLocation},
+    };
+
+    fn check(source_code: &str, size_threshold: usize) -> Vec<Issue> {
+        let rule = LargePassByValueCheck::new(size_threshold);
+        let tree = parse_rust_code(source_code).unwrap();
+        rule.check(&tree, source_code).unwrap()
+    }
+
+    #[test]
+    fn test_large_array_only_read() {
+        let source_code = r#"
+fn sum(values: [u64; 64]) -> u64 {
+    let mut total = 0;
+    for i in 0..values.len() {
+        total += values[i];
+    }
+    total
+}
+"#;
+        assert_eq!(
+            check(source_code, 256),
+            vec![Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: "Pass \"values\" by reference, its type takes at least 512 bytes and it is only read.".to_string(),
+                location: SonarLocation {
+                    start_line: 2,
+                    start_column: 7,
+                    end_line: 2,
+                    end_column: 24,
+                },
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            }]
+        );
+        assert_eq!(check(source_code, 512), vec![]);
+    }
+
+    #[test]
+    fn test_struct_declared_in_file() {
+        let source_code = r#"
+struct Header {
+    magic: [u8; 200],
+    version: (u32, u32),
+    name: String,
+    extra: Option<Trailer>,
+}
+
+struct Trailer([u8; 100]);
+
+enum Payload {
+    Empty,
+    Data([u8; 500]),
+}
+
+fn is_valid(header: Header) -> bool {
+    header.version.0 > 1 && header.magic[0] == 0x7f && &header.name != "" && check!(header)
+}
+
+fn payload_length(payload: Payload) -> usize {
+    match &payload {
+        Payload::Empty => 0,
+        Payload::Data(data) => data.len(),
+    }
+}
+"#;
+        let issues = check(source_code, 256);
+        let messages: Vec<&str> = issues.iter().map(|issue| issue.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Pass \"header\" by reference, its type takes at least 332 bytes and it is only read.",
+                "Pass \"payload\" by reference, its type takes at least 500 bytes and it is only read.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_moved_or_mutated() {
+        let source_code = r#"
+struct Big([u8; 1024]);
+
+impl Big {
+    fn consume(self) {}
+    fn peek(&self) -> u8 { self.0[0] }
+}
+
+fn moved(big: Big) -> Big { big }
+fn passed(big: Big) { drop(big); }
+fn consumed(big: Big) { big.consume(); }
+fn converted(big: Big) -> Vec<u8> { big.into() }
+fn assigned(big: Big) { big.0[0] = 1; }
+fn mutably_borrowed(big: Big) { let r = &mut big.0; }
+fn declared_mut(mut big: Big) { big.peek(); }
+fn captured(big: Big) { std::thread::spawn(move || big.peek()); }
+fn captured_async(big: Big) { spawn(async move |x| big.peek() + x); }
+fn unused(big: Big) {}
+fn small(value: [u8; 16]) -> u8 { value[0] }
+fn unknown<T>(value: T, other: Other) { value.peek(); other.peek(); }
+fn borrowed(big: &Big) -> u8 { big.peek() }
+"#;
+        assert_eq!(check(source_code, 256), vec![]);
+    }
+
+    #[test]
+    fn test_traces() {
+        let source_code =
+            "fn f(big: [u8; 300]) -> u8 {\n    let first = big[0];\n    drop(big);\n    first\n}\n";
+        let rule = LargePassByValueCheck::new(256);
+        let tree = parse_rust_code(source_code).unwrap();
+        let mut traces: Vec<Trace> = vec![];
+
+        assert_eq!(
+            rule.check_traced(&tree, source_code, &mut traces).unwrap(),
+            vec![]
+        );
+        let messages: Vec<(usize, &str)> = traces
+            .iter()
+            .map(|trace| (trace.location.start_line, trace.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                (
+                    1,
+                    "type takes at least 300 bytes, above the threshold of 256"
+                ),
+                (2, "read"),
+                (3, "possibly moved"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fixed_signatures() {
+        let source_code = r#"
+struct Big([u8; 1024]);
+
+trait Visitor {
+    fn visit(&self, big: Big) -> u8 { big.0[0] }
+}
+
+impl Visitor for Foo {
+    fn visit(&self, big: Big) -> u8 { big.0[0] }
+}
+
+impl Foo {
+    fn inherent(&self, big: Big) -> u8 { big.0[0] }
+}
+
+extern "C" fn callback(big: Big) -> u8 { big.0[0] }
+"#;
+        let issues = check(source_code, 256);
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].location.start_line, 13);
+    }
+}
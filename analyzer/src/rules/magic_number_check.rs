@@ -0,0 +1,311 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::test_code::TestCode,
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S109";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Magic numbers should not be used",
+    markdown: r#"
+This rule raises an issue on the number literals used in expressions, except the numbers of the `authorizedNumbers`
+parameter, a comma-separated list. The literals of the `const` and `static` items, of the enum discriminants, of the
+array sizes and of the attributes are not reported, nor are the patterns, the tuple indexes, the arguments of the
+macro invocations (unless the macros are expanded) and the test code. A number which is the whole initializer of a
+variable, e.g. `let timeout = 30;`, is not reported either, as the name of the variable explains it.
+
+## Why is this an issue?
+
+A magic number is a number whose meaning is not obvious from the context. A named constant explains the meaning of the
+number, and gives one place to change it.
+
+```noncompliant
+fn retry(request: &Request) -> Result<Response, Error> {
+    for _ in 0..5 {
+        if let Ok(response) = request.send() {
+            return Ok(response);
+        }
+        thread::sleep(Duration::from_millis(250));
+    }
+    Err(Error::Unavailable)
+}
+```
+
+### How to fix it
+
+Replace the number with a constant.
+
+```compliant
+const MAX_ATTEMPTS: usize = 5;
+const RETRY_DELAY: Duration = Duration::from_millis(250);
+
+fn retry(request: &Request) -> Result<Response, Error> {
+    for _ in 0..MAX_ATTEMPTS {
+        if let Ok(response) = request.send() {
+            return Ok(response);
+        }
+        thread::sleep(RETRY_DELAY);
+    }
+    Err(Error::Unavailable)
+}
+```
+"#,
+};
+
+pub struct MagicNumberCheck {
+    authorized_numbers: Vec<f64>,
+    /// The file only contains test code, see `TestCode`.
+    test_file: bool,
+}
+
+impl MagicNumberCheck {
+    pub fn new(authorized_numbers: &str, test_file: bool) -> Self {
+        MagicNumberCheck {
+            authorized_numbers: authorized_numbers
+                .split(',')
+                .filter_map(|number| number.trim().parse().ok())
+                .collect(),
+            test_file,
+        }
+    }
+}
+
+impl Rule for MagicNumberCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let test_code = TestCode::find(tree, source_code, self.test_file);
+        let mut issues = vec![];
+        for literal in NodeIterator::new(tree.root_node(), |node| {
+            matches!(node.kind(), "integer_literal" | "float_literal")
+        }) {
+            let Some(mut value) = literal_value(&source_code[literal.byte_range()]) else {
+                continue;
+            };
+            let mut number = literal;
+            if let Some(negation) = literal.parent().filter(|parent| is_negation(*parent)) {
+                value = -value;
+                number = negation;
+            }
+            if self.authorized_numbers.contains(&value) {
+                continue;
+            }
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(number).to_sonar_location(source_code);
+            if let Some(reason) = excluded_context(literal) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, reason.to_string());
+                }
+                continue;
+            }
+            if test_code.contains_node(literal) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, "number in test code".to_string());
+                }
+                continue;
+            }
+
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: format!(
+                    "Assign this magic number {} to a well-named constant, and use the constant instead.",
+                    &source_code[number.byte_range()]
+                ),
+                location,
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// The value of an integer or float literal, without its separators and type suffix.
+fn literal_value(text: &str) -> Option<f64> {
+    let text = text.replace('_', "");
+    let radix = match text.get(..2) {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => 10,
+    };
+    if radix == 10 {
+        let end = text
+            .find(|c: char| c.is_ascii_alphabetic() && c != 'e' && c != 'E')
+            .unwrap_or(text.len());
+        text[..end].parse().ok()
+    } else {
+        let digits = &text[2..];
+        let end = digits.find(['u', 'i']).unwrap_or(digits.len());
+        u128::from_str_radix(&digits[..end], radix)
+            .ok()
+            .map(|value| value as f64)
+    }
+}
+
+fn is_negation(node: Node<'_>) -> bool {
+    node.kind() == "unary_expression"
+        && node.child(0).is_some_and(|operator| operator.kind() == "-")
+}
+
+/// The reason why the numbers are allowed in the context of the literal, if they are.
+fn excluded_context(literal: Node<'_>) -> Option<&'static str> {
+    let parent = literal.parent()?;
+    if parent.kind().ends_with("_pattern")
+        || matches!(parent.kind(), "match_pattern" | "negative_literal")
+    {
+        return Some("number in a pattern");
+    }
+    if parent.kind() == "field_expression" && parent.child_by_field_name("field") == Some(literal) {
+        return Some("tuple index");
+    }
+    let initialized = if is_negation(parent) {
+        parent.parent()
+    } else {
+        Some(parent)
+    };
+    if initialized.is_some_and(|declaration| {
+        declaration.kind() == "let_declaration"
+            && declaration
+                .child_by_field_name("pattern")
+                .is_some_and(|pattern| pattern.kind() == "identifier")
+    }) {
+        return Some("initializer of a named variable");
+    }
+    let mut current = literal;
+    while let Some(ancestor) = current.parent() {
+        match ancestor.kind() {
+            "const_item" | "static_item" => return Some("number of a constant"),
+            "enum_variant" => return Some("enum discriminant"),
+            "attribute_item" | "inner_attribute_item" => return Some("number in an attribute"),
+            "type_arguments" => return Some("const generic argument"),
+            "token_tree" => return Some("number in an unexpanded macro invocation"),
+            "array_type" | "array_expression"
+                if ancestor.child_by_field_name("length") == Some(current) =>
+            {
+                return Some("array size")
+            }
+            "function_item" | "closure_expression" => return None,
+            _ => {}
+        }
+        current = ancestor;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_magic_number() {
+        assert_issues(
+            &MagicNumberCheck::new("-1, 0, 1, 2", false),
+            r#"
+const MAX: usize = 10;
+static NAMES: [&str; 3] = ["a", "b", "c"];
+
+#[repr(u8)]
+enum Level {
+    Low = 4,
+}
+
+#[derive(Clone)]
+#[cfg_attr(test, allow(dead_code))]
+struct Buffer([u8; 64]);
+
+fn main() {
+    let zero = 0 + 1 + 2 - 1 + -1 + 0.0 + 2.0f64 + 0x1 + 1_u8;
+    let timeout = 30;
+    let ratio = 1.5 * -3; // Noncompliant {{Assign this magic number 1.5 to a well-named constant, and use the constant instead.}} {{Assign this magic number -3 to a well-named constant, and use the constant instead.}}
+//              ^^^
+//                    ^^
+    let buffer = [0u8; 256];
+    let tuple = (5, 6); // Noncompliant 2
+//               ^
+//                  ^
+    let six = tuple.1;
+    let big = 1_000_000u64 + 0xFF + 1e3; // Noncompliant 3
+//            ^^^^^^^^^^^^
+//                           ^^^^
+//                                  ^^^
+    match timeout {
+        7 | 8 => {}
+        -9..=9 => {}
+        _ => {}
+    }
+    let list: SmallVec<[u8; 4]> = vec![3, 4];
+    let parsed = Fixed::<12>::new();
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test() {
+        assert_eq!(compute(), 42);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_authorized_numbers() {
+        assert_issues(
+            &MagicNumberCheck::new("1.5, -3", false),
+            r#"
+fn main() {
+    let ratio = 1.5 * -3;
+    let zero = 0 + 1; // Noncompliant {{Assign this magic number 0 to a well-named constant, and use the constant instead.}} {{Assign this magic number 1 to a well-named constant, and use the constant instead.}}
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_literal_value() {
+        assert_eq!(literal_value("1_000"), Some(1000.0));
+        assert_eq!(literal_value("2u8"), Some(2.0));
+        assert_eq!(literal_value("1.5e3f64"), Some(1500.0));
+        assert_eq!(literal_value("0xFFu8"), Some(255.0));
+        assert_eq!(literal_value("0b101"), Some(5.0));
+        assert_eq!(literal_value("0o17"), Some(15.0));
+    }
+}
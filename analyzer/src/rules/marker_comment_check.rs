@@ -0,0 +1,244 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{line_starts, point_of, AnalyzerError, NodeIterator, TreeSitterLocation},
+};
+use tree_sitter::Tree;
+
+const TODO_DESCRIPTION: RuleDescription = RuleDescription {
+    key: "S1135",
+    title: "Track uses of \"TODO\" tags",
+    markdown: r#"
+This rule raises an issue on each comment containing the `TODO` tag, or one of the tags of the `additionalMarkers`
+parameter, a comma-separated list. The tags are matched as whole words, ignoring the case.
+
+## Why is this an issue?
+
+`TODO` tags mark code needing more work. They are easily forgotten: tracking them makes sure that the work is done
+eventually.
+
+```noncompliant
+fn parse(input: &str) -> Config {
+    // TODO: support the comments
+    Config::from_lines(input.lines())
+}
+```
+"#,
+};
+
+const FIXME_DESCRIPTION: RuleDescription = RuleDescription {
+    key: "S1134",
+    title: "Track uses of \"FIXME\" tags",
+    markdown: r#"
+This rule raises an issue on each comment containing the `FIXME` tag, or one of the tags of the `additionalMarkers`
+parameter, a comma-separated list. The tags are matched as whole words, ignoring the case.
+
+## Why is this an issue?
+
+`FIXME` tags mark code which is known to be wrong, e.g. a bug not fixed yet. Tracking them makes sure that the code is
+eventually fixed.
+
+```noncompliant
+fn average(values: &[u32]) -> u32 {
+    // FIXME: divides by zero when empty
+    values.iter().sum::<u32>() / values.len() as u32
+}
+```
+"#,
+};
+
+/// Flags the first tag of each comment among a list of tags, e.g. `TODO`.
+pub struct MarkerCommentCheck {
+    description: RuleDescription,
+    /// Upper-case tags.
+    markers: Vec<String>,
+    message: fn(&str) -> String,
+}
+
+impl MarkerCommentCheck {
+    /// S1135 for the `TODO` tags, and the comma-separated additional ones.
+    pub fn todo(additional_markers: &str) -> Self {
+        Self::new(TODO_DESCRIPTION, "TODO", additional_markers, |marker| {
+            format!(
+                "Complete the task associated to this \"{}\" comment.",
+                marker
+            )
+        })
+    }
+
+    /// S1134 for the `FIXME` tags, and the comma-separated additional ones.
+    pub fn fixme(additional_markers: &str) -> Self {
+        Self::new(FIXME_DESCRIPTION, "FIXME", additional_markers, |marker| {
+            format!(
+                "Take the required action to fix the issue indicated by this \"{}\" comment.",
+                marker
+            )
+        })
+    }
+
+    fn new(
+        description: RuleDescription,
+        marker: &str,
+        additional_markers: &str,
+        message: fn(&str) -> String,
+    ) -> Self {
+        let markers = [marker]
+            .into_iter()
+            .chain(additional_markers.split(','))
+            .map(|marker| marker.trim().to_uppercase())
+            .filter(|marker| !marker.is_empty())
+            .collect();
+        MarkerCommentCheck {
+            description,
+            markers,
+            message,
+        }
+    }
+}
+
+impl Rule for MarkerCommentCheck {
+    fn key(&self) -> &'static str {
+        self.description.key
+    }
+
+    fn description(&self) -> RuleDescription {
+        self.description
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let line_starts = line_starts(source_code);
+        let mut issues = vec![];
+        for comment in NodeIterator::new(tree.root_node(), |node| {
+            matches!(node.kind(), "line_comment" | "block_comment")
+        }) {
+            let text = &source_code[comment.byte_range()];
+            let Some((offset, marker)) = find_marker(text, &self.markers) else {
+                continue;
+            };
+            let start_byte = comment.start_byte() + offset;
+            let end_byte = start_byte + marker.len();
+            let location = TreeSitterLocation {
+                start_byte,
+                end_byte,
+                start_position: point_of(&line_starts, start_byte),
+                end_position: point_of(&line_starts, end_byte),
+            }
+            .to_sonar_location(source_code);
+            if tracer.is_enabled() {
+                tracer.trace(&location, &location, format!("tag '{}'", marker));
+            }
+            issues.push(Issue {
+                rule_key: self.description.key.to_string(),
+                message: (self.message)(marker),
+                location,
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Offset of the first tag of the text, as a whole word ignoring the case, with the tag.
+fn find_marker<'a>(text: &str, markers: &'a [String]) -> Option<(usize, &'a str)> {
+    let upper = text.to_ascii_uppercase();
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    markers
+        .iter()
+        .filter_map(|marker| {
+            upper
+                .match_indices(marker.as_str())
+                .find(|&(offset, _)| {
+                    !upper[..offset].ends_with(is_word)
+                        && !upper[offset + marker.len()..].starts_with(is_word)
+                })
+                .map(|(offset, _)| (offset, marker.as_str()))
+        })
+        .min_by_key(|&(offset, _)| offset)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_todo() {
+        // The annotations with a message, which contains the tag, are part of the reported comment: a comment of their
+        // own would be reported too
+        assert_issues(
+            &MarkerCommentCheck::todo(""),
+            r#"
+// TODO: support the comments // Noncompliant {{Complete the task associated to this "TODO" comment.}}
+// ^^^^
+fn parse(input: &str) -> u32 {
+    /* Remove the
+       todo!() and fixme */ // Noncompliant
+//     ^^^^
+    let todos = 0; // Not a TODOS tag
+    /// XXX and Todo // Noncompliant
+//              ^^^^
+    todo!()
+}
+"#,
+        );
+        assert_issues(
+            &MarkerCommentCheck::todo(" xxx, ,HACK"),
+            r#"
+// TODO: support the comments // Noncompliant
+fn parse(input: &str) -> u32 {
+    /* Remove the
+       todo!() and fixme */ // Noncompliant
+    let todos = 0; // Not a TODOS tag
+    /// XXX and Todo // Noncompliant {{Complete the task associated to this "XXX" comment.}}
+//      ^^^
+    todo!()
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_fixme() {
+        assert_issues(
+            &MarkerCommentCheck::fixme(""),
+            r#"
+// TODO: support the comments
+fn parse(input: &str) -> u32 {
+    /* Remove the
+       todo!() and fixme // Noncompliant {{Take the required action to fix the issue indicated by this "FIXME" comment.}} */
+//                 ^^^^^
+    let todos = 0; // Not a TODOS tag
+    /// XXX and Todo
+    todo!()
+}
+"#,
+        );
+    }
+}
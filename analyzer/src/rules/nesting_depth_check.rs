@@ -0,0 +1,275 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, SecondaryLocation},
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{walk_tree, AnalyzerError, NodeVisitor, SonarLocation, TreeSitterLocation},
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S134";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Control flow statements \"if\", \"match\" and loops should not be nested too deeply",
+    markdown: r#"
+This rule raises an issue on the `if`, `match`, `loop`, `while` and `for` expressions nested deeper than the maximum
+depth, counted from the enclosing function. The `else if` branches do not add nesting, they belong to the same chain
+as the first `if`. Only the outermost expression exceeding the maximum is reported.
+
+## Why is this an issue?
+
+Deeply nested control flow is hard to read and to maintain, as the reader must keep track of all the enclosing
+conditions.
+
+```noncompliant
+for order in orders {
+    if order.is_paid() {
+        match order.shipping() {
+            Shipping::Express => {
+                if order.weight() > MAX_WEIGHT { // Noncompliant
+                    split(order);
+                }
+            }
+            Shipping::Standard => ship(order),
+        }
+    }
+}
+```
+
+### How to fix it
+
+Return early, or extract the nested code into functions.
+
+```compliant
+for order in orders.iter().filter(|order| order.is_paid()) {
+    match order.shipping() {
+        Shipping::Express => ship_express(order),
+        Shipping::Standard => ship(order),
+    }
+}
+```
+"#,
+};
+
+pub struct NestingDepthCheck {
+    max: usize,
+}
+
+impl NestingDepthCheck {
+    pub fn new(max: usize) -> Self {
+        NestingDepthCheck { max }
+    }
+}
+
+impl Rule for NestingDepthCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut visitor = NestingVisitor {
+            max: self.max,
+            source_code,
+            tracer,
+            functions: vec![vec![]],
+            issues: vec![],
+        };
+        walk_tree(tree.root_node(), &mut visitor)?;
+
+        Ok(visitor.issues)
+    }
+}
+
+struct NestingVisitor<'a> {
+    max: usize,
+    source_code: &'a str,
+    tracer: &'a mut dyn RuleTracer,
+    /// For each enclosing function, the ids and keyword locations of the enclosing nesting expressions.
+    functions: Vec<Vec<(usize, SonarLocation)>>,
+    issues: Vec<Issue>,
+}
+
+impl NodeVisitor for NestingVisitor<'_> {
+    fn enter_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
+        if node.kind() == "function_item" {
+            self.functions.push(vec![]);
+            return Ok(());
+        }
+        let Some(keyword) = nesting_keyword(node) else {
+            return Ok(());
+        };
+        let location =
+            TreeSitterLocation::from_tree_sitter_node(keyword).to_sonar_location(self.source_code);
+        let Some(enclosing) = self.functions.last_mut() else {
+            return Ok(());
+        };
+        if enclosing.len() == self.max {
+            self.issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: format!(
+                    "Refactor this code to not nest more than {} \"if\", \"match\" and loop expressions.",
+                    self.max
+                ),
+                location: location.clone(),
+                secondary_locations: enclosing
+                    .iter()
+                    .map(|(_, location)| SecondaryLocation {
+                        message: "Nesting +1".to_string(),
+                        location: location.clone(),
+                        file: None,
+                    })
+                    .collect(),
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        } else if enclosing.len() > self.max && self.tracer.is_enabled() {
+            self.tracer.trace(
+                &location,
+                &location,
+                "nested in an expression already reported".to_string(),
+            );
+        }
+        enclosing.push((node.id(), location));
+        Ok(())
+    }
+
+    fn exit_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
+        if node.kind() == "function_item" {
+            self.functions.pop();
+        } else if let Some(enclosing) = self.functions.last_mut() {
+            if enclosing.last().is_some_and(|(id, _)| *id == node.id()) {
+                enclosing.pop();
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The keyword of the expression if it increases the nesting, i.e. it is a loop, a `match`, or an `if` which is not
+/// the `else if` of a chain.
+fn nesting_keyword(node: Node<'_>) -> Option<Node<'_>> {
+    let keyword = match node.kind() {
+        "if_expression"
+            if node
+                .parent()
+                .is_none_or(|parent| parent.kind() != "else_clause") =>
+        {
+            "if"
+        }
+        "match_expression" => "match",
+        "loop_expression" => "loop",
+        "while_expression" => "while",
+        "for_expression" => "for",
+        _ => return None,
+    };
+    let mut cursor = node.walk();
+    let found = node
+        .children(&mut cursor)
+        .find(|child| child.kind() == keyword);
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tree::parse_rust_code, verifier::assert_issues};
+
+    #[test]
+    fn test_nesting_depth() {
+        assert_issues(
+            &NestingDepthCheck::new(3),
+            r#"
+fn main() {
+    for order in orders {
+        if order.is_paid() {
+            match order.shipping() {
+                Shipping::Express => {
+                    if heavy(order) { // Noncompliant {{Refactor this code to not nest more than 3 "if", "match" and loop expressions.}}
+//                  ^^
+                        while busy() {}
+                    }
+                }
+                Shipping::Standard => 'retry: loop {}, // Noncompliant
+//                                            ^^^^
+            }
+        } else if order.is_late() {
+            while busy() {
+                if ready() {} // Noncompliant
+//              ^^
+            }
+        } else {
+            fn nested() {
+                if a { if b { if c {} } }
+            }
+        }
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_nesting_locations() {
+        // The secondary locations precede the issue, where the annotations cannot give them
+        let source_code = r#"
+fn main() {
+    for order in orders {
+        if order.is_paid() {
+            match order.shipping() {
+                Shipping::Express => {
+                    if heavy(order) {
+                        while busy() {} // Noncompliant {{Refactor this code to not nest more than 4 "if", "match" and loop expressions.}}
+                    }
+                }
+                Shipping::Standard => 'retry: loop {},
+            }
+        }
+    }
+}
+"#;
+        let rule = NestingDepthCheck::new(4);
+        assert_issues(&rule, source_code);
+        let tree = parse_rust_code(source_code).unwrap();
+        let issues = rule.check(&tree, source_code).unwrap();
+        assert_eq!(
+            issues[0]
+                .secondary_locations
+                .iter()
+                .map(|secondary| (secondary.location.start_line, secondary.message.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, "Nesting +1"),
+                (4, "Nesting +1"),
+                (5, "Nesting +1"),
+                (7, "Nesting +1")
+            ]
+        );
+    }
+}
@@ -0,0 +1,287 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::test_code::TestCode,
+};
+use std::collections::HashSet;
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S7468";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Panicking macros should not be used outside of tests",
+    markdown: r#"
+This rule raises an issue on the invocations of the `panic!`, `todo!`, `unimplemented!` and `unreachable!` macros
+outside of the test code: the test files and the items annotated with `#[test]` or `#[cfg(test)]`.
+
+## Why is this an issue?
+
+These macros abort the thread, or the whole program, at runtime. `todo!` and `unimplemented!` are placeholders for
+code which was not written yet and should not reach production. `panic!` is rarely the right way to report an error
+from a library: the caller gets no chance to recover from it.
+
+```noncompliant
+fn parse_mode(text: &str) -> Mode {
+    match text {
+        "fast" => Mode::Fast,
+        "safe" => Mode::Safe,
+        _ => panic!("unknown mode {}", text),
+    }
+}
+```
+
+### How to fix it
+
+Return an error to the caller, and write the missing code.
+
+```compliant
+fn parse_mode(text: &str) -> Result<Mode, ParseError> {
+    match text {
+        "fast" => Ok(Mode::Fast),
+        "safe" => Ok(Mode::Safe),
+        _ => Err(ParseError::UnknownMode(text.to_string())),
+    }
+}
+```
+
+An `unreachable!` invocation documents an invariant of the code. The `allowJustifiedUnreachable` parameter allows the
+invocations which are justified by a comment, on the same line or on the line before.
+
+```compliant
+match index % 3 {
+    0 => left(),
+    1 => middle(),
+    2 => right(),
+    // The remainder of a division by 3 is lower than 3
+    _ => unreachable!(),
+}
+```
+
+## Resources
+
+### Documentation
+
+- The Rust Programming Language - [Unrecoverable Errors with
+  panic!](https://doc.rust-lang.org/book/ch09-01-unrecoverable-errors-with-panic.html)
+- Rust Documentation - [unreachable](https://doc.rust-lang.org/std/macro.unreachable.html)
+"#,
+};
+
+pub struct PanicMacroCheck {
+    allow_justified_unreachable: bool,
+    /// The file only contains test code, see `TestCode`.
+    test_file: bool,
+}
+
+impl PanicMacroCheck {
+    pub fn new(allow_justified_unreachable: bool, test_file: bool) -> Self {
+        PanicMacroCheck {
+            allow_justified_unreachable,
+            test_file,
+        }
+    }
+}
+
+impl Rule for PanicMacroCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let test_code = TestCode::find(tree, source_code, self.test_file);
+        let comment_lines = if self.allow_justified_unreachable {
+            comment_lines(tree)
+        } else {
+            HashSet::new()
+        };
+        let mut issues = vec![];
+        for invocation in
+            NodeIterator::new(tree.root_node(), |node| node.kind() == "macro_invocation")
+        {
+            let Some(name) = panicking_macro(invocation, source_code) else {
+                continue;
+            };
+            let macro_name = &source_code[name.byte_range()];
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(name).to_sonar_location(source_code);
+            if test_code.contains_node(invocation) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, "invocation in test code".to_string());
+                }
+                continue;
+            }
+            if macro_name == "unreachable" && is_justified(invocation, &comment_lines) {
+                if tracer.is_enabled() {
+                    tracer.trace(
+                        &location,
+                        &location,
+                        "justified by a comment, allowed by 'allowJustifiedUnreachable'"
+                            .to_string(),
+                    );
+                }
+                continue;
+            }
+
+            let message = match macro_name {
+                "todo" | "unimplemented" => format!(
+                    "Complete this code instead of panicking with \"{}!\".",
+                    macro_name
+                ),
+                _ => format!(
+                    "Handle this case instead of panicking with \"{}!\".",
+                    macro_name
+                ),
+            };
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message,
+                location,
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Name of the invoked macro if it is one of the panicking macros, also with a path like `std::panic!`.
+fn panicking_macro<'a>(invocation: Node<'a>, source_code: &str) -> Option<Node<'a>> {
+    let path = invocation.child_by_field_name("macro")?;
+    let name = match path.kind() {
+        "scoped_identifier" => path.child_by_field_name("name")?,
+        _ => path,
+    };
+    matches!(
+        &source_code[name.byte_range()],
+        "panic" | "todo" | "unimplemented" | "unreachable"
+    )
+    .then_some(name)
+}
+
+/// Lines, starting at 0, on which a comment ends.
+fn comment_lines(tree: &Tree) -> HashSet<usize> {
+    NodeIterator::new(tree.root_node(), |node| {
+        matches!(node.kind(), "line_comment" | "block_comment")
+    })
+    .map(|comment| comment.end_position().row)
+    .collect()
+}
+
+/// Whether a comment ends on the line before the invocation, or on the line of its end.
+fn is_justified(invocation: Node<'_>, comment_lines: &HashSet<usize>) -> bool {
+    let start = invocation.start_position().row;
+    (start > 0 && comment_lines.contains(&(start - 1)))
+        || comment_lines.contains(&invocation.end_position().row)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_panic_macro() {
+        assert_issues(
+            &PanicMacroCheck::new(false, false),
+            r#"
+fn mode(text: &str) -> u8 {
+    match text {
+        "fast" => 1,
+        "safe" => todo!(), // Noncompliant {{Complete this code instead of panicking with "todo!".}}
+//                ^^^^
+        "" => unimplemented!("empty"), // Noncompliant {{Complete this code instead of panicking with "unimplemented!".}}
+        _ => std::panic!("unknown mode {}", text), // Noncompliant {{Handle this case instead of panicking with "panic!".}}
+    }
+}
+
+fn remainder(index: usize) -> u8 {
+    match index % 2 {
+        0 => 0,
+        1 => 1,
+        // The remainder of a division by 2 is lower than 2
+        _ => unreachable!(), // Noncompliant {{Handle this case instead of panicking with "unreachable!".}}
+    }
+}
+
+#[test]
+fn test_mode() {
+    if mode("fast") != 1 {
+        panic!("wrong mode");
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_justified_unreachable() {
+        // The annotations are not on the lines of the invocations or before, where they would justify them
+        assert_issues(
+            &PanicMacroCheck::new(true, false),
+            r#"
+fn remainder(index: usize) -> u8 {
+    match index % 2 {
+        0 => 0,
+        1 => 1,
+        // The remainder of a division by 2 is lower than 2
+        _ => unreachable!(),
+    }
+}
+
+fn last(index: usize) -> u8 { // Noncompliant@+3
+    match index {
+        0 => 0,
+        _ => unreachable!("not justified"),
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_test_file() {
+        assert_issues(
+            &PanicMacroCheck::new(false, true),
+            r#"
+fn mode(text: &str) -> u8 {
+    match text {
+        "fast" => 1,
+        _ => todo!(),
+    }
+}
+"#,
+        );
+    }
+}
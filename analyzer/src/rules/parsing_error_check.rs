@@ -17,13 +17,29 @@
 
 use crate::{
     issue::Issue,
-    rules::rule::Rule,
-    tree::{walk_tree, AnalyzerError, NodeVisitor, SonarLocation, TreeSitterLocation},
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{
+        is_async_closure_error, walk_tree, AnalyzerError, NodeVisitor, SonarLocation,
+        TreeSitterLocation,
+    },
 };
 use tree_sitter::{Node, Tree};
 
 const RULE_KEY: &str = "S2260";
 
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Rust source files should not have syntax errors",
+    markdown: r#"
+## Why is this an issue?
+
+When the Rust parser fails, it is possible to record the failure as a violation on the file. This way, not only it is
+possible to track the number of files that do not parse but also to easily find out why they do not parse.
+"#,
+};
+
+#[derive(Default)]
 pub struct ParsingErrorCheck;
 
 impl ParsingErrorCheck {
@@ -33,8 +49,21 @@ impl ParsingErrorCheck {
 }
 
 impl Rule for ParsingErrorCheck {
-    fn check(&self, tree: &Tree, source_code: &str) -> Result<Vec<Issue>, AnalyzerError> {
-        let mut visitor = RuleVisitor::new(source_code);
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut visitor = RuleVisitor::new(source_code, tracer);
         walk_tree(tree.root_node(), &mut visitor)?;
 
         Ok(visitor.issues)
@@ -43,13 +72,15 @@ impl Rule for ParsingErrorCheck {
 
 struct RuleVisitor<'a> {
     source_code: &'a str,
+    tracer: &'a mut dyn RuleTracer,
     issues: Vec<Issue>,
 }
 
 impl<'a> RuleVisitor<'a> {
-    fn new(source_code: &'a str) -> Self {
+    fn new(source_code: &'a str, tracer: &'a mut dyn RuleTracer) -> Self {
         Self {
             source_code,
+            tracer,
             issues: Vec::new(),
         }
     }
@@ -60,6 +91,10 @@ impl<'a> RuleVisitor<'a> {
             message,
             location,
             secondary_locations: vec![],
+            severity: None,
+            quick_fixes: vec![],
+            fingerprint: None,
+            hotspot: None,
         });
     }
 }
@@ -70,15 +105,28 @@ impl NodeVisitor for RuleVisitor<'_> {
         // - Error nodes: Syntax errors representing parts of the code that could not be incorporated into a valid syntax tree.
         // - Missing nodes: Missing nodes that are inserted by the parser in order to recover from certain kinds of syntax errors.
 
-        if node.is_error() {
+        if node.is_error() && !has_error_ancestor(node) && !is_async_closure_error(node) {
             // Error nodes don't include a comprehensive syntax error message and can spread over multiple nodes.
             // Tree-sitter supposedly introduced some undocumented API supposed to help in that direction, but it's low-level.
-            // Therefore, we only emit a generic parsing error message until the following ticket is fixed:
+            // Therefore, we only report the first token that could not be parsed until the following ticket is fixed:
             // https://github.com/tree-sitter/tree-sitter/issues/255
-            let message = "A syntax error occurred during parsing.".to_string();
+            let message = match first_token(node) {
+                Some(token) => format!(
+                    "A syntax error occurred during parsing: unexpected {}.",
+                    token_text(token, self.source_code)
+                ),
+                None => "A syntax error occurred during parsing.".to_string(),
+            };
             let location =
                 TreeSitterLocation::from_tree_sitter_node(node).to_sonar_location(self.source_code);
 
+            if self.tracer.is_enabled() {
+                self.tracer.trace(
+                    &location,
+                    &location,
+                    "Tree-sitter could not incorporate this code into the syntax tree".to_string(),
+                );
+            }
             self.new_issue(message, location);
         }
 
@@ -90,8 +138,8 @@ impl NodeVisitor for RuleVisitor<'_> {
             let message = format!("A syntax error occurred during parsing: {}.", error);
 
             // The location of the missing node is the location of the token that should have been there, which means that the location might not
-            // even exist in the original source code. In order to avoid reporting on non-existant locations, we use the location of either the closest
-            // sibling (if it exists) or the parent node.
+            // even exist in the original source code. In order to avoid reporting on non-existant locations, we use the location of either the last
+            // token of the closest sibling (if it exists) or the parent node.
             let parent = get_sibling_or_parent(node).ok_or(AnalyzerError::FileError(
                 "a missing node must have a valid parent".to_string(),
             ))?;
@@ -99,6 +147,18 @@ impl NodeVisitor for RuleVisitor<'_> {
             let location = TreeSitterLocation::from_tree_sitter_node(parent)
                 .to_sonar_location(self.source_code);
 
+            if self.tracer.is_enabled() {
+                let missing = TreeSitterLocation::from_tree_sitter_node(node)
+                    .to_sonar_location(self.source_code);
+                self.tracer.trace(
+                    &location,
+                    &missing,
+                    format!(
+                        "Tree-sitter inserted the node {} to recover, it is reported on its closest sibling or parent",
+                        sexp
+                    ),
+                );
+            }
             self.new_issue(message, location);
         }
 
@@ -109,12 +169,65 @@ impl NodeVisitor for RuleVisitor<'_> {
 fn get_sibling_or_parent(node: Node<'_>) -> Option<Node<'_>> {
     match node.prev_sibling() {
         Some(prev_sibling) if !prev_sibling.is_error() && !prev_sibling.is_missing() => {
-            Some(prev_sibling)
+            Some(last_token(prev_sibling))
         }
         _ => node.parent(),
     }
 }
 
+fn has_error_ancestor(node: Node<'_>) -> bool {
+    let mut parent = node.parent();
+    while let Some(ancestor) = parent {
+        if ancestor.is_error() {
+            return true;
+        }
+        parent = ancestor.parent();
+    }
+    false
+}
+
+/// Literals are tokens, despite their quotes and content being distinct nodes.
+fn is_token(node: Node<'_>) -> bool {
+    node.child_count() == 0 || node.kind().ends_with("_literal")
+}
+
+/// First token of the node which is not empty, e.g. the first unexpected token of an error node.
+fn first_token(node: Node<'_>) -> Option<Node<'_>> {
+    if is_token(node) {
+        return Some(node).filter(|node| node.start_byte() < node.end_byte());
+    }
+    let mut cursor = node.walk();
+    let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+    children.into_iter().find_map(first_token)
+}
+
+/// Last token of the node which is not missing, or the node itself when there is none.
+fn last_token(node: Node<'_>) -> Node<'_> {
+    let mut last = node;
+    while let Some(child) = (0..last.child_count())
+        .filter(|_| !is_token(last))
+        .rev()
+        .filter_map(|i| last.child(i))
+        .find(|child| !child.is_missing() && child.start_byte() < child.end_byte())
+    {
+        last = child;
+    }
+    last
+}
+
+/// Text of the token quoted for a message, truncated to its first line and a few characters.
+fn token_text(token: Node<'_>, source_code: &str) -> String {
+    const MAX_CHARS: usize = 20;
+    let text = &source_code[token.byte_range()];
+    let first_line = text.lines().next().unwrap_or_default();
+    if first_line.len() < text.len() || first_line.chars().count() > MAX_CHARS {
+        let truncated: String = first_line.chars().take(MAX_CHARS).collect();
+        format!("\"{}...\"", truncated)
+    } else {
+        format!("\"{}\"", text)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,6 +238,8 @@ mod tests {
         let source_code = r#"
 fn main() {
     let x = 42;
+    let c = async move |x| x;
+    spawn(async move || { x });
 }
 "#;
         let rule = ParsingErrorCheck::new();
@@ -160,10 +275,14 @@ fn
                     end_column: 14,
                 },
                 secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
             },
             Issue {
                 rule_key: RULE_KEY.to_string(),
-                message: "A syntax error occurred during parsing.".to_string(),
+                message: "A syntax error occurred during parsing: unexpected \"fn\".".to_string(),
                 location: SonarLocation {
                     start_line: 6,
                     start_column: 0,
@@ -171,9 +290,84 @@ fn
                     end_column: 2,
                 },
                 secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
             },
         ];
 
         assert_eq!(actual, expected);
     }
+
+    #[test]
+    fn test_precise_locations() {
+        let source_code = r#"
+fn main() {
+    foo(1, 2)
+    let y = 1;
+}
+
+fn bar() {
+    let v = vec![1]
+        .len()
+}
+
+fn baz() { x + "a very long string literal"
+    "another one" }
+
+fn qux() { let s = r"multi
+line" }
+"#;
+        let rule = ParsingErrorCheck::new();
+        let tree = parse_rust_code(source_code).unwrap();
+
+        let actual: Vec<(String, SonarLocation)> = rule
+            .check(&tree, source_code)
+            .unwrap()
+            .into_iter()
+            .map(|issue| (issue.message, issue.location))
+            .collect();
+        let expected = vec![
+            (
+                "A syntax error occurred during parsing: unexpected \"foo\".".to_string(),
+                SonarLocation {
+                    start_line: 3,
+                    start_column: 4,
+                    end_line: 3,
+                    end_column: 13,
+                },
+            ),
+            (
+                "A syntax error occurred during parsing: missing \";\".".to_string(),
+                SonarLocation {
+                    start_line: 9,
+                    start_column: 13,
+                    end_line: 9,
+                    end_column: 14,
+                },
+            ),
+            (
+                "A syntax error occurred during parsing: unexpected \"\"a very long string ...\"."
+                    .to_string(),
+                SonarLocation {
+                    start_line: 12,
+                    start_column: 15,
+                    end_line: 12,
+                    end_column: 43,
+                },
+            ),
+            (
+                "A syntax error occurred during parsing: missing \";\".".to_string(),
+                SonarLocation {
+                    start_line: 15,
+                    start_column: 19,
+                    end_line: 16,
+                    end_column: 5,
+                },
+            ),
+        ];
+
+        assert_eq!(actual, expected);
+    }
 }
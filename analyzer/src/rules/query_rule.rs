@@ -0,0 +1,415 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, Severity},
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    toml::parse_parameter,
+    tree::{check_deadline, interrupted, AnalyzerError, TreeSitterLocation},
+};
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex, OnceLock},
+    time::SystemTime,
+};
+use tree_sitter::{Query, QueryCursor, QueryCursorOptions, StreamingIterator, Tree};
+
+/// Extension of the query files of the 'custom_rules_dir' setting.
+const QUERY_FILE_EXTENSION: &str = "scm";
+
+/// Name of the capture locating the issues, the first capture of the pattern being used when it is absent.
+const ISSUE_CAPTURE: &str = "issue";
+
+/// Rule defined by a tree-sitter query file of the 'custom_rules_dir' setting, raising an issue on each match.
+///
+/// The file starts with a header of `key = value` comments, in TOML, followed by the query:
+///
+/// ```scheme
+/// ; key = "custom:no-unwrap"
+/// ; message = "Handle the error instead of calling '{method}'."
+/// ; severity = "MAJOR"
+///
+/// (call_expression
+///   function: (field_expression field: (field_identifier) @method)
+///   (#eq? @method "unwrap")) @issue
+/// ```
+///
+/// The issue is located on the `@issue` capture, and the `{name}` placeholders of the message are replaced by the text
+/// of the captures. The severity is optional.
+pub struct QueryRule {
+    definition: Arc<QueryRuleDefinition>,
+}
+
+struct QueryRuleDefinition {
+    key: &'static str,
+    message: String,
+    severity: Option<Severity>,
+    query: Query,
+}
+
+/// Compiled rules by query file, recompiled when the file is modified.
+type QueryRuleCache = HashMap<PathBuf, (SystemTime, Arc<QueryRuleDefinition>)>;
+
+impl QueryRule {
+    /// Parses and compiles a query file, whose path is only used in the errors.
+    fn parse(text: &str, path: &Path) -> Result<QueryRuleDefinition, AnalyzerError> {
+        let invalid = |message: &str| {
+            AnalyzerError::GlobalError(format!(
+                "invalid custom rule {}: {}",
+                path.display(),
+                message
+            ))
+        };
+        let mut header = HashMap::new();
+        for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let Some(comment) = line.strip_prefix(';') else {
+                break;
+            };
+            let Some((name, value)) = comment.trim_start_matches(';').split_once('=') else {
+                continue;
+            };
+            let value = parse_parameter(value.trim()).map_err(|err| invalid(&err))?;
+            header.insert(name.trim().to_string(), value);
+        }
+        let field = |name: &str| {
+            header
+                .get(name)
+                .filter(|value| !value.is_empty())
+                .ok_or_else(|| invalid(&format!("missing '{}' in the header", name)))
+        };
+        let key = field("key")?;
+        let message = field("message")?.clone();
+        let severity = header
+            .get("severity")
+            .map(|severity| severity.to_ascii_uppercase().parse::<Severity>())
+            .transpose()
+            .map_err(|err| invalid(&err))?;
+        let query = Query::new(&tree_sitter_rust::LANGUAGE.into(), text)
+            .map_err(|err| invalid(&format!("{}", err)))?;
+        if query.capture_names().is_empty() {
+            return Err(invalid("the query has no capture to report the issues on"));
+        }
+        Ok(QueryRuleDefinition {
+            key: intern(key),
+            message,
+            severity,
+            query,
+        })
+    }
+}
+
+impl Rule for QueryRule {
+    fn key(&self) -> &'static str {
+        self.definition.key
+    }
+
+    fn description(&self) -> RuleDescription {
+        RuleDescription {
+            key: self.definition.key,
+            title: intern(&self.definition.message),
+            markdown: "",
+        }
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let definition = &self.definition;
+        let capture_names = definition.query.capture_names();
+        let issue_capture = definition.query.capture_index_for_name(ISSUE_CAPTURE);
+
+        let mut cursor = QueryCursor::new();
+        let mut progress = |_: &_| interrupted();
+        let mut matches = cursor.matches_with_options(
+            &definition.query,
+            tree.root_node(),
+            source_code.as_bytes(),
+            QueryCursorOptions::new().progress_callback(&mut progress),
+        );
+        let mut issues = vec![];
+        let mut reported = HashSet::new();
+        while let Some(query_match) = matches.next() {
+            let Some(node) = query_match
+                .captures
+                .iter()
+                .find(|capture| Some(capture.index) == issue_capture)
+                .or(query_match.captures.first())
+                .map(|capture| capture.node)
+            else {
+                continue;
+            };
+            if !reported.insert(node.id()) {
+                continue;
+            }
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(node).to_sonar_location(source_code);
+            let mut message = definition.message.clone();
+            for capture in query_match.captures {
+                let placeholder = format!("{{{}}}", capture_names[capture.index as usize]);
+                message = message.replace(&placeholder, &source_code[capture.node.byte_range()]);
+            }
+            if tracer.is_enabled() {
+                tracer.trace(
+                    &location,
+                    &location,
+                    format!(
+                        "matched pattern {} of the query",
+                        query_match.pattern_index + 1
+                    ),
+                );
+            }
+            issues.push(Issue {
+                rule_key: definition.key.to_string(),
+                message,
+                location,
+                secondary_locations: vec![],
+                severity: definition.severity,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+        // Query execution halts when the progress callback reports that the deadline is exceeded
+        check_deadline()?;
+        Ok(issues)
+    }
+}
+
+/// Rules of the query files of the 'custom_rules_dir' setting, relative to the project directory, in the order of
+/// their file names.
+pub fn query_rules(parameters: &HashMap<String, String>) -> Result<Vec<QueryRule>, AnalyzerError> {
+    let Some(directory) = parameters.get("custom_rules_dir") else {
+        return Ok(vec![]);
+    };
+    let directory = Path::new(
+        parameters
+            .get("project_dir")
+            .map_or(".", |dir| dir.as_str()),
+    )
+    .join(directory);
+    let read_error = |path: &Path, err: std::io::Error| {
+        AnalyzerError::GlobalError(format!("failed to read {}: {}", path.display(), err))
+    };
+    let mut paths: Vec<PathBuf> = fs::read_dir(&directory)
+        .map_err(|err| read_error(&directory, err))?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == QUERY_FILE_EXTENSION)
+        })
+        .collect();
+    paths.sort();
+
+    static CACHE: OnceLock<Mutex<QueryRuleCache>> = OnceLock::new();
+    let mut cache = CACHE
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let mut rules = vec![];
+    for path in paths {
+        let modified = fs::metadata(&path)
+            .and_then(|metadata| metadata.modified())
+            .map_err(|err| read_error(&path, err))?;
+        let definition = match cache.get(&path) {
+            Some((cached, definition)) if *cached == modified => definition.clone(),
+            _ => {
+                let text = fs::read_to_string(&path).map_err(|err| read_error(&path, err))?;
+                let definition = Arc::new(QueryRule::parse(&text, &path)?);
+                cache.insert(path, (modified, definition.clone()));
+                definition
+            }
+        };
+        rules.push(QueryRule { definition });
+    }
+    Ok(rules)
+}
+
+/// Static copy of a text of the custom rules, as the built-in rules have static keys. Each distinct text is copied once.
+fn intern(text: &str) -> &'static str {
+    static TEXTS: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+    let mut texts = TEXTS
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    match texts.get(text) {
+        Some(interned) => interned,
+        None => {
+            let interned: &'static str = Box::leak(text.to_string().into_boxed_str());
+            texts.insert(interned);
+            interned
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        issue::find_issues,
+        rules::rule::default_parameters,
+        tree::{parse_rust_code, SonarLocation},
+    };
+
+    const NO_UNWRAP: &str = r#"
+; key = "custom:no-unwrap"
+; message = "Handle the error instead of calling '{method}'."
+; severity = "major"
+
+(call_expression
+  function: (field_expression field: (field_identifier) @method)
+  (#match? @method "^(unwrap|expect)$")) @issue
+"#;
+
+    #[test]
+    fn test_query_rules() {
+        let dir = std::env::temp_dir().join(format!("query-rules-{}", std::process::id()));
+        fs::create_dir_all(dir.join("rules")).unwrap();
+        fs::write(dir.join("rules/no_unwrap.scm"), NO_UNWRAP).unwrap();
+        fs::write(
+            dir.join("rules/todo.scm"),
+            "; key = \"custom:todo-macro\"\n; message = \"Implement this.\"\n(macro_invocation macro: (identifier) @name (#eq? @name \"todo\"))\n",
+        )
+        .unwrap();
+        fs::write(dir.join("rules/README.md"), "Not a rule").unwrap();
+        let mut parameters = default_parameters();
+        parameters.insert("project_dir".to_string(), dir.to_string_lossy().to_string());
+        parameters.insert("custom_rules_dir".to_string(), "rules".to_string());
+
+        let source_code =
+            "fn main() {\n    let x = parse().unwrap();\n    f(x).expect(\"f\");\n    todo!()\n}\n";
+        let tree = parse_rust_code(source_code).unwrap();
+        // The built-in rules are not under test
+        let custom_issues = |parameters: &HashMap<String, String>| -> Vec<Issue> {
+            find_issues(&tree, source_code, parameters)
+                .unwrap()
+                .into_iter()
+                .filter(|issue| issue.rule_key.starts_with("custom:"))
+                .collect()
+        };
+        let issues = custom_issues(&parameters);
+        assert_eq!(
+            issues,
+            vec![
+                Issue {
+                    rule_key: "custom:no-unwrap".to_string(),
+                    message: "Handle the error instead of calling 'unwrap'.".to_string(),
+                    location: SonarLocation {
+                        start_line: 2,
+                        start_column: 12,
+                        end_line: 2,
+                        end_column: 28,
+                    },
+                    secondary_locations: vec![],
+                    severity: Some(Severity::Major),
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                },
+                Issue {
+                    rule_key: "custom:no-unwrap".to_string(),
+                    message: "Handle the error instead of calling 'expect'.".to_string(),
+                    location: SonarLocation {
+                        start_line: 3,
+                        start_column: 4,
+                        end_line: 3,
+                        end_column: 20,
+                    },
+                    secondary_locations: vec![],
+                    severity: Some(Severity::Major),
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                },
+                Issue {
+                    rule_key: "custom:todo-macro".to_string(),
+                    message: "Implement this.".to_string(),
+                    location: SonarLocation {
+                        start_line: 4,
+                        start_column: 4,
+                        end_line: 4,
+                        end_column: 8,
+                    },
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                },
+            ]
+        );
+
+        // The severity and the disabled rules settings apply to the custom rules
+        parameters.insert(
+            "custom:no-unwrap:severity".to_string(),
+            "BLOCKER".to_string(),
+        );
+        parameters.insert(
+            "disabled_rules".to_string(),
+            "custom:todo-macro".to_string(),
+        );
+        let issues = custom_issues(&parameters);
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].severity, Some(Severity::Blocker));
+
+        fs::write(
+            dir.join("rules/todo.scm"),
+            "; key = \"S3776\"\n; message = \"Duplicate\"\n(identifier) @issue\n",
+        )
+        .unwrap();
+        // The modification time of the file may not change within the same tick, the cache is cleared with a new file
+        fs::rename(dir.join("rules/todo.scm"), dir.join("rules/duplicate.scm")).unwrap();
+        assert!(matches!(
+            find_issues(&tree, source_code, &parameters),
+            Err(AnalyzerError::GlobalError(message)) if message.contains("rule 'S3776' is already defined")
+        ));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_invalid_query_rules() {
+        let path = Path::new("rules/invalid.scm");
+        let error = |text: &str| match QueryRule::parse(text, path) {
+            Err(AnalyzerError::GlobalError(message)) => message,
+            _ => panic!("expected an error for {}", text),
+        };
+        assert_eq!(
+            error("; message = \"Message\"\n(identifier) @issue"),
+            "invalid custom rule rules/invalid.scm: missing 'key' in the header"
+        );
+        assert_eq!(
+            error("; key = \"custom\"\n; message = \"Message\"\n; severity = \"HIGH\"\n(identifier) @issue"),
+            "invalid custom rule rules/invalid.scm: unknown severity 'HIGH', expected one of BLOCKER, CRITICAL, MAJOR, MINOR or INFO"
+        );
+        assert_eq!(
+            error("; key = \"custom\"\n; message = \"Message\"\n(identifier)"),
+            "invalid custom rule rules/invalid.scm: the query has no capture to report the issues on"
+        );
+        assert!(
+            error("; key = \"custom\"\n; message = \"Message\"\n(unknown_node) @issue")
+                .starts_with(
+                    "invalid custom rule rules/invalid.scm: Query error at 3:2. Invalid node type"
+                )
+        );
+    }
+}
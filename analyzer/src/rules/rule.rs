@@ -15,44 +15,386 @@
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
 
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
-    issue::Issue,
+    issue::{Issue, RuleType},
+    rule_description::RuleDescription,
     rules::{
+        boolean_literal_check::BooleanLiteralCheck,
         cognitive_complexity_check::CognitiveComplexityCheck,
+        collapsible_if_check::CollapsibleIfCheck,
+        comment_pattern_check::{CommentPatternCheck, DEFAULT_MESSAGE},
+        commented_out_code_check::CommentedOutCodeCheck,
+        duplicate_condition_check::DuplicateConditionCheck,
+        duplicated_string_check::DuplicatedStringCheck,
+        empty_block_check::EmptyBlockCheck,
+        empty_function_check::EmptyFunctionCheck,
+        file_length_check::FileLengthCheck,
+        function_length_check::FunctionLengthCheck,
+        hardcoded_credentials_check::HardcodedCredentialsCheck,
+        hardcoded_ip_check::HardcodedIpCheck,
+        identical_branches_check::{AllBranchesIdenticalCheck, DuplicateBranchCheck},
+        identical_operands_check::IdenticalOperandsCheck,
+        large_pass_by_value_check::LargePassByValueCheck,
+        magic_number_check::MagicNumberCheck,
+        marker_comment_check::MarkerCommentCheck,
+        nesting_depth_check::NestingDepthCheck,
+        panic_macro_check::PanicMacroCheck,
         parsing_error_check::ParsingErrorCheck,
+        query_rule::query_rules,
+        too_many_parameters_check::TooManyParametersCheck,
+        unused_parameter_check::UnusedParameterCheck,
+        unused_variable_check::UnusedVariableCheck,
+        unwrap_check::UnwrapCheck,
     },
-    tree::AnalyzerError,
+    tree::{AnalyzerError, SonarLocation},
 };
 use tree_sitter::Tree;
 
 pub trait Rule {
-    fn check(&self, tree: &Tree, source_code: &str) -> Result<Vec<Issue>, AnalyzerError>;
+    /// Key of the rule, as declared by the Sonar plugin.
+    fn key(&self) -> &'static str;
+
+    /// Description of the rule, exported as its description in the Sonar plugin.
+    fn description(&self) -> RuleDescription;
+
+    /// Type of the issues of the rule, which must match the type declared in its metadata.
+    fn rule_type(&self) -> RuleType {
+        RuleType::CodeSmell
+    }
+
+    fn check(&self, tree: &Tree, source_code: &str) -> Result<Vec<Issue>, AnalyzerError> {
+        self.check_traced(tree, source_code, &mut NoTrace)
+    }
+
+    /// Same as `check`, additionally reporting to the tracer how the rule came to its decisions.
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError>;
+}
+
+/// Hook through which rules explain their decisions, e.g. the nodes they matched or how they compared a value to their threshold.
+///
+/// Each trace relates to a subject: the location where the rule reports an issue, or would have reported one.
+pub trait RuleTracer {
+    /// Rules should not spend time building trace messages when tracing is disabled.
+    fn is_enabled(&self) -> bool {
+        true
+    }
+
+    fn trace(&mut self, subject: &SonarLocation, location: &SonarLocation, message: String);
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Trace {
+    pub subject: SonarLocation,
+    pub location: SonarLocation,
+    pub message: String,
+}
+
+impl RuleTracer for Vec<Trace> {
+    fn trace(&mut self, subject: &SonarLocation, location: &SonarLocation, message: String) {
+        self.push(Trace {
+            subject: subject.clone(),
+            location: location.clone(),
+            message,
+        });
+    }
+}
+
+struct NoTrace;
+
+impl RuleTracer for NoTrace {
+    fn is_enabled(&self) -> bool {
+        false
+    }
+
+    fn trace(&mut self, _subject: &SonarLocation, _location: &SonarLocation, _message: String) {}
+}
+
+/// Rules compiled in by another crate, which builds its own analyzer on top of this one (see `register_rule_pack`).
+///
+/// ```
+/// use analyzer::{
+///     analyze::analyze,
+///     issue::Issue,
+///     rule_description::RuleDescription,
+///     rules::rule::{default_parameters, register_rule_pack, rule_parameter, Rule, RulePack, RuleTracer},
+///     tree::{AnalyzerError, SonarLocation},
+/// };
+/// use std::collections::HashMap;
+/// use tree_sitter::Tree;
+///
+/// struct LongFileCheck {
+///     max: usize,
+/// }
+///
+/// impl Rule for LongFileCheck {
+///     fn key(&self) -> &'static str {
+///         "company:long-file"
+///     }
+///
+///     fn description(&self) -> RuleDescription {
+///         RuleDescription {
+///             key: self.key(),
+///             title: "Files should not be too long",
+///             markdown: "Split the files which have more lines than the maximum.",
+///         }
+///     }
+///
+///     fn check_traced(
+///         &self,
+///         _tree: &Tree,
+///         source_code: &str,
+///         _tracer: &mut dyn RuleTracer,
+///     ) -> Result<Vec<Issue>, AnalyzerError> {
+///         let lines = source_code.lines().count();
+///         if lines <= self.max {
+///             return Ok(vec![]);
+///         }
+///         Ok(vec![Issue {
+///             rule_key: self.key().to_string(),
+///             message: format!("Split this file of {} lines.", lines),
+///             location: SonarLocation {
+///                 start_line: 1,
+///                 start_column: 0,
+///                 end_line: 1,
+///                 end_column: 0,
+///             },
+///             secondary_locations: vec![],
+///             severity: None,
+///             quick_fixes: vec![],
+///             fingerprint: None,
+///             hotspot: None,
+///         }])
+///     }
+/// }
+///
+/// struct CompanyRules;
+///
+/// impl RulePack for CompanyRules {
+///     fn default_parameters(&self) -> HashMap<String, String> {
+///         HashMap::from([("company:long-file:max".to_string(), "2".to_string())])
+///     }
+///
+///     fn rules(&self, parameters: &HashMap<String, String>) -> Result<Vec<Box<dyn Rule>>, AnalyzerError> {
+///         let max = rule_parameter(parameters, "company:long-file:max")?;
+///         Ok(vec![Box::new(LongFileCheck { max })])
+///     }
+/// }
+///
+/// register_rule_pack(CompanyRules);
+///
+/// let issues = analyze("fn a() {}\nfn b() {}\nfn c() {}\n", &default_parameters())?.issues;
+/// assert_eq!(issues[0].message, "Split this file of 3 lines.");
+/// # Ok::<(), AnalyzerError>(())
+/// ```
+pub trait RulePack: Send + Sync {
+    /// Default values of the parameters of the rules, see `default_parameters`.
+    fn default_parameters(&self) -> HashMap<String, String> {
+        HashMap::new()
+    }
+
+    /// Creates the rules with the parameters of the analysis, completed by the defaults of the pack.
+    fn rules(
+        &self,
+        parameters: &HashMap<String, String>,
+    ) -> Result<Vec<Box<dyn Rule>>, AnalyzerError>;
+}
+
+static RULE_PACKS: Mutex<Vec<Arc<dyn RulePack>>> = Mutex::new(Vec::new());
+
+/// Adds the rules of a pack to `all_rules`, for all the analyses of the process. The packs should be registered once,
+/// before the first analysis, e.g. at the start of the `main` function of the binary embedding the analyzer.
+///
+/// The rules of the packs are run, disabled and configured as the built-in rules, their keys must not collide with
+/// the keys of the other rules.
+pub fn register_rule_pack(pack: impl RulePack + 'static) {
+    RULE_PACKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .push(Arc::new(pack));
+}
+
+/// The registered packs, which are called without holding the lock, as they may use the functions of this module.
+fn rule_packs() -> Vec<Arc<dyn RulePack>> {
+    RULE_PACKS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Default values of the rule parameters, matching the defaults declared by the Sonar plugin, followed by the defaults
+/// of the registered rule packs.
+///
+/// These are used when the analyzer runs outside of SonarQube (e.g. from the command line).
+pub fn default_parameters() -> HashMap<String, String> {
+    let mut parameters = HashMap::new();
+    for pack in rule_packs() {
+        parameters.extend(pack.default_parameters());
+    }
+    parameters.extend([
+        ("S104:maximum".to_string(), "1000".to_string()),
+        ("S107:max".to_string(), "7".to_string()),
+        ("S124:regularExpression".to_string(), "".to_string()),
+        ("S124:message".to_string(), DEFAULT_MESSAGE.to_string()),
+        ("S138:max".to_string(), "100".to_string()),
+        ("S3776:threshold".to_string(), "15".to_string()),
+        ("S7465:sizeThreshold".to_string(), "256".to_string()),
+        ("S7467:allowInMain".to_string(), "false".to_string()),
+        (
+            "S7468:allowJustifiedUnreachable".to_string(),
+            "false".to_string(),
+        ),
+        ("S1135:additionalMarkers".to_string(), "".to_string()),
+        ("S1134:additionalMarkers".to_string(), "".to_string()),
+        ("S134:max".to_string(), "3".to_string()),
+        ("S109:authorizedNumbers".to_string(), "-1,0,1,2".to_string()),
+        ("S1192:threshold".to_string(), "3".to_string()),
+        (
+            "S2068:credentialWords".to_string(),
+            "password,passwd,passphrase,secret,token,api_key,apikey,access_key,private_key"
+                .to_string(),
+        ),
+    ]);
+    parameters
 }
 
+/// The built-in rules, followed by the rules of the registered rule packs (see `register_rule_pack`) and by the custom
+/// rules of the 'custom_rules_dir' setting (see `QueryRule`).
 pub fn all_rules(
     parameters: &HashMap<String, String>,
 ) -> Result<Vec<Box<dyn Rule>>, AnalyzerError> {
-    let cognitive_complexity_threshold = parameters
-        .get("S3776:threshold")
-        .ok_or(AnalyzerError::GlobalError(
-            "rule parameter for 'S3776:threshold' not found".to_string(),
-        ))
-        .and_then(|value| {
-            value.parse::<i32>().map_err(|err| {
-                AnalyzerError::GlobalError(format!(
-                    "could not parse 'S3776:threshold' parameter: {}",
-                    err
-                ))
-            })
-        })?;
-
-    Ok(vec![
-        Box::new(CognitiveComplexityCheck::new(
-            cognitive_complexity_threshold,
-        )),
+    let test_file = parameters
+        .get("test_file")
+        .is_some_and(|test_file| test_file == "true");
+    let mut rules: Vec<Box<dyn Rule>> = vec![
+        Box::new(CognitiveComplexityCheck::new(rule_parameter(
+            parameters,
+            "S3776:threshold",
+        )?)),
+        Box::new(LargePassByValueCheck::new(rule_parameter(
+            parameters,
+            "S7465:sizeThreshold",
+        )?)),
         Box::new(ParsingErrorCheck::new()),
+        Box::new(FunctionLengthCheck::new(rule_parameter(
+            parameters, "S138:max",
+        )?)),
+        Box::new(TooManyParametersCheck::new(rule_parameter(
+            parameters, "S107:max",
+        )?)),
+        Box::new(FileLengthCheck::new(rule_parameter(
+            parameters,
+            "S104:maximum",
+        )?)),
+        Box::new(UnwrapCheck::new(
+            rule_parameter(parameters, "S7467:allowInMain")?,
+            test_file,
+        )),
+        Box::new(PanicMacroCheck::new(
+            rule_parameter(parameters, "S7468:allowJustifiedUnreachable")?,
+            test_file,
+        )),
+        Box::new(UnusedVariableCheck),
+        Box::new(UnusedParameterCheck),
+        Box::new(CommentedOutCodeCheck),
+        Box::new(MarkerCommentCheck::todo(&rule_parameter::<String>(
+            parameters,
+            "S1135:additionalMarkers",
+        )?)),
+        Box::new(MarkerCommentCheck::fixme(&rule_parameter::<String>(
+            parameters,
+            "S1134:additionalMarkers",
+        )?)),
+        Box::new(EmptyFunctionCheck),
+        Box::new(EmptyBlockCheck),
+        Box::new(AllBranchesIdenticalCheck),
+        Box::new(DuplicateBranchCheck),
+        Box::new(DuplicateConditionCheck),
+        Box::new(NestingDepthCheck::new(rule_parameter(
+            parameters, "S134:max",
+        )?)),
+        Box::new(BooleanLiteralCheck),
+        Box::new(CollapsibleIfCheck),
+        Box::new(MagicNumberCheck::new(
+            &rule_parameter::<String>(parameters, "S109:authorizedNumbers")?,
+            test_file,
+        )),
+        Box::new(IdenticalOperandsCheck::new(test_file)),
+        Box::new(DuplicatedStringCheck::new(
+            rule_parameter(parameters, "S1192:threshold")?,
+            test_file,
+        )),
+        Box::new(HardcodedCredentialsCheck::new(
+            &rule_parameter::<String>(parameters, "S2068:credentialWords")?,
+            test_file,
+        )),
+        Box::new(HardcodedIpCheck::new(test_file)),
+        // Without a regular expression, the rule has nothing to look for
+        Box::new(CommentPatternCheck::new(
+            parameters
+                .get("S124:regularExpression")
+                .map_or("", String::as_str),
+            parameters.get("S124:message").map_or("", String::as_str),
+        )?),
         // Add other rules here
-    ])
+    ];
+    let mut added_rules: Vec<Box<dyn Rule>> = vec![];
+    for pack in rule_packs() {
+        // The Sonar plugin only sends the parameters of the rules it declares
+        let mut pack_parameters = pack.default_parameters();
+        pack_parameters.extend(
+            parameters
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone())),
+        );
+        added_rules.extend(pack.rules(&pack_parameters)?);
+    }
+    added_rules.extend(
+        query_rules(parameters)?
+            .into_iter()
+            .map(|rule| Box::new(rule) as Box<dyn Rule>),
+    );
+    for rule in added_rules {
+        if rules.iter().any(|other| other.key() == rule.key()) {
+            return Err(AnalyzerError::GlobalError(format!(
+                "rule '{}' is already defined",
+                rule.key()
+            )));
+        }
+        rules.push(rule);
+    }
+    Ok(rules)
+}
+
+/// Reads the parameter of a rule, e.g. 'S3776:threshold', which must be set.
+pub fn rule_parameter<T>(
+    parameters: &HashMap<String, String>,
+    key: &str,
+) -> Result<T, AnalyzerError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    parameters
+        .get(key)
+        .ok_or(AnalyzerError::GlobalError(format!(
+            "rule parameter for '{}' not found",
+            key
+        )))
+        .and_then(|value| {
+            value.parse::<T>().map_err(|err| {
+                AnalyzerError::GlobalError(format!("could not parse '{}' parameter: {}", key, err))
+            })
+        })
 }
@@ -0,0 +1,213 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S107";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Functions should not have too many parameters",
+    markdown: r#"
+This rule raises an issue when a function or a closure has more parameters than the maximum. The `self` parameter of
+methods does not count, nor do the parameters of the methods implementing a trait, whose signature is declared by the
+trait.
+
+## Why is this an issue?
+
+Functions with a long parameter list are difficult to use because maintainers must figure out the role of each
+parameter and keep track of their position.
+
+```noncompliant
+fn set_coordinates(x1: i32, y1: i32, z1: i32, x2: i32, y2: i32, z2: i32) {
+    // ...
+}
+```
+
+### How to fix it
+
+Split the function into smaller ones, or group the parameters in a struct which makes sense for the domain of the
+application.
+
+```compliant
+struct Point {
+    x: i32,
+    y: i32,
+    z: i32,
+}
+
+fn set_coordinates(p1: &Point, p2: &Point) {
+    // ...
+}
+```
+
+## Resources
+
+### Documentation
+
+- Clippy Lints - [too_many_arguments](https://rust-lang.github.io/rust-clippy/master/index.html#too_many_arguments)
+"#,
+};
+
+pub struct TooManyParametersCheck {
+    max: usize,
+}
+
+impl TooManyParametersCheck {
+    pub fn new(max: usize) -> Self {
+        TooManyParametersCheck { max }
+    }
+}
+
+impl Rule for TooManyParametersCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for function in NodeIterator::new(tree.root_node(), |node| {
+            matches!(
+                node.kind(),
+                "function_item" | "function_signature_item" | "closure_expression"
+            )
+        }) {
+            let Some(parameters) = function.child_by_field_name("parameters") else {
+                continue;
+            };
+            if implements_trait_method(function) {
+                continue;
+            }
+
+            let count = parameter_count(parameters);
+            let location = TreeSitterLocation::from_tree_sitter_node(parameters)
+                .to_sonar_location(source_code);
+            if tracer.is_enabled() {
+                let comparison = if count > self.max {
+                    "above"
+                } else {
+                    "not above"
+                };
+                tracer.trace(
+                    &location,
+                    &location,
+                    format!(
+                        "{} parameters without self, {} the maximum of {}",
+                        count, comparison, self.max
+                    ),
+                );
+            }
+            if count > self.max {
+                let kind = if function.kind() == "closure_expression" {
+                    "closure"
+                } else {
+                    "function"
+                };
+                issues.push(Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: format!(
+                        "This {} has {} parameters, which is greater than the {} authorized.",
+                        kind, count, self.max
+                    ),
+                    location,
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![],
+                    fingerprint: None,
+                    hotspot: None,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// The methods of a trait implementation must have the parameters declared by the trait.
+fn implements_trait_method(function: Node<'_>) -> bool {
+    function
+        .parent()
+        .filter(|parent| parent.kind() == "declaration_list")
+        .and_then(|declaration_list| declaration_list.parent())
+        .is_some_and(|item| {
+            item.kind() == "impl_item" && item.child_by_field_name("trait").is_some()
+        })
+}
+
+/// Parameters of a function or closure, without `self` and the attributes of the parameters.
+fn parameter_count(parameters: Node<'_>) -> usize {
+    let mut cursor = parameters.walk();
+    let count = parameters
+        .named_children(&mut cursor)
+        .filter(|parameter| {
+            !parameter.is_extra()
+                && !matches!(parameter.kind(), "self_parameter" | "attribute_item")
+        })
+        .count();
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_too_many_parameters() {
+        assert_issues(
+            &TooManyParametersCheck::new(2),
+            r#"
+fn few(a: i32, b: i32) {}
+fn many(a: i32, b: i32, #[cfg(unix)] c: i32) {} // Noncompliant {{This function has 3 parameters, which is greater than the 2 authorized.}}
+//     ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+
+struct Point;
+
+impl Point {
+    fn method(&self, a: i32, b: i32) {}
+    fn function(a: i32, b: i32, c: i32) -> Point { Point } // Noncompliant
+}
+
+trait Shape {
+    fn declared(&self, a: i32, b: i32, c: i32); // Noncompliant
+}
+
+impl Shape for Point {
+    fn declared(&self, a: i32, b: i32, c: i32) {}
+}
+
+fn main() {
+    let add = |a, b: i32, (c, d)| a + b + c + d; // Noncompliant {{This closure has 3 parameters, which is greater than the 2 authorized.}}
+}
+"#,
+        );
+    }
+}
@@ -0,0 +1,292 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::{
+        rule::{Rule, RuleTracer},
+        unused_variable_check::{format_captures, prefix_quick_fix},
+    },
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::data_flow::{calculate_data_flow, pattern_identifiers, VariableKind},
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S1172";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Unused function parameters should be removed",
+    markdown: r#"
+This rule raises an issue on the parameters of private functions which are never read in the body. The parameters whose
+name starts with an underscore, the `_` patterns, `self` and the parameters with attributes, e.g.
+`#[allow(unused)]`, are ignored, as well as the methods of trait implementations and the default methods of traits,
+whose signature is given by the trait.
+
+## Why is this an issue?
+
+An unused parameter makes the callers compute and pass a value for nothing, and the reader look for a use which does
+not exist. It is often the sign of a bug, e.g. a parameter shadowed by a local variable.
+
+```noncompliant
+fn area(width: u32, height: u32, unit: &str) -> u32 {
+    width * height
+}
+```
+
+### How to fix it
+
+Remove the parameter, or prefix its name with an underscore if the function must keep its signature, e.g. when it is
+passed as a callback.
+
+```compliant
+fn area(width: u32, height: u32) -> u32 {
+    width * height
+}
+```
+
+## Resources
+
+### Documentation
+
+- The Rust Reference - [Function parameters](https://doc.rust-lang.org/reference/items/functions.html#function-parameters)
+"#,
+};
+
+pub struct UnusedParameterCheck;
+
+impl Rule for UnusedParameterCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for function in NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item") {
+            if !is_private(function) || has_fixed_signature(function) {
+                continue;
+            }
+
+            let data_flow = calculate_data_flow(function, source_code);
+            let captured = format_captures(function, source_code);
+            let names = parameter_names(function, source_code);
+            for variable in data_flow.unused_variables() {
+                if variable.kind != VariableKind::Parameter
+                    || variable.name.starts_with('_')
+                    || variable.name == "self"
+                {
+                    continue;
+                }
+                if captured.contains(variable.name.as_str()) {
+                    if tracer.is_enabled() {
+                        tracer.trace(
+                            &variable.location,
+                            &variable.location,
+                            "name captured by a format string".to_string(),
+                        );
+                    }
+                    continue;
+                }
+
+                let name = names.iter().copied().find(|name| {
+                    TreeSitterLocation::from_tree_sitter_node(*name).to_sonar_location(source_code)
+                        == variable.location
+                });
+                if name.is_some_and(is_attributed) {
+                    if tracer.is_enabled() {
+                        tracer.trace(
+                            &variable.location,
+                            &variable.location,
+                            "parameter with attributes, e.g. `#[allow(unused)]`".to_string(),
+                        );
+                    }
+                    continue;
+                }
+                let shorthand = name.filter(|name| name.kind() == "shorthand_field_identifier");
+                issues.push(Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: format!(
+                        "Remove the unused function parameter \"{}\", or rename it to \"_{}\".",
+                        variable.name, variable.name
+                    ),
+                    location: variable.location.clone(),
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![prefix_quick_fix(
+                        &variable.name,
+                        &variable.location,
+                        shorthand,
+                        source_code,
+                    )],
+                    fingerprint: None,
+                    hotspot: None,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Whether the function has no visibility modifier, e.g. `pub` or `pub(crate)`.
+fn is_private(function: Node<'_>) -> bool {
+    let mut cursor = function.walk();
+    let is_private = !function
+        .children(&mut cursor)
+        .any(|child| child.kind() == "visibility_modifier");
+    is_private
+}
+
+/// Whether the function is a method of a trait implementation or a default method of a trait.
+fn has_fixed_signature(function: Node<'_>) -> bool {
+    function
+        .parent()
+        .filter(|parent| parent.kind() == "declaration_list")
+        .and_then(|list| list.parent())
+        .is_some_and(|item| match item.kind() {
+            "trait_item" => true,
+            "impl_item" => item.child_by_field_name("trait").is_some(),
+            _ => false,
+        })
+}
+
+/// Names bound by the parameters of a function.
+fn parameter_names<'a>(function: Node<'a>, source_code: &str) -> Vec<Node<'a>> {
+    let Some(parameters) = function.child_by_field_name("parameters") else {
+        return vec![];
+    };
+    let mut cursor = parameters.walk();
+    let patterns: Vec<Node<'a>> = parameters
+        .named_children(&mut cursor)
+        .filter(|parameter| parameter.kind() == "parameter")
+        .filter_map(|parameter| parameter.child_by_field_name("pattern"))
+        .collect();
+    patterns
+        .into_iter()
+        .flat_map(|pattern| pattern_identifiers(pattern, source_code))
+        .collect()
+}
+
+/// Whether the parameter declaring the name has attributes, e.g. `#[allow(unused)]`, which precede it in the list of
+/// parameters.
+fn is_attributed(name: Node<'_>) -> bool {
+    let mut current = name;
+    while let Some(parent) = current.parent() {
+        if parent.kind() == "parameter" {
+            return parent
+                .prev_named_sibling()
+                .is_some_and(|sibling| sibling.kind() == "attribute_item");
+        }
+        current = parent;
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        issue::{QuickFix, TextEdit},
+        tree::{parse_rust_code, SonarLocation},
+        verifier::assert_issues,
+    };
+
+    #[test]
+    fn test_unused_parameter() {
+        assert_issues(
+            &UnusedParameterCheck,
+            r#"
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn area(width: u32, height: u32, unit: &str, _scale: u32, _: u32) -> u32 { // Noncompliant {{Remove the unused function parameter "unit", or rename it to "_unit".}}
+//                               ^^^^
+    width * height
+}
+
+fn describe(name: &str, Point { x, y }: Point) { // Noncompliant {{Remove the unused function parameter "y", or rename it to "_y".}}
+    println!("{name} {}", x);
+}
+
+pub fn exported(unused: u32) {}
+
+fn allowed(#[allow(unused)] level: u32) {}
+
+trait Shape {
+    fn scale(&self, factor: f64) {}
+}
+
+impl Shape for Point {
+    fn scale(&self, factor: f64) {}
+}
+
+impl Point {
+    fn moved(self, dx: i32) -> Self { // Noncompliant {{Remove the unused function parameter "dx", or rename it to "_dx".}}
+        self
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_quick_fixes() {
+        let source_code = r#"
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn area(width: u32, height: u32, unit: &str) -> u32 {
+    width * height
+}
+
+fn describe(name: &str, Point { x, y }: Point) {
+    println!("{name} {}", x);
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let issues = UnusedParameterCheck.check(&tree, source_code).unwrap();
+        assert_eq!(
+            issues[0].quick_fixes[0],
+            QuickFix {
+                message: "Rename \"unit\" to \"_unit\"".to_string(),
+                edits: vec![TextEdit {
+                    location: SonarLocation {
+                        start_line: 7,
+                        start_column: 33,
+                        end_line: 7,
+                        end_column: 33
+                    },
+                    text: "_".to_string(),
+                }],
+            }
+        );
+        assert_eq!(issues[1].quick_fixes[0].edits[0].text, "y: _y");
+    }
+}
@@ -0,0 +1,348 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::{Issue, QuickFix, TextEdit},
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, SonarLocation, TreeSitterLocation},
+    visitors::data_flow::{calculate_data_flow, pattern_identifiers, VariableKind},
+};
+use std::collections::HashSet;
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S1481";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "Unused local variables should be removed",
+    markdown: r#"
+This rule raises an issue on the variables declared by a `let` statement which are never read. The variables whose name
+starts with an underscore, and the `_` patterns, are deliberately unused and ignored.
+
+## Why is this an issue?
+
+An unused variable is dead code: it makes the reader look for a use which does not exist. It is often the sign of a
+bug, e.g. a value computed and then forgotten, or a variable shadowed by mistake.
+
+```noncompliant
+fn total(prices: &[u32]) -> u32 {
+    let count = prices.len();
+    prices.iter().sum()
+}
+```
+
+### How to fix it
+
+Remove the variable, or prefix its name with an underscore if the value, or its destructor, is needed.
+
+```compliant
+fn total(prices: &[u32]) -> u32 {
+    prices.iter().sum()
+}
+```
+
+## Resources
+
+### Documentation
+
+- The Rust Reference - [Identifier patterns](https://doc.rust-lang.org/reference/patterns.html#identifier-patterns)
+"#,
+};
+
+pub struct UnusedVariableCheck;
+
+impl Rule for UnusedVariableCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let mut issues = vec![];
+        for function in NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item") {
+            let data_flow = calculate_data_flow(function, source_code);
+            let names = let_names(function, source_code);
+            let captured = format_captures(function, source_code);
+            for variable in data_flow.unused_variables() {
+                if variable.kind != VariableKind::Local || variable.name.starts_with('_') {
+                    continue;
+                }
+                if captured.contains(variable.name.as_str()) {
+                    if tracer.is_enabled() {
+                        tracer.trace(
+                            &variable.location,
+                            &variable.location,
+                            "name captured by a format string".to_string(),
+                        );
+                    }
+                    continue;
+                }
+
+                let name = names
+                    .iter()
+                    .find(|(_, location)| *location == variable.location)
+                    .map(|(name, _)| *name);
+                if name.is_some_and(is_attributed) {
+                    if tracer.is_enabled() {
+                        tracer.trace(
+                            &variable.location,
+                            &variable.location,
+                            "declaration with attributes, e.g. a cfg variant".to_string(),
+                        );
+                    }
+                    continue;
+                }
+                let shorthand = name.filter(|name| name.kind() == "shorthand_field_identifier");
+                issues.push(Issue {
+                    rule_key: RULE_KEY.to_string(),
+                    message: format!("Remove this unused \"{}\" local variable.", variable.name),
+                    location: variable.location.clone(),
+                    secondary_locations: vec![],
+                    severity: None,
+                    quick_fixes: vec![prefix_quick_fix(
+                        &variable.name,
+                        &variable.location,
+                        shorthand,
+                        source_code,
+                    )],
+                    fingerprint: None,
+                    hotspot: None,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Names declared by the `let` statements of a function, excluding its nested functions.
+fn let_names<'a>(function: Node<'a>, source_code: &str) -> Vec<(Node<'a>, SonarLocation)> {
+    NodeIterator::new(function, |node| node.kind() == "let_declaration")
+        .filter_map(|declaration| declaration.child_by_field_name("pattern"))
+        .flat_map(|pattern| pattern_identifiers(pattern, source_code))
+        .map(|name| {
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(name).to_sonar_location(source_code);
+            (name, location)
+        })
+        .collect()
+}
+
+/// Whether the `let` statement declaring the name has attributes, e.g. one of the `#[cfg]` variants of a variable.
+fn is_attributed(name: Node<'_>) -> bool {
+    let mut current = name;
+    while let Some(parent) = current.parent() {
+        if parent.kind() == "let_declaration" {
+            return parent
+                .prev_named_sibling()
+                .is_some_and(|sibling| sibling.kind() == "attribute_item");
+        }
+        current = parent;
+    }
+    false
+}
+
+/// Names read by the format strings of the macros of a function, e.g. `name` in `println!("{name}")`, which are not
+/// known to the data flow.
+pub(crate) fn format_captures<'a>(function: Node<'_>, source_code: &'a str) -> HashSet<&'a str> {
+    NodeIterator::new(function, |node| node.kind() == "string_literal")
+        .filter(|literal| {
+            literal
+                .parent()
+                .is_some_and(|parent| parent.kind() == "token_tree")
+        })
+        .flat_map(|literal| {
+            let text = &source_code[literal.byte_range()];
+            text.split('{')
+                .skip(1)
+                .filter_map(|placeholder| {
+                    let end = placeholder
+                        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+                        .unwrap_or(placeholder.len());
+                    let name = &placeholder[..end];
+                    (!name.is_empty() && !name.starts_with(|c: char| c.is_ascii_digit()))
+                        .then_some(name)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Inserts an underscore before the name, a shorthand field pattern like `Point { x, .. }` being expanded to
+/// `Point { x: _x, .. }`. The binding modifiers of a shorthand move to the expanded pattern, e.g. `Point { ref mut x }`
+/// becomes `Point { x: ref mut _x }`.
+pub(crate) fn prefix_quick_fix(
+    name: &str,
+    location: &SonarLocation,
+    shorthand: Option<Node<'_>>,
+    source_code: &str,
+) -> QuickFix {
+    let edit = if let Some(identifier) = shorthand {
+        let pattern = identifier.parent().unwrap_or(identifier);
+        let modifiers: String = source_code[pattern.start_byte()..identifier.start_byte()]
+            .split_whitespace()
+            .map(|modifier| format!("{} ", modifier))
+            .collect();
+        TextEdit {
+            location: TreeSitterLocation::from_tree_sitter_node(pattern)
+                .to_sonar_location(source_code),
+            text: format!("{}: {}_{}", name, modifiers, name),
+        }
+    } else {
+        TextEdit {
+            location: SonarLocation {
+                start_line: location.start_line,
+                start_column: location.start_column,
+                end_line: location.start_line,
+                end_column: location.start_column,
+            },
+            text: "_".to_string(),
+        }
+    };
+    QuickFix {
+        message: format!("Rename \"{}\" to \"_{}\"", name, name),
+        edits: vec![edit],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{tree::parse_rust_code, verifier::assert_issues};
+
+    #[test]
+    fn test_unused_variable() {
+        assert_issues(
+            &UnusedVariableCheck,
+            r#"
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+fn total(prices: &[u32], point: Point) -> u32 {
+    let count = prices.len();
+    let _ignored = count;
+    let _ = prices.first();
+    let mut sum = 0;
+    sum = prices.iter().sum();
+    let Point { x, y: ordinate } = point; // Noncompliant {{Remove this unused "ordinate" local variable.}}
+//                    ^^^^^^^^
+    let label = "total";
+    let unit = "EUR";
+    #[cfg(windows)]
+    let separator = "\\";
+    #[cfg(not(windows))]
+    let separator = "/";
+    println!("{label}: {} {unit:>4}{separator}", sum);
+    (x, 0).0 as u32
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_quick_fix() {
+        let source_code = r#"
+fn main() {
+    let mut count = 0;
+    let Some((first, _)) = Some((1, 2)) else { return };
+    let S { x, .. } = s();
+    let P { mut y, ref mut z } = p();
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let issues = UnusedVariableCheck.check(&tree, source_code).unwrap();
+        assert_eq!(issues.len(), 5);
+        assert_eq!(
+            issues[0].message,
+            "Remove this unused \"count\" local variable."
+        );
+        assert_eq!(
+            issues[0].location,
+            SonarLocation {
+                start_line: 3,
+                start_column: 12,
+                end_line: 3,
+                end_column: 17
+            }
+        );
+        assert_eq!(
+            issues[0].quick_fixes,
+            vec![QuickFix {
+                message: "Rename \"count\" to \"_count\"".to_string(),
+                edits: vec![TextEdit {
+                    location: SonarLocation {
+                        start_line: 3,
+                        start_column: 12,
+                        end_line: 3,
+                        end_column: 12
+                    },
+                    text: "_".to_string(),
+                }],
+            }]
+        );
+        assert_eq!(
+            issues[1].message,
+            "Remove this unused \"first\" local variable."
+        );
+        assert_eq!(
+            issues[2].quick_fixes[0].edits,
+            vec![TextEdit {
+                location: SonarLocation {
+                    start_line: 5,
+                    start_column: 12,
+                    end_line: 5,
+                    end_column: 13
+                },
+                text: "x: _x".to_string(),
+            }]
+        );
+        assert_eq!(
+            issues[3].quick_fixes[0].edits,
+            vec![TextEdit {
+                location: SonarLocation {
+                    start_line: 6,
+                    start_column: 12,
+                    end_line: 6,
+                    end_column: 17
+                },
+                text: "y: mut _y".to_string(),
+            }]
+        );
+        assert_eq!(
+            issues[4].quick_fixes[0].edits,
+            vec![TextEdit {
+                location: SonarLocation {
+                    start_line: 6,
+                    start_column: 19,
+                    end_line: 6,
+                    end_column: 28
+                },
+                text: "z: ref mut _z".to_string(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,235 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rule_description::RuleDescription,
+    rules::rule::{Rule, RuleTracer},
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    visitors::test_code::TestCode,
+};
+use tree_sitter::{Node, Tree};
+
+const RULE_KEY: &str = "S7467";
+
+const DESCRIPTION: RuleDescription = RuleDescription {
+    key: RULE_KEY,
+    title: "\"unwrap\" and \"expect\" should not be called outside of tests",
+    markdown: r#"
+This rule raises an issue on the calls of the `unwrap` and `expect` methods, e.g. of `Option` and `Result`, outside of
+the test code: the test files and the items annotated with `#[test]` or `#[cfg(test)]`.
+
+## Why is this an issue?
+
+`unwrap` and `expect` panic when the value is `None` or an `Err`. In production code, a panic aborts the thread, or the
+whole program, for a condition which is often an expected failure: a missing file, an invalid input or a network
+error. The caller gets no chance to recover from it or to report it properly.
+
+```noncompliant
+fn read_config(path: &Path) -> Config {
+    let text = fs::read_to_string(path).unwrap();
+    toml::from_str(&text).expect("invalid configuration")
+}
+```
+
+### How to fix it
+
+Propagate the error to the caller with the `?` operator, or handle the missing value explicitly, e.g. with a default.
+
+```compliant
+fn read_config(path: &Path) -> Result<Config, ConfigError> {
+    let text = fs::read_to_string(path)?;
+    Ok(toml::from_str(&text)?)
+}
+```
+
+The `allowInMain` parameter allows the calls in the `main` functions of binaries, where a panic is an acceptable way to
+report an error and exit.
+
+## Resources
+
+### Documentation
+
+- The Rust Programming Language - [To panic! or Not to
+  panic!](https://doc.rust-lang.org/book/ch09-03-to-panic-or-not-to-panic.html)
+- Clippy Lints - [unwrap_used](https://rust-lang.github.io/rust-clippy/master/index.html#unwrap_used)
+"#,
+};
+
+pub struct UnwrapCheck {
+    allow_in_main: bool,
+    /// The file only contains test code, see `TestCode`.
+    test_file: bool,
+}
+
+impl UnwrapCheck {
+    pub fn new(allow_in_main: bool, test_file: bool) -> Self {
+        UnwrapCheck {
+            allow_in_main,
+            test_file,
+        }
+    }
+}
+
+impl Rule for UnwrapCheck {
+    fn key(&self) -> &'static str {
+        RULE_KEY
+    }
+
+    fn description(&self) -> RuleDescription {
+        DESCRIPTION
+    }
+
+    fn check_traced(
+        &self,
+        tree: &Tree,
+        source_code: &str,
+        tracer: &mut dyn RuleTracer,
+    ) -> Result<Vec<Issue>, AnalyzerError> {
+        let test_code = TestCode::find(tree, source_code, self.test_file);
+        let mut issues = vec![];
+        for call in NodeIterator::new(tree.root_node(), |node| node.kind() == "call_expression") {
+            let Some(method) = panicking_method(call, source_code) else {
+                continue;
+            };
+            let location =
+                TreeSitterLocation::from_tree_sitter_node(method).to_sonar_location(source_code);
+            if test_code.contains_node(call) {
+                if tracer.is_enabled() {
+                    tracer.trace(&location, &location, "call in test code".to_string());
+                }
+                continue;
+            }
+            if self.allow_in_main && in_main_function(call, source_code) {
+                if tracer.is_enabled() {
+                    tracer.trace(
+                        &location,
+                        &location,
+                        "call in the main function, allowed by 'allowInMain'".to_string(),
+                    );
+                }
+                continue;
+            }
+
+            let name = &source_code[method.byte_range()];
+            issues.push(Issue {
+                rule_key: RULE_KEY.to_string(),
+                message: format!("Handle the error instead of panicking with \"{}\".", name),
+                location,
+                secondary_locations: vec![],
+                severity: None,
+                quick_fixes: vec![],
+                fingerprint: None,
+                hotspot: None,
+            });
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Name of the called method if the call is `x.unwrap()` or `x.expect(message)`.
+fn panicking_method<'a>(call: Node<'a>, source_code: &str) -> Option<Node<'a>> {
+    let method = call
+        .child_by_field_name("function")
+        .filter(|function| function.kind() == "field_expression")?
+        .child_by_field_name("field")?;
+    let arguments = call.child_by_field_name("arguments")?.named_child_count();
+    match (&source_code[method.byte_range()], arguments) {
+        ("unwrap", 0) | ("expect", 1) => Some(method),
+        _ => None,
+    }
+}
+
+/// Whether the node is in the `main` function declared at the root of the file, the entry point of binaries.
+fn in_main_function(node: Node<'_>, source_code: &str) -> bool {
+    let mut ancestor = node.parent();
+    while let Some(current) = ancestor {
+        if current.kind() == "function_item" {
+            return current
+                .parent()
+                .is_some_and(|parent| parent.kind() == "source_file")
+                && current
+                    .child_by_field_name("name")
+                    .is_some_and(|name| &source_code[name.byte_range()] == "main");
+        }
+        ancestor = current.parent();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::verifier::assert_issues;
+
+    #[test]
+    fn test_unwrap() {
+        assert_issues(
+            &UnwrapCheck::new(false, false),
+            r#"
+fn parse(text: &str) -> i32 {
+    let value = text.parse::<i32>().unwrap(); // Noncompliant {{Handle the error instead of panicking with "unwrap".}}
+//                                  ^^^^^^
+    let other = text.get(1..).expect("too short"); // Noncompliant {{Handle the error instead of panicking with "expect".}}
+    value + other.len().unwrap_or(0) as i32
+}
+
+fn main() {
+    let x = parse("42").to_string().parse::<i32>().unwrap(); // Noncompliant
+    let f = || Some(1).unwrap(); // Noncompliant
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_parse() {
+        assert_eq!(super::parse("42").checked_add(1).unwrap(), 43);
+    }
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_allow_in_main() {
+        assert_issues(
+            &UnwrapCheck::new(true, false),
+            r#"
+fn parse(text: &str) -> i32 {
+    text.parse::<i32>().unwrap() // Noncompliant
+}
+
+fn main() {
+    let x = parse("42").to_string().parse::<i32>().unwrap();
+    let f = || Some(1).unwrap();
+}
+"#,
+        );
+    }
+
+    #[test]
+    fn test_test_file() {
+        assert_issues(
+            &UnwrapCheck::new(false, true),
+            r#"
+fn parse(text: &str) -> i32 {
+    text.parse::<i32>().unwrap()
+}
+"#,
+        );
+    }
+}
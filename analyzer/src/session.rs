@@ -0,0 +1,171 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    analyze::{analyze_borrowed_incrementally, BorrowedOutput, Output},
+    options::file_parameters,
+    tree::{set_cancellation, AnalyzerError, CancellationToken},
+};
+use std::collections::HashMap;
+use tree_sitter::Tree;
+
+/// Analysis of the files open in an editor (e.g. by SonarLint), whose content may not be saved yet.
+///
+/// Each file is identified by its logical path, relative to the project directory as for `file_parameters`. The
+/// session keeps the last analyzed content of each file: analyzing the same content again returns the previous results,
+/// and a new content is parsed incrementally from the tree of the previous one.
+pub struct AnalysisSession {
+    parameters: HashMap<String, String>,
+    files: HashMap<String, OpenFile>,
+}
+
+struct OpenFile {
+    source_code: String,
+    /// Tree of the source code, `None` when the file was not parsed (e.g. excluded or too large).
+    tree: Option<Tree>,
+    output: Output,
+}
+
+impl AnalysisSession {
+    /// Starts a session with the parameters of the project, as for `analyze`.
+    pub fn new(parameters: HashMap<String, String>) -> Self {
+        AnalysisSession {
+            parameters,
+            files: HashMap::new(),
+        }
+    }
+
+    /// Analyzes the content of the editor buffer of the file at the given path.
+    pub fn analyze(&mut self, path: &str, content: &str) -> Result<&Output, AnalyzerError> {
+        // The BOM is not part of the analyzed code, the incremental parse compares the code only
+        let source_code = content.strip_prefix('\u{feff}').unwrap_or(content);
+        let previous = self.files.remove(path);
+        let open_file = match previous {
+            Some(open_file) if open_file.source_code == source_code => open_file,
+            previous => {
+                let parameters = file_parameters(&self.parameters, path);
+                let previous_tree = previous.as_ref().and_then(|open_file| {
+                    open_file
+                        .tree
+                        .as_ref()
+                        .map(|tree| (tree, open_file.source_code.as_str()))
+                });
+                let (output, tree) =
+                    match analyze_borrowed_incrementally(source_code, &parameters, previous_tree) {
+                        Ok(result) => result,
+                        Err(err) => {
+                            // The next analysis, e.g. after a cancelled one, still starts from the previous content
+                            if let Some(previous) = previous {
+                                self.files.insert(path.to_string(), previous);
+                            }
+                            return Err(err);
+                        }
+                    };
+                OpenFile {
+                    source_code: source_code.to_string(),
+                    tree,
+                    output: BorrowedOutput::into_owned(output),
+                }
+            }
+        };
+        Ok(&self
+            .files
+            .entry(path.to_string())
+            .or_insert(open_file)
+            .output)
+    }
+
+    /// Same as `analyze`, failing with `AnalyzerError::Cancelled` once the token is cancelled, e.g. when the user
+    /// types again before the end of the analysis.
+    pub fn analyze_cancellable(
+        &mut self,
+        path: &str,
+        content: &str,
+        cancellation: &CancellationToken,
+    ) -> Result<&Output, AnalyzerError> {
+        let _cancellation = set_cancellation(Some(cancellation.clone()));
+        self.analyze(path, content)
+    }
+
+    /// Forgets the content of the file, e.g. when its editor is closed.
+    pub fn close(&mut self, path: &str) {
+        self.files.remove(path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analyze::analyze, rules::rule::default_parameters};
+
+    #[test]
+    fn test_analyze_buffers() {
+        let mut session = AnalysisSession::new(default_parameters());
+        let saved = "fn main() {\n    let x = 1;\n}\n";
+        let typing = "fn main() {\n    let x = 1;\n    if x > 0 { if x > 1 { if x > 2 {\n}\n";
+        let edited = "fn main() {\n    let x = 1;\n    if x > 0 {}\n}\n\nfn other() {}\n";
+
+        for content in [saved, typing, edited, saved, "\u{feff}fn é() {}\n", edited] {
+            let output = session.analyze("src/main.rs", content).unwrap();
+            let expected = analyze(content, &default_parameters()).unwrap();
+            assert_eq!(output.metrics, expected.metrics);
+            assert_eq!(output.highlight_tokens, expected.highlight_tokens);
+            assert_eq!(output.symbols, expected.symbols);
+            assert_eq!(output.issues, expected.issues);
+        }
+
+        let syntax_errors = |session: &mut AnalysisSession, content| {
+            session
+                .analyze("src/main.rs", content)
+                .unwrap()
+                .warnings
+                .len()
+        };
+        assert_eq!(syntax_errors(&mut session, typing), 1);
+        assert_eq!(syntax_errors(&mut session, edited), 0);
+
+        // The path gives the settings of the file
+        let test_output = session.analyze("tests/it.rs", edited).unwrap();
+        assert!(test_output.test_file);
+        session.close("tests/it.rs");
+        assert!(!session.files.contains_key("tests/it.rs"));
+    }
+
+    #[test]
+    fn test_cancelled_analysis() {
+        let mut session = AnalysisSession::new(default_parameters());
+        let saved = "fn main() {}
+";
+        let edited = "fn main() {
+    let x = 1;
+}
+";
+        session.analyze("src/main.rs", saved).unwrap();
+
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        assert!(matches!(
+            session.analyze_cancellable("src/main.rs", edited, &cancellation),
+            Err(AnalyzerError::Cancelled)
+        ));
+        assert_eq!(session.files["src/main.rs"].source_code, saved);
+
+        let output = session
+            .analyze_cancellable("src/main.rs", edited, &CancellationToken::new())
+            .unwrap();
+        assert_eq!(output.metrics.ncloc, 3);
+    }
+}
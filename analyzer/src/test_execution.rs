@@ -0,0 +1,349 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    options::list_parameter,
+    project_index::file_module,
+    tree::{parse_rust_code, AnalyzerError, SonarLocation},
+    visitors::test_code::find_test_functions,
+};
+use serde_json::Value;
+use std::{collections::HashMap, fs, path::Path, time::Duration};
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    Skipped,
+}
+
+/// Result of a test in a test report.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TestResult {
+    pub status: TestStatus,
+    /// Duration of the test, when reported with `--report-time`.
+    pub duration: Option<Duration>,
+    /// Output of a failed test, with its panic message.
+    pub message: Option<String>,
+}
+
+/// A `#[test]` function of a file, with its result in the test reports.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TestCase {
+    /// Path of the function in its crate, as named by the test harness (e.g. 'parser::tests::test_parse').
+    pub name: String,
+    /// Location of the name of the function.
+    pub location: SonarLocation,
+    pub result: TestResult,
+}
+
+/// Results of the tests of the JSON output of the test harness, from `cargo test -- -Z unstable-options --format json`
+/// or `cargo nextest run --message-format libtest-json`, by name.
+#[derive(Debug, Default, PartialEq, Eq, Clone)]
+pub struct TestReport {
+    /// Results by name, with the identifier of the test binary when reported by nextest (e.g. 'demo::integration').
+    results: HashMap<String, Vec<(Option<String>, TestResult)>>,
+}
+
+impl TestReport {
+    /// Parses the events of the tests, one JSON object per line. The other events (e.g. of the suites) and the lines
+    /// which are not JSON (e.g. the output of Cargo) are ignored.
+    pub fn parse(text: &str) -> Self {
+        let mut report = TestReport::default();
+        for line in text.lines() {
+            let Ok(event) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            if event["type"] != "test" {
+                continue;
+            }
+            let status = match event["event"].as_str() {
+                Some("ok") => TestStatus::Passed,
+                Some("failed") => TestStatus::Failed,
+                Some("ignored") => TestStatus::Skipped,
+                _ => continue,
+            };
+            let Some(name) = event["name"].as_str() else {
+                continue;
+            };
+            // Nextest prefixes the names with the identifier of their binary, e.g. 'demo::integration$test_parse'
+            let (binary, name) = match name.split_once('$') {
+                Some((binary, name)) => (Some(binary.to_string()), name),
+                None => (None, name),
+            };
+            let result = TestResult {
+                status,
+                duration: event["exec_time"].as_f64().map(Duration::from_secs_f64),
+                message: event["stdout"]
+                    .as_str()
+                    .filter(|_| status == TestStatus::Failed)
+                    .map(str::to_string),
+            };
+            report
+                .results
+                .entry(name.to_string())
+                .or_default()
+                .push((binary, result));
+        }
+        report
+    }
+
+    /// Reads the reports of the 'test_report_paths' setting, relative to the project directory, if any. The test cases
+    /// of the files are added to the results of `analyze_batch`.
+    pub fn load(parameters: &HashMap<String, String>) -> Result<Option<Self>, AnalyzerError> {
+        let Some(paths) = parameters.get("test_report_paths") else {
+            return Ok(None);
+        };
+        let project_dir = Path::new(
+            parameters
+                .get("project_dir")
+                .map_or(".", |dir| dir.as_str()),
+        );
+        let mut report = TestReport::default();
+        for path in list_parameter(paths) {
+            let path = project_dir.join(path);
+            let text = fs::read_to_string(&path).map_err(|err| {
+                AnalyzerError::GlobalError(format!("failed to read {}: {}", path.display(), err))
+            })?;
+            for (name, results) in Self::parse(&text).results {
+                report.results.entry(name).or_default().extend(results);
+            }
+        }
+        Ok(Some(report))
+    }
+
+    /// Test cases of the `#[test]` functions of the file at the given path, relative to the project directory, which
+    /// have a result in the report.
+    ///
+    /// The functions of the 'src' directory are the unit tests of their crate, those of the files directly under the
+    /// 'tests' directory are integration tests named from the root of their binary. When a function has several
+    /// results, e.g. because several binaries have a test of the same name, it failed if one of them failed.
+    pub fn test_cases(
+        &self,
+        path: &str,
+        source_code: &str,
+    ) -> Result<Vec<TestCase>, AnalyzerError> {
+        let Some((module, binary)) = test_module(path) else {
+            return Ok(vec![]);
+        };
+        let tree = parse_rust_code(source_code)?;
+        let test_cases = find_test_functions(&tree, source_code)
+            .into_iter()
+            .filter_map(|test| {
+                let mut path = module.clone();
+                path.push(test.name);
+                let name = path.join("::");
+                let result = self.result(&name, binary.as_deref())?;
+                Some(TestCase {
+                    name,
+                    location: test.location,
+                    result,
+                })
+            })
+            .collect();
+        Ok(test_cases)
+    }
+
+    /// Combined result of the tests of the given name, in the binary of the integration test of the given name if any.
+    fn result(&self, name: &str, integration_test: Option<&str>) -> Option<TestResult> {
+        let results: Vec<&TestResult> = self
+            .results
+            .get(name)?
+            .iter()
+            .filter(|(binary, _)| {
+                // The binaries of nextest are named '<crate>' for the library, '<crate>::bin/<name>' for the binaries
+                // and '<crate>::<name>' for the integration tests
+                let target = binary
+                    .as_deref()
+                    .map(|binary| binary.split_once("::").map(|(_, target)| target));
+                match (integration_test, target) {
+                    (_, None) => true,
+                    (Some(test), Some(target)) => target == Some(test),
+                    (None, Some(target)) => target.is_none_or(|target| target.starts_with("bin/")),
+                }
+            })
+            .map(|(_, result)| result)
+            .collect();
+        let status = [TestStatus::Failed, TestStatus::Passed, TestStatus::Skipped]
+            .into_iter()
+            .find(|status| results.iter().any(|result| result.status == *status))?;
+        Some(TestResult {
+            status,
+            duration: results
+                .iter()
+                .filter_map(|result| result.duration)
+                .reduce(|total, duration| total + duration),
+            message: results.iter().find_map(|result| result.message.clone()),
+        })
+    }
+}
+
+/// Module path of the tests of a file in the test harness, and name of its binary for the integration tests.
+fn test_module(path: &str) -> Option<(Vec<String>, Option<String>)> {
+    if let Some(module) = file_module(path) {
+        return Some((module[1..].to_vec(), None));
+    }
+    let components: Vec<&str> = path.split(['/', '\\']).collect();
+    match components.as_slice() {
+        [.., "tests", file] => Some((vec![], Some(file.strip_suffix(".rs")?.to_string()))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{analyze::analyze_batch, rules::rule::default_parameters};
+
+    const REPORT: &str = r#"   Compiling demo v0.1.0
+{ "type": "suite", "event": "started", "test_count": 4 }
+{ "type": "test", "event": "started", "name": "parser::tests::test_parse" }
+{ "type": "test", "name": "parser::tests::test_parse", "event": "ok", "exec_time": 0.25 }
+{ "type": "test", "name": "parser::tests::test_fail", "event": "failed", "exec_time": 0.5, "stdout": "thread 'parser::tests::test_fail' panicked at src/parser.rs:9:9:\nboom\n" }
+{ "type": "test", "name": "parser::tests::test_ignored", "event": "ignored" }
+{ "type": "test", "name": "parser::tests::test_slow", "event": "timeout" }
+{ "type": "suite", "event": "failed", "passed": 1, "failed": 1, "ignored": 1 }
+{ "type": "test", "name": "demo::smoke$test_smoke", "event": "ok", "exec_time": 1.0 }
+{ "type": "test", "name": "demo::other$test_smoke", "event": "failed", "exec_time": 1.0 }
+"#;
+
+    const PARSER: &str = r#"
+pub fn parse() {}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_parse() {}
+
+    #[test]
+    #[should_panic]
+    fn test_fail() {}
+
+    #[test]
+    #[ignore]
+    fn test_ignored() {}
+
+    #[tokio::test]
+    async fn test_slow() {}
+
+    fn helper() {}
+}
+"#;
+
+    fn statuses(test_cases: &[TestCase]) -> Vec<(&str, TestStatus, Option<Duration>)> {
+        test_cases
+            .iter()
+            .map(|test_case| {
+                (
+                    test_case.name.as_str(),
+                    test_case.result.status,
+                    test_case.result.duration,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_test_cases() {
+        let report = TestReport::parse(REPORT);
+        let test_cases = report.test_cases("src/parser.rs", PARSER).unwrap();
+        assert_eq!(
+            statuses(&test_cases),
+            vec![
+                (
+                    "parser::tests::test_parse",
+                    TestStatus::Passed,
+                    Some(Duration::from_millis(250))
+                ),
+                (
+                    "parser::tests::test_fail",
+                    TestStatus::Failed,
+                    Some(Duration::from_millis(500))
+                ),
+                ("parser::tests::test_ignored", TestStatus::Skipped, None),
+            ]
+        );
+        assert_eq!(
+            test_cases[0].location,
+            SonarLocation {
+                start_line: 7,
+                start_column: 7,
+                end_line: 7,
+                end_column: 17,
+            }
+        );
+        assert!(test_cases[1]
+            .result
+            .message
+            .as_ref()
+            .is_some_and(|message| message.contains("boom")));
+
+        // Outside of the module of the tests
+        assert!(report.test_cases("src/lib.rs", PARSER).unwrap().is_empty());
+        assert!(report
+            .test_cases("examples/parser.rs", PARSER)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_integration_test_cases() {
+        let report = TestReport::parse(REPORT);
+        let source_code = "#[test]\nfn test_smoke() {}\n";
+        assert_eq!(
+            statuses(&report.test_cases("tests/smoke.rs", source_code).unwrap()),
+            vec![(
+                "test_smoke",
+                TestStatus::Passed,
+                Some(Duration::from_secs(1))
+            )]
+        );
+        assert_eq!(
+            statuses(&report.test_cases("tests/other.rs", source_code).unwrap()),
+            vec![(
+                "test_smoke",
+                TestStatus::Failed,
+                Some(Duration::from_secs(1))
+            )]
+        );
+        assert!(report
+            .test_cases("src/lib.rs", source_code)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_load() {
+        let dir = std::env::temp_dir().join(format!("test-report-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("tests.json"), REPORT).unwrap();
+        let mut parameters = default_parameters();
+        parameters.insert("project_dir".to_string(), dir.to_string_lossy().to_string());
+        parameters.insert("test_report_paths".to_string(), "tests.json".to_string());
+
+        let files = vec![("src/parser.rs".to_string(), PARSER.as_bytes().to_vec())];
+        let outputs = analyze_batch(&files, &parameters).unwrap();
+        assert_eq!(outputs[0].as_ref().unwrap().test_cases.len(), 3);
+
+        parameters.insert(
+            "test_report_paths".to_string(),
+            "tests.json,missing.json".to_string(),
+        );
+        assert!(TestReport::load(&parameters).is_err());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,418 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+//! The subset of TOML read by the analyzer, in the `sonar-rust.toml` configuration (see `ProjectConfig`) and in
+//! the `Cargo.toml` manifests (see `Manifest`): tables, dotted keys, strings, booleans, numbers, arrays and inline
+//! tables.
+
+/// A TOML value. Numbers and dates are kept as text.
+#[derive(Debug, PartialEq, Clone)]
+pub enum Value {
+    String(String),
+    Boolean(bool),
+    Array(Vec<Value>),
+    Table(Vec<(String, Value)>),
+    Other(String),
+}
+
+impl Value {
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    /// Converts the value to its parameter text: strings are unquoted, arrays are joined by commas.
+    pub(crate) fn to_parameter(&self) -> Result<String, String> {
+        match self {
+            Value::String(string) => Ok(string.clone()),
+            Value::Boolean(boolean) => Ok(boolean.to_string()),
+            Value::Array(elements) => {
+                let mut values = vec![];
+                for element in elements {
+                    if matches!(element, Value::Array(_)) {
+                        return Err("nested arrays are not supported".to_string());
+                    }
+                    values.push(element.to_parameter()?);
+                }
+                Ok(values.join(","))
+            }
+            Value::Table(_) => Err("inline tables are not supported".to_string()),
+            Value::Other(text) => {
+                let bare = text.replace('_', "");
+                if bare.parse::<i64>().is_ok() || bare.parse::<f64>().is_ok() {
+                    Ok(bare)
+                } else {
+                    Err(format!("unsupported value '{}'", text))
+                }
+            }
+        }
+    }
+}
+
+/// Parses the whole text as a single value, followed by an optional comment.
+pub(crate) fn parse_value(text: &str) -> Result<Value, String> {
+    ValueParser::new(text).parse_complete()
+}
+
+/// Parses the whole text as a single value, converted to its parameter text (see `Value::to_parameter`).
+pub(crate) fn parse_parameter(text: &str) -> Result<String, String> {
+    parse_value(text)?.to_parameter()
+}
+
+/// Parses the value of a `key = value` line, reading the next lines of the arrays, inline tables and multi-line
+/// strings spanning several lines.
+pub(crate) fn read_value<'a>(
+    value: &str,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<Value, String> {
+    let mut value = value.to_string();
+    while !is_complete(&value) {
+        let Some((_, next)) = lines.next() else {
+            return Err(if value.trim_start().starts_with('[') {
+                "unclosed array".to_string()
+            } else {
+                "unclosed value".to_string()
+            });
+        };
+        value.push('\n');
+        value.push_str(next);
+    }
+    parse_value(&value)
+}
+
+/// Whether the value is complete: its arrays and inline tables are closed, and so are its multi-line strings.
+fn is_complete(value: &str) -> bool {
+    let mut parser = ValueParser::new(value);
+    match parser.parse_value() {
+        Ok(_) => true,
+        Err(message) => message != "unexpected end of value",
+    }
+}
+
+/// Removes a '#' comment, which may not start in a string.
+pub(crate) fn strip_comment(line: &str) -> &str {
+    let mut quote = None;
+    let mut escaped = false;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (Some('"'), '\\') if !escaped => {
+                escaped = true;
+                continue;
+            }
+            (Some(q), c) if c == q && !escaped => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => return &line[..index],
+            _ => {}
+        }
+        escaped = false;
+    }
+    line
+}
+
+/// Splits a key on its dots outside quotes, and unquotes its parts.
+pub(crate) fn split_key(key: &str) -> Result<Vec<String>, String> {
+    let mut parts = vec![];
+    let mut parser = ValueParser::new(key);
+    loop {
+        parser.skip_whitespace();
+        let part = match parser.peek() {
+            Some('"' | '\'') => match parser.parse_value()? {
+                Value::String(part) => part,
+                _ => unreachable!("quoted keys are strings"),
+            },
+            _ => parser.take_while(|c| c.is_alphanumeric() || c == '_' || c == '-'),
+        };
+        if part.is_empty() {
+            return Err(format!("invalid key '{}'", key.trim()));
+        }
+        parts.push(part);
+        parser.skip_whitespace();
+        match parser.next() {
+            None => return Ok(parts),
+            Some('.') => {}
+            Some(c) => return Err(format!("unexpected '{}' in key '{}'", c, key.trim())),
+        }
+    }
+}
+
+/// Splits a `key = value` line on its first '=' outside quotes.
+pub(crate) fn split_key_value(line: &str) -> Option<(&str, &str)> {
+    let mut quote = None;
+    for (index, c) in line.char_indices() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '=') => return Some((&line[..index], &line[index + 1..])),
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Parser of the values.
+struct ValueParser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> ValueParser<'a> {
+    fn new(text: &'a str) -> Self {
+        ValueParser { text, position: 0 }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.position..].chars().next()
+    }
+
+    fn next(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.position += c.len_utf8();
+        Some(c)
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(char) -> bool) -> String {
+        let start = self.position;
+        while self.peek().is_some_and(&predicate) {
+            self.next();
+        }
+        self.text[start..self.position].to_string()
+    }
+
+    /// Skips the whitespace, including the new lines and comments of multi-line arrays.
+    fn skip_whitespace(&mut self) {
+        loop {
+            self.take_while(char::is_whitespace);
+            if self.peek() != Some('#') {
+                return;
+            }
+            self.take_while(|c| c != '\n');
+        }
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.next() {
+            Some(c) if c == expected => Ok(()),
+            Some(c) => Err(format!("expected '{}' but found '{}'", expected, c)),
+            None => Err("unexpected end of value".to_string()),
+        }
+    }
+
+    fn parse_complete(&mut self) -> Result<Value, String> {
+        let value = self.parse_value()?;
+        self.skip_whitespace();
+        match self.peek() {
+            None => Ok(value),
+            Some(c) => Err(format!("unexpected '{}' after value", c)),
+        }
+    }
+
+    fn parse_value(&mut self) -> Result<Value, String> {
+        self.skip_whitespace();
+        let rest = &self.text[self.position..];
+        if rest.starts_with("\"\"\"") || rest.starts_with("'''") {
+            return self.parse_multiline_string();
+        }
+        match self.peek() {
+            None => Err("unexpected end of value".to_string()),
+            Some('"') => self.parse_basic_string().map(Value::String),
+            Some('\'') => {
+                self.next();
+                let string = self.take_while(|c| c != '\'' && c != '\n');
+                self.expect('\'')?;
+                Ok(Value::String(string))
+            }
+            Some('[') => {
+                self.next();
+                let mut elements = vec![];
+                loop {
+                    self.skip_whitespace();
+                    if self.peek() == Some(']') {
+                        self.next();
+                        return Ok(Value::Array(elements));
+                    }
+                    elements.push(self.parse_value()?);
+                    self.skip_whitespace();
+                    match self.next() {
+                        Some(',') => {}
+                        Some(']') => return Ok(Value::Array(elements)),
+                        Some(c) => return Err(format!("unexpected '{}' in array", c)),
+                        None => return Err("unexpected end of value".to_string()),
+                    }
+                }
+            }
+            Some('{') => {
+                self.next();
+                let mut fields = vec![];
+                loop {
+                    self.take_while(|c| c == ' ' || c == '\t');
+                    if self.peek() == Some('}') {
+                        self.next();
+                        return Ok(Value::Table(fields));
+                    }
+                    let key = self.take_while(|c| c != '=' && c != '}' && c != '\n');
+                    self.expect('=')?;
+                    let key = split_key(&key)?;
+                    let value = self.parse_value()?;
+                    // Dotted keys of inline tables, e.g. `{ features.default = true }`, are fields of their first part
+                    fields.push((key[0].clone(), value));
+                    self.take_while(|c| c == ' ' || c == '\t');
+                    match self.next() {
+                        Some(',') => {}
+                        Some('}') => return Ok(Value::Table(fields)),
+                        Some(c) => return Err(format!("unexpected '{}' in inline table", c)),
+                        None => return Err("unexpected end of value".to_string()),
+                    }
+                }
+            }
+            Some(_) => {
+                let bare =
+                    self.take_while(|c| !c.is_whitespace() && !matches!(c, ',' | ']' | '}' | '#'));
+                match bare.as_str() {
+                    "true" => Ok(Value::Boolean(true)),
+                    "false" => Ok(Value::Boolean(false)),
+                    "" => Err("expected a value".to_string()),
+                    _ => Ok(Value::Other(bare)),
+                }
+            }
+        }
+    }
+
+    fn parse_basic_string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut string = String::new();
+        loop {
+            match self.next() {
+                None | Some('\n') => return Err("unclosed string".to_string()),
+                Some('"') => return Ok(string),
+                Some('\\') => match self.next() {
+                    Some('"') => string.push('"'),
+                    Some('\\') => string.push('\\'),
+                    Some('n') => string.push('\n'),
+                    Some('t') => string.push('\t'),
+                    Some(c) => {
+                        // Unicode escapes are kept as is, none of the settings or rules depend on them
+                        string.push('\\');
+                        string.push(c);
+                    }
+                    None => return Err("unclosed string".to_string()),
+                },
+                Some(c) => string.push(c),
+            }
+        }
+    }
+
+    fn parse_multiline_string(&mut self) -> Result<Value, String> {
+        let delimiter = &self.text[self.position..self.position + 3];
+        self.position += 3;
+        match self.text[self.position..].find(delimiter) {
+            Some(end) => {
+                let string = self.text[self.position..self.position + end]
+                    .strip_prefix('\n')
+                    .unwrap_or(&self.text[self.position..self.position + end])
+                    .to_string();
+                self.position += end + 3;
+                Ok(Value::String(string))
+            }
+            None => Err("unexpected end of value".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value() {
+        assert_eq!(
+            parse_value(r#"{ version = "1", features = ["a", 'b'] } # comment"#),
+            Ok(Value::Table(vec![
+                ("version".to_string(), Value::String("1".to_string())),
+                (
+                    "features".to_string(),
+                    Value::Array(vec![
+                        Value::String("a".to_string()),
+                        Value::String("b".to_string())
+                    ])
+                ),
+            ]))
+        );
+        assert_eq!(
+            parse_value(r#""a \"b\" \\ c""#),
+            Ok(Value::String("a \"b\" \\ c".to_string()))
+        );
+        assert_eq!(
+            parse_value("\"unclosed"),
+            Err("unclosed string".to_string())
+        );
+        assert_eq!(
+            parse_value("1 2"),
+            Err("unexpected '2' after value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_parameter() {
+        assert_eq!(parse_parameter("1_000").as_deref(), Ok("1000"));
+        assert_eq!(parse_parameter("[\"a\", 'b',]").as_deref(), Ok("a,b"));
+        assert_eq!(
+            parse_parameter("[[1]]"),
+            Err("nested arrays are not supported".to_string())
+        );
+        assert_eq!(
+            parse_parameter("soon"),
+            Err("unsupported value 'soon'".to_string())
+        );
+    }
+
+    #[test]
+    fn test_read_value() {
+        let mut lines = "  \"b\", # comment\n]\nnext = 1".lines().enumerate();
+        assert_eq!(
+            read_value("[\"a\",", &mut lines),
+            Ok(Value::Array(vec![
+                Value::String("a".to_string()),
+                Value::String("b".to_string())
+            ]))
+        );
+        assert_eq!(lines.next(), Some((2, "next = 1")));
+        assert_eq!(
+            read_value("[\"a\",", &mut std::iter::empty()),
+            Err("unclosed array".to_string())
+        );
+        assert_eq!(
+            read_value("\"\"\"text", &mut std::iter::empty()),
+            Err("unclosed value".to_string())
+        );
+    }
+
+    #[test]
+    fn test_keys_and_comments() {
+        assert_eq!(
+            split_key("target . 'cfg(unix)'.dependencies"),
+            Ok(vec![
+                "target".to_string(),
+                "cfg(unix)".to_string(),
+                "dependencies".to_string()
+            ])
+        );
+        assert_eq!(split_key_value(r#""a=b" = 1"#), Some((r#""a=b" "#, " 1")));
+        assert_eq!(strip_comment(r#"[a] # "b""#), "[a] ");
+        assert_eq!(strip_comment(r##"a = "#\"#" # c"##), r##"a = "#\"#" "##);
+    }
+}
@@ -14,7 +14,17 @@
  * You should have received a copy of the Sonar Source-Available License
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
-use tree_sitter::{Node, Parser, Point, Tree, TreeCursor};
+use std::{
+    borrow::Cow,
+    cell::{Cell, RefCell},
+    panic::{catch_unwind, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Instant,
+};
+use tree_sitter::{InputEdit, Node, ParseOptions, ParseState, Parser, Point, Tree, TreeCursor};
 
 /// Source location as defined by Tree-sitter.
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -56,6 +66,22 @@ pub enum AnalyzerError {
     FileError(String),
     /// Global errors that prevent the analysis of all files.
     GlobalError(String),
+    /// The analysis of the file exceeded its deadline, see `set_deadline`.
+    TimedOut,
+    /// The caller cancelled the analysis of the file, see `CancellationToken`.
+    Cancelled,
+}
+
+impl std::fmt::Display for AnalyzerError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AnalyzerError::FileError(message) | AnalyzerError::GlobalError(message) => {
+                write!(f, "{}", message)
+            }
+            AnalyzerError::TimedOut => write!(f, "the analysis timed out"),
+            AnalyzerError::Cancelled => write!(f, "the analysis was cancelled"),
+        }
+    }
 }
 
 impl TreeSitterLocation {
@@ -75,8 +101,17 @@ impl TreeSitterLocation {
             str::encode_utf16(&source_code[first_line_start_byte..self.start_byte]).count() as i32;
 
         let last_line_start_byte = self.end_byte - self.end_position.column;
+        // A "\r" before the "\n" ending the line is part of the line terminator, not of the line (e.g. for the line
+        // comments of files with Windows line endings)
+        let mut end_byte = self.end_byte;
+        if end_byte > last_line_start_byte
+            && source_code[..end_byte].ends_with('\r')
+            && source_code[end_byte..].starts_with('\n')
+        {
+            end_byte -= 1;
+        }
         let last_line_offset =
-            str::encode_utf16(&source_code[last_line_start_byte..self.end_byte]).count() as i32;
+            str::encode_utf16(&source_code[last_line_start_byte..end_byte]).count() as i32;
 
         SonarLocation {
             start_line: self.start_position.row + 1,
@@ -96,6 +131,136 @@ impl TreeSitterLocation {
     }
 }
 
+thread_local! {
+    /// Instant after which the analysis running on the current thread should stop, if any.
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+    /// Token of the analysis running on the current thread, if it can be cancelled.
+    static CANCELLATION: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+    /// Whether the code running on the current thread is wrapped in `catch_panic`.
+    static CATCHING_PANIC: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Number of nodes visited by `walk_tree` between two deadline checks.
+const DEADLINE_CHECK_INTERVAL: usize = 1024;
+
+/// Sets the deadline of the analysis running on the current thread.
+///
+/// Once the deadline is exceeded, parsing and tree traversals fail with `AnalyzerError::TimedOut`.
+/// The previous deadline is restored when the returned guard is dropped.
+pub(crate) fn set_deadline(deadline: Option<Instant>) -> DeadlineGuard {
+    DeadlineGuard {
+        previous: DEADLINE.replace(deadline),
+    }
+}
+
+pub(crate) struct DeadlineGuard {
+    previous: Option<Instant>,
+}
+
+impl Drop for DeadlineGuard {
+    fn drop(&mut self) {
+        DEADLINE.set(self.previous);
+    }
+}
+
+pub(crate) fn deadline_exceeded() -> bool {
+    DEADLINE
+        .get()
+        .is_some_and(|deadline| Instant::now() >= deadline)
+}
+
+/// Flag shared between the caller of an analysis and the threads running it, which the caller sets to abort the
+/// analysis early, e.g. when the editor buffer of the file changed again.
+///
+/// The analysis checks the token between its steps and at the same points as its deadline, see `set_deadline`, then
+/// fails with `AnalyzerError::Cancelled`.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Sets the cancellation token of the analysis running on the current thread.
+///
+/// The previous token is restored when the returned guard is dropped.
+pub(crate) fn set_cancellation(token: Option<CancellationToken>) -> CancellationGuard {
+    CancellationGuard {
+        previous: CANCELLATION.replace(token),
+    }
+}
+
+pub(crate) struct CancellationGuard {
+    previous: Option<CancellationToken>,
+}
+
+impl Drop for CancellationGuard {
+    fn drop(&mut self) {
+        CANCELLATION.set(self.previous.take());
+    }
+}
+
+/// Cancellation token of the analysis running on the current thread, to set on the threads it spawns.
+pub(crate) fn current_cancellation() -> Option<CancellationToken> {
+    CANCELLATION.with_borrow(|token| token.clone())
+}
+
+fn cancelled() -> bool {
+    CANCELLATION.with_borrow(|token| token.as_ref().is_some_and(CancellationToken::is_cancelled))
+}
+
+/// Whether the analysis running on the current thread should stop, because it was cancelled or exceeded its
+/// deadline.
+pub(crate) fn interrupted() -> bool {
+    cancelled() || deadline_exceeded()
+}
+
+/// Fails with `AnalyzerError::Cancelled` or `AnalyzerError::TimedOut` once the analysis running on the current thread
+/// should stop, see `interrupted`.
+pub(crate) fn check_deadline() -> Result<(), AnalyzerError> {
+    if cancelled() {
+        Err(AnalyzerError::Cancelled)
+    } else if deadline_exceeded() {
+        Err(AnalyzerError::TimedOut)
+    } else {
+        Ok(())
+    }
+}
+
+/// Runs a step or a rule of the analysis, converting a panic into an `AnalyzerError::FileError`: a bug triggered by
+/// an unusual file then only loses the results of this step or rule, instead of aborting the whole analysis.
+pub(crate) fn catch_panic<T>(
+    f: impl FnOnce() -> Result<T, AnalyzerError>,
+) -> Result<T, AnalyzerError> {
+    let previous = CATCHING_PANIC.replace(true);
+    let result = catch_unwind(AssertUnwindSafe(f));
+    CATCHING_PANIC.set(previous);
+    result.unwrap_or_else(|payload| {
+        let cause = payload
+            .downcast_ref::<&str>()
+            .map(|cause| cause.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown cause".to_string());
+        Err(AnalyzerError::FileError(format!("panicked: {}", cause)))
+    })
+}
+
+/// Whether a panic of the current thread is caught by `catch_panic`, and is then reported as a failure of the step or
+/// rule which panicked.
+pub fn catching_panic() -> bool {
+    CATCHING_PANIC.get()
+}
+
 /// Performs a depth-first traversal of the tree, calling the callbacks defined in the visitor whenever entering and leaving a node.
 /// The visitor visits "extra" nodes (e.g. comments) as well, however, it does not visit their children
 /// (i.e. comments are treated as leaves in the tree).
@@ -106,10 +271,16 @@ pub(crate) fn walk_tree(
     let mut cursor = tree.walk();
     let mut has_next = true;
     let mut visited_children = false;
+    let mut visited_nodes: usize = 0;
 
     while has_next {
         let node = cursor.node();
 
+        visited_nodes += 1;
+        if visited_nodes.is_multiple_of(DEADLINE_CHECK_INTERVAL) {
+            check_deadline()?;
+        }
+
         if node.is_extra() {
             // "Extra" nodes are nodes that are not part of the grammar (e.g. comments), so there is no need to visit their children.
             visited_children = true;
@@ -136,7 +307,19 @@ pub(crate) fn walk_tree(
     Ok(())
 }
 
+/// Parses the source code, which may use syntax that is not supported by the grammar yet.
+///
+/// `let ... else`, `gen` blocks and C-string literals are supported by the grammar. Async closures are not, the error
+/// nodes the grammar recovers from them with are not syntax errors, see `is_async_closure_error`.
 pub(crate) fn parse_rust_code(source_code: &str) -> Result<Tree, AnalyzerError> {
+    parse_rust_code_incrementally(source_code, None)
+}
+
+/// Same as `parse_rust_code`, reusing the tree of a previous version of the source code for the unchanged parts.
+pub(crate) fn parse_rust_code_incrementally(
+    source_code: &str,
+    previous: Option<(&Tree, &str)>,
+) -> Result<Tree, AnalyzerError> {
     let mut parser = Parser::new();
     parser
         .set_language(&tree_sitter_rust::LANGUAGE.into())
@@ -144,20 +327,217 @@ pub(crate) fn parse_rust_code(source_code: &str) -> Result<Tree, AnalyzerError>
             AnalyzerError::GlobalError(format!("failed to initialize parser: {:?}", err))
         })?;
 
-    let tree = parser
-        .parse(source_code, None)
-        .ok_or(AnalyzerError::FileError(
-            "failed to parse the source code".to_string(),
-        ))?;
+    let previous_tree = previous.map(|(tree, previous_source_code)| {
+        let mut tree = tree.clone();
+        tree.edit(&input_edit(previous_source_code, source_code));
+        tree
+    });
+    // Tree-sitter only ends the rows with "\n": the lone "\r" are replaced by "\n", which keeps the byte offsets, so
+    // that the rows of the tree are the lines of SonarQube
+    let bytes = normalize_line_endings(source_code);
+    parse_bytes(&mut parser, &bytes, previous_tree.as_ref())
+}
+
+/// Edit from the previous version of the source code to the new one, replacing the bytes between their common prefix
+/// and their common suffix.
+fn input_edit(previous: &str, source_code: &str) -> InputEdit {
+    let mut start = previous
+        .bytes()
+        .zip(source_code.bytes())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !source_code.is_char_boundary(start) || !previous.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut suffix = previous[start..]
+        .bytes()
+        .rev()
+        .zip(source_code[start..].bytes().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    while !previous.is_char_boundary(previous.len() - suffix)
+        || !source_code.is_char_boundary(source_code.len() - suffix)
+    {
+        suffix -= 1;
+    }
+    let point = |text: &str, offset: usize| point_of(&line_starts(text), offset);
+    let old_end = previous.len() - suffix;
+    let new_end = source_code.len() - suffix;
+    InputEdit {
+        start_byte: start,
+        old_end_byte: old_end,
+        new_end_byte: new_end,
+        start_position: point(previous, start),
+        old_end_position: point(previous, old_end),
+        new_end_position: point(source_code, new_end),
+    }
+}
+
+/// Bytes of the source code, with the lone "\r" replaced by "\n", see `line_starts`.
+fn normalize_line_endings(source_code: &str) -> Cow<'_, [u8]> {
+    let bytes = source_code.as_bytes();
+    let lone_cr = |index: usize| bytes[index] == b'\r' && bytes.get(index + 1) != Some(&b'\n');
+    if !(0..bytes.len()).any(lone_cr) {
+        return Cow::Borrowed(bytes);
+    }
+    Cow::Owned(
+        (0..bytes.len())
+            .map(|index| if lone_cr(index) { b'\n' } else { bytes[index] })
+            .collect(),
+    )
+}
+
+/// Byte offsets of the start of each line of the text. Lines end with "\r\n", "\n" or a lone "\r", as in SonarQube.
+pub(crate) fn line_starts(text: &str) -> Vec<usize> {
+    let bytes = text.as_bytes();
+    let mut starts = vec![0];
+    for (index, &byte) in bytes.iter().enumerate() {
+        if byte == b'\n' || (byte == b'\r' && bytes.get(index + 1) != Some(&b'\n')) {
+            starts.push(index + 1);
+        }
+    }
+    starts
+}
+
+/// Lines of the text without their terminator, see `line_starts`. As for `str::lines`, a terminator at the end of the
+/// text does not start another line.
+pub(crate) fn split_lines(text: &str) -> impl Iterator<Item = &str> {
+    let starts = line_starts(text);
+    let ends: Vec<usize> = starts.iter().skip(1).copied().chain([text.len()]).collect();
+    starts
+        .into_iter()
+        .zip(ends)
+        .filter(move |&(start, _)| start < text.len())
+        .map(move |(start, end)| {
+            let line = &text[start..end];
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            line.strip_suffix('\r').unwrap_or(line)
+        })
+}
+
+/// Tree-sitter point of a byte offset, given the `line_starts` of the text.
+pub(crate) fn point_of(line_starts: &[usize], offset: usize) -> Point {
+    let row = line_starts.partition_point(|&start| start <= offset) - 1;
+    Point {
+        row,
+        column: offset - line_starts[row],
+    }
+}
+
+fn parse_bytes(
+    parser: &mut Parser,
+    bytes: &[u8],
+    previous_tree: Option<&Tree>,
+) -> Result<Tree, AnalyzerError> {
+    let mut progress = |_: &ParseState| interrupted();
+    let tree = parser.parse_with_options(
+        &mut |offset, _| &bytes[offset.min(bytes.len())..],
+        previous_tree,
+        Some(ParseOptions::new().progress_callback(&mut progress)),
+    );
+
+    match tree {
+        Some(tree) => Ok(tree),
+        None => {
+            // Parsing halts when the progress callback reports that the analysis is cancelled or past its deadline
+            check_deadline()?;
+            Err(AnalyzerError::FileError(
+                "failed to parse the source code".to_string(),
+            ))
+        }
+    }
+}
 
-    Ok(tree)
+/// Whether the node is one of the error nodes the grammar recovers from async closures with.
+///
+/// `async |x| x` and `async move |x| x` are parsed as an error node containing the `async` and `move` keywords,
+/// followed by a regular closure. `async move || { ... }` is parsed as an async block containing an error node for
+/// the `||` of its parameters, followed by its body.
+pub(crate) fn is_async_closure_error(node: Node<'_>) -> bool {
+    if !node.is_error() {
+        return false;
+    }
+    let mut cursor = node.walk();
+    let kinds: Vec<&str> = node
+        .children(&mut cursor)
+        .map(|child| child.kind())
+        .collect();
+    let next = node.next_sibling();
+    match kinds.as_slice() {
+        // The closure may start an expression statement
+        ["async"] | ["async", "move"] => next
+            .map(|next| match next.kind() {
+                "expression_statement" => next.child(0).unwrap_or(next),
+                _ => next,
+            })
+            .is_some_and(|next| next.kind() == "closure_expression"),
+        ["|", "|"] => {
+            node.parent().map(|parent| parent.kind()) == Some("async_block")
+                && next.map(|next| next.kind()) == Some("block")
+        }
+        _ => false,
+    }
+}
+
+/// Whether the node is a closure, including the `async move || { ... }` closures parsed as async blocks, see
+/// `is_async_closure_error`.
+pub(crate) fn is_closure(node: Node<'_>) -> bool {
+    match node.kind() {
+        "closure_expression" => true,
+        "async_block" => {
+            let mut cursor = node.walk();
+            let is_closure = node.children(&mut cursor).any(is_async_closure_error);
+            is_closure
+        }
+        _ => false,
+    }
+}
+
+/// Whether the closure captures its environment by value, including the async closures whose `move` keyword is in
+/// the error node preceding them, see `is_async_closure_error`.
+pub(crate) fn is_move_closure(closure: Node<'_>) -> bool {
+    let has_move = |node: Node<'_>| {
+        let mut cursor = node.walk();
+        let has_move = node
+            .children(&mut cursor)
+            .any(|child| child.kind() == "move");
+        has_move
+    };
+    let statement = closure.parent().filter(|parent| {
+        parent.kind() == "expression_statement" && parent.child(0) == Some(closure)
+    });
+    has_move(closure)
+        || statement
+            .unwrap_or(closure)
+            .prev_sibling()
+            .is_some_and(|previous| is_async_closure_error(previous) && has_move(previous))
+}
+
+/// The outermost error nodes and the missing nodes of the tree, but the error nodes of the async closures, see
+/// `is_async_closure_error`.
+pub(crate) fn syntax_errors(tree: &Tree) -> Vec<Node<'_>> {
+    let mut errors = vec![];
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        if node.is_missing() || (node.is_error() && !is_async_closure_error(node)) {
+            errors.push(node);
+        } else if node.has_error() {
+            let mut cursor = node.walk();
+            stack.extend(node.children(&mut cursor));
+        }
+    }
+    errors
 }
 
 /// Iterator for iterating over nodes of a tree filtered by a predicate.
 ///
 /// For example, you can use this iterator to iterate over all function nodes in a tree:
 /// ```rust
-/// let iter = NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item");
+/// # use analyzer::tree::NodeIterator;
+/// # let mut parser = tree_sitter::Parser::new();
+/// # parser.set_language(&tree_sitter_rust::LANGUAGE.into()).unwrap();
+/// # let tree = parser.parse("fn main() {}", None).unwrap();
+/// let mut iter = NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item");
 /// while let Some(function) = iter.next() {
 ///     // Handle function...
 /// }
@@ -223,3 +603,232 @@ pub fn child_of_kind<'a>(node: Node<'a>, kind: &str) -> Option<Node<'a>> {
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_catch_panic() {
+        assert!(matches!(catch_panic(|| Ok(42)), Ok(42)));
+        assert!(!catching_panic());
+        assert!(matches!(catch_panic(|| Ok(catching_panic())), Ok(true)));
+        assert!(matches!(
+            catch_panic::<()>(|| Err(AnalyzerError::TimedOut)),
+            Err(AnalyzerError::TimedOut)
+        ));
+        match catch_panic::<()>(|| panic!("index {} out of bounds", 3)) {
+            Err(AnalyzerError::FileError(message)) => {
+                assert_eq!(message, "panicked: index 3 out of bounds")
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
+
+    struct CountingVisitor {
+        nodes: usize,
+    }
+
+    impl NodeVisitor for CountingVisitor {
+        fn enter_node(&mut self, _node: Node<'_>) -> Result<(), AnalyzerError> {
+            self.nodes += 1;
+            Ok(())
+        }
+    }
+
+    fn large_source() -> String {
+        "fn foo() { let x = 1 + 2; }\n".repeat(500)
+    }
+
+    #[test]
+    fn test_walk_tree_within_deadline() {
+        let source_code = large_source();
+        let tree = parse_rust_code(&source_code).unwrap();
+
+        let _deadline = set_deadline(Some(Instant::now() + Duration::from_secs(3600)));
+        let mut visitor = CountingVisitor { nodes: 0 };
+        walk_tree(tree.root_node(), &mut visitor).unwrap();
+        assert!(visitor.nodes > DEADLINE_CHECK_INTERVAL);
+    }
+
+    #[test]
+    fn test_walk_tree_deadline_exceeded() {
+        let source_code = large_source();
+        let tree = parse_rust_code(&source_code).unwrap();
+
+        let _deadline = set_deadline(Some(Instant::now()));
+        let mut visitor = CountingVisitor { nodes: 0 };
+        assert!(matches!(
+            walk_tree(tree.root_node(), &mut visitor),
+            Err(AnalyzerError::TimedOut)
+        ));
+        assert!(visitor.nodes < DEADLINE_CHECK_INTERVAL);
+    }
+
+    #[test]
+    fn test_walk_tree_cancelled() {
+        let source_code = large_source();
+        let tree = parse_rust_code(&source_code).unwrap();
+
+        let token = CancellationToken::new();
+        let _cancellation = set_cancellation(Some(token.clone()));
+        let mut visitor = CountingVisitor { nodes: 0 };
+        walk_tree(tree.root_node(), &mut visitor).unwrap();
+
+        token.cancel();
+        let mut visitor = CountingVisitor { nodes: 0 };
+        assert!(matches!(
+            walk_tree(tree.root_node(), &mut visitor),
+            Err(AnalyzerError::Cancelled)
+        ));
+        assert!(visitor.nodes < DEADLINE_CHECK_INTERVAL);
+        // The cancellation takes precedence over the deadline
+        let _deadline = set_deadline(Some(Instant::now()));
+        assert!(matches!(
+            parse_rust_code(&source_code),
+            Err(AnalyzerError::Cancelled)
+        ));
+    }
+
+    #[test]
+    fn test_parse_deadline_exceeded() {
+        let _deadline = set_deadline(Some(Instant::now()));
+        assert!(matches!(
+            parse_rust_code(&large_source()),
+            Err(AnalyzerError::TimedOut)
+        ));
+    }
+
+    fn has_error(source_code: &str) -> bool {
+        !syntax_errors(&parse_rust_code(source_code).unwrap()).is_empty()
+    }
+
+    #[test]
+    fn test_modern_syntax() {
+        assert!(!has_error("fn f() { let Some(x) = y else { return; }; }"));
+        assert!(!has_error("fn f() { let g = gen { yield 1; }; }"));
+        assert!(!has_error(
+            r#"fn f() { let s = c"hello"; let r = cr"raw"; }"#
+        ));
+        assert!(!has_error(
+            "fn f() { async || {}; spawn(async move || { x }); foo(async |x: i32| x + 1); }"
+        ));
+    }
+
+    #[test]
+    fn test_async_closures() {
+        let source_code =
+            "fn f() { let c = async move |x| x; let d = async |y| y; spawn(async move || { z }); \
+                           async move || {}; async || x; }";
+        let tree = parse_rust_code(source_code).unwrap();
+
+        let closures: Vec<(&str, bool)> = NodeIterator::new(tree.root_node(), is_closure)
+            .map(|closure| (&source_code[closure.byte_range()], is_move_closure(closure)))
+            .collect();
+        assert_eq!(
+            closures,
+            vec![
+                ("|x| x", true),
+                ("|y| y", false),
+                ("async move || { z }", true),
+                ("async move || {}", true),
+                ("|| x", false)
+            ]
+        );
+
+        // Other syntax errors are still reported
+        assert!(has_error("fn f() { let c = async |x| x }"));
+        assert!(has_error("fn f() { async; let x = ; }"));
+        assert!(has_error("fn f() { let c = async x; }"));
+    }
+
+    #[test]
+    fn test_parse_rust_code_incrementally() {
+        let versions = [
+            "fn f() { let é = 1; }",
+            "fn f() { let è = 1; }\nfn g() {}",
+            "fn f() { let c = async |x| x; }\nfn g() {}",
+            "fn f() { let c = async |x| x + 1; }\nfn g() {}",
+            "",
+        ];
+        let mut previous: Option<(Tree, &str)> = None;
+        for source_code in versions {
+            let tree = parse_rust_code_incrementally(
+                source_code,
+                previous.as_ref().map(|(tree, code)| (tree, *code)),
+            )
+            .unwrap();
+            assert_eq!(
+                tree.root_node().to_sexp(),
+                parse_rust_code(source_code).unwrap().root_node().to_sexp()
+            );
+            previous = Some((tree, source_code));
+        }
+    }
+
+    #[test]
+    fn test_line_endings() {
+        assert_eq!(line_starts("a\r\nb\rc\n\nd"), vec![0, 3, 5, 7, 8]);
+        assert_eq!(
+            split_lines("a\r\nb\rc\n\nd\r\n").collect::<Vec<_>>(),
+            vec!["a", "b", "c", "", "d"]
+        );
+        assert_eq!(split_lines("").count(), 0);
+
+        // Location of the comment and of the identifier after it
+        let locations = |source_code: &str| {
+            let tree = parse_rust_code(source_code).unwrap();
+            let root = tree.root_node();
+            let comment = root.child(0).unwrap();
+            let name = root.child(1).unwrap().child_by_field_name("name").unwrap();
+            [comment, name].map(|node| {
+                TreeSitterLocation::from_tree_sitter_node(node).to_sonar_location(source_code)
+            })
+        };
+        let location = |start_line, start_column, end_line, end_column| SonarLocation {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        };
+        let expected = [location(1, 0, 1, 7), location(3, 3, 3, 7)];
+        assert_eq!(locations("// café\n\nfn main() {}\n"), expected);
+        assert_eq!(locations("// café\r\n\r\nfn main() {}\r\n"), expected);
+        assert_eq!(locations("// café\r\rfn main() {}\r"), expected);
+        assert_eq!(locations("// café\r\n\rfn main() {}\n"), expected);
+
+        // The replaced lone "\r" do not prevent the incremental parsing
+        let previous = "fn a() {}\rfn b() {}\r";
+        let source_code = "fn a() {}\rfn c() {}\r";
+        let tree = parse_rust_code_incrementally(
+            source_code,
+            Some((&parse_rust_code(previous).unwrap(), previous)),
+        )
+        .unwrap();
+        assert_eq!(
+            tree.root_node().to_sexp(),
+            parse_rust_code(source_code).unwrap().root_node().to_sexp()
+        );
+        assert_eq!(tree.root_node().child(1).unwrap().start_position().row, 1);
+    }
+
+    #[test]
+    fn test_deadline_is_restored() {
+        {
+            let _deadline = set_deadline(Some(Instant::now()));
+            assert!(deadline_exceeded());
+        }
+        assert!(!deadline_exceeded());
+        assert!(parse_rust_code("fn main() {}").is_ok());
+
+        let token = CancellationToken::new();
+        token.cancel();
+        {
+            let _cancellation = set_cancellation(Some(token));
+            assert!(interrupted());
+        }
+        assert!(!interrupted());
+        assert!(current_cancellation().is_none());
+    }
+}
@@ -0,0 +1,663 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+//! Type-aware analysis, built with the `type-aware` feature: the types of the calls of a file are inferred by a
+//! rust-analyzer server, started in the 'project_dir' with the 'rust_analyzer' setting as command.
+//!
+//! The server is driven as an editor drives it, with the Language Server Protocol on its standard input and output,
+//! rather than by linking the `ra_ap_*` crates, whose API changes with every release of rust-analyzer. It indexes the
+//! whole project and its dependencies before answering, so a single server is started and kept for the files of the
+//! project.
+use crate::{
+    issue::severity_override,
+    issue::Issue,
+    rule_description::RuleDescription,
+    tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    process::{Child, ChildStdin, ChildStdout, Command, Stdio},
+    sync::{Mutex, PoisonError},
+};
+use tree_sitter::{Node, Tree};
+
+pub const DISCARDED_RESULT_KEY: &str = "S7476";
+
+/// Description of the type-aware rule, which is only checked when the analysis infers the types.
+pub const DISCARDED_RESULT_DESCRIPTION: RuleDescription = RuleDescription {
+    key: DISCARDED_RESULT_KEY,
+    title: "\"Result\" values should not be silently discarded",
+    markdown: r#"
+This rule raises an issue on the `let _ = ...;` statements whose value is the `Result` returned by a function or a
+method call. It is only checked by the type-aware analysis, which infers the return types of the calls with
+rust-analyzer: the analyzer must be built with the `type-aware` feature, and the `sonar.rust.rustAnalyzer.command`
+property must give the command of rust-analyzer.
+
+## Why is this an issue?
+
+The compiler warns about an unused `Result`, as the error it may contain would be lost. Assigning it to `_` silences
+the warning without handling the error: a failure to write a file or to send a message goes unnoticed.
+
+```noncompliant
+fn save(path: &Path, data: &[u8]) {
+    let _ = fs::write(path, data);
+}
+```
+
+### How to fix it
+
+Propagate the error with `?`, or handle it, for instance by logging it when the operation may fail.
+
+```compliant
+fn save(path: &Path, data: &[u8]) -> io::Result<()> {
+    fs::write(path, data)?;
+    Ok(())
+}
+```
+
+## Resources
+
+### Documentation
+
+- Clippy Lints - [let_underscore_must_use](https://rust-lang.github.io/rust-clippy/master/index.html#let_underscore_must_use)
+"#,
+};
+
+/// Source of the types of the code of a file.
+pub trait TypeOracle {
+    /// Text shown when hovering the position, e.g. the signature of the function named there. The line starts at 0
+    /// and the column counts UTF-16 code units, as in the Language Server Protocol.
+    fn hover(&mut self, line: usize, column: usize) -> Result<Option<String>, AnalyzerError>;
+}
+
+/// Server of the project being analyzed, started for its first file. It exits with the analyzer, at the end of its
+/// input. It is `None` when it failed to start or stopped answering, the following files are then not checked.
+static SERVER: Mutex<Option<(PathBuf, Option<RustAnalyzer>)>> = Mutex::new(None);
+
+/// Reports the issues of the type-aware rules on the file at 'file_path' in the 'project_dir', when the 'rust_analyzer'
+/// command is set.
+///
+/// A server which cannot start, e.g. because rust-analyzer is not installed, or which stops answering fails with a
+/// `FileError` for the current file only: the other rules keep their issues, and the following files are not checked.
+pub fn find_type_aware_issues(
+    tree: &Tree,
+    source_code: &str,
+    parameters: &HashMap<String, String>,
+    disabled_rules: &[String],
+) -> Result<Vec<Issue>, AnalyzerError> {
+    let (Some(command), Some(project_dir), Some(file_path)) = (
+        parameters.get("rust_analyzer"),
+        parameters.get("project_dir").map(Path::new),
+        parameters.get("file_path"),
+    ) else {
+        return Ok(vec![]);
+    };
+    if disabled_rules.iter().any(|key| key == DISCARDED_RESULT_KEY) {
+        return Ok(vec![]);
+    }
+    let severity = severity_override(parameters, DISCARDED_RESULT_KEY)?;
+
+    let mut state = SERVER.lock().unwrap_or_else(PoisonError::into_inner);
+    if state.as_ref().is_none_or(|(dir, _)| dir != project_dir) {
+        // The server of another project stops before the new one indexes
+        *state = None;
+        let started = RustAnalyzer::start(command, project_dir);
+        let failure = started.as_ref().err().map(ToString::to_string);
+        *state = Some((project_dir.to_path_buf(), started.ok()));
+        if let Some(message) = failure {
+            return Err(AnalyzerError::FileError(message));
+        }
+    }
+    let Some((_, Some(server))) = state.as_mut() else {
+        return Ok(vec![]);
+    };
+    match check_file(server, &project_dir.join(file_path), tree, source_code) {
+        Ok(issues) => Ok(issues
+            .into_iter()
+            .map(|issue| Issue { severity, ..issue })
+            .collect()),
+        Err(AnalyzerError::GlobalError(message)) => {
+            // The input or output of the server failed
+            *state = Some((project_dir.to_path_buf(), None));
+            Err(AnalyzerError::FileError(message))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Issues of the file, opened in the server with the source code. The input and output failures of the server are
+/// `GlobalError`s, its errors on the file are `FileError`s.
+fn check_file(
+    server: &mut RustAnalyzer,
+    path: &Path,
+    tree: &Tree,
+    source_code: &str,
+) -> Result<Vec<Issue>, AnalyzerError> {
+    server.open(path, source_code)?;
+    let issues = find_discarded_results(tree, source_code, &mut FileHovers { server, path });
+    server.close(path)?;
+    issues
+}
+
+/// Reports the `let _ = <call>;` statements whose call returns a `Result`.
+pub fn find_discarded_results(
+    tree: &Tree,
+    source_code: &str,
+    oracle: &mut dyn TypeOracle,
+) -> Result<Vec<Issue>, AnalyzerError> {
+    let mut issues = vec![];
+    for declaration in NodeIterator::new(tree.root_node(), |node| node.kind() == "let_declaration")
+    {
+        let (Some(pattern), Some(value)) = (
+            declaration.child_by_field_name("pattern"),
+            declaration.child_by_field_name("value"),
+        ) else {
+            continue;
+        };
+        if &source_code[pattern.byte_range()] != "_" {
+            continue;
+        }
+        let Some(callee) = callee_name(value) else {
+            continue;
+        };
+        let callee =
+            TreeSitterLocation::from_tree_sitter_node(callee).to_sonar_location(source_code);
+        let Some(hover) = oracle.hover(callee.start_line - 1, callee.start_column)? else {
+            continue;
+        };
+        if !return_type(&hover).is_some_and(is_result) {
+            continue;
+        }
+        issues.push(Issue {
+            rule_key: DISCARDED_RESULT_KEY.to_string(),
+            message: "Handle the \"Result\" of this call instead of discarding it.".to_string(),
+            location: TreeSitterLocation::from_tree_sitter_node(value)
+                .to_sonar_location(source_code),
+            secondary_locations: vec![],
+            severity: None,
+            quick_fixes: vec![],
+            fingerprint: None,
+            hotspot: None,
+        });
+    }
+    Ok(issues)
+}
+
+/// Name of the function or method called by the expression, whose hover is its signature.
+fn callee_name(expression: Node<'_>) -> Option<Node<'_>> {
+    if expression.kind() != "call_expression" {
+        return None;
+    }
+    let mut function = expression.child_by_field_name("function")?;
+    loop {
+        function = match function.kind() {
+            "identifier" => return Some(function),
+            "scoped_identifier" => return function.child_by_field_name("name"),
+            "field_expression" => return function.child_by_field_name("field"),
+            "generic_function" => function.child_by_field_name("function")?,
+            _ => return None,
+        };
+    }
+}
+
+/// Return type of the function whose signature is shown by the hover, e.g. `io::Result<()>`.
+fn return_type(hover: &str) -> Option<&str> {
+    let start = hover.find("fn ")?;
+    let signature = &hover[start..];
+    let mut depth = 0usize;
+    let mut chars = signature.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '(' | '[' | '<' => depth += 1,
+            ')' | ']' | '>' => depth = depth.saturating_sub(1),
+            '-' if chars.peek().is_some_and(|&(_, next)| next == '>') => {
+                chars.next();
+                if depth == 0 {
+                    let rest = &signature[index + 2..];
+                    let end = rest
+                        .find(['\n', '{'])
+                        .or_else(|| rest.find(" where"))
+                        .unwrap_or(rest.len());
+                    return Some(rest[..end].trim());
+                }
+            }
+            '\n' if depth == 0 => return None,
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Whether the type is a `Result`, including the aliases named after it, such as `io::Result<()>`.
+fn is_result(type_name: &str) -> bool {
+    let path = type_name.split('<').next().unwrap_or_default().trim();
+    path.rsplit("::").next() == Some("Result")
+}
+
+/// Hovers of a file open in the server.
+struct FileHovers<'a> {
+    server: &'a mut RustAnalyzer,
+    path: &'a Path,
+}
+
+impl TypeOracle for FileHovers<'_> {
+    fn hover(&mut self, line: usize, column: usize) -> Result<Option<String>, AnalyzerError> {
+        self.server.hover(self.path, line, column)
+    }
+}
+
+/// Client of a rust-analyzer server.
+pub struct RustAnalyzer {
+    child: Child,
+    stdin: ChildStdin,
+    stdout: BufReader<ChildStdout>,
+    next_id: i64,
+}
+
+impl RustAnalyzer {
+    /// Starts the server in the project directory, and waits until it has indexed the project.
+    pub fn start(command: &str, project_dir: &Path) -> Result<Self, AnalyzerError> {
+        // A command which cannot run fails the same way for all files
+        let mut child = Command::new(command)
+            .current_dir(project_dir)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|err| {
+                AnalyzerError::GlobalError(format!("failed to run {}: {}", command, err))
+            })?;
+        let (Some(stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+            return Err(AnalyzerError::GlobalError(format!(
+                "failed to run {}: no standard input or output",
+                command
+            )));
+        };
+        let mut server = RustAnalyzer {
+            child,
+            stdin,
+            stdout: BufReader::new(stdout),
+            next_id: 0,
+        };
+        server.request(
+            "initialize",
+            json!({
+                "processId": std::process::id(),
+                "rootUri": file_uri(project_dir),
+                "capabilities": {
+                    "textDocument": { "hover": { "contentFormat": ["plaintext"] } },
+                    "experimental": { "serverStatusNotification": true },
+                },
+            }),
+        )?;
+        server.notify("initialized", json!({}))?;
+        server.wait_until_quiescent()?;
+        Ok(server)
+    }
+
+    /// Opens the file with the source code, which may differ from the file on disk.
+    pub fn open(&mut self, path: &Path, source_code: &str) -> Result<(), AnalyzerError> {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": file_uri(path),
+                    "languageId": "rust",
+                    "version": 1,
+                    "text": source_code,
+                },
+            }),
+        )
+    }
+
+    pub fn close(&mut self, path: &Path) -> Result<(), AnalyzerError> {
+        self.notify(
+            "textDocument/didClose",
+            json!({ "textDocument": { "uri": file_uri(path) } }),
+        )
+    }
+
+    /// Text of the hover of the position of an open file, see `TypeOracle::hover`.
+    pub fn hover(
+        &mut self,
+        path: &Path,
+        line: usize,
+        column: usize,
+    ) -> Result<Option<String>, AnalyzerError> {
+        let result = self.request(
+            "textDocument/hover",
+            json!({
+                "textDocument": { "uri": file_uri(path) },
+                "position": { "line": line, "character": column },
+            }),
+        )?;
+        Ok(hover_text(&result))
+    }
+
+    fn request(&mut self, method: &str, params: Value) -> Result<Value, AnalyzerError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        write_message(
+            &mut self.stdin,
+            &json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }),
+        )?;
+        loop {
+            let mut message = read_message(&mut self.stdout)?;
+            if message.get("method").is_some() {
+                self.answer(&message)?;
+                continue;
+            }
+            if message["id"] != id {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                return Err(AnalyzerError::FileError(format!(
+                    "rust-analyzer failed to answer {}: {}",
+                    method, error["message"]
+                )));
+            }
+            return Ok(message["result"].take());
+        }
+    }
+
+    fn notify(&mut self, method: &str, params: Value) -> Result<(), AnalyzerError> {
+        write_message(
+            &mut self.stdin,
+            &json!({ "jsonrpc": "2.0", "method": method, "params": params }),
+        )
+    }
+
+    /// Answers the requests of the server, e.g. the creation of a progress token, with an empty result. The
+    /// notifications need no answer.
+    fn answer(&mut self, message: &Value) -> Result<(), AnalyzerError> {
+        match message.get("id") {
+            Some(id) => write_message(
+                &mut self.stdin,
+                &json!({ "jsonrpc": "2.0", "id": id, "result": null }),
+            ),
+            None => Ok(()),
+        }
+    }
+
+    /// Waits for the status notification of the server telling that it has no more work to do, the project being
+    /// indexed.
+    fn wait_until_quiescent(&mut self) -> Result<(), AnalyzerError> {
+        loop {
+            let message = read_message(&mut self.stdout)?;
+            if message["method"] == "experimental/serverStatus"
+                && message["params"]["quiescent"] == true
+            {
+                return Ok(());
+            }
+            self.answer(&message)?;
+        }
+    }
+}
+
+impl Drop for RustAnalyzer {
+    fn drop(&mut self) {
+        let _ = self.notify("exit", Value::Null);
+        let _ = self.child.wait();
+    }
+}
+
+/// Text of the contents of a hover result: a markup content, a marked string, or an array of marked strings.
+fn hover_text(result: &Value) -> Option<String> {
+    let text = |content: &Value| match content {
+        Value::String(text) => Some(text.clone()),
+        Value::Object(object) => object.get("value")?.as_str().map(str::to_string),
+        _ => None,
+    };
+    match &result.get("contents")? {
+        Value::Array(contents) => {
+            let texts: Vec<String> = contents.iter().filter_map(text).collect();
+            (!texts.is_empty()).then(|| texts.join("\n"))
+        }
+        contents => text(contents),
+    }
+}
+
+/// URI of an absolute path, with the characters other than the unreserved ones and the separators percent-encoded.
+fn file_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = if path.starts_with('/') {
+        "file://".to_string()
+    } else {
+        "file:///".to_string()
+    };
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' | b':' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+fn write_message(writer: &mut impl Write, message: &Value) -> Result<(), AnalyzerError> {
+    let content = message.to_string();
+    write!(
+        writer,
+        "Content-Length: {}\r\n\r\n{}",
+        content.len(),
+        content
+    )
+    .and_then(|_| writer.flush())
+    .map_err(|err| AnalyzerError::GlobalError(format!("failed to write to rust-analyzer: {}", err)))
+}
+
+/// Reads a message framed by its headers, of which only 'Content-Length' is used.
+fn read_message(reader: &mut impl BufRead) -> Result<Value, AnalyzerError> {
+    let error = |cause: String| {
+        AnalyzerError::GlobalError(format!("failed to read from rust-analyzer: {}", cause))
+    };
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if reader
+            .read_line(&mut header)
+            .map_err(|err| error(err.to_string()))?
+            == 0
+        {
+            return Err(error("unexpected end of output".to_string()));
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = value.trim().parse::<usize>().ok();
+            }
+        }
+    }
+    let length = length.ok_or_else(|| error("missing Content-Length header".to_string()))?;
+    let mut content = vec![0; length];
+    reader
+        .read_exact(&mut content)
+        .map_err(|err| error(err.to_string()))?;
+    serde_json::from_slice(&content).map_err(|err| error(err.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+    use std::io::Cursor;
+
+    /// Hovers of the lines, as rust-analyzer shows the signatures of the functions.
+    struct Signatures(HashMap<usize, &'static str>);
+
+    impl TypeOracle for Signatures {
+        fn hover(&mut self, line: usize, _column: usize) -> Result<Option<String>, AnalyzerError> {
+            Ok(self.0.get(&line).map(|signature| signature.to_string()))
+        }
+    }
+
+    #[test]
+    fn test_discarded_results() {
+        let source_code = r#"
+fn main() {
+    let _ = fs::write("out.txt", data);
+    let _ = sender.send(message);
+    let _ = parse::<u32>(text);
+    let _ = compute();
+    let _ignored = fs::remove_file("out.txt");
+    let _ = fs::remove_file("out.txt")?;
+    fs::remove_file("out.txt");
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let mut oracle = Signatures(HashMap::from([
+            (
+                2,
+                "std::fs\n\npub fn write<P, C>(path: P, contents: C) -> Result<()>\nwhere\n    P: AsRef<Path>,",
+            ),
+            (
+                3,
+                "std::sync::mpsc::Sender\n\npub fn send(&self, t: T) -> Result<(), SendError<T>>",
+            ),
+            (4, "fn parse<T: FromStr>(text: &str) -> io::Result<T>"),
+            (5, "fn compute() -> u32"),
+            (6, "pub fn remove_file<P: AsRef<Path>>(path: P) -> Result<()>"),
+            (7, "pub fn remove_file<P: AsRef<Path>>(path: P) -> Result<()>"),
+            (8, "pub fn remove_file<P: AsRef<Path>>(path: P) -> Result<()>"),
+        ]));
+        let issues = find_discarded_results(&tree, source_code, &mut oracle).unwrap();
+        assert_eq!(
+            issues
+                .iter()
+                .map(|issue| (
+                    issue.location.start_line,
+                    issue.location.start_column,
+                    issue.location.end_column
+                ))
+                .collect::<Vec<_>>(),
+            vec![(3, 12, 38), (4, 12, 32), (5, 12, 30)]
+        );
+        assert_eq!(
+            issues[0].message,
+            "Handle the \"Result\" of this call instead of discarding it."
+        );
+    }
+
+    #[test]
+    fn test_return_type() {
+        assert_eq!(
+            return_type("pub fn write<P, C>(path: P, contents: C) -> Result<()>\nwhere"),
+            Some("Result<()>")
+        );
+        assert_eq!(
+            return_type("fn apply(f: impl Fn(u32) -> u32) -> Option<u32> where F: Copy"),
+            Some("Option<u32>")
+        );
+        assert_eq!(return_type("fn run(self)"), None);
+        assert_eq!(return_type("let x: Result<()>"), None);
+        assert!(is_result("io::Result<()>"));
+        assert!(is_result("std::result::Result<T, E>"));
+        assert!(!is_result("ResultSet"));
+        assert!(!is_result("Option<Result<()>>"));
+    }
+
+    #[test]
+    fn test_messages() {
+        let mut output = vec![];
+        write_message(&mut output, &json!({ "id": 1, "result": null })).unwrap();
+        write_message(&mut output, &json!({ "method": "$/progress" })).unwrap();
+        assert!(output.starts_with(b"Content-Length: 22\r\n\r\n{\"id\":1,\"result\":null}"));
+
+        let mut input = Cursor::new(output);
+        assert_eq!(
+            read_message(&mut input).unwrap(),
+            json!({ "id": 1, "result": null })
+        );
+        assert_eq!(
+            read_message(&mut input).unwrap(),
+            json!({ "method": "$/progress" })
+        );
+        assert!(matches!(
+            read_message(&mut input),
+            Err(AnalyzerError::GlobalError(message)) if message.ends_with("unexpected end of output")
+        ));
+        assert!(read_message(&mut Cursor::new(b"Content-Type: x\r\n\r\n{}".to_vec())).is_err());
+    }
+
+    #[test]
+    fn test_hover_text() {
+        assert_eq!(
+            hover_text(&json!({ "contents": { "kind": "plaintext", "value": "fn f() -> u32" } })),
+            Some("fn f() -> u32".to_string())
+        );
+        assert_eq!(
+            hover_text(
+                &json!({ "contents": ["std::fs", { "language": "rust", "value": "fn f()" }] })
+            ),
+            Some("std::fs\nfn f()".to_string())
+        );
+        assert_eq!(hover_text(&Value::Null), None);
+    }
+
+    #[test]
+    fn test_file_uri() {
+        assert_eq!(
+            file_uri(Path::new("/home/me/my project/src/lib.rs")),
+            "file:///home/me/my%20project/src/lib.rs"
+        );
+        assert_eq!(
+            file_uri(Path::new("C:\\work\\src\\main.rs")),
+            "file:///C:/work/src/main.rs"
+        );
+    }
+
+    #[test]
+    fn test_missing_settings() {
+        let tree = parse_rust_code("fn main() {}").unwrap();
+        assert_eq!(
+            find_type_aware_issues(&tree, "fn main() {}", &HashMap::new(), &[]).unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_missing_server() {
+        let tree = parse_rust_code("fn main() {}").unwrap();
+        let parameters = HashMap::from([
+            (
+                "rust_analyzer".to_string(),
+                "missing-rust-analyzer".to_string(),
+            ),
+            (
+                "project_dir".to_string(),
+                std::env::temp_dir().to_string_lossy().to_string(),
+            ),
+            ("file_path".to_string(), "src/main.rs".to_string()),
+        ]);
+        assert!(matches!(
+            find_type_aware_issues(&tree, "fn main() {}", &parameters, &[]),
+            Err(AnalyzerError::FileError(message)) if message.starts_with("failed to run missing-rust-analyzer")
+        ));
+        // The server is not started again for the following files
+        assert_eq!(
+            find_type_aware_issues(&tree, "fn main() {}", &parameters, &[]).unwrap(),
+            vec![]
+        );
+    }
+}
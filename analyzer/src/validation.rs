@@ -0,0 +1,262 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{analyze::AnalysisWarning, analyze::BorrowedOutput, tree::SonarLocation};
+
+/// Lengths of the lines of a file, in UTF-16 code units as the columns of `SonarLocation`.
+struct LineLengths(Vec<usize>);
+
+/// Outcome of the validation of a location.
+#[derive(Debug, PartialEq, Eq)]
+enum Validation {
+    Valid,
+    /// The location exceeded the end of its lines, and was moved to their ends.
+    Clamped,
+    /// The location starts outside of the file, or ends before it starts.
+    Invalid,
+}
+
+impl LineLengths {
+    fn new(source_code: &str) -> Self {
+        // A file ending with a line terminator ends with an empty line, as for SonarQube
+        LineLengths(
+            source_code
+                .split('\n')
+                .map(|line| {
+                    line.strip_suffix('\r')
+                        .unwrap_or(line)
+                        .encode_utf16()
+                        .count()
+                })
+                .collect(),
+        )
+    }
+
+    fn validate(&self, location: &mut SonarLocation) -> Validation {
+        let lines = self.0.len();
+        if location.start_line == 0 || location.start_line > lines || location.end_line == 0 {
+            return Validation::Invalid;
+        }
+        let mut validation = Validation::Valid;
+        if location.end_line > lines {
+            location.end_line = lines;
+            location.end_column = self.0[lines - 1];
+            validation = Validation::Clamped;
+        }
+        let start_length = self.0[location.start_line - 1];
+        if location.start_column > start_length {
+            location.start_column = start_length;
+            validation = Validation::Clamped;
+        }
+        let end_length = self.0[location.end_line - 1];
+        if location.end_column > end_length {
+            location.end_column = end_length;
+            validation = Validation::Clamped;
+        }
+        if (location.start_line, location.start_column) > (location.end_line, location.end_column) {
+            return Validation::Invalid;
+        }
+        validation
+    }
+}
+
+/// Counts of the clamped and dropped locations.
+#[derive(Default)]
+struct Counts {
+    clamped: usize,
+    dropped: usize,
+}
+
+impl Counts {
+    /// Validates the location, returns whether it is kept.
+    fn keep(&mut self, lines: &LineLengths, location: &mut SonarLocation) -> bool {
+        match lines.validate(location) {
+            Validation::Valid => true,
+            Validation::Clamped => {
+                self.clamped += 1;
+                true
+            }
+            Validation::Invalid => {
+                self.dropped += 1;
+                false
+            }
+        }
+    }
+}
+
+/// Checks the locations of the highlighting, the symbols, the CPD tokens and the issues against the lines of the file.
+///
+/// The plugin rejects all the results of a file for a single location outside of the file, so a location exceeding
+/// the end of its lines is clamped to them, and a location starting outside of the file or ending before its start is
+/// dropped, with the result it belongs to: e.g. an issue for an invalid primary location, a quick fix for an invalid
+/// edit. Returns a warning when any location was clamped or dropped.
+pub fn validate_locations(
+    output: &mut BorrowedOutput<'_>,
+    source_code: &str,
+) -> Option<AnalysisWarning> {
+    let lines = LineLengths::new(source_code);
+    let mut counts = Counts::default();
+
+    output
+        .highlight_tokens
+        .retain_mut(|token| counts.keep(&lines, &mut token.location));
+    output
+        .cpd_tokens
+        .retain_mut(|token| counts.keep(&lines, &mut token.location));
+    output.symbols.retain_mut(|symbol| {
+        symbol
+            .references
+            .retain_mut(|reference| counts.keep(&lines, reference));
+        counts.keep(&lines, &mut symbol.declaration)
+    });
+    output.issues.retain_mut(|issue| {
+        issue.secondary_locations.retain_mut(|secondary| {
+            // The locations in other files are checked by the project-level rules
+            secondary.file.is_some() || counts.keep(&lines, &mut secondary.location)
+        });
+        issue.quick_fixes.retain_mut(|quick_fix| {
+            let mut valid = true;
+            for edit in &mut quick_fix.edits {
+                valid &= counts.keep(&lines, &mut edit.location);
+            }
+            valid
+        });
+        counts.keep(&lines, &mut issue.location)
+    });
+
+    (counts.clamped > 0 || counts.dropped > 0).then_some(AnalysisWarning::InvalidLocations {
+        clamped: counts.clamped,
+        dropped: counts.dropped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        issue::{Issue, QuickFix, SecondaryLocation, TextEdit},
+        visitors::highlight::{HighlightToken, HighlightTokenType},
+    };
+
+    fn location(start: (usize, usize), end: (usize, usize)) -> SonarLocation {
+        SonarLocation {
+            start_line: start.0,
+            start_column: start.1,
+            end_line: end.0,
+            end_column: end.1,
+        }
+    }
+
+    fn token(start: (usize, usize), end: (usize, usize)) -> HighlightToken {
+        HighlightToken {
+            token_type: HighlightTokenType::Keyword,
+            location: location(start, end),
+        }
+    }
+
+    fn issue(primary: SonarLocation, secondary: SonarLocation, edit: SonarLocation) -> Issue {
+        Issue {
+            rule_key: "S1".to_string(),
+            message: "message".to_string(),
+            location: primary,
+            secondary_locations: vec![SecondaryLocation {
+                message: "secondary".to_string(),
+                location: secondary,
+                file: None,
+            }],
+            severity: None,
+            quick_fixes: vec![QuickFix {
+                message: "fix".to_string(),
+                edits: vec![TextEdit {
+                    location: edit,
+                    text: String::new(),
+                }],
+            }],
+            fingerprint: None,
+            hotspot: None,
+        }
+    }
+
+    #[test]
+    fn test_validate_locations() {
+        // The columns count UTF-16 code units, the line terminators are not part of the lines
+        let source_code = "fn ©() {}\r\nstruct S;\n";
+        let mut output = BorrowedOutput {
+            highlight_tokens: vec![
+                token((1, 0), (1, 2)),
+                token((1, 4), (1, 12)),
+                token((2, 0), (3, 0)),
+                token((2, 9), (4, 1)),
+                token((4, 0), (4, 1)),
+                token((2, 5), (2, 2)),
+            ],
+            issues: vec![
+                issue(
+                    location((1, 0), (1, 2)),
+                    location((2, 0), (2, 12)),
+                    location((1, 9), (1, 9)),
+                ),
+                issue(
+                    location((1, 0), (1, 2)),
+                    location((5, 0), (5, 1)),
+                    location((0, 0), (1, 0)),
+                ),
+                issue(
+                    location((7, 0), (7, 1)),
+                    location((1, 0), (1, 1)),
+                    location((1, 0), (1, 1)),
+                ),
+            ],
+            ..BorrowedOutput::default()
+        };
+
+        let warning = validate_locations(&mut output, source_code);
+        assert_eq!(
+            warning,
+            Some(AnalysisWarning::InvalidLocations {
+                clamped: 3,
+                dropped: 5,
+            })
+        );
+        assert_eq!(
+            output
+                .highlight_tokens
+                .iter()
+                .map(|token| token.location.clone())
+                .collect::<Vec<_>>(),
+            vec![
+                location((1, 0), (1, 2)),
+                location((1, 4), (1, 9)),
+                location((2, 0), (3, 0)),
+                location((2, 9), (3, 0)),
+            ]
+        );
+        assert_eq!(output.issues.len(), 2);
+        assert_eq!(
+            output.issues[0].secondary_locations[0].location,
+            location((2, 0), (2, 9))
+        );
+        assert_eq!(output.issues[0].quick_fixes.len(), 1);
+        assert!(output.issues[1].secondary_locations.is_empty());
+        assert!(output.issues[1].quick_fixes.is_empty());
+
+        let mut output = BorrowedOutput {
+            highlight_tokens: vec![token((1, 0), (1, 2))],
+            ..BorrowedOutput::default()
+        };
+        assert_eq!(validate_locations(&mut output, source_code), None);
+    }
+}
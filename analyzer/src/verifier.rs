@@ -0,0 +1,530 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::Issue,
+    rules::rule::Rule,
+    tree::{parse_rust_code, SonarLocation},
+};
+
+/// An issue expected by the annotations of a test file, see `expected_issues`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExpectedIssue {
+    /// 1-based line of the issue.
+    pub line: usize,
+    pub message: Option<String>,
+    /// Start and end columns given by a `^^^` line, 0-based and counting UTF-16 code units like `SonarLocation`.
+    pub columns: Option<(usize, usize)>,
+    pub secondary_locations: Vec<ExpectedSecondaryLocation>,
+}
+
+/// A secondary location expected by a `^^^<` line.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ExpectedSecondaryLocation {
+    pub line: usize,
+    pub columns: (usize, usize),
+    pub message: Option<String>,
+}
+
+/// Reads the issues expected by the annotations of a Rust test file:
+///
+/// - `// Noncompliant` expects an issue on its line, or on a relative line with `@+1`/`@-1` or on an absolute line
+///   with `@12`. It is followed by the number of expected issues (`// Noncompliant 2`), or by their messages
+///   (`// Noncompliant {{first}} {{second}}`), or neither for a single issue with any message.
+/// - A comment made of carets (`//  ^^^^`) gives the columns of an issue on the nearest line above which is not such a
+///   comment. It applies to the first issue of the line without columns, or expects a new issue, and it may be
+///   followed by the message of the issue.
+/// - A comment made of carets followed by `<` is a secondary location of the issue expected last, and it may be
+///   followed by the message of the location.
+pub fn expected_issues(source_code: &str) -> Result<Vec<ExpectedIssue>, String> {
+    let mut expected: Vec<ExpectedIssue> = vec![];
+    let mut code_line = None;
+    for (index, line) in source_code.lines().enumerate() {
+        let line_number = index + 1;
+        if let Some((start, end, rest)) = carets(line) {
+            let Some(target) = code_line else {
+                return Err(format!("line {}: no line above the carets", line_number));
+            };
+            let columns = (utf16_len(&line[..start]), utf16_len(&line[..end]));
+            if let Some(rest) = rest.strip_prefix('<') {
+                let message = single_message(rest, line_number)?;
+                let Some(primary) = expected.last_mut() else {
+                    return Err(format!(
+                        "line {}: no issue for the secondary location",
+                        line_number
+                    ));
+                };
+                primary.secondary_locations.push(ExpectedSecondaryLocation {
+                    line: target,
+                    columns,
+                    message,
+                });
+            } else {
+                let message = single_message(rest, line_number)?;
+                match expected
+                    .iter_mut()
+                    .find(|issue| issue.line == target && issue.columns.is_none())
+                {
+                    Some(issue) => {
+                        issue.columns = Some(columns);
+                        if message.is_some() {
+                            issue.message = message;
+                        }
+                    }
+                    None => expected.push(ExpectedIssue {
+                        line: target,
+                        message,
+                        columns: Some(columns),
+                        secondary_locations: vec![],
+                    }),
+                }
+            }
+            continue;
+        }
+        code_line = Some(line_number);
+        if let Some(position) = line.find("// Noncompliant") {
+            expected.extend(noncompliant(
+                &line[position + "// Noncompliant".len()..],
+                line_number,
+            )?);
+        }
+    }
+    Ok(expected)
+}
+
+/// Runs the rule on the annotated source code and compares its issues with the expected ones, see `expected_issues`.
+///
+/// The error lists the differences, one per line. Messages and columns are only compared when they are expected, and
+/// secondary locations when at least one of them is expected.
+pub fn verify(rule: &dyn Rule, source_code: &str) -> Result<(), String> {
+    let expected = expected_issues(source_code)?;
+    let tree = parse_rust_code(source_code).map_err(|err| err.to_string())?;
+    let actual = rule
+        .check(&tree, source_code)
+        .map_err(|err| err.to_string())?;
+    let differences = differences(expected, actual);
+    if differences.is_empty() {
+        Ok(())
+    } else {
+        Err(differences.join("\n"))
+    }
+}
+
+/// Panics with the differences reported by `verify`.
+pub fn assert_issues(rule: &dyn Rule, source_code: &str) {
+    if let Err(differences) = verify(rule, source_code) {
+        panic!("unexpected issues of rule {}:\n{}", rule.key(), differences);
+    }
+}
+
+fn differences(mut expected: Vec<ExpectedIssue>, mut actual: Vec<Issue>) -> Vec<String> {
+    expected.sort_by_key(|issue| (issue.line, issue.columns));
+    actual.sort_by(|a, b| a.location.cmp(&b.location));
+
+    // Exact matches first, so that an issue with a wrong message does not take the place of a right one
+    let mut matched = vec![None; expected.len()];
+    let mut used = vec![false; actual.len()];
+    for (index, issue) in expected.iter().enumerate() {
+        if let Some(position) = (0..actual.len())
+            .find(|&position| !used[position] && compare(issue, &actual[position]).is_empty())
+        {
+            matched[index] = Some(position);
+            used[position] = true;
+        }
+    }
+
+    let mut differences = vec![];
+    for (index, issue) in expected.iter().enumerate() {
+        if matched[index].is_some() {
+            continue;
+        }
+        match (0..actual.len())
+            .find(|&position| !used[position] && actual[position].location.start_line == issue.line)
+        {
+            Some(position) => {
+                used[position] = true;
+                differences.extend(compare(issue, &actual[position]));
+            }
+            None => differences.push(match &issue.message {
+                Some(message) => format!("line {}: missing issue '{}'", issue.line, message),
+                None => format!("line {}: missing issue", issue.line),
+            }),
+        }
+    }
+    for (position, issue) in actual.iter().enumerate() {
+        if !used[position] {
+            differences.push(format!(
+                "line {}: unexpected issue '{}' at {}",
+                issue.location.start_line,
+                issue.message,
+                columns(&issue.location)
+            ));
+        }
+    }
+    differences.sort_by_key(|difference| line_of(difference));
+    differences
+}
+
+fn compare(expected: &ExpectedIssue, actual: &Issue) -> Vec<String> {
+    let line = expected.line;
+    if actual.location.start_line != line {
+        return vec![format!(
+            "line {}: expected on line {} but was on line {}",
+            line, line, actual.location.start_line
+        )];
+    }
+    let mut differences = vec![];
+    if let Some(message) = &expected.message {
+        if *message != actual.message {
+            differences.push(format!(
+                "line {}: expected message '{}' but was '{}'",
+                line, message, actual.message
+            ));
+        }
+    }
+    if let Some(columns) = expected.columns {
+        if !columns_match(columns, &actual.location) {
+            differences.push(format!(
+                "line {}: expected columns {}..{} but was {}",
+                line,
+                columns.0,
+                columns.1,
+                self::columns(&actual.location)
+            ));
+        }
+    }
+    if !expected.secondary_locations.is_empty() {
+        let matches = expected.secondary_locations.len() == actual.secondary_locations.len()
+            && expected
+                .secondary_locations
+                .iter()
+                .zip(&actual.secondary_locations)
+                .all(|(expected, actual)| {
+                    actual.location.start_line == expected.line
+                        && columns_match(expected.columns, &actual.location)
+                        && expected
+                            .message
+                            .as_ref()
+                            .is_none_or(|message| *message == actual.message)
+                });
+        if !matches {
+            let expected = expected
+                .secondary_locations
+                .iter()
+                .map(|location| {
+                    format!(
+                        "{}:{}..{}",
+                        location.line, location.columns.0, location.columns.1
+                    )
+                })
+                .collect::<Vec<_>>();
+            let actual = actual
+                .secondary_locations
+                .iter()
+                .map(|location| {
+                    format!(
+                        "{}:{}..{}",
+                        location.location.start_line,
+                        location.location.start_column,
+                        location.location.end_column
+                    )
+                })
+                .collect::<Vec<_>>();
+            differences.push(format!(
+                "line {}: expected secondary locations [{}] but were [{}]",
+                line,
+                expected.join(", "),
+                actual.join(", ")
+            ));
+        }
+    }
+    differences
+}
+
+/// The end column is only compared for locations ending on their start line, carets covering a single line.
+fn columns_match(columns: (usize, usize), location: &SonarLocation) -> bool {
+    location.start_column == columns.0
+        && (location.end_line != location.start_line || location.end_column == columns.1)
+}
+
+fn columns(location: &SonarLocation) -> String {
+    if location.end_line == location.start_line {
+        format!("columns {}..{}", location.start_column, location.end_column)
+    } else {
+        format!(
+            "column {} to line {} column {}",
+            location.start_column, location.end_line, location.end_column
+        )
+    }
+}
+
+fn line_of(difference: &str) -> usize {
+    difference
+        .strip_prefix("line ")
+        .and_then(|rest| rest.split(':').next())
+        .and_then(|line| line.parse().ok())
+        .unwrap_or_default()
+}
+
+/// Returns the byte range of the carets of a comment made of carets, and the text following them.
+fn carets(line: &str) -> Option<(usize, usize, &str)> {
+    let comment = line.trim_start().strip_prefix("//")?;
+    let start = line.len() - comment.trim_start().len();
+    let end = start
+        + line[start..]
+            .find(|c| c != '^')
+            .unwrap_or(line.len() - start);
+    if end == start {
+        return None;
+    }
+    Some((start, end, &line[end..]))
+}
+
+fn noncompliant(annotation: &str, line: usize) -> Result<Vec<ExpectedIssue>, String> {
+    let mut rest = annotation.trim_start();
+    let mut target = line;
+    if let Some(offset) = rest.strip_prefix('@') {
+        let (value, remaining) = split_number(offset.trim_start_matches(['+', '-']));
+        let value: usize = value
+            .parse()
+            .map_err(|_| format!("line {}: invalid line of the issue", line))?;
+        target = if offset.starts_with('+') {
+            line + value
+        } else if offset.starts_with('-') {
+            line.checked_sub(value)
+                .filter(|&target| target > 0)
+                .ok_or_else(|| format!("line {}: invalid line of the issue", line))?
+        } else if value > 0 {
+            value
+        } else {
+            return Err(format!("line {}: invalid line of the issue", line));
+        };
+        rest = remaining.trim_start();
+    }
+    let (count, remaining) = split_number(rest);
+    let count = if count.is_empty() {
+        None
+    } else {
+        rest = remaining.trim_start();
+        Some(count.parse::<usize>().unwrap_or_default())
+    };
+    let messages = messages(rest, line)?;
+    let count = count.unwrap_or(1).max(messages.len());
+    Ok((0..count)
+        .map(|index| ExpectedIssue {
+            line: target,
+            message: messages.get(index).cloned(),
+            columns: None,
+            secondary_locations: vec![],
+        })
+        .collect())
+}
+
+fn split_number(text: &str) -> (&str, &str) {
+    text.split_at(
+        text.find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(text.len()),
+    )
+}
+
+fn messages(mut text: &str, line: usize) -> Result<Vec<String>, String> {
+    let mut messages = vec![];
+    while let Some(start) = text.find("{{") {
+        let end = text[start..]
+            .find("}}")
+            .ok_or_else(|| format!("line {}: unterminated message", line))?;
+        messages.push(text[start + 2..start + end].to_string());
+        text = &text[start + end + 2..];
+    }
+    Ok(messages)
+}
+
+fn single_message(text: &str, line: usize) -> Result<Option<String>, String> {
+    let mut messages = messages(text, line)?;
+    if messages.len() > 1 {
+        return Err(format!(
+            "line {}: several messages for a single location",
+            line
+        ));
+    }
+    Ok(messages.pop())
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        issue::SecondaryLocation,
+        rule_description::RuleDescription,
+        rules::rule::RuleTracer,
+        tree::{AnalyzerError, NodeIterator, TreeSitterLocation},
+    };
+    use tree_sitter::Tree;
+
+    /// Flags the functions named 'bad' on their name, with secondary locations on their parameters.
+    struct BadFunctionCheck;
+
+    impl Rule for BadFunctionCheck {
+        fn key(&self) -> &'static str {
+            "Bad"
+        }
+
+        fn description(&self) -> RuleDescription {
+            RuleDescription {
+                key: "Bad",
+                title: "Functions should not be bad",
+                markdown: "",
+            }
+        }
+
+        fn check_traced(
+            &self,
+            tree: &Tree,
+            source_code: &str,
+            _tracer: &mut dyn RuleTracer,
+        ) -> Result<Vec<Issue>, AnalyzerError> {
+            Ok(
+                NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item")
+                    .filter_map(|function| function.child_by_field_name("name"))
+                    .filter(|name| &source_code[name.byte_range()] == "bad")
+                    .map(|name| {
+                        let parameters = name.next_named_sibling().unwrap();
+                        let mut cursor = parameters.walk();
+                        Issue {
+                            rule_key: "Bad".to_string(),
+                            message: "Rename this function.".to_string(),
+                            location: TreeSitterLocation::from_tree_sitter_node(name)
+                                .to_sonar_location(source_code),
+                            secondary_locations: parameters
+                                .named_children(&mut cursor)
+                                .map(|parameter| SecondaryLocation {
+                                    message: "parameter".to_string(),
+                                    location: TreeSitterLocation::from_tree_sitter_node(parameter)
+                                        .to_sonar_location(source_code),
+                                    file: None,
+                                })
+                                .collect(),
+                            severity: None,
+                            quick_fixes: vec![],
+                            fingerprint: None,
+                            hotspot: None,
+                        }
+                    })
+                    .collect(),
+            )
+        }
+    }
+
+    #[test]
+    fn test_expected_issues() {
+        let source_code = r#"
+fn a() {} // Noncompliant
+// Noncompliant@+1 2
+fn b() {}
+fn c() {} // Noncompliant {{first}} {{second}}
+fn d(x: i32) {} // Noncompliant@2
+// ^^^^^^^^^^^^ {{whole}}
+//   ^^^^^^< {{parameter}}
+  fn e() {}
+//   ^
+"#;
+        let issue = |line, message: Option<&str>, columns| ExpectedIssue {
+            line,
+            message: message.map(str::to_string),
+            columns,
+            secondary_locations: vec![],
+        };
+        assert_eq!(
+            expected_issues(source_code).unwrap(),
+            vec![
+                issue(2, None, None),
+                issue(4, None, None),
+                issue(4, None, None),
+                issue(5, Some("first"), None),
+                issue(5, Some("second"), None),
+                issue(2, None, None),
+                ExpectedIssue {
+                    secondary_locations: vec![ExpectedSecondaryLocation {
+                        line: 6,
+                        columns: (5, 11),
+                        message: Some("parameter".to_string()),
+                    }],
+                    ..issue(6, Some("whole"), Some((3, 15)))
+                },
+                issue(9, None, Some((5, 6))),
+            ]
+        );
+        assert_eq!(
+            expected_issues("// ^^^\nfn a() {}\n"),
+            Err("line 1: no line above the carets".to_string())
+        );
+        assert_eq!(
+            expected_issues("fn a() {}\n// ^^^<\n"),
+            Err("line 2: no issue for the secondary location".to_string())
+        );
+        assert_eq!(
+            expected_issues("fn a() {} // Noncompliant {{unterminated\n"),
+            Err("line 1: unterminated message".to_string())
+        );
+        assert_eq!(
+            expected_issues("fn a() {} // Noncompliant@0\n"),
+            Err("line 1: invalid line of the issue".to_string())
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_issues(
+            &BadFunctionCheck,
+            r#"
+fn good(x: i32) {}
+fn bad(x: i32, y: i32) {} // Noncompliant {{Rename this function.}}
+// ^^^
+//     ^^^^^^< {{parameter}}
+//             ^^^^^^<
+mod m {
+    // Noncompliant@+1
+    fn bad() {}
+}
+"#,
+        );
+
+        assert_eq!(
+            verify(
+                &BadFunctionCheck,
+                r#"
+fn bad(x: i32) {} // Noncompliant {{Rename it.}}
+//  ^^
+//      ^^^^^<
+fn good() {} // Noncompliant
+fn bad() {}
+"#
+            ),
+            Err([
+                "line 2: expected message 'Rename it.' but was 'Rename this function.'",
+                "line 2: expected columns 4..6 but was columns 3..6",
+                "line 2: expected secondary locations [2:8..13] but were [2:7..13]",
+                "line 5: missing issue",
+                "line 6: unexpected issue 'Rename this function.' at columns 3..6",
+            ]
+            .join("\n"))
+        );
+    }
+}
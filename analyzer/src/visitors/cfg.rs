@@ -0,0 +1,211 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::tree::{child_of_kind, SonarLocation, TreeSitterLocation};
+use tree_sitter::{Node, Tree};
+
+/// Part of a file which is only compiled in some configurations, i.e. an item annotated with `#[cfg(...)]`.
+///
+/// The parser sees the code of all configurations, so the metrics and the rules cover every cfg-gated item. The
+/// regions tell which configuration an issue belongs to, see `configuration`.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct CfgRegion {
+    /// Location of the item, including its attributes, or of the whole module for inner attributes.
+    pub location: SonarLocation,
+    /// Configuration predicate of the attribute, e.g. 'windows' or 'feature = "serde"'.
+    pub predicate: String,
+}
+
+/// Computes the cfg regions of the file, outer regions first.
+pub fn calculate_cfg_regions(tree: &Tree, source_code: &str) -> Vec<CfgRegion> {
+    let mut regions = vec![];
+    let mut stack = vec![tree.root_node()];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        let mut pending: Vec<(Node<'_>, String)> = vec![];
+        for child in node.children(&mut cursor) {
+            if let Some(predicate) = cfg_predicate(child, source_code) {
+                if child.kind() == "inner_attribute_item" {
+                    // Inner attributes apply to the enclosing module, or to the whole file
+                    let module = match node.parent() {
+                        Some(parent) if node.kind() == "declaration_list" => parent,
+                        _ => node,
+                    };
+                    regions.push(region(module, module, predicate, source_code));
+                } else {
+                    pending.push((child, predicate));
+                }
+            } else if child.kind() == "attribute_item" || child.is_extra() {
+                // Other attributes and comments may stand between the cfg attribute and the item
+            } else {
+                for (attribute, predicate) in pending.drain(..) {
+                    regions.push(region(attribute, child, predicate, source_code));
+                }
+                stack.push(child);
+            }
+        }
+    }
+    regions.sort();
+    regions
+}
+
+/// Configuration of the code at the given location: the predicate of the innermost cfg region, or the conjunction of
+/// the predicates of all nested regions. `None` when the code is compiled in all configurations.
+pub fn configuration(regions: &[CfgRegion], location: &SonarLocation) -> Option<String> {
+    let start = (location.start_line, location.start_column);
+    let predicates: Vec<&str> = regions
+        .iter()
+        .filter(|region| {
+            let region_start = (region.location.start_line, region.location.start_column);
+            let region_end = (region.location.end_line, region.location.end_column);
+            region_start <= start && start < region_end
+        })
+        .map(|region| region.predicate.as_str())
+        .collect();
+
+    match predicates.as_slice() {
+        [] => None,
+        [predicate] => Some(predicate.to_string()),
+        _ => Some(format!("all({})", predicates.join(", "))),
+    }
+}
+
+/// Region from the start of the first node to the end of the item.
+fn region(first: Node<'_>, item: Node<'_>, predicate: String, source_code: &str) -> CfgRegion {
+    let location = TreeSitterLocation {
+        start_byte: first.start_byte(),
+        end_byte: item.end_byte(),
+        start_position: first.start_position(),
+        end_position: item.end_position(),
+    };
+    CfgRegion {
+        location: location.to_sonar_location(source_code),
+        predicate,
+    }
+}
+
+/// Predicate of a `#[cfg(...)]` or `#![cfg(...)]` attribute.
+fn cfg_predicate(node: Node<'_>, source_code: &str) -> Option<String> {
+    if !matches!(node.kind(), "attribute_item" | "inner_attribute_item") {
+        return None;
+    }
+
+    let attribute = child_of_kind(node, "attribute")?;
+    let name = attribute.named_child(0)?;
+    if &source_code[name.byte_range()] != "cfg" {
+        return None;
+    }
+    let arguments = attribute.child_by_field_name("arguments")?;
+    let text = &source_code[arguments.byte_range()];
+    Some(text[1..text.len() - 1].trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    fn location(
+        start_line: usize,
+        start_column: usize,
+        end_line: usize,
+        end_column: usize,
+    ) -> SonarLocation {
+        SonarLocation {
+            start_line,
+            start_column,
+            end_line,
+            end_column,
+        }
+    }
+
+    #[test]
+    fn test_cfg_regions() {
+        let source_code = r#"
+#[cfg(windows)]
+fn path() -> &'static str { "C:\\" }
+
+#[cfg(not(windows))]
+#[inline]
+fn path() -> &'static str { "/" }
+
+#[cfg(feature = "serde")]
+mod serde {
+    #[cfg(unix)]
+    fn unix() {}
+}
+
+mod os {
+    #![cfg(target_os = "linux")]
+}
+
+#[cfg_attr(test, derive(Debug))]
+struct S;
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+
+        assert_eq!(
+            calculate_cfg_regions(&tree, source_code),
+            vec![
+                CfgRegion {
+                    location: location(2, 0, 3, 36),
+                    predicate: "windows".to_string(),
+                },
+                CfgRegion {
+                    location: location(5, 0, 7, 33),
+                    predicate: "not(windows)".to_string(),
+                },
+                CfgRegion {
+                    location: location(9, 0, 13, 1),
+                    predicate: "feature = \"serde\"".to_string(),
+                },
+                CfgRegion {
+                    location: location(11, 4, 12, 16),
+                    predicate: "unix".to_string(),
+                },
+                CfgRegion {
+                    location: location(15, 0, 17, 1),
+                    predicate: "target_os = \"linux\"".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_configuration() {
+        let source_code = r#"#![cfg(unix)]
+fn a() {}
+
+#[cfg(feature = "x")]
+fn b() {
+    #[cfg(debug_assertions)]
+    fn c() {}
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let regions = calculate_cfg_regions(&tree, source_code);
+
+        assert_eq!(
+            configuration(&regions, &location(2, 3, 2, 4)),
+            Some("unix".to_string())
+        );
+        assert_eq!(
+            configuration(&regions, &location(7, 7, 7, 8)),
+            Some("all(unix, feature = \"x\", debug_assertions)".to_string())
+        );
+        assert_eq!(configuration(&[], &location(2, 3, 2, 4)), None);
+    }
+}
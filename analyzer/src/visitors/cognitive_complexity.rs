@@ -14,13 +14,14 @@
  * You should have received a copy of the Sonar Source-Available License
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
-use crate::tree::{walk_tree, AnalyzerError, NodeVisitor, TreeSitterLocation};
+use crate::tree::{
+    child_of_kind, is_closure, walk_tree, AnalyzerError, NodeVisitor, TreeSitterLocation,
+};
 use std::collections::HashSet;
 use tree_sitter::{Node, Tree};
 
-#[allow(dead_code)] // Location is currently only used in tests, so we allow dead code
 pub struct Increment {
-    pub location: TreeSitterLocation,
+    pub(crate) location: TreeSitterLocation,
     pub nesting: i32,
 }
 
@@ -132,7 +133,14 @@ impl NodeVisitor for ComplexityVisitor {
                     self.visited_operators.insert(operator.id());
                 }
             }
-            "closure_expression" => {
+            "let_declaration" if is_let_else(node) => {
+                // 'let ... else' is a conditional branch, like the if expression it replaces
+                if let Some(else_keyword) = child_of_kind(node, "else") {
+                    self.increment_with_nesting(else_keyword, self.current_nesting);
+                }
+                self.current_nesting += 1;
+            }
+            "closure_expression" | "async_block" if is_closure(node) => {
                 self.current_nesting += 1;
             }
             // TODO SKUNK-29: Check calls and handle recursion if/when we are able to reliably infer the called function
@@ -144,10 +152,8 @@ impl NodeVisitor for ComplexityVisitor {
 
     fn exit_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
         match node.kind() {
-            "if_expression" => {
-                if !is_else_if(node) {
-                    self.current_nesting -= 1;
-                }
+            "if_expression" if !is_else_if(node) => {
+                self.current_nesting -= 1;
             }
             "while_expression" | "loop_expression" | "for_expression" | "match_expression" => {
                 self.current_nesting -= 1;
@@ -158,7 +164,10 @@ impl NodeVisitor for ComplexityVisitor {
                     self.current_nesting -= 1;
                 }
             }
-            "closure_expression" => {
+            "let_declaration" if is_let_else(node) => {
+                self.current_nesting -= 1;
+            }
+            "closure_expression" | "async_block" if is_closure(node) => {
                 self.current_nesting -= 1;
             }
             _ => {}
@@ -168,6 +177,10 @@ impl NodeVisitor for ComplexityVisitor {
     }
 }
 
+pub(crate) fn is_let_else(node: Node<'_>) -> bool {
+    node.kind() == "let_declaration" && node.child_by_field_name("alternative").is_some()
+}
+
 fn is_else_if(node: Node<'_>) -> bool {
     if let Some(parent) = node.parent() {
         if parent.kind() == "else_clause" && parent.named_child(0) == Some(node) {
@@ -459,6 +472,37 @@ match x { // +1
         );
     }
 
+    #[test]
+    fn test_let_else() {
+        check_complexity(
+            r#"
+    let Some(x) = y else { // +1
+        return;
+    };
+    if x { // +1
+        let Ok(z) = x.parse() else { // +2
+            if verbose { // +3
+                log();
+            }
+            return;
+        };
+    }
+"#,
+        );
+    }
+
+    #[test]
+    fn test_async_closures() {
+        check_complexity(
+            r#"
+    spawn(async move || {
+        if a { // +2
+        }
+    });
+"#,
+        );
+    }
+
     #[test]
     fn complex_nested_functions() {
         check_complexity(
@@ -539,8 +583,8 @@ match x { // +1
                 let text = source_code[capture.node.start_byte()..capture.node.end_byte()]
                     .trim_start_matches("//")
                     .trim();
-                if text.starts_with("+") {
-                    text[1..]
+                if let Some(increment) = text.strip_prefix("+") {
+                    increment
                         .parse::<i32>()
                         .map(|increment| {
                             increments.push(IncrementLines {
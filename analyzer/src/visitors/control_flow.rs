@@ -0,0 +1,538 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use std::collections::HashSet;
+use tree_sitter::Node;
+
+/// Sequence of statements and expressions always executed in order.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct BasicBlock<'a> {
+    /// Statements and expressions without branches, in evaluation order. An element containing a branch is preceded by
+    /// the blocks of the branch, e.g. `let x = if a { b } else { c };` follows the blocks of the `if`.
+    pub elements: Vec<Node<'a>>,
+    /// Expression choosing among the successors, or jumping, e.g. an `if`, a `?` or a `break`.
+    pub terminator: Option<Node<'a>>,
+    /// Indices of the blocks which can follow, in `ControlFlowGraph::blocks`.
+    pub successors: Vec<usize>,
+}
+
+/// Control-flow graph of the body of a function or a closure.
+///
+/// The branches are the `if`, `match`, loops, `?` operators, jumps and the diverging macros, e.g. `panic!`. The
+/// operands of `&&` and `||` are not branches. The code after a jump is in blocks without predecessor.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ControlFlowGraph<'a> {
+    pub blocks: Vec<BasicBlock<'a>>,
+    pub entry: usize,
+    /// Empty block ending the function, following the returns and the diverging expressions.
+    pub exit: usize,
+}
+
+impl<'a> ControlFlowGraph<'a> {
+    /// Builds the graph of a function item or a closure expression.
+    pub fn build(function: Node<'a>, source_code: &'a str) -> Self {
+        let mut builder = Builder {
+            source_code,
+            blocks: vec![BasicBlock::default(), BasicBlock::default()],
+            current: 0,
+            targets: vec![],
+        };
+        if let Some(body) = function.child_by_field_name("body") {
+            builder.build(body);
+        }
+        builder.link(builder.current, EXIT);
+        builder.compact()
+    }
+
+    /// Indices of the blocks preceding the given block.
+    pub fn predecessors(&self, block: usize) -> Vec<usize> {
+        (0..self.blocks.len())
+            .filter(|predecessor| self.blocks[*predecessor].successors.contains(&block))
+            .collect()
+    }
+
+    /// Whether each block can be reached from the entry.
+    pub fn reachable(&self) -> Vec<bool> {
+        let mut reachable = vec![false; self.blocks.len()];
+        let mut stack = vec![self.entry];
+        while let Some(block) = stack.pop() {
+            if !reachable[block] {
+                reachable[block] = true;
+                stack.extend(&self.blocks[block].successors);
+            }
+        }
+        reachable
+    }
+
+    /// First node of each unreachable block, e.g. the statement following a `return`.
+    pub fn unreachable_nodes(&self) -> Vec<Node<'a>> {
+        self.reachable()
+            .into_iter()
+            .zip(&self.blocks)
+            .filter(|(reachable, _)| !reachable)
+            .filter_map(|(_, block)| block.elements.first().copied().or(block.terminator))
+            .collect()
+    }
+}
+
+const ENTRY: usize = 0;
+const EXIT: usize = 1;
+
+/// Kinds of the nodes whose evaluation can branch.
+const BRANCHES: &[&str] = &[
+    "if_expression",
+    "match_expression",
+    "loop_expression",
+    "while_expression",
+    "for_expression",
+    "return_expression",
+    "break_expression",
+    "continue_expression",
+    "try_expression",
+];
+
+/// Macros which never return.
+const DIVERGING_MACROS: &[&str] = &["panic", "unreachable", "todo", "unimplemented"];
+
+/// Loop, or labeled block, which can be the target of a `break` or a `continue`.
+struct Target {
+    label: Option<String>,
+    break_block: usize,
+    /// `None` for a labeled block.
+    continue_block: Option<usize>,
+}
+
+struct Builder<'a> {
+    source_code: &'a str,
+    blocks: Vec<BasicBlock<'a>>,
+    current: usize,
+    targets: Vec<Target>,
+}
+
+impl<'a> Builder<'a> {
+    fn build(&mut self, node: Node<'a>) {
+        match node.kind() {
+            "block" => {
+                let label = self.label(node);
+                let after = label.is_some().then(|| self.new_block());
+                if let Some(after) = after {
+                    self.targets.push(Target {
+                        label,
+                        break_block: after,
+                        continue_block: None,
+                    });
+                }
+                self.build_children(node);
+                if let Some(after) = after {
+                    self.targets.pop();
+                    self.link(self.current, after);
+                    self.current = after;
+                }
+            }
+            "expression_statement" | "unsafe_block" | "parenthesized_expression" => {
+                self.build_children(node)
+            }
+            "if_expression" => {
+                if let Some(condition) = node.child_by_field_name("condition") {
+                    self.build(condition);
+                }
+                let after = self.new_block();
+                let branch = self.terminate(node);
+                let consequence = self.new_block();
+                self.link(branch, consequence);
+                self.current = consequence;
+                if let Some(consequence) = node.child_by_field_name("consequence") {
+                    self.build(consequence);
+                }
+                self.link(self.current, after);
+                match node.child_by_field_name("alternative") {
+                    Some(alternative) => {
+                        let alternative_block = self.new_block();
+                        self.link(branch, alternative_block);
+                        self.current = alternative_block;
+                        self.build_children(alternative);
+                        self.link(self.current, after);
+                    }
+                    None => self.link(branch, after),
+                }
+                self.current = after;
+            }
+            "match_expression" => {
+                if let Some(value) = node.child_by_field_name("value") {
+                    self.build(value);
+                }
+                let after = self.new_block();
+                let branch = self.terminate(node);
+                let arms = node
+                    .child_by_field_name("body")
+                    .map(|body| {
+                        let mut cursor = body.walk();
+                        let arms: Vec<Node<'a>> = body
+                            .named_children(&mut cursor)
+                            .filter(|arm| arm.kind() == "match_arm")
+                            .collect();
+                        arms
+                    })
+                    .unwrap_or_default();
+                for arm in arms {
+                    let arm_block = self.new_block();
+                    self.link(branch, arm_block);
+                    self.current = arm_block;
+                    if let Some(pattern) = arm.child_by_field_name("pattern") {
+                        self.blocks[arm_block].elements.push(pattern);
+                    }
+                    if let Some(value) = arm.child_by_field_name("value") {
+                        self.build(value);
+                    }
+                    self.link(self.current, after);
+                }
+                self.current = after;
+            }
+            "loop_expression" | "while_expression" | "for_expression" => {
+                if let Some(value) = node.child_by_field_name("value") {
+                    self.build(value);
+                }
+                let head = self.new_block();
+                self.link(self.current, head);
+                self.current = head;
+                if let Some(condition) = node.child_by_field_name("condition") {
+                    self.build(condition);
+                }
+                let after = self.new_block();
+                let branch = self.terminate(node);
+                if node.kind() != "loop_expression" {
+                    self.link(branch, after);
+                }
+                let body = self.new_block();
+                self.blocks[branch].successors.insert(0, body);
+                self.current = body;
+                self.targets.push(Target {
+                    label: self.label(node),
+                    break_block: after,
+                    continue_block: Some(head),
+                });
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.build(body);
+                }
+                self.targets.pop();
+                self.link(self.current, head);
+                self.current = after;
+            }
+            "return_expression" | "break_expression" | "continue_expression" => {
+                self.build_children(node);
+                let target = match node.kind() {
+                    "return_expression" => Some(EXIT),
+                    kind => {
+                        let label = self.label(node);
+                        self.targets
+                            .iter()
+                            .rev()
+                            .find(|target| match &label {
+                                Some(label) => target.label.as_ref() == Some(label),
+                                None => target.continue_block.is_some(),
+                            })
+                            .and_then(|target| match kind {
+                                "break_expression" => Some(target.break_block),
+                                _ => target.continue_block,
+                            })
+                    }
+                };
+                let jump = self.terminate(node);
+                if let Some(target) = target {
+                    self.link(jump, target);
+                }
+                self.current = self.new_block();
+            }
+            "try_expression" => {
+                self.build_children(node);
+                let branch = self.terminate(node);
+                let after = self.new_block();
+                self.link(branch, after);
+                self.link(branch, EXIT);
+                self.current = after;
+            }
+            "macro_invocation" if self.is_diverging(node) => {
+                let jump = self.terminate(node);
+                self.link(jump, EXIT);
+                self.current = self.new_block();
+            }
+            "line_comment" | "block_comment" | "label" => {}
+            "let_declaration" if node.child_by_field_name("alternative").is_some() => {
+                if let Some(value) = node.child_by_field_name("value") {
+                    self.build(value);
+                }
+                let branch = self.terminate(node);
+                let after = self.new_block();
+                let alternative = self.new_block();
+                self.link(branch, after);
+                self.link(branch, alternative);
+                self.current = alternative;
+                if let Some(alternative) = node.child_by_field_name("alternative") {
+                    self.build(alternative);
+                }
+                self.link(self.current, after);
+                self.current = after;
+            }
+            _ if self.contains_branch(node) => {
+                // The parts without branch are evaluated with the node
+                let mut cursor = node.walk();
+                let children: Vec<Node<'a>> = node.named_children(&mut cursor).collect();
+                for child in children {
+                    if self.contains_branch(child) {
+                        self.build(child);
+                    }
+                }
+                self.blocks[self.current].elements.push(node);
+            }
+            _ => self.blocks[self.current].elements.push(node),
+        }
+    }
+
+    fn build_children(&mut self, node: Node<'a>) {
+        let mut cursor = node.walk();
+        let children: Vec<Node<'a>> = node.named_children(&mut cursor).collect();
+        for child in children {
+            if !child.is_extra() {
+                self.build(child);
+            }
+        }
+    }
+
+    fn new_block(&mut self) -> usize {
+        self.blocks.push(BasicBlock::default());
+        self.blocks.len() - 1
+    }
+
+    /// Ends the current block with the given node, and returns the block.
+    fn terminate(&mut self, node: Node<'a>) -> usize {
+        self.blocks[self.current].terminator = Some(node);
+        self.current
+    }
+
+    fn link(&mut self, from: usize, to: usize) {
+        if !self.blocks[from].successors.contains(&to) {
+            self.blocks[from].successors.push(to);
+        }
+    }
+
+    fn label(&self, node: Node<'a>) -> Option<String> {
+        let mut cursor = node.walk();
+        let label = node
+            .children(&mut cursor)
+            .find(|child| child.kind() == "label")
+            .map(|label| self.source_code[label.byte_range()].to_string());
+        label
+    }
+
+    fn is_diverging(&self, node: Node<'a>) -> bool {
+        node.child_by_field_name("macro")
+            .map(|name| &self.source_code[name.byte_range()])
+            .is_some_and(|name| {
+                DIVERGING_MACROS.contains(&name.rsplit("::").next().unwrap_or(name))
+            })
+    }
+
+    /// Whether the evaluation of the node can branch, excluding the closures and nested items.
+    fn contains_branch(&self, node: Node<'a>) -> bool {
+        let mut stack = vec![node];
+        while let Some(node) = stack.pop() {
+            if BRANCHES.contains(&node.kind())
+                || node.kind() == "macro_invocation" && self.is_diverging(node)
+            {
+                return true;
+            }
+            if !matches!(node.kind(), "closure_expression" | "function_item") {
+                let mut cursor = node.walk();
+                stack.extend(node.named_children(&mut cursor));
+            }
+        }
+        false
+    }
+
+    /// Removes the empty blocks with a single successor, which the construction introduces at the joins.
+    fn compact(self) -> ControlFlowGraph<'a> {
+        let removed = |block: usize| {
+            let block = &self.blocks[block];
+            block.elements.is_empty() && block.terminator.is_none() && block.successors.len() == 1
+        };
+        let forward = |mut block: usize| {
+            let mut visited = HashSet::new();
+            while block != ENTRY && block != EXIT && removed(block) && visited.insert(block) {
+                block = self.blocks[block].successors[0];
+            }
+            block
+        };
+
+        let kept: Vec<usize> = (0..self.blocks.len())
+            .filter(|block| *block == ENTRY || *block == EXIT || !removed(*block))
+            .collect();
+        let index = |block: usize| {
+            kept.iter()
+                .position(|kept| *kept == forward(block))
+                .expect("the blocks are forwarded to kept blocks")
+        };
+        let blocks = kept
+            .iter()
+            .map(|block| {
+                let block = &self.blocks[*block];
+                let mut successors: Vec<usize> = vec![];
+                for successor in &block.successors {
+                    let successor = index(*successor);
+                    if !successors.contains(&successor) {
+                        successors.push(successor);
+                    }
+                }
+                BasicBlock {
+                    elements: block.elements.clone(),
+                    terminator: block.terminator,
+                    successors,
+                }
+            })
+            .collect();
+        ControlFlowGraph {
+            blocks,
+            entry: index(ENTRY),
+            exit: index(EXIT),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{parse_rust_code, NodeIterator};
+
+    /// One line per block: its elements, its terminator and its successors.
+    fn dump(source_code: &str) -> Vec<String> {
+        let tree = parse_rust_code(source_code).unwrap();
+        let function = NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item")
+            .last()
+            .unwrap();
+        let graph = ControlFlowGraph::build(function, source_code);
+        let text = |node: Node<'_>| source_code[node.byte_range()].to_string();
+        graph
+            .blocks
+            .iter()
+            .enumerate()
+            .map(|(index, block)| {
+                let name = match index {
+                    _ if index == graph.entry => "entry".to_string(),
+                    _ if index == graph.exit => "exit".to_string(),
+                    _ => index.to_string(),
+                };
+                format!(
+                    "{}: [{}] {}-> {:?}",
+                    name,
+                    block
+                        .elements
+                        .iter()
+                        .map(|node| text(*node))
+                        .collect::<Vec<_>>()
+                        .join("; "),
+                    block
+                        .terminator
+                        .map(|node| format!("{} ", node.kind()))
+                        .unwrap_or_default(),
+                    block.successors
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_branches() {
+        let source_code = r#"
+fn f(a: bool) -> i32 {
+    let x = 1;
+    if a {
+        return x;
+    } else {
+        foo();
+    }
+    let y = match x { 0 => 1, _ => bar()? };
+    x + y
+}
+"#;
+        assert_eq!(
+            dump(source_code),
+            vec![
+                "entry: [let x = 1;; a] if_expression -> [3, 4]",
+                "exit: [] -> []",
+                "2: [x] match_expression -> [6, 7]",
+                "3: [x] return_expression -> [1]",
+                "4: [foo()] -> [2]",
+                "5: [let y = match x { 0 => 1, _ => bar()? };; x + y] -> [1]",
+                "6: [0; 1] -> [5]",
+                "7: [_; bar()] try_expression -> [5, 1]",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loops_and_jumps() {
+        let source_code = r#"
+fn g(items: &[i32]) {
+    'outer: for item in items {
+        while check(item) {
+            if skip() {
+                continue 'outer;
+            }
+            break;
+        }
+        loop {
+            break 'outer;
+        }
+        unreachable_code();
+    }
+    let Some(x) = first() else { panic!("none") };
+    done(x);
+}
+"#;
+        assert_eq!(
+            dump(source_code),
+            vec![
+                "entry: [items] -> [2]",
+                "exit: [] -> []",
+                "2: [] for_expression -> [4, 3]",
+                "3: [first()] let_declaration -> [11, 12]",
+                "4: [check(item)] while_expression -> [5, 8]",
+                "5: [skip()] if_expression -> [7, 6]",
+                "6: [] break_expression -> [8]",
+                "7: [] continue_expression -> [2]",
+                "8: [] loop_expression -> [10]",
+                "9: [unreachable_code()] -> [2]",
+                "10: [] break_expression -> [3]",
+                "11: [done(x)] -> [1]",
+                "12: [] macro_invocation -> [1]",
+            ]
+        );
+
+        let tree = parse_rust_code(source_code).unwrap();
+        let function = NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item")
+            .next()
+            .unwrap();
+        let graph = ControlFlowGraph::build(function, source_code);
+        assert_eq!(
+            graph
+                .unreachable_nodes()
+                .iter()
+                .map(|node| &source_code[node.byte_range()])
+                .collect::<Vec<_>>(),
+            vec!["unreachable_code()"]
+        );
+        assert_eq!(graph.predecessors(2), vec![0, 7, 9]);
+    }
+}
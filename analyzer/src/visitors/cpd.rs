@@ -15,9 +15,12 @@
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
 use crate::tree::{
-    child_of_kind, walk_tree, AnalyzerError, NodeVisitor, SonarLocation, TreeSitterLocation,
+    walk_tree, AnalyzerError, NodeIterator, NodeVisitor, SonarLocation, TreeSitterLocation,
 };
+use crate::visitors::generated_code::{default_generated_markers, GeneratedCode};
+use crate::visitors::test_code::TestCode;
 use tree_sitter::Node;
+use tree_sitter::Point;
 use tree_sitter::Tree;
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
@@ -26,53 +29,252 @@ pub struct CpdToken {
     pub location: SonarLocation,
 }
 
+/// Same as `CpdToken`, with an image borrowed from the source code (or a static normalized image such as "NUMBER").
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct BorrowedCpdToken<'a> {
+    pub image: &'a str,
+    pub location: SonarLocation,
+}
+
+impl BorrowedCpdToken<'_> {
+    pub fn to_owned_token(&self) -> CpdToken {
+        CpdToken {
+            image: self.image.to_string(),
+            location: self.location.clone(),
+        }
+    }
+}
+
+/// Settings of the computation of CPD tokens.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct CpdOptions {
+    /// Replace number and string literals by the "NUMBER" and "STRING" placeholders, so that code differing only by
+    /// its constants is detected as duplicated.
+    pub normalize_literals: bool,
+    /// Do not produce tokens for test code, see `TestCode`, as duplication between tests is usually intentional.
+    pub exclude_test_code: bool,
+    /// Do not produce tokens for generated code, see `GeneratedCode`.
+    pub exclude_generated_code: bool,
+    /// Do not produce tokens for the items (e.g. functions) having fewer tokens or fewer lines, such as one-line
+    /// getters, whose duplications are noise. 0 when there is no minimum.
+    pub minimum_item_tokens: usize,
+    pub minimum_item_lines: usize,
+}
+
+impl Default for CpdOptions {
+    fn default() -> Self {
+        CpdOptions {
+            normalize_literals: true,
+            exclude_test_code: true,
+            exclude_generated_code: true,
+            minimum_item_tokens: 0,
+            minimum_item_lines: 0,
+        }
+    }
+}
+
 pub fn calculate_cpd_tokens(
     tree: &Tree,
     source_code: &str,
 ) -> Result<Vec<CpdToken>, AnalyzerError> {
-    let mut cpd_visitor = CPDVisitor::new(source_code);
+    calculate_cpd_tokens_with_options(
+        tree,
+        source_code,
+        &CpdOptions::default(),
+        &TestCode::find(tree, source_code, false),
+        &GeneratedCode::find(tree, source_code, &default_generated_markers(), false),
+    )
+}
+
+pub fn calculate_cpd_tokens_with_options(
+    tree: &Tree,
+    source_code: &str,
+    options: &CpdOptions,
+    test_code: &TestCode<'_>,
+    generated_code: &GeneratedCode,
+) -> Result<Vec<CpdToken>, AnalyzerError> {
+    Ok(
+        calculate_borrowed_cpd_tokens(tree, source_code, options, test_code, generated_code)?
+            .iter()
+            .map(BorrowedCpdToken::to_owned_token)
+            .collect(),
+    )
+}
+
+pub fn calculate_borrowed_cpd_tokens<'a>(
+    tree: &Tree,
+    source_code: &'a str,
+    options: &CpdOptions,
+    test_code: &TestCode<'_>,
+    generated_code: &GeneratedCode,
+) -> Result<Vec<BorrowedCpdToken<'a>>, AnalyzerError> {
+    let mut cpd_visitor = CPDVisitor::new(source_code, options.clone(), test_code, generated_code);
     walk_tree(tree.root_node(), &mut cpd_visitor)?;
     Ok(cpd_visitor.tokens)
 }
 
 #[derive(Debug)]
-struct CPDVisitor<'a> {
+struct CPDVisitor<'a, 'b> {
     source_code: &'a str,
-    tokens: Vec<CpdToken>,
-    test_code_node: Option<usize>,
+    options: CpdOptions,
+    test_code: &'b TestCode<'b>,
+    generated_code: &'b GeneratedCode,
+    tokens: Vec<BorrowedCpdToken<'a>>,
+    /// Node whose subtree does not produce any more tokens.
+    skipped_node: Option<usize>,
 }
 
-impl<'a> CPDVisitor<'a> {
-    fn new(source_code: &'a str) -> Self {
+impl<'a, 'b> CPDVisitor<'a, 'b> {
+    fn new(
+        source_code: &'a str,
+        options: CpdOptions,
+        test_code: &'b TestCode<'b>,
+        generated_code: &'b GeneratedCode,
+    ) -> Self {
         Self {
             source_code,
+            options,
+            test_code,
+            generated_code,
             tokens: Vec::new(),
-            test_code_node: None,
+            skipped_node: None,
         }
     }
 
-    fn new_token(&mut self, image: &str, node: Node) {
-        self.tokens.push(CpdToken {
-            image: image.to_string(),
-            location: TreeSitterLocation::from_tree_sitter_node(node)
-                .to_sonar_location(self.source_code),
+    fn new_token(&mut self, image: &'a str, node: Node) {
+        self.new_ranged_token(image, node, node);
+    }
+
+    fn new_ranged_token(&mut self, image: &'a str, first: Node, last: Node) {
+        self.tokens.push(BorrowedCpdToken {
+            image,
+            location: TreeSitterLocation {
+                start_byte: first.start_byte(),
+                end_byte: last.end_byte(),
+                start_position: first.start_position(),
+                end_position: last.end_position(),
+            }
+            .to_sonar_location(self.source_code),
         });
     }
+
+    fn image(&self, node: Node) -> &'a str {
+        &self.source_code[node.start_byte()..node.end_byte()]
+    }
+
+    /// Whether the node is an item below the minimum number of tokens or lines of the options.
+    fn is_small_item(&self, node: Node) -> bool {
+        if !node.kind().ends_with("_item") || node.kind().ends_with("attribute_item") {
+            return false;
+        }
+        let lines = node.end_position().row - node.start_position().row + 1;
+        if lines < self.options.minimum_item_lines {
+            return true;
+        }
+        self.options.minimum_item_tokens > 0
+            && NodeIterator::new(node, |node| {
+                node.child_count() == 0 && !node.is_extra() && !node.is_missing()
+            })
+            .take(self.options.minimum_item_tokens)
+            .count()
+                < self.options.minimum_item_tokens
+    }
+
+    /// Produces a token for the separator of a macro repetition, e.g. the ',' of `$($x:expr),*`, which is not a node of
+    /// the grammar: it is the text between the closing parenthesis and the repetition operator.
+    fn new_separator_token(&mut self, operator: Node) {
+        let Some(close) = operator.prev_sibling().filter(|close| close.kind() == ")") else {
+            return;
+        };
+        let gap = &self.source_code[close.end_byte()..operator.start_byte()];
+        let separator = gap.trim();
+        if separator.is_empty() {
+            return;
+        }
+        let offset = gap.find(separator).unwrap_or_default();
+        let point = |offset: usize| match gap[..offset].rfind('\n') {
+            Some(newline) => Point {
+                row: close.end_position().row + gap[..offset].matches('\n').count(),
+                column: offset - newline - 1,
+            },
+            None => Point {
+                row: close.end_position().row,
+                column: close.end_position().column + offset,
+            },
+        };
+        self.tokens.push(BorrowedCpdToken {
+            image: separator,
+            location: TreeSitterLocation {
+                start_byte: close.end_byte() + offset,
+                end_byte: close.end_byte() + offset + separator.len(),
+                start_position: point(offset),
+                end_position: point(offset + separator.len()),
+            }
+            .to_sonar_location(self.source_code),
+        });
+    }
+
+    /// Produces the quotes of a string literal and a single "STRING" token for its content, including escape sequences.
+    fn new_string_tokens(&mut self, string_literal: Node) {
+        let mut cursor = string_literal.walk();
+        let children: Vec<Node> = string_literal.children(&mut cursor).collect();
+        if let [open, content @ .., close] = children.as_slice() {
+            self.new_token(self.image(*open), *open);
+            if let (Some(first), Some(last)) = (content.first(), content.last()) {
+                self.new_ranged_token("STRING", *first, *last);
+            }
+            self.new_token(self.image(*close), *close);
+        }
+    }
 }
 
-impl NodeVisitor for CPDVisitor<'_> {
+impl NodeVisitor for CPDVisitor<'_, '_> {
     fn enter_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
-        if is_cfg_test_attribute(node, self.source_code) {
-            // Ignore everything under '#[cfg(test)]' nodes as we do not want CPD on test code.
-            // In the grammar, the attribute is not attached to the tree it applies to, rather it's a sibling node, so we'll look for the next sibling
-            // and attach its effects there.
-            if let Some(sibling) = node.next_named_sibling() {
-                self.test_code_node = Some(sibling.id());
-                return Ok(());
-            }
+        if self.skipped_node.is_some() {
+            return Ok(());
+        }
+
+        // The ranges of the test code include the test attributes, which are siblings of the items they apply to
+        if self.options.exclude_test_code && self.test_code.contains_node(node) {
+            self.skipped_node = Some(node.id());
+            return Ok(());
+        }
+
+        if self.options.exclude_generated_code
+            && self.generated_code.contains_byte(node.start_byte())
+        {
+            self.skipped_node = Some(node.id());
+            return Ok(());
+        }
+
+        if node.kind() == "shebang" {
+            return Ok(());
         }
 
-        if node.child_count() == 0 && self.test_code_node.is_none() {
+        if self.is_small_item(node) {
+            self.skipped_node = Some(node.id());
+            return Ok(());
+        }
+
+        if self.options.normalize_literals && node.kind() == "string_literal" && !node.has_error() {
+            self.new_string_tokens(node);
+            self.skipped_node = Some(node.id());
+            return Ok(());
+        }
+
+        // The delimiters of raw strings are not nodes of the grammar
+        if node.kind() == "raw_string_literal" {
+            let image = if self.options.normalize_literals {
+                "STRING"
+            } else {
+                self.image(node)
+            };
+            self.new_token(image, node);
+            self.skipped_node = Some(node.id());
+            return Ok(());
+        }
+
+        if node.child_count() == 0 {
             // Ignore source files
             // We wrongly consider them as tokens when they denote empty files
             if node.kind() == "source_file" {
@@ -92,59 +294,47 @@ impl NodeVisitor for CPDVisitor<'_> {
             }
 
             // Number-like tokens
-            if node.kind() == "integer_literal" || node.kind() == "float_literal" {
+            if self.options.normalize_literals
+                && (node.kind() == "integer_literal" || node.kind() == "float_literal")
+            {
                 self.new_token("NUMBER", node);
                 return Ok(());
             }
 
-            // String-like tokens
-            if node.kind() == "string_content" {
-                if let Some(parent) = node
-                    .parent()
-                    .filter(|parent| parent.kind() == "raw_string_literal")
-                {
-                    self.new_token("STRING", parent);
-                } else {
-                    self.new_token("STRING", node);
-                }
+            // Content of malformed string literals
+            if self.options.normalize_literals && node.kind() == "string_content" {
+                self.new_token("STRING", node);
                 return Ok(());
             }
 
+            if is_repetition_operator(node) {
+                self.new_separator_token(node);
+            }
+
             // Default case
-            let image = &self.source_code[node.start_byte()..node.end_byte()];
-            self.new_token(image, node);
+            self.new_token(self.image(node), node);
         }
         Ok(())
     }
 
     fn exit_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
-        if Some(node.id()) == self.test_code_node {
-            self.test_code_node = None;
+        if Some(node.id()) == self.skipped_node {
+            self.skipped_node = None;
         }
         Ok(())
     }
 }
 
-fn is_cfg_test_attribute(node: Node<'_>, source_code: &str) -> bool {
-    // '#[cfg(test)]' attributes have the following structure:
-    //  (attribute_item (attribute (identifier) arguments: (token_tree (identifier))))
-    if node.kind() != "attribute_item" {
-        return false;
-    }
-
-    if let Some(attribute) = child_of_kind(node, "attribute") {
-        let identifier = child_of_kind(attribute, "identifier")
-            .map(|n| &source_code[n.start_byte()..n.end_byte()]);
-
-        let argument = attribute
-            .child_by_field_name("arguments")
-            .and_then(|arg| child_of_kind(arg, "identifier"))
-            .map(|n| &source_code[n.start_byte()..n.end_byte()]);
-
-        return Some("cfg") == identifier && Some("test") == argument;
-    }
-
-    false
+/// Whether the node is the '+', '*' or '?' operator ending a repetition of a `macro_rules!` definition.
+fn is_repetition_operator(node: Node) -> bool {
+    matches!(node.kind(), "+" | "*" | "?")
+        && node.next_sibling().is_none()
+        && node.parent().is_some_and(|parent| {
+            matches!(
+                parent.kind(),
+                "token_repetition" | "token_repetition_pattern"
+            )
+        })
 }
 
 #[cfg(test)]
@@ -213,6 +403,110 @@ bar
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_cpd_tokens_of_escaped_strings() {
+        let source_code = r#"let s = "a\n\u{1F600}b"; let t = "";"#;
+        let tree = parse_rust_code(source_code).unwrap();
+
+        let actual = calculate_cpd_tokens(&tree, source_code).unwrap();
+        let expected = vec![
+            token("let", 1, 0, 1, 3),
+            token("s", 1, 4, 1, 5),
+            token("=", 1, 6, 1, 7),
+            token("\"", 1, 8, 1, 9),
+            token("STRING", 1, 9, 1, 22),
+            token("\"", 1, 22, 1, 23),
+            token(";", 1, 23, 1, 24),
+            token("let", 1, 25, 1, 28),
+            token("t", 1, 29, 1, 30),
+            token("=", 1, 31, 1, 32),
+            token("\"", 1, 33, 1, 34),
+            token("\"", 1, 34, 1, 35),
+            token(";", 1, 35, 1, 36),
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_cpd_tokens_without_normalization() {
+        let source_code = r#"let s = ("a\n", 42, 3.14, r"raw");"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let options = CpdOptions {
+            normalize_literals: false,
+            ..CpdOptions::default()
+        };
+
+        let actual: Vec<String> = calculate_cpd_tokens_with_options(
+            &tree,
+            source_code,
+            &options,
+            &TestCode::find(&tree, source_code, false),
+            &GeneratedCode::default(),
+        )
+        .unwrap()
+        .iter()
+        .map(|t| t.image.clone())
+        .collect();
+        let expected = vec![
+            "let", "s", "=", "(", "\"", "a", "\\n", "\"", ",", "42", ",", "3.14", ",", "r\"raw\"",
+            ")", ";",
+        ];
+
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_cpd_tokens_of_macro_rules() {
+        let source_code = r#"
+macro_rules! sum {
+    // Adds the expressions
+    ($($x:expr),+ $(,)?) => {
+        0 $(+ $x)+
+    };
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let tokens = calculate_cpd_tokens(&tree, source_code).unwrap();
+        // The separators of the repetitions are not nodes of the grammar
+        assert_eq!(tokens[10], token(",", 4, 15, 4, 16));
+        assert_eq!(
+            tokens.iter().map(|t| t.image.as_str()).collect::<Vec<_>>(),
+            vec![
+                "macro_rules!",
+                "sum",
+                "{",
+                "(",
+                "$",
+                "(",
+                "$x",
+                ":",
+                "expr",
+                ")",
+                ",",
+                "+",
+                "$",
+                "(",
+                ",",
+                ")",
+                "?",
+                ")",
+                "=>",
+                "{",
+                "NUMBER",
+                "$",
+                "(",
+                "+",
+                "$x",
+                ")",
+                "+",
+                "}",
+                ";",
+                "}"
+            ]
+        );
+    }
+
     #[test]
     fn test_empty_source() {
         let source_code = "";
@@ -300,18 +594,153 @@ fn bar() {}
         assert_eq!(actual, expected);
     }
 
+    fn images(source_code: &str, exclude_test_code: bool, test_file: bool) -> Vec<String> {
+        let tree = parse_rust_code(source_code).unwrap();
+        let options = CpdOptions {
+            exclude_test_code,
+            ..CpdOptions::default()
+        };
+        calculate_cpd_tokens_with_options(
+            &tree,
+            source_code,
+            &options,
+            &TestCode::find(&tree, source_code, test_file),
+            &GeneratedCode::default(),
+        )
+        .unwrap()
+        .iter()
+        .map(|t| t.image.clone())
+        .collect()
+    }
+
     #[test]
-    fn test_is_cfg_test_attribute() {
-        fn check(source: &str) -> bool {
-            let tree = parse_rust_code(source).unwrap();
-            is_cfg_test_attribute(tree.root_node().child(0).unwrap(), source)
-        }
+    fn test_cpd_is_disabled_in_test_functions() {
+        let source_code = r#"
+fn foo() {}
 
-        assert_eq!(check("#[cfg(test)]"), true);
-        assert_eq!(check("#[ cfg (test ) ]"), true);
-        assert_eq!(check("#[cfg(abc)]"), false);
-        assert_eq!(check("#[cfg(target=\"Windows\")]"), false);
-        assert_eq!(check("#[cfg(not(test))]"), false);
-        assert_eq!(check("#[test]"), false);
+#[test]
+#[should_panic]
+fn test_foo() {
+    foo();
+}
+
+#[tokio::test]
+async fn test_bar() {}
+"#;
+        assert_eq!(
+            images(source_code, true, false),
+            vec!["fn", "foo", "(", ")", "{", "}"]
+        );
+    }
+
+    #[test]
+    fn test_cpd_of_test_code() {
+        let source_code = r#"
+#[cfg(test)]
+mod tests {}
+"#;
+        assert_eq!(
+            images(source_code, false, false),
+            vec!["#", "[", "cfg", "(", "test", ")", "]", "mod", "tests", "{", "}"]
+        );
+        assert_eq!(images(source_code, true, false), Vec::<String>::new());
+
+        assert_eq!(images("fn foo() {}", true, true), Vec::<String>::new());
+        assert_eq!(
+            images("fn foo() {}", false, true),
+            vec!["fn", "foo", "(", ")", "{", "}"]
+        );
+    }
+
+    fn generated_images(source_code: &str, options: &CpdOptions) -> Vec<String> {
+        let tree = parse_rust_code(source_code).unwrap();
+        calculate_cpd_tokens_with_options(
+            &tree,
+            source_code,
+            options,
+            &TestCode::find(&tree, source_code, false),
+            &GeneratedCode::find(&tree, source_code, &default_generated_markers(), false),
+        )
+        .unwrap()
+        .iter()
+        .map(|t| t.image.clone())
+        .collect()
+    }
+
+    #[test]
+    fn test_cpd_is_disabled_in_generated_files() {
+        let options = CpdOptions::default();
+        assert_eq!(
+            generated_images("// @generated\nfn foo() {}", &options),
+            Vec::<String>::new()
+        );
+
+        let options = CpdOptions {
+            exclude_generated_code: false,
+            ..CpdOptions::default()
+        };
+        assert_eq!(
+            generated_images("// @generated\nfn foo() {}", &options).len(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_cpd_is_disabled_in_automatically_derived_items() {
+        let source_code = r#"
+struct A;
+
+#[automatically_derived]
+#[allow(unused_qualifications)]
+impl Clone for A {
+    fn clone(&self) -> A {
+        A
+    }
+}
+"#;
+        assert_eq!(
+            generated_images(source_code, &CpdOptions::default()),
+            vec!["struct", "A", ";"]
+        );
+
+        let options = CpdOptions {
+            exclude_generated_code: false,
+            ..CpdOptions::default()
+        };
+        assert_eq!(generated_images(source_code, &options).len(), 31);
+    }
+
+    #[test]
+    fn test_minimum_item_size() {
+        let source_code = r#"
+struct Point {
+    x: i32,
+}
+
+impl Point {
+    fn x(&self) -> i32 { self.x }
+
+    fn shift(&mut self, dx: i32) {
+        self.x += dx;
+    }
+}
+"#;
+        let options = CpdOptions {
+            minimum_item_lines: 2,
+            ..CpdOptions::default()
+        };
+        assert_eq!(
+            generated_images(source_code, &options).join(" "),
+            "struct Point { x : i32 , } impl Point { fn shift ( & mut self , dx : i32 ) { self . x += dx ; } }"
+        );
+
+        let options = CpdOptions {
+            minimum_item_tokens: 16,
+            ..CpdOptions::default()
+        };
+        assert_eq!(
+            generated_images(source_code, &options).join(" "),
+            "impl Point { fn shift ( & mut self , dx : i32 ) { self . x += dx ; } }"
+        );
     }
 }
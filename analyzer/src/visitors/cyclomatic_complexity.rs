@@ -15,14 +15,19 @@
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
 use crate::{
-    tree::{walk_tree, AnalyzerError, NodeVisitor},
-    visitors::cognitive_complexity::is_logical_operator,
+    tree::{is_closure, walk_tree, AnalyzerError, NodeVisitor},
+    visitors::cognitive_complexity::{is_let_else, is_logical_operator},
 };
 use tree_sitter::{Node, Tree};
 
 pub(crate) fn calculate_cyclomatic_complexity(tree: &Tree) -> Result<i32, AnalyzerError> {
+    calculate_node_cyclomatic_complexity(tree.root_node())
+}
+
+/// Complexity of a subtree, e.g. of a single function.
+pub(crate) fn calculate_node_cyclomatic_complexity(node: Node<'_>) -> Result<i32, AnalyzerError> {
     let mut visitor = CyclomaticComplexityVisitor::default();
-    walk_tree(tree.root_node(), &mut visitor)?;
+    walk_tree(node, &mut visitor)?;
     Ok(visitor.complexity)
 }
 
@@ -38,9 +43,15 @@ impl NodeVisitor for CyclomaticComplexityVisitor {
             | "closure_expression" => {
                 self.complexity += 1;
             }
+            "async_block" if is_closure(node) => {
+                self.complexity += 1;
+            }
             "binary_expression" if is_logical_operator(node) => {
                 self.complexity += 1;
             }
+            "let_declaration" if is_let_else(node) => {
+                self.complexity += 1;
+            }
             "match_arm" if has_non_empty_field(node, "value") => {
                 self.complexity += 1;
             }
@@ -149,6 +160,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_modern_syntax() {
+        assert_eq!(
+            complexity(
+                r#"
+    fn foo() { // +1
+        let Some(x) = y else { // +1
+            return;
+        };
+        let c = async move |a| a + x; // +1
+        let g = gen { yield c"item"; };
+    }
+        "#
+            ),
+            3
+        );
+    }
+
     fn complexity(source_code: &str) -> i32 {
         let tree = parse_rust_code(source_code).unwrap();
         calculate_cyclomatic_complexity(&tree).unwrap()
@@ -0,0 +1,792 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::tree::{NodeIterator, SonarLocation, TreeSitterLocation};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use tree_sitter::Node;
+
+/// Kind of a local variable.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum VariableKind {
+    /// Declared by a `let` statement.
+    Local,
+    /// Parameter of the function or of a closure.
+    Parameter,
+    /// Bound by the pattern of a `match` arm, an `if let`, a `while let` or a `for` loop.
+    Pattern,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Variable {
+    pub name: String,
+    /// Location of the name in the declaration.
+    pub location: SonarLocation,
+    pub kind: VariableKind,
+    pub mutable: bool,
+}
+
+/// Kind of the statement or expression giving a value to a variable.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum DefinitionKind {
+    /// `let` statement with a value.
+    Initialization,
+    /// Binding of a parameter or of a pattern.
+    Binding,
+    Assignment,
+    /// Assignment with an operator, e.g. `+=`, which also uses the variable.
+    CompoundAssignment,
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Definition {
+    /// Index of the variable in `DataFlow::variables`.
+    pub variable: usize,
+    pub kind: DefinitionKind,
+    /// Location of the name of the variable.
+    pub location: SonarLocation,
+    /// Indices of the uses the definition reaches, in `DataFlow::uses`.
+    pub uses: Vec<usize>,
+}
+
+impl Definition {
+    /// Whether the value of the definition may be read.
+    pub fn is_live(&self) -> bool {
+        !self.uses.is_empty()
+    }
+}
+
+/// Read of a variable, including the reads through a reference or a method call, e.g. `list.push(1)`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Use {
+    /// Index of the variable in `DataFlow::variables`.
+    pub variable: usize,
+    pub location: SonarLocation,
+    /// Indices of the definitions which may give its value to the variable, in `DataFlow::definitions`.
+    pub definitions: Vec<usize>,
+}
+
+/// Def-use chains of the local variables of a function.
+///
+/// The variables are resolved with the lexical scopes of the function, so that a shadowing `let` declares a new
+/// variable. The definitions and uses are in the order in which they are found, i.e. in evaluation order.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct DataFlow {
+    pub variables: Vec<Variable>,
+    pub definitions: Vec<Definition>,
+    pub uses: Vec<Use>,
+}
+
+impl DataFlow {
+    /// Definitions whose value is never read, e.g. an assignment overwritten before any read, excluding the variables
+    /// whose name starts with an underscore.
+    pub fn dead_stores(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions.iter().filter(|definition| {
+            !definition.is_live() && !self.variables[definition.variable].name.starts_with('_')
+        })
+    }
+
+    /// Variables which are never read.
+    pub fn unused_variables(&self) -> impl Iterator<Item = &Variable> {
+        self.variables
+            .iter()
+            .enumerate()
+            .filter_map(|(index, variable)| {
+                (!self.uses.iter().any(|u| u.variable == index)).then_some(variable)
+            })
+    }
+}
+
+/// Computes the def-use chains of a function or a closure, excluding its nested functions.
+///
+/// The definitions reaching each use are computed on the syntax tree, following the branches of `if` and `match`,
+/// the iterations of loops and the jumps of `break`, `continue` and `return`. The code after a jump is unreachable and
+/// reached by no definition. The variables captured by a closure are used where the closure is declared, and the
+/// assignments in a closure are not known to the code after it.
+pub fn calculate_data_flow(function: Node<'_>, source_code: &str) -> DataFlow {
+    let mut analysis = DataFlowAnalysis {
+        source_code,
+        data_flow: DataFlow::default(),
+        use_definitions: vec![],
+        variables_by_node: HashMap::new(),
+        definitions_by_node: HashMap::new(),
+        uses_by_node: HashMap::new(),
+        scopes: vec![vec![]],
+        jump_targets: vec![],
+    };
+    let mut state = Some(State::new());
+    if let Some(parameters) = function.child_by_field_name("parameters") {
+        analysis.bind_parameters(parameters, &mut state);
+    }
+    if let Some(body) = function.child_by_field_name("body") {
+        analysis.visit(body, &mut state);
+    }
+
+    let mut data_flow = analysis.data_flow;
+    for (index, definitions) in analysis.use_definitions.into_iter().enumerate() {
+        data_flow.uses[index].definitions = definitions.into_iter().collect();
+        for definition in &data_flow.uses[index].definitions {
+            data_flow.definitions[*definition].uses.push(index);
+        }
+    }
+    data_flow
+}
+
+/// Definitions reaching a point of the function, by variable.
+type State = BTreeMap<usize, BTreeSet<usize>>;
+
+/// Loop, or labeled block, which can be the target of a `break` or a `continue`.
+struct JumpTarget {
+    label: Option<String>,
+    is_loop: bool,
+    breaks: Option<State>,
+    continues: Option<State>,
+}
+
+struct DataFlowAnalysis<'a> {
+    source_code: &'a str,
+    data_flow: DataFlow,
+    use_definitions: Vec<BTreeSet<usize>>,
+    // Loop bodies are visited several times, the nodes are mapped to the variables, definitions and uses they created
+    variables_by_node: HashMap<usize, usize>,
+    definitions_by_node: HashMap<usize, usize>,
+    uses_by_node: HashMap<usize, usize>,
+    scopes: Vec<Vec<(String, usize)>>,
+    jump_targets: Vec<JumpTarget>,
+}
+
+impl<'a> DataFlowAnalysis<'a> {
+    fn visit(&mut self, node: Node<'a>, state: &mut Option<State>) {
+        match node.kind() {
+            "function_item" | "impl_item" | "trait_item" | "mod_item" | "struct_item"
+            | "enum_item" | "union_item" | "const_item" | "static_item" | "type_item"
+            | "use_declaration" | "macro_definition" | "attribute_item" | "label"
+            | "scoped_identifier" | "field_identifier" | "line_comment" | "block_comment" => {}
+            "identifier" | "self" => self.read(node, state),
+            "block" => {
+                let label = self.label(node);
+                let labeled = label.is_some();
+                if labeled {
+                    self.jump_targets.push(JumpTarget {
+                        label,
+                        is_loop: false,
+                        breaks: None,
+                        continues: None,
+                    });
+                }
+                self.scopes.push(vec![]);
+                self.visit_children(node, state);
+                self.scopes.pop();
+                if labeled {
+                    if let Some(target) = self.jump_targets.pop() {
+                        *state = union(state.take(), target.breaks);
+                    }
+                }
+            }
+            "let_declaration" => {
+                if let Some(value) = node.child_by_field_name("value") {
+                    self.visit(value, state);
+                }
+                if let Some(alternative) = node.child_by_field_name("alternative") {
+                    let mut alternative_state = state.clone();
+                    self.visit(alternative, &mut alternative_state);
+                }
+                if let Some(pattern) = node.child_by_field_name("pattern") {
+                    let kind = node
+                        .child_by_field_name("value")
+                        .map(|_| DefinitionKind::Initialization);
+                    self.declare(pattern, VariableKind::Local, kind, state);
+                }
+            }
+            "let_condition" => {
+                if let Some(value) = node.child_by_field_name("value") {
+                    self.visit(value, state);
+                }
+                if let Some(pattern) = node.child_by_field_name("pattern") {
+                    self.declare(
+                        pattern,
+                        VariableKind::Pattern,
+                        Some(DefinitionKind::Binding),
+                        state,
+                    );
+                }
+            }
+            "assignment_expression" | "compound_assignment_expr" => {
+                if let Some(right) = node.child_by_field_name("right") {
+                    self.visit(right, state);
+                }
+                if let Some(left) = node.child_by_field_name("left") {
+                    if left.kind() == "identifier" {
+                        if node.kind() == "assignment_expression" {
+                            self.write(left, DefinitionKind::Assignment, state);
+                        } else {
+                            self.read(left, state);
+                            self.write(left, DefinitionKind::CompoundAssignment, state);
+                        }
+                    } else {
+                        // Assigning a field or an element reads the variable
+                        self.visit(left, state);
+                    }
+                }
+            }
+            "if_expression" => self.visit_if(node, state),
+            "match_expression" => self.visit_match(node, state),
+            "loop_expression" | "while_expression" | "for_expression" => {
+                self.visit_loop(node, state)
+            }
+            "closure_expression" => {
+                let mut closure_state = state.clone();
+                self.scopes.push(vec![]);
+                let jump_targets = std::mem::take(&mut self.jump_targets);
+                if let Some(parameters) = node.child_by_field_name("parameters") {
+                    self.bind_parameters(parameters, &mut closure_state);
+                }
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.visit(body, &mut closure_state);
+                }
+                self.jump_targets = jump_targets;
+                self.scopes.pop();
+            }
+            "return_expression" => {
+                self.visit_children(node, state);
+                *state = None;
+            }
+            "break_expression" | "continue_expression" => {
+                self.visit_children(node, state);
+                let label = self.label(node);
+                let is_break = node.kind() == "break_expression";
+                let target = self
+                    .jump_targets
+                    .iter_mut()
+                    .rev()
+                    .find(|target| match &label {
+                        Some(label) => target.label.as_ref() == Some(label),
+                        None => target.is_loop,
+                    });
+                if let Some(target) = target {
+                    let jumped = if is_break {
+                        &mut target.breaks
+                    } else {
+                        &mut target.continues
+                    };
+                    *jumped = union(jumped.take(), state.take());
+                }
+                *state = None;
+            }
+            "macro_invocation" => {
+                // The arguments of macros are not parsed, the identifiers naming variables are read
+                for identifier in
+                    NodeIterator::new(node, |node| matches!(node.kind(), "identifier" | "self"))
+                        .filter(|node| {
+                            node.parent()
+                                .is_some_and(|parent| parent.kind() == "token_tree")
+                        })
+                        .collect::<Vec<_>>()
+                {
+                    self.read(identifier, state);
+                }
+            }
+            _ => self.visit_children(node, state),
+        }
+    }
+
+    fn visit_children(&mut self, node: Node<'a>, state: &mut Option<State>) {
+        let mut cursor = node.walk();
+        let children: Vec<Node<'a>> = node.named_children(&mut cursor).collect();
+        for child in children {
+            if !child.is_extra() && !is_type(node, child) {
+                self.visit(child, state);
+            }
+        }
+    }
+
+    fn visit_if(&mut self, node: Node<'a>, state: &mut Option<State>) {
+        // The variables bound by `if let` are only visible in the consequence
+        self.scopes.push(vec![]);
+        if let Some(condition) = node.child_by_field_name("condition") {
+            self.visit(condition, state);
+        }
+        let mut consequence_state = state.clone();
+        if let Some(consequence) = node.child_by_field_name("consequence") {
+            self.visit(consequence, &mut consequence_state);
+        }
+        self.scopes.pop();
+        if let Some(alternative) = node.child_by_field_name("alternative") {
+            self.visit(alternative, state);
+        }
+        *state = union(state.take(), consequence_state);
+    }
+
+    fn visit_match(&mut self, node: Node<'a>, state: &mut Option<State>) {
+        if let Some(value) = node.child_by_field_name("value") {
+            self.visit(value, state);
+        }
+        let arms: Vec<Node<'a>> = node
+            .child_by_field_name("body")
+            .map(|body| {
+                let mut cursor = body.walk();
+                let arms = body
+                    .named_children(&mut cursor)
+                    .filter(|arm| arm.kind() == "match_arm")
+                    .collect();
+                arms
+            })
+            .unwrap_or_default();
+        let mut result = None;
+        for arm in arms {
+            let mut arm_state = state.clone();
+            self.scopes.push(vec![]);
+            if let Some(pattern) = arm.child_by_field_name("pattern") {
+                self.visit_match_pattern(pattern, &mut arm_state);
+            }
+            if let Some(value) = arm.child_by_field_name("value") {
+                self.visit(value, &mut arm_state);
+            }
+            self.scopes.pop();
+            result = union(result, arm_state);
+        }
+        *state = result;
+    }
+
+    /// Binds the variables of the pattern of a match arm, and visits its guard.
+    fn visit_match_pattern(&mut self, pattern: Node<'a>, state: &mut Option<State>) {
+        let mut cursor = pattern.walk();
+        let children: Vec<Node<'a>> = pattern.named_children(&mut cursor).collect();
+        for child in children {
+            if pattern.child_by_field_name("condition") == Some(child) {
+                self.visit(child, state);
+            } else {
+                self.declare(
+                    child,
+                    VariableKind::Pattern,
+                    Some(DefinitionKind::Binding),
+                    state,
+                );
+            }
+        }
+    }
+
+    fn visit_loop(&mut self, node: Node<'a>, state: &mut Option<State>) {
+        if node.kind() == "for_expression" {
+            if let Some(value) = node.child_by_field_name("value") {
+                self.visit(value, state);
+            }
+        }
+        let label = self.label(node);
+        let mut head = state.clone();
+        loop {
+            let mut iteration = head.clone();
+            self.scopes.push(vec![]);
+            if let Some(pattern) = node.child_by_field_name("pattern") {
+                self.declare(
+                    pattern,
+                    VariableKind::Pattern,
+                    Some(DefinitionKind::Binding),
+                    &mut iteration,
+                );
+            }
+            if let Some(condition) = node.child_by_field_name("condition") {
+                self.visit(condition, &mut iteration);
+            }
+            let exit = match node.kind() {
+                "loop_expression" => None,
+                "for_expression" => head.clone(),
+                _ => iteration.clone(),
+            };
+            self.jump_targets.push(JumpTarget {
+                label: label.clone(),
+                is_loop: true,
+                breaks: None,
+                continues: None,
+            });
+            if let Some(body) = node.child_by_field_name("body") {
+                self.visit(body, &mut iteration);
+            }
+            let target = self.jump_targets.pop();
+            self.scopes.pop();
+            let (breaks, continues) = target
+                .map(|target| (target.breaks, target.continues))
+                .unwrap_or_default();
+
+            let next_head = union(union(state.clone(), iteration), continues);
+            if next_head == head {
+                *state = union(exit, breaks);
+                return;
+            }
+            head = next_head;
+        }
+    }
+
+    fn bind_parameters(&mut self, parameters: Node<'a>, state: &mut Option<State>) {
+        let mut cursor = parameters.walk();
+        let parameters: Vec<Node<'a>> = parameters.named_children(&mut cursor).collect();
+        for parameter in parameters {
+            let pattern = match parameter.kind() {
+                "parameter" => parameter.child_by_field_name("pattern"),
+                "self_parameter" => {
+                    NodeIterator::new(parameter, |node| node.kind() == "self").next()
+                }
+                "attribute_item" => None,
+                _ => Some(parameter),
+            };
+            if let Some(pattern) = pattern {
+                self.declare(
+                    pattern,
+                    VariableKind::Parameter,
+                    Some(DefinitionKind::Binding),
+                    state,
+                );
+            }
+        }
+    }
+
+    /// Declares the variables of a pattern in the current scope, with a definition unless the declaration has no
+    /// value, e.g. `let x;`.
+    fn declare(
+        &mut self,
+        pattern: Node<'a>,
+        kind: VariableKind,
+        definition: Option<DefinitionKind>,
+        state: &mut Option<State>,
+    ) {
+        for name in pattern_identifiers(pattern, self.source_code) {
+            let variable = match self.variables_by_node.get(&name.id()) {
+                Some(variable) => *variable,
+                None => {
+                    self.data_flow.variables.push(Variable {
+                        name: self.text(name).to_string(),
+                        location: self.location(name),
+                        kind,
+                        mutable: is_mutable(name),
+                    });
+                    let variable = self.data_flow.variables.len() - 1;
+                    self.variables_by_node.insert(name.id(), variable);
+                    variable
+                }
+            };
+            let declared = self.text(name).to_string();
+            self.scopes
+                .last_mut()
+                .expect("the function scope is never popped")
+                .push((declared, variable));
+            match definition {
+                Some(definition) => self.define(name, variable, definition, state),
+                None => {
+                    if let Some(state) = state {
+                        state.insert(variable, BTreeSet::new());
+                    }
+                }
+            }
+        }
+    }
+
+    fn read(&mut self, name: Node<'a>, state: &Option<State>) {
+        let Some(variable) = self.resolve(self.text(name)) else {
+            return;
+        };
+        let index = match self.uses_by_node.get(&name.id()) {
+            Some(index) => *index,
+            None => {
+                self.data_flow.uses.push(Use {
+                    variable,
+                    location: self.location(name),
+                    definitions: vec![],
+                });
+                self.use_definitions.push(BTreeSet::new());
+                self.uses_by_node
+                    .insert(name.id(), self.use_definitions.len() - 1);
+                self.use_definitions.len() - 1
+            }
+        };
+        if let Some(definitions) = state.as_ref().and_then(|state| state.get(&variable)) {
+            self.use_definitions[index].extend(definitions);
+        }
+    }
+
+    fn write(&mut self, name: Node<'a>, kind: DefinitionKind, state: &mut Option<State>) {
+        if let Some(variable) = self.resolve(self.text(name)) {
+            self.define(name, variable, kind, state);
+        }
+    }
+
+    fn define(
+        &mut self,
+        name: Node<'a>,
+        variable: usize,
+        kind: DefinitionKind,
+        state: &mut Option<State>,
+    ) {
+        let definition = match self.definitions_by_node.get(&name.id()) {
+            Some(definition) => *definition,
+            None => {
+                self.data_flow.definitions.push(Definition {
+                    variable,
+                    kind,
+                    location: self.location(name),
+                    uses: vec![],
+                });
+                let definition = self.data_flow.definitions.len() - 1;
+                self.definitions_by_node.insert(name.id(), definition);
+                definition
+            }
+        };
+        if let Some(state) = state {
+            state.insert(variable, BTreeSet::from([definition]));
+        }
+    }
+
+    fn resolve(&self, name: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .flat_map(|scope| scope.iter().rev())
+            .find(|(declared, _)| declared == name)
+            .map(|(_, variable)| *variable)
+    }
+
+    /// Label of a loop, a block, a `break` or a `continue`.
+    fn label(&self, node: Node<'a>) -> Option<String> {
+        let mut cursor = node.walk();
+        let label = node
+            .children(&mut cursor)
+            .find(|child| child.kind() == "label")
+            .map(|label| self.text(label).to_string());
+        label
+    }
+
+    fn text(&self, node: Node<'a>) -> &'a str {
+        &self.source_code[node.byte_range()]
+    }
+
+    fn location(&self, node: Node<'a>) -> SonarLocation {
+        TreeSitterLocation::from_tree_sitter_node(node).to_sonar_location(self.source_code)
+    }
+}
+
+/// Union of the definitions of two states, where `None` is unreachable code.
+fn union(first: Option<State>, second: Option<State>) -> Option<State> {
+    match (first, second) {
+        (Some(mut first), Some(second)) => {
+            for (variable, definitions) in second {
+                first.entry(variable).or_default().extend(definitions);
+            }
+            Some(first)
+        }
+        (first, None) => first,
+        (None, second) => second,
+    }
+}
+
+/// Whether the child is the type of a declaration, a cast or a generic call, which names no variable.
+fn is_type(parent: Node<'_>, child: Node<'_>) -> bool {
+    parent.child_by_field_name("type") == Some(child)
+        || parent.child_by_field_name("type_arguments") == Some(child)
+        || parent.child_by_field_name("return_type") == Some(child)
+}
+
+fn is_mutable(name: Node<'_>) -> bool {
+    name.parent().is_some_and(|parent| {
+        let mut cursor = parent.walk();
+        let is_mutable = matches!(
+            parent.kind(),
+            "mut_pattern" | "let_declaration" | "parameter"
+        ) && parent
+            .children(&mut cursor)
+            .any(|child| child.kind() == "mutable_specifier");
+        is_mutable
+    })
+}
+
+/// Variables bound by a pattern, excluding the paths and the constants, e.g. `Some` and `None` in
+/// `Some(value) | None`.
+pub(crate) fn pattern_identifiers<'a>(pattern: Node<'a>, source_code: &str) -> Vec<Node<'a>> {
+    if matches!(pattern.kind(), "identifier" | "self") {
+        return vec![pattern];
+    }
+    NodeIterator::new(pattern, |node| {
+        matches!(node.kind(), "identifier" | "shorthand_field_identifier")
+    })
+    .filter(|identifier| match identifier.parent() {
+        Some(parent) if parent.kind() == "scoped_identifier" => false,
+        Some(parent) if matches!(parent.kind(), "tuple_struct_pattern" | "struct_pattern") => {
+            parent.child_by_field_name("type") != Some(*identifier)
+        }
+        _ => true,
+    })
+    .filter(|identifier| !source_code[identifier.byte_range()].starts_with(char::is_uppercase))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    fn data_flow(source_code: &str) -> DataFlow {
+        let tree = parse_rust_code(source_code).unwrap();
+        let function = NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item")
+            .last()
+            .unwrap();
+        calculate_data_flow(function, source_code)
+    }
+
+    /// Name and line of the definitions, with the lines of their uses.
+    fn chains(data_flow: &DataFlow) -> Vec<(&str, usize, Vec<usize>)> {
+        data_flow
+            .definitions
+            .iter()
+            .map(|definition| {
+                (
+                    data_flow.variables[definition.variable].name.as_str(),
+                    definition.location.start_line,
+                    definition
+                        .uses
+                        .iter()
+                        .map(|u| data_flow.uses[*u].location.start_line)
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_definitions_and_uses() {
+        let source_code = r#"
+fn compute(input: i32, _unused: i32) -> i32 {
+    let mut total = 0;
+    let unused = 1;
+    total = input;
+    if input > 0 {
+        total += 1;
+    } else {
+        total = 2;
+    }
+    let total = total * 2;
+    total
+}
+"#;
+        let data_flow = data_flow(source_code);
+        assert_eq!(
+            chains(&data_flow),
+            vec![
+                ("input", 2, vec![5, 6]),
+                ("_unused", 2, vec![]),
+                ("total", 3, vec![]),
+                ("unused", 4, vec![]),
+                ("total", 5, vec![7]),
+                ("total", 7, vec![11]),
+                ("total", 9, vec![11]),
+                ("total", 11, vec![12]),
+            ]
+        );
+        assert_eq!(
+            data_flow
+                .dead_stores()
+                .map(|definition| definition.location.start_line)
+                .collect::<Vec<_>>(),
+            vec![3, 4]
+        );
+        assert_eq!(
+            data_flow
+                .definitions
+                .iter()
+                .map(|definition| definition.kind)
+                .collect::<Vec<_>>(),
+            vec![
+                DefinitionKind::Binding,
+                DefinitionKind::Binding,
+                DefinitionKind::Initialization,
+                DefinitionKind::Initialization,
+                DefinitionKind::Assignment,
+                DefinitionKind::CompoundAssignment,
+                DefinitionKind::Assignment,
+                DefinitionKind::Initialization,
+            ]
+        );
+        assert!(data_flow.variables[2].mutable);
+        assert!(!data_flow.variables[3].mutable);
+    }
+
+    #[test]
+    fn test_loops_and_jumps() {
+        let source_code = r#"
+fn find(items: &[i32]) -> Option<i32> {
+    let mut found = None;
+    let mut index = 0;
+    'outer: loop {
+        for item in items {
+            if *item == index {
+                found = Some(*item);
+                break 'outer;
+            }
+            index += 1;
+            continue;
+            index = 0;
+        }
+        return None;
+    }
+    found
+}
+"#;
+        assert_eq!(
+            chains(&data_flow(source_code)),
+            vec![
+                ("items", 2, vec![6]),
+                ("found", 3, vec![]),
+                ("index", 4, vec![7, 11]),
+                ("item", 6, vec![7, 8]),
+                ("found", 8, vec![17]),
+                ("index", 11, vec![7, 11]),
+                ("index", 13, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_patterns_and_closures() {
+        let source_code = r#"
+fn describe(value: Option<i32>, unused: i32) {
+    let prefix = "value";
+    let print = |n: i32| println!("{} {}", prefix, n);
+    match value {
+        Some(n) if n > 0 => print(n),
+        Some(_) | None => {}
+    }
+    let Some(first) = value else { return };
+    let _ = first;
+}
+"#;
+        let data_flow = data_flow(source_code);
+        assert_eq!(
+            chains(&data_flow),
+            vec![
+                ("value", 2, vec![5, 9]),
+                ("unused", 2, vec![]),
+                ("prefix", 3, vec![4]),
+                ("n", 4, vec![4]),
+                ("print", 4, vec![6]),
+                ("n", 6, vec![6, 6]),
+                ("first", 9, vec![10]),
+            ]
+        );
+        assert_eq!(
+            data_flow
+                .unused_variables()
+                .map(|variable| (variable.name.as_str(), variable.kind))
+                .collect::<Vec<_>>(),
+            vec![("unused", VariableKind::Parameter)]
+        );
+    }
+}
@@ -0,0 +1,269 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    tree::{AnalyzerError, NodeIterator, SonarLocation, TreeSitterLocation},
+    visitors::{
+        cognitive_complexity::calculate_cognitive_complexity,
+        cyclomatic_complexity::calculate_node_cyclomatic_complexity,
+        halstead::{calculate_halstead_metrics, maintainability_index, HalsteadMetrics},
+        test_code::TestCode,
+    },
+};
+use std::collections::HashSet;
+use tree_sitter::{Node, Tree};
+
+/// Metrics of a single function, including the functions nested in it.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct FunctionMetrics {
+    /// Name of the function, prefixed by the implemented type for methods (e.g. 'Point::new').
+    pub name: String,
+    /// Location of the name of the function.
+    pub location: SonarLocation,
+    pub ncloc: i32,
+    pub cognitive_complexity: i32,
+    pub cyclomatic_complexity: i32,
+    /// Number of parameters, including `self`.
+    pub parameters: i32,
+    /// Whether the function is test code, see `TestCode`.
+    pub test: bool,
+    pub halstead: Option<HalsteadMetrics>,
+}
+
+impl FunctionMetrics {
+    /// Maintainability index of the function, see `maintainability_index`.
+    pub fn maintainability_index(&self) -> Option<f64> {
+        self.halstead.as_ref().map(|halstead| {
+            maintainability_index(halstead.volume(), self.cyclomatic_complexity, self.ncloc)
+        })
+    }
+}
+
+/// Computes the metrics of all functions with a body, in source order.
+pub fn calculate_function_metrics(
+    tree: &Tree,
+    source_code: &str,
+    test_code: &TestCode<'_>,
+) -> Result<Vec<FunctionMetrics>, AnalyzerError> {
+    let mut functions = NodeIterator::new(tree.root_node(), |node| {
+        node.kind() == "function_item" && node.child_by_field_name("body").is_some()
+    })
+    .filter_map(|function_item| {
+        function_item
+            .child_by_field_name("name")
+            .map(|name| (function_item, name))
+    })
+    .map(|(function_item, name)| {
+        Ok(FunctionMetrics {
+            name: qualified_name(function_item, name, source_code),
+            location: TreeSitterLocation::from_tree_sitter_node(name)
+                .to_sonar_location(source_code),
+            ncloc: lines_of_code(function_item),
+            cognitive_complexity: calculate_cognitive_complexity(function_item)?
+                .iter()
+                .map(|inc| inc.nesting + 1)
+                .sum(),
+            cyclomatic_complexity: calculate_node_cyclomatic_complexity(function_item)?,
+            parameters: parameter_count(function_item),
+            test: test_code.contains_node(function_item),
+            halstead: calculate_halstead_metrics(function_item, source_code)?,
+        })
+    })
+    .collect::<Result<Vec<_>, AnalyzerError>>()?;
+
+    functions.sort_by(|a, b| a.location.cmp(&b.location));
+    Ok(functions)
+}
+
+fn qualified_name(function_item: Node<'_>, name: Node<'_>, source_code: &str) -> String {
+    let name = &source_code[name.byte_range()];
+    let implemented_type = function_item
+        .parent()
+        .filter(|parent| parent.kind() == "declaration_list")
+        .and_then(|declaration_list| declaration_list.parent())
+        .filter(|item| item.kind() == "impl_item")
+        .and_then(|impl_item| impl_item.child_by_field_name("type"));
+
+    match implemented_type {
+        Some(implemented_type) => {
+            format!("{}::{}", &source_code[implemented_type.byte_range()], name)
+        }
+        None => name.to_string(),
+    }
+}
+
+/// Number of lines containing code in the node, as for the 'ncloc' metric: comments and blank lines do not count.
+pub(crate) fn lines_of_code(node: Node<'_>) -> i32 {
+    let mut lines = HashSet::new();
+    let mut stack = vec![node];
+    while let Some(node) = stack.pop() {
+        if node.is_extra() {
+            continue;
+        }
+        if node.child_count() == 0 {
+            lines.extend(node.start_position().row..=node.end_position().row);
+        }
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor));
+    }
+    lines.len() as i32
+}
+
+fn parameter_count(function_item: Node<'_>) -> i32 {
+    function_item
+        .child_by_field_name("parameters")
+        .map(|parameters| {
+            let mut cursor = parameters.walk();
+            let count = parameters
+                .named_children(&mut cursor)
+                .filter(|parameter| {
+                    matches!(
+                        parameter.kind(),
+                        "parameter" | "self_parameter" | "variadic_parameter"
+                    )
+                })
+                .count();
+            count as i32
+        })
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    #[test]
+    fn test_function_metrics() {
+        let source_code = r#"
+struct Point {
+    x: i32,
+}
+
+impl Point {
+    fn new(x: i32) -> Point {
+        Point { x }
+    }
+
+    // Comments are not code
+    fn shift(&mut self, dx: i32, dy: i32) {
+        if dx > 0 && dy > 0 {
+            self.x += dx;
+        }
+    }
+}
+
+fn declaration_only();
+
+fn main() {
+    fn nested() {}
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_function_metrics(
+            &tree,
+            source_code,
+            &TestCode::find(&tree, source_code, false),
+        )
+        .unwrap();
+
+        assert_eq!(
+            actual,
+            vec![
+                FunctionMetrics {
+                    name: "Point::new".to_string(),
+                    location: SonarLocation {
+                        start_line: 7,
+                        start_column: 7,
+                        end_line: 7,
+                        end_column: 10,
+                    },
+                    ncloc: 3,
+                    cognitive_complexity: 0,
+                    cyclomatic_complexity: 1,
+                    parameters: 1,
+                    test: false,
+                    halstead: Some(HalsteadMetrics {
+                        distinct_operators: 5,
+                        distinct_operands: 4,
+                        operators: 6,
+                        operands: 6,
+                    }),
+                },
+                FunctionMetrics {
+                    name: "Point::shift".to_string(),
+                    location: SonarLocation {
+                        start_line: 12,
+                        start_column: 7,
+                        end_line: 12,
+                        end_column: 12,
+                    },
+                    ncloc: 5,
+                    cognitive_complexity: 2,
+                    cyclomatic_complexity: 3,
+                    parameters: 3,
+                    test: false,
+                    halstead: Some(HalsteadMetrics {
+                        distinct_operators: 11,
+                        distinct_operands: 8,
+                        operators: 14,
+                        operands: 14,
+                    }),
+                },
+                FunctionMetrics {
+                    name: "main".to_string(),
+                    location: SonarLocation {
+                        start_line: 21,
+                        start_column: 3,
+                        end_line: 21,
+                        end_column: 7,
+                    },
+                    ncloc: 3,
+                    cognitive_complexity: 0,
+                    cyclomatic_complexity: 1,
+                    parameters: 0,
+                    test: false,
+                    halstead: Some(HalsteadMetrics {
+                        distinct_operators: 3,
+                        distinct_operands: 2,
+                        operators: 6,
+                        operands: 2,
+                    }),
+                },
+                FunctionMetrics {
+                    name: "nested".to_string(),
+                    location: SonarLocation {
+                        start_line: 22,
+                        start_column: 7,
+                        end_line: 22,
+                        end_column: 13,
+                    },
+                    ncloc: 1,
+                    cognitive_complexity: 0,
+                    cyclomatic_complexity: 0,
+                    parameters: 0,
+                    test: false,
+                    halstead: Some(HalsteadMetrics {
+                        distinct_operators: 3,
+                        distinct_operands: 1,
+                        operators: 3,
+                        operands: 1,
+                    }),
+                },
+            ]
+        );
+    }
+}
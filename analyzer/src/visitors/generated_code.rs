@@ -0,0 +1,229 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::tree::child_of_kind;
+use std::{ops::Range, str::FromStr};
+use tree_sitter::{Node, Tree};
+
+/// Markers of generated files in their leading comments, e.g. '// @generated by prost-build', the header of
+/// rust-bindgen, or '// Code generated by build.rs. DO NOT EDIT.'.
+pub const DEFAULT_GENERATED_MARKERS: [&str; 3] = [
+    "@generated",
+    "automatically generated by rust-bindgen",
+    "DO NOT EDIT",
+];
+
+pub fn default_generated_markers() -> Vec<String> {
+    DEFAULT_GENERATED_MARKERS
+        .iter()
+        .map(|marker| marker.to_string())
+        .collect()
+}
+
+/// How generated files are analyzed, see the 'generated_code' analysis setting.
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+pub enum GeneratedCodeMode {
+    /// Analyze generated files as any other file.
+    Analyze,
+    /// Only compute the highlighting and the metrics of generated files, without issues nor CPD tokens.
+    #[default]
+    Suppress,
+    /// Do not analyze generated files, the output is empty.
+    Skip,
+}
+
+impl FromStr for GeneratedCodeMode {
+    type Err = String;
+
+    fn from_str(mode: &str) -> Result<Self, Self::Err> {
+        match mode {
+            "analyze" => Ok(GeneratedCodeMode::Analyze),
+            "suppress" => Ok(GeneratedCodeMode::Suppress),
+            "skip" => Ok(GeneratedCodeMode::Skip),
+            _ => Err(format!(
+                "unknown mode '{}', expected 'analyze', 'suppress' or 'skip'",
+                mode
+            )),
+        }
+    }
+}
+
+/// Settings of the generated files.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct GeneratedCodeOptions {
+    pub mode: GeneratedCodeMode,
+    /// Markers of generated files in their leading comments.
+    pub markers: Vec<String>,
+}
+
+impl Default for GeneratedCodeOptions {
+    fn default() -> Self {
+        GeneratedCodeOptions {
+            mode: GeneratedCodeMode::default(),
+            markers: default_generated_markers(),
+        }
+    }
+}
+
+/// The parts of a file which are generated: the whole file when its leading comments contain a generated code marker
+/// (or its path matches the 'generated_paths' globs), otherwise the items annotated with `#[automatically_derived]`.
+#[derive(Debug, Default)]
+pub struct GeneratedCode {
+    generated_file: bool,
+    /// Byte ranges of the generated items, including their attributes, or of the whole file.
+    ranges: Vec<Range<usize>>,
+}
+
+impl GeneratedCode {
+    pub fn find(tree: &Tree, source_code: &str, markers: &[String], generated_file: bool) -> Self {
+        let root = tree.root_node();
+        if generated_file || has_generated_marker(root, source_code, markers) {
+            return GeneratedCode {
+                generated_file: true,
+                ranges: vec![Range {
+                    start: 0,
+                    end: source_code.len(),
+                }],
+            };
+        }
+        GeneratedCode {
+            generated_file: false,
+            ranges: automatically_derived_items(root, source_code),
+        }
+    }
+
+    /// Whether the whole file is generated.
+    pub fn is_generated_file(&self) -> bool {
+        self.generated_file
+    }
+
+    pub fn contains_byte(&self, byte: usize) -> bool {
+        self.ranges.iter().any(|range| range.contains(&byte))
+    }
+}
+
+/// Whether a comment before the first item, other than inner attributes, contains one of the markers.
+fn has_generated_marker(root: Node<'_>, source_code: &str, markers: &[String]) -> bool {
+    let mut cursor = root.walk();
+    let result = root
+        .children(&mut cursor)
+        .take_while(|child| {
+            child.is_extra() || matches!(child.kind(), "inner_attribute_item" | "shebang")
+        })
+        .filter(|child| child.is_extra())
+        .any(|comment| {
+            let text = &source_code[comment.byte_range()];
+            markers.iter().any(|marker| text.contains(marker.as_str()))
+        });
+    result
+}
+
+fn automatically_derived_items(root: Node<'_>, source_code: &str) -> Vec<Range<usize>> {
+    let mut ranges = vec![];
+    let mut stack = vec![root];
+    while let Some(node) = stack.pop() {
+        let mut cursor = node.walk();
+        let mut generated_attribute_start = None;
+        for child in node.children(&mut cursor) {
+            if is_automatically_derived_attribute(child, source_code) {
+                generated_attribute_start.get_or_insert(child.start_byte());
+            } else if child.kind() == "attribute_item" || child.is_extra() {
+                // Other attributes and comments may stand between the attribute and the item
+            } else if let Some(start) = generated_attribute_start.take() {
+                ranges.push(start..child.end_byte());
+            } else {
+                stack.push(child);
+            }
+        }
+    }
+    ranges
+}
+
+fn is_automatically_derived_attribute(node: Node<'_>, source_code: &str) -> bool {
+    node.kind() == "attribute_item"
+        && child_of_kind(node, "attribute")
+            .filter(|attribute| attribute.child_by_field_name("arguments").is_none())
+            .and_then(|attribute| attribute.named_child(0))
+            .is_some_and(|name| &source_code[name.byte_range()] == "automatically_derived")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    fn is_generated_file(source_code: &str, markers: &[String]) -> bool {
+        let tree = parse_rust_code(source_code).unwrap();
+        GeneratedCode::find(&tree, source_code, markers, false).is_generated_file()
+    }
+
+    #[test]
+    fn test_generated_files() {
+        let markers = default_generated_markers();
+        assert!(is_generated_file("// @generated\nfn foo() {}", &markers));
+        assert!(is_generated_file(
+            "/* automatically generated by rust-bindgen 0.69.4 */\n#![allow(dead_code)]\nfn foo() {}",
+            &markers
+        ));
+        assert!(is_generated_file(
+            "// This file is @generated by prost-build.\npub struct Foo {}",
+            &markers
+        ));
+        assert!(is_generated_file(
+            "//! Code generated by build.rs, DO NOT EDIT\nfn foo() {}",
+            &markers
+        ));
+        // Markers after the first item do not denote generated files
+        assert!(!is_generated_file("fn foo() {}\n// @generated", &markers));
+
+        let markers = vec!["Generated by xtask".to_string()];
+        assert!(is_generated_file(
+            "// Generated by xtask\nfn foo() {}",
+            &markers
+        ));
+        assert!(!is_generated_file("// @generated\nfn foo() {}", &markers));
+    }
+
+    #[test]
+    fn test_generated_paths() {
+        let source_code = "fn foo() {}";
+        let tree = parse_rust_code(source_code).unwrap();
+        let generated_code = GeneratedCode::find(&tree, source_code, &[], true);
+        assert!(generated_code.is_generated_file());
+        assert!(generated_code.contains_byte(0));
+    }
+
+    #[test]
+    fn test_automatically_derived_items() {
+        let source_code = "struct A;\n#[automatically_derived]\nimpl Clone for A {}\n";
+        let tree = parse_rust_code(source_code).unwrap();
+        let generated_code =
+            GeneratedCode::find(&tree, source_code, &default_generated_markers(), false);
+        assert!(!generated_code.is_generated_file());
+        assert!(!generated_code.contains_byte(0));
+        assert!(generated_code.contains_byte(10));
+        assert!(generated_code.contains_byte(source_code.len() - 2));
+    }
+
+    #[test]
+    fn test_modes() {
+        assert_eq!("skip".parse(), Ok(GeneratedCodeMode::Skip));
+        assert_eq!(
+            "ignore".parse::<GeneratedCodeMode>(),
+            Err("unknown mode 'ignore', expected 'analyze', 'suppress' or 'skip'".to_string())
+        );
+    }
+}
@@ -0,0 +1,191 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::tree::{walk_tree, AnalyzerError, NodeVisitor};
+use std::collections::HashSet;
+use tree_sitter::Node;
+
+/// Halstead counts of the tokens of some code, from which the volume, difficulty and effort are derived.
+///
+/// Operands are identifiers and literals, operators are all the other tokens (keywords and punctuation). Closing
+/// delimiters are not counted, as a pair of delimiters is one operator.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
+pub struct HalsteadMetrics {
+    pub distinct_operators: i32,
+    pub distinct_operands: i32,
+    pub operators: i32,
+    pub operands: i32,
+}
+
+impl HalsteadMetrics {
+    pub fn vocabulary(&self) -> i32 {
+        self.distinct_operators + self.distinct_operands
+    }
+
+    pub fn length(&self) -> i32 {
+        self.operators + self.operands
+    }
+
+    pub fn volume(&self) -> f64 {
+        let vocabulary = self.vocabulary();
+        if vocabulary == 0 {
+            return 0.0;
+        }
+        self.length() as f64 * (vocabulary as f64).log2()
+    }
+
+    pub fn difficulty(&self) -> f64 {
+        if self.distinct_operands == 0 {
+            return 0.0;
+        }
+        (self.distinct_operators as f64 / 2.0)
+            * (self.operands as f64 / self.distinct_operands as f64)
+    }
+
+    pub fn effort(&self) -> f64 {
+        self.difficulty() * self.volume()
+    }
+}
+
+/// Maintainability index normalized between 0 (hard to maintain) and 100, from the Halstead volume, the cyclomatic
+/// complexity and the lines of code of some code.
+pub fn maintainability_index(volume: f64, cyclomatic_complexity: i32, ncloc: i32) -> f64 {
+    let index = 171.0
+        - 5.2 * volume.max(1.0).ln()
+        - 0.23 * cyclomatic_complexity as f64
+        - 16.2 * (ncloc.max(1) as f64).ln();
+    (index * 100.0 / 171.0).clamp(0.0, 100.0)
+}
+
+/// Computes the Halstead counts of a subtree, e.g. of a single function, or `None` if it has no tokens.
+pub fn calculate_halstead_metrics(
+    node: Node<'_>,
+    source_code: &str,
+) -> Result<Option<HalsteadMetrics>, AnalyzerError> {
+    let mut visitor = HalsteadVisitor {
+        source_code,
+        counter: HalsteadCounter::default(),
+    };
+    walk_tree(node, &mut visitor)?;
+    Ok(visitor.counter.to_metrics())
+}
+
+/// Accumulates the operators and operands of the nodes it is given, see `add`.
+#[derive(Debug, Default)]
+pub(crate) struct HalsteadCounter<'a> {
+    distinct_operators: HashSet<&'a str>,
+    distinct_operands: HashSet<&'a str>,
+    operators: i32,
+    operands: i32,
+}
+
+impl<'a> HalsteadCounter<'a> {
+    /// Counts the node if it is a token, to be called on every node of the measured code.
+    pub(crate) fn add(&mut self, node: Node<'_>, source_code: &'a str) {
+        if node.is_extra() || node.byte_range().is_empty() || is_literal_part(node) {
+            return;
+        }
+
+        let text = &source_code[node.byte_range()];
+        if is_literal(node) || (node.child_count() == 0 && node.is_named()) {
+            self.operands += 1;
+            self.distinct_operands.insert(text);
+        } else if node.child_count() == 0 && !matches!(node.kind(), ")" | "]" | "}" | ">") {
+            self.operators += 1;
+            self.distinct_operators.insert(text);
+        }
+    }
+
+    pub(crate) fn to_metrics(&self) -> Option<HalsteadMetrics> {
+        if self.operators + self.operands == 0 {
+            return None;
+        }
+        Some(HalsteadMetrics {
+            distinct_operators: self.distinct_operators.len() as i32,
+            distinct_operands: self.distinct_operands.len() as i32,
+            operators: self.operators,
+            operands: self.operands,
+        })
+    }
+}
+
+struct HalsteadVisitor<'a> {
+    source_code: &'a str,
+    counter: HalsteadCounter<'a>,
+}
+
+impl NodeVisitor for HalsteadVisitor<'_> {
+    fn exit_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
+        self.counter.add(node, self.source_code);
+        Ok(())
+    }
+}
+
+/// Literals with children, which are counted as a single operand.
+fn is_literal(node: Node<'_>) -> bool {
+    matches!(
+        node.kind(),
+        "string_literal" | "raw_string_literal" | "boolean_literal"
+    )
+}
+
+fn is_literal_part(node: Node<'_>) -> bool {
+    node.parent().is_some_and(is_literal)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    fn halstead(source_code: &str) -> Option<HalsteadMetrics> {
+        let tree = parse_rust_code(source_code).unwrap();
+        calculate_halstead_metrics(tree.root_node(), source_code).unwrap()
+    }
+
+    #[test]
+    fn test_halstead_metrics() {
+        // Operators: fn ( { let = + ; (7 distinct, 11 total with the repeated 'let', '=', '+' and ';')
+        // Operands: add, x, 1, "a b", y (5 distinct, 7 total with the repeated x)
+        let actual = halstead(r#"fn add() { let x = 1 + x; let y = "a b" + x; }"#).unwrap();
+        assert_eq!(
+            actual,
+            HalsteadMetrics {
+                distinct_operators: 7,
+                distinct_operands: 5,
+                operators: 11,
+                operands: 7,
+            }
+        );
+        assert_eq!(actual.vocabulary(), 12);
+        assert_eq!(actual.length(), 18);
+        assert!((actual.volume() - 18.0 * 12f64.log2()).abs() < 1e-9);
+        assert!((actual.difficulty() - 3.5 * 7.0 / 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_no_tokens() {
+        assert_eq!(halstead(""), None);
+        assert_eq!(halstead("// Only a comment"), None);
+    }
+
+    #[test]
+    fn test_maintainability_index() {
+        assert_eq!(maintainability_index(0.0, 0, 0), 100.0);
+        assert!(maintainability_index(100.0, 2, 10) < maintainability_index(50.0, 1, 5));
+        assert_eq!(maintainability_index(1e30, 1000, 100_000), 0.0);
+    }
+}
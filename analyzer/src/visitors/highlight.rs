@@ -14,12 +14,16 @@
  * You should have received a copy of the Sonar Source-Available License
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
-use crate::tree::{AnalyzerError, SonarLocation, TreeSitterLocation};
-use std::collections::HashSet;
-use tree_sitter::{Node, Query, QueryCursor, StreamingIterator, Tree};
+use crate::{
+    tree::{
+        check_deadline, interrupted, AnalyzerError, NodeIterator, SonarLocation, TreeSitterLocation,
+    },
+    visitors::name_resolution::{resolve_bindings, Bindings, DeclarationKind, ItemKind},
+};
+use std::collections::{BTreeSet, HashSet};
+use tree_sitter::{Node, Query, QueryCursor, QueryCursorOptions, StreamingIterator, Tree};
 
 #[derive(Debug, PartialOrd, Ord, PartialEq, Eq, Clone)]
-#[allow(dead_code)]
 pub enum HighlightTokenType {
     Annotation,
     Constant,
@@ -42,10 +46,14 @@ impl HighlightTokenType {
         match name {
             "keyword" => Some(HighlightTokenType::Keyword),
             "comment" => Some(HighlightTokenType::Comment),
+            // String literals, including raw and byte strings, and char literals
             "string" => Some(HighlightTokenType::String),
             // Built-in constants are boolean, float and integer literals
             "constant.builtin" => Some(HighlightTokenType::Constant),
             "comment.documentation" => Some(HighlightTokenType::StructuredComment),
+            // Outer and inner attributes, including derives and attribute macros
+            "attribute" => Some(HighlightTokenType::Annotation),
+            "lifetime" | "type.parameter" => Some(HighlightTokenType::KeywordLight),
             _ => None,
         }
     }
@@ -64,18 +72,50 @@ impl HighlightTokenType {
     }
 }
 
+/// Captures added to the highlights query of the grammar, which only captures the name of lifetimes and does not
+/// distinguish generic parameters from other types. The metavariables and fragment specifiers of `macro_rules!` are
+/// the generic parameters of macros, except for `$crate` which is part of paths.
+const EXTRA_HIGHLIGHTS_QUERY: &str = r#"
+(lifetime) @lifetime
+(type_parameters (type_identifier) @type.parameter)
+(constrained_type_parameter left: (type_identifier) @type.parameter)
+(optional_type_parameter name: (type_identifier) @type.parameter)
+(const_parameter name: (identifier) @type.parameter)
+((metavariable) @type.parameter (#not-eq? @type.parameter "$crate"))
+(fragment_specifier) @type.parameter
+"#;
+
 pub fn highlight(tree: &Tree, source_code: &str) -> Result<Vec<HighlightToken>, AnalyzerError> {
+    highlight_with_bindings(tree, source_code, &resolve_bindings(tree, source_code)?)
+}
+
+/// Highlighting of the file, where the names of the file are highlighted by what they declare, see
+/// `semantic_tokens`.
+pub fn highlight_with_bindings(
+    tree: &Tree,
+    source_code: &str,
+    bindings: &Bindings,
+) -> Result<Vec<HighlightToken>, AnalyzerError> {
     let highlight_query = Query::new(
         &tree_sitter_rust::LANGUAGE.into(),
-        tree_sitter_rust::HIGHLIGHTS_QUERY,
+        &format!(
+            "{}\n{}",
+            tree_sitter_rust::HIGHLIGHTS_QUERY,
+            EXTRA_HIGHLIGHTS_QUERY
+        ),
     )
     .map_err(|err| {
         AnalyzerError::GlobalError(format!("Failed to create highlight query: {}", err))
     })?;
 
     let mut cursor = QueryCursor::new();
-    let mut query_matches =
-        cursor.matches(&highlight_query, tree.root_node(), source_code.as_bytes());
+    let mut progress = |_: &_| interrupted();
+    let mut query_matches = cursor.matches_with_options(
+        &highlight_query,
+        tree.root_node(),
+        source_code.as_bytes(),
+        QueryCursorOptions::new().progress_callback(&mut progress),
+    );
 
     let mut tokens: Vec<HighlightToken> = Vec::new();
     let capture_names = highlight_query.capture_names();
@@ -106,6 +146,9 @@ pub fn highlight(tree: &Tree, source_code: &str) -> Result<Vec<HighlightToken>,
         }
     }
 
+    // Query execution halts when the progress callback reports that the deadline is exceeded
+    check_deadline()?;
+
     let comments_without_doc_comments = &comments - &doc_comments;
     for comment in comments_without_doc_comments {
         tokens.push(HighlightToken {
@@ -115,5 +158,291 @@ pub fn highlight(tree: &Tree, source_code: &str) -> Result<Vec<HighlightToken>,
         });
     }
 
+    tokens.extend(
+        macro_invocation_names(tree.root_node()).map(|(start, end)| HighlightToken {
+            token_type: HighlightTokenType::PreprocessDirective,
+            location: TreeSitterLocation {
+                start_byte: start.start_byte(),
+                end_byte: end.end_byte(),
+                start_position: start.start_position(),
+                end_position: end.end_position(),
+            }
+            .to_sonar_location(source_code),
+        }),
+    );
+
+    // The names of the lifetimes can be keywords, e.g. 'static, which are highlighted with the whole lifetime
+    let lifetimes: Vec<(Node<'_>, Node<'_>)> = token_tree_lifetimes(tree.root_node()).collect();
+    let names: BTreeSet<SonarLocation> = lifetimes
+        .iter()
+        .map(|(_, name)| {
+            TreeSitterLocation::from_tree_sitter_node(*name).to_sonar_location(source_code)
+        })
+        .collect();
+    tokens.retain(|token| !names.contains(&token.location));
+    tokens.extend(lifetimes.into_iter().map(|(quote, name)| {
+        HighlightToken {
+            token_type: HighlightTokenType::KeywordLight,
+            location: TreeSitterLocation {
+                start_byte: quote.start_byte(),
+                end_byte: name.end_byte(),
+                start_position: quote.start_position(),
+                end_position: name.end_position(),
+            }
+            .to_sonar_location(source_code),
+        }
+    }));
+
+    let semantic_tokens = semantic_tokens(bindings, &tokens);
+    tokens.extend(semantic_tokens);
+
     Ok(tokens)
 }
+
+/// Highlighting of the declarations and references of constants and types, which keeps the functions and the local
+/// variables in the default style.
+///
+/// The constants and statics are highlighted as constants, the structs, enums, unions, traits and type aliases as
+/// light keywords, like the generic parameters. The kind of an imported name is given by the naming conventions, e.g.
+/// `MAX_SIZE` is a constant and `HashMap` a type. The names inside attributes and macro names, already highlighted,
+/// are not highlighted again.
+fn semantic_tokens(bindings: &Bindings, tokens: &[HighlightToken]) -> Vec<HighlightToken> {
+    let enclosing_tokens: Vec<&SonarLocation> = tokens
+        .iter()
+        .filter(|token| {
+            matches!(
+                token.token_type,
+                HighlightTokenType::Annotation | HighlightTokenType::PreprocessDirective
+            )
+        })
+        .map(|token| &token.location)
+        .collect();
+    let highlighted: BTreeSet<&SonarLocation> =
+        tokens.iter().map(|token| &token.location).collect();
+
+    let mut semantic_tokens = vec![];
+    for declaration in &bindings.declarations {
+        let token_type = match declaration.kind {
+            DeclarationKind::Item(ItemKind::Const | ItemKind::Static) => {
+                HighlightTokenType::Constant
+            }
+            DeclarationKind::Item(kind) if kind.is_type() => HighlightTokenType::KeywordLight,
+            DeclarationKind::Import { .. } if is_constant_name(&declaration.name) => {
+                HighlightTokenType::Constant
+            }
+            DeclarationKind::Import { .. } if declaration.name.starts_with(char::is_uppercase) => {
+                HighlightTokenType::KeywordLight
+            }
+            _ => continue,
+        };
+        for location in std::iter::once(&declaration.location).chain(&declaration.references) {
+            let enclosed = enclosing_tokens
+                .iter()
+                .any(|enclosing| contains(enclosing, location));
+            if !enclosed && !highlighted.contains(location) {
+                semantic_tokens.push(HighlightToken {
+                    token_type: token_type.clone(),
+                    location: location.clone(),
+                });
+            }
+        }
+    }
+    semantic_tokens
+}
+
+/// Whether the name follows the naming convention of constants, e.g. `MAX_SIZE`.
+fn is_constant_name(name: &str) -> bool {
+    name.len() > 1
+        && name.starts_with(|c: char| c.is_ascii_uppercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_')
+}
+
+fn contains(outer: &SonarLocation, inner: &SonarLocation) -> bool {
+    (outer.start_line, outer.start_column) <= (inner.start_line, inner.start_column)
+        && (inner.end_line, inner.end_column) <= (outer.end_line, outer.end_column)
+}
+
+/// Quote and name of the lifetimes in token trees, e.g. in the bodies of `macro_rules!`, where they are not parsed.
+fn token_tree_lifetimes(root: Node<'_>) -> impl Iterator<Item = (Node<'_>, Node<'_>)> {
+    NodeIterator::new(root, |node| node.kind() == "token_tree").flat_map(|node| {
+        let mut cursor = node.walk();
+        let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+        children
+            .windows(2)
+            .filter(|pair| {
+                pair[0].kind() == "'"
+                    && matches!(pair[1].kind(), "identifier" | "static")
+                    && pair[0].end_byte() == pair[1].start_byte()
+            })
+            .map(|pair| (pair[0], pair[1]))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// First and last nodes of the path and bang of each macro invocation (e.g. `std::vec!`).
+///
+/// The arguments of macros are not parsed, so invocations nested in the arguments of other macros are recognized
+/// from their tokens: a path followed by a bang and a token tree.
+fn macro_invocation_names(root: Node<'_>) -> impl Iterator<Item = (Node<'_>, Node<'_>)> {
+    NodeIterator::new(root, |node| {
+        matches!(node.kind(), "macro_invocation" | "token_tree")
+    })
+    .flat_map(|node| {
+        let mut cursor = node.walk();
+        let children: Vec<Node<'_>> = node.children(&mut cursor).collect();
+        if node.kind() == "macro_invocation" {
+            let path = node.child_by_field_name("macro");
+            let bang = children.iter().find(|child| child.kind() == "!");
+            return path.zip(bang.copied()).into_iter().collect::<Vec<_>>();
+        }
+
+        let mut names = vec![];
+        for (index, window) in children.windows(3).enumerate() {
+            if window[0].kind() == "identifier"
+                && window[1].kind() == "!"
+                && window[2].kind() == "token_tree"
+            {
+                let mut start = index;
+                while start >= 2
+                    && children[start - 1].kind() == "::"
+                    && matches!(
+                        children[start - 2].kind(),
+                        "identifier" | "self" | "crate" | "super" | "metavariable"
+                    )
+                {
+                    start -= 2;
+                }
+                names.push((children[start], window[1]));
+            }
+        }
+        names
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    fn highlighted(source_code: &str, token_type: HighlightTokenType) -> Vec<String> {
+        let tree = parse_rust_code(source_code).unwrap();
+        let mut tokens = highlight(&tree, source_code).unwrap();
+        tokens.sort();
+        tokens
+            .into_iter()
+            .filter(|token| token.token_type == token_type)
+            .map(|token| {
+                let line = source_code
+                    .lines()
+                    .nth(token.location.start_line - 1)
+                    .unwrap();
+                line[token.location.start_column..token.location.end_column].to_string()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_attributes() {
+        let source_code =
+            "#![allow(dead_code)]\n#[derive(Debug, Clone)]\n#[tokio::main]\nstruct S;";
+        assert_eq!(
+            highlighted(source_code, HighlightTokenType::Annotation),
+            vec![
+                "#![allow(dead_code)]",
+                "#[derive(Debug, Clone)]",
+                "#[tokio::main]"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lifetimes_and_generic_parameters() {
+        let source_code =
+            "fn f<'a, T: Clone, U, const N: usize>(x: &'a T) -> &'static U {}\nstruct S<V = u8>(V);";
+        assert_eq!(
+            highlighted(source_code, HighlightTokenType::KeywordLight),
+            vec!["'a", "T", "U", "N", "'a", "'static", "S", "V"]
+        );
+    }
+
+    #[test]
+    fn test_macro_invocations() {
+        let source_code = r#"fn f() { println!("{:?}", vec![1]); std::format!("{}", inner!(x)); }"#;
+        assert_eq!(
+            highlighted(source_code, HighlightTokenType::PreprocessDirective),
+            vec!["println!", "vec!", "std::format!", "inner!"]
+        );
+    }
+
+    #[test]
+    fn test_macro_invocations_in_macro_definitions() {
+        let source_code = "macro_rules! m {\n    () => { $crate::helper!(); assert!(true) };\n}";
+        assert_eq!(
+            highlighted(source_code, HighlightTokenType::PreprocessDirective),
+            vec!["$crate::helper!", "assert!"]
+        );
+    }
+
+    #[test]
+    fn test_semantic_tokens() {
+        let source_code = r#"
+use std::collections::HashMap;
+use std::u32::MAX;
+use serde::Serialize;
+const LIMIT: u32 = MAX;
+static mut COUNT: u32 = 0;
+#[derive(Serialize)]
+struct Point { x: u32 }
+type Points = HashMap<u32, Point>;
+fn count(points: &Points, limit: u32) -> u32 {
+    let total = points.len() as u32;
+    total.min(LIMIT).min(limit)
+}
+"#;
+        assert_eq!(
+            highlighted(source_code, HighlightTokenType::Constant),
+            vec!["MAX", "LIMIT", "MAX", "COUNT", "0", "LIMIT"]
+        );
+        assert_eq!(
+            highlighted(source_code, HighlightTokenType::KeywordLight),
+            vec![
+                "HashMap",
+                "Serialize",
+                "Point",
+                "Points",
+                "HashMap",
+                "Point",
+                "Points"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_macro_definitions() {
+        let source_code = "macro_rules! m {\n    ($name:ident, $t:ty) => { fn $name(x: &'static $t) { $crate::f(x) } };\n}";
+        assert_eq!(
+            highlighted(source_code, HighlightTokenType::KeywordLight),
+            vec!["$name", "ident", "$t", "ty", "$name", "'static", "$t"]
+        );
+    }
+
+    #[test]
+    fn test_string_literals() {
+        let source_code =
+            r####"fn f() { (r#"a"#, b"b", br##"c"#"##, 'd', b'e', c"f", "\"g\"") }"####;
+        assert_eq!(
+            highlighted(source_code, HighlightTokenType::String),
+            vec![
+                r###"r#"a"#"###,
+                r#"b"b""#,
+                r###"br##"c"#"##"###,
+                "'d'",
+                "b'e'",
+                r#"c"f""#,
+                r#""\"g\"""#
+            ]
+        );
+    }
+}
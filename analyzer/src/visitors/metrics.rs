@@ -14,10 +14,15 @@
  * You should have received a copy of the Sonar Source-Available License
  * along with this program; if not, see https://sonarsource.com/license/ssal/
  */
-use crate::tree::{walk_tree, AnalyzerError, NodeVisitor};
-use crate::visitors::cognitive_complexity::calculate_total_cognitive_complexity;
-use crate::visitors::cyclomatic_complexity::calculate_cyclomatic_complexity;
-use std::collections::HashSet;
+use crate::tree::{child_of_kind, split_lines, walk_tree, AnalyzerError, NodeVisitor};
+use crate::visitors::cognitive_complexity::calculate_cognitive_complexity;
+use crate::visitors::cyclomatic_complexity::{
+    calculate_cyclomatic_complexity, calculate_node_cyclomatic_complexity,
+};
+use crate::visitors::function_metrics::{calculate_function_metrics, FunctionMetrics};
+use crate::visitors::halstead::{maintainability_index, HalsteadCounter, HalsteadMetrics};
+use crate::visitors::test_code::TestCode;
+use std::collections::{BTreeSet, HashSet};
 use tree_sitter::{Node, Tree};
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Default)]
@@ -26,42 +31,200 @@ pub struct Metrics {
     pub comment_lines: i32,
     pub functions: i32,
     pub statements: i32,
+    /// Structs and enums.
     pub classes: i32,
+    pub structs: i32,
+    pub enums: i32,
+    pub traits: i32,
+    pub impls: i32,
+    pub modules: i32,
+    /// Public functions, structs, enums and traits.
+    pub public_api: i32,
+    /// Public items preceded by a doc comment or a `doc` attribute.
+    pub public_documented_api: i32,
+    /// Names exported by the file: public items, exported macros and re-exported names, see `exported_items`.
+    pub exported_items: i32,
+    /// `unsafe` blocks, including the nested ones.
+    pub unsafe_blocks: i32,
+    /// `unsafe fn` items with a body.
+    pub unsafe_functions: i32,
+    /// `unsafe impl` items.
+    pub unsafe_impls: i32,
+    /// Lines of code inside the unsafe regions, see `is_unsafe_region`.
+    pub unsafe_lines: i32,
     pub cognitive_complexity: i32,
     pub cyclomatic_complexity: i32,
+    /// Halstead counts of the tokens, if there are any.
+    pub halstead: Option<HalsteadMetrics>,
+    /// Lines containing code (1-based), the 'ncloc' metric is their count.
+    pub ncloc_data: BTreeSet<usize>,
+    /// Lines containing a comment (1-based), the 'comment_lines' metric is their count.
+    pub comment_lines_data: BTreeSet<usize>,
+    /// Lines containing a statement which can be covered by tests (1-based).
+    pub executable_lines: BTreeSet<usize>,
+    /// Number of functions per range of cyclomatic complexity, as pairs of the lower bound of the range and the count,
+    /// see `FUNCTION_COMPLEXITY_DISTRIBUTION_LIMITS`.
+    pub function_complexity_distribution: Vec<(i32, i32)>,
 }
 
+impl Metrics {
+    /// Maintainability index of the file, if it has any token, see `maintainability_index`.
+    pub fn maintainability_index(&self) -> Option<f64> {
+        self.halstead.as_ref().map(|halstead| {
+            maintainability_index(halstead.volume(), self.cyclomatic_complexity, self.ncloc)
+        })
+    }
+
+    /// Percentage of the public items that are documented, if there are any.
+    pub fn public_documented_api_density(&self) -> Option<f64> {
+        if self.public_api == 0 {
+            None
+        } else {
+            Some(100.0 * self.public_documented_api as f64 / self.public_api as f64)
+        }
+    }
+}
+
+/// Lower bounds of the ranges of the Sonar 'function_complexity_distribution' measure.
+pub const FUNCTION_COMPLEXITY_DISTRIBUTION_LIMITS: [i32; 7] = [1, 2, 4, 6, 8, 10, 12];
+
+/// Metrics of a file, with the test code measured apart from the production code.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct FileMetrics {
+    /// Metrics of the production code.
+    pub metrics: Metrics,
+    /// Metrics of the test code, see `TestCode`.
+    pub test_metrics: Metrics,
+    /// Metrics of each function of the file, both production and test code.
+    pub functions: Vec<FunctionMetrics>,
+}
+
+/// Computes the metrics of the production code of a file.
 pub fn calculate_metrics(tree: &Tree, source_code: &str) -> Result<Metrics, AnalyzerError> {
-    let mut metrics_visitor = MetricsVisitor::new(source_code);
-    walk_tree(tree.root_node(), &mut metrics_visitor)?;
+    calculate_file_metrics(tree, source_code, false).map(|file_metrics| file_metrics.metrics)
+}
+
+/// Computes the metrics of the production code and of the test code of a file, along with the metrics of each function.
+///
+/// The test files, see `is_test_file`, are measured as a whole in the metrics of the production code, so that their
+/// lines are reported like the lines of the other files.
+pub fn calculate_file_metrics(
+    tree: &Tree,
+    source_code: &str,
+    test_file: bool,
+) -> Result<FileMetrics, AnalyzerError> {
+    let test_code = if test_file {
+        TestCode::default()
+    } else {
+        TestCode::find(tree, source_code, false)
+    };
+    let functions = calculate_function_metrics(tree, source_code, &test_code)?;
+
+    let increments = calculate_cognitive_complexity(tree.root_node())?;
+    let cyclomatic_complexity = calculate_cyclomatic_complexity(tree)?;
+    let test_cyclomatic_complexity = test_code
+        .nodes()
+        .iter()
+        .map(|node| calculate_node_cyclomatic_complexity(*node))
+        .sum::<Result<i32, AnalyzerError>>()?;
+
+    let mut file_metrics = FileMetrics::default();
+    for (test, metrics) in [
+        (false, &mut file_metrics.metrics),
+        (true, &mut file_metrics.test_metrics),
+    ] {
+        let mut metrics_visitor = MetricsVisitor::new(source_code, &test_code, test);
+        walk_tree(tree.root_node(), &mut metrics_visitor)?;
+        metrics_visitor.update_metrics(metrics);
 
-    let mut metrics = Metrics::default();
-    metrics_visitor.update_metrics(&mut metrics);
-    metrics.cognitive_complexity = calculate_total_cognitive_complexity(tree)?;
-    metrics.cyclomatic_complexity = calculate_cyclomatic_complexity(tree)?;
+        metrics.cognitive_complexity = increments
+            .iter()
+            .filter(|inc| test_code.contains(inc.location.start_byte) == test)
+            .map(|inc| inc.nesting + 1)
+            .sum();
+        metrics.cyclomatic_complexity = if test {
+            test_cyclomatic_complexity
+        } else {
+            cyclomatic_complexity - test_cyclomatic_complexity
+        };
+        let functions: Vec<&FunctionMetrics> =
+            functions.iter().filter(|f| f.test == test).collect();
+        metrics.function_complexity_distribution = complexity_distribution(&functions);
+    }
+    file_metrics.functions = functions;
+
+    Ok(file_metrics)
+}
 
-    Ok(metrics)
+fn complexity_distribution(functions: &[&FunctionMetrics]) -> Vec<(i32, i32)> {
+    let mut distribution: Vec<(i32, i32)> = FUNCTION_COMPLEXITY_DISTRIBUTION_LIMITS
+        .iter()
+        .map(|limit| (*limit, 0))
+        .collect();
+    for function in functions {
+        // Functions with an empty body have a complexity of 0, they belong to the first range
+        let range = distribution
+            .iter()
+            .rposition(|(limit, _)| function.cyclomatic_complexity >= *limit)
+            .unwrap_or(0);
+        distribution[range].1 += 1;
+    }
+    distribution
 }
 
 #[derive(Debug)]
 struct MetricsVisitor<'a> {
     source_code: &'a str,
+    test_code: &'a TestCode<'a>,
+    /// Whether the test code is measured rather than the production code.
+    test: bool,
     comment_lines: HashSet<usize>,
     lines_of_code: HashSet<usize>,
+    executable_lines: BTreeSet<usize>,
     functions: i32,
     statements: i32,
-    classes: i32,
+    structs: i32,
+    enums: i32,
+    traits: i32,
+    impls: i32,
+    modules: i32,
+    public_api: i32,
+    public_documented_api: i32,
+    exported_items: i32,
+    unsafe_blocks: i32,
+    unsafe_functions: i32,
+    unsafe_impls: i32,
+    /// Number of unsafe regions containing the visited node.
+    unsafe_depth: usize,
+    unsafe_lines: HashSet<usize>,
+    halstead: HalsteadCounter<'a>,
 }
 
 impl<'a> MetricsVisitor<'a> {
-    fn new(source_code: &'a str) -> Self {
+    fn new(source_code: &'a str, test_code: &'a TestCode<'a>, test: bool) -> Self {
         Self {
             source_code,
+            test_code,
+            test,
             comment_lines: HashSet::new(),
             lines_of_code: HashSet::new(),
+            executable_lines: BTreeSet::new(),
             functions: 0,
             statements: 0,
-            classes: 0,
+            structs: 0,
+            enums: 0,
+            traits: 0,
+            impls: 0,
+            modules: 0,
+            public_api: 0,
+            public_documented_api: 0,
+            exported_items: 0,
+            unsafe_blocks: 0,
+            unsafe_functions: 0,
+            unsafe_impls: 0,
+            unsafe_depth: 0,
+            unsafe_lines: HashSet::new(),
+            halstead: HalsteadCounter::default(),
         }
     }
 
@@ -70,27 +233,80 @@ impl<'a> MetricsVisitor<'a> {
         metrics.comment_lines = self.comment_lines.len() as i32;
         metrics.functions = self.functions;
         metrics.statements = self.statements;
-        metrics.classes = self.classes;
+        metrics.classes = self.structs + self.enums;
+        metrics.structs = self.structs;
+        metrics.enums = self.enums;
+        metrics.traits = self.traits;
+        metrics.impls = self.impls;
+        metrics.modules = self.modules;
+        metrics.public_api = self.public_api;
+        metrics.public_documented_api = self.public_documented_api;
+        metrics.exported_items = self.exported_items;
+        metrics.unsafe_blocks = self.unsafe_blocks;
+        metrics.unsafe_functions = self.unsafe_functions;
+        metrics.unsafe_impls = self.unsafe_impls;
+        metrics.unsafe_lines = self.unsafe_lines.len() as i32;
+        metrics.halstead = self.halstead.to_metrics();
+        metrics.ncloc_data = self.lines_of_code.iter().map(|line| line + 1).collect();
+        metrics.comment_lines_data = self.comment_lines.iter().map(|line| line + 1).collect();
+        metrics.executable_lines = self.executable_lines.clone();
     }
 }
 
 impl NodeVisitor for MetricsVisitor<'_> {
+    fn enter_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
+        if is_unsafe_region(node) {
+            self.unsafe_depth += 1;
+        }
+        Ok(())
+    }
+
     fn exit_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
+        let in_unsafe_region = self.unsafe_depth > 0;
+        if is_unsafe_region(node) {
+            self.unsafe_depth -= 1;
+        }
+        if self.test_code.contains_node(node) != self.test {
+            return Ok(());
+        }
+
         match node.kind() {
+            // The interpreter line of scripts, e.g. '#!/usr/bin/env cargo'
+            "shebang" => return Ok(()),
             "line_comment" | "block_comment" => {
-                let mut current_line = node.start_position().row;
-                for line in self.source_code[node.start_byte()..node.end_byte()].lines() {
+                let lines = split_lines(&self.source_code[node.start_byte()..node.end_byte()]);
+                for (current_line, line) in (node.start_position().row..).zip(lines) {
                     if !is_blank(line) {
                         self.comment_lines.insert(current_line);
                     }
-                    current_line += 1;
                 }
             }
-            "struct_item" | "enum_item" => {
-                self.classes += 1;
+            "struct_item" => {
+                self.structs += 1;
+            }
+            "enum_item" => {
+                self.enums += 1;
+            }
+            "trait_item" => {
+                self.traits += 1;
+            }
+            "impl_item" => {
+                self.impls += 1;
+                if has_unsafe_keyword(node) {
+                    self.unsafe_impls += 1;
+                }
+            }
+            "unsafe_block" => {
+                self.unsafe_blocks += 1;
+            }
+            "mod_item" => {
+                self.modules += 1;
             }
             "function_item" => {
                 self.functions += 1;
+                if is_unsafe_function(node) {
+                    self.unsafe_functions += 1;
+                }
             }
             "expression_statement" | "let_declaration" | "empty_statement" => {
                 self.statements += 1;
@@ -98,12 +314,28 @@ impl NodeVisitor for MetricsVisitor<'_> {
             _ => {}
         }
 
+        self.halstead.add(node, self.source_code);
+        self.exported_items += exported_items(node, self.source_code);
+        if is_public_api(node) {
+            self.public_api += 1;
+            if is_documented(node, self.source_code) {
+                self.public_documented_api += 1;
+            }
+        }
+
+        if is_executable(node) {
+            self.executable_lines.insert(node.start_position().row + 1);
+        }
+
         if node.child_count() == 0 {
             let start_line = node.start_position().row;
             let end_line = node.end_position().row;
 
             for line in start_line..=end_line {
                 self.lines_of_code.insert(line);
+                if in_unsafe_region && !node.is_extra() {
+                    self.unsafe_lines.insert(line);
+                }
             }
         }
 
@@ -111,6 +343,136 @@ impl NodeVisitor for MetricsVisitor<'_> {
     }
 }
 
+/// Whether the node is an `unsafe` block or the body of an `unsafe fn`, in which unsafe operations are allowed.
+pub(crate) fn is_unsafe_region(node: Node<'_>) -> bool {
+    match node.kind() {
+        "unsafe_block" => true,
+        "block" => node.parent().is_some_and(|parent| {
+            is_unsafe_function(parent)
+                && parent
+                    .child_by_field_name("body")
+                    .is_some_and(|body| body.id() == node.id())
+        }),
+        _ => false,
+    }
+}
+
+/// Whether the node is an `unsafe fn` item with a body.
+pub(crate) fn is_unsafe_function(node: Node<'_>) -> bool {
+    node.kind() == "function_item"
+        && node.child_by_field_name("body").is_some()
+        && child_of_kind(node, "function_modifiers").is_some_and(has_unsafe_keyword)
+}
+
+fn has_unsafe_keyword(node: Node<'_>) -> bool {
+    child_of_kind(node, "unsafe").is_some()
+}
+
+/// Statements, and the expressions ending a block, which produce the value of the block.
+fn is_executable(node: Node<'_>) -> bool {
+    match node.kind() {
+        "expression_statement" | "let_declaration" => true,
+        kind => {
+            node.is_named()
+                && !node.is_extra()
+                && !kind.ends_with("_item")
+                && !matches!(kind, "empty_statement" | "attribute_item")
+                && node.parent().is_some_and(|parent| parent.kind() == "block")
+                && next_non_extra_sibling(node).is_some_and(|next| next.kind() == "}")
+        }
+    }
+}
+
+fn is_public_api(node: Node<'_>) -> bool {
+    matches!(
+        node.kind(),
+        "function_item" | "struct_item" | "enum_item" | "trait_item"
+    ) && is_public(node)
+}
+
+/// Number of names the item adds to the API of the crate: one for public items and exported macros, one per imported
+/// name for public re-exports.
+fn exported_items(node: Node<'_>, source_code: &str) -> i32 {
+    match node.kind() {
+        "function_item" | "struct_item" | "enum_item" | "union_item" | "type_item"
+        | "trait_item" | "const_item" | "static_item"
+            if is_public(node) =>
+        {
+            1
+        }
+        "macro_definition"
+            if preceding_attributes(node)
+                .any(|attribute| has_attribute_name(attribute, source_code, "macro_export")) =>
+        {
+            1
+        }
+        "use_declaration" if is_public(node) => node
+            .child_by_field_name("argument")
+            .map_or(0, imported_names),
+        _ => 0,
+    }
+}
+
+fn imported_names(use_tree: Node<'_>) -> i32 {
+    match use_tree.kind() {
+        "scoped_use_list" => use_tree
+            .child_by_field_name("list")
+            .map_or(0, imported_names),
+        "use_list" => {
+            let mut cursor = use_tree.walk();
+            use_tree
+                .named_children(&mut cursor)
+                .filter(|child| !child.is_extra())
+                .map(imported_names)
+                .sum()
+        }
+        _ => 1,
+    }
+}
+
+fn is_public(node: Node<'_>) -> bool {
+    child_of_kind(node, "visibility_modifier").is_some_and(|visibility| {
+        // Restricted visibilities such as `pub(crate)` are not part of the public API
+        visibility.child_count() == 1
+    })
+}
+
+/// Whether the item is preceded by an outer doc comment (`///` or `/** */`) or a `#[doc = "..."]` attribute.
+fn is_documented(node: Node<'_>, source_code: &str) -> bool {
+    preceding_attributes(node).any(|previous| match previous.kind() {
+        "attribute_item" => has_attribute_name(previous, source_code, "doc"),
+        _ => previous.child_by_field_name("outer").is_some(),
+    })
+}
+
+/// Attributes and comments directly preceding an item, closest first.
+pub(crate) fn preceding_attributes(node: Node<'_>) -> impl Iterator<Item = Node<'_>> {
+    std::iter::successors(node.prev_sibling(), |previous| previous.prev_sibling()).take_while(
+        |previous| {
+            matches!(
+                previous.kind(),
+                "attribute_item" | "line_comment" | "block_comment"
+            )
+        },
+    )
+}
+
+fn has_attribute_name(attribute_item: Node<'_>, source_code: &str, name: &str) -> bool {
+    attribute_item.kind() == "attribute_item"
+        && source_code[attribute_item.byte_range()]
+            .trim_start_matches("#[")
+            .trim_start()
+            .starts_with(name)
+}
+
+fn next_non_extra_sibling(node: Node<'_>) -> Option<Node<'_>> {
+    let mut sibling = node.next_sibling();
+    while let Some(next) = sibling.filter(|next| next.is_extra()) {
+        sibling = next.next_sibling();
+    }
+    sibling
+}
+
 fn is_blank(line: &str) -> bool {
     line.chars()
         .all(|c| c.is_whitespace() || c.is_ascii_punctuation())
@@ -141,8 +503,38 @@ fn main() {
                 functions: 1,
                 statements: 1,
                 classes: 0,
+                structs: 0,
+                enums: 0,
+                traits: 0,
+                impls: 0,
+                modules: 0,
+                public_api: 0,
+                public_documented_api: 0,
+                exported_items: 0,
+                unsafe_blocks: 0,
+                unsafe_functions: 0,
+                unsafe_impls: 0,
+                unsafe_lines: 0,
                 cognitive_complexity: 0,
-                cyclomatic_complexity: 1
+                cyclomatic_complexity: 1,
+                halstead: Some(HalsteadMetrics {
+                    distinct_operators: 6,
+                    distinct_operands: 3,
+                    operators: 6,
+                    operands: 3,
+                }),
+                ncloc_data: BTreeSet::from([3, 4, 5]),
+                comment_lines_data: BTreeSet::from([2, 4]),
+                executable_lines: BTreeSet::from([4]),
+                function_complexity_distribution: vec![
+                    (1, 1),
+                    (2, 0),
+                    (4, 0),
+                    (6, 0),
+                    (8, 0),
+                    (10, 0),
+                    (12, 0)
+                ],
             }
         );
     }
@@ -167,12 +559,60 @@ fn main() {
                 functions: 1,
                 statements: 1,
                 classes: 0,
+                structs: 0,
+                enums: 0,
+                traits: 0,
+                impls: 0,
+                modules: 0,
+                public_api: 0,
+                public_documented_api: 0,
+                exported_items: 0,
+                unsafe_blocks: 0,
+                unsafe_functions: 0,
+                unsafe_impls: 0,
+                unsafe_lines: 0,
                 cognitive_complexity: 0,
-                cyclomatic_complexity: 1
+                cyclomatic_complexity: 1,
+                halstead: Some(HalsteadMetrics {
+                    distinct_operators: 6,
+                    distinct_operands: 3,
+                    operators: 6,
+                    operands: 3,
+                }),
+                ncloc_data: BTreeSet::from([4, 5, 6]),
+                comment_lines_data: BTreeSet::from([2, 3]),
+                executable_lines: BTreeSet::from([5]),
+                function_complexity_distribution: vec![
+                    (1, 1),
+                    (2, 0),
+                    (4, 0),
+                    (6, 0),
+                    (8, 0),
+                    (10, 0),
+                    (12, 0)
+                ],
             }
         );
     }
 
+    #[test]
+    fn test_comment_metrics_line_endings() {
+        for line_ending in ["\n", "\r\n", "\r"] {
+            let source_code =
+                ["/* first", "", "third */", "// fourth", "fn main() {}", ""].join(line_ending);
+            let tree = parse_rust_code(&source_code).unwrap();
+            let actual = calculate_metrics(&tree, &source_code).unwrap();
+            assert_eq!(actual.comment_lines_data, BTreeSet::from([1, 3, 4]));
+            assert_eq!(actual.ncloc_data, BTreeSet::from([5]));
+        }
+
+        let source_code = "/* first\r\n\rthird */\n// fourth\r\nfn main() {}\r";
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+        assert_eq!(actual.comment_lines_data, BTreeSet::from([1, 3, 4]));
+        assert_eq!(actual.ncloc_data, BTreeSet::from([5]));
+    }
+
     #[test]
     fn test_comment_metrics_empty_lines() {
         let source_code = r#"
@@ -198,8 +638,38 @@ fn main() {
                 functions: 1,
                 statements: 1,
                 classes: 0,
+                structs: 0,
+                enums: 0,
+                traits: 0,
+                impls: 0,
+                modules: 0,
+                public_api: 0,
+                public_documented_api: 0,
+                exported_items: 0,
+                unsafe_blocks: 0,
+                unsafe_functions: 0,
+                unsafe_impls: 0,
+                unsafe_lines: 0,
                 cognitive_complexity: 0,
                 cyclomatic_complexity: 1,
+                halstead: Some(HalsteadMetrics {
+                    distinct_operators: 6,
+                    distinct_operands: 3,
+                    operators: 6,
+                    operands: 3,
+                }),
+                ncloc_data: BTreeSet::from([9, 10, 11]),
+                comment_lines_data: BTreeSet::from([4, 7]),
+                executable_lines: BTreeSet::from([10]),
+                function_complexity_distribution: vec![
+                    (1, 1),
+                    (2, 0),
+                    (4, 0),
+                    (6, 0),
+                    (8, 0),
+                    (10, 0),
+                    (12, 0)
+                ],
             }
         );
     }
@@ -240,9 +710,319 @@ fn main() {
                 functions: 2,
                 statements: 2,
                 classes: 2,
+                structs: 1,
+                enums: 1,
+                traits: 0,
+                impls: 1,
+                modules: 0,
+                public_api: 0,
+                public_documented_api: 0,
+                exported_items: 0,
+                unsafe_blocks: 0,
+                unsafe_functions: 0,
+                unsafe_impls: 0,
+                unsafe_lines: 0,
                 cognitive_complexity: 0,
-                cyclomatic_complexity: 2
+                cyclomatic_complexity: 2,
+                halstead: Some(HalsteadMetrics {
+                    distinct_operators: 12,
+                    distinct_operands: 11,
+                    operators: 28,
+                    operands: 22,
+                }),
+                ncloc_data: BTreeSet::from([
+                    2, 3, 4, 5, 6, 8, 9, 10, 11, 13, 14, 15, 16, 17, 19, 20, 21, 22
+                ]),
+                comment_lines_data: BTreeSet::from([21]),
+                executable_lines: BTreeSet::from([15, 20]),
+                function_complexity_distribution: vec![
+                    (1, 2),
+                    (2, 0),
+                    (4, 0),
+                    (6, 0),
+                    (8, 0),
+                    (10, 0),
+                    (12, 0)
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_executable_lines() {
+        let source_code = r#"
+fn compute(x: i32) -> i32 {
+    let y = x
+        + 1;
+    if y > 2 {
+        return y;
+    }
+    // Tail expression
+    y * 2
+}
+
+const VALUE: i32 = 1;
+
+fn empty() {
+    fn nested() {}
+}
+
+fn unit() {
+    println!("done")
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+
+        assert_eq!(actual.executable_lines, BTreeSet::from([3, 5, 6, 9, 19]));
+    }
+
+    #[test]
+    fn test_function_complexity_distribution() {
+        let source_code = r#"
+fn empty() {}
+fn simple() { foo(); }
+fn branching(a: bool, b: bool) { if a && b { foo(); } }
+fn matching(x: i32) -> i32 {
+    match x { 1 => 1, 2 => 2, 3 => 3, 4 => 4, 5 => 5, 6 => 6, 7 => 7, 8 => 8, 9 => 9, 10 => 10, 11 => 11, _ => 0 }
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+
+        assert_eq!(
+            actual.function_complexity_distribution,
+            vec![(1, 2), (2, 1), (4, 0), (6, 0), (8, 0), (10, 0), (12, 1)]
+        );
+    }
+
+    #[test]
+    fn test_type_metrics() {
+        let source_code = r#"
+mod shapes {
+    pub struct Circle(f64);
+    pub struct Square(f64);
+    pub enum Shape { Circle(Circle), Square(Square) }
+    pub trait Area { fn area(&self) -> f64; }
+    impl Area for Circle { fn area(&self) -> f64 { 3.14 * self.0 * self.0 } }
+    impl Area for Square { fn area(&self) -> f64 { self.0 * self.0 } }
+}
+mod tests;
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+
+        assert_eq!(
+            (
+                actual.classes,
+                actual.structs,
+                actual.enums,
+                actual.traits,
+                actual.impls,
+                actual.modules
+            ),
+            (3, 2, 1, 1, 2, 2)
+        );
+    }
+
+    #[test]
+    fn test_public_api_metrics() {
+        let source_code = r#"
+/// Documented with a line comment.
+#[derive(Debug)]
+pub struct Documented;
+
+/** Documented with a block comment. */
+pub fn documented() {}
+
+#[doc = "Documented with an attribute."]
+pub enum Attribute {}
+
+// Not a doc comment
+pub trait Undocumented {}
+
+/// Not public
+fn private() {}
+
+pub(crate) fn restricted() {}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+
+        assert_eq!(actual.public_api, 4);
+        assert_eq!(actual.public_documented_api, 3);
+        assert_eq!(actual.public_documented_api_density(), Some(75.0));
+    }
+
+    #[test]
+    fn test_public_documented_api_density_without_public_api() {
+        let source_code = "fn main() {}";
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+
+        assert_eq!(actual.public_api, 0);
+        assert_eq!(actual.public_documented_api_density(), None);
+    }
+
+    #[test]
+    fn test_maintainability_index() {
+        let source_code = "fn main() {\n    let x = 42;\n}\n";
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+        let expected = maintainability_index(actual.halstead.as_ref().unwrap().volume(), 1, 3);
+        assert_eq!(actual.maintainability_index(), Some(expected));
+
+        let tree = parse_rust_code("").unwrap();
+        let actual = calculate_metrics(&tree, "").unwrap();
+        assert_eq!(actual.halstead, None);
+        assert_eq!(actual.maintainability_index(), None);
+    }
+
+    #[test]
+    fn test_test_code_metrics() {
+        let source_code = r#"
+fn production(x: bool) {
+    if x {}
+}
+
+#[cfg(test)]
+mod tests {
+    // Comment in tests
+    #[test]
+    fn test_production() {
+        if true && false {}
+    }
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_file_metrics(&tree, source_code, false).unwrap();
+
+        assert_eq!(
+            (
+                actual.metrics.ncloc,
+                actual.metrics.functions,
+                actual.metrics.modules,
+                actual.metrics.comment_lines,
+                actual.metrics.cognitive_complexity,
+                actual.metrics.cyclomatic_complexity
+            ),
+            (3, 1, 0, 0, 1, 2)
+        );
+        assert_eq!(
+            (
+                actual.test_metrics.ncloc,
+                actual.test_metrics.functions,
+                actual.test_metrics.modules,
+                actual.test_metrics.comment_lines,
+                actual.test_metrics.cognitive_complexity,
+                actual.test_metrics.cyclomatic_complexity
+            ),
+            (7, 1, 1, 1, 2, 3)
+        );
+        let tests: Vec<bool> = actual.functions.iter().map(|f| f.test).collect();
+        assert_eq!(tests, vec![false, true]);
+        assert_eq!(actual.metrics.function_complexity_distribution[1], (2, 1));
+        assert_eq!(
+            actual.test_metrics.function_complexity_distribution[1],
+            (2, 1)
+        );
+
+        let actual = calculate_file_metrics(&tree, source_code, true).unwrap();
+        assert_eq!(actual.metrics.ncloc, 10);
+        assert_eq!(actual.metrics.functions, 2);
+        assert_eq!(
+            actual.test_metrics,
+            Metrics {
+                function_complexity_distribution: complexity_distribution(&[]),
+                ..Metrics::default()
             }
         );
     }
+
+    #[test]
+    fn test_exported_items() {
+        let source_code = r#"
+pub use crate::shapes::{Circle, square::Square as Quad, prelude::*};
+pub use crate::shapes::Area;
+use crate::private::Hidden;
+
+pub fn exported() {}
+pub struct Point;
+pub union Bits { value: u32 }
+pub type Alias = u8;
+pub const LIMIT: u8 = 42;
+pub static NAME: &str = "";
+pub(crate) fn restricted() {}
+fn private() {}
+
+/// Exported macro
+#[macro_export]
+macro_rules! exported_macro { () => {} }
+
+macro_rules! private_macro { () => {} }
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+
+        assert_eq!(actual.exported_items, 11);
+    }
+
+    #[test]
+    fn test_macro_rules_metrics() {
+        let source_code = r#"
+macro_rules! sum {
+    // Adds the expressions
+    ($($x:expr),+ $(,)?) => {
+        0 $(+ $x)+
+    };
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+
+        assert_eq!(actual.ncloc, 5);
+        assert_eq!(actual.ncloc_data, BTreeSet::from([2, 4, 5, 6, 7]));
+        assert_eq!(actual.comment_lines_data, BTreeSet::from([3]));
+    }
+
+    #[test]
+    fn test_unsafe_metrics() {
+        let source_code = r#"
+struct Buffer(*mut u8);
+
+unsafe impl Send for Buffer {}
+
+impl Buffer {
+    unsafe fn read(&self) -> u8 {
+        // Safety: the pointer is valid
+        *self.0
+    }
+
+    fn write(&self, value: u8) {
+        let nested = unsafe {
+            *self.0 = value;
+            unsafe { *self.0 }
+        };
+    }
+
+    fn safe(&self) {}
+}
+
+trait Raw {
+    unsafe fn raw(&self);
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let actual = calculate_metrics(&tree, source_code).unwrap();
+
+        assert_eq!(
+            (
+                actual.unsafe_blocks,
+                actual.unsafe_functions,
+                actual.unsafe_impls,
+                actual.unsafe_lines
+            ),
+            (2, 1, 1, 7)
+        );
+    }
 }
@@ -0,0 +1,635 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::tree::{walk_tree, AnalyzerError, NodeVisitor, SonarLocation, TreeSitterLocation};
+use std::collections::HashMap;
+use tree_sitter::{Node, Tree};
+
+/// Kind of a named item.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum ItemKind {
+    Function,
+    Struct,
+    Enum,
+    Union,
+    Trait,
+    TypeAlias,
+    Const,
+    Static,
+    Module,
+}
+
+impl ItemKind {
+    fn from_node_kind(kind: &str) -> Option<Self> {
+        match kind {
+            "function_item" => Some(ItemKind::Function),
+            "struct_item" => Some(ItemKind::Struct),
+            "enum_item" => Some(ItemKind::Enum),
+            "union_item" => Some(ItemKind::Union),
+            "trait_item" => Some(ItemKind::Trait),
+            "type_item" => Some(ItemKind::TypeAlias),
+            "const_item" => Some(ItemKind::Const),
+            "static_item" => Some(ItemKind::Static),
+            "mod_item" => Some(ItemKind::Module),
+            _ => None,
+        }
+    }
+
+    /// Whether the item is a type, which can be referred to in types.
+    pub fn is_type(&self) -> bool {
+        matches!(
+            self,
+            ItemKind::Struct
+                | ItemKind::Enum
+                | ItemKind::Union
+                | ItemKind::Trait
+                | ItemKind::TypeAlias
+        )
+    }
+}
+
+/// Kind of a declaration.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum DeclarationKind {
+    /// Named item, e.g. a function or a struct.
+    Item(ItemKind),
+    /// Local binding of a pattern, e.g. a `let` variable, a parameter or a match arm binding.
+    Binding,
+    /// Name imported by a `use` declaration, re-exported by a `pub use`.
+    Import { reexported: bool },
+}
+
+/// Declaration of a name in the file.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Declaration {
+    pub name: String,
+    pub kind: DeclarationKind,
+    /// Location of the name in the declaration.
+    pub location: SonarLocation,
+    /// Locations of the identifiers referring to the declaration.
+    pub references: Vec<SonarLocation>,
+    /// Index of the declaration of the same name hidden by this one, e.g. by a shadowing `let`.
+    pub shadowed: Option<usize>,
+}
+
+/// Declarations of a file, with the identifiers referring to them.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Bindings {
+    /// Declarations in the order of their scopes: the items and imports of a block or a module come first.
+    pub declarations: Vec<Declaration>,
+    /// Declaration index, by node id of the declaring and of the referring identifiers.
+    by_node: HashMap<usize, usize>,
+}
+
+impl Bindings {
+    /// Declaration of an identifier, either declaring or referring to it.
+    pub fn declaration_of(&self, identifier: Node<'_>) -> Option<&Declaration> {
+        self.by_node
+            .get(&identifier.id())
+            .map(|declaration| &self.declarations[*declaration])
+    }
+
+    /// Imports which are neither referred to in the file nor re-exported.
+    pub fn unused_imports(&self) -> impl Iterator<Item = &Declaration> {
+        self.declarations.iter().filter(|declaration| {
+            declaration.kind == DeclarationKind::Import { reexported: false }
+                && declaration.references.is_empty()
+        })
+    }
+}
+
+/// Resolves the names of a file to their declarations.
+///
+/// Names are resolved lexically: items and imports are visible in the whole module or block declaring them, local
+/// bindings from the end of their declaration to the end of their scope. Blocks, functions, closures, match arms and
+/// loops open scopes. Types and values share the same namespace, and names that cannot be resolved in the file (e.g.
+/// fields, methods and glob imports) are ignored. The paths of attributes only refer to imports, e.g. `Serialize`
+/// in `#[derive(Serialize)]`.
+pub fn resolve_bindings(tree: &Tree, source_code: &str) -> Result<Bindings, AnalyzerError> {
+    let mut resolver = BindingResolver {
+        source_code,
+        declarations: vec![],
+        references: vec![],
+        by_node: HashMap::new(),
+        scopes: vec![],
+        pending_bindings: vec![],
+        skipped_node: None,
+    };
+    walk_tree(tree.root_node(), &mut resolver)?;
+    let declarations = resolver
+        .declarations
+        .into_iter()
+        .zip(resolver.references)
+        .map(|(declaration, references)| Declaration {
+            name: declaration.name.to_string(),
+            kind: declaration.kind,
+            location: declaration.location.to_sonar_location(source_code),
+            references: references
+                .iter()
+                .map(|reference| reference.to_sonar_location(source_code))
+                .collect(),
+            shadowed: declaration.shadowed,
+        })
+        .collect();
+    Ok(Bindings {
+        declarations,
+        by_node: resolver.by_node,
+    })
+}
+
+struct PendingDeclaration<'a> {
+    name: &'a str,
+    kind: DeclarationKind,
+    location: TreeSitterLocation,
+    shadowed: Option<usize>,
+}
+
+/// Node id, location and text of the name of a binding.
+type PendingBinding<'a> = (usize, TreeSitterLocation, &'a str);
+
+struct BindingResolver<'a> {
+    source_code: &'a str,
+    declarations: Vec<PendingDeclaration<'a>>,
+    /// References of each declaration, by declaration index.
+    references: Vec<Vec<TreeSitterLocation>>,
+    by_node: HashMap<usize, usize>,
+    /// Visible names, innermost scope last.
+    scopes: Vec<HashMap<&'a str, usize>>,
+    /// Bindings of the patterns being visited, by binder node (e.g. a 'let' declaration).
+    /// They are added to the current scope once the binder is complete, so that the initializer of a 'let'
+    /// refers to the shadowed bindings.
+    pending_bindings: Vec<(usize, Vec<PendingBinding<'a>>)>,
+    /// Node whose subtree is not resolved, such as an attribute.
+    skipped_node: Option<usize>,
+}
+
+impl<'a> BindingResolver<'a> {
+    fn text(&self, node: Node<'_>) -> &'a str {
+        &self.source_code[node.byte_range()]
+    }
+
+    fn lookup(&self, text: &str) -> Option<usize> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(text).copied())
+    }
+
+    fn declare(&mut self, name: Node<'_>, kind: DeclarationKind) {
+        let binding = (
+            name.id(),
+            TreeSitterLocation::from_tree_sitter_node(name),
+            self.text(name),
+        );
+        self.declare_binding(binding, kind);
+    }
+
+    fn declare_binding(&mut self, (id, location, text): PendingBinding<'a>, kind: DeclarationKind) {
+        let declaration = self.declarations.len();
+        self.declarations.push(PendingDeclaration {
+            name: text,
+            kind,
+            location,
+            shadowed: self.lookup(text),
+        });
+        self.references.push(vec![]);
+        self.by_node.insert(id, declaration);
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(text, declaration);
+        }
+    }
+
+    fn resolve(&mut self, reference: Node<'_>) {
+        if let Some(declaration) = self.lookup(self.text(reference)) {
+            self.references[declaration].push(TreeSitterLocation::from_tree_sitter_node(reference));
+            self.by_node.insert(reference.id(), declaration);
+        }
+    }
+
+    /// Resolves the paths of an attribute, e.g. a derive macro, to the imports.
+    fn resolve_attribute(&mut self, attribute: Node<'_>) {
+        let mut stack = vec![attribute];
+        while let Some(node) = stack.pop() {
+            if node.kind() == "identifier" {
+                let declaration = self.lookup(self.text(node)).filter(|declaration| {
+                    matches!(
+                        self.declarations[*declaration].kind,
+                        DeclarationKind::Import { .. }
+                    )
+                });
+                if declaration.is_some() {
+                    self.resolve(node);
+                }
+            }
+            let mut cursor = node.walk();
+            stack.extend(node.named_children(&mut cursor));
+        }
+    }
+
+    /// Opens a scope, declaring the items and imports of modules and blocks up front.
+    fn open_scope(&mut self, node: Node<'_>) {
+        self.scopes.push(HashMap::new());
+        if matches!(node.kind(), "source_file" | "block" | "declaration_list") {
+            let mut cursor = node.walk();
+            let children: Vec<Node<'_>> = node.named_children(&mut cursor).collect();
+            for child in children {
+                if let Some(kind) = ItemKind::from_node_kind(child.kind()) {
+                    if let Some(name) = child.child_by_field_name("name") {
+                        self.declare(name, DeclarationKind::Item(kind));
+                    }
+                } else if child.kind() == "use_declaration" {
+                    let reexported = child
+                        .named_child(0)
+                        .is_some_and(|visibility| visibility.kind() == "visibility_modifier");
+                    if let Some(argument) = child.child_by_field_name("argument") {
+                        for name in imported_names(argument) {
+                            self.declare(name, DeclarationKind::Import { reexported });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn bind_pending(&mut self, binder: Node<'_>) {
+        if self
+            .pending_bindings
+            .last()
+            .is_some_and(|(id, _)| *id == binder.id())
+        {
+            if let Some((_, bindings)) = self.pending_bindings.pop() {
+                for binding in bindings {
+                    self.declare_binding(binding, DeclarationKind::Binding);
+                }
+            }
+        }
+    }
+}
+
+impl<'a> NodeVisitor for BindingResolver<'a> {
+    fn enter_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
+        if self.skipped_node.is_some() {
+            return Ok(());
+        }
+
+        match node.kind() {
+            "attribute_item" | "inner_attribute_item" => {
+                self.resolve_attribute(node);
+                self.skipped_node = Some(node.id());
+                return Ok(());
+            }
+            "use_declaration" | "macro_definition" | "lifetime" | "label" => {
+                self.skipped_node = Some(node.id());
+                return Ok(());
+            }
+            kind if opens_scope(node, kind) => self.open_scope(node),
+            _ => {}
+        }
+
+        if is_binder(node) {
+            self.pending_bindings.push((node.id(), vec![]));
+        }
+
+        Ok(())
+    }
+
+    fn exit_node(&mut self, node: Node<'_>) -> Result<(), AnalyzerError> {
+        if let Some(skipped) = self.skipped_node {
+            if skipped == node.id() {
+                self.skipped_node = None;
+            }
+            return Ok(());
+        }
+
+        match node.kind() {
+            "identifier" | "type_identifier" | "shorthand_field_identifier" => {
+                let text = self.text(node);
+                if is_binding(node, text) {
+                    if let Some((_, bindings)) = self.pending_bindings.last_mut() {
+                        bindings.push((
+                            node.id(),
+                            TreeSitterLocation::from_tree_sitter_node(node),
+                            text,
+                        ));
+                    }
+                } else if is_reference(node) {
+                    self.resolve(node);
+                }
+            }
+            kind if opens_scope(node, kind) => {
+                self.scopes.pop();
+            }
+            _ => {}
+        }
+
+        if is_binder(node) {
+            self.bind_pending(node);
+        }
+        if let Some(parent) = node.parent() {
+            // Bindings of 'for' loops are visible in their body, bindings of match arms in their guard
+            let binds_parent = match parent.kind() {
+                "for_expression" => parent.child_by_field_name("value") == Some(node),
+                "match_pattern" => parent.named_child(0) == Some(node),
+                _ => false,
+            };
+            if binds_parent {
+                self.bind_pending(parent);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Names declared by the argument of a `use` declaration, e.g. `b` and `c` in `use a::{b, c as d}`.
+fn imported_names(argument: Node<'_>) -> Vec<Node<'_>> {
+    match argument.kind() {
+        "identifier" => vec![argument],
+        "scoped_identifier" => argument
+            .child_by_field_name("name")
+            .filter(|name| name.kind() == "identifier")
+            .into_iter()
+            .collect(),
+        "use_as_clause" => argument
+            .child_by_field_name("alias")
+            .filter(|alias| alias.kind() == "identifier")
+            .into_iter()
+            .collect(),
+        "scoped_use_list" => {
+            let path = argument.child_by_field_name("path");
+            argument
+                .child_by_field_name("list")
+                .map(|list| {
+                    let mut cursor = list.walk();
+                    let names = list
+                        .named_children(&mut cursor)
+                        .flat_map(|item| match item.kind() {
+                            // `self` imports the last segment of the path, e.g. `io` in `use std::io::{self}`
+                            "self" => path.map(imported_names).unwrap_or_default(),
+                            _ => imported_names(item),
+                        })
+                        .collect();
+                    names
+                })
+                .unwrap_or_default()
+        }
+        "use_list" => {
+            let mut cursor = argument.walk();
+            let names = argument
+                .named_children(&mut cursor)
+                .flat_map(imported_names)
+                .collect();
+            names
+        }
+        _ => vec![],
+    }
+}
+
+fn opens_scope(node: Node<'_>, kind: &str) -> bool {
+    match kind {
+        "source_file" | "block" | "function_item" | "closure_expression" | "match_arm"
+        | "for_expression" | "if_expression" | "while_expression" => true,
+        // Items of impls and traits are not visible by their name alone
+        "declaration_list" => node
+            .parent()
+            .is_some_and(|parent| parent.kind() == "mod_item"),
+        _ => false,
+    }
+}
+
+/// Nodes declaring the bindings of their patterns.
+fn is_binder(node: Node<'_>) -> bool {
+    matches!(
+        node.kind(),
+        "let_declaration"
+            | "parameter"
+            | "closure_parameters"
+            | "let_condition"
+            | "match_pattern"
+            | "for_expression"
+    )
+}
+
+/// Whether an identifier declares a binding in a pattern.
+fn is_binding(node: Node<'_>, text: &str) -> bool {
+    if node.kind() == "type_identifier" || text.starts_with(|c: char| c.is_uppercase()) {
+        // Uppercase identifiers in patterns are constants or enum variants
+        return false;
+    }
+
+    let mut current = node;
+    while let Some(parent) = current.parent() {
+        let field = field_name(parent, current);
+        match parent.kind() {
+            "tuple_pattern" | "slice_pattern" | "reference_pattern" | "mut_pattern"
+            | "ref_pattern" | "captured_pattern" | "or_pattern" => {}
+            "tuple_struct_pattern" | "struct_pattern" if field != Some("type") => {}
+            "field_pattern" => {
+                if field == Some("name") {
+                    return node.kind() == "shorthand_field_identifier";
+                }
+            }
+            "match_pattern" => return field != Some("condition"),
+            "let_declaration" | "parameter" | "let_condition" | "for_expression" => {
+                return field == Some("pattern");
+            }
+            "closure_parameters" => return true,
+            _ => return false,
+        }
+        current = parent;
+    }
+    false
+}
+
+/// Whether an identifier which is not a binding may refer to a symbol of the file.
+fn is_reference(node: Node<'_>) -> bool {
+    let Some(parent) = node.parent() else {
+        return false;
+    };
+    let field = field_name(parent, node);
+    match parent.kind() {
+        // Declarations
+        kind if ItemKind::from_node_kind(kind).is_some() => field != Some("name"),
+        "enum_variant"
+        | "field_declaration"
+        | "type_parameters"
+        | "const_parameter"
+        | "constrained_type_parameter"
+        | "optional_type_parameter" => false,
+        // Only the first segment of paths is resolved in the file
+        "scoped_identifier" | "scoped_type_identifier" => field != Some("name"),
+        "shorthand_field_initializer" => true,
+        _ => node.kind() != "shorthand_field_identifier",
+    }
+}
+
+fn field_name(parent: Node<'_>, child: Node<'_>) -> Option<&'static str> {
+    let mut cursor = parent.walk();
+    for (index, candidate) in parent.children(&mut cursor).enumerate() {
+        if candidate == child {
+            return parent.field_name_for_child(index as u32);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::{parse_rust_code, NodeIterator};
+
+    /// Declarations as their name, kind and line, with the lines of their references.
+    fn declarations(source_code: &str) -> Vec<(String, DeclarationKind, usize, Vec<usize>)> {
+        let tree = parse_rust_code(source_code).unwrap();
+        resolve_bindings(&tree, source_code)
+            .unwrap()
+            .declarations
+            .into_iter()
+            .map(|declaration| {
+                (
+                    declaration.name,
+                    declaration.kind,
+                    declaration.location.start_line,
+                    declaration
+                        .references
+                        .iter()
+                        .map(|r| r.start_line)
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_imports() {
+        let source_code = r#"
+use std::collections::{self, HashMap as Map};
+use std::fmt::Display;
+use serde::Serialize;
+pub use crate::tree::Node;
+use log::{info, warn};
+use std::io::*;
+
+#[derive(Serialize)]
+struct Config;
+
+fn main() {
+    use std::sync::Arc;
+    let map: Map<i32, i32> = collections::BTreeMap::new().into_iter().collect();
+    info!("{:?}", map);
+}
+"#;
+        let import = DeclarationKind::Import { reexported: false };
+        assert_eq!(
+            declarations(source_code),
+            vec![
+                ("collections".to_string(), import, 2, vec![14]),
+                ("Map".to_string(), import, 2, vec![14]),
+                ("Display".to_string(), import, 3, vec![]),
+                ("Serialize".to_string(), import, 4, vec![9]),
+                (
+                    "Node".to_string(),
+                    DeclarationKind::Import { reexported: true },
+                    5,
+                    vec![]
+                ),
+                ("info".to_string(), import, 6, vec![15]),
+                ("warn".to_string(), import, 6, vec![]),
+                (
+                    "Config".to_string(),
+                    DeclarationKind::Item(ItemKind::Struct),
+                    10,
+                    vec![]
+                ),
+                (
+                    "main".to_string(),
+                    DeclarationKind::Item(ItemKind::Function),
+                    12,
+                    vec![]
+                ),
+                ("Arc".to_string(), import, 13, vec![]),
+                ("map".to_string(), DeclarationKind::Binding, 14, vec![15]),
+            ]
+        );
+
+        let tree = parse_rust_code(source_code).unwrap();
+        let bindings = resolve_bindings(&tree, source_code).unwrap();
+        assert_eq!(
+            bindings
+                .unused_imports()
+                .map(|declaration| declaration.name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Display", "warn", "Arc"]
+        );
+    }
+
+    #[test]
+    fn test_shadowing() {
+        let source_code = r#"
+fn parse(input: &str) -> u32 {
+    let input = input.trim();
+    let value = match input.parse::<u32>() {
+        Ok(input) => input,
+        Err(_) => 0,
+    };
+    let closure = |value: u32| value + 1;
+    closure(value)
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let bindings = resolve_bindings(&tree, source_code).unwrap();
+        let shadowed = |index: usize| {
+            bindings.declarations[index]
+                .shadowed
+                .map(|shadowed| bindings.declarations[shadowed].location.start_line)
+        };
+        let names: Vec<(&str, usize)> = bindings
+            .declarations
+            .iter()
+            .map(|declaration| (declaration.name.as_str(), declaration.location.start_line))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("parse", 2),
+                ("input", 2),
+                ("input", 3),
+                ("input", 5),
+                ("value", 4),
+                ("value", 8),
+                ("closure", 8),
+            ]
+        );
+        assert_eq!(
+            (0..names.len()).map(shadowed).collect::<Vec<_>>(),
+            vec![None, None, Some(2), Some(3), None, Some(4), None]
+        );
+
+        // The identifiers of the arm refer to its binding
+        let arm_value = NodeIterator::new(tree.root_node(), |node| node.kind() == "match_arm")
+            .next()
+            .and_then(|arm| arm.child_by_field_name("value"))
+            .unwrap();
+        let declaration = bindings.declaration_of(arm_value).unwrap();
+        assert_eq!(
+            (
+                declaration.location.start_line,
+                declaration.references.len()
+            ),
+            (5, 1)
+        );
+    }
+}
@@ -0,0 +1,171 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    tree::{AnalyzerError, SonarLocation},
+    visitors::name_resolution::{resolve_bindings, Bindings},
+};
+use tree_sitter::Tree;
+
+/// Declaration of a local binding, an item or an import, along with its references in the same file.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct Symbol {
+    pub declaration: SonarLocation,
+    pub references: Vec<SonarLocation>,
+}
+
+/// Computes the symbols declared in a file, for the highlighting of usages, see `resolve_bindings`.
+pub fn calculate_symbols(tree: &Tree, source_code: &str) -> Result<Vec<Symbol>, AnalyzerError> {
+    Ok(symbols_of_bindings(&resolve_bindings(tree, source_code)?))
+}
+
+/// Symbols of the declarations of the file.
+pub fn symbols_of_bindings(bindings: &Bindings) -> Vec<Symbol> {
+    let mut symbols: Vec<Symbol> = bindings
+        .declarations
+        .iter()
+        .map(|declaration| Symbol {
+            declaration: declaration.location.clone(),
+            references: declaration.references.clone(),
+        })
+        .collect();
+    symbols.sort();
+    symbols
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    /// Symbols as their name and the lines of their declaration and references.
+    fn symbols(source_code: &str) -> Vec<(String, usize, Vec<usize>)> {
+        let tree = parse_rust_code(source_code).unwrap();
+        calculate_symbols(&tree, source_code)
+            .unwrap()
+            .into_iter()
+            .map(|symbol| {
+                let line = source_code
+                    .lines()
+                    .nth(symbol.declaration.start_line - 1)
+                    .unwrap();
+                let name = line[symbol.declaration.start_column..symbol.declaration.end_column]
+                    .to_string();
+                let references = symbol.references.iter().map(|r| r.start_line).collect();
+                (name, symbol.declaration.start_line, references)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_local_bindings() {
+        let source_code = r#"
+fn compute(x: i32) -> i32 {
+    let y = x + 1;
+    let y = y * 2;
+    println!("{}", y);
+    y
+}
+"#;
+        assert_eq!(
+            symbols(source_code),
+            vec![
+                ("compute".to_string(), 2, vec![]),
+                ("x".to_string(), 2, vec![3]),
+                ("y".to_string(), 3, vec![4]),
+                ("y".to_string(), 4, vec![5, 6]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_patterns() {
+        let source_code = r#"
+fn f(p: Option<(i32, i32)>) {
+    if let Some((a, b)) = p {
+        a + b;
+    }
+    match p {
+        Some((c, _)) if c > 0 => c,
+        None => 0,
+    };
+    for (i, item) in p.iter().enumerate() {
+        i + item;
+    }
+    let square = |n| n * n;
+    square(2);
+}
+"#;
+        assert_eq!(
+            symbols(source_code),
+            vec![
+                ("f".to_string(), 2, vec![]),
+                ("p".to_string(), 2, vec![3, 6, 10]),
+                ("a".to_string(), 3, vec![4]),
+                ("b".to_string(), 3, vec![4]),
+                ("c".to_string(), 7, vec![7, 7]),
+                ("i".to_string(), 10, vec![11]),
+                ("item".to_string(), 10, vec![11]),
+                ("square".to_string(), 13, vec![14]),
+                ("n".to_string(), 13, vec![13, 13]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_items() {
+        let source_code = r#"
+fn main() {
+    let point = Point { x: helper(), y: 0 };
+    Point::origin();
+}
+
+struct Point { x: i32, y: i32 }
+
+fn helper() -> i32 { 42 }
+"#;
+        assert_eq!(
+            symbols(source_code),
+            vec![
+                ("main".to_string(), 2, vec![]),
+                ("point".to_string(), 3, vec![]),
+                ("Point".to_string(), 7, vec![3, 4]),
+                ("helper".to_string(), 9, vec![3]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_struct_field_shorthands() {
+        let source_code = r#"
+fn f(x: i32) {
+    let p = P { x };
+    let P { x: renamed, y } = p;
+    renamed + y;
+}
+"#;
+        assert_eq!(
+            symbols(source_code),
+            vec![
+                ("f".to_string(), 2, vec![]),
+                ("x".to_string(), 2, vec![3]),
+                ("p".to_string(), 3, vec![4]),
+                ("renamed".to_string(), 4, vec![5]),
+                ("y".to_string(), 4, vec![5]),
+            ]
+        );
+    }
+}
@@ -0,0 +1,727 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    issue::SecondaryLocation,
+    options::list_parameter,
+    tree::{AnalyzerError, NodeIterator, SonarLocation, TreeSitterLocation},
+    visitors::data_flow::pattern_identifiers,
+};
+use std::{collections::HashMap, fmt::Display, str::FromStr};
+use tree_sitter::{Node, Tree};
+
+/// Kind of operation which must not receive untrusted data.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum SinkKind {
+    /// Execution of an SQL query.
+    Sql,
+    /// Spawning of a process.
+    Command,
+    /// Construction of a file system path.
+    Path,
+}
+
+impl Display for SinkKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SinkKind::Sql => write!(f, "sql"),
+            SinkKind::Command => write!(f, "command"),
+            SinkKind::Path => write!(f, "path"),
+        }
+    }
+}
+
+impl FromStr for SinkKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sql" => Ok(SinkKind::Sql),
+            "command" => Ok(SinkKind::Command),
+            "path" => Ok(SinkKind::Path),
+            _ => Err(format!("unknown sink kind '{}'", s)),
+        }
+    }
+}
+
+/// Function or method receiving untrusted data in a sink.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Sink {
+    pub kind: SinkKind,
+    /// See `TaintConfig` for the matching of the names.
+    pub name: String,
+}
+
+impl Sink {
+    pub fn new(kind: SinkKind, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+        }
+    }
+}
+
+/// Sources, sinks and sanitizers of the taint analysis.
+///
+/// A name without `::` matches the methods, and the functions called by that single name, e.g. `execute` matches
+/// `connection.execute(sql)`. A path matches the calls of the same path, or of a path made of its last segments, with at
+/// least two of them, e.g. `std::env::var` matches `env::var("HOME")` but not `var("HOME")`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TaintConfig {
+    /// Whether the parameters of the function are untrusted, e.g. the extractors of an HTTP handler.
+    pub parameters_are_sources: bool,
+    /// Functions and methods returning untrusted data.
+    pub sources: Vec<String>,
+    pub sinks: Vec<Sink>,
+    /// Functions and methods whose result is trusted, whatever their arguments.
+    pub sanitizers: Vec<String>,
+}
+
+impl Default for TaintConfig {
+    fn default() -> Self {
+        Self {
+            parameters_are_sources: true,
+            sources: [
+                "std::env::var",
+                "std::env::var_os",
+                "std::env::args",
+                "std::env::args_os",
+                "std::io::stdin",
+                "query_string",
+                "match_info",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+            sinks: vec![
+                Sink::new(SinkKind::Sql, "execute"),
+                Sink::new(SinkKind::Sql, "batch_execute"),
+                Sink::new(SinkKind::Sql, "query"),
+                Sink::new(SinkKind::Sql, "prepare"),
+                Sink::new(SinkKind::Sql, "sqlx::query"),
+                Sink::new(SinkKind::Sql, "sqlx::query_as"),
+                Sink::new(SinkKind::Command, "std::process::Command::new"),
+                Sink::new(SinkKind::Command, "arg"),
+                Sink::new(SinkKind::Command, "args"),
+                Sink::new(SinkKind::Path, "std::path::Path::new"),
+                Sink::new(SinkKind::Path, "std::path::PathBuf::from"),
+                Sink::new(SinkKind::Path, "std::fs::File::open"),
+                Sink::new(SinkKind::Path, "std::fs::File::create"),
+                Sink::new(SinkKind::Path, "std::fs::read"),
+                Sink::new(SinkKind::Path, "std::fs::read_to_string"),
+                Sink::new(SinkKind::Path, "std::fs::write"),
+                Sink::new(SinkKind::Path, "std::fs::remove_file"),
+                Sink::new(SinkKind::Path, "std::fs::remove_dir_all"),
+                Sink::new(SinkKind::Path, "join"),
+            ],
+            sanitizers: [
+                "parse",
+                "len",
+                "is_empty",
+                "file_name",
+                "shell_escape::escape",
+            ]
+            .map(str::to_string)
+            .to_vec(),
+        }
+    }
+}
+
+impl TaintConfig {
+    /// Default configuration extended with the comma-separated 'taint_sources', 'taint_sinks' (as `kind:name`, e.g.
+    /// `sql:run_query`) and 'taint_sanitizers' parameters.
+    pub fn from_parameters(parameters: &HashMap<String, String>) -> Result<Self, AnalyzerError> {
+        let mut config = Self::default();
+        let list = |key: &str| {
+            parameters
+                .get(key)
+                .map_or(vec![], |value| list_parameter(value))
+        };
+
+        config.sources.extend(list("taint_sources"));
+        config.sanitizers.extend(list("taint_sanitizers"));
+        for sink in list("taint_sinks") {
+            let (kind, name) = sink
+                .split_once(':')
+                .ok_or(AnalyzerError::GlobalError(format!(
+                    "invalid taint sink '{}', expected 'kind:name'",
+                    sink
+                )))?;
+            let kind = kind
+                .trim()
+                .parse::<SinkKind>()
+                .map_err(AnalyzerError::GlobalError)?;
+            config.sinks.push(Sink::new(kind, name.trim()));
+        }
+        Ok(config)
+    }
+
+    fn is_source(&self, name: &str) -> bool {
+        self.sources.iter().any(|source| matches_name(source, name))
+    }
+
+    fn is_sanitizer(&self, name: &str) -> bool {
+        self.sanitizers
+            .iter()
+            .any(|sanitizer| matches_name(sanitizer, name))
+    }
+
+    fn sink(&self, name: &str) -> Option<SinkKind> {
+        self.sinks
+            .iter()
+            .find(|sink| matches_name(&sink.name, name))
+            .map(|sink| sink.kind)
+    }
+}
+
+/// Path from the source of untrusted data to a sink.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone)]
+pub struct TaintFlow {
+    /// Call of the sink.
+    pub sink: SonarLocation,
+    pub sink_kind: SinkKind,
+    pub source: SonarLocation,
+    /// Description of the source, e.g. "parameter 'name'".
+    pub source_description: String,
+    /// Variables the data went through, in order.
+    pub steps: Vec<(String, SonarLocation)>,
+}
+
+impl TaintFlow {
+    /// The source and the steps of the flow, for the issue reported on the sink.
+    pub fn secondary_locations(&self) -> Vec<SecondaryLocation> {
+        let mut locations = vec![SecondaryLocation {
+            message: format!(
+                "Untrusted data comes from this {}.",
+                self.source_description
+            ),
+            location: self.source.clone(),
+            file: None,
+        }];
+        locations.extend(
+            self.steps
+                .iter()
+                .map(|(variable, location)| SecondaryLocation {
+                    message: format!("'{}' is tainted.", variable),
+                    location: location.clone(),
+                    file: None,
+                }),
+        );
+        locations
+    }
+}
+
+/// Finds the flows of untrusted data to sinks in all functions of the file, see `find_function_taint_flows`.
+pub fn find_taint_flows(tree: &Tree, source_code: &str, config: &TaintConfig) -> Vec<TaintFlow> {
+    let mut flows: Vec<TaintFlow> = NodeIterator::new(tree.root_node(), |node| {
+        node.kind() == "function_item" && node.child_by_field_name("body").is_some()
+    })
+    .flat_map(|function_item| find_function_taint_flows(function_item, source_code, config))
+    .collect();
+    flows.sort();
+    flows
+}
+
+/// Finds the flows of untrusted data to sinks in the body of a function, excluding the nested functions.
+///
+/// The statements are followed once in source order: a variable is tainted from its first tainted assignment, in
+/// any branch, until it is shadowed or assigned trusted data. Calls of other functions propagate the taint of their
+/// arguments and receiver, unless they are sanitizers.
+pub fn find_function_taint_flows(
+    function_item: Node<'_>,
+    source_code: &str,
+    config: &TaintConfig,
+) -> Vec<TaintFlow> {
+    let mut analysis = TaintAnalysis {
+        source_code,
+        config,
+        variables: HashMap::new(),
+        flows: vec![],
+    };
+    if config.parameters_are_sources {
+        analysis.taint_parameters(function_item);
+    }
+    if let Some(body) = function_item.child_by_field_name("body") {
+        analysis.visit(body);
+    }
+    analysis.flows.sort();
+    analysis.flows.dedup();
+    analysis.flows
+}
+
+/// Untrusted data held by a variable.
+#[derive(Debug, Clone)]
+struct Taint {
+    source: SonarLocation,
+    source_description: String,
+    steps: Vec<(String, SonarLocation)>,
+}
+
+struct TaintAnalysis<'a> {
+    source_code: &'a str,
+    config: &'a TaintConfig,
+    variables: HashMap<String, Taint>,
+    flows: Vec<TaintFlow>,
+}
+
+impl<'a> TaintAnalysis<'a> {
+    fn taint_parameters(&mut self, function_item: Node<'a>) {
+        let Some(parameters) = function_item.child_by_field_name("parameters") else {
+            return;
+        };
+        let mut cursor = parameters.walk();
+        for parameter in parameters.named_children(&mut cursor) {
+            if let Some(pattern) = parameter
+                .child_by_field_name("pattern")
+                .filter(|_| parameter.kind() == "parameter")
+            {
+                for name in pattern_identifiers(pattern, self.source_code) {
+                    let variable = self.text(name).to_string();
+                    let taint = Taint {
+                        source: self.location(name),
+                        source_description: format!("parameter '{}'", variable),
+                        steps: vec![],
+                    };
+                    self.variables.insert(variable, taint);
+                }
+            }
+        }
+    }
+
+    fn visit(&mut self, node: Node<'a>) {
+        match node.kind() {
+            "function_item" => {}
+            "let_declaration" | "let_condition" => {
+                let taint = node.child_by_field_name("value").and_then(|value| {
+                    self.visit(value);
+                    self.taint(value)
+                });
+                if let Some(pattern) = node.child_by_field_name("pattern") {
+                    self.bind(pattern, taint);
+                }
+                if let Some(alternative) = node.child_by_field_name("alternative") {
+                    self.visit(alternative);
+                }
+            }
+            "for_expression" => {
+                let taint = node.child_by_field_name("value").and_then(|value| {
+                    self.visit(value);
+                    self.taint(value)
+                });
+                if let Some(pattern) = node.child_by_field_name("pattern") {
+                    self.bind(pattern, taint);
+                }
+                if let Some(body) = node.child_by_field_name("body") {
+                    self.visit(body);
+                }
+            }
+            "match_expression" => {
+                let taint = node.child_by_field_name("value").and_then(|value| {
+                    self.visit(value);
+                    self.taint(value)
+                });
+                for arm in NodeIterator::new(node, |node| node.kind() == "match_arm")
+                    .filter(|arm| arm.parent().and_then(|block| block.parent()) == Some(node))
+                {
+                    if let Some(pattern) = arm.child_by_field_name("pattern") {
+                        self.bind(pattern, taint.clone());
+                    }
+                    if let Some(value) = arm.child_by_field_name("value") {
+                        self.visit(value);
+                    }
+                }
+            }
+            "assignment_expression" | "compound_assignment_expr" => {
+                let taint = node.child_by_field_name("right").and_then(|right| {
+                    self.visit(right);
+                    self.taint(right)
+                });
+                if let Some(left) = node.child_by_field_name("left") {
+                    let overwritten = node.kind() == "assignment_expression";
+                    self.assign(left, taint, overwritten);
+                }
+            }
+            "call_expression" => {
+                self.visit_children(node);
+                self.check_call(node);
+            }
+            _ => self.visit_children(node),
+        }
+    }
+
+    fn visit_children(&mut self, node: Node<'a>) {
+        let mut cursor = node.walk();
+        for child in node.named_children(&mut cursor) {
+            self.visit(child);
+        }
+    }
+
+    /// Taints the variables of the pattern with the given taint, or untaints them.
+    fn bind(&mut self, pattern: Node<'a>, taint: Option<Taint>) {
+        for name in pattern_identifiers(pattern, self.source_code) {
+            self.set(name, taint.clone(), true);
+        }
+    }
+
+    /// Assignment of the variable, or of a field or element of it.
+    fn assign(&mut self, left: Node<'a>, taint: Option<Taint>, overwritten: bool) {
+        let mut target = left;
+        while matches!(
+            target.kind(),
+            "field_expression"
+                | "index_expression"
+                | "unary_expression"
+                | "parenthesized_expression"
+        ) {
+            match target.named_child(0) {
+                Some(child) => target = child,
+                None => return,
+            }
+        }
+        if target.kind() == "identifier" {
+            // Assigning a part of a variable does not untaint the other parts
+            let overwritten = overwritten && target == left;
+            if taint.is_some() || overwritten {
+                self.set(target, taint, overwritten);
+            }
+        }
+    }
+
+    fn set(&mut self, name: Node<'a>, taint: Option<Taint>, overwritten: bool) {
+        let variable = self.text(name).to_string();
+        match taint {
+            Some(mut taint) => {
+                taint.steps.push((variable.clone(), self.location(name)));
+                self.variables.insert(variable, taint);
+            }
+            None if overwritten => {
+                self.variables.remove(&variable);
+            }
+            None => {}
+        }
+    }
+
+    /// Reports the flows into a sink, and taints the `&mut` arguments of methods called on untrusted data, e.g. the
+    /// buffer of `stdin().read_line(&mut buffer)`.
+    fn check_call(&mut self, call: Node<'a>) {
+        let (Some(function), Some(arguments)) = (
+            call.child_by_field_name("function"),
+            call.child_by_field_name("arguments"),
+        ) else {
+            return;
+        };
+        let mut cursor = arguments.walk();
+        let arguments: Vec<Node<'a>> = arguments.named_children(&mut cursor).collect();
+
+        if let Some(kind) = self.config.sink(&self.callee_name(function)) {
+            if let Some(taint) = arguments.iter().find_map(|argument| self.taint(*argument)) {
+                self.flows.push(TaintFlow {
+                    sink: self.location(call),
+                    sink_kind: kind,
+                    source: taint.source,
+                    source_description: taint.source_description,
+                    steps: taint.steps,
+                });
+            }
+        }
+
+        let receiver_taint = Some(function)
+            .filter(|function| function.kind() == "field_expression")
+            .and_then(|function| function.child_by_field_name("value"))
+            .and_then(|receiver| self.taint(receiver));
+        if let Some(taint) = receiver_taint {
+            for argument in arguments {
+                let buffer = Some(argument)
+                    .filter(|argument| {
+                        argument.kind() == "reference_expression" && child_is_mut(*argument)
+                    })
+                    .and_then(|argument| argument.child_by_field_name("value"));
+                if let Some(buffer) = buffer {
+                    self.assign(buffer, Some(taint.clone()), false);
+                }
+            }
+        }
+    }
+
+    /// Taint of the value of an expression.
+    fn taint(&self, node: Node<'a>) -> Option<Taint> {
+        match node.kind() {
+            "identifier" => self.variables.get(self.text(node)).cloned(),
+            "closure_expression" | "function_item" => None,
+            "call_expression" => self.call_taint(node),
+            // The arguments of macros are not parsed, e.g. `format!("{}", name)`
+            "macro_invocation" => NodeIterator::new(node, |node| node.kind() == "identifier")
+                .filter(|identifier| {
+                    identifier
+                        .parent()
+                        .is_some_and(|parent| parent.kind() == "token_tree")
+                })
+                .find_map(|identifier| self.taint(identifier)),
+            "field_expression" => node
+                .child_by_field_name("value")
+                .and_then(|value| self.taint(value)),
+            _ => {
+                let mut cursor = node.walk();
+                let children: Vec<Node<'a>> = node.named_children(&mut cursor).collect();
+                children.into_iter().find_map(|child| self.taint(child))
+            }
+        }
+    }
+
+    fn call_taint(&self, call: Node<'a>) -> Option<Taint> {
+        let function = call.child_by_field_name("function")?;
+        let name = self.callee_name(function);
+        if self.config.is_sanitizer(&name) {
+            return None;
+        }
+        if self.config.is_source(&name) {
+            return Some(Taint {
+                source: self.location(call),
+                source_description: format!("call to '{}'", name),
+                steps: vec![],
+            });
+        }
+        let receiver = Some(function)
+            .filter(|function| function.kind() == "field_expression")
+            .and_then(|function| function.child_by_field_name("value"));
+        receiver
+            .into_iter()
+            .chain(call.child_by_field_name("arguments"))
+            .find_map(|node| self.taint(node))
+    }
+
+    /// Name of the called function or method, without generic arguments and whitespace.
+    fn callee_name(&self, function: Node<'a>) -> String {
+        let function = match function.kind() {
+            "generic_function" => function.child_by_field_name("function").unwrap_or(function),
+            _ => function,
+        };
+        let name = match function.kind() {
+            "field_expression" => function.child_by_field_name("field").unwrap_or(function),
+            _ => function,
+        };
+        self.text(name).split_whitespace().collect()
+    }
+
+    fn text(&self, node: Node<'a>) -> &'a str {
+        &self.source_code[node.byte_range()]
+    }
+
+    fn location(&self, node: Node<'a>) -> SonarLocation {
+        TreeSitterLocation::from_tree_sitter_node(node).to_sonar_location(self.source_code)
+    }
+}
+
+/// Whether a configured name matches the name of a called function or method, see `TaintConfig`.
+fn matches_name(configured: &str, called: &str) -> bool {
+    if configured == called {
+        return true;
+    }
+    let configured: Vec<&str> = configured.split("::").collect();
+    let called: Vec<&str> = called.split("::").collect();
+    called.len() >= 2 && configured.len() > called.len() && configured.ends_with(&called)
+}
+
+fn child_is_mut(node: Node<'_>) -> bool {
+    let mut cursor = node.walk();
+    let is_mut = node
+        .children(&mut cursor)
+        .any(|child| child.kind() == "mutable_specifier");
+    is_mut
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    fn flows(
+        source_code: &str,
+        config: &TaintConfig,
+    ) -> Vec<(usize, SinkKind, String, Vec<String>)> {
+        let tree = parse_rust_code(source_code).unwrap();
+        find_taint_flows(&tree, source_code, config)
+            .into_iter()
+            .map(|flow| {
+                (
+                    flow.sink.start_line,
+                    flow.sink_kind,
+                    flow.source_description,
+                    flow.steps
+                        .into_iter()
+                        .map(|(variable, _)| variable)
+                        .collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_sources_and_sinks() {
+        let source_code = r#"
+use std::process::Command;
+
+fn run(connection: &Connection, name: &str) {
+    let query = format!("SELECT * FROM users WHERE name = '{}'", name);
+    connection.execute(&query);
+
+    let program = std::env::var("PROGRAM").unwrap();
+    let mut command = Command::new(program);
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap();
+    let file = input.trim();
+    Path::new("/data").join(file);
+}
+"#;
+        assert_eq!(
+            flows(source_code, &TaintConfig::default()),
+            vec![
+                (
+                    6,
+                    SinkKind::Sql,
+                    "parameter 'name'".to_string(),
+                    vec!["query".to_string()]
+                ),
+                (
+                    9,
+                    SinkKind::Command,
+                    "call to 'std::env::var'".to_string(),
+                    vec!["program".to_string()]
+                ),
+                (
+                    14,
+                    SinkKind::Path,
+                    "call to 'io::stdin'".to_string(),
+                    vec!["input".to_string(), "file".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_untainted_data() {
+        let source_code = r#"
+fn run(connection: &Connection, id: &str, name: &str) {
+    let id: u32 = id.parse().unwrap();
+    connection.execute(&format!("DELETE FROM users WHERE id = {}", id));
+
+    let name = "guest";
+    connection.execute(&format!("DELETE FROM users WHERE name = '{}'", name));
+
+    let mut path = std::env::var("DIR").unwrap();
+    path = "/tmp".to_string();
+    Path::new(&path);
+
+    fn nested(other: &str) {}
+}
+"#;
+        assert_eq!(flows(source_code, &TaintConfig::default()), vec![]);
+    }
+
+    #[test]
+    fn test_patterns_and_parts() {
+        let source_code = r#"
+fn run(request: Request) {
+    if let Some(user) = request.user {
+        Command::new("id").arg(user);
+    }
+    let mut options = Options::default();
+    options.name = std::env::args().next();
+    for argument in options.name {
+        Path::new(argument);
+    }
+}
+"#;
+        let config = TaintConfig {
+            parameters_are_sources: false,
+            ..TaintConfig::default()
+        };
+        assert_eq!(
+            flows(source_code, &config),
+            vec![(
+                9,
+                SinkKind::Path,
+                "call to 'std::env::args'".to_string(),
+                vec!["options".to_string(), "argument".to_string()]
+            )]
+        );
+        assert_eq!(
+            flows(source_code, &TaintConfig::default()),
+            vec![
+                (
+                    4,
+                    SinkKind::Command,
+                    "parameter 'request'".to_string(),
+                    vec!["user".to_string()]
+                ),
+                (
+                    9,
+                    SinkKind::Path,
+                    "call to 'std::env::args'".to_string(),
+                    vec!["options".to_string(), "argument".to_string()]
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_config_from_parameters() {
+        let parameters = HashMap::from([
+            ("taint_sources".to_string(), "read_form".to_string()),
+            (
+                "taint_sinks".to_string(),
+                "sql:run_query, path:open".to_string(),
+            ),
+            ("taint_sanitizers".to_string(), "escape_sql".to_string()),
+        ]);
+        let config = TaintConfig::from_parameters(&parameters).unwrap();
+        let source_code = r#"
+fn run(db: &Db) {
+    let form = read_form();
+    db.run_query(escape_sql(&form.name));
+    db.run_query(&form.name);
+}
+"#;
+        assert_eq!(
+            flows(source_code, &config),
+            vec![(
+                5,
+                SinkKind::Sql,
+                "call to 'read_form'".to_string(),
+                vec!["form".to_string()]
+            )]
+        );
+
+        let flow =
+            &find_taint_flows(&parse_rust_code(source_code).unwrap(), source_code, &config)[0];
+        assert_eq!(
+            flow.secondary_locations()
+                .iter()
+                .map(|location| (location.message.as_str(), location.location.start_line))
+                .collect::<Vec<_>>(),
+            vec![
+                ("Untrusted data comes from this call to 'read_form'.", 3),
+                ("'form' is tainted.", 3),
+            ]
+        );
+
+        let parameters = HashMap::from([("taint_sinks".to_string(), "eval:run".to_string())]);
+        assert!(matches!(
+            TaintConfig::from_parameters(&parameters),
+            Err(AnalyzerError::GlobalError(message)) if message == "unknown sink kind 'eval'"
+        ));
+    }
+}
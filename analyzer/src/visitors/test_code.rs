@@ -0,0 +1,396 @@
+/*
+ * SonarQube Rust Plugin
+ * Copyright (C) 2025 SonarSource SA
+ * mailto:info AT sonarsource DOT com
+ *
+ * This program is free software; you can redistribute it and/or
+ * modify it under the terms of the Sonar Source-Available License Version 1, as published by SonarSource SA.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.
+ * See the Sonar Source-Available License for more details.
+ *
+ * You should have received a copy of the Sonar Source-Available License
+ * along with this program; if not, see https://sonarsource.com/license/ssal/
+ */
+use crate::{
+    tree::{child_of_kind, NodeIterator, SonarLocation, TreeSitterLocation},
+    visitors::metrics::preceding_attributes,
+};
+use std::{ops::Range, path::Path};
+use tree_sitter::{Node, Tree};
+
+/// Whether a file contains test code only, i.e. it is an integration test under a 'tests' directory.
+pub fn is_test_file(path: &str) -> bool {
+    Path::new(path)
+        .parent()
+        .is_some_and(|parent| parent.components().any(|c| c.as_os_str() == "tests"))
+}
+
+/// The parts of a file which are test code: the items annotated with `#[cfg(test)]` or `#[test]`,
+/// or the whole file for test files.
+#[derive(Debug, Default)]
+pub struct TestCode<'a> {
+    /// Outermost test items, or the root node for test files.
+    nodes: Vec<Node<'a>>,
+    /// Byte ranges of the test items, including their attributes, sorted and without overlaps so that `contains` can
+    /// search them by bisection.
+    ranges: Vec<Range<usize>>,
+}
+
+impl<'a> TestCode<'a> {
+    pub fn find(tree: &'a Tree, source_code: &str, test_file: bool) -> Self {
+        let root = tree.root_node();
+        if test_file {
+            return TestCode {
+                nodes: vec![root],
+                ranges: vec![Range {
+                    start: 0,
+                    end: source_code.len(),
+                }],
+            };
+        }
+
+        let mut test_code = TestCode {
+            nodes: vec![],
+            ranges: vec![],
+        };
+        let mut stack = vec![root];
+        while let Some(node) = stack.pop() {
+            let mut cursor = node.walk();
+            let mut test_attribute_start = None;
+            for child in node.children(&mut cursor) {
+                if is_test_attribute(child, source_code) {
+                    test_attribute_start.get_or_insert(child.start_byte());
+                } else if child.kind() == "attribute_item" || child.is_extra() {
+                    // Other attributes and comments may stand between the test attribute and the item
+                } else if let Some(start) = test_attribute_start.take() {
+                    // Nested test items are already covered by their outermost test item
+                    test_code.nodes.push(child);
+                    test_code.ranges.push(start..child.end_byte());
+                } else {
+                    stack.push(child);
+                }
+            }
+        }
+        test_code.nodes.sort_by_key(|node| node.start_byte());
+        test_code.ranges = merge_ranges(test_code.ranges);
+        test_code
+    }
+
+    /// Whether the given byte offset belongs to the test code.
+    pub fn contains(&self, byte: usize) -> bool {
+        // The first range ending after the offset is the only one which may contain it
+        let index = self.ranges.partition_point(|range| range.end <= byte);
+        self.ranges
+            .get(index)
+            .is_some_and(|range| range.contains(&byte))
+    }
+
+    /// Whether the node starts in the test code.
+    pub fn contains_node(&self, node: Node<'_>) -> bool {
+        self.contains(node.start_byte())
+    }
+
+    /// The outermost test nodes, which do not overlap.
+    pub fn nodes(&self) -> &[Node<'a>] {
+        &self.nodes
+    }
+}
+
+/// Sorts the ranges and merges the overlapping ones, e.g. a test function nested in a `#[cfg(test)]` module.
+fn merge_ranges(mut ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    ranges.sort_by_key(|range| range.start);
+    let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match merged.last_mut() {
+            Some(last) if range.start < last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// A test of a file, as run by the test harness: a test function, or a case of a parameterized test.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct TestFunction {
+    /// Path of the test in the file (e.g. 'tests::test_parse'), without the modules of the file itself. The cases of
+    /// the `#[rstest]` functions are named as the tests generated by rstest, e.g. 'test_sum::case_1' or
+    /// 'test_sum::case_2_overflow' for a `#[case::overflow(...)]` attribute.
+    pub name: String,
+    /// Location of the name of the function, or of the `#[case]` attribute of a case.
+    pub location: SonarLocation,
+}
+
+/// The tests of the file, in source order: the functions annotated with `#[test]` or with the test attribute of a
+/// crate such as `#[tokio::test]`, and the cases of the `#[rstest]` functions. A `#[rstest]` function without cases
+/// is a single test.
+pub fn find_test_functions(tree: &Tree, source_code: &str) -> Vec<TestFunction> {
+    let mut tests = vec![];
+    for function_item in NodeIterator::new(tree.root_node(), |node| node.kind() == "function_item")
+    {
+        let Some(name) = function_item.child_by_field_name("name") else {
+            continue;
+        };
+        let mut path = enclosing_modules(function_item, source_code);
+        path.push(source_code[name.byte_range()].to_string());
+        let mut attributes: Vec<(Node<'_>, String)> = preceding_attributes(function_item)
+            .filter_map(|attribute| {
+                attribute_path(attribute, source_code).map(|path| (attribute, path))
+            })
+            .collect();
+        attributes.reverse();
+
+        if attributes
+            .iter()
+            .any(|(_, path)| path == "rstest" || path == "rstest::rstest")
+        {
+            let cases: Vec<(Node<'_>, Option<&str>)> = attributes
+                .iter()
+                .filter(|(_, path)| path == "case" || path.starts_with("case::"))
+                .map(|(attribute, path)| (*attribute, path.strip_prefix("case::")))
+                .collect();
+            if cases.is_empty() {
+                tests.push(TestFunction {
+                    name: path.join("::"),
+                    location: TreeSitterLocation::from_tree_sitter_node(name)
+                        .to_sonar_location(source_code),
+                });
+            }
+            // rstest pads the numbers of the cases to the width of the largest one
+            let width = cases.len().to_string().len();
+            for (index, (attribute, description)) in cases.into_iter().enumerate() {
+                let mut case = format!("case_{:0width$}", index + 1, width = width);
+                if let Some(description) = description {
+                    case.push('_');
+                    case.push_str(description);
+                }
+                tests.push(TestFunction {
+                    name: format!("{}::{}", path.join("::"), case),
+                    location: TreeSitterLocation::from_tree_sitter_node(attribute)
+                        .to_sonar_location(source_code),
+                });
+            }
+        } else if attributes
+            .iter()
+            .any(|(_, path)| path == "test" || path.ends_with("::test"))
+        {
+            tests.push(TestFunction {
+                name: path.join("::"),
+                location: TreeSitterLocation::from_tree_sitter_node(name)
+                    .to_sonar_location(source_code),
+            });
+        }
+    }
+    tests.sort_by(|a, b| a.location.cmp(&b.location));
+    tests
+}
+
+/// Path of the attribute of an attribute item without its arguments, e.g. 'tokio::test' for `#[tokio::test(flavor = "multi_thread")]`.
+fn attribute_path(attribute_item: Node<'_>, source_code: &str) -> Option<String> {
+    child_of_kind(attribute_item, "attribute")
+        .and_then(|attribute| attribute.named_child(0))
+        .filter(|path| matches!(path.kind(), "identifier" | "scoped_identifier"))
+        .map(|path| {
+            source_code[path.byte_range()]
+                .split_whitespace()
+                .collect::<String>()
+        })
+}
+
+/// Names of the inline `mod` items containing the node, outermost first.
+fn enclosing_modules(node: Node<'_>, source_code: &str) -> Vec<String> {
+    let mut names: Vec<String> = std::iter::successors(node.parent(), |parent| parent.parent())
+        .filter(|parent| parent.kind() == "mod_item")
+        .filter_map(|module| module.child_by_field_name("name"))
+        .map(|name| source_code[name.byte_range()].to_string())
+        .collect();
+    names.reverse();
+    names
+}
+
+/// Whether the node is a `#[cfg(test)]` or a `#[test]` attribute, including test attributes of crates such as `#[tokio::test]`.
+pub(crate) fn is_test_attribute(node: Node<'_>, source_code: &str) -> bool {
+    if is_cfg_test_attribute(node, source_code) {
+        return true;
+    }
+
+    node.kind() == "attribute_item"
+        && child_of_kind(node, "attribute")
+            .filter(|attribute| attribute.child_by_field_name("arguments").is_none())
+            .and_then(|attribute| attribute.named_child(0))
+            .is_some_and(|path| {
+                let name = match path.kind() {
+                    "scoped_identifier" => path.child_by_field_name("name"),
+                    _ => Some(path),
+                };
+                name.is_some_and(|name| &source_code[name.byte_range()] == "test")
+            })
+}
+
+pub(crate) fn is_cfg_test_attribute(node: Node<'_>, source_code: &str) -> bool {
+    // '#[cfg(test)]' attributes have the following structure:
+    //  (attribute_item (attribute (identifier) arguments: (token_tree (identifier))))
+    if node.kind() != "attribute_item" {
+        return false;
+    }
+
+    if let Some(attribute) = child_of_kind(node, "attribute") {
+        let identifier = child_of_kind(attribute, "identifier")
+            .map(|n| &source_code[n.start_byte()..n.end_byte()]);
+
+        let argument = attribute
+            .child_by_field_name("arguments")
+            .and_then(|arg| child_of_kind(arg, "identifier"))
+            .map(|n| &source_code[n.start_byte()..n.end_byte()]);
+
+        return Some("cfg") == identifier && Some("test") == argument;
+    }
+
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tree::parse_rust_code;
+
+    #[test]
+    fn test_is_test_file() {
+        assert!(is_test_file("tests/integration.rs"));
+        assert!(is_test_file("crates/foo/tests/common/mod.rs"));
+        assert!(!is_test_file("src/tests.rs"));
+        assert!(!is_test_file("src/lib.rs"));
+        assert!(!is_test_file("tests.rs"));
+    }
+
+    #[test]
+    fn test_is_cfg_test_attribute() {
+        fn check(source: &str) -> bool {
+            let tree = parse_rust_code(source).unwrap();
+            is_cfg_test_attribute(tree.root_node().child(0).unwrap(), source)
+        }
+
+        assert!(check("#[cfg(test)]"));
+        assert!(check("#[ cfg (test ) ]"));
+        assert!(!check("#[cfg(abc)]"));
+        assert!(!check("#[cfg(target=\"Windows\")]"));
+        assert!(!check("#[cfg(not(test))]"));
+        assert!(!check("#[test]"));
+    }
+
+    #[test]
+    fn test_is_test_attribute() {
+        fn check(source: &str) -> bool {
+            let tree = parse_rust_code(source).unwrap();
+            is_test_attribute(tree.root_node().child(0).unwrap(), source)
+        }
+
+        assert!(check("#[test]"));
+        assert!(check("#[cfg(test)]"));
+        assert!(check("#[tokio::test]"));
+        assert!(!check("#[cfg(not(test))]"));
+        assert!(!check("#[test_case(1)]"));
+        assert!(!check("#[derive(Debug)]"));
+        assert!(!check("fn test() {}"));
+    }
+
+    #[test]
+    fn test_find_test_code() {
+        let source_code = r#"
+fn production() {}
+
+#[test]
+#[should_panic]
+fn standalone_test() { panic!() }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn nested_test() {}
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let test_code = TestCode::find(&tree, source_code, false);
+
+        let nodes: Vec<&str> = test_code.nodes().iter().map(|node| node.kind()).collect();
+        assert_eq!(nodes, vec!["function_item", "mod_item"]);
+
+        let offset = |text: &str| source_code.find(text).unwrap();
+        assert!(!test_code.contains(offset("production")));
+        assert!(test_code.contains(offset("#[test]")));
+        assert!(test_code.contains(offset("#[should_panic]")));
+        assert!(test_code.contains(offset("nested_test")));
+        assert!(!test_code.contains(offset("\n\n#[cfg(test)]")));
+        assert!(!test_code.contains(source_code.len()));
+    }
+
+    #[test]
+    fn test_merge_ranges() {
+        assert!(merge_ranges(vec![]).is_empty());
+        assert_eq!(
+            merge_ranges(vec![20..30, 0..10, 22..25, 5..12, 12..15]),
+            vec![0..12, 12..15, 20..30]
+        );
+    }
+
+    #[test]
+    fn test_find_test_functions() {
+        let source_code = r#"
+#[test]
+fn standalone_test() {}
+
+fn production() {}
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test(flavor = "multi_thread")]
+    async fn async_test() {}
+
+    #[rstest]
+    #[case(1, 2)]
+    #[case::overflow(i32::MAX, 1)]
+    fn test_sum(#[case] a: i32, #[case] b: i32) {}
+
+    #[rstest]
+    fn test_fixture(value: i32) {}
+}
+"#;
+        let tree = parse_rust_code(source_code).unwrap();
+        let tests: Vec<(String, usize)> = find_test_functions(&tree, source_code)
+            .into_iter()
+            .map(|test| (test.name, test.location.start_line))
+            .collect();
+
+        assert_eq!(
+            tests,
+            vec![
+                ("standalone_test".to_string(), 3),
+                ("tests::async_test".to_string(), 10),
+                ("tests::test_sum::case_1".to_string(), 13),
+                ("tests::test_sum::case_2_overflow".to_string(), 14),
+                ("tests::test_fixture".to_string(), 18),
+            ]
+        );
+
+        let source_code = format!(
+            "#[rstest]\n{}fn many(#[case] n: i32) {{}}",
+            "#[case(0)]\n".repeat(10)
+        );
+        let tree = parse_rust_code(&source_code).unwrap();
+        let tests = find_test_functions(&tree, &source_code);
+        assert_eq!(tests[0].name, "many::case_01");
+        assert_eq!(tests[9].name, "many::case_10");
+    }
+
+    #[test]
+    fn test_find_test_code_in_test_file() {
+        let source_code = "fn helper() {}";
+        let tree = parse_rust_code(source_code).unwrap();
+        let test_code = TestCode::find(&tree, source_code, true);
+
+        assert!(test_code.contains(0));
+        assert_eq!(test_code.nodes().len(), 1);
+    }
+}
@@ -31,6 +31,11 @@ public class Analyzer implements AutoCloseable {
 
   private static final Logger LOG = LoggerFactory.getLogger(Analyzer.class);
 
+  /**
+   * Version of the protocol spoken with the analyzer, which must match the one implemented by the analyzer binary.
+   */
+  public static final int PROTOCOL_VERSION = 8;
+
   private final ProcessWrapper process;
   private final DataOutputStream outputStream;
   private final DataInputStream inputStream;
@@ -38,30 +43,53 @@ public class Analyzer implements AutoCloseable {
   public Analyzer(List<String> command, Map<String, String> parameters) {
     try {
       process = new ProcessWrapper();
-      process.start(command, null, null, LOG::warn);
+      process.start(command, null, null, Analyzer::log);
       this.outputStream = new DataOutputStream(process.getOutputStream());
       this.inputStream = new DataInputStream(process.getInputStream());
 
       writeString("sonar");
+      writeInt(PROTOCOL_VERSION);
+      checkProtocolVersion();
       writeMap(parameters);
     } catch (IOException ex) {
       throw new IllegalStateException("Failed to start the analyzer process", ex);
     }
   }
 
+  private void checkProtocolVersion() throws IOException {
+    String messageType = readString();
+    if (!"version".equals(messageType)) {
+      throw new IllegalStateException("Unexpected message from the analyzer: " + messageType);
+    }
+    int version = inputStream.readInt();
+    if (version != PROTOCOL_VERSION) {
+      throw new IllegalStateException(String.format("The analyzer implements version %d of the protocol, but the plugin expects version %d", version,
+        PROTOCOL_VERSION));
+    }
+  }
+
   /**
    * Use the analyzer subprocess to analyze the given code.
+   * @param path path of the file relative to the project base directory, used to recognize test files
    * @throws IOException if executing the analyzer fails due to an I/O error
    */
-  public AnalysisResult analyze(String code) throws IOException {
+  public AnalysisResult analyze(String path, String code) throws IOException {
     writeString("analyze");
+    writeString(path);
 
     byte[] bytes = code.getBytes(StandardCharsets.UTF_8);
     writeInt(bytes.length);
     write(bytes);
 
     List<HighlightTokens> highlightTokens = new ArrayList<>();
+    List<Symbol> symbols = new ArrayList<>();
     Measures measures = new Measures();
+    boolean testFile = false;
+    List<DistributionRange> functionComplexityDistribution = new ArrayList<>();
+    List<Integer> nclocData = new ArrayList<>();
+    List<Integer> commentLinesData = new ArrayList<>();
+    List<Integer> executableLines = new ArrayList<>();
+    List<TestCase> testCases = new ArrayList<>();
     List<CpdToken> cpdTokens = new ArrayList<>();
     List<Issue> issues = new ArrayList<>();
 
@@ -71,6 +99,14 @@ public class Analyzer implements AutoCloseable {
         String tokenType = readString();
         Location location = readLocation();
         highlightTokens.add(new HighlightTokens(tokenType, location));
+      } else if ("symbol".equals(messageType)) {
+        Location declaration = readLocation();
+        int numReferences = inputStream.readInt();
+        List<Location> references = new ArrayList<>();
+        for (int i = 0; i < numReferences; i++) {
+          references.add(readLocation());
+        }
+        symbols.add(new Symbol(declaration, references));
       } else if ("metrics".equals(messageType)) {
         int ncloc = inputStream.readInt();
         int commentLines = inputStream.readInt();
@@ -79,32 +115,99 @@ public class Analyzer implements AutoCloseable {
         int classes = inputStream.readInt();
         int cognitiveComplexity = inputStream.readInt();
         int cyclomaticComplexity = inputStream.readInt();
+        int publicApi = inputStream.readInt();
+        int publicDocumentedApi = inputStream.readInt();
+        testFile = inputStream.readInt() != 0;
 
-        measures = new Measures(ncloc, commentLines, functions, statements, classes, cognitiveComplexity, cyclomaticComplexity);
+        measures = new Measures(ncloc, commentLines, functions, statements, classes, cognitiveComplexity, cyclomaticComplexity, publicApi, publicDocumentedApi);
+      } else if ("function_complexity_distribution".equals(messageType)) {
+        int numRanges = inputStream.readInt();
+        for (int i = 0; i < numRanges; i++) {
+          int lowerBound = inputStream.readInt();
+          int count = inputStream.readInt();
+          functionComplexityDistribution.add(new DistributionRange(lowerBound, count));
+        }
+      } else if ("ncloc_data".equals(messageType)) {
+        readLines(nclocData);
+      } else if ("comment_lines_data".equals(messageType)) {
+        readLines(commentLinesData);
+      } else if ("executable_lines".equals(messageType)) {
+        readLines(executableLines);
+      } else if ("test".equals(messageType)) {
+        String name = readString();
+        Location location = readLocation();
+        testCases.add(new TestCase(name, location));
       } else if ("cpd".equals(messageType)) {
         String image = readString();
         Location location = readLocation();
         cpdTokens.add(new CpdToken(image, location));
-      } else if ("issue".endsWith(messageType)) {
-        String ruleKey = readString();
-        String message = readString();
-        Location location = readLocation();
-        int numSecondaryLocations = inputStream.readInt();
-
-        List<SecondaryLocation> secondaryLocations = new ArrayList<>();
-        for (int i = 0; i < numSecondaryLocations; i++) {
-          String secondaryMessage = readString();
-          Location secondaryLocation = readLocation();
-          secondaryLocations.add(new SecondaryLocation(secondaryMessage, secondaryLocation));
-        }
-
-        issues.add(new Issue(ruleKey, message, location, secondaryLocations));
+      } else if ("issue".equals(messageType)) {
+        issues.add(readIssue(null));
       } else {
         break;
       }
     }
 
-    return new AnalysisResult(highlightTokens, measures, cpdTokens, issues);
+    return new AnalysisResult(highlightTokens, symbols, measures, testFile, functionComplexityDistribution, nclocData, commentLinesData, executableLines, testCases, cpdTokens, issues);
+  }
+
+  /**
+   * Use the analyzer subprocess to compute the issues of the rules which depend on all the files analyzed before, e.g.
+   * the unused public items, and on the Cargo.toml manifest of the project directory.
+   * @throws IOException if executing the analyzer fails due to an I/O error
+   */
+  public List<ProjectIssue> analyzeProject() throws IOException {
+    writeString("project");
+
+    List<ProjectIssue> issues = new ArrayList<>();
+    while ("project_issue".equals(readString())) {
+      String path = readString();
+      List<String> secondaryPaths = new ArrayList<>();
+      Issue issue = readIssue(secondaryPaths);
+      issues.add(new ProjectIssue(path, issue, secondaryPaths));
+    }
+    return issues;
+  }
+
+  /**
+   * @param secondaryPaths the paths of the files of the secondary locations, which precede them, are added to it when not null
+   */
+  private Issue readIssue(List<String> secondaryPaths) throws IOException {
+    String ruleKey = readString();
+    String message = readString();
+    String severity = readString();
+    String hotspotStatus = readString();
+    Location location = readLocation();
+    int numSecondaryLocations = inputStream.readInt();
+
+    List<SecondaryLocation> secondaryLocations = new ArrayList<>();
+    for (int i = 0; i < numSecondaryLocations; i++) {
+      if (secondaryPaths != null) {
+        String secondaryPath = readString();
+        secondaryPaths.add(secondaryPath.isEmpty() ? null : secondaryPath);
+      }
+      String secondaryMessage = readString();
+      Location secondaryLocation = readLocation();
+      secondaryLocations.add(new SecondaryLocation(secondaryMessage, secondaryLocation));
+    }
+
+    return new Issue(ruleKey, message, location, secondaryLocations, severity.isEmpty() ? null : severity,
+      hotspotStatus.isEmpty() ? null : hotspotStatus);
+  }
+
+  /**
+   * Log a line written by the analyzer on its standard error, using the level it starts with ("debug", "info", "warn" or "error").
+   */
+  static void log(String line) {
+    var separator = line.indexOf(' ');
+    var message = separator < 0 ? line : line.substring(separator + 1);
+    switch (separator < 0 ? "" : line.substring(0, separator)) {
+      case "debug" -> LOG.debug(message);
+      case "info" -> LOG.info(message);
+      case "warn" -> LOG.warn(message);
+      case "error" -> LOG.error(message);
+      default -> LOG.warn(line);
+    }
   }
 
   @Override
@@ -128,14 +231,23 @@ public class Analyzer implements AutoCloseable {
     return new Location(startLine, startColumn, endLine, endColumn);
   }
 
+  private void readLines(List<Integer> lines) throws IOException {
+    int numLines = inputStream.readInt();
+    for (int i = 0; i < numLines; i++) {
+      lines.add(inputStream.readInt());
+    }
+  }
+
   private void writeInt(int value) throws IOException {
     outputStream.writeInt(value);
     outputStream.flush();
   }
 
   private void writeString(String value) throws IOException {
-    outputStream.writeInt(value.length());
-    outputStream.write(value.getBytes(StandardCharsets.UTF_8));
+    // The length is the one of the encoded bytes, which differs from the number of chars for non-ASCII values
+    byte[] bytes = value.getBytes(StandardCharsets.UTF_8);
+    outputStream.writeInt(bytes.length);
+    outputStream.write(bytes);
     outputStream.flush();
   }
 
@@ -152,18 +264,43 @@ public class Analyzer implements AutoCloseable {
     }
   }
 
-  public record AnalysisResult(List<HighlightTokens> highlightTokens, Measures measures, List<CpdToken> cpdTokens, List<Issue> issues) {
+  /**
+   * @param testFile whether the file only contains test code, e.g. an integration test under a "tests" directory
+   */
+  public record AnalysisResult(List<HighlightTokens> highlightTokens, List<Symbol> symbols, Measures measures, boolean testFile,
+    List<DistributionRange> functionComplexityDistribution, List<Integer> nclocData, List<Integer> commentLinesData,
+    List<Integer> executableLines, List<TestCase> testCases, List<CpdToken> cpdTokens, List<Issue> issues) {
   }
 
   public record HighlightTokens(String tokenType, Location location) {
   }
 
-  public record Measures(int ncloc, int commentLines, int functions, int statements, int classes, int cognitiveComplexity, int cyclomaticComplexity) {
+  public record Symbol(Location declaration, List<Location> references) {
+  }
+
+  /**
+   * @param publicApi public functions, structs, enums and traits
+   * @param publicDocumentedApi public items preceded by a doc comment or a "doc" attribute
+   */
+  public record Measures(int ncloc, int commentLines, int functions, int statements, int classes, int cognitiveComplexity, int cyclomaticComplexity,
+    int publicApi, int publicDocumentedApi) {
     public Measures() {
-      this(0, 0, 0, 0, 0, 0, 0);
+      this(0, 0, 0, 0, 0, 0, 0, 0, 0);
     }
   }
 
+  /**
+   * Number of functions whose complexity is at least the lower bound of the range, and below the lower bound of the next range.
+   */
+  public record DistributionRange(int lowerBound, int count) {
+  }
+
+  /**
+   * A test function of the file, or a case of a parameterized test, named by its path in the file.
+   */
+  public record TestCase(String name, Location location) {
+  }
+
   public record CpdToken(String image, Location location) {
   }
 
@@ -171,10 +308,22 @@ public class Analyzer implements AutoCloseable {
 
   }
 
-  public record Issue(String ruleKey, String message, Location location, List<SecondaryLocation> secondaryLocations) {
+  /**
+   * @param severity overridden severity of the issue (e.g. "BLOCKER"), or null for the default severity of the rule
+   * @param hotspotStatus review status of the security hotspot (e.g. "TO_REVIEW"), or null for the other issues
+   */
+  public record Issue(String ruleKey, String message, Location location, List<SecondaryLocation> secondaryLocations, String severity,
+    String hotspotStatus) {
   }
 
   public record SecondaryLocation(String message, Location location) {
 
   }
+
+  /**
+   * @param path path of the file of the issue relative to the project base directory, e.g. "Cargo.toml"
+   * @param secondaryPaths paths of the files of the secondary locations, null for the file of the issue
+   */
+  public record ProjectIssue(String path, Issue issue, List<String> secondaryPaths) {
+  }
 }
@@ -23,6 +23,7 @@ import org.sonarsource.rust.clippy.ClippySensor;
 import org.sonarsource.rust.coverage.CoberturaSensor;
 import org.sonarsource.rust.coverage.LcovSensor;
 import org.sonar.api.Plugin;
+import org.sonar.api.PropertyType;
 import org.sonar.api.config.PropertyDefinition;
 import org.sonar.api.config.PropertyDefinition.ConfigScope;
 
@@ -76,6 +77,295 @@ public class RustPlugin implements Plugin {
         .multiValues(true)
         .build());
 
+    // Analysis timeout
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.ANALYSIS_TIMEOUT)
+        .category(CATEGORY_RUST)
+        .subCategory("Analysis Scope")
+        .name("Analysis timeout")
+        .description("Maximum time in milliseconds spent analyzing a single file. When exceeded, only the results computed so far are "
+          + "reported for that file. No timeout is applied by default.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.INTEGER)
+        .build());
+
+    // Maximum file size
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.MAX_FILE_SIZE)
+        .category(CATEGORY_RUST)
+        .subCategory("Analysis Scope")
+        .name("Maximum file size")
+        .description("Maximum size in bytes of the files to analyze. Larger files, such as generated bindings, are skipped with a warning. "
+          + "No limit is applied by default.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.INTEGER)
+        .build());
+
+    // Soft memory limit of the analysis of a file
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.MEMORY_LIMIT)
+        .category(CATEGORY_RUST)
+        .subCategory("Analysis Scope")
+        .name("Memory limit")
+        .description("Soft limit in bytes of the estimated memory of the analysis of a file. Only the metrics of the files exceeding it "
+          + "are computed, with a warning. No limit is applied by default.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.INTEGER)
+        .build());
+
+    // Concurrent steps of the analysis of a file
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.PARALLEL_STEPS)
+        .category(CATEGORY_RUST)
+        .subCategory("Analysis Scope")
+        .name("Parallel analysis steps")
+        .description("Compute the highlighting, the metrics, the duplications and the issues of each file on separate threads. "
+          + "It shortens the analysis of very large files, such as generated bindings.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.BOOLEAN)
+        .defaultValue("false")
+        .build());
+
+    // Expansion of declarative macros
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.EXPAND_MACROS)
+        .category(CATEGORY_RUST)
+        .subCategory("Analysis Scope")
+        .name("Expand macros")
+        .description("Also check the code generated by the invocations of the <code>macro_rules!</code> macros defined in the same file. "
+          + "The issues found in the expanded code are reported on the invocations. Only the macros without repetitions are expanded.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.BOOLEAN)
+        .defaultValue("false")
+        .build());
+
+    // Code blocks of the doc comments
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.ANALYZE_DOCTESTS)
+        .category(CATEGORY_RUST)
+        .subCategory("Analysis Scope")
+        .name("Analyze doctests")
+        .description("Also highlight the Rust code blocks of the doc comments, and report their syntax errors in the doc comments.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.BOOLEAN)
+        .defaultValue("false")
+        .build());
+
+    // Targets of the Cargo workspace
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.CARGO_METADATA)
+        .category(CATEGORY_RUST)
+        .subCategory("Analysis Scope")
+        .name("Cargo metadata")
+        .description("Identify the build scripts, examples, tests and benches of the workspace from its Cargo targets: "
+          + "<code>run</code> runs <code>cargo metadata</code> in the project directory, any other value is the path of its JSON output, "
+          + "relative to the project directory. The files of the tests and benches are analyzed as test code, and the examples are not "
+          + "checked for duplications.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.STRING)
+        .build());
+
+    // Normalization of literals in duplication detection
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.CPD_NORMALIZE_LITERALS)
+        .category(CATEGORY_RUST)
+        .subCategory("Duplications")
+        .name("Normalize literals")
+        .description("Ignore the values of number and string literals when detecting duplicated code, so that blocks differing only "
+          + "by their constants are reported as duplications.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.BOOLEAN)
+        .defaultValue("true")
+        .build());
+
+    // Exclusion of test code from duplication detection
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.CPD_EXCLUDE_TEST_CODE)
+        .category(CATEGORY_RUST)
+        .subCategory("Duplications")
+        .name("Exclude test code")
+        .description("Ignore test code when detecting duplicated code: the items annotated with <code>#[cfg(test)]</code> or "
+          + "<code>#[test]</code>, and the files under a <code>tests</code> directory.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.BOOLEAN)
+        .defaultValue("true")
+        .build());
+
+    // Exclusion of generated code from duplication detection
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.CPD_EXCLUDE_GENERATED_CODE)
+        .category(CATEGORY_RUST)
+        .subCategory("Duplications")
+        .name("Exclude generated code")
+        .description("Ignore generated code when detecting duplicated code: the items annotated with "
+          + "<code>#[automatically_derived]</code>, and the files whose leading comments contain a generated code marker.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.BOOLEAN)
+        .defaultValue("true")
+        .build());
+
+    // Minimum size of the items checked for duplications
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.CPD_MINIMUM_ITEM_TOKENS)
+        .category(CATEGORY_RUST)
+        .subCategory("Duplications")
+        .name("Minimum tokens of items")
+        .description("Ignore the items, such as functions, having fewer tokens when detecting duplicated code. "
+          + "No minimum is applied by default.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.INTEGER)
+        .build());
+
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.CPD_MINIMUM_ITEM_LINES)
+        .category(CATEGORY_RUST)
+        .subCategory("Duplications")
+        .name("Minimum lines of items")
+        .description("Ignore the items, such as one-line getters, spanning fewer lines when detecting duplicated code. "
+          + "No minimum is applied by default.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.INTEGER)
+        .build());
+
+    // Analysis of generated code
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.GENERATED_CODE_MODE)
+        .category(CATEGORY_RUST)
+        .subCategory("Generated Code")
+        .name("Generated code analysis")
+        .description("How generated files are analyzed: <code>suppress</code> only computes their metrics, without issues nor "
+          + "duplications, <code>skip</code> ignores them, and <code>analyze</code> analyzes them as any other file.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.SINGLE_SELECT_LIST)
+        .options("suppress", "skip", "analyze")
+        .defaultValue("suppress")
+        .build());
+
+    // Generated code markers
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.GENERATED_MARKERS)
+        .category(CATEGORY_RUST)
+        .subCategory("Generated Code")
+        .name("Generated code markers")
+        .description("Comma-delimited list of texts which denote a generated file when found in its leading comments.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .multiValues(true)
+        .defaultValue("@generated,automatically generated by rust-bindgen,DO NOT EDIT")
+        .build());
+
+    // Generated code paths
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.GENERATED_PATHS)
+        .category(CATEGORY_RUST)
+        .subCategory("Generated Code")
+        .name("Generated code paths")
+        .description("Comma-delimited list of glob patterns, relative to the project directory, of generated files without "
+          + "markers, e.g. <code>src/proto/**</code>.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .multiValues(true)
+        .build());
+
+    ////////////////////////// FORMATTING //////////////////////////
+
+    // Command of the formatting check
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.RUSTFMT_COMMAND)
+        .category(CATEGORY_RUST)
+        .subCategory("Formatting")
+        .name("Rustfmt command")
+        .description("Command used to check that the files are formatted, e.g. <code>rustfmt</code>. It runs in the project directory, "
+          + "with the <code>rustfmt.toml</code> configuration of the project. The files are not checked when it is not set.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.STRING)
+        .build());
+
+    // Edition of the formatting check
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.RUSTFMT_EDITION)
+        .category(CATEGORY_RUST)
+        .subCategory("Formatting")
+        .name("Rustfmt edition")
+        .description("Rust edition of the code, passed to rustfmt.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.STRING)
+        .defaultValue("2021")
+        .build());
+
+    // Verbosity of the analyzer
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.LOG_LEVEL)
+        .category(CATEGORY_RUST)
+        .subCategory("Logging")
+        .name("Analyzer log level")
+        .description("Minimum level of the messages of the analyzer forwarded to the scanner logs. It is <code>debug</code> "
+          + "when the scanner runs in verbose mode, and <code>info</code> otherwise.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.SINGLE_SELECT_LIST)
+        .options("error", "warn", "info", "debug")
+        .build());
+
+    // JSON log of the analyzer
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.LOG_FILE)
+        .category(CATEGORY_RUST)
+        .subCategory("Logging")
+        .name("Analyzer log file")
+        .description("Path of a file to which the messages of the analyzer are appended as JSON lines, with the analyzed file, "
+          + "the rule and the location they relate to.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.STRING)
+        .build());
+
+    ////////////////////////// TYPE-AWARE ANALYSIS //////////////////////////
+
+    // Command of the type-aware analysis
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.RUST_ANALYZER_COMMAND)
+        .category(CATEGORY_RUST)
+        .subCategory("Type-Aware Analysis")
+        .name("rust-analyzer command")
+        .description("Command of the rust-analyzer server inferring the types of the type-aware rules, e.g. <code>rust-analyzer</code>. "
+          + "It runs in the project directory, and is only used by an analyzer built with the <code>type-aware</code> feature.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .type(PropertyType.STRING)
+        .build());
+
+    ////////////////////////// DEPENDENCIES //////////////////////////
+
+    // Advisories report paths
+    context.addExtension(
+      PropertyDefinition
+        .builder(RustSensor.ADVISORIES_REPORT_PATHS)
+        .category(CATEGORY_RUST)
+        .subCategory("Dependencies")
+        .name("Advisories report paths")
+        .description("Comma-delimited list of paths, relative to the project directory, to JSON reports of the advisories on "
+          + "the dependencies of the <code>Cargo.toml</code> manifest, generated with <code>cargo audit --json</code> or "
+          + "<code>cargo deny --format json check advisories</code>.")
+        .onConfigScopes(ConfigScope.PROJECT)
+        .multiValues(true)
+        .build());
+
     ////////////////////////// CLIPPY //////////////////////////
 
     // Clippy report paths
@@ -57,7 +57,7 @@ public class RustRulesDefinition implements RulesDefinition {
     }
   }
 
-  public static final Set<String> SONAR_RULES = Set.of("S2260", "S3776");
+  public static final Set<String> SONAR_RULES = Set.of("S104", "S107", "S108", "S109", "S124", "S125", "S134", "S138", "S1066", "S1125", "S1134", "S1135", "S1172", "S1186", "S1192", "S1313", "S1481", "S1764", "S1862", "S1871", "S2068", "S2260", "S3776", "S3923", "S4144", "S7465", "S7466", "S7467", "S7468", "S7469", "S7470", "S7471", "S7472", "S7473", "S7474", "S7475", "S7476");
 
   private final SonarRuntime sonarRuntime;
 
@@ -88,7 +88,21 @@ public class RustRulesDefinition implements RulesDefinition {
 
   public static List<RuleParameter> parameters() {
     return List.of(
-      new RuleParameter("S3776", "threshold", "15", "The maximum authorized complexity", RuleParamType.INTEGER)
+      new RuleParameter("S104", "maximum", "1000", "The maximum authorized lines of code of a file", RuleParamType.INTEGER),
+      new RuleParameter("S107", "max", "7", "The maximum authorized number of parameters, without self", RuleParamType.INTEGER),
+      new RuleParameter("S109", "authorizedNumbers", "-1,0,1,2", "Comma-separated list of the numbers which are not magic", RuleParamType.STRING),
+      new RuleParameter("S1134", "additionalMarkers", "", "Comma-separated list of tags tracked in addition to FIXME", RuleParamType.STRING),
+      new RuleParameter("S1135", "additionalMarkers", "", "Comma-separated list of tags tracked in addition to TODO", RuleParamType.STRING),
+      new RuleParameter("S1192", "threshold", "3", "The minimum number of occurrences of the duplicated strings", RuleParamType.INTEGER),
+      new RuleParameter("S124", "regularExpression", "", "The regular expression matched against the comments, an empty one raises no issue", RuleParamType.STRING),
+      new RuleParameter("S124", "message", "The regular expression matches this comment.", "The message of the issues", RuleParamType.STRING),
+      new RuleParameter("S134", "max", "3", "The maximum authorized nesting depth of the \"if\", \"match\" and loop expressions", RuleParamType.INTEGER),
+      new RuleParameter("S138", "max", "100", "The maximum authorized lines of code of the body of a function or closure", RuleParamType.INTEGER),
+      new RuleParameter("S2068", "credentialWords", "password,passwd,passphrase,secret,token,api_key,apikey,access_key,private_key", "Comma-separated list of the words which identify the credentials in the names", RuleParamType.STRING),
+      new RuleParameter("S3776", "threshold", "15", "The maximum authorized complexity", RuleParamType.INTEGER),
+      new RuleParameter("S7465", "sizeThreshold", "256", "The minimum size in bytes of the types that should be passed by reference", RuleParamType.INTEGER),
+      new RuleParameter("S7467", "allowInMain", "false", "Whether the calls are allowed in the main functions of binaries", RuleParamType.BOOLEAN),
+      new RuleParameter("S7468", "allowJustifiedUnreachable", "false", "Whether the \"unreachable!\" invocations justified by a comment are allowed", RuleParamType.BOOLEAN)
     );
   }
 
@@ -18,9 +18,12 @@ package org.sonarsource.rust.plugin;
 
 import org.sonarsource.rust.plugin.PlatformDetection.Platform;
 import java.io.IOException;
+import java.nio.file.Path;
+import java.util.Collections;
 import java.util.HashMap;
 import java.util.List;
 import java.util.Map;
+import java.util.stream.Collectors;
 import java.util.stream.StreamSupport;
 import org.slf4j.Logger;
 import org.slf4j.LoggerFactory;
@@ -28,31 +31,60 @@ import org.sonar.api.batch.fs.FilePredicate;
 import org.sonar.api.batch.fs.FileSystem;
 import org.sonar.api.batch.fs.InputFile;
 import org.sonar.api.batch.fs.TextRange;
+import org.sonar.api.batch.rule.Severity;
 import org.sonar.api.batch.measure.Metric;
 import org.sonar.api.batch.sensor.Sensor;
 import org.sonar.api.batch.sensor.SensorContext;
 import org.sonar.api.batch.sensor.SensorDescriptor;
 import org.sonar.api.batch.sensor.highlighting.NewHighlighting;
 import org.sonar.api.batch.sensor.highlighting.TypeOfText;
+import org.sonar.api.batch.sensor.symbol.NewSymbol;
+import org.sonar.api.batch.sensor.symbol.NewSymbolTable;
 import org.sonar.api.measures.CoreMetrics;
+import org.sonar.api.measures.FileLinesContextFactory;
 import org.sonar.api.rule.RuleKey;
 
 public class RustSensor implements Sensor {
 
   private static final Logger LOG = LoggerFactory.getLogger(RustSensor.class);
 
+  public static final String ANALYSIS_TIMEOUT = "sonar.rust.analysis.timeoutMs";
+  public static final String MAX_FILE_SIZE = "sonar.rust.analysis.maxFileSize";
+  public static final String MEMORY_LIMIT = "sonar.rust.analysis.memoryLimit";
+  public static final String PARALLEL_STEPS = "sonar.rust.analysis.parallelSteps";
+  public static final String EXPAND_MACROS = "sonar.rust.analysis.expandMacros";
+  public static final String ANALYZE_DOCTESTS = "sonar.rust.analysis.doctests";
+  public static final String CARGO_METADATA = "sonar.rust.analysis.cargoMetadata";
+  public static final String CPD_NORMALIZE_LITERALS = "sonar.rust.cpd.normalizeLiterals";
+  public static final String CPD_EXCLUDE_TEST_CODE = "sonar.rust.cpd.excludeTestCode";
+  public static final String CPD_EXCLUDE_GENERATED_CODE = "sonar.rust.cpd.excludeGeneratedCode";
+  public static final String CPD_MINIMUM_ITEM_TOKENS = "sonar.rust.cpd.minimumItemTokens";
+  public static final String CPD_MINIMUM_ITEM_LINES = "sonar.rust.cpd.minimumItemLines";
+  public static final String GENERATED_CODE_MODE = "sonar.rust.generatedCode.mode";
+  public static final String GENERATED_MARKERS = "sonar.rust.generatedCode.markers";
+  public static final String GENERATED_PATHS = "sonar.rust.generatedCode.paths";
+  public static final String RUSTFMT_COMMAND = "sonar.rust.rustfmt.command";
+  public static final String RUSTFMT_EDITION = "sonar.rust.rustfmt.edition";
+  public static final String RUST_ANALYZER_COMMAND = "sonar.rust.rustAnalyzer.command";
+  public static final String ADVISORIES_REPORT_PATHS = "sonar.rust.advisories.reportPaths";
+  public static final String LOG_LEVEL = "sonar.rust.logLevel";
+  public static final String LOG_FILE = "sonar.rust.logFile";
+
   private final AnalyzerFactory analyzerFactory;
   private final PlatformDetection platformDetection;
   private final AnalysisWarningsWrapper analysisWarnings;
+  private final FileLinesContextFactory fileLinesContextFactory;
 
-  public RustSensor(AnalyzerFactory analyzerFactory, AnalysisWarningsWrapper analysisWarnings) {
-    this(analyzerFactory, analysisWarnings, new PlatformDetection());
+  public RustSensor(AnalyzerFactory analyzerFactory, AnalysisWarningsWrapper analysisWarnings, FileLinesContextFactory fileLinesContextFactory) {
+    this(analyzerFactory, analysisWarnings, fileLinesContextFactory, new PlatformDetection());
   }
 
-  RustSensor(AnalyzerFactory analyzerFactory, AnalysisWarningsWrapper analysisWarnings, PlatformDetection platformDetection) {
+  RustSensor(AnalyzerFactory analyzerFactory, AnalysisWarningsWrapper analysisWarnings, FileLinesContextFactory fileLinesContextFactory,
+    PlatformDetection platformDetection) {
     this.analyzerFactory = analyzerFactory;
     this.platformDetection = platformDetection;
     this.analysisWarnings = analysisWarnings;
+    this.fileLinesContextFactory = fileLinesContextFactory;
   }
 
   @Override
@@ -85,12 +117,48 @@ public class RustSensor implements Sensor {
         parameters.put(String.format("%s:%s", activeRule.ruleKey().rule(), parameter.getKey()), parameter.getValue());
       }
     }
+    // The analyzer reads the sonar-rust.toml configuration file of the project from this directory
+    parameters.put("project_dir", sensorContext.fileSystem().baseDir().getAbsolutePath());
+    sensorContext.config().get(ANALYSIS_TIMEOUT).ifPresent(timeout -> parameters.put("timeout_ms", timeout));
+    sensorContext.config().get(MAX_FILE_SIZE).ifPresent(maxFileSize -> parameters.put("max_file_size", maxFileSize));
+    sensorContext.config().get(MEMORY_LIMIT).ifPresent(memoryLimit -> parameters.put("memory_limit", memoryLimit));
+    sensorContext.config().get(PARALLEL_STEPS).ifPresent(parallel -> parameters.put("parallel_steps", parallel));
+    sensorContext.config().get(EXPAND_MACROS).ifPresent(expand -> parameters.put("expand_macros", expand));
+    sensorContext.config().get(ANALYZE_DOCTESTS).ifPresent(analyze -> parameters.put("analyze_doctests", analyze));
+    sensorContext.config().get(CARGO_METADATA).ifPresent(metadata -> parameters.put("cargo_metadata", metadata));
+    sensorContext.config().get(CPD_NORMALIZE_LITERALS).ifPresent(normalize -> parameters.put("cpd_normalize_literals", normalize));
+    sensorContext.config().get(CPD_EXCLUDE_TEST_CODE).ifPresent(exclude -> parameters.put("cpd_exclude_test_code", exclude));
+    sensorContext.config().get(CPD_EXCLUDE_GENERATED_CODE).ifPresent(exclude -> parameters.put("cpd_exclude_generated_code", exclude));
+    sensorContext.config().get(CPD_MINIMUM_ITEM_TOKENS).ifPresent(tokens -> parameters.put("cpd_minimum_item_tokens", tokens));
+    sensorContext.config().get(CPD_MINIMUM_ITEM_LINES).ifPresent(lines -> parameters.put("cpd_minimum_item_lines", lines));
+    sensorContext.config().get(GENERATED_CODE_MODE).ifPresent(mode -> parameters.put("generated_code", mode));
+    if (sensorContext.config().hasKey(GENERATED_MARKERS)) {
+      parameters.put("generated_markers", String.join(",", sensorContext.config().getStringArray(GENERATED_MARKERS)));
+    }
+    if (sensorContext.config().hasKey(GENERATED_PATHS)) {
+      parameters.put("generated_paths", String.join(",", sensorContext.config().getStringArray(GENERATED_PATHS)));
+    }
+    sensorContext.config().get(RUSTFMT_COMMAND).ifPresent(command -> parameters.put("rustfmt", command));
+    sensorContext.config().get(RUSTFMT_EDITION).ifPresent(edition -> parameters.put("rustfmt_edition", edition));
+    sensorContext.config().get(RUST_ANALYZER_COMMAND).ifPresent(command -> parameters.put("rust_analyzer", command));
+    if (sensorContext.config().hasKey(ADVISORIES_REPORT_PATHS)) {
+      parameters.put("advisories_report_paths", String.join(",", sensorContext.config().getStringArray(ADVISORIES_REPORT_PATHS)));
+    }
+    if (LOG.isDebugEnabled()) {
+      // Per-file durations of the analysis steps and rules, logged by the analyzer at debug level
+      parameters.put("telemetry", "true");
+      parameters.put("log_level", "debug");
+    }
+    sensorContext.config().get(LOG_LEVEL).ifPresent(level -> parameters.put("log_level", level));
+    sensorContext.config().get(LOG_FILE).ifPresent(file -> parameters.put("log_file", file));
     analyzerFactory.addParameters(parameters);
 
     try (Analyzer analyzer = analyzerFactory.create(platform)) {
       for (InputFile inputFile : inputFiles) {
         analyzeFile(analyzer, sensorContext, inputFile);
       }
+      // The project-level rules depend on all the files analyzed above
+      analyzeProject(analyzer, sensorContext);
     } catch (Exception ex) {
       LOG.error("Failed to create Rust analyzer: {}", ex.getMessage());
       analysisWarnings.addUnique("Failed to create Rust analyzer: " + ex.getMessage());
@@ -104,12 +172,19 @@ public class RustSensor implements Sensor {
     }
   }
 
-  private static void analyzeFile(Analyzer analyzer, SensorContext sensorContext, InputFile inputFile) {
+  private void analyzeFile(Analyzer analyzer, SensorContext sensorContext, InputFile inputFile) {
     try {
-      var result = analyzer.analyze(inputFile.contents());
+      var result = analyzer.analyze(relativePath(sensorContext, inputFile), inputFile.contents());
 
-      saveMeasures(sensorContext, inputFile, result.measures());
+      // The size and complexity of the test files are not part of the measures of the project, unlike their lines
+      if (!result.testFile()) {
+        saveMeasures(sensorContext, inputFile, result.measures());
+        saveFunctionComplexityDistribution(sensorContext, inputFile, result.functionComplexityDistribution());
+      }
+      saveTests(sensorContext, inputFile, result.testCases());
+      saveLinesData(inputFile, result);
       saveHighlighting(sensorContext, inputFile, result.highlightTokens());
+      saveSymbols(sensorContext, inputFile, result.symbols());
       saveCPD(sensorContext, inputFile, result.cpdTokens());
       saveIssues(sensorContext, inputFile, result.issues());
     } catch (IOException ex) {
@@ -117,6 +192,19 @@ public class RustSensor implements Sensor {
     }
   }
 
+  private static void analyzeProject(Analyzer analyzer, SensorContext sensorContext) {
+    try {
+      saveProjectIssues(sensorContext, analyzer.analyzeProject());
+    } catch (IOException ex) {
+      LOG.error("Failed to analyze the project. Reason: {}", ex.getMessage());
+    }
+  }
+
+  private static String relativePath(SensorContext sensorContext, InputFile inputFile) {
+    Path baseDir = sensorContext.fileSystem().baseDir().toPath();
+    return baseDir.relativize(Path.of(inputFile.uri())).toString();
+  }
+
   private static List<InputFile> inputFiles(SensorContext sensorContext) {
     FileSystem fileSystem = sensorContext.fileSystem();
     FilePredicate predicate = fileSystem.predicates().hasLanguage(RustLanguage.KEY);
@@ -139,6 +227,26 @@ public class RustSensor implements Sensor {
     highlighting.save();
   }
 
+  private static void saveSymbols(SensorContext sensorContext, InputFile inputFile, List<Analyzer.Symbol> symbols) {
+    NewSymbolTable symbolTable = sensorContext.newSymbolTable();
+    symbolTable.onFile(inputFile);
+    for (var symbol : symbols) {
+      try {
+        NewSymbol newSymbol = symbolTable.newSymbol(range(inputFile, symbol.declaration()));
+        for (var reference : symbol.references()) {
+          newSymbol.newReference(range(inputFile, reference));
+        }
+      } catch (IllegalArgumentException e) {
+        LOG.error("Invalid symbol: {}. Reason: {}", symbol, e.getMessage());
+      }
+    }
+    symbolTable.save();
+  }
+
+  private static TextRange range(InputFile inputFile, Analyzer.Location location) {
+    return inputFile.newRange(location.startLine(), location.startColumn(), location.endLine(), location.endColumn());
+  }
+
   private static void saveMeasures(SensorContext sensorContext, InputFile inputFile, Analyzer.Measures measures) {
     saveMetric(sensorContext, inputFile, CoreMetrics.NCLOC, measures.ncloc());
     saveMetric(sensorContext, inputFile, CoreMetrics.COMMENT_LINES, measures.commentLines());
@@ -147,7 +255,44 @@ public class RustSensor implements Sensor {
     saveMetric(sensorContext, inputFile, CoreMetrics.CLASSES, measures.classes());
     saveMetric(sensorContext, inputFile, CoreMetrics.COGNITIVE_COMPLEXITY, measures.cognitiveComplexity());
     saveMetric(sensorContext, inputFile, CoreMetrics.COMPLEXITY, measures.cyclomaticComplexity());
+    // The documented API density is computed by SonarQube from these measures
+    saveMetric(sensorContext, inputFile, CoreMetrics.PUBLIC_API, measures.publicApi());
+    saveMetric(sensorContext, inputFile, CoreMetrics.PUBLIC_UNDOCUMENTED_API, measures.publicApi() - measures.publicDocumentedApi());
+  }
+
+  private static void saveFunctionComplexityDistribution(SensorContext sensorContext, InputFile inputFile, List<Analyzer.DistributionRange> ranges) {
+    // Format of the distribution measures, e.g. "1=3;2=0;4=1"
+    String distribution = ranges.stream()
+      .map(range -> range.lowerBound() + "=" + range.count())
+      .collect(Collectors.joining(";"));
+    sensorContext.<String>newMeasure()
+      .on(inputFile)
+      .forMetric(CoreMetrics.FUNCTION_COMPLEXITY_DISTRIBUTION)
+      .withValue(distribution)
+      .save();
   }
+
+  private static void saveTests(SensorContext sensorContext, InputFile inputFile, List<Analyzer.TestCase> testCases) {
+    // The number of tests is attributed to the files declaring them, even when the test suite is not executed
+    if (!testCases.isEmpty()) {
+      saveMetric(sensorContext, inputFile, CoreMetrics.TESTS, testCases.size());
+    }
+  }
+
+  private void saveLinesData(InputFile inputFile, Analyzer.AnalysisResult result) {
+    var fileLinesContext = fileLinesContextFactory.createFor(inputFile);
+    for (var line : result.nclocData()) {
+      fileLinesContext.setIntValue(CoreMetrics.NCLOC_DATA_KEY, line, 1);
+    }
+    for (var line : result.commentLinesData()) {
+      fileLinesContext.setIntValue(CoreMetrics.COMMENT_LINES_DATA_KEY, line, 1);
+    }
+    for (var line : result.executableLines()) {
+      fileLinesContext.setIntValue(CoreMetrics.EXECUTABLE_LINES_DATA_KEY, line, 1);
+    }
+    fileLinesContext.save();
+  }
+
   private static void saveMetric(SensorContext sensorContext, InputFile inputFile, Metric<Integer> metric, Integer value) {
     sensorContext.<Integer>newMeasure()
       .on(inputFile)
@@ -170,31 +315,67 @@ public class RustSensor implements Sensor {
 
   private static void saveIssues(SensorContext sensorContext, InputFile inputFile, List<Analyzer.Issue> issues) {
     for (var issue : issues) {
-      try {
-        var newIssue = sensorContext.newIssue();
-        var location = newIssue.newLocation()
-          .on(inputFile)
-          .at(inputFile.newRange(issue.location().startLine(), issue.location().startColumn(), issue.location().endLine(), issue.location().endColumn()))
-          .message(issue.message());
-        newIssue
-          .forRule(RuleKey.of(RustLanguage.KEY, issue.ruleKey()))
-          .at(location);
-
-        for (var secondaryLocation : issue.secondaryLocations()) {
-          newIssue.addLocation(newIssue.newLocation()
-            .on(inputFile)
-            .at(inputFile.newRange(
-              secondaryLocation.location().startLine(),
-              secondaryLocation.location().startColumn(),
-              secondaryLocation.location().endLine(),
-              secondaryLocation.location().endColumn()))
-            .message(secondaryLocation.message()));
-        }
+      saveIssue(sensorContext, inputFile, issue, Collections.nCopies(issue.secondaryLocations().size(), inputFile));
+    }
+  }
 
-        newIssue.save();
-      } catch (IllegalArgumentException e) {
-        LOG.error("Invalid issue: {}. Reason: {}", issue, e.getMessage());
+  private static void saveProjectIssues(SensorContext sensorContext, List<Analyzer.ProjectIssue> issues) {
+    FileSystem fileSystem = sensorContext.fileSystem();
+    for (var projectIssue : issues) {
+      InputFile inputFile = fileSystem.inputFile(fileSystem.predicates().hasRelativePath(projectIssue.path()));
+      if (inputFile == null) {
+        // e.g. a Cargo.toml manifest outside of the sources of the project
+        LOG.debug("Issue on a file which is not part of the project: {}", projectIssue);
+        continue;
       }
+      // The secondary locations in files which are not part of the project are dropped
+      List<InputFile> secondaryFiles = projectIssue.secondaryPaths().stream()
+        .map(path -> path == null ? inputFile : fileSystem.inputFile(fileSystem.predicates().hasRelativePath(path)))
+        .toList();
+      saveIssue(sensorContext, inputFile, projectIssue.issue(), secondaryFiles);
+    }
+  }
+
+  /**
+   * @param secondaryFiles files of the secondary locations of the issue, null for the ones which are not part of the project
+   */
+  private static void saveIssue(SensorContext sensorContext, InputFile inputFile, Analyzer.Issue issue, List<InputFile> secondaryFiles) {
+    if ("SAFE".equals(issue.hotspotStatus())) {
+      // Reviewed in the 'reviewed_hotspots' setting as not needing a change
+      return;
+    }
+    try {
+      var newIssue = sensorContext.newIssue();
+      var location = newIssue.newLocation()
+        .on(inputFile)
+        .at(inputFile.newRange(issue.location().startLine(), issue.location().startColumn(), issue.location().endLine(), issue.location().endColumn()))
+        .message(issue.message());
+      newIssue
+        .forRule(RuleKey.of(RustLanguage.KEY, issue.ruleKey()))
+        .at(location);
+      if (issue.severity() != null) {
+        newIssue.overrideSeverity(Severity.valueOf(issue.severity()));
+      }
+
+      for (int i = 0; i < issue.secondaryLocations().size(); i++) {
+        var secondaryLocation = issue.secondaryLocations().get(i);
+        var secondaryFile = secondaryFiles.get(i);
+        if (secondaryFile == null) {
+          continue;
+        }
+        newIssue.addLocation(newIssue.newLocation()
+          .on(secondaryFile)
+          .at(secondaryFile.newRange(
+            secondaryLocation.location().startLine(),
+            secondaryLocation.location().startColumn(),
+            secondaryLocation.location().endLine(),
+            secondaryLocation.location().endColumn()))
+          .message(secondaryLocation.message()));
+      }
+
+      newIssue.save();
+    } catch (IllegalArgumentException e) {
+      LOG.error("Invalid issue: {}. Reason: {}", issue, e.getMessage());
     }
   }
 }
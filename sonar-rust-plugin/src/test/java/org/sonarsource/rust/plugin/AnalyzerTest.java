@@ -17,16 +17,26 @@
 package org.sonarsource.rust.plugin;
 
 import java.io.IOException;
+import java.nio.file.Files;
+import java.nio.file.Path;
 import java.util.Collections;
 import java.util.HashMap;
 import java.util.List;
 import java.util.Map;
 import org.junit.jupiter.api.Test;
+import org.junit.jupiter.api.extension.RegisterExtension;
+import org.junit.jupiter.api.io.TempDir;
+import org.slf4j.event.Level;
+import org.sonar.api.testfixtures.log.LogTesterJUnit5;
 
 import static org.assertj.core.api.Assertions.assertThat;
+import static org.assertj.core.api.Assertions.tuple;
 
 class AnalyzerTest {
 
+  @RegisterExtension
+  LogTesterJUnit5 logTester = new LogTesterJUnit5().setLevel(Level.DEBUG);
+
   public static final List<String> RUN_LOCAL_ANALYZER_COMMAND = List.of("cargo", "run", "--manifest-path", "../analyzer/Cargo.toml");
 
   public static final Map<String, String> TEST_PARAMETERS = new HashMap<>();
@@ -39,22 +49,27 @@ class AnalyzerTest {
   @Test
   void analyze() throws IOException {
     try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, TEST_PARAMETERS)) {
-      var result1 = analyzer.analyze("fn main() {}");
-      var result2 = analyzer.analyze("fn foo() -> i32 { 42 }");
+      var result1 = analyzer.analyze("main.rs", "fn main() {}");
+      var result2 = analyzer.analyze("main.rs", "fn foo() -> i32 { 42 }");
 
       assertThat(result1.highlightTokens()).containsExactly(new Analyzer.HighlightTokens("KEYWORD", new Analyzer.Location(1, 0, 1, 2)));
       assertThat(result2.highlightTokens()).containsExactly(
         new Analyzer.HighlightTokens("KEYWORD", new Analyzer.Location(1, 0, 1, 2)),
         new Analyzer.HighlightTokens("CONSTANT", new Analyzer.Location(1, 18, 1, 20)));
-      assertThat(result1.measures()).isEqualTo(new Analyzer.Measures(1, 0, 1, 0, 0, 0, 0));
-      assertThat(result2.measures()).isEqualTo(new Analyzer.Measures(1, 0, 1, 0, 0, 0, 1));
+      assertThat(result1.measures()).isEqualTo(new Analyzer.Measures(1, 0, 1, 0, 0, 0, 0, 0, 0));
+      assertThat(result2.measures()).isEqualTo(new Analyzer.Measures(1, 0, 1, 0, 0, 0, 1, 0, 0));
+      assertThat(result1.symbols()).containsExactly(new Analyzer.Symbol(new Analyzer.Location(1, 3, 1, 7), List.of()));
+      assertThat(result1.nclocData()).containsExactly(1);
+      assertThat(result1.commentLinesData()).isEmpty();
+      assertThat(result1.executableLines()).isEmpty();
+      assertThat(result2.executableLines()).containsExactly(1);
     }
   }
 
   @Test
   void cognitive_and_cyclomatic_complexity() throws IOException {
     try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, TEST_PARAMETERS)) {
-      var result = analyzer.analyze("""
+      var result = analyzer.analyze("main.rs", """
         fn foo(x: bool, y: bool) -> i32 {
           if x { // +1
             if y { // +2
@@ -79,15 +94,57 @@ class AnalyzerTest {
 
       assertThat(result.measures().cognitiveComplexity()).isEqualTo(6);
       assertThat(result.measures().cyclomaticComplexity()).isEqualTo(8);
+      assertThat(result.functionComplexityDistribution()).containsExactly(
+        new Analyzer.DistributionRange(1, 0),
+        new Analyzer.DistributionRange(2, 1),
+        new Analyzer.DistributionRange(4, 1),
+        new Analyzer.DistributionRange(6, 0),
+        new Analyzer.DistributionRange(8, 0),
+        new Analyzer.DistributionRange(10, 0),
+        new Analyzer.DistributionRange(12, 0));
 
     }
 
   }
 
+  @Test
+  void public_api() throws IOException {
+    try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, TEST_PARAMETERS)) {
+      var result = analyzer.analyze("main.rs", """
+        /// Opens the connection.
+        pub fn open() {}
+
+        pub struct Connection;
+
+        fn close() {}
+        """);
+
+      assertThat(result.measures().publicApi()).isEqualTo(2);
+      assertThat(result.measures().publicDocumentedApi()).isEqualTo(1);
+    }
+  }
+
+  @Test
+  void test_cases() throws IOException {
+    try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, TEST_PARAMETERS)) {
+      var result = analyzer.analyze("main.rs", """
+        fn main() {}
+
+        #[cfg(test)]
+        mod tests {
+          #[test]
+          fn test_main() {}
+        }
+        """);
+
+      assertThat(result.testCases()).containsExactly(new Analyzer.TestCase("tests::test_main", new Analyzer.Location(6, 5, 6, 14)));
+    }
+  }
+
   @Test
   void cpd_tokens() throws IOException {
     try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, TEST_PARAMETERS)) {
-      var result = analyzer.analyze("""
+      var result = analyzer.analyze("main.rs", """
         fn main() {
           println!("Hello, world!");
         }
@@ -114,14 +171,72 @@ class AnalyzerTest {
   @Test
   void syntax_errors() throws IOException {
     try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, TEST_PARAMETERS)) {
-      var result = analyzer.analyze("""
+      var result = analyzer.analyze("main.rs", """
         fn main() {
-          let x = 42
+          let _x = 42
         }
         """);
 
       assertThat(result.issues()).containsExactly(
-        new Analyzer.Issue("S2260", "A syntax error occurred during parsing: missing \";\".", new Analyzer.Location(2, 10, 2, 12), Collections.emptyList()));
+        new Analyzer.Issue("S2260", "A syntax error occurred during parsing: missing \";\".", new Analyzer.Location(2, 11, 2, 13), Collections.emptyList(), null, null));
+    }
+  }
+
+  @Test
+  void severity_override() throws IOException {
+    var parameters = new HashMap<>(TEST_PARAMETERS);
+    parameters.put("S2260:severity", "BLOCKER");
+
+    try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, parameters)) {
+      var result = analyzer.analyze("main.rs", "fn main() { let _x = 42 }");
+
+      assertThat(result.issues()).extracting(Analyzer.Issue::severity).containsExactly("BLOCKER");
+    }
+  }
+
+  @Test
+  void security_hotspots() throws IOException {
+    try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, TEST_PARAMETERS)) {
+      var result = analyzer.analyze("main.rs", "fn main() { let _syntax_error = 42 let _address = \"10.1.24.8\"; }");
+
+      assertThat(result.issues()).extracting(Analyzer.Issue::ruleKey, Analyzer.Issue::hotspotStatus)
+        .containsExactlyInAnyOrder(tuple("S2260", null), tuple("S1313", "TO_REVIEW"));
+    }
+  }
+
+  @Test
+  void non_ascii_path_and_parameters() throws IOException {
+    var parameters = new HashMap<>(TEST_PARAMETERS);
+    parameters.put("S124:regularExpression", "À faire");
+    parameters.put("S124:message", "Traitez ce « À faire ».");
+
+    try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, parameters)) {
+      var result1 = analyzer.analyze("src/données/main.rs", "// À faire\nfn main() { run(); }");
+      var result2 = analyzer.analyze("main.rs", "fn main() { let _x = 42 }");
+
+      assertThat(result1.issues()).extracting(Analyzer.Issue::ruleKey, Analyzer.Issue::message)
+        .containsExactly(tuple("S124", "Traitez ce « À faire »."));
+      assertThat(result2.issues()).extracting(Analyzer.Issue::ruleKey).containsExactly("S2260");
+    }
+  }
+
+  @Test
+  void project_issues(@TempDir Path projectDir) throws IOException {
+    Files.writeString(projectDir.resolve("Cargo.toml"), "[package]\nname = \"app\"\n\n[dependencies]\nserde = \"*\"\n");
+    var parameters = new HashMap<>(TEST_PARAMETERS);
+    parameters.put("project_dir", projectDir.toString());
+    String function = "fn area(width: u32, height: u32) -> u32 {\n    let area = width * height;\n    let doubled = area * 2;\n    doubled\n}\n";
+
+    try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, parameters)) {
+      analyzer.analyze("src/lib.rs", "pub fn used() -> u8 { 1 }\npub fn unused() -> u8 { 2 }\n" + function);
+      analyzer.analyze("src/main.rs", "fn main() { used(); }\n" + function);
+      var issues = analyzer.analyzeProject();
+
+      assertThat(issues).extracting(Analyzer.ProjectIssue::path, issue -> issue.issue().ruleKey(), issue -> issue.issue().location().startLine())
+        .contains(tuple("src/lib.rs", "S7466", 2), tuple("src/main.rs", "S4144", 2), tuple("Cargo.toml", "S7469", 5));
+      assertThat(issues).filteredOn(issue -> issue.issue().ruleKey().equals("S4144"))
+        .extracting(Analyzer.ProjectIssue::secondaryPaths)
+        .containsExactly(List.of("src/lib.rs"));
     }
   }
 
@@ -131,11 +246,13 @@ class AnalyzerTest {
     parameters.put(String.format("%s:%s", "S3776", "threshold"), "3");
 
     try (Analyzer analyzer = new Analyzer(RUN_LOCAL_ANALYZER_COMMAND, parameters)) {
-      var result = analyzer.analyze("""
+      var result = analyzer.analyze("main.rs", """
 fn foo(c1: bool, c2: bool) {
   if c1 { // +1
     if c2 { // +2
+      run();
     } else { // +1
+      stop();
     }
   }
 }
@@ -145,8 +262,22 @@ fn foo(c1: bool, c2: bool) {
         new Analyzer.Issue("S3776", "Refactor this function to reduce its Cognitive Complexity from 4 to the 3 allowed.", new Analyzer.Location(1, 3, 1, 6), List.of(
           new Analyzer.SecondaryLocation("+1", new Analyzer.Location(2, 2, 2, 4)),
           new Analyzer.SecondaryLocation("+2 (incl 1 for nesting)", new Analyzer.Location(3, 4, 3, 6)),
-          new Analyzer.SecondaryLocation("+1", new Analyzer.Location(4, 6, 4, 10))
-        )));
+          new Analyzer.SecondaryLocation("+1", new Analyzer.Location(5, 6, 5, 10))
+        ), null, null));
     }
   }
+
+  @Test
+  void log_analyzer_output_by_level() {
+    Analyzer.log("debug telemetry parse=1ms");
+    Analyzer.log("info some information");
+    Analyzer.log("warn analysis timed out after 10 ms, missing results: issues");
+    Analyzer.log("error could not parse 'skip_cpd' parameter");
+    Analyzer.log("unprefixed output");
+
+    assertThat(logTester.logs(Level.DEBUG)).containsExactly("telemetry parse=1ms");
+    assertThat(logTester.logs(Level.INFO)).containsExactly("some information");
+    assertThat(logTester.logs(Level.WARN)).containsExactly("analysis timed out after 10 ms, missing results: issues", "unprefixed output");
+    assertThat(logTester.logs(Level.ERROR)).containsExactly("could not parse 'skip_cpd' parameter");
+  }
 }
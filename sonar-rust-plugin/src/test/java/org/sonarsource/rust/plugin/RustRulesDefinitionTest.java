@@ -33,14 +33,14 @@ class RustRulesDefinitionTest {
   void testClippyRules() {
     var rules = RustRulesDefinition.CLIPPY_RULES;
     assertThat(rules.keySet()).hasSize(new HashSet<>(rules.values()).size());
-    assertThat(rules.keySet()).hasSize(83);
+    assertThat(rules.keySet()).hasSize(80);
     assertThat(rules.keySet()).allSatisfy(ruleKey -> assertThat(ruleKey).startsWith("clippy::"));
   }
 
   @Test
   void testSonarRules() {
     var rules = RustRulesDefinition.SONAR_RULES;
-    assertThat(rules).hasSize(2);
+    assertThat(rules).hasSize(37);
   }
 
   @Test
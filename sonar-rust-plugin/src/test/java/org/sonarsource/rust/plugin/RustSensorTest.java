@@ -31,6 +31,8 @@ import org.sonar.api.batch.sensor.highlighting.TypeOfText;
 import org.sonar.api.batch.sensor.internal.DefaultSensorDescriptor;
 import org.sonar.api.batch.sensor.internal.SensorContextTester;
 import org.sonar.api.measures.CoreMetrics;
+import org.sonar.api.measures.FileLinesContext;
+import org.sonar.api.measures.FileLinesContextFactory;
 import org.sonar.api.testfixtures.log.LogTesterJUnit5;
 import org.sonar.api.batch.rule.internal.ActiveRulesBuilder;
 import org.sonar.api.batch.rule.internal.NewActiveRule;
@@ -38,6 +40,12 @@ import org.sonar.api.rule.RuleKey;
 
 import static org.assertj.core.api.Assertions.assertThat;
 import static org.assertj.core.api.Assertions.assertThatThrownBy;
+import static org.mockito.ArgumentMatchers.any;
+import static org.mockito.Mockito.mock;
+import static org.mockito.Mockito.never;
+import static org.mockito.Mockito.verify;
+import static org.mockito.Mockito.when;
+import java.util.List;
 import java.util.Map;
 import java.util.concurrent.atomic.AtomicReference;
 
@@ -51,10 +59,15 @@ class RustSensorTest {
   @TempDir
   protected File baseDir;
   protected SensorContextTester context;
+  protected FileLinesContext fileLinesContext;
+  protected FileLinesContextFactory fileLinesContextFactory;
 
   @BeforeEach
   void setup() {
     context = SensorContextTester.create(baseDir);
+    fileLinesContext = mock(FileLinesContext.class);
+    fileLinesContextFactory = mock(FileLinesContextFactory.class);
+    when(fileLinesContextFactory.createFor(any())).thenReturn(fileLinesContext);
   }
 
   @Test
@@ -78,6 +91,65 @@ class RustSensorTest {
       .isEqualTo(1);
   }
 
+  @Test
+  void analyze_public_api() {
+    RustSensor sensor = sensor();
+    context.fileSystem().add(inputFile("test.rs", "/// Opens the connection.\npub fn open() {}\npub struct Connection;\nfn close() {}\n"));
+    sensor.execute(context);
+    assertThat(context.measure("%s:test.rs".formatted(PROJECT_KEY), CoreMetrics.PUBLIC_API).value())
+      .isEqualTo(2);
+    assertThat(context.measure("%s:test.rs".formatted(PROJECT_KEY), CoreMetrics.PUBLIC_UNDOCUMENTED_API).value())
+      .isEqualTo(1);
+  }
+
+  @Test
+  void analyze_function_complexity_distribution() {
+    RustSensor sensor = sensor();
+    context.fileSystem().add(inputFile("test.rs", "fn main() {}\nfn check(x: bool) { if x { run(); } }\n"));
+    sensor.execute(context);
+    assertThat(context.measure("%s:test.rs".formatted(PROJECT_KEY), CoreMetrics.FUNCTION_COMPLEXITY_DISTRIBUTION).value())
+      .isEqualTo("1=1;2=1;4=0;6=0;8=0;10=0;12=0");
+  }
+
+  @Test
+  void analyze_tests() {
+    RustSensor sensor = sensor();
+    context.fileSystem().add(inputFile("test.rs", "#[test]\nfn test_one() {}\n#[tokio::test]\nasync fn test_two() {}\n"));
+    context.fileSystem().add(inputFile("main.rs", "fn main() {}"));
+    sensor.execute(context);
+    assertThat(context.measure("%s:test.rs".formatted(PROJECT_KEY), CoreMetrics.TESTS).value())
+      .isEqualTo(2);
+    assertThat(context.measure("%s:main.rs".formatted(PROJECT_KEY), CoreMetrics.TESTS)).isNull();
+  }
+
+  @Test
+  void analyze_test_file() {
+    RustSensor sensor = sensor();
+    context.fileSystem().add(inputFile("tests/integration.rs", "#[test]\nfn test_one() {\n    check();\n}\n"));
+    sensor.execute(context);
+    assertThat(context.measure("%s:tests/integration.rs".formatted(PROJECT_KEY), CoreMetrics.TESTS).value())
+      .isEqualTo(1);
+    assertThat(context.measure("%s:tests/integration.rs".formatted(PROJECT_KEY), CoreMetrics.NCLOC)).isNull();
+    verify(fileLinesContext).setIntValue(CoreMetrics.NCLOC_DATA_KEY, 1, 1);
+    verify(fileLinesContext).setIntValue(CoreMetrics.NCLOC_DATA_KEY, 4, 1);
+  }
+
+  @Test
+  void analyze_symbols() {
+    RustSensor sensor = sensor();
+    context.fileSystem().add(inputFile("test.rs", """
+fn main() {
+  let x = 42;
+  println!("{}", x + x);
+}
+"""));
+    sensor.execute(context);
+
+    var references = context.referencesForSymbolAt("%s:test.rs".formatted(PROJECT_KEY), 2, 6);
+    assertThat(references).hasSize(2);
+    assertThat(references.iterator().next().start().line()).isEqualTo(3);
+  }
+
   @Test
   void analyze_unicode() {
     RustSensor sensor = sensor();
@@ -97,10 +169,50 @@ class RustSensorTest {
       .isOne();
   }
 
+  @Test
+  void analyze_executable_lines() {
+    var sensor = sensor();
+    context.fileSystem().add(inputFile("test.rs", """
+fn main() {
+  let x = 42;
+
+  println!("{}", x);
+}
+"""));
+
+    sensor.execute(context);
+
+    verify(fileLinesContext).setIntValue(CoreMetrics.EXECUTABLE_LINES_DATA_KEY, 2, 1);
+    verify(fileLinesContext).setIntValue(CoreMetrics.EXECUTABLE_LINES_DATA_KEY, 4, 1);
+    verify(fileLinesContext).save();
+  }
+
+  @Test
+  void analyze_ncloc_and_comment_lines_data() {
+    var sensor = sensor();
+    context.fileSystem().add(inputFile("test.rs", """
+// Comment
+fn main() {
+
+  let x = 42; // Trailing comment
+}
+"""));
+
+    sensor.execute(context);
+
+    verify(fileLinesContext).setIntValue(CoreMetrics.NCLOC_DATA_KEY, 2, 1);
+    verify(fileLinesContext, never()).setIntValue(CoreMetrics.NCLOC_DATA_KEY, 3, 1);
+    verify(fileLinesContext).setIntValue(CoreMetrics.NCLOC_DATA_KEY, 4, 1);
+    verify(fileLinesContext).setIntValue(CoreMetrics.NCLOC_DATA_KEY, 5, 1);
+    verify(fileLinesContext).setIntValue(CoreMetrics.COMMENT_LINES_DATA_KEY, 1, 1);
+    verify(fileLinesContext).setIntValue(CoreMetrics.COMMENT_LINES_DATA_KEY, 4, 1);
+    verify(fileLinesContext).save();
+  }
+
   @Test
   void analyze_syntax_errors() {
     var sensor = sensor();
-    context.fileSystem().add(inputFile("test.rs", "fn main() { let x = 42 }"));
+    context.fileSystem().add(inputFile("test.rs", "fn main() { let _x = 42 }"));
 
     sensor.execute(context);
 
@@ -117,7 +229,7 @@ class RustSensorTest {
     var sensor = sensor();
     context.fileSystem().add(inputFile("main.rs", """
 fn main() {
-  let x = 42
+  let _x = 42
 }"""));
 
     sensor.execute(context);
@@ -130,20 +242,35 @@ fn main() {
     assertThat(issue.primaryLocation().textRange().start().line()).isEqualTo(2);
   }
 
+  @Test
+  void analyze_project_issues() {
+    RustSensor sensor = sensor();
+    String function = "fn area(width: u32, height: u32) -> u32 {\n    let area = width * height;\n    let doubled = area * 2;\n    doubled\n}\n";
+    context.fileSystem().add(inputFile("src/lib.rs", function));
+    context.fileSystem().add(inputFile("src/main.rs", "fn main() { area(1, 2); }\n" + function));
+    sensor.execute(context);
+
+    // The issue of the duplicated function is on one of the files, with a secondary location on the other one
+    var issue = context.allIssues().stream().filter(i -> i.ruleKey().rule().equals("S4144")).findFirst().orElseThrow();
+    var secondaryLocation = issue.flows().get(0).locations().get(0);
+    assertThat(List.of(issue.primaryLocation().inputComponent().key(), secondaryLocation.inputComponent().key()))
+      .containsExactlyInAnyOrder("%s:src/lib.rs".formatted(PROJECT_KEY), "%s:src/main.rs".formatted(PROJECT_KEY));
+  }
+
   @Test
   void analyze_cognitive_complexity() {
     // Test to ensure that the sensor correctly reports secondary locations
     var sensor = sensor();
     context.fileSystem().add(inputFile("test.rs", """
 fn foo(c1: bool) {
-  if c1 {} else {}
-  if c1 {} else {}
-  if c1 {} else {}
-  if c1 {} else {}
-  if c1 {} else {}
-  if c1 {} else {}
-  if c1 {} else {}
-  if c1 {} else {}
+  if c1 { a(); } else { b(); }
+  if c1 { a(); } else { b(); }
+  if c1 { a(); } else { b(); }
+  if c1 { a(); } else { b(); }
+  if c1 { a(); } else { b(); }
+  if c1 { a(); } else { b(); }
+  if c1 { a(); } else { b(); }
+  if c1 { a(); } else { b(); }
 }
 """));
 
@@ -173,7 +300,7 @@ fn foo(c1: bool) {
       }
 
     };
-    var sensor = new RustSensor(null, new AnalysisWarningsWrapper(warnings), mockUnsupportedPlatform);
+    var sensor = new RustSensor(null, new AnalysisWarningsWrapper(warnings), fileLinesContextFactory, mockUnsupportedPlatform);
 
     sensor.execute(context);
     assertThat(warnings.warnings).hasSize(1);
@@ -188,7 +315,7 @@ fn foo(c1: bool) {
       public Analyzer create(Platform platform) {
         throw new RuntimeException("Cannot run program");
       }
-    }, new AnalysisWarningsWrapper(warnings));
+    }, new AnalysisWarningsWrapper(warnings), fileLinesContextFactory);
 
     context.settings().setProperty("sonar.internal.analysis.rust.failFast", "true");
 
@@ -216,7 +343,7 @@ fn foo(c1: bool) {
       }
     };
 
-    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper());
+    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper(), fileLinesContextFactory);
 
     // Setup active rules with custom parameters
     var activeRulesBuilder = new ActiveRulesBuilder();
@@ -258,7 +385,7 @@ fn foo(c1: bool) {
       }
     };
 
-    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper());
+    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper(), fileLinesContextFactory);
 
     // No active rules set - should use default parameters only
     context.fileSystem().add(inputFile("test.rs", "fn main() {}"));
@@ -273,6 +400,160 @@ fn foo(c1: bool) {
         .containsEntry("S3776:threshold", "15"); // Should contain the default parameter from RustRulesDefinition.parameters()
   }
 
+  @Test
+  void analysis_timeout_passed_to_analyzer_factory() {
+    AtomicReference<Map<String, String>> capturedParameters = new AtomicReference<>();
+
+    var mockAnalyzerFactory = new AnalyzerFactory(null) {
+      @Override
+      public void addParameters(Map<String, String> parameters) {
+        capturedParameters.set(Map.copyOf(parameters));
+      }
+
+      @Override
+      public Analyzer create(Platform platform) {
+        return new Analyzer(AnalyzerTest.RUN_LOCAL_ANALYZER_COMMAND, AnalyzerTest.TEST_PARAMETERS);
+      }
+    };
+
+    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper(), fileLinesContextFactory);
+    context.settings().setProperty(RustSensor.ANALYSIS_TIMEOUT, "5000");
+    context.fileSystem().add(inputFile("test.rs", "fn main() {}"));
+
+    sensor.execute(context);
+
+    assertThat(capturedParameters.get()).containsEntry("timeout_ms", "5000");
+  }
+
+  @Test
+  void max_file_size_passed_to_analyzer_factory() {
+    AtomicReference<Map<String, String>> capturedParameters = new AtomicReference<>();
+
+    var mockAnalyzerFactory = new AnalyzerFactory(null) {
+      @Override
+      public void addParameters(Map<String, String> parameters) {
+        capturedParameters.set(Map.copyOf(parameters));
+      }
+
+      @Override
+      public Analyzer create(Platform platform) {
+        return new Analyzer(AnalyzerTest.RUN_LOCAL_ANALYZER_COMMAND, AnalyzerTest.TEST_PARAMETERS);
+      }
+    };
+
+    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper(), fileLinesContextFactory);
+    context.settings().setProperty(RustSensor.MAX_FILE_SIZE, "1048576");
+    context.fileSystem().add(inputFile("test.rs", "fn main() {}"));
+
+    sensor.execute(context);
+
+    assertThat(capturedParameters.get()).containsEntry("max_file_size", "1048576");
+  }
+
+  @Test
+  void memory_limit_passed_to_analyzer_factory() {
+    AtomicReference<Map<String, String>> capturedParameters = new AtomicReference<>();
+
+    var mockAnalyzerFactory = new AnalyzerFactory(null) {
+      @Override
+      public void addParameters(Map<String, String> parameters) {
+        capturedParameters.set(Map.copyOf(parameters));
+      }
+
+      @Override
+      public Analyzer create(Platform platform) {
+        return new Analyzer(AnalyzerTest.RUN_LOCAL_ANALYZER_COMMAND, AnalyzerTest.TEST_PARAMETERS);
+      }
+    };
+
+    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper(), fileLinesContextFactory);
+    context.settings().setProperty(RustSensor.MEMORY_LIMIT, "268435456");
+    context.fileSystem().add(inputFile("test.rs", "fn main() {}"));
+
+    sensor.execute(context);
+
+    assertThat(capturedParameters.get()).containsEntry("memory_limit", "268435456");
+  }
+
+  @Test
+  void cpd_and_generated_code_settings_passed_to_analyzer_factory() {
+    AtomicReference<Map<String, String>> capturedParameters = new AtomicReference<>();
+
+    var mockAnalyzerFactory = new AnalyzerFactory(null) {
+      @Override
+      public void addParameters(Map<String, String> parameters) {
+        capturedParameters.set(Map.copyOf(parameters));
+      }
+
+      @Override
+      public Analyzer create(Platform platform) {
+        return new Analyzer(AnalyzerTest.RUN_LOCAL_ANALYZER_COMMAND, AnalyzerTest.TEST_PARAMETERS);
+      }
+    };
+
+    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper(), fileLinesContextFactory);
+    context.settings().setProperty(RustSensor.CPD_NORMALIZE_LITERALS, "false");
+    context.settings().setProperty(RustSensor.CPD_EXCLUDE_TEST_CODE, "false");
+    context.settings().setProperty(RustSensor.CPD_EXCLUDE_GENERATED_CODE, "false");
+    context.settings().setProperty(RustSensor.CPD_MINIMUM_ITEM_TOKENS, "20");
+    context.settings().setProperty(RustSensor.CPD_MINIMUM_ITEM_LINES, "3");
+    context.settings().setProperty(RustSensor.GENERATED_CODE_MODE, "skip");
+    context.settings().setProperty(RustSensor.GENERATED_MARKERS, "@generated,DO NOT EDIT");
+    context.settings().setProperty(RustSensor.GENERATED_PATHS, "src/proto/**");
+    context.fileSystem().add(inputFile("test.rs", "fn main() {}"));
+
+    sensor.execute(context);
+
+    assertThat(capturedParameters.get())
+      .containsEntry("cpd_normalize_literals", "false")
+      .containsEntry("cpd_exclude_test_code", "false")
+      .containsEntry("cpd_exclude_generated_code", "false")
+      .containsEntry("cpd_minimum_item_tokens", "20")
+      .containsEntry("cpd_minimum_item_lines", "3")
+      .containsEntry("generated_code", "skip")
+      .containsEntry("generated_markers", "@generated,DO NOT EDIT")
+      .containsEntry("generated_paths", "src/proto/**");
+  }
+
+  @Test
+  void analysis_scope_settings_passed_to_analyzer_factory() {
+    AtomicReference<Map<String, String>> capturedParameters = new AtomicReference<>();
+
+    var mockAnalyzerFactory = new AnalyzerFactory(null) {
+      @Override
+      public void addParameters(Map<String, String> parameters) {
+        capturedParameters.set(Map.copyOf(parameters));
+      }
+
+      @Override
+      public Analyzer create(Platform platform) {
+        return new Analyzer(AnalyzerTest.RUN_LOCAL_ANALYZER_COMMAND, AnalyzerTest.TEST_PARAMETERS);
+      }
+    };
+
+    var sensor = new RustSensor(mockAnalyzerFactory, new AnalysisWarningsWrapper(), fileLinesContextFactory);
+    context.settings().setProperty(RustSensor.EXPAND_MACROS, "true");
+    context.settings().setProperty(RustSensor.PARALLEL_STEPS, "true");
+    context.settings().setProperty(RustSensor.ANALYZE_DOCTESTS, "true");
+    context.settings().setProperty(RustSensor.CARGO_METADATA, "target/metadata.json");
+    context.settings().setProperty(RustSensor.RUSTFMT_COMMAND, "rustfmt");
+    context.settings().setProperty(RustSensor.LOG_LEVEL, "warn");
+    context.settings().setProperty(RustSensor.LOG_FILE, "target/analyzer.log");
+    context.fileSystem().add(inputFile("test.rs", "fn main() {}"));
+
+    sensor.execute(context);
+
+    assertThat(capturedParameters.get())
+      .containsEntry("expand_macros", "true")
+      .containsEntry("parallel_steps", "true")
+      .containsEntry("analyze_doctests", "true")
+      .containsEntry("cargo_metadata", "target/metadata.json")
+      .containsEntry("rustfmt", "rustfmt")
+      .containsEntry("log_level", "warn")
+      .containsEntry("log_file", "target/analyzer.log")
+      .containsEntry("project_dir", baseDir.getAbsolutePath());
+  }
+
   private InputFile inputFile(String relativePath, String content) {
     return new TestInputFileBuilder(PROJECT_KEY, relativePath)
       .setModuleBaseDir(baseDir.toPath())
@@ -289,7 +570,7 @@ fn foo(c1: bool) {
       public Analyzer create(Platform platform) {
         return new Analyzer(AnalyzerTest.RUN_LOCAL_ANALYZER_COMMAND, AnalyzerTest.TEST_PARAMETERS);
       }
-    }, new AnalysisWarningsWrapper());
+    }, new AnalysisWarningsWrapper(), fileLinesContextFactory);
   }
 
 }